@@ -324,6 +324,20 @@ impl App {
 				};
 				self.app_event_scheduler.schedule(AppEvent::OpenFiles(launch_documents));
 			}
+			DesktopFrontendMessage::ImportFiles { paths } => {
+				let app_event_scheduler = self.app_event_scheduler.clone();
+				let _ = thread::spawn(move || {
+					for path in paths {
+						tracing::info!("Importing file: {}", path.display());
+						if let Ok(content) = fs::read(&path) {
+							let message = DesktopWrapperMessage::ImportFile { path, content };
+							app_event_scheduler.schedule(AppEvent::DesktopWrapperMessage(message));
+						} else {
+							tracing::error!("Failed to read file: {}", path.display());
+						}
+					}
+				});
+			}
 			DesktopFrontendMessage::UpdateMenu { entries } => {
 				if let Some(window) = &self.window {
 					window.update_menu(entries);
@@ -91,6 +91,9 @@ pub(super) fn intercept_frontend_message(dispatcher: &mut DesktopWrapperMessageD
 		FrontendMessage::TriggerOpenLaunchDocuments => {
 			dispatcher.respond(DesktopFrontendMessage::OpenLaunchDocuments);
 		}
+		FrontendMessage::TriggerImportLibraries { paths } => {
+			dispatcher.respond(DesktopFrontendMessage::ImportFiles { paths });
+		}
 		FrontendMessage::TriggerSavePreferences { preferences } => {
 			dispatcher.respond(DesktopFrontendMessage::PersistenceWritePreferences { preferences });
 		}
@@ -13,6 +13,9 @@ pub use graphite_editor::messages::prelude::PreferencesMessageHandler as Prefere
 pub enum DesktopFrontendMessage {
 	ToWeb(Vec<FrontendMessage>),
 	OpenLaunchDocuments,
+	ImportFiles {
+		paths: Vec<PathBuf>,
+	},
 	OpenFileDialog {
 		title: String,
 		filters: Vec<FileFilter>,
@@ -28,6 +28,7 @@ pub mod export;
 pub mod io;
 pub mod layout;
 pub mod manifest;
+pub mod merge;
 pub mod persist;
 pub mod resource;
 pub mod session_state;
@@ -244,6 +245,15 @@ impl<L: Layout> Gdd<L> {
 		self.session.registry()
 	}
 
+	/// Sign this peer's commits from now on (see
+	/// [`Session::set_signing_key`](document_graph_storage::Session::set_signing_key)). The key is held for
+	/// this handle's lifetime only and never written to the document; its public half is registered with
+	/// the next commit.
+	pub fn set_signing_key(&mut self, key: document_graph_storage::SigningKey) -> Result<(), Error> {
+		self.session.set_signing_key(key)?;
+		Ok(())
+	}
+
 	/// The in-memory manifest. `Gdd` is its sole writer, so this is authoritative without re-reading
 	/// disk.
 	pub fn manifest(&self) -> &Manifest {
@@ -0,0 +1,42 @@
+//! Three-way merge of two diverged copies of a document on the [`Gdd`] handle (see
+//! [`merge_from`](Gdd::merge_from)). Unlike the per-edit persist path this reads from the other copy's
+//! byte store, so it is async.
+
+use document_container::AsyncContainer;
+use document_graph_storage::BranchMerge;
+
+use crate::Gdd;
+use crate::error::Error;
+use crate::layout::Layout;
+
+impl<L: Layout> Gdd<L> {
+	/// Merge `other`, a copy of this document edited independently since they diverged, into this one (see
+	/// [`Session::merge_branch`](document_graph_storage::Session::merge_branch)). Pending hot ops on this
+	/// side are retired first so they take part in the merge. Only `other`'s history reachable from its
+	/// cursor is imported: edits it has undone stay behind. The history file is rewritten and the registry
+	/// and cursor re-persisted when head moves, then every resource the merged history references that only
+	/// `other` stores is copied over. Returns the merge report, conflicts included.
+	///
+	/// Errors with [`CrdtError::UnrelatedHistories`](document_graph_storage::CrdtError::UnrelatedHistories)
+	/// when the two documents share no history.
+	pub async fn merge_from<M: Layout>(&mut self, other: &Gdd<M>) -> Result<BranchMerge, Error> {
+		self.retire_pending_interaction()?;
+
+		let head = self.session.head_rev();
+		let report = self.session.merge_branch(other.session.reachable_deltas().cloned())?;
+		if self.session.head_rev() != head {
+			self.rewrite_history()?;
+			self.persist_registry_snapshot()?;
+			self.persist_session_state()?;
+		}
+
+		for hash in self.session.all_referenced_resource_hashes() {
+			if self.has_resource(&hash).await || !other.has_resource(&hash).await {
+				continue;
+			}
+			let bytes = other.read_resource(&hash).await?;
+			self.working.write_non_blocking(&self.layout.resource_path(&hash), bytes.as_slice())?;
+		}
+		Ok(report)
+	}
+}
@@ -6,7 +6,7 @@
 use document_container::AsyncContainer;
 #[cfg(feature = "conversion")]
 use document_graph_storage::NodeMetadataSource;
-use document_graph_storage::{HotOp, Rev, TimeStamp};
+use document_graph_storage::{CherryPick, Delta, HotOp, Rev, TimeStamp};
 #[cfg(feature = "conversion")]
 use graphene_resource::ResourceStorage;
 
@@ -76,6 +76,29 @@ impl<L: Layout> Gdd<L> {
 		Ok(())
 	}
 
+	/// Apply a hot op broadcast by another peer (see
+	/// [`Session::apply_remote_hot_op`](document_graph_storage::Session::apply_remote_hot_op)). Nothing is
+	/// persisted: the op isn't ours to retire, and its retired delta arrives through [`merge`](Self::merge).
+	pub fn apply_remote_hot_op(&mut self, op: HotOp) -> Result<(), Error> {
+		self.session.apply_remote_hot_op(op)?;
+		Ok(())
+	}
+
+	/// Integrate retired deltas received from other peers (see
+	/// [`Session::merge`](document_graph_storage::Session::merge)) and persist the result. Absorbing deltas
+	/// re-sorts history canonically, so the history file is rewritten rather than appended. Returns the
+	/// minted merge `Rev`, if any. A no-op when every delta is already known.
+	pub fn merge(&mut self, deltas: impl IntoIterator<Item = Delta>) -> Result<Option<Rev>, Error> {
+		let head = self.session.head_rev();
+		let merge = self.session.merge(deltas)?;
+		if self.session.head_rev() != head {
+			self.rewrite_history()?;
+			self.persist_registry_snapshot()?;
+			self.persist_session_state()?;
+		}
+		Ok(merge)
+	}
+
 	/// Retire every pending hot op into durable history as a single interaction (marking the batch's last
 	/// delta as the interaction boundary), then re-snapshot the registry. One interaction is one undo unit,
 	/// so the caller invokes this at each undo-step boundary and before any undo/redo. A no-op when there
@@ -157,9 +180,48 @@ impl<L: Layout> Gdd<L> {
 		Ok(())
 	}
 
+	/// Drop retired deltas no longer reachable from the cursor, the redo stack, or the published frontier
+	/// (see [`Session::prune_unreachable`](document_graph_storage::Session::prune_unreachable)) and rewrite
+	/// the history file. Surviving `Rev`s are unchanged, so the cursor and session state stay valid. Returns
+	/// the number of deltas removed; the history file is only rewritten when that is non-zero.
+	pub fn prune_history(&mut self) -> Result<usize, Error> {
+		let removed = self.session.prune_unreachable();
+		if removed > 0 {
+			self.rewrite_history()?;
+		}
+		Ok(removed)
+	}
+
+	/// Collapse the history reachable from the cursor into a single chain (see
+	/// [`Session::linearize`](document_graph_storage::Session::linearize)), then rewrite the history file and
+	/// persist the remapped cursor and cleared redo stack. Returns the old-to-new `Rev` map. O(history).
+	pub fn linearize_history(&mut self) -> Result<std::collections::HashMap<Rev, Rev>, Error> {
+		let remapped = self.session.linearize()?;
+		self.rewrite_history()?;
+		self.persist_session_state()?;
+		Ok(remapped)
+	}
+
+	/// Re-commit the edits of `deltas` that aren't applied here yet (see
+	/// [`Session::cherry_pick`](document_graph_storage::Session::cherry_pick)) as one interaction, append them
+	/// to the history file, and re-snapshot the registry. Moves edits between forked documents without
+	/// duplicating the ones both already share. Resource bytes are content-addressed and not copied: the
+	/// caller stores any the picked edits reference that this document's byte store lacks.
+	pub fn cherry_pick(&mut self, deltas: impl IntoIterator<Item = Delta>) -> Result<CherryPick, Error> {
+		let report = self.session.cherry_pick(deltas)?;
+		let revs: Vec<Rev> = report.picked.iter().map(|&(_, rev)| rev).collect();
+		let Some(&last) = revs.last() else { return Ok(report) };
+
+		self.session.mark_interaction_end(last);
+		self.append_history_deltas(&revs)?;
+		self.persist_registry_snapshot()?;
+		self.persist_session_state()?;
+		Ok(report)
+	}
+
 	/// Rewrite the entire history file from the in-memory session. `history()` yields deltas in
 	/// topological (append) order, which is a valid replay order, so no separate sort is needed.
-	fn rewrite_history(&mut self) -> Result<(), Error> {
+	pub(crate) fn rewrite_history(&mut self) -> Result<(), Error> {
 		let mut buffer = Vec::new();
 		for delta in self.session.history() {
 			self.manifest.codecs.history.append(&mut buffer, delta)?;
@@ -168,7 +230,7 @@ impl<L: Layout> Gdd<L> {
 		Ok(())
 	}
 
-	fn persist_session_state(&mut self) -> Result<(), Error> {
+	pub(crate) fn persist_session_state(&mut self) -> Result<(), Error> {
 		let state = SessionState {
 			peer_id: self.session.peer(),
 			head_rev: self.session.head_rev(),
@@ -186,7 +248,7 @@ impl<L: Layout> Gdd<L> {
 	/// registry to match the persisted `head`, so any cursor move (undo/redo) that rewinds the working
 	/// registry without retiring must re-persist it or a reopen would read a registry inconsistent with
 	/// `head`. Synchronous and hot-path-safe (`write_non_blocking`).
-	fn persist_registry_snapshot(&mut self) -> Result<(), Error> {
+	pub(crate) fn persist_registry_snapshot(&mut self) -> Result<(), Error> {
 		io::write_single(&self.working, self.layout.registry_basename(), self.manifest.codecs.registry, self.session.registry())?;
 		Ok(())
 	}
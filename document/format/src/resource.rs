@@ -85,6 +85,20 @@ impl<L: Layout> Gdd<L> {
 		Ok(hashes)
 	}
 
+	/// Delete every blob in the working copy's byte store that nothing in the session references any more
+	/// (see [`Session::all_referenced_resource_hashes`](document_graph_storage::Session::all_referenced_resource_hashes)).
+	/// History counts as a reference, so bytes an undo or a past revision could still need are kept.
+	/// Returns the removed hashes, sorted.
+	pub async fn remove_unreferenced_resources(&self) -> Result<Vec<ResourceHash>, ContainerError> {
+		let referenced = self.session.all_referenced_resource_hashes();
+		let mut unreferenced: Vec<ResourceHash> = self.resource_hashes().await?.into_iter().filter(|hash| !referenced.contains(hash)).collect();
+		unreferenced.sort_unstable();
+		for hash in &unreferenced {
+			self.remove_resource(hash)?;
+		}
+		Ok(unreferenced)
+	}
+
 	pub fn resource_proxy(&self) -> ResourceProxy<L>
 	where
 		L: Clone,
@@ -215,6 +215,159 @@ fn last_broadcast_rev_persists_across_reopen() {
 	});
 }
 
+/// Retire one `AddNetwork` hot op as a complete interaction (one undo unit).
+fn retire_network_interaction(gdd: &mut GddV1, id: NetworkId, counter: u64) {
+	let op = HotOp {
+		op: RegistryDelta::AddNetwork { id, network: Network::default() },
+		timestamp: TimeStamp { counter, peer: PeerId(5) },
+	};
+	gdd.apply_hot_op(op).unwrap_or_else(|error| panic!("apply_hot_op failed: {error:?}"));
+	gdd.retire_pending_interaction().unwrap_or_else(|error| panic!("retire failed: {error:?}"));
+}
+
+/// Pruning rewrites the history file without the branch abandoned by an edit after an undo, so a reopen
+/// loads the shorter history with the same cursor and registry.
+#[test]
+fn prune_history_rewrites_history_file() {
+	futures::executor::block_on(async {
+		let mut gdd = GddV1::create_in(empty_container(), GddV1Layout, PeerId(5), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+
+		retire_network_interaction(&mut gdd, ROOT_NETWORK, 1);
+		retire_network_interaction(&mut gdd, NetworkId(1), 2);
+		gdd.undo().unwrap_or_else(|error| panic!("undo failed: {error:?}"));
+		retire_network_interaction(&mut gdd, NetworkId(2), 10);
+		assert_eq!(gdd.session().history().count(), 3);
+
+		assert_eq!(gdd.prune_history().unwrap_or_else(|error| panic!("prune_history failed: {error:?}")), 1);
+		let head = gdd.session().head_rev();
+
+		let (working, layout) = gdd.into_storage();
+		let reopened = GddV1::open_in(working, layout).await.unwrap_or_else(|error| panic!("open_in failed: {error:?}"));
+		assert_eq!(reopened.session().history().count(), 2, "the abandoned interaction is gone from the history file");
+		assert_eq!(reopened.session().head_rev(), head);
+		assert!(!reopened.registry().networks.contains_key(&NetworkId(1)));
+		assert!(reopened.registry().networks.contains_key(&NetworkId(2)));
+		reopened.session().verify_history().unwrap_or_else(|error| panic!("pruned history must verify: {error:?}"));
+	});
+}
+
+/// Linearizing persists the rewritten chain and the remapped cursor, so a reopen lands on the new head.
+#[test]
+fn linearize_history_persists_remapped_head() {
+	futures::executor::block_on(async {
+		let mut gdd = GddV1::create_in(empty_container(), GddV1Layout, PeerId(5), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+
+		retire_network_interaction(&mut gdd, ROOT_NETWORK, 1);
+		retire_network_interaction(&mut gdd, NetworkId(1), 2);
+		let old_head = gdd.session().head_rev().expect("head exists");
+
+		let remapped = gdd.linearize_history().unwrap_or_else(|error| panic!("linearize_history failed: {error:?}"));
+		let new_head = remapped.get(&old_head).copied();
+		assert_eq!(gdd.session().head_rev(), new_head);
+
+		let (working, layout) = gdd.into_storage();
+		let reopened = GddV1::open_in(working, layout).await.unwrap_or_else(|error| panic!("open_in failed: {error:?}"));
+		assert_eq!(reopened.session().head_rev(), new_head, "the remapped cursor must survive reopen");
+		assert!(reopened.registry().networks.contains_key(&NetworkId(1)));
+		reopened.session().verify_history().unwrap_or_else(|error| panic!("linearized history must verify: {error:?}"));
+	});
+}
+
+/// Cherry-picking from a forked document persists only the edit it lacks: the one both made independently
+/// is recognized by content and skipped, and a reopen sees the picked edit in history and registry.
+#[test]
+fn cherry_pick_persists_only_missing_edits() {
+	futures::executor::block_on(async {
+		let mut gdd = GddV1::create_in(empty_container(), GddV1Layout, PeerId(5), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+		let mut fork = GddV1::create_in(empty_container(), GddV1Layout, PeerId(6), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+
+		retire_network_interaction(&mut gdd, ROOT_NETWORK, 1);
+		retire_network_interaction(&mut fork, ROOT_NETWORK, 4);
+		retire_network_interaction(&mut fork, NetworkId(3), 5);
+
+		let report = gdd.cherry_pick(fork.session().history().cloned()).unwrap_or_else(|error| panic!("cherry_pick failed: {error:?}"));
+		assert_eq!(report.already_applied.len(), 1, "the shared edit is skipped");
+		assert_eq!(report.picked.len(), 1);
+
+		let (working, layout) = gdd.into_storage();
+		let reopened = GddV1::open_in(working, layout).await.unwrap_or_else(|error| panic!("open_in failed: {error:?}"));
+		assert_eq!(reopened.session().history().count(), 2);
+		assert!(reopened.registry().networks.contains_key(&NetworkId(3)));
+		assert!(
+			reopened.session().delta(report.picked[0].1).is_some_and(|delta| delta.is_interaction_end()),
+			"the pick is one undo step"
+		);
+	});
+}
+
+/// A signing peer's key registration and signatures persist with history, so a reopen, which holds no key,
+/// still verifies every commit.
+#[test]
+fn signed_history_verifies_after_reopen() {
+	use document_graph_storage::{PeerKey, SigningKey};
+	use graphene_resource::ResourceId;
+
+	futures::executor::block_on(async {
+		let mut gdd = GddV1::create_in(empty_container(), GddV1Layout, PeerId(5), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+		let key = SigningKey::from_bytes(&[5; 32]);
+		gdd.set_signing_key(key.clone()).unwrap_or_else(|error| panic!("set_signing_key failed: {error:?}"));
+		gdd.add_resource(ResourceId::new(), b"signed").unwrap_or_else(|error| panic!("add_resource failed: {error:?}"));
+
+		let (working, layout) = gdd.into_storage();
+		let reopened = GddV1::open_in(working, layout).await.unwrap_or_else(|error| panic!("open_in failed: {error:?}"));
+		assert_eq!(reopened.registry().peer_keys.get(&PeerId(5)), Some(&PeerKey::of(&key)), "the key registration survives reopen");
+
+		let audit = reopened.session().verify_signed_history().unwrap_or_else(|error| panic!("verify_signed_history failed: {error:?}"));
+		assert!(audit.is_trusted(), "every commit must verify after reopen: {:?}", audit.issues);
+		assert_eq!(audit.verified, reopened.session().history().count());
+	});
+}
+
+#[test]
+fn merge_from_joins_forked_documents_and_copies_their_resources() {
+	use graphene_resource::{ResourceHash, ResourceId};
+
+	futures::executor::block_on(async {
+		let mut ours = GddV1::create_in(empty_container(), GddV1Layout, PeerId(1), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+		ours.add_resource(ResourceId::new(), b"base").unwrap_or_else(|error| panic!("add_resource failed: {error:?}"));
+		let base = ours.session().head_rev().expect("base commit");
+
+		// Fork: a second copy adopts the shared history, then each side adds its own resource.
+		let mut theirs = GddV1::create_in(empty_container(), GddV1Layout, PeerId(2), 0xDEAD, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+		theirs.merge(ours.session().history().cloned()).unwrap_or_else(|error| panic!("fork failed: {error:?}"));
+		ours.add_resource(ResourceId::new(), b"ours").unwrap_or_else(|error| panic!("add_resource failed: {error:?}"));
+		theirs.add_resource(ResourceId::new(), b"theirs").unwrap_or_else(|error| panic!("add_resource failed: {error:?}"));
+
+		let report = ours.merge_from(&theirs).await.unwrap_or_else(|error| panic!("merge_from failed: {error:?}"));
+		assert_eq!(report.bases, vec![base]);
+		assert!(report.merge.is_some(), "diverged copies need a merge commit");
+		assert!(report.conflicts.is_empty(), "adding distinct resources writes no shared slot");
+		let theirs_hash = ResourceHash::from(b"theirs".as_slice());
+		assert!(ours.has_resource(&theirs_hash).await, "bytes only the other copy stored are copied over");
+
+		let merged_head = ours.session().head_rev();
+		let (working, layout) = ours.into_storage();
+		let reopened = GddV1::open_in(working, layout).await.unwrap_or_else(|error| panic!("open_in failed: {error:?}"));
+		assert_eq!(reopened.session().head_rev(), merged_head, "the merge head survives reopen");
+		assert_eq!(reopened.registry().resources.len(), 3, "the registry holds both sides' resources");
+		assert_eq!(reopened.read_resource(&theirs_hash).await.expect("copied bytes").as_slice(), b"theirs");
+	});
+}
+
 #[test]
 fn export_folder_round_trips_through_open() {
 	use document_format::{ExportFormat, ExportOptions};
@@ -329,6 +482,27 @@ fn resource_round_trip_add_read_remove() {
 	});
 }
 
+#[test]
+fn remove_unreferenced_resources_keeps_referenced_bytes() {
+	use graphene_resource::{ResourceHash, ResourceId, ResourceStorage};
+
+	futures::executor::block_on(async {
+		let mut gdd = GddV1::create_in(empty_container(), GddV1Layout, PeerId(99), 0xCAFE, "ed".into(), "std".into())
+			.await
+			.unwrap_or_else(|error| panic!("create_in failed: {error:?}"));
+		gdd.add_resource(ResourceId::new(), b"referenced").unwrap_or_else(|error| panic!("add_resource failed: {error:?}"));
+		let orphan = gdd.store(b"orphan");
+
+		let removed = gdd
+			.remove_unreferenced_resources()
+			.await
+			.unwrap_or_else(|error| panic!("remove_unreferenced_resources failed: {error:?}"));
+		assert_eq!(removed, vec![orphan]);
+		assert_eq!(gdd.resource_hashes().await.unwrap(), vec![ResourceHash::from(b"referenced".as_slice())]);
+		assert!(gdd.remove_unreferenced_resources().await.unwrap().is_empty(), "a second pass finds nothing");
+	});
+}
+
 #[test]
 fn resource_survives_reopen() {
 	use graphene_resource::{ResourceHash, ResourceId};
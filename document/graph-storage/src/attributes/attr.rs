@@ -5,6 +5,12 @@ pub mod node {
 	pub const SKIP_DEDUPLICATION: &str = "skip_deduplication";
 	pub const REFLECTION_METADATA: &str = "reflection_metadata";
 	pub const ORIGINAL_NODE_ID: &str = "original_node_id";
+	/// Serialized `ComponentEntry` linking the node to a reusable component as its master or as an instance.
+	/// Instances whose network matches their master's share it through `Implementation::Network`.
+	pub const COMPONENT: &str = "component";
+	/// Serialized `LibraryLinkEntry` recording which exported node of which library document this node was imported from,
+	/// at which library version, and whether it is pinned to that version.
+	pub const LIBRARY_LINK: &str = "library_link";
 
 	pub mod input {
 		pub const IMPORT_TYPE: &str = "import_type";
@@ -26,6 +32,8 @@ pub mod node {
 		pub const PINNED: &str = "ui::pinned";
 		pub const OUTPUT_NAMES: &str = "ui::output_names";
 		pub const REFERENCE: &str = "ui::reference"; // TODO: Remove?
+		/// Serialized layout grid laid over an artboard. Part of the document, so it's shared with collaborators and undone with the artboard.
+		pub const LAYOUT_GRID: &str = "ui::layout_grid";
 	}
 }
 
@@ -46,15 +54,26 @@ pub mod session {
 		pub const RENDER_MODE: &str = "ui::render_mode";
 		pub const OVERLAYS: &str = "ui::overlays";
 		pub const RULERS_VISIBLE: &str = "ui::rulers_visible";
+		pub const GUIDES: &str = "ui::guides";
 		pub const SNAPPING: &str = "ui::snapping";
 		pub const COLLAPSED: &str = "ui::collapsed";
+		/// Where this peer imported each linked node library from, by library ID. Paths are only meaningful on the machine
+		/// they were recorded on, so they stay out of the document content.
+		pub const LIBRARY_PATHS: &str = "ui::library_paths";
 	}
 }
 
 pub mod registry {
+	/// The document's library API: the storage `NodeId`s of root-network nodes an importing document can place.
 	pub const EXPORTED_NODES: &str = "exported_nodes";
 }
 
+pub mod library {
+	// Library metadata on a node listed in `registry::EXPORTED_NODES`, read by documents importing this one.
+	pub const NAME: &str = "library::name";
+	pub const CATEGORY: &str = "library::category";
+}
+
 pub mod network {
 	/// Whole-map LWW of a network's `scope_injections` (`key -> (storage NodeId, Type)`), stored as a
 	/// serialized blob so its shape can evolve (e.g. dropping the `Type`) without a model change. The
@@ -65,4 +84,6 @@ pub mod network {
 pub mod delta {
 	/// Marks the last delta of a user interaction, so the undo cursor steps per-interaction, not per-delta.
 	pub const INTERACTION_END: &str = "interaction_end";
+	/// Hex-encoded Ed25519 signature by the delta's author. See [`crate::signing`].
+	pub const SIGNATURE: &str = "signature";
 }
@@ -0,0 +1,99 @@
+//! Conflict reporting for merges of diverged branches (see [`Session::merge_branch`](crate::Session::merge_branch)).
+//!
+//! A merge never fails on concurrent writes: each LWW slot keeps the later-timestamped value and the other
+//! side's write is silently superseded. That is the right convergence rule, but it hides decisions a person
+//! may want to revisit after reconciling two forks, so a branch merge also reports every LWW slot (node
+//! inputs, network exports, attributes at every scope, resource hashes and sources) that both sides wrote
+//! since their common ancestry with different final values. Structural ops (adding or removing nodes,
+//! networks and resources) aren't slots and are never reported.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Delta, RegistryDelta, Rev, TimeStamp, TimestampKey};
+
+/// Which side of a branch merge a value came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+	/// The session being merged into.
+	Ours,
+	/// The incoming branch.
+	Theirs,
+}
+
+/// One LWW slot both sides of a merge wrote with different values. Values are JSON, with `null` meaning
+/// the side removed (or cleared) the slot.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+	pub slot: TimestampKey,
+	/// Our last write to the slot since the common ancestry.
+	pub ours: serde_json::Value,
+	/// Their last write to the slot since the common ancestry.
+	pub theirs: serde_json::Value,
+	/// Whose value the merged registry holds: the later-timestamped write.
+	pub winner: Side,
+}
+
+/// Outcome of [`Session::merge_branch`](crate::Session::merge_branch).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BranchMerge {
+	/// The merge bases: the newest commits both sides share. One for a plain fork, several after criss-cross
+	/// merges, and none if either side had no history.
+	pub bases: Vec<Rev>,
+	/// How many of the incoming branch's deltas were new here.
+	pub imported: usize,
+	/// The minted `Merge` delta, or `None` if nothing was imported or one side simply fast-forwarded.
+	pub merge: Option<Rev>,
+	/// Slots written differently on both sides, ordered by slot.
+	pub conflicts: Vec<MergeConflict>,
+}
+
+/// Every slot both `ours` and `theirs` wrote, with differing final values. Each side's final value is its
+/// latest-timestamped write to the slot, which is what LWW keeps from that side.
+pub(crate) fn conflicting_writes<'a>(ours: impl IntoIterator<Item = &'a Delta>, theirs: impl IntoIterator<Item = &'a Delta>) -> Vec<MergeConflict> {
+	let ours = last_writes(ours);
+	let mut theirs = last_writes(theirs);
+
+	let mut conflicts = Vec::new();
+	for (slot, (ours, ours_timestamp)) in ours {
+		let Some((theirs, theirs_timestamp)) = theirs.remove(&slot) else { continue };
+		if ours == theirs {
+			continue;
+		}
+		let winner = if ours_timestamp > theirs_timestamp { Side::Ours } else { Side::Theirs };
+		conflicts.push(MergeConflict { slot, ours, theirs, winner });
+	}
+	conflicts
+}
+
+fn last_writes<'a>(deltas: impl IntoIterator<Item = &'a Delta>) -> BTreeMap<TimestampKey, (serde_json::Value, TimeStamp)> {
+	let mut writes: BTreeMap<TimestampKey, (serde_json::Value, TimeStamp)> = BTreeMap::new();
+	for delta in deltas {
+		let Some((slot, value)) = written_slot(&delta.kind) else { continue };
+		if writes.get(&slot).is_none_or(|(_, timestamp)| delta.timestamp > *timestamp) {
+			writes.insert(slot, (value, delta.timestamp));
+		}
+	}
+	writes
+}
+
+/// The LWW slot `op` writes and the value it writes there, or `None` for structural and bookkeeping ops.
+fn written_slot(op: &RegistryDelta) -> Option<(TimestampKey, serde_json::Value)> {
+	fn json(value: &impl Serialize) -> serde_json::Value {
+		serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+	}
+
+	Some(match op {
+		RegistryDelta::ChangeNodeInput { id, index, new_input } => (TimestampKey::NodeInput(*id, *index as usize), json(new_input)),
+		RegistryDelta::ChangeNodeAttribute { id, delta } => (TimestampKey::NodeAttribute(*id, delta.key.clone()), json(&delta.value)),
+		RegistryDelta::ChangeNodeInputAttribute { id, index, delta } => (TimestampKey::NodeInputAttribute(*id, *index as usize, delta.key.clone()), json(&delta.value)),
+		RegistryDelta::SetNetworkExport { id, index, export } => (TimestampKey::NetworkExport(*id, *index as usize), json(export)),
+		RegistryDelta::ChangeNetworkAttribute { id, delta } => (TimestampKey::NetworkAttribute(*id, delta.key.clone()), json(&delta.value)),
+		RegistryDelta::ChangeDocumentAttribute { delta } => (TimestampKey::DocumentAttribute(delta.key.clone()), json(&delta.value)),
+		RegistryDelta::SetResourceHash { id, hash } => (TimestampKey::ResourceHash(*id), json(hash)),
+		RegistryDelta::AddSource { id, key, source } => (TimestampKey::ResourceSource(*id, *key), source.clone()),
+		RegistryDelta::RemoveSource { id, key } => (TimestampKey::ResourceSource(*id, *key), serde_json::Value::Null),
+		_ => return None,
+	})
+}
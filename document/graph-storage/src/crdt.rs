@@ -1,4 +1,7 @@
-use crate::{Attributes, AttributesWrite, Network, NetworkId, Node, NodeId, NodeInput, PeerId, ResourceEntry, ResourceId, Rev, SourceKey, TimeStamp, UserId, Value, attr, compute_rev};
+use crate::{
+	Attributes, AttributesWrite, ContentId, Network, NetworkId, Node, NodeId, NodeInput, PeerId, PeerKey, ResourceEntry, ResourceId, Rev, SourceKey, TimeStamp, UserId, Value, attr,
+	compute_content_id, compute_rev,
+};
 use graphene_resource::ResourceHash;
 use serde::{Deserialize, Serialize};
 
@@ -88,6 +91,11 @@ impl Delta {
 	pub fn has_valid_id(&self) -> bool {
 		self.id == self.recomputed_id()
 	}
+
+	/// The position-independent identity of this delta's edit. See [`RegistryDelta::content_id`].
+	pub fn content_id(&self) -> Option<ContentId> {
+		self.kind.content_id()
+	}
 }
 
 /// Op payload. Timestamps live on the wrapping `Delta` — one per delta, applied to all LWW-eligible
@@ -175,6 +183,13 @@ pub enum RegistryDelta {
 		peer: PeerId,
 		user: UserId,
 	},
+	/// Append-only registration of a device's Ed25519 public key, against which its signed deltas are
+	/// verified. Same semantics as `RegisterPeer`: first write wins, a conflicting key errors, and an
+	/// identical re-registration is a no-op. See [`crate::signing`].
+	RegisterPeerKey {
+		peer: PeerId,
+		key: PeerKey,
+	},
 	ChangeDocumentAttribute {
 		delta: AttributeDelta,
 	},
@@ -189,6 +204,64 @@ pub enum RegistryDelta {
 	Other(serde_json::Value),
 }
 
+impl RegistryDelta {
+	/// The [`ContentId`] of this op: a hash of the payload alone, so the same edit replayed onto another
+	/// branch or document is recognizable as such. `None` for a `Merge`, which is DAG bookkeeping with no
+	/// edit of its own.
+	///
+	/// Normalizes out what a replay elsewhere would change: the LWW timestamps embedded in `AddNode`,
+	/// `AddNetwork` and `AddResource` payloads are reset to [`TimeStamp::ORIGIN`], and the `Remove*` ops hash
+	/// only the removed id, since their `snapshot` is pre-state (removing a node is the same edit whatever
+	/// the node held at the time). Computed on demand; O(payload size).
+	pub fn content_id(&self) -> Option<ContentId> {
+		let id = match self {
+			RegistryDelta::Merge { .. } => return None,
+			RegistryDelta::RemoveNode { id, .. } => compute_content_id(&("RemoveNode", id)),
+			RegistryDelta::RemoveNetwork { id, .. } => compute_content_id(&("RemoveNetwork", id)),
+			RegistryDelta::RemoveResource { id, .. } => compute_content_id(&("RemoveResource", id)),
+			_ => {
+				let mut normalized = self.clone();
+				normalized.clear_payload_timestamps();
+				compute_content_id(&normalized)
+			}
+		};
+		Some(id)
+	}
+
+	/// Reset every LWW timestamp carried inside the payload. The wrapping `Delta`'s timestamp is the live
+	/// one; these only record when the embedded state was last written, which is position-dependent.
+	fn clear_payload_timestamps(&mut self) {
+		fn clear_attributes(attributes: &mut Attributes) {
+			for value in attributes.values_mut() {
+				value.timestamp = TimeStamp::ORIGIN;
+			}
+		}
+
+		match self {
+			RegistryDelta::AddNode { node, .. } => {
+				clear_attributes(&mut node.attributes);
+				for slot in &mut node.inputs {
+					slot.timestamp = TimeStamp::ORIGIN;
+					clear_attributes(&mut slot.attributes);
+				}
+			}
+			RegistryDelta::AddNetwork { network, .. } => {
+				clear_attributes(&mut network.attributes);
+				for slot in &mut network.exports {
+					slot.timestamp = TimeStamp::ORIGIN;
+				}
+			}
+			RegistryDelta::AddResource { entry, .. } => {
+				entry.hash_timestamp = TimeStamp::ORIGIN;
+				for (_, source) in &mut entry.sources {
+					source.timestamp = TimeStamp::ORIGIN;
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
 /// `value: None` means remove. The timestamp comes from the wrapping `Delta`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AttributeDelta {
@@ -284,6 +284,16 @@ impl Document {
 					registry.peer_users.insert(peer, user);
 				}
 			},
+			// A peer only registers its own key, so a registration stamped by another peer's clock is ignored
+			// rather than allowed to claim the slot (see `signing::registered_keys` for the matching check on history).
+			RegistryDelta::RegisterPeerKey { peer, .. } if timestamp.peer != peer => {}
+			RegistryDelta::RegisterPeerKey { peer, key } => match registry.peer_keys.get(&peer) {
+				Some(existing) if *existing != key => return Err(CrdtError::PeerKeyConflict(peer)),
+				Some(_) => {}
+				None => {
+					registry.peer_keys.insert(peer, key);
+				}
+			},
 			RegistryDelta::ChangeDocumentAttribute { delta } => {
 				apply_attribute_delta(delta, timestamp, force, &mut registry.attributes);
 			}
@@ -388,6 +398,7 @@ impl Document {
 			// Registrations are append-only and not user-undoable; reverse is the same op,
 			// which applies as a no-op on the already-registered PeerId.
 			&RegistryDelta::RegisterPeer { peer, user } => RegistryDelta::RegisterPeer { peer, user },
+			&RegistryDelta::RegisterPeerKey { peer, key } => RegistryDelta::RegisterPeerKey { peer, key },
 			&RegistryDelta::SetResourceHash { id, .. } => RegistryDelta::SetResourceHash {
 				id,
 				hash: registry.resources.get(&id).and_then(|entry| entry.hash),
@@ -9,7 +9,7 @@ use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeInput
 use serde::Serialize;
 
 use crate::attr::*;
-use crate::metadata_source::{NoMetadata, NodeMetadataSource};
+use crate::metadata_source::{ComponentEntry, NoMetadata, NodeMetadataSource};
 use crate::{AttributesWrite, ExportSlot, Implementation, InputSlot, Network, NetworkId, Node, NodeId, NodeInput, PeerId, ProtoNode, ROOT_NETWORK, Registry, ResourceHash, ResourceId, TimeStamp};
 
 fn map_serialization_error(key: &str) -> impl FnOnce(serde_json::Error) -> ConversionError + '_ {
@@ -146,16 +146,29 @@ impl Registry {
 		peer: PeerId,
 	) -> Result<RuntimeConversion, ConversionError> {
 		let mut registry = Registry::default();
+		let mut component_masters = HashMap::new();
+		collect_component_masters(node_network, ROOT_NETWORK, None, &[], metadata, peer, &mut component_masters);
+
 		let mut ctx = ConversionContext {
 			declaration_ids: HashMap::new(),
 			declaration_bytes: HashMap::new(),
 			network_ids: HashMap::new(),
+			component_masters,
+			exported_nodes: Vec::new(),
 			metadata,
 			peer,
 		};
 
 		convert_network(node_network, ROOT_NETWORK, None, &[], &mut registry, &mut ctx)?;
 
+		if !ctx.exported_nodes.is_empty() {
+			ctx.exported_nodes.sort();
+			registry
+				.attributes
+				.set_serialized(registry::EXPORTED_NODES, &ctx.exported_nodes, TimeStamp::ORIGIN)
+				.map_err(map_serialization_error(registry::EXPORTED_NODES))?;
+		}
+
 		// Only snapshot resources the network actually references. The runtime resource cache also keeps
 		// resources alive across undo (so legacy redo can restore them), so it can contain orphans whose
 		// node was removed by an undo. Snapshotting those would re-introduce an `AddResource` on the next
@@ -257,10 +270,50 @@ struct ConversionContext<'m, M: NodeMetadataSource + ?Sized> {
 	/// Maps each network's runtime `metadata_path` to its stable storage `NetworkId`, so the caller can
 	/// associate per-network, per-peer view state (in `session.json`) with networks without re-deriving ids.
 	network_ids: HashMap<Vec<RuntimeNodeId>, NetworkId>,
+	/// Each component's master network and the `NetworkId` it is stored under. Instances whose network
+	/// still equals their master's reference that id instead of storing another copy.
+	component_masters: HashMap<u64, (NetworkId, &'m NodeNetwork)>,
+	/// Storage IDs of the root-network nodes the metadata marks as exported, written to `exported_nodes`.
+	exported_nodes: Vec<NodeId>,
 	metadata: &'m M,
 	peer: PeerId,
 }
 
+/// Find every component master's nested network ahead of the conversion, since an instance may be converted
+/// before its master. Instances aren't descended into: a master copied into an instance's network is not the
+/// component's definition.
+fn collect_component_masters<'n, M: NodeMetadataSource + ?Sized>(
+	node_network: &'n NodeNetwork,
+	network_id: NetworkId,
+	parent_path: Option<&NodePath>,
+	metadata_path: &[RuntimeNodeId],
+	metadata: &M,
+	peer: PeerId,
+	masters: &mut HashMap<u64, (NetworkId, &'n NodeNetwork)>,
+) {
+	for (runtime_node_id, doc_node) in &node_network.nodes {
+		let DocumentNodeImplementation::Network(nested_network) = &doc_node.implementation else {
+			continue;
+		};
+
+		let component = metadata.component(metadata_path, *runtime_node_id);
+		if component.as_ref().is_some_and(|component| !component.master) {
+			continue;
+		}
+
+		// Mirrors the path and id derivation in `convert_network` and `convert_implementation`
+		let node_path = child_path(parent_path, network_id, *runtime_node_id);
+		let nested_network_id = node_path.owned_network_id(peer);
+		if let Some(component) = component {
+			masters.entry(component.component_id).or_insert((nested_network_id, nested_network));
+		}
+
+		let mut child_metadata_path = metadata_path.to_vec();
+		child_metadata_path.push(*runtime_node_id);
+		collect_component_masters(nested_network, nested_network_id, Some(&node_path), &child_metadata_path, metadata, peer, masters);
+	}
+}
+
 fn convert_network<M: NodeMetadataSource + ?Sized>(
 	node_network: &NodeNetwork,
 	network_id: NetworkId,
@@ -383,7 +436,11 @@ fn convert_node<M: NodeMetadataSource + ?Sized>(doc_node: &DocumentNode, locatio
 	} else {
 		metadata_path
 	};
-	let implementation = convert_implementation(&doc_node.implementation, &node_path, child_metadata_path, registry, ctx)?;
+	let component = ctx.metadata.component(metadata_path, runtime_node_id);
+	let implementation = match shared_component_network(doc_node, component.as_ref(), ctx) {
+		Some(shared_network_id) => Implementation::Network(shared_network_id),
+		None => convert_implementation(&doc_node.implementation, &node_path, child_metadata_path, registry, ctx)?,
+	};
 
 	// Defaults match `DocumentNode::default()`; `to_runtime` rehydrates absent keys from the same defaults.
 	let mut attributes = crate::Attributes::new();
@@ -400,6 +457,29 @@ fn convert_node<M: NodeMetadataSource + ?Sized>(doc_node: &DocumentNode, locatio
 		.set_if_not_default(node::SKIP_DEDUPLICATION, &doc_node.skip_deduplication, &false, timestamp)
 		.map_err(map_serialization_error(node::SKIP_DEDUPLICATION))?;
 
+	if let Some(component) = &component {
+		attributes.set_serialized(node::COMPONENT, component, timestamp).map_err(map_serialization_error(node::COMPONENT))?;
+	}
+	if let Some(library_link) = ctx.metadata.library_link(metadata_path, runtime_node_id) {
+		attributes
+			.set_serialized(node::LIBRARY_LINK, &library_link, timestamp)
+			.map_err(map_serialization_error(node::LIBRARY_LINK))?;
+	}
+	if let Some(layout_grid) = ctx.metadata.layout_grid(metadata_path, runtime_node_id) {
+		attributes.set(node::ui::LAYOUT_GRID, layout_grid, timestamp);
+	}
+
+	// Only root-network nodes can be placed by an importing document
+	if network_id == ROOT_NETWORK
+		&& let Some(export) = ctx.metadata.library_export(metadata_path, runtime_node_id)
+	{
+		attributes.set(library::NAME, serde_json::json!(export.name), timestamp);
+		if !export.category.is_empty() {
+			attributes.set(library::CATEGORY, serde_json::json!(export.category), timestamp);
+		}
+		ctx.exported_nodes.push(node_path.to_global_id(ctx.peer));
+	}
+
 	write_ui_attributes(&mut attributes, ctx.metadata, metadata_path, runtime_node_id, timestamp)?;
 
 	Ok(Node {
@@ -410,6 +490,18 @@ fn convert_node<M: NodeMetadataSource + ?Sized>(doc_node: &DocumentNode, locatio
 	})
 }
 
+/// The master's stored network, when `doc_node` is an instance whose nested network is identical to its master's.
+/// An instance that has drifted (its master was edited since the last sync, or the master is gone) keeps its own copy.
+fn shared_component_network<M: NodeMetadataSource + ?Sized>(doc_node: &DocumentNode, component: Option<&ComponentEntry>, ctx: &ConversionContext<'_, M>) -> Option<NetworkId> {
+	let component = component.filter(|component| !component.master)?;
+	let DocumentNodeImplementation::Network(nested_network) = &doc_node.implementation else {
+		return None;
+	};
+	let &(master_network_id, master_network) = ctx.component_masters.get(&component.component_id)?;
+
+	(nested_network == master_network).then_some(master_network_id)
+}
+
 fn write_ui_attributes<M: NodeMetadataSource + ?Sized>(
 	attributes: &mut crate::Attributes,
 	metadata: &M,
@@ -128,6 +128,42 @@ impl History {
 		tips
 	}
 
+	/// The tips of the sub-DAG reachable from `roots`: like [`tips`](Self::tips), but ignoring branches
+	/// outside that ancestry. Sorted ascending for determinism.
+	pub fn tips_of(&self, roots: impl IntoIterator<Item = Rev>) -> Vec<Rev> {
+		let reachable = self.ancestry(roots);
+		let referenced: std::collections::HashSet<Rev> = reachable.iter().filter_map(|&rev| self.get(rev)).flat_map(|delta| delta.all_parents()).collect();
+		let mut tips: Vec<Rev> = reachable.into_iter().filter(|rev| !referenced.contains(rev)).collect();
+		tips.sort_unstable();
+		tips
+	}
+
+	/// Every rev reachable from `roots` by following all parents (a merge's `extra_parents` included),
+	/// roots themselves included. Roots absent from history are ignored. The result is closed under
+	/// parents, so it is always a valid argument to [`retain`](Self::retain).
+	pub fn ancestry(&self, roots: impl IntoIterator<Item = Rev>) -> std::collections::HashSet<Rev> {
+		let mut reachable = std::collections::HashSet::new();
+		let mut stack: Vec<Rev> = roots.into_iter().filter(|rev| self.index.contains_key(rev)).collect();
+		while let Some(rev) = stack.pop() {
+			if !reachable.insert(rev) {
+				continue;
+			}
+			let Some(delta) = self.get(rev) else { continue };
+			stack.extend(delta.all_parents().filter(|parent| self.index.contains_key(parent) && !reachable.contains(parent)));
+		}
+		reachable
+	}
+
+	/// Drop every delta not in `keep`, preserving the relative (topological) order of the survivors and
+	/// rebuilding the index. `keep` must be closed under parents, as [`ancestry`](Self::ancestry) returns,
+	/// or a survivor would reference a dropped parent. Returns the number of deltas removed.
+	pub fn retain(&mut self, keep: &std::collections::HashSet<Rev>) -> usize {
+		let before = self.deltas.len();
+		self.deltas.retain(|delta| keep.contains(&delta.id));
+		self.index = self.deltas.iter().enumerate().map(|(position, delta)| (delta.id, position)).collect();
+		before - self.deltas.len()
+	}
+
 	/// Mark a retired delta as the end of a user interaction. Mutates only the delta's attributes
 	/// (excluded from its `Rev`), so the index stays valid. Returns whether the delta was found.
 	pub fn mark_interaction_end(&mut self, rev: Rev, timestamp: TimeStamp) -> bool {
@@ -162,12 +198,20 @@ impl History {
 	/// content-addressed `id` matches its recomputed hash, and the deltas are in topological order
 	/// (each delta's in-history parents precede it). Returns the first violation found.
 	pub fn verify(&self) -> Result<(), CrdtError> {
-		let mut seen: std::collections::HashSet<Rev> = std::collections::HashSet::with_capacity(self.deltas.len());
 		for delta in &self.deltas {
 			let expected = delta.recomputed_id();
 			if delta.id != expected {
 				return Err(CrdtError::RevMismatch { stored: delta.id, expected });
 			}
+		}
+		self.verify_order()
+	}
+
+	/// The topological half of [`verify`](Self::verify): each delta's in-history parents precede it. Returns
+	/// the first parent found out of order.
+	pub fn verify_order(&self) -> Result<(), CrdtError> {
+		let mut seen: std::collections::HashSet<Rev> = std::collections::HashSet::with_capacity(self.deltas.len());
+		for delta in &self.deltas {
 			for parent in delta.all_parents() {
 				if self.index.contains_key(&parent) && !seen.contains(&parent) {
 					return Err(CrdtError::NotFoundInHistory(parent));
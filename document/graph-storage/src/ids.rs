@@ -50,6 +50,20 @@ impl std::fmt::Display for Rev {
 	}
 }
 
+/// Position-independent identity for a delta's op payload: the analogue of `git patch-id`. Unlike a
+/// [`Rev`] it commits to neither parent, author, nor timestamp, so the same logical edit carries the same
+/// `ContentId` on any branch or in any document. Derived on demand via [`RegistryDelta::content_id`]; never
+/// stored, so it can change with the normalization rules without a format version bump.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ContentId(pub u128);
+
+impl std::fmt::Display for ContentId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:032x}", self.0)
+	}
+}
+
 /// Root network ID. The renderable graph lives in `networks[&ROOT_NETWORK]`.
 pub const ROOT_NETWORK: NetworkId = NetworkId(0);
 
@@ -124,10 +138,22 @@ pub(crate) fn compute_rev(parent: Option<Rev>, author: PeerId, timestamp: TimeSt
 		_ => rmp_serde::to_vec(&(parent, author, timestamp, delta_type)).expect("Delta identity fields must serialize"),
 	};
 	hasher.update(&bytes);
-	let digest = hasher.finalize();
-	let mut truncated = [0u8; 16];
-	truncated.copy_from_slice(&digest.as_bytes()[..16]);
 	// A 128-bit blake3 truncation is zero with probability 2^-128 (never in practice); map it to 1 so
 	// the non-zero invariant is total rather than relying on a panic that can't realistically fire.
-	Rev::new(u128::from_le_bytes(truncated)).unwrap_or(Rev(std::num::NonZeroU128::MIN))
+	Rev::new(truncate_digest(hasher.finalize())).unwrap_or(Rev(std::num::NonZeroU128::MIN))
+}
+
+/// Hash an already-normalized op payload (see [`RegistryDelta::content_id`]) with blake3 and truncate to
+/// 128 bits. Domain-separated from `compute_rev` so a `ContentId` can never be mistaken for a `Rev`.
+pub(crate) fn compute_content_id(normalized: &impl Serialize) -> ContentId {
+	let bytes = rmp_serde::to_vec(&("content", normalized)).expect("Normalized op payload must serialize");
+	let mut hasher = blake3::Hasher::new();
+	hasher.update(&bytes);
+	ContentId(truncate_digest(hasher.finalize()))
+}
+
+fn truncate_digest(digest: blake3::Hash) -> u128 {
+	let mut truncated = [0u8; 16];
+	truncated.copy_from_slice(&digest.as_bytes()[..16]);
+	u128::from_le_bytes(truncated)
 }
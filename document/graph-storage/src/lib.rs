@@ -1,15 +1,18 @@
 pub use graphene_resource::{ResourceHash, ResourceId};
 
 pub mod attributes;
+pub mod conflict;
 pub mod crdt;
 pub mod delta;
 pub mod document;
 pub mod history;
 pub mod ids;
+pub mod library;
 pub mod model;
 pub mod registry;
 pub mod resources;
 pub mod session;
+pub mod signing;
 
 #[cfg(any(feature = "conversion", test))]
 pub mod from_runtime;
@@ -19,19 +22,22 @@ pub mod metadata_source;
 pub mod to_runtime;
 
 pub use attributes::*;
+pub use conflict::{BranchMerge, MergeConflict, Side};
 pub use crdt::*;
 pub use document::*;
 pub use history::History;
 pub use ids::*;
+pub use library::LibraryExport;
 pub use model::*;
 pub use registry::*;
 pub use resources::*;
 pub use session::*;
+pub use signing::{CommitIssue, HistoryAudit, PeerKey, SigningKey};
 
 #[cfg(any(feature = "conversion", test))]
 pub use from_runtime::{RuntimeConversion, decode_declaration, encode_declaration};
 #[cfg(any(feature = "conversion", test))]
-pub use metadata_source::{InputMetadataEntry, NetworkMetadataEntry, NoMetadata, NodeMetadataEntry, NodeMetadataSource, Position};
+pub use metadata_source::{ComponentEntry, InputMetadataEntry, LibraryExportEntry, LibraryLinkEntry, NetworkMetadataEntry, NoMetadata, NodeMetadataEntry, NodeMetadataSource, Position};
 #[cfg(any(feature = "conversion", test))]
 pub use to_runtime::Declarations;
 
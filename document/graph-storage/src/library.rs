@@ -0,0 +1,43 @@
+//! Reading a document's library API: the root-network nodes listed in its `exported_nodes` document attribute, which
+//! other documents can import. The exported nodes keep their implementation as stored, so the proto-node declarations
+//! they reference stay content-addressed resources shared with any document that imports them.
+
+use crate::attr::{library, node, registry};
+use crate::{AttributesRead, NodeId, ROOT_NETWORK, Registry};
+
+/// One node in a document's library API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LibraryExport {
+	/// Stable storage ID, recorded by importing documents to find this node again in a later version of the library.
+	pub node: NodeId,
+	/// The node's ID in the runtime root network.
+	pub local_id: u64,
+	pub name: String,
+	pub category: String,
+}
+
+impl Registry {
+	/// The storage IDs listed in the `exported_nodes` document attribute, in stored order.
+	pub fn exported_nodes(&self) -> Vec<NodeId> {
+		self.attributes.get_or_default(registry::EXPORTED_NODES)
+	}
+
+	/// Every listed node that still exists in the root network, with its library metadata. Listed IDs whose node was
+	/// removed or lives in a nested network are skipped.
+	pub fn library_exports(&self) -> Vec<LibraryExport> {
+		self.exported_nodes()
+			.into_iter()
+			.filter_map(|id| {
+				let stored = self.node_instances.get(&id).filter(|stored| stored.network == ROOT_NETWORK)?;
+				let attributes = stored.attributes();
+
+				Some(LibraryExport {
+					node: id,
+					local_id: attributes.get(node::ORIGINAL_NODE_ID).and_then(|value| value.value.as_u64()).unwrap_or(id.0),
+					name: attributes.get_or_default(library::NAME),
+					category: attributes.get_or_default(library::CATEGORY),
+				})
+			})
+			.collect()
+	}
+}
@@ -22,6 +22,10 @@ pub struct NodeMetadataEntry {
 	/// returns an error if this length does not match the node's input count.
 	pub input_metadata: Vec<InputMetadataEntry>,
 	pub output_names: Vec<String>,
+	pub component: Option<ComponentEntry>,
+	pub library_export: Option<LibraryExportEntry>,
+	pub library_link: Option<LibraryLinkEntry>,
+	pub layout_grid: Option<serde_json::Value>,
 }
 
 impl NodeMetadataEntry {
@@ -32,10 +36,44 @@ impl NodeMetadataEntry {
 			&& !self.locked
 			&& !self.pinned
 			&& self.output_names.is_empty()
+			&& self.component.is_none()
+			&& self.library_export.is_none()
+			&& self.library_link.is_none()
+			&& self.layout_grid.is_none()
 			&& self.input_metadata.iter().all(InputMetadataEntry::is_empty)
 	}
 }
 
+/// Links a node to a reusable component. The master's nested network defines the component, and every
+/// instance whose nested network still matches it references that same network in storage instead of a copy.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ComponentEntry {
+	pub component_id: u64,
+	pub master: bool,
+	/// Instance inputs whose value was set on the instance itself rather than inherited from the master.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub overridden_inputs: Vec<u32>,
+}
+
+/// How a root-network node is listed in the document's library API. Stored as `library::*` attributes on the node,
+/// with the node's ID in the `exported_nodes` document attribute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LibraryExportEntry {
+	pub name: String,
+	pub category: String,
+}
+
+/// Links a node to the library node it was imported from. `version` is the library's head revision when the node was
+/// imported or last updated, so an unpinned node can be brought up to date when a newer version of the library is loaded.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LibraryLinkEntry {
+	pub library_id: u64,
+	pub node: crate::NodeId,
+	pub version: String,
+	#[serde(default)]
+	pub pinned: bool,
+}
+
 /// Per-network metadata (navigation, previewing). Separate from `NodeMetadataEntry` since these are
 /// properties of a network, not of any node.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -110,6 +148,26 @@ pub trait NodeMetadataSource {
 		HashMap::new()
 	}
 
+	/// The node's component link, if it is a component master or instance. Stored as `attr::node::COMPONENT`.
+	fn component(&self, _network_path: &[RuntimeNodeId], _local_id: RuntimeNodeId) -> Option<ComponentEntry> {
+		None
+	}
+
+	/// Whether the node is part of the document's library API. Only read for nodes in the root network.
+	fn library_export(&self, _network_path: &[RuntimeNodeId], _local_id: RuntimeNodeId) -> Option<LibraryExportEntry> {
+		None
+	}
+
+	/// The library node this node was imported from. Stored as `attr::node::LIBRARY_LINK`.
+	fn library_link(&self, _network_path: &[RuntimeNodeId], _local_id: RuntimeNodeId) -> Option<LibraryLinkEntry> {
+		None
+	}
+
+	/// The layout grid laid over an artboard. Stored as `attr::node::ui::LAYOUT_GRID`.
+	fn layout_grid(&self, _network_path: &[RuntimeNodeId], _local_id: RuntimeNodeId) -> Option<serde_json::Value> {
+		None
+	}
+
 	fn reference(&self, _network_path: &[RuntimeNodeId]) -> Option<&str> {
 		None
 	}
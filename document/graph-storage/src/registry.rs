@@ -1,4 +1,4 @@
-use crate::{Attributes, Network, NetworkId, Node, NodeId, PeerId, ResourceId, ResourceStore, SourceKey, TimeStamp, UserId};
+use crate::{Attributes, Network, NetworkId, Node, NodeId, PeerId, PeerKey, ResourceId, ResourceStore, SourceKey, TimeStamp, UserId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,15 +13,19 @@ pub struct Registry {
 	/// Registered by each device's first contribution via `RegistryDelta::RegisterPeer`.
 	pub peer_users: HashMap<PeerId, UserId>,
 	pub attributes: Attributes,
+	/// Append-only mapping from `PeerId` to the public key its deltas are signed with. Registered by a
+	/// signing device's first contribution via `RegistryDelta::RegisterPeerKey`; absent for unsigned peers.
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	pub peer_keys: HashMap<PeerId, PeerKey>,
 }
 
 impl Registry {
 	/// True if both registries agree on every value-bearing field, ignoring per-slot and
 	/// per-attribute timestamps. Mirrors `compute_deltas`'s value-only semantics, so unchanged
-	/// state at a stamped slot doesn't count as drift. `peer_users` is excluded: it isn't diffed by
-	/// `compute_deltas` (the mapping is injected on the commit path via `RegisterPeer`, never by a
-	/// fresh `from_runtime` conversion), so a committed registry and a fresh conversion legitimately
-	/// differ there without it counting as drift.
+	/// state at a stamped slot doesn't count as drift. `peer_users` and `peer_keys` are excluded: they
+	/// aren't diffed by `compute_deltas` (the mappings are injected on the commit path via `RegisterPeer`
+	/// and `RegisterPeerKey`, never by a fresh `from_runtime` conversion), so a committed registry and a
+	/// fresh conversion legitimately differ there without it counting as drift.
 	pub fn value_equal(&self, other: &Self) -> bool {
 		if !resources_value_equal(&self.resources, &other.resources) {
 			return false;
@@ -106,9 +110,10 @@ pub(crate) fn resources_value_equal(a: &ResourceStore, b: &ResourceStore) -> boo
 	})
 }
 
-/// Stable identity for any timestamped slot in a `Registry`. Used by `order_consistent`.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum TimestampKey {
+/// Stable identity for any timestamped slot in a `Registry`. Used by `order_consistent`, and to name the
+/// slots a branch merge reports as conflicting (see [`MergeConflict`](crate::MergeConflict)).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TimestampKey {
 	NodeInput(NodeId, usize),
 	NodeInputAttribute(NodeId, usize, String),
 	NodeAttribute(NodeId, String),
@@ -119,6 +124,21 @@ enum TimestampKey {
 	ResourceSource(ResourceId, SourceKey),
 }
 
+impl std::fmt::Display for TimestampKey {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			TimestampKey::NodeInput(node, index) => write!(f, "node {node} input {index}"),
+			TimestampKey::NodeInputAttribute(node, index, key) => write!(f, "node {node} input {index} attribute {key}"),
+			TimestampKey::NodeAttribute(node, key) => write!(f, "node {node} attribute {key}"),
+			TimestampKey::NetworkExport(network, index) => write!(f, "network {network} export {index}"),
+			TimestampKey::NetworkAttribute(network, key) => write!(f, "network {network} attribute {key}"),
+			TimestampKey::DocumentAttribute(key) => write!(f, "document attribute {key}"),
+			TimestampKey::ResourceHash(id) => write!(f, "resource {id} hash"),
+			TimestampKey::ResourceSource(id, key) => write!(f, "resource {id} source at priority {} (peer {})", key.priority.value(), key.peer.0),
+		}
+	}
+}
+
 fn collect_timestamps(registry: &Registry) -> HashMap<TimestampKey, TimeStamp> {
 	let mut out = HashMap::new();
 	for (node_id, node) in &registry.node_instances {
@@ -2,7 +2,10 @@
 use crate::NodeMetadataSource;
 #[cfg(any(feature = "conversion", test))]
 use crate::from_runtime;
-use crate::{ApplyMode, Delta, Document, History, LamportClock, NetworkId, NodeId, PeerId, Registry, RegistryDelta, RegistryTarget, ResourceEntry, Rev, TimeStamp, UserId};
+use crate::{
+	ApplyMode, BranchMerge, ContentId, Delta, Document, History, HistoryAudit, LamportClock, NetworkId, NodeId, PeerId, PeerKey, Registry, RegistryDelta, RegistryTarget, ResourceEntry, Rev,
+	SigningKey, TimeStamp, UserId, signing,
+};
 use graphene_resource::{ResourceHash, ResourceId};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -16,6 +19,8 @@ pub struct Session {
 	/// leader-eligibility computation (lowest PeerId among peers whose tip matches the session max).
 	#[expect(dead_code, reason = "Populated once heartbeat/leader-election transport lands; held now so the field and constructors are in place.")]
 	remote_tips: HashMap<PeerId, Rev>,
+	/// Signs every delta this session commits, when set. Never persisted: the editor supplies it each session.
+	signing_key: Option<SigningKey>,
 }
 
 impl Session {
@@ -44,6 +49,7 @@ impl Session {
 				next_node_counter: 0,
 			},
 			remote_tips: HashMap::new(),
+			signing_key: None,
 		}
 	}
 
@@ -55,6 +61,26 @@ impl Session {
 		&self.document.working_registry
 	}
 
+	/// Sign every delta this session commits from now on with `key`. Its public half is registered in the
+	/// document (a `RegisterPeerKey` op) with this peer's next contribution, so other peers can verify the
+	/// signatures with [`verify_signed_history`](Self::verify_signed_history). Earlier commits stay unsigned.
+	///
+	/// Errors with [`CrdtError::PeerKeyConflict`] if this peer already registered a different key:
+	/// registrations are forever, so signatures by another key would never verify.
+	pub fn set_signing_key(&mut self, key: SigningKey) -> Result<(), CrdtError> {
+		let peer = self.document.peer;
+		if self.document.working_registry.peer_keys.get(&peer).is_some_and(|registered| *registered != PeerKey::of(&key)) {
+			return Err(CrdtError::PeerKeyConflict(peer));
+		}
+		self.signing_key = Some(key);
+		Ok(())
+	}
+
+	/// The public key deltas committed by this session are signed with, if signing is enabled.
+	pub fn signing_key(&self) -> Option<PeerKey> {
+		self.signing_key.as_ref().map(PeerKey::of)
+	}
+
 	/// The registry after applying retired history only, without the unretired hot tail. Persisted as the
 	/// snapshot alongside `history` + hot log so a reopen restores the same retired-then-hot layering.
 	pub fn retired_registry(&self) -> &Registry {
@@ -135,17 +161,25 @@ impl Session {
 	///
 	/// The peer's first contribution is preceded by a `RegisterPeer` op, so the device's
 	/// `PeerId → UserId` mapping is established (and, under causal delivery, observed by other peers)
-	/// before any of its edits. A no-op batch doesn't register — registration rides a real edit.
+	/// before any of its edits. A signing session's first contribution likewise registers its public key
+	/// with `RegisterPeerKey`. A no-op batch doesn't register — registration rides a real edit.
 	fn stage_ops(&mut self, ops: impl IntoIterator<Item = RegistryDelta>) -> Result<Vec<HotOp>, CrdtError> {
 		let mut pending: Vec<RegistryDelta> = ops.into_iter().collect();
 		if pending.is_empty() {
 			return Ok(Vec::new());
 		}
 
-		if !self.document.working_registry.peer_users.contains_key(&self.document.peer) {
-			let user = UserId(self.document.peer.0);
-			pending.insert(0, RegistryDelta::RegisterPeer { peer: self.document.peer, user });
+		let peer = self.document.peer;
+		let mut registrations = Vec::new();
+		if !self.document.working_registry.peer_users.contains_key(&peer) {
+			registrations.push(RegistryDelta::RegisterPeer { peer, user: UserId(peer.0) });
+		}
+		if let Some(key) = &self.signing_key
+			&& !self.document.working_registry.peer_keys.contains_key(&peer)
+		{
+			registrations.push(RegistryDelta::RegisterPeerKey { peer, key: PeerKey::of(key) });
 		}
+		pending.splice(0..0, registrations);
 
 		let mut staged = Vec::with_capacity(pending.len());
 		for op in pending {
@@ -167,7 +201,8 @@ impl Session {
 	/// equal the snapshot when there are none), so it is left untouched.
 	///
 	/// `idempotent`: pass `true` when the snapshot already reflects the op (retirement of an already-
-	/// applied hot op) so duplicate structural inserts no-op rather than error.
+	/// applied hot op) so duplicate structural inserts no-op rather than error. Each delta is signed if the
+	/// session has a signing key.
 	fn commit_ops(&mut self, ops: impl IntoIterator<Item = RegistryDelta>, idempotent: bool) -> Result<Vec<Rev>, CrdtError> {
 		let target = RegistryTarget::Snapshot;
 		let ops = ops.into_iter();
@@ -186,7 +221,10 @@ impl Session {
 			let parent = self.document.head;
 			let author = self.document.peer;
 
-			let delta = Delta::new(parent, author, timestamp, op, reverse);
+			let mut delta = Delta::new(parent, author, timestamp, op, reverse);
+			if let Some(key) = &self.signing_key {
+				delta.sign(key);
+			}
 			let rev = delta.id;
 
 			// `parent` is `None` for the root commit; otherwise it must already be in history.
@@ -230,6 +268,7 @@ impl Session {
 				next_node_counter,
 			},
 			remote_tips: HashMap::new(),
+			signing_key: None,
 		}
 	}
 
@@ -263,9 +302,16 @@ impl Session {
 	}
 
 	/// Integrate `incoming` retired deltas from another branch and emit a [`RegistryDelta::Merge`]
-	/// joining the resulting tips, returning the new merge `Rev` (or `None` if `incoming` adds nothing).
-	/// Applies each incoming op to the registry, then hands the set to [`History::merge`]. Incoming
-	/// deltas must arrive in causal order.
+	/// joining `head` with the incoming tips, returning the new merge `Rev`. Returns `None` when no merge is
+	/// needed: `incoming` adds nothing, or it only extends `head` (a fast-forward, which moves `head` onto the
+	/// incoming tip). Applies each incoming op to the registry, then hands the set to [`History::merge`].
+	/// Incoming deltas must arrive in causal order.
+	///
+	/// The incoming deltas are retired history, so they are applied to the retired snapshot as well, which
+	/// leaves any local hot ops and remote hot ops out of it: those stay in the working registry only.
+	///
+	/// Only `head` and the incoming deltas' tips are joined, not every tip in the DAG: an abandoned or
+	/// undone branch is a tip too, but it isn't part of the current state and the other side never saw it.
 	pub fn merge(&mut self, incoming: impl IntoIterator<Item = Delta>) -> Result<Option<Rev>, CrdtError> {
 		let mut absorbed: Vec<Delta> = Vec::new();
 		for delta in incoming {
@@ -273,27 +319,79 @@ impl Session {
 				continue;
 			}
 			self.document.apply_op_idempotent(delta.kind.clone(), delta.timestamp)?;
+			self.document.apply_op_with(RegistryTarget::Snapshot, delta.kind.clone(), delta.timestamp, ApplyMode::Idempotent)?;
 			absorbed.push(delta);
 		}
 		if absorbed.is_empty() {
 			return Ok(None);
 		}
 
+		let roots: Vec<Rev> = self.document.head.into_iter().chain(absorbed.iter().map(|delta| delta.id)).collect();
 		self.document.history.merge(absorbed);
-		let tips = self.document.history.tips();
+		let tips = self.document.history.tips_of(roots);
+
+		if let [tip] = tips[..] {
+			self.document.head = Some(tip);
+			return Ok(None);
+		}
+
 		let timestamp = self.document.clock.tick();
-		let merge = Delta::merge(tips, self.document.peer, timestamp);
+		let mut merge = Delta::merge(tips, self.document.peer, timestamp);
+		if let Some(key) = &self.signing_key {
+			merge.sign(key);
+		}
 		let merge_rev = merge.id;
 		// The merge's parents are the current tips, so it sorts last: `push` preserves the canonical
 		// order without re-sorting the whole history.
 		self.document.history.push(merge);
 		self.document.head = Some(merge_rev);
-
-		// Merge runs with an empty hot log; keep the retired snapshot in step with the working registry.
-		self.document.retired_snapshot = self.document.working_registry.clone();
 		Ok(Some(merge_rev))
 	}
 
+	/// Merge a diverged branch, such as another copy of this document that was edited independently, and
+	/// report the slots both sides wrote differently. `incoming` is the other branch's reachable history in
+	/// replay order (e.g. its [`reachable_deltas`](Self::reachable_deltas)), shared ancestry included: the
+	/// common ancestors are the revs both sides hold, and only the rest is imported, through
+	/// [`merge`](Self::merge). Each side's edits since the merge bases are then compared slot by slot (see
+	/// [`crate::conflict`]); the registry already holds the LWW winner of each reported conflict.
+	///
+	/// Errors with [`CrdtError::UnrelatedHistories`] when both sides have history but share none, since
+	/// the two registries would then interleave rather than reconcile. O(history).
+	pub fn merge_branch(&mut self, incoming: impl IntoIterator<Item = Delta>) -> Result<BranchMerge, CrdtError> {
+		let incoming: Vec<Delta> = incoming.into_iter().collect();
+		let ours = self.document.history.ancestry(self.document.head);
+		if !ours.is_empty() && !incoming.is_empty() && !incoming.iter().any(|delta| ours.contains(&delta.id)) {
+			return Err(CrdtError::UnrelatedHistories);
+		}
+
+		// Their tips, taken from the incoming set itself: once merged, history alone can't tell their tips apart from ours.
+		let referenced: HashSet<Rev> = incoming.iter().flat_map(Delta::all_parents).collect();
+		let their_tips: Vec<Rev> = incoming.iter().map(|delta| delta.id).filter(|rev| !referenced.contains(rev)).collect();
+		let imported = incoming.iter().filter(|delta| !self.document.history.contains(delta.id)).count();
+
+		let merge = self.merge(incoming)?;
+
+		let theirs = self.document.history.ancestry(their_tips);
+		let common: HashSet<Rev> = ours.intersection(&theirs).copied().collect();
+		let ours_only = self.document.history.iter().filter(|delta| ours.contains(&delta.id) && !common.contains(&delta.id));
+		let theirs_only = self.document.history.iter().filter(|delta| theirs.contains(&delta.id) && !common.contains(&delta.id));
+
+		Ok(BranchMerge {
+			bases: self.document.history.tips_of(common.iter().copied()),
+			imported,
+			merge,
+			conflicts: crate::conflict::conflicting_writes(ours_only, theirs_only),
+		})
+	}
+
+	/// Apply a hot op broadcast by another peer to the working registry without adding it to the local hot
+	/// log: its author retires it and broadcasts the retired delta, which [`merge`](Self::merge) absorbs.
+	/// Retiring it here as well would commit the same edit twice under two authors. Idempotent on
+	/// structural ops, since the retired delta may have overtaken it.
+	pub fn apply_remote_hot_op(&mut self, hot_op: HotOp) -> Result<(), CrdtError> {
+		self.document.apply_op_idempotent(hot_op.op, hot_op.timestamp)
+	}
+
 	/// Promote hot ops with timestamp `≤ up_to` into retired deltas, re-applied with fresh
 	/// retirement timestamps so LWW arms bump field timestamps to `T_retire`.
 	///
@@ -448,12 +546,171 @@ impl Session {
 		self.document.history.get(rev)
 	}
 
+	/// Retired deltas in `head`'s ancestry, in history (replay) order: the edits the current state is built
+	/// from, without undone or abandoned branches. What a peer publishes. O(history).
+	pub fn reachable_deltas(&self) -> impl Iterator<Item = &Delta> + '_ {
+		let reachable = self.document.history.ancestry(self.document.head);
+		self.document.history.iter().filter(move |delta| reachable.contains(&delta.id))
+	}
+
+	/// The retired registry as of `rev`: `rev`'s ancestry replayed from an empty registry, the way
+	/// [`replay_from_history`](Self::replay_from_history) rebuilds a document. For inspecting or diffing past
+	/// states; the session itself is untouched. O(history).
+	pub fn registry_at(&self, rev: Rev) -> Result<Registry, CrdtError> {
+		if !self.document.history.contains(rev) {
+			return Err(CrdtError::NotFoundInHistory(rev));
+		}
+		let ancestry = self.document.history.ancestry([rev]);
+		let deltas = self.document.history.iter().filter(|delta| ancestry.contains(&delta.id)).cloned();
+		Ok(Self::replay_from_history(self.document.peer, deltas, self.document.next_node_counter)?.document.working_registry)
+	}
+
 	/// Verify the retired history loaded from an untrusted source: content-addressed ids match their
 	/// recomputed hashes, and the deltas are topologically ordered. See [`History::verify`].
 	pub fn verify_history(&self) -> Result<(), CrdtError> {
 		self.document.history.verify()
 	}
 
+	/// The signature-checking mode of [`verify_history`](Self::verify_history): check every retired delta's
+	/// `Rev` and its author's signature against the key the author registered in history, reporting each
+	/// commit that is tampered, unsigned, or signed by the wrong key rather than stopping at the first.
+	/// Topological order is still checked up front, since a misordered history can't be replayed at all.
+	/// Registered keys come from history rather than the registry, so a registration on a branch since
+	/// undone still vouches for the commits made on it. See [`crate::signing`]. O(history).
+	pub fn verify_signed_history(&self) -> Result<HistoryAudit, CrdtError> {
+		self.document.history.verify_order()?;
+
+		let keys = signing::registered_keys(self.document.history.iter());
+		let mut audit = HistoryAudit::default();
+		for delta in self.document.history.iter() {
+			let expected = delta.recomputed_id();
+			let verified = if delta.id != expected {
+				Err(signing::CommitIssue::Tampered { expected })
+			} else {
+				delta.verify_signature(keys.get(&delta.author))
+			};
+			match verified {
+				Ok(()) => audit.verified += 1,
+				Err(issue) => audit.issues.push((delta.id, issue)),
+			}
+		}
+		Ok(audit)
+	}
+
+	/// The revs that anchor live history: `head`, the redo checkpoints (undone interactions that redo can
+	/// still reach), and the published frontier. Everything outside their ancestry is an abandoned branch.
+	fn live_roots(&self) -> Vec<Rev> {
+		self.document
+			.head
+			.into_iter()
+			.chain(self.document.redo_stack.iter().copied())
+			.chain(self.document.last_broadcast_rev)
+			.collect()
+	}
+
+	/// Drop every retired delta that can no longer be reached from `head`, the redo stack, or the published
+	/// frontier: branches abandoned by an edit made after an undo. Surviving deltas keep their `Rev`s, so
+	/// this is safe on published history and the result still passes [`verify_history`](Self::verify_history).
+	/// The registry is untouched (it never reflected the abandoned branches). Returns the number of deltas removed.
+	pub fn prune_unreachable(&mut self) -> usize {
+		let reachable = self.document.history.ancestry(self.live_roots());
+		self.document.history.retain(&reachable)
+	}
+
+	/// Rewrite the history reachable from `head` into a single first-parent chain: `Merge` deltas are
+	/// dropped, the merged-in branches are threaded onto the chain in canonical (topological) order, and
+	/// anything unreachable from `head` (including the redo stack) is discarded. Each delta keeps its
+	/// author, timestamp, forward op, and annotations (so interaction boundaries survive), while its
+	/// `reverse` is recomputed against the new linear predecessor so undo stays correct along the chain.
+	///
+	/// Replaying the new chain rebuilds the same registry, but every `Rev` changes (a `Rev` commits to its
+	/// parent), so the returned map from old to new `Rev` lets callers remap anything keyed by them. Dropped
+	/// merges map to the chain rev that precedes them. Refused once any commit has been published, since
+	/// other peers hold the old `Rev`s. Old signatures don't cover the new `Rev`s, so they are dropped; this
+	/// session's own commits are re-signed if it has a signing key.
+	pub fn linearize(&mut self) -> Result<HashMap<Rev, Rev>, CrdtError> {
+		if let Some(published) = self.document.last_broadcast_rev {
+			return Err(CrdtError::HistoryPublished(published));
+		}
+
+		let reachable = self.document.history.ancestry(self.document.head);
+		let mut linear = Self::with_peer(self.document.peer);
+		let mut remapped = HashMap::with_capacity(reachable.len());
+
+		for delta in self.document.history.iter().filter(|delta| reachable.contains(&delta.id)) {
+			if let RegistryDelta::Merge { .. } = delta.kind {
+				if let Some(previous) = linear.document.head {
+					remapped.insert(delta.id, previous);
+				}
+				continue;
+			}
+
+			let reverse = linear.document.compute_reverse_delta(RegistryTarget::Working, &delta.kind)?;
+			let mut rewritten = Delta::new(linear.document.head, delta.author, delta.timestamp, delta.kind.clone(), reverse);
+			rewritten.attributes = delta.attributes.clone();
+			rewritten.clear_signature();
+			if let Some(key) = self.signing_key.as_ref().filter(|_| delta.author == self.document.peer) {
+				rewritten.sign(key);
+			}
+			let rev = rewritten.id;
+
+			linear.document.apply_op_idempotent(rewritten.kind.clone(), rewritten.timestamp)?;
+			linear.document.history.push(rewritten);
+			linear.document.head = Some(rev);
+			remapped.insert(delta.id, rev);
+		}
+
+		self.document.history = linear.document.history;
+		self.document.head = linear.document.head;
+		self.document.redo_stack.clear();
+		Ok(remapped)
+	}
+
+	/// The [`ContentId`] of every edit in `head`'s ancestry: what the current state already contains.
+	/// Undone and abandoned branches don't count, since their edits aren't applied. O(history).
+	pub fn applied_content_ids(&self) -> HashSet<ContentId> {
+		self.reachable_deltas().filter_map(Delta::content_id).collect()
+	}
+
+	/// Whether `delta`'s edit is already part of the current state: either the delta itself is in `head`'s
+	/// ancestry, or one with the same [`ContentId`] is (the same edit replayed here, e.g. from a forked
+	/// document). Checking many deltas is cheaper through one [`applied_content_ids`](Self::applied_content_ids).
+	pub fn is_applied(&self, delta: &Delta) -> bool {
+		if self.document.history.ancestry(self.document.head).contains(&delta.id) {
+			return true;
+		}
+		delta.content_id().is_some_and(|content_id| self.applied_content_ids().contains(&content_id))
+	}
+
+	/// Re-commit the edits of `deltas` (from another branch or another document) onto `head` as new local
+	/// deltas, skipping any whose edit is already applied (see [`is_applied`](Self::is_applied)), including
+	/// repeats within `deltas` itself. `Merge` deltas carry no edit and are ignored. Each pick gets a fresh
+	/// local author, timestamp and `reverse`, so it wins LWW as the newest write and undoes like any local
+	/// edit; only its `ContentId` ties it to the source. `deltas` should be in the source's replay order.
+	///
+	/// Like [`embed_resource_sources`](Self::embed_resource_sources) the picks are retired deltas mirrored
+	/// onto the working registry, so unretired hot ops are kept. An op the local state can't accept (e.g. an
+	/// edit to a node that doesn't exist here) errors; earlier picks stay committed.
+	pub fn cherry_pick(&mut self, deltas: impl IntoIterator<Item = Delta>) -> Result<CherryPick, CrdtError> {
+		let mut applied = self.applied_content_ids();
+		let mut report = CherryPick::default();
+
+		for delta in deltas {
+			let Some(content_id) = delta.content_id() else { continue };
+			if !applied.insert(content_id) {
+				report.already_applied.push(delta.id);
+				continue;
+			}
+
+			let Some(&rev) = self.commit_ops([delta.kind.clone()], false)?.first() else { continue };
+			let timestamp = self.document.history.get(rev).map_or(delta.timestamp, |committed| committed.timestamp);
+			self.document.apply_op_idempotent(delta.kind, timestamp)?;
+			report.picked.push((delta.id, rev));
+		}
+
+		Ok(report)
+	}
+
 	/// Every resource hash referenced by the current registry *or* anywhere in history. Undo removes a
 	/// interaction's `AddResource` from the working registry, so a redoable (or re-undoable) interaction's
 	/// resources no longer appear in `registry().resources` even though redo still needs them. Resource GC
@@ -533,6 +790,15 @@ impl Default for Session {
 	}
 }
 
+/// Outcome of [`Session::cherry_pick`], keyed by the source deltas' `Rev`s.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CherryPick {
+	/// `(source Rev, new local Rev)` for each edit re-committed onto `head`, in commit order.
+	pub picked: Vec<(Rev, Rev)>,
+	/// Source `Rev`s skipped because their edit was already applied.
+	pub already_applied: Vec<Rev>,
+}
+
 /// One live op in the hot zone. Carries only enough to drive live LWW; no parents (transient),
 /// no Rev (not content-addressed in the durable DAG). GC'd at retirement.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -568,6 +834,15 @@ pub enum CrdtError {
 	/// PeerId is already registered to a different UserId.
 	#[error("Peer {0:?} is already registered to a different user")]
 	PeerRegistrationConflict(PeerId),
+	/// PeerId already registered a different signing key.
+	#[error("Peer {0:?} already registered a different signing key")]
+	PeerKeyConflict(PeerId),
 	#[error("Delta stored under {stored} hashes to {expected}")]
 	RevMismatch { stored: Rev, expected: Rev },
+	/// Both branches of a [`Session::merge_branch`] have history, but share none of it.
+	#[error("The branches share no history")]
+	UnrelatedHistories,
+	/// History has been published up to this rev, so rewriting it would strand other peers' `Rev`s.
+	#[error("History is published up to {0} and cannot be rewritten")]
+	HistoryPublished(Rev),
 }
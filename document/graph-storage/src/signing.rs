@@ -0,0 +1,151 @@
+//! Optional Ed25519 signing of retired deltas, so a commit's `author` can be trusted.
+//!
+//! A [`PeerId`] is only a number and `Delta::attributes` are unauthenticated, so without signatures anyone
+//! holding a document can forge edits attributed to a teammate. A session given a [`SigningKey`] (see
+//! [`Session::set_signing_key`](crate::Session::set_signing_key)) registers the matching [`PeerKey`] in the
+//! document via [`RegistryDelta::RegisterPeerKey`](crate::RegistryDelta::RegisterPeerKey) on its first
+//! contribution, and signs every delta it retires or mints. The signature is stored as the
+//! [`attr::delta::SIGNATURE`] annotation: like the other annotations it is excluded from the `Rev`, so a
+//! signed and an unsigned copy of a commit still dedup.
+//!
+//! Keys are trusted on first use. A registration only counts when the registering delta is authored by the
+//! peer it registers and signed by the key it registers, proving possession, so no peer can claim another's
+//! identity. The first such registration wins, and only commits by peers with a registered key can be
+//! verified. [`Session::verify_signed_history`](crate::Session::verify_signed_history) reports every commit
+//! that fails.
+
+use crate::{AttributesWrite, Delta, PeerId, Rev, attr};
+use ed25519_dalek::{Signature, Signer, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+pub use ed25519_dalek::SigningKey;
+
+/// A peer's Ed25519 public key, as registered in [`Registry::peer_keys`](crate::Registry::peer_keys).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PeerKey(pub [u8; 32]);
+
+impl PeerKey {
+	/// The public half of `key`.
+	pub fn of(key: &SigningKey) -> Self {
+		Self(key.verifying_key().to_bytes())
+	}
+
+	/// `None` if the stored bytes aren't a valid curve point (a corrupted or forged registration).
+	fn verifying_key(&self) -> Option<VerifyingKey> {
+		VerifyingKey::from_bytes(&self.0).ok()
+	}
+}
+
+impl std::fmt::Display for PeerKey {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&encode_hex(&self.0))
+	}
+}
+
+/// Why a retired delta failed [`Session::verify_signed_history`](crate::Session::verify_signed_history).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CommitIssue {
+	/// The stored `Rev` doesn't match the recomputed content hash: the delta was edited after it was committed.
+	Tampered { expected: Rev },
+	/// No signature annotation. Expected for commits made before their author registered a key.
+	Unsigned,
+	/// Signed, but the author has no registered key to check the signature against.
+	UnknownSigner,
+	/// The signature doesn't verify against the author's registered key: forged, re-attributed, or edited and re-hashed.
+	BadSignature,
+}
+
+impl std::fmt::Display for CommitIssue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CommitIssue::Tampered { expected } => write!(f, "tampered (content hashes to {expected})"),
+			CommitIssue::Unsigned => f.write_str("unsigned"),
+			CommitIssue::UnknownSigner => f.write_str("signed by an author with no registered key"),
+			CommitIssue::BadSignature => f.write_str("invalid signature"),
+		}
+	}
+}
+
+/// Outcome of [`Session::verify_signed_history`](crate::Session::verify_signed_history): every retired
+/// delta that isn't a verified, untampered commit by its stated author, in history order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistoryAudit {
+	/// Deltas whose signature verified against their author's registered key.
+	pub verified: usize,
+	pub issues: Vec<(Rev, CommitIssue)>,
+}
+
+impl HistoryAudit {
+	/// Whether every retired delta is signed by its author and untampered.
+	pub fn is_trusted(&self) -> bool {
+		self.issues.is_empty()
+	}
+}
+
+impl Delta {
+	/// Sign this delta as its author with `key`, replacing any previous signature. The caller is responsible
+	/// for `key` belonging to `author`; a mismatch shows up as [`CommitIssue::BadSignature`] on verification.
+	pub fn sign(&mut self, key: &SigningKey) {
+		let signature = key.sign(&self.signing_payload());
+		self.attributes
+			.set(attr::delta::SIGNATURE, serde_json::Value::String(encode_hex(&signature.to_bytes())), self.timestamp);
+	}
+
+	pub fn is_signed(&self) -> bool {
+		self.attributes.contains_key(attr::delta::SIGNATURE)
+	}
+
+	/// Drop the signature, e.g. after rewriting a delta into a new `Rev` its author hasn't signed.
+	pub(crate) fn clear_signature(&mut self) {
+		self.attributes.remove(attr::delta::SIGNATURE);
+	}
+
+	/// Check the signature against the author's registered key, if there is one. `Ok` means the delta is
+	/// signed by `key` over exactly its current identity fields and `reverse`; a stale `id` is not detected
+	/// here (see [`has_valid_id`](Self::has_valid_id)).
+	pub fn verify_signature(&self, key: Option<&PeerKey>) -> Result<(), CommitIssue> {
+		let encoded = match self.attributes.get(attr::delta::SIGNATURE).map(|signature| &signature.value) {
+			Some(serde_json::Value::String(encoded)) => encoded,
+			Some(_) => return Err(CommitIssue::BadSignature),
+			None => return Err(CommitIssue::Unsigned),
+		};
+		let key = key.ok_or(CommitIssue::UnknownSigner)?.verifying_key().ok_or(CommitIssue::BadSignature)?;
+		let bytes: [u8; 64] = decode_hex(encoded).and_then(|bytes| bytes.try_into().ok()).ok_or(CommitIssue::BadSignature)?;
+		key.verify(&self.signing_payload(), &Signature::from_bytes(&bytes)).map_err(|_| CommitIssue::BadSignature)
+	}
+
+	/// What a signature covers: the `Rev` plus the fields it is derived from (so the signature doesn't lean on
+	/// the 128-bit truncation alone), and `reverse`, which peers apply on undo without recomputing it.
+	/// Domain-separated from the `Rev` and `ContentId` preimages.
+	fn signing_payload(&self) -> Vec<u8> {
+		rmp_serde::to_vec(&("signature", self.id, self.parent, self.author, self.timestamp, &self.kind, &self.reverse)).expect("Delta signing fields must serialize")
+	}
+}
+
+/// The key each author registered, first registration in history order winning, the way
+/// [`RegistryDelta::RegisterPeerKey`](crate::RegistryDelta::RegisterPeerKey) applies. A registration is
+/// ignored unless its delta is authored by the registered peer and signed by the registered key.
+pub(crate) fn registered_keys<'a>(deltas: impl IntoIterator<Item = &'a Delta>) -> std::collections::HashMap<PeerId, PeerKey> {
+	let mut keys = std::collections::HashMap::new();
+	for delta in deltas {
+		if let crate::RegistryDelta::RegisterPeerKey { peer, key } = delta.kind
+			&& delta.author == peer
+			&& delta.verify_signature(Some(&key)).is_ok()
+		{
+			keys.entry(peer).or_insert(key);
+		}
+	}
+	keys
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(encoded: &str) -> Option<Vec<u8>> {
+	if !encoded.len().is_multiple_of(2) {
+		return None;
+	}
+	(0..encoded.len()).step_by(2).map(|i| u8::from_str_radix(encoded.get(i..i + 2)?, 16).ok()).collect()
+}
@@ -233,6 +233,313 @@ fn resurrection_reaches_across_a_merge() {
 		.expect("resurrection must find the AddNetwork on the merged-in branch");
 }
 
+/// Commit one op as a complete interaction (its delta marked as the undo boundary), returning its `Rev`.
+fn commit_interaction(session: &mut Session, op: RegistryDelta) -> crate::Rev {
+	session.commit_op_for_test(op).expect("commit failed");
+	let rev = session.head_rev().expect("commit advanced head");
+	session.mark_interaction_end(rev);
+	rev
+}
+
+/// An edit made after an undo abandons the undone interaction: it stays in the DAG but nothing reaches it.
+/// Pruning drops exactly that branch, keeps every surviving `Rev`, and the pruned history still verifies
+/// and replays without the abandoned edit.
+#[test]
+fn prune_unreachable_drops_abandoned_undo_branch() {
+	let mut session = Session::with_peer(PeerId(1));
+	commit_interaction(&mut session, set_document_attribute("compute::base", 0));
+	let abandoned = commit_interaction(&mut session, set_document_attribute("compute::a", 1));
+	session.undo().expect("undo failed");
+	commit_interaction(&mut session, set_document_attribute("compute::b", 2));
+
+	let before: Vec<crate::Rev> = session.history().map(|delta| delta.id).collect();
+	assert_eq!(session.prune_unreachable(), 1, "only the undone-then-abandoned interaction is unreachable");
+
+	let after: Vec<crate::Rev> = session.history().map(|delta| delta.id).collect();
+	let expected: Vec<crate::Rev> = before.into_iter().filter(|rev| *rev != abandoned).collect();
+	assert_eq!(after, expected, "survivors keep their Revs and relative order");
+	session.verify_history().expect("pruned history must verify");
+
+	let replayed = Session::replay_from_history(PeerId(1), session.cloned_deltas(), 0).expect("replay failed");
+	let keys: Vec<&str> = replayed.registry().attributes.keys().map(String::as_str).collect();
+	assert_eq!(keys, ["compute::b", "compute::base"], "replaying the pruned history no longer resurrects the abandoned edit");
+	assert_eq!(session.prune_unreachable(), 0, "pruning is idempotent");
+}
+
+/// Pruning keeps what redo still needs: an undone interaction on the redo stack is reachable.
+#[test]
+fn prune_unreachable_keeps_redoable_interactions() {
+	let mut session = Session::with_peer(PeerId(1));
+	commit_interaction(&mut session, set_document_attribute("compute::base", 0));
+	commit_interaction(&mut session, set_document_attribute("compute::a", 1));
+	session.undo().expect("undo failed");
+
+	assert_eq!(session.prune_unreachable(), 0, "a redoable interaction must not be pruned");
+	session.redo().expect("redo must still find the undone interaction");
+}
+
+/// Linearizing a merged history yields one first-parent chain with no `Merge` deltas that verifies,
+/// replays to the same registry, and lands `head` on the remapped tip.
+#[test]
+fn linearize_flattens_merge_into_a_verified_chain() {
+	let mut session_a = Session::with_peer(PeerId(1));
+	session_a.commit_op_for_test(set_document_attribute("compute::base", 0)).expect("base commit");
+	let mut session_b = session_a.clone();
+	session_a.commit_op_for_test(set_document_attribute("compute::a", 1)).expect("A edit");
+	session_b.commit_op_for_test(set_document_attribute("compute::b", 2)).expect("B edit");
+	session_a.merge(session_b.cloned_deltas()).expect("merge failed");
+	session_a.commit_op_for_test(set_document_attribute("compute::after", 3)).expect("post-merge edit");
+
+	let old_head = session_a.head_rev().expect("head exists");
+	let original = Session::replay_from_history(PeerId(1), session_a.cloned_deltas(), 0).expect("replay of the merged history failed");
+	let remapped = session_a.linearize().expect("linearize failed");
+
+	let chain: Vec<&Delta> = session_a.history().collect();
+	assert_eq!(chain.len(), 4, "base, both branch edits, and the post-merge edit survive; the merge is dropped");
+	assert!(chain.iter().all(|delta| !matches!(delta.kind, RegistryDelta::Merge { .. })), "no merge deltas remain");
+	assert_eq!(chain[0].parent, None, "the chain starts at the root");
+	for pair in chain.windows(2) {
+		assert_eq!(pair[1].parent, Some(pair[0].id), "every delta's parent is its predecessor");
+	}
+	assert_eq!(session_a.head_rev(), remapped.get(&old_head).copied(), "head is remapped onto the new tip");
+	session_a.verify_history().expect("linearized history must verify");
+
+	let replayed = Session::replay_from_history(PeerId(1), session_a.cloned_deltas(), 0).expect("replay failed");
+	assert!(replayed.registry().value_equal(original.registry()), "linearized history must rebuild the same registry");
+}
+
+/// Published commits are held by other peers under their current `Rev`s, so linearization refuses.
+#[test]
+fn linearize_refuses_published_history() {
+	let mut session = Session::with_peer(PeerId(1));
+	let rev = commit_interaction(&mut session, set_document_attribute("compute::base", 0));
+	session.publish_up_to(rev);
+
+	assert!(matches!(session.linearize(), Err(crate::CrdtError::HistoryPublished(published)) if published == rev));
+}
+
+/// The same edit committed on two unrelated chains gets two `Rev`s but one `ContentId`; a different edit,
+/// or the same edit with a different payload, gets a different one. Merges have no content.
+#[test]
+fn content_id_ignores_parent_author_and_timestamp() {
+	let mut session_a = Session::with_peer(PeerId(1));
+	let mut session_b = Session::with_peer(PeerId(2));
+	session_b.commit_op_for_test(set_document_attribute("compute::unrelated", 0)).expect("B base commit");
+	let rev_a = commit_interaction(&mut session_a, set_document_attribute("compute::shared", 1));
+	let rev_b = commit_interaction(&mut session_b, set_document_attribute("compute::shared", 1));
+
+	let (delta_a, delta_b) = (session_a.delta(rev_a).expect("A delta"), session_b.delta(rev_b).expect("B delta"));
+	assert_ne!(delta_a.id, delta_b.id, "parent, author and timestamp differ, so the Revs do");
+	assert_eq!(delta_a.content_id(), delta_b.content_id(), "the edit itself is the same");
+	assert_ne!(delta_a.content_id(), set_document_attribute("compute::shared", 2).content_id(), "a different value is a different edit");
+
+	let merge = Delta::merge([rev_a, rev_b], PeerId(1), TimeStamp::ORIGIN);
+	assert_eq!(merge.content_id(), None, "a merge carries no edit");
+}
+
+/// LWW timestamps embedded in an added payload and the pre-state snapshot of a removal are position-dependent,
+/// so they are normalized out of the `ContentId`.
+#[test]
+fn content_id_normalizes_embedded_timestamps_and_snapshots() {
+	let network_at = |counter| Network {
+		exports: vec![crate::ExportSlot {
+			target: Some(crate::NodeInput::Import { index: 0 }),
+			timestamp: TimeStamp { counter, peer: PeerId(counter) },
+		}],
+		..Default::default()
+	};
+	let add = |counter| RegistryDelta::AddNetwork {
+		id: NetworkId(7),
+		network: network_at(counter),
+	};
+	assert_eq!(add(1).content_id(), add(9).content_id());
+
+	let remove = |snapshot| RegistryDelta::RemoveNetwork { id: NetworkId(7), snapshot };
+	assert_eq!(remove(network_at(1)).content_id(), remove(Network::default()).content_id());
+	assert_ne!(remove(Network::default()).content_id(), add(1).content_id(), "the op kind is part of the content");
+}
+
+/// Cherry-picking a forked branch re-commits only the edits not yet applied: one already made independently
+/// (same `ContentId`, different `Rev`) and the shared fork point are skipped, and picking again is a no-op.
+#[test]
+fn cherry_pick_skips_already_applied_edits() {
+	let mut session_a = Session::with_peer(PeerId(1));
+	commit_interaction(&mut session_a, set_document_attribute("compute::base", 0));
+	let mut session_b = session_a.clone();
+	let duplicate = commit_interaction(&mut session_b, set_document_attribute("compute::fix", 1));
+	let wanted = commit_interaction(&mut session_b, set_document_attribute("compute::feature", 2));
+	commit_interaction(&mut session_a, set_document_attribute("compute::fix", 1));
+
+	let source = session_b.cloned_deltas();
+	assert!(session_a.is_applied(&source[0]), "the shared fork point is applied by Rev");
+	assert!(session_a.is_applied(session_b.delta(duplicate).expect("duplicate delta")), "the independent fix is applied by content");
+	assert!(!session_a.is_applied(session_b.delta(wanted).expect("wanted delta")));
+
+	let report = session_a.cherry_pick(source.clone()).expect("cherry-pick failed");
+	assert_eq!(report.already_applied, vec![source[0].id, duplicate]);
+	assert_eq!(report.picked.len(), 1);
+	assert_eq!(report.picked[0].0, wanted);
+	assert_eq!(session_a.head_rev(), Some(report.picked[0].1), "the pick lands on head");
+	assert!(session_a.retired_registry().attributes.contains_key("compute::feature"));
+	session_a.verify_history().expect("picked history must verify");
+
+	let again = session_a.cherry_pick(source).expect("second cherry-pick failed");
+	assert!(again.picked.is_empty(), "every edit is now applied");
+}
+
+/// An undone edit is no longer part of the current state, so it doesn't count as applied.
+#[test]
+fn undone_edit_is_not_applied() {
+	let mut session = Session::with_peer(PeerId(1));
+	commit_interaction(&mut session, set_document_attribute("compute::base", 0));
+	let undone = commit_interaction(&mut session, set_document_attribute("compute::a", 1));
+	let delta = session.delta(undone).expect("delta").clone();
+	session.undo().expect("undo failed");
+
+	assert!(!session.is_applied(&delta));
+	assert!(!session.applied_content_ids().contains(&delta.content_id().expect("content id")));
+}
+
+/// Merging deltas that only extend `head` fast-forwards onto them instead of minting a one-parent merge,
+/// and a locally undone branch is not folded into a later merge the other side never saw.
+#[test]
+fn merge_fast_forwards_and_ignores_undone_branches() {
+	let mut session_a = Session::with_peer(PeerId(1));
+	commit_interaction(&mut session_a, set_document_attribute("compute::base", 0));
+	let mut session_b = session_a.clone();
+	let ahead = commit_interaction(&mut session_b, set_document_attribute("compute::b", 1));
+
+	assert_eq!(session_a.merge(session_b.cloned_deltas()).expect("merge failed"), None, "a fast-forward needs no merge");
+	assert_eq!(session_a.head_rev(), Some(ahead));
+
+	let undone = commit_interaction(&mut session_a, set_document_attribute("compute::undone", 2));
+	session_a.undo().expect("undo failed");
+	let remote = commit_interaction(&mut session_b, set_document_attribute("compute::remote", 3));
+	let local = commit_interaction(&mut session_a, set_document_attribute("compute::local", 4));
+
+	let merge = session_a.merge(session_b.cloned_deltas()).expect("merge failed").expect("diverged tips need a merge");
+	let parents: Vec<crate::Rev> = session_a.delta(merge).expect("merge delta").all_parents().collect();
+	assert!(
+		parents.contains(&remote) && parents.contains(&local) && !parents.contains(&undone),
+		"only head and the incoming tip are joined"
+	);
+}
+
+/// Merging keeps the retired snapshot at retired history: the absorbed deltas land in it, while the local hot
+/// log and a remote peer's hot op stay in the working registry only, so persisting the snapshot can't retire them.
+#[test]
+fn merge_leaves_hot_ops_out_of_the_retired_snapshot() {
+	let mut session_a = Session::with_peer(PeerId(1));
+	commit_interaction(&mut session_a, set_document_attribute("compute::base", 0));
+	let mut session_b = session_a.clone();
+	commit_interaction(&mut session_b, set_document_attribute("compute::remote", 1));
+
+	let staged = session_a
+		.stage_embedded_resource(crate::ResourceId::new(), crate::ResourceHash::from(&b"hot"[..]))
+		.expect("stage failed");
+	assert!(!session_a.hot_log().is_empty(), "the staged op is still hot");
+	let remote_hot = HotOp {
+		op: set_document_attribute("compute::remote_hot", 2),
+		timestamp: TimeStamp { counter: 1000, peer: PeerId(3) },
+	};
+	session_a.apply_remote_hot_op(remote_hot).expect("remote hot op failed");
+
+	session_a.merge(session_b.cloned_deltas()).expect("merge failed");
+
+	let retired = session_a.retired_registry();
+	assert!(retired.attributes.contains_key("compute::remote"), "the absorbed delta is retired");
+	assert!(!retired.attributes.contains_key("compute::remote_hot"), "a remote hot op isn't retired");
+	assert!(retired.resources.is_empty() && retired.peer_users.is_empty(), "the local hot log isn't retired");
+	assert!(session_a.registry().attributes.contains_key("compute::remote_hot"));
+	assert_eq!(session_a.registry().resources.len(), 1, "the working registry still holds the staged resource");
+
+	let replayed = Session::replay_from_history(PeerId(1), session_a.cloned_deltas(), 0).expect("replay failed");
+	assert!(replayed.registry().value_equal(retired), "the snapshot matches a replay of retired history");
+
+	session_a.retire(staged.last().expect("staged hot ops").timestamp).expect("retire failed");
+	assert_eq!(session_a.retired_registry().resources.len(), 1, "retiring the hot log moves it into the snapshot");
+}
+
+/// A branch merge reports the merge base and every slot both forks wrote differently, with the LWW winner
+/// already applied. Slots written identically on both sides, or by only one side, aren't conflicts.
+#[test]
+fn merge_branch_reports_conflicting_slots() {
+	let mut ours = Session::with_peer(PeerId(1));
+	let base = commit_interaction(&mut ours, set_document_attribute("compute::shared", 0));
+	let mut theirs = ours.clone();
+
+	commit_interaction(&mut ours, set_document_attribute("compute::shared", 1));
+	commit_interaction(&mut ours, set_document_attribute("compute::same", 5));
+	commit_interaction(&mut ours, set_document_attribute("compute::ours_only", 6));
+	commit_interaction(&mut theirs, set_document_attribute("compute::same", 5));
+	commit_interaction(&mut theirs, set_document_attribute("compute::shared", 2));
+	commit_interaction(&mut theirs, set_document_attribute("compute::theirs_only", 7));
+	commit_interaction(&mut theirs, set_document_attribute("compute::shared", 3));
+
+	let report = ours.merge_branch(theirs.cloned_deltas()).expect("merge failed");
+	assert_eq!(report.bases, vec![base], "the fork point is the only merge base");
+	assert_eq!(report.imported, 4);
+	assert!(report.merge.is_some(), "diverged branches need a merge commit");
+	assert_eq!(
+		report.conflicts,
+		vec![crate::MergeConflict {
+			slot: crate::TimestampKey::DocumentAttribute("compute::shared".into()),
+			ours: serde_json::json!(1),
+			theirs: serde_json::json!(3),
+			winner: crate::Side::Theirs,
+		}]
+	);
+	assert_eq!(
+		ours.registry().attributes.get("compute::shared").map(|value| &value.value),
+		Some(&serde_json::json!(3)),
+		"the registry holds the winner"
+	);
+
+	// Merging the same branch again imports nothing and has nothing left to report.
+	let again = ours.merge_branch(theirs.cloned_deltas()).expect("re-merge failed");
+	assert_eq!((again.imported, again.merge, again.conflicts.len()), (0, None, 0));
+}
+
+/// Two documents with no shared history can't be merged as branches of each other.
+#[test]
+fn merge_branch_rejects_unrelated_histories() {
+	let mut ours = Session::with_peer(PeerId(1));
+	commit_interaction(&mut ours, set_document_attribute("compute::a", 1));
+	let mut theirs = Session::with_peer(PeerId(2));
+	commit_interaction(&mut theirs, set_document_attribute("compute::b", 2));
+
+	let head = ours.head_rev();
+	assert!(matches!(ours.merge_branch(theirs.cloned_deltas()), Err(crate::CrdtError::UnrelatedHistories)));
+	assert_eq!(ours.head_rev(), head, "a rejected merge leaves the session untouched");
+
+	// An empty side is trivially related: merging into a fresh session just adopts the branch.
+	let mut fresh = Session::with_peer(PeerId(3));
+	let report = fresh.merge_branch(theirs.cloned_deltas()).expect("merge into an empty session failed");
+	assert_eq!((report.bases.len(), report.imported, report.conflicts.len()), (0, 1, 0));
+	assert_eq!(fresh.head_rev(), theirs.head_rev(), "an empty session fast-forwards onto the branch");
+}
+
+/// `registry_at` replays a past rev's ancestry without touching the session: an older rev lacks later
+/// edits, and head replays to the retired registry.
+#[test]
+fn registry_at_materializes_past_revs() {
+	let mut session = Session::with_peer(PeerId(1));
+	let first = commit_interaction(&mut session, set_document_attribute("compute::a", 1));
+	commit_interaction(&mut session, set_document_attribute("compute::a", 2));
+	let head = commit_interaction(&mut session, set_document_attribute("compute::b", 3));
+
+	let at_first = session.registry_at(first).expect("registry_at first");
+	assert_eq!(at_first.attributes.get("compute::a").map(|value| &value.value), Some(&serde_json::json!(1)));
+	assert!(!at_first.attributes.contains_key("compute::b"), "later edits are absent");
+	assert!(
+		session.registry_at(head).expect("registry_at head").value_equal(session.retired_registry()),
+		"head replays to the retired registry"
+	);
+
+	let missing = crate::Rev::new(1).expect("non-zero");
+	assert!(matches!(session.registry_at(missing), Err(crate::CrdtError::NotFoundInHistory(rev)) if rev == missing));
+}
+
 /// Committing the same NodeNetwork twice must produce zero history entries on the second commit.
 /// Without value-only diffing in compute_deltas, the second commit would emit spurious
 /// ChangeNodeInput / ChangeNodeAttribute ops because self.registry has real timestamps while the
@@ -284,6 +591,124 @@ fn first_contribution_registers_the_peer() {
 	assert_eq!(fresh.registry().peer_users, peers_before, "a no-op batch must not add a registration");
 }
 
+fn signing_key(seed: u8) -> crate::SigningKey {
+	crate::SigningKey::from_bytes(&[seed; 32])
+}
+
+/// Stage and retire one embedded resource: a real contribution, so it goes through peer registration.
+fn contribute(session: &mut Session, bytes: &[u8]) {
+	let hot_ops = session.stage_embedded_resource(crate::ResourceId::new(), crate::ResourceHash::from(bytes)).expect("stage failed");
+	let up_to = hot_ops.last().expect("staged a hot op").timestamp;
+	session.retire(up_to).expect("retire failed");
+}
+
+/// A signing peer registers its key with its first contribution and signs everything it retires, so a
+/// history two signing peers built verifies in full. Stripping a signature, editing a delta in place, or
+/// re-attributing one to the other peer (even with a recomputed `Rev`) is each reported against that commit.
+#[test]
+fn signed_history_verifies_and_reports_forgeries() {
+	use crate::{CommitIssue, PeerKey};
+
+	let mut alice = Session::with_peer(PeerId(1));
+	alice.set_signing_key(signing_key(1)).expect("set alice's key");
+	let first = alice.stage_embedded_resource(crate::ResourceId::new(), crate::ResourceHash::from(&b"alice"[..])).expect("stage failed");
+	assert!(
+		matches!(first[1].op, RegistryDelta::RegisterPeerKey { peer: PeerId(1), .. }),
+		"the key is registered right after the peer"
+	);
+	alice.retire(first.last().expect("staged hot ops").timestamp).expect("retire failed");
+	assert_eq!(alice.registry().peer_keys.get(&PeerId(1)), Some(&PeerKey::of(&signing_key(1))));
+
+	let mut bob = Session::with_peer(PeerId(2));
+	bob.merge(alice.cloned_deltas()).expect("fast-forward onto alice's history");
+	bob.set_signing_key(signing_key(2)).expect("set bob's key");
+	contribute(&mut bob, b"bob");
+
+	let audit = bob.verify_signed_history().expect("history is ordered");
+	assert!(audit.is_trusted(), "unexpected issues: {:?}", audit.issues);
+	assert_eq!(audit.verified, bob.history().count());
+
+	let mut deltas = bob.cloned_deltas();
+	let last = deltas.len() - 1;
+	deltas[0].attributes.remove(crate::attr::delta::SIGNATURE);
+	// Tamper with alice's resource commit rather than her key registration, which would unregister her key.
+	deltas[2].timestamp.counter += 100;
+	deltas[last].author = PeerId(1);
+	deltas[last].id = deltas[last].recomputed_id();
+	let expected = vec![
+		(deltas[0].id, CommitIssue::Unsigned),
+		(deltas[2].id, CommitIssue::Tampered { expected: deltas[2].recomputed_id() }),
+		(deltas[last].id, CommitIssue::BadSignature),
+	];
+
+	let forged = Session::replay_from_history(PeerId(3), deltas, 0).expect("replay failed");
+	let audit = forged.verify_signed_history().expect("history is ordered");
+	assert_eq!(audit.issues, expected);
+	assert_eq!(audit.verified, forged.history().count() - 3);
+}
+
+/// A peer can't register a key on another peer's behalf: bob's `RegisterPeerKey` for alice is ignored, so a
+/// commit attributed to alice and signed with the key bob registered doesn't verify, and alice can still
+/// register her own key afterwards and have her commits verify against it.
+#[test]
+fn keys_registered_for_another_peer_are_rejected() {
+	use crate::{CommitIssue, PeerKey};
+
+	let mut bob = Session::with_peer(PeerId(2));
+	bob.set_signing_key(signing_key(2)).expect("set bob's key");
+	contribute(&mut bob, b"bob");
+	let forged_key = signing_key(9);
+	let register_for_alice = RegistryDelta::RegisterPeerKey {
+		peer: PeerId(1),
+		key: PeerKey::of(&forged_key),
+	};
+	bob.commit_op_for_test(register_for_alice).expect("bob registers a key for alice");
+	assert!(!bob.registry().peer_keys.contains_key(&PeerId(1)), "the registry ignores a key registered by another peer");
+
+	let op = set_document_attribute("compute::forged", 1);
+	let timestamp = TimeStamp { counter: 100, peer: PeerId(1) };
+	let mut forged = Delta::new(bob.head_rev(), PeerId(1), timestamp, op.clone(), op);
+	forged.sign(&forged_key);
+	let mut deltas = bob.cloned_deltas();
+	deltas.push(forged.clone());
+
+	let mut alice = Session::replay_from_history(PeerId(1), deltas, 0).expect("replay failed");
+	let audit = alice.verify_signed_history().expect("history is ordered");
+	assert_eq!(audit.issues, vec![(forged.id, CommitIssue::UnknownSigner)], "alice has no key, so the forgery can't verify");
+
+	alice.set_signing_key(signing_key(1)).expect("alice's own key isn't blocked by bob's registration");
+	contribute(&mut alice, b"alice");
+	assert_eq!(alice.registry().peer_keys.get(&PeerId(1)), Some(&PeerKey::of(&signing_key(1))));
+	let audit = alice.verify_signed_history().expect("history is ordered");
+	assert_eq!(audit.issues, vec![(forged.id, CommitIssue::BadSignature)], "alice's commits verify and the forgery doesn't");
+}
+
+/// Without a key nothing is signed or registered, and verification reports each commit as unsigned rather
+/// than failing. Linearizing re-signs the peer's own rewritten commits (including those made before it had a
+/// key), and once a key is registered, switching the peer to a different one is refused.
+#[test]
+fn unsigned_commits_are_reported_and_registered_keys_are_permanent() {
+	use crate::CommitIssue;
+
+	let mut session = Session::with_peer(PeerId(1));
+	contribute(&mut session, b"unsigned");
+	assert!(session.registry().peer_keys.is_empty(), "an unsigned session registers no key");
+	let audit = session.verify_signed_history().expect("history is ordered");
+	assert_eq!(audit.verified, 0);
+	assert_eq!(audit.issues.len(), session.history().count());
+	assert!(audit.issues.iter().all(|(_, issue)| *issue == CommitIssue::Unsigned));
+
+	session.set_signing_key(signing_key(1)).expect("first key");
+	contribute(&mut session, b"signed");
+	assert_eq!(session.verify_signed_history().expect("history is ordered").verified, 2, "the key registration and the edit are signed");
+
+	session.linearize().expect("linearize failed");
+	let audit = session.verify_signed_history().expect("history is ordered");
+	assert!(audit.is_trusted(), "every rewritten commit is the peer's own, so all are re-signed: {:?}", audit.issues);
+
+	assert!(matches!(session.set_signing_key(signing_key(2)), Err(crate::CrdtError::PeerKeyConflict(PeerId(1)))));
+}
+
 /// A SetExport against a removed network must restore the network from history rather than error.
 #[test]
 fn set_export_resurrects_absent_network() {
@@ -392,6 +392,10 @@ struct UiState {
 	display_name: Option<String>,
 	locked: bool,
 	pinned: bool,
+	component: Option<crate::ComponentEntry>,
+	library_export: Option<crate::LibraryExportEntry>,
+	library_link: Option<crate::LibraryLinkEntry>,
+	layout_grid: Option<serde_json::Value>,
 }
 
 /// In-test `NodeMetadataSource` backed by a `HashMap` keyed on the full `(network_path, local_id)`
@@ -430,6 +434,18 @@ impl NodeMetadataSource for TestMetadata {
 	fn pinned(&self, network_path: &[NodeId], local_id: NodeId) -> bool {
 		self.get(network_path, local_id).is_some_and(|s| s.pinned)
 	}
+	fn component(&self, network_path: &[NodeId], local_id: NodeId) -> Option<crate::ComponentEntry> {
+		self.get(network_path, local_id).and_then(|s| s.component.clone())
+	}
+	fn library_export(&self, network_path: &[NodeId], local_id: NodeId) -> Option<crate::LibraryExportEntry> {
+		self.get(network_path, local_id).and_then(|s| s.library_export.clone())
+	}
+	fn library_link(&self, network_path: &[NodeId], local_id: NodeId) -> Option<crate::LibraryLinkEntry> {
+		self.get(network_path, local_id).and_then(|s| s.library_link.clone())
+	}
+	fn layout_grid(&self, network_path: &[NodeId], local_id: NodeId) -> Option<serde_json::Value> {
+		self.get(network_path, local_id).and_then(|s| s.layout_grid.clone())
+	}
 }
 
 /// Round-trips a nested network with editor metadata: layer + absolute position on one node,
@@ -452,6 +468,8 @@ fn test_ui_metadata_round_trip() {
 			display_name: Some("Outer layer".into()),
 			locked: true,
 			pinned: false,
+			layout_grid: Some(serde_json::json!({ "columns": { "count": 12 } })),
+			..Default::default()
 		},
 	);
 
@@ -497,6 +515,7 @@ fn test_ui_metadata_round_trip() {
 	assert_eq!(root_layer.display_name.as_deref(), Some("Outer layer"));
 	assert!(root_layer.locked);
 	assert!(!root_layer.pinned);
+	assert_eq!(root_layer.layout_grid, Some(serde_json::json!({ "columns": { "count": 12 } })));
 
 	let root_node = lookup.get(&(vec![], NodeId(1))).expect("entry for root-network chain node missing");
 	assert_eq!(root_node.position, Some(Position::Chain));
@@ -507,6 +526,139 @@ fn test_ui_metadata_round_trip() {
 	assert!(nested_layer.is_layer);
 }
 
+/// Component instances whose nested network matches their master's reference the master's stored network
+/// instead of a copy, while a drifted instance keeps its own. Every instance still materializes in full.
+#[test]
+fn component_instances_share_master_network() {
+	use crate::{ComponentEntry, Implementation};
+
+	let master_network = create_nested_network();
+	let DocumentNodeImplementation::Network(component_network) = &master_network.nodes[&NodeId(0)].implementation else {
+		panic!("node 0 should own a nested network");
+	};
+	let mut drifted_network = component_network.clone();
+	drifted_network.exports = vec![NodeInput::import(concrete!(u32), 0)];
+
+	let mut network = master_network.clone();
+	for (id, nested) in [(2, component_network.clone()), (3, drifted_network)] {
+		let node = DocumentNode {
+			inputs: vec![NodeInput::import(concrete!(u32), 0)],
+			implementation: DocumentNodeImplementation::Network(nested),
+			..Default::default()
+		};
+		network.nodes.insert(NodeId(id), node);
+	}
+
+	let mut metadata = TestMetadata::new();
+	for (id, master) in [(0, true), (2, false), (3, false)] {
+		let component = ComponentEntry {
+			component_id: 42,
+			master,
+			overridden_inputs: if master { vec![] } else { vec![0] },
+		};
+		let state = UiState {
+			component: Some(component),
+			..Default::default()
+		};
+		metadata.insert(&[], NodeId(id), state);
+	}
+
+	let conversion = Registry::convert_from_runtime(&network, &metadata, &Default::default(), PeerId(0)).expect("Failed to convert components to Registry");
+	let declarations = conversion.declarations().expect("rebuild declarations");
+	let registry = conversion.registry;
+
+	// Root, the master's network, and the drifted instance's copy
+	assert_eq!(registry.networks.len(), 3);
+	let implementation_of = |local_id: u64| {
+		registry
+			.node_instances
+			.values()
+			.find(|node| node.network() == crate::ROOT_NETWORK && node.attributes().get(crate::attr::node::ORIGINAL_NODE_ID).and_then(|v| v.value.as_u64()) == Some(local_id))
+			.map(|node| node.implementation().clone())
+			.expect("root-network node missing")
+	};
+	let Implementation::Network(master_network_id) = implementation_of(0) else {
+		panic!("master should reference a network");
+	};
+	assert_eq!(implementation_of(2), Implementation::Network(master_network_id));
+	assert_ne!(implementation_of(3), Implementation::Network(master_network_id));
+
+	let (converted, entries) = registry.to_runtime_with_metadata(&declarations).expect("Failed to convert components back to NodeNetwork");
+	assert_eq!(converted, network);
+
+	let instance = entries
+		.iter()
+		.find(|entry| entry.network_path.is_empty() && entry.local_id == NodeId(2))
+		.expect("instance entry missing");
+	assert_eq!(instance.component.as_ref().map(|component| (component.component_id, component.master)), Some((42, false)));
+	assert_eq!(instance.component.as_ref().map(|component| component.overridden_inputs.clone()), Some(vec![0]));
+}
+
+/// Root-network nodes marked for export are listed in the `exported_nodes` document attribute and read back as the
+/// document's library API, while exports in nested networks are ignored. Library links on importing nodes round-trip.
+#[test]
+fn library_exports_round_trip() {
+	use crate::{LibraryExportEntry, LibraryLinkEntry};
+
+	let network = create_nested_network();
+
+	let mut metadata = TestMetadata::new();
+	let export = LibraryExportEntry {
+		name: "Outer".into(),
+		category: "Shapes".into(),
+	};
+	metadata.insert(
+		&[],
+		NodeId(0),
+		UiState {
+			library_export: Some(export.clone()),
+			..Default::default()
+		},
+	);
+	let nested_export = LibraryExportEntry {
+		name: "Nested".into(),
+		category: String::new(),
+	};
+	metadata.insert(
+		&[NodeId(0)],
+		NodeId(10),
+		UiState {
+			library_export: Some(nested_export),
+			..Default::default()
+		},
+	);
+	let link = LibraryLinkEntry {
+		library_id: 9,
+		node: crate::NodeId(3),
+		version: "abc".into(),
+		pinned: true,
+	};
+	metadata.insert(
+		&[],
+		NodeId(1),
+		UiState {
+			library_link: Some(link.clone()),
+			..Default::default()
+		},
+	);
+
+	let conversion = Registry::convert_from_runtime(&network, &metadata, &Default::default(), PeerId(0)).expect("Failed to convert exports to Registry");
+	let declarations = conversion.declarations().expect("rebuild declarations");
+	let registry = conversion.registry;
+
+	let exports = registry.library_exports();
+	assert_eq!(registry.exported_nodes().len(), 1, "only the root-network export is listed");
+	assert_eq!(exports.len(), 1);
+	assert_eq!(exports[0].local_id, 0);
+	assert_eq!(exports[0].name, "Outer");
+	assert_eq!(exports[0].category, "Shapes");
+
+	let (_, entries) = registry.to_runtime_with_metadata(&declarations).expect("Failed to convert exports back to NodeNetwork");
+	let entry = |network_path: &[NodeId], local_id: NodeId| entries.iter().find(|entry| entry.network_path == network_path && entry.local_id == local_id).cloned();
+	assert_eq!(entry(&[], NodeId(0)).and_then(|entry| entry.library_export), Some(export));
+	assert_eq!(entry(&[], NodeId(1)).and_then(|entry| entry.library_link), Some(link));
+}
+
 /// A runtime `ResourceRegistry` (source chain + resolved hash) survives conversion into the storage
 /// `Registry`: source bodies are preserved in priority order and the hash carries through.
 #[test]
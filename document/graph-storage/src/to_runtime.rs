@@ -9,7 +9,7 @@ use graph_craft::{ProtoNodeIdentifier, Type, concrete};
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::attr::*;
-use crate::metadata_source::{InputMetadataEntry, NetworkMetadataEntry, NodeMetadataEntry};
+use crate::metadata_source::{ComponentEntry, InputMetadataEntry, LibraryExportEntry, LibraryLinkEntry, NetworkMetadataEntry, NodeMetadataEntry};
 use crate::{AttributesRead, Implementation, NetworkId, Node, NodeId, NodeInput, Position, ProtoNode, ROOT_NETWORK, Registry, ResourceId};
 
 #[derive(Debug, thiserror::Error)]
@@ -249,6 +249,13 @@ fn extract_ui_metadata(node: &crate::Node, network_path: &[RuntimeNodeId], local
 	let locked = node.attributes.get_or(node::ui::LOCKED, false);
 	let pinned = node.attributes.get_or(node::ui::PINNED, false);
 	let output_names: Vec<String> = node.attributes.get_or_default(node::ui::OUTPUT_NAMES);
+	let component: Option<ComponentEntry> = node.attributes.get_typed(node::COMPONENT);
+	let library_export = node.attributes.get_typed(library::NAME).map(|name| LibraryExportEntry {
+		name,
+		category: node.attributes.get_or_default(library::CATEGORY),
+	});
+	let library_link: Option<LibraryLinkEntry> = node.attributes.get_typed(node::LIBRARY_LINK);
+	let layout_grid: Option<serde_json::Value> = node.attributes.get_typed(node::ui::LAYOUT_GRID);
 
 	let input_metadata: Vec<InputMetadataEntry> = node.inputs.iter().map(|slot| &slot.attributes).map(extract_input_metadata).collect();
 
@@ -262,6 +269,10 @@ fn extract_ui_metadata(node: &crate::Node, network_path: &[RuntimeNodeId], local
 		pinned,
 		input_metadata,
 		output_names,
+		component,
+		library_export,
+		library_link,
+		layout_grid,
 	};
 	(!entry.is_empty()).then_some(entry)
 }
@@ -0,0 +1,22 @@
+//! Reference relay server for `document-sync`: `gdd-relay --listen 0.0.0.0:7878`.
+
+use clap::Parser;
+use document_sync::Relay;
+
+#[derive(Debug, Parser)]
+#[clap(name = "gdd-relay", version, about = "Relay server for real-time .gdd collaboration")]
+struct Args {
+	/// Address to listen on
+	#[clap(long, short = 'l', default_value = "127.0.0.1:7878")]
+	listen: String,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+	env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+	let args = Args::parse();
+
+	let relay = Relay::bind(&args.listen).await?;
+	log::info!("Relaying on {}", relay.local_addr()?);
+	relay.run().await
+}
@@ -0,0 +1,27 @@
+//! Error type for the `document-sync` crate.
+
+use document_format::CodecError;
+use document_graph_storage::CrdtError;
+
+/// Anything that can go wrong moving sync messages over the wire or applying them to a session.
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+	/// The socket failed or closed mid-frame.
+	#[error("I/O error: {0}")]
+	Io(#[from] std::io::Error),
+	/// A frame failed to encode or decode.
+	#[error("codec error: {0}")]
+	Codec(#[from] CodecError),
+	/// A frame's length prefix exceeds [`crate::frame::MAX_FRAME_LEN`], so it is rejected before allocating.
+	#[error("frame length {0} exceeds the {max} byte limit", max = crate::frame::MAX_FRAME_LEN)]
+	FrameTooLarge(usize),
+	/// The peer sent a message that is not valid at this point of the protocol (e.g. no `Join` first).
+	#[error("protocol error: {0}")]
+	Protocol(&'static str),
+	/// An incoming delta or hot op was rejected by the session.
+	#[error("CRDT error: {0}")]
+	Crdt(#[from] CrdtError),
+	/// Persisting an incoming message into a `.gdd` working copy failed.
+	#[error("document error: {0}")]
+	Document(#[from] document_format::Error),
+}
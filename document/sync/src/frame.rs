@@ -0,0 +1,71 @@
+//! Length-prefixed MessagePack framing of [`Message`]s over an async byte stream. Byte-identical to a
+//! [`Codec::MessagePackFrames`] stream, so a captured session can be decoded with the `.gdd` codec.
+
+use document_format::Codec;
+use document_graph_storage::Delta;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{Message, SyncError};
+
+/// Upper bound on one frame's payload, guarding against a malicious or corrupt length prefix forcing an
+/// unbounded allocation. A history larger than this crosses the wire in several `Deltas` frames (see [`encode_deltas`]).
+pub const MAX_FRAME_LEN: usize = 256 << 20;
+
+/// Encode `message` as one frame (length prefix included), ready to write or to fan out unchanged.
+pub fn encode(message: &Message) -> Result<Vec<u8>, SyncError> {
+	let frame = Codec::MessagePackFrames.write_single(message)?;
+	if frame.len() - 4 > MAX_FRAME_LEN {
+		return Err(SyncError::FrameTooLarge(frame.len() - 4));
+	}
+	Ok(frame)
+}
+
+/// Encode `deltas` as one or more `Deltas` frames whose payloads each fit in `max_len` bytes, keeping their
+/// order. Halves the run until each half fits, so a history of any size can be sent; errors with
+/// [`SyncError::FrameTooLarge`] only if a single delta is larger than `max_len` on its own.
+pub fn encode_deltas(deltas: &[Delta], max_len: usize) -> Result<Vec<Vec<u8>>, SyncError> {
+	let frame = Codec::MessagePackFrames.write_single(&Message::Deltas(deltas.to_vec()))?;
+	if frame.len() - 4 <= max_len.min(MAX_FRAME_LEN) {
+		return Ok(vec![frame]);
+	}
+	if deltas.len() <= 1 {
+		return Err(SyncError::FrameTooLarge(frame.len() - 4));
+	}
+
+	let (first, second) = deltas.split_at(deltas.len() / 2);
+	let mut frames = encode_deltas(first, max_len)?;
+	frames.extend(encode_deltas(second, max_len)?);
+	Ok(frames)
+}
+
+pub async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Message) -> Result<(), SyncError> {
+	write_frame(writer, &encode(message)?).await
+}
+
+/// Write an already-encoded frame and flush it.
+pub async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, frame: &[u8]) -> Result<(), SyncError> {
+	writer.write_all(frame).await?;
+	writer.flush().await?;
+	Ok(())
+}
+
+/// Read the next message, or `None` if the stream closed cleanly on a frame boundary. A stream that ends
+/// inside a frame is an error.
+pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<Message>, SyncError> {
+	let mut prefix = [0_u8; 4];
+	match reader.read_exact(&mut prefix).await {
+		Ok(_) => {}
+		Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(error) => return Err(error.into()),
+	}
+
+	let length = u32::from_be_bytes(prefix) as usize;
+	if length > MAX_FRAME_LEN {
+		return Err(SyncError::FrameTooLarge(length));
+	}
+
+	let mut frame = vec![0_u8; 4 + length];
+	frame[..4].copy_from_slice(&prefix);
+	reader.read_exact(&mut frame[4..]).await?;
+	Ok(Some(Codec::MessagePackFrames.read_single(&frame)?))
+}
@@ -0,0 +1,26 @@
+//! Real-time collaboration transport for [`document_graph_storage::Session`] deltas.
+//!
+//! Peers exchange [`Message`]s over TCP, each one length-prefixed MessagePack frame in the same framing
+//! the `.gdd` history file uses ([`document_format::Codec::MessagePackFrames`]). Every peer connects to a
+//! [`Relay`], which groups connections into one room per document and fans each frame out to the rest of
+//! the room. TCP delivers each connection's frames in order and the relay forwards them in arrival order,
+//! which is the causal, exactly-once delivery the CmRDT assumes (see "Causal delivery" in
+//! `node-graph/rfcs/document-format.md`).
+//!
+//! The relay keeps every retired delta it has forwarded while the room is occupied, so a late joiner receives
+//! the room's history before any live traffic. Hot ops are transient and only reach peers connected at the time.
+//!
+//! [`SyncState`] is the peer side: it decides which retired deltas still need publishing and applies
+//! incoming messages to anything implementing [`SyncTarget`] (a bare `Session`, or a `.gdd` handle that
+//! also persists what it receives).
+
+pub mod error;
+pub mod frame;
+pub mod message;
+pub mod peer;
+pub mod relay;
+
+pub use error::SyncError;
+pub use message::Message;
+pub use peer::{Connection, SyncState, SyncTarget};
+pub use relay::Relay;
@@ -0,0 +1,18 @@
+//! The wire protocol: one [`Message`] per frame.
+
+use document_graph_storage::{Delta, HotOp, PeerId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Message {
+	/// First frame on every connection: join the room for `document` (the manifest's `document_id`) as `peer`.
+	/// The relay answers with the room's history so far as one `Deltas` frame (empty for a new room), or as
+	/// several when it is too large for one frame.
+	Join { document: u64, peer: PeerId },
+	/// Retired deltas, in causal order. Sent by a peer to publish its commits, and forwarded by the relay
+	/// to every other peer in the room.
+	Deltas(Vec<Delta>),
+	/// A live, not yet retired op. Forwarded to the peers connected at the time and never stored: its
+	/// author retires it and publishes the resulting delta as usual.
+	Hot(HotOp),
+}
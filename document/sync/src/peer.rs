@@ -0,0 +1,154 @@
+//! The peer side of the protocol: a relay [`Connection`], and [`SyncState`] to decide what to publish and to
+//! apply what arrives.
+
+use std::collections::HashSet;
+
+use document_format::{Gdd, Layout};
+use document_graph_storage::{Delta, HotOp, PeerId, Rev, Session};
+use tokio::io::BufReader;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+use crate::{Message, SyncError, frame};
+
+/// A peer's connection to a [`Relay`](crate::Relay), joined to one document's room.
+pub struct Connection {
+	reader: BufReader<OwnedReadHalf>,
+	writer: OwnedWriteHalf,
+}
+
+impl Connection {
+	/// Connect and join the room for `document` as `peer`. The first messages received are the room's backlog,
+	/// in one or more `Deltas` frames.
+	pub async fn connect(address: impl ToSocketAddrs, document: u64, peer: PeerId) -> Result<Self, SyncError> {
+		let stream = TcpStream::connect(address).await?;
+		stream.set_nodelay(true)?;
+		let (reader, writer) = stream.into_split();
+		let mut connection = Self {
+			reader: BufReader::new(reader),
+			writer,
+		};
+		connection.send(&Message::Join { document, peer }).await?;
+		Ok(connection)
+	}
+
+	pub async fn send(&mut self, message: &Message) -> Result<(), SyncError> {
+		frame::write_message(&mut self.writer, message).await
+	}
+
+	/// The next message from the relay, or `None` once the relay has closed the connection.
+	pub async fn recv(&mut self) -> Result<Option<Message>, SyncError> {
+		frame::read_message(&mut self.reader).await
+	}
+
+	/// Split into independently owned read and write halves, e.g. to receive on one task while an editor
+	/// loop sends on another.
+	pub fn into_split(self) -> (BufReader<OwnedReadHalf>, OwnedWriteHalf) {
+		(self.reader, self.writer)
+	}
+}
+
+/// Something a sync peer keeps in step with the room: a bare [`Session`], or a [`Gdd`] that also persists
+/// what it receives to its working copy.
+pub trait SyncTarget {
+	fn session(&self) -> &Session;
+	fn merge(&mut self, deltas: Vec<Delta>) -> Result<(), SyncError>;
+	fn apply_remote_hot_op(&mut self, hot_op: HotOp) -> Result<(), SyncError>;
+	fn publish_up_to(&mut self, rev: Rev) -> Result<(), SyncError>;
+}
+
+impl SyncTarget for Session {
+	fn session(&self) -> &Session {
+		self
+	}
+
+	fn merge(&mut self, deltas: Vec<Delta>) -> Result<(), SyncError> {
+		Session::merge(self, deltas)?;
+		Ok(())
+	}
+
+	fn apply_remote_hot_op(&mut self, hot_op: HotOp) -> Result<(), SyncError> {
+		Session::apply_remote_hot_op(self, hot_op)?;
+		Ok(())
+	}
+
+	fn publish_up_to(&mut self, rev: Rev) -> Result<(), SyncError> {
+		Session::publish_up_to(self, rev);
+		Ok(())
+	}
+}
+
+impl<L: Layout> SyncTarget for Gdd<L> {
+	fn session(&self) -> &Session {
+		Gdd::session(self)
+	}
+
+	fn merge(&mut self, deltas: Vec<Delta>) -> Result<(), SyncError> {
+		Gdd::merge(self, deltas)?;
+		Ok(())
+	}
+
+	fn apply_remote_hot_op(&mut self, hot_op: HotOp) -> Result<(), SyncError> {
+		Gdd::apply_remote_hot_op(self, hot_op)?;
+		Ok(())
+	}
+
+	fn publish_up_to(&mut self, rev: Rev) -> Result<(), SyncError> {
+		Gdd::publish_up_to(self, rev)?;
+		Ok(())
+	}
+}
+
+/// Which retired deltas a peer and its room already share, so each crosses the wire once.
+#[derive(Clone, Debug, Default)]
+pub struct SyncState {
+	shared: HashSet<Rev>,
+}
+
+impl SyncState {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The `Deltas` message publishing every delta in `head`'s ancestry the room hasn't seen yet, or `None` if
+	/// there is nothing new. Undone and abandoned branches are never published. Advances the published
+	/// frontier to `head`, since from here on other peers hold these `Rev`s (so they can't be silently undone
+	/// or rewritten). Send the returned message before the next call.
+	pub fn outgoing<T: SyncTarget>(&mut self, target: &mut T) -> Result<Option<Message>, SyncError> {
+		let session = target.session();
+		let unpublished: Vec<Delta> = session.reachable_deltas().filter(|delta| !self.shared.contains(&delta.id)).cloned().collect();
+		let Some(head) = session.head_rev() else { return Ok(None) };
+		if unpublished.is_empty() {
+			return Ok(None);
+		}
+
+		self.shared.extend(unpublished.iter().map(|delta| delta.id));
+		target.publish_up_to(head)?;
+		Ok(Some(Message::Deltas(unpublished)))
+	}
+
+	/// Apply a message received from the relay. Incoming deltas are merged (minting a `Merge` if they
+	/// diverge from local edits, which the next [`outgoing`](Self::outgoing) publishes) and count as
+	/// published; incoming hot ops only touch the working registry.
+	pub fn incoming<T: SyncTarget>(&mut self, target: &mut T, message: Message) -> Result<(), SyncError> {
+		match message {
+			Message::Join { .. } => Err(SyncError::Protocol("a relay never sends Join")),
+			Message::Hot(hot_op) => target.apply_remote_hot_op(hot_op),
+			Message::Deltas(deltas) => {
+				if deltas.is_empty() {
+					return Ok(());
+				}
+				self.shared.extend(deltas.iter().map(|delta| delta.id));
+				let head = target.session().head_rev();
+				target.merge(deltas)?;
+
+				// Fast-forwarding onto the room's edits makes them the new frontier: they're already published.
+				let new_head = target.session().head_rev();
+				if let Some(new_head) = new_head.filter(|&rev| Some(rev) != head && self.shared.contains(&rev)) {
+					target.publish_up_to(new_head)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
@@ -0,0 +1,155 @@
+//! Reference relay server: one room per document, fanning each peer's frames out to the rest of the room.
+//!
+//! The relay never applies ops or inspects registry state. It only dedups retired deltas by `Rev` (so a
+//! delta two peers both publish, like a shared merge, is forwarded once) and keeps them as the room's
+//! backlog for late joiners. Rooms live in memory while any peer is connected: when the last one leaves, the
+//! room and its backlog are dropped, and the next peer to join starts a fresh room from its own history.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use document_graph_storage::{Delta, PeerId, Rev};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+
+use crate::{Message, SyncError, frame};
+
+/// An encoded frame shared by every connection it is fanned out to.
+type Frame = Arc<Vec<u8>>;
+
+#[derive(Default)]
+struct Room {
+	/// Every retired delta forwarded so far, in arrival (causal) order.
+	backlog: Vec<Delta>,
+	seen: HashSet<Rev>,
+	/// Outgoing frame queue per connection, keyed by connection id.
+	connections: HashMap<u64, (PeerId, mpsc::UnboundedSender<Frame>)>,
+}
+
+impl Room {
+	fn broadcast(&self, from: u64, frame: &Frame) {
+		for (&id, (_, sender)) in &self.connections {
+			if id != from {
+				// A closed receiver means that connection is shutting down and will leave the room itself.
+				let _ = sender.send(frame.clone());
+			}
+		}
+	}
+}
+
+#[derive(Default)]
+struct Rooms {
+	rooms: HashMap<u64, Room>,
+	next_connection: u64,
+}
+
+pub struct Relay {
+	listener: TcpListener,
+	rooms: Arc<Mutex<Rooms>>,
+}
+
+impl Relay {
+	pub async fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+		Ok(Self {
+			listener: TcpListener::bind(address).await?,
+			rooms: Arc::default(),
+		})
+	}
+
+	/// The bound address, e.g. to learn the port after binding `127.0.0.1:0`.
+	pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+		self.listener.local_addr()
+	}
+
+	/// Accept connections forever, serving each on its own task. Only returns if accepting fails.
+	pub async fn run(self) -> std::io::Result<()> {
+		loop {
+			let (stream, address) = self.listener.accept().await?;
+			let rooms = self.rooms.clone();
+			tokio::spawn(async move {
+				if let Err(error) = serve(stream, rooms).await {
+					log::warn!("Connection from {address} closed: {error}");
+				}
+			});
+		}
+	}
+}
+
+/// Serve one connection: expect `Join`, send the room's backlog, then forward every frame it sends until it
+/// disconnects.
+async fn serve(stream: TcpStream, rooms: Arc<Mutex<Rooms>>) -> Result<(), SyncError> {
+	stream.set_nodelay(true)?;
+	let (mut reader, mut writer) = stream.into_split();
+
+	let Some(Message::Join { document, peer }) = frame::read_message(&mut reader).await? else {
+		return Err(SyncError::Protocol("the first message must be Join"));
+	};
+
+	// Register and snapshot the backlog under one lock, so no delta forwarded in between is missed or doubled.
+	let (sender, mut receiver) = mpsc::unbounded_channel::<Frame>();
+	let (connection, backlog) = {
+		let mut rooms = rooms.lock().expect("relay rooms lock poisoned");
+		let connection = rooms.next_connection;
+		rooms.next_connection += 1;
+		let room = rooms.rooms.entry(document).or_default();
+		room.connections.insert(connection, (peer, sender));
+		(connection, room.backlog.clone())
+	};
+	log::info!("Peer {} joined document {document}", peer.0);
+
+	let result = async {
+		// A backlog too large for one frame is split across several, still in causal order.
+		for backlog_frame in frame::encode_deltas(&backlog, frame::MAX_FRAME_LEN)? {
+			frame::write_frame(&mut writer, &backlog_frame).await?;
+		}
+
+		let forward = async {
+			while let Some(frame) = receiver.recv().await {
+				frame::write_frame(&mut writer, &frame).await?;
+			}
+			Ok::<_, SyncError>(())
+		};
+		let receive = async {
+			while let Some(message) = frame::read_message(&mut reader).await? {
+				relay_message(&rooms, document, connection, message)?;
+			}
+			Ok::<_, SyncError>(())
+		};
+
+		// Whichever side finishes first (the peer hanging up, or a socket error) ends the connection.
+		tokio::select! {
+			result = forward => result,
+			result = receive => result,
+		}
+	}
+	.await;
+
+	let mut rooms = rooms.lock().expect("relay rooms lock poisoned");
+	if let Some(room) = rooms.rooms.get_mut(&document) {
+		room.connections.remove(&connection);
+		if room.connections.is_empty() {
+			rooms.rooms.remove(&document);
+		}
+	}
+	log::info!("Peer {} left document {document}", peer.0);
+	result
+}
+
+fn relay_message(rooms: &Mutex<Rooms>, document: u64, from: u64, message: Message) -> Result<(), SyncError> {
+	let mut rooms = rooms.lock().expect("relay rooms lock poisoned");
+	let Some(room) = rooms.rooms.get_mut(&document) else { return Ok(()) };
+
+	match message {
+		Message::Join { .. } => return Err(SyncError::Protocol("Join sent twice")),
+		Message::Hot(hot_op) => room.broadcast(from, &Arc::new(frame::encode(&Message::Hot(hot_op))?)),
+		Message::Deltas(deltas) => {
+			let fresh: Vec<Delta> = deltas.into_iter().filter(|delta| room.seen.insert(delta.id)).collect();
+			if !fresh.is_empty() {
+				room.backlog.extend(fresh.iter().cloned());
+				room.broadcast(from, &Arc::new(frame::encode(&Message::Deltas(fresh))?));
+			}
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,215 @@
+//! Several peers co-editing through a localhost relay.
+
+use document_container::AnyContainer;
+use document_container::backends::memory::MemoryBackend;
+use document_format::{GddV1, GddV1Layout};
+use document_graph_storage::{AttributeDelta, HotOp, PeerId, RegistryDelta, Session, TimeStamp};
+use document_sync::{Connection, Message, Relay, SyncState, SyncTarget, frame};
+
+const DOCUMENT: u64 = 0xD0C;
+
+async fn start_relay() -> std::net::SocketAddr {
+	let relay = Relay::bind("127.0.0.1:0").await.expect("bind relay");
+	let address = relay.local_addr().expect("relay address");
+	tokio::spawn(relay.run());
+	address
+}
+
+struct Peer<T: SyncTarget> {
+	target: T,
+	state: SyncState,
+	connection: Connection,
+}
+
+impl<T: SyncTarget> Peer<T> {
+	/// Connect and absorb the room's backlog.
+	async fn join(address: std::net::SocketAddr, target: T) -> Self {
+		let peer = target.session().peer();
+		let connection = Connection::connect(address, DOCUMENT, peer).await.expect("connect");
+		let mut peer = Self {
+			target,
+			state: SyncState::new(),
+			connection,
+		};
+		peer.receive().await;
+		peer
+	}
+
+	async fn publish(&mut self) {
+		if let Some(message) = self.state.outgoing(&mut self.target).expect("outgoing") {
+			self.connection.send(&message).await.expect("send");
+		}
+	}
+
+	async fn receive(&mut self) {
+		let message = self.connection.recv().await.expect("recv").expect("relay closed the connection");
+		self.state.incoming(&mut self.target, message).expect("incoming");
+	}
+}
+
+fn set_attribute(key: &str, value: u32) -> RegistryDelta {
+	RegistryDelta::ChangeDocumentAttribute {
+		delta: AttributeDelta {
+			key: key.to_string(),
+			value: Some(serde_json::json!(value)),
+		},
+	}
+}
+
+/// Stage `op` as a hot op and retire it, the way a local edit reaches history.
+fn edit(session: &mut Session, op: RegistryDelta, counter: u64) {
+	let timestamp = TimeStamp { counter, peer: session.peer() };
+	session.apply_hot_op(HotOp { op, timestamp }).expect("apply_hot_op");
+	session.retire(timestamp).expect("retire");
+}
+
+fn attribute(session: &Session, key: &str) -> Option<serde_json::Value> {
+	session.registry().attributes.get(key).map(|value| value.value.clone())
+}
+
+/// Two peers editing concurrently converge on the same registry and byte-identical history: each merges the
+/// other's edit into the same parent-set-addressed `Merge`, which the relay forwards only once.
+#[tokio::test]
+async fn concurrent_edits_converge() {
+	let address = start_relay().await;
+	let mut alice = Peer::join(address, Session::with_peer(PeerId(1))).await;
+	let mut bob = Peer::join(address, Session::with_peer(PeerId(2))).await;
+
+	edit(&mut alice.target, set_attribute("compute::alice", 1), 1);
+	edit(&mut bob.target, set_attribute("compute::bob", 2), 1);
+	alice.publish().await;
+	bob.publish().await;
+	alice.receive().await;
+	bob.receive().await;
+
+	// Both minted the same merge; publishing it again is deduped by the relay.
+	assert_eq!(alice.target.head_rev(), bob.target.head_rev());
+	alice.publish().await;
+	bob.publish().await;
+
+	for key in ["compute::alice", "compute::bob"] {
+		assert!(attribute(&alice.target, key).is_some());
+		assert_eq!(attribute(&alice.target, key), attribute(&bob.target, key));
+	}
+	let history = |session: &Session| session.history().map(|delta| delta.id).collect::<Vec<_>>();
+	assert_eq!(history(&alice.target), history(&bob.target));
+	assert_eq!(alice.target.last_broadcast_rev(), alice.target.head_rev(), "published commits are no longer silently undoable");
+}
+
+/// A peer joining later receives the room's whole history before any live traffic and can build on it.
+#[tokio::test]
+async fn late_joiner_receives_backlog() {
+	let address = start_relay().await;
+	let mut alice = Peer::join(address, Session::with_peer(PeerId(1))).await;
+	let mut witness = Peer::join(address, Session::with_peer(PeerId(3))).await;
+	edit(&mut alice.target, set_attribute("compute::first", 1), 1);
+	edit(&mut alice.target, set_attribute("compute::second", 2), 2);
+	alice.publish().await;
+
+	// The relay records deltas in the backlog before forwarding them, so once the witness has them Bob's join
+	// is guaranteed to see them.
+	witness.receive().await;
+	let bob = Peer::join(address, Session::with_peer(PeerId(2))).await;
+
+	assert_eq!(bob.target.head_rev(), alice.target.head_rev(), "the backlog fast-forwards an empty session");
+	assert_eq!(attribute(&bob.target, "compute::second"), Some(serde_json::json!(2)));
+}
+
+/// Once every peer has left, the room and its backlog are dropped, so the next joiner starts from an empty room.
+#[tokio::test]
+async fn empty_rooms_are_dropped() {
+	let address = start_relay().await;
+	let mut alice = Peer::join(address, Session::with_peer(PeerId(1))).await;
+	let mut witness = Peer::join(address, Session::with_peer(PeerId(3))).await;
+	edit(&mut alice.target, set_attribute("compute::gone", 1), 1);
+	alice.publish().await;
+	witness.receive().await;
+
+	// The relay notices a disconnect on its next read from the socket, so give it a moment before each attempt.
+	drop((alice, witness));
+	let mut emptied = false;
+	for _ in 0..100 {
+		tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+		let bob = Peer::join(address, Session::with_peer(PeerId(2))).await;
+		if bob.target.head_rev().is_none() {
+			emptied = true;
+			break;
+		}
+	}
+	assert!(emptied, "the backlog left with the last peer");
+}
+
+/// A history too large for one frame is split into several `Deltas` frames that each fit, in the original
+/// order, and applying them one by one rebuilds the same history.
+#[test]
+fn large_backlogs_are_split_across_frames() {
+	let mut alice = Session::with_peer(PeerId(1));
+	for counter in 1..=20 {
+		edit(&mut alice, set_attribute(&format!("compute::edit_{counter}"), counter as u32), counter);
+	}
+	let deltas: Vec<_> = alice.history().cloned().collect();
+	let whole = frame::encode_deltas(&deltas, frame::MAX_FRAME_LEN).expect("encode whole");
+	assert_eq!(whole.len(), 1, "a small history fits one frame");
+
+	let max_len = (whole[0].len() - 4) / 5;
+	let frames = frame::encode_deltas(&deltas, max_len).expect("encode split");
+	assert!(frames.len() >= 5);
+	assert!(frames.iter().all(|frame| frame.len() - 4 <= max_len));
+
+	let mut bob = Session::with_peer(PeerId(2));
+	let mut state = SyncState::new();
+	let mut received = Vec::new();
+	for frame in frames {
+		let message = document_format::Codec::MessagePackFrames.read_single::<Message>(&frame).expect("decode");
+		let Message::Deltas(chunk) = &message else { panic!("expected a Deltas frame") };
+		received.extend(chunk.iter().map(|delta| delta.id));
+		state.incoming(&mut bob, message).expect("incoming");
+	}
+	assert_eq!(received, deltas.iter().map(|delta| delta.id).collect::<Vec<_>>());
+	assert_eq!(bob.head_rev(), alice.head_rev());
+
+	assert!(
+		matches!(frame::encode_deltas(&deltas[..1], 8), Err(document_sync::SyncError::FrameTooLarge(_))),
+		"one delta can't be split"
+	);
+}
+
+/// Hot ops reach connected peers' working registries without entering their hot logs, so the edit is
+/// retired once, by its author.
+#[tokio::test]
+async fn hot_ops_are_relayed_but_not_retired_remotely() {
+	let address = start_relay().await;
+	let mut alice = Peer::join(address, Session::with_peer(PeerId(1))).await;
+	let mut bob = Peer::join(address, Session::with_peer(PeerId(2))).await;
+
+	let hot_op = HotOp {
+		op: set_attribute("compute::live", 7),
+		timestamp: TimeStamp { counter: 1, peer: PeerId(1) },
+	};
+	alice.target.apply_hot_op(hot_op.clone()).expect("apply_hot_op");
+	alice.connection.send(&Message::Hot(hot_op)).await.expect("send hot op");
+	bob.receive().await;
+
+	assert_eq!(attribute(&bob.target, "compute::live"), Some(serde_json::json!(7)));
+	assert!(bob.target.hot_log().is_empty());
+}
+
+/// A `.gdd` peer persists what it receives, so a reopen sees the other peer's edits.
+#[tokio::test]
+async fn gdd_peer_persists_received_deltas() {
+	let address = start_relay().await;
+	let gdd = GddV1::create_in(AnyContainer::Memory(MemoryBackend::new()), GddV1Layout, PeerId(2), DOCUMENT, "ed".into(), "std".into())
+		.await
+		.expect("create_in");
+	let mut alice = Peer::join(address, Session::with_peer(PeerId(1))).await;
+	let mut bob = Peer::join(address, gdd).await;
+
+	edit(&mut alice.target, set_attribute("compute::shared", 3), 1);
+	alice.publish().await;
+	bob.receive().await;
+
+	let (working, layout) = bob.target.into_storage();
+	let reopened = GddV1::open_in(working, layout).await.expect("open_in");
+	assert_eq!(reopened.session().head_rev(), alice.target.head_rev());
+	assert_eq!(attribute(reopened.session(), "compute::shared"), Some(serde_json::json!(3)));
+}
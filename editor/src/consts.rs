@@ -66,6 +66,11 @@ pub const SELECTION_DRAG_ANGLE: f64 = 90.;
 pub const LAYER_ORIGIN_CROSS_DIAMETER: f64 = 10.;
 pub const LAYER_ORIGIN_CROSS_THICKNESS: f64 = 1.;
 
+// GUIDES
+/// How close (in viewport pixels) the pointer has to be to a guide to grab it.
+pub const GUIDE_GRAB_DISTANCE: f64 = 3.;
+pub const GUIDE_ROTATE_SNAP_ANGLE: f64 = 15.;
+
 // PIVOT
 pub const PIVOT_CROSSHAIR_THICKNESS: f64 = 1.;
 pub const PIVOT_CROSSHAIR_LENGTH: f64 = 9.;
@@ -161,12 +166,14 @@ pub const COLOR_OVERLAY_BLUE: &str = "#00a8ff";
 pub const COLOR_OVERLAY_BLUE_50: &str = "#00a8ff80";
 pub const COLOR_OVERLAY_BLUE_25: &str = "#00a8ff40";
 pub const COLOR_OVERLAY_BLUE_05: &str = "#00a8ff0d";
+pub const COLOR_OVERLAY_CYAN: &str = "#00d7d7";
 pub const COLOR_OVERLAY_YELLOW: &str = "#ffc848";
 pub const COLOR_OVERLAY_YELLOW_DULL: &str = "#d7ba8b";
 pub const COLOR_OVERLAY_GREEN: &str = "#63ce63";
 pub const COLOR_OVERLAY_GREEN_25: &str = "#63ce6340";
 pub const COLOR_OVERLAY_RED: &str = "#ef5454";
 pub const COLOR_OVERLAY_RED_25: &str = "#ef545440";
+pub const COLOR_OVERLAY_RED_10: &str = "#ef54541a";
 pub const COLOR_OVERLAY_GRAY: &str = "#cccccc";
 pub const COLOR_OVERLAY_GRAY_25: &str = "#cccccc40";
 pub const COLOR_OVERLAY_WHITE: &str = "#ffffff";
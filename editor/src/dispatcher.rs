@@ -274,12 +274,16 @@ impl Dispatcher {
 					menu_bar_message_handler.reset_node_definitions_on_open = self.message_handlers.portfolio_message_handler.reset_node_definitions_on_open;
 					menu_bar_message_handler.show_storage_preferences = self.message_handlers.preferences_message_handler.show_storage_preferences;
 
+					let node_libraries = &self.message_handlers.portfolio_message_handler.node_libraries;
 					if let Some(document) = self
 						.message_handlers
 						.portfolio_message_handler
 						.active_document_id
 						.and_then(|document_id| self.message_handlers.portfolio_message_handler.documents.get_mut(&document_id))
 					{
+						let library_nodes = document.network_interface.library_nodes();
+						menu_bar_message_handler.has_library_updates = node_libraries.has_updates(library_nodes.iter().map(|(_, _, library_link)| library_link));
+
 						let selected_nodes = document.network_interface.selected_nodes();
 						let metadata = &document.network_interface.document_network_metadata().persistent_metadata;
 
@@ -287,9 +291,19 @@ impl Dispatcher {
 						menu_bar_message_handler.canvas_tilted = document.document_ptz.tilt() != 0.;
 						menu_bar_message_handler.canvas_flipped = document.document_ptz.flip;
 						menu_bar_message_handler.rulers_visible = document.rulers_visible;
+						menu_bar_message_handler.guides_locked = document.guides.locked;
+						menu_bar_message_handler.has_guides = !document.guides.guides.is_empty();
 						menu_bar_message_handler.node_graph_open = document.is_graph_overlay_open();
 						menu_bar_message_handler.has_selected_nodes = selected_nodes.selected_nodes().next().is_some();
 						menu_bar_message_handler.has_selected_layers = selected_nodes.selected_visible_layers(&document.network_interface).next().is_some();
+						let selected_components = selected_nodes
+							.selected_visible_layers(&document.network_interface)
+							.filter_map(|layer| document.network_interface.component(&layer.to_node(), &[]))
+							.collect::<Vec<_>>();
+						menu_bar_message_handler.has_selected_components = !selected_components.is_empty();
+						menu_bar_message_handler.has_selected_instances = selected_components.iter().any(|component| !component.is_master());
+						menu_bar_message_handler.has_exportable_selection = selected_nodes.selected_nodes().any(|node_id| document.network_interface.can_export_to_library(node_id, &[]));
+						menu_bar_message_handler.has_selected_library_nodes = selected_nodes.selected_nodes().any(|node_id| document.network_interface.library_link(node_id, &[]).is_some());
 						menu_bar_message_handler.has_selection_history = (!metadata.selection_undo_history.is_empty(), !metadata.selection_redo_history.is_empty());
 						menu_bar_message_handler.make_path_editable_is_allowed = make_path_editable_is_allowed(&mut document.network_interface).is_some();
 					} else {
@@ -297,9 +311,16 @@ impl Dispatcher {
 						menu_bar_message_handler.canvas_tilted = false;
 						menu_bar_message_handler.canvas_flipped = false;
 						menu_bar_message_handler.rulers_visible = false;
+						menu_bar_message_handler.guides_locked = false;
+						menu_bar_message_handler.has_guides = false;
 						menu_bar_message_handler.node_graph_open = false;
 						menu_bar_message_handler.has_selected_nodes = false;
 						menu_bar_message_handler.has_selected_layers = false;
+						menu_bar_message_handler.has_selected_components = false;
+						menu_bar_message_handler.has_selected_instances = false;
+						menu_bar_message_handler.has_exportable_selection = false;
+						menu_bar_message_handler.has_selected_library_nodes = false;
+						menu_bar_message_handler.has_library_updates = false;
 						menu_bar_message_handler.has_selection_history = (false, false);
 						menu_bar_message_handler.make_path_editable_is_allowed = false;
 					}
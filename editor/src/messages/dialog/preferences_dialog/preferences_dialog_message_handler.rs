@@ -275,7 +275,7 @@ impl PreferencesDialogMessageHandler {
 
 			let checkbox_id = CheckboxId::new();
 			let brush_tool_description = "
-				Enable the Brush tool to support basic raster-based layer painting.\n\
+				Enable the Brush tool to support basic raster-based layer painting, along with the Heal and Patch tools built on it.\n\
 				\n\
 				This legacy experimental tool has performance and quality limitations and is slated for replacement in future versions of Graphite that will have a renewed focus on raster graphics editing.\n\
 				\n\
@@ -127,6 +127,10 @@ pub enum FrontendMessage {
 		document: String,
 	},
 	TriggerOpenLaunchDocuments,
+	/// Asks the desktop app to read the `.gdd` node libraries at these paths and import them.
+	TriggerImportLibraries {
+		paths: Vec<PathBuf>,
+	},
 	TriggerLoadPreferences,
 	TriggerOpen,
 	TriggerImport,
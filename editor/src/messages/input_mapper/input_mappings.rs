@@ -10,6 +10,7 @@ use crate::messages::portfolio::document::node_graph::utility_types::Direction;
 use crate::messages::portfolio::document::utility_types::misc::GroupFolderType;
 use crate::messages::prelude::*;
 use crate::messages::tool::tool_messages::brush_tool::BrushToolMessageOptionsUpdate;
+use crate::messages::tool::tool_messages::heal_tool::HealToolMessageOptionsUpdate;
 use crate::messages::tool::tool_messages::select_tool::SelectToolPointerKeys;
 use glam::DVec2;
 
@@ -108,7 +109,7 @@ pub fn input_mappings(zoom_with_scroll: bool) -> Mapping {
 		entry!(PointerMove; refresh_keys=[Control, Shift], action_dispatch=TransformLayerMessage::PointerMove { slow_key: Shift, increments_key: Control }),
 		//
 		// SelectToolMessage
-		entry!(PointerMove; refresh_keys=[Control, Alt, Shift], action_dispatch=SelectToolMessage::PointerMove { modifier_keys: SelectToolPointerKeys { axis_align: Shift, snap_angle: Shift, center: Alt, duplicate: Alt } }),
+		entry!(PointerMove; refresh_keys=[Control, Alt, Shift], action_dispatch=SelectToolMessage::PointerMove { modifier_keys: SelectToolPointerKeys { axis_align: Shift, snap_angle: Shift, center: Alt, duplicate: Alt, rotate_guide: Control } }),
 		entry!(KeyDown(MouseLeft); action_dispatch=SelectToolMessage::DragStart { extend_selection: Shift, remove_from_selection: Alt, select_deepest: Accel, lasso_select: Control, skew: Control }),
 		entry!(KeyUp(MouseLeft); action_dispatch=SelectToolMessage::DragStop { remove_from_selection: Alt }),
 		entry!(KeyDown(Enter); action_dispatch=SelectToolMessage::Enter),
@@ -307,13 +308,29 @@ pub fn input_mappings(zoom_with_scroll: bool) -> Mapping {
 		//
 		// BrushToolMessage
 		entry!(PointerMove; action_dispatch=BrushToolMessage::PointerMove),
-		entry!(KeyDown(MouseLeft); action_dispatch=BrushToolMessage::DragStart),
+		entry!(KeyDown(MouseLeft); action_dispatch=BrushToolMessage::DragStart { set_clone_source: Alt }),
 		entry!(KeyUp(MouseLeft); action_dispatch=BrushToolMessage::DragStop),
 		entry!(KeyDown(BracketLeft); action_dispatch=BrushToolMessage::UpdateOptions { options: BrushToolMessageOptionsUpdate::ChangeDiameter(-BRUSH_SIZE_CHANGE_KEYBOARD) }),
 		entry!(KeyDown(BracketRight); action_dispatch=BrushToolMessage::UpdateOptions { options: BrushToolMessageOptionsUpdate::ChangeDiameter(BRUSH_SIZE_CHANGE_KEYBOARD) }),
 		entry!(KeyDown(MouseRight); action_dispatch=BrushToolMessage::Abort),
 		entry!(KeyDown(Escape); action_dispatch=BrushToolMessage::Abort),
 		//
+		// HealToolMessage
+		entry!(PointerMove; action_dispatch=HealToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); action_dispatch=HealToolMessage::DragStart { set_source: Alt }),
+		entry!(KeyUp(MouseLeft); action_dispatch=HealToolMessage::DragStop),
+		entry!(KeyDown(BracketLeft); action_dispatch=HealToolMessage::UpdateOptions { options: HealToolMessageOptionsUpdate::ChangeDiameter(-BRUSH_SIZE_CHANGE_KEYBOARD) }),
+		entry!(KeyDown(BracketRight); action_dispatch=HealToolMessage::UpdateOptions { options: HealToolMessageOptionsUpdate::ChangeDiameter(BRUSH_SIZE_CHANGE_KEYBOARD) }),
+		entry!(KeyDown(MouseRight); action_dispatch=HealToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=HealToolMessage::Abort),
+		//
+		// PatchToolMessage
+		entry!(PointerMove; action_dispatch=PatchToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); action_dispatch=PatchToolMessage::DragStart),
+		entry!(KeyUp(MouseLeft); action_dispatch=PatchToolMessage::DragStop),
+		entry!(KeyDown(MouseRight); action_dispatch=PatchToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=PatchToolMessage::Abort),
+		//
 		// ToolMessage
 		entry!(KeyDown(KeyV); action_dispatch=ToolMessage::ActivateToolSelect),
 		entry!(KeyDown(KeyZ); action_dispatch=ToolMessage::ActivateToolNavigate),
@@ -329,6 +346,8 @@ pub fn input_mappings(zoom_with_scroll: bool) -> Mapping {
 		entry!(KeyDown(KeyE); action_dispatch=ToolMessage::ActivateToolShapeEllipse),
 		entry!(KeyDown(KeyY); action_dispatch=ToolMessage::ActivateToolShape),
 		entry!(KeyDown(KeyB); action_dispatch=ToolMessage::ActivateToolBrush),
+		entry!(KeyDown(KeyJ); action_dispatch=ToolMessage::ActivateToolHeal),
+		entry!(KeyDown(KeyJ); modifiers=[Shift], action_dispatch=ToolMessage::ActivateToolPatch),
 		entry!(KeyDown(KeyD); action_dispatch=ToolMessage::ResetColors),
 		entry!(KeyDown(KeyX); modifiers=[Shift], action_dispatch=ToolMessage::SwapColors),
 		entry!(KeyDown(KeyC); modifiers=[Alt], action_dispatch=ToolMessage::SelectRandomWorkingColor { primary: true }),
@@ -50,12 +50,37 @@ impl ScrollDelta {
 	}
 }
 
+/// The readings of a pen tablet's stylus which accompany a pointer event. A mouse reports full pressure with no tilt or twist.
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StylusState {
+	/// How hard the stylus is pressed, from 0 to 1.
+	pub pressure: f64,
+	/// The angles between the stylus and the tablet's normal along the X and Y axes, in degrees from -90 to 90.
+	pub tilt: DVec2,
+	/// The clockwise rotation of the stylus around its own axis, in degrees from 0 to 359.
+	pub twist: f64,
+	/// When the pointer event occurred, in milliseconds.
+	pub timestamp: f64,
+}
+
+impl Default for StylusState {
+	fn default() -> Self {
+		Self {
+			pressure: 1.,
+			tilt: DVec2::ZERO,
+			twist: 0.,
+			timestamp: 0.,
+		}
+	}
+}
+
 // TODO: Document the difference between this and EditorMouseState
 #[derive(Debug, Copy, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct MouseState {
 	pub position: ViewportPosition,
 	pub mouse_keys: MouseKeys,
 	pub scroll_delta: ScrollDelta,
+	pub stylus: StylusState,
 }
 
 impl MouseState {
@@ -72,6 +97,7 @@ pub struct EditorMouseState {
 	pub editor_position: EditorPosition,
 	pub mouse_keys: MouseKeys,
 	pub scroll_delta: ScrollDelta,
+	pub stylus: StylusState,
 }
 
 impl EditorMouseState {
@@ -83,6 +109,7 @@ impl EditorMouseState {
 			editor_position,
 			mouse_keys,
 			scroll_delta: ScrollDelta::default(),
+			stylus: StylusState::default(),
 		}
 	}
 
@@ -91,6 +118,7 @@ impl EditorMouseState {
 			position: (viewport.logical(self.editor_position) - viewport.offset()).into(),
 			mouse_keys: self.mouse_keys,
 			scroll_delta: self.scroll_delta,
+			stylus: self.stylus,
 		}
 	}
 }
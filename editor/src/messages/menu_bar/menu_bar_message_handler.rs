@@ -11,9 +11,16 @@ pub struct MenuBarMessageHandler {
 	pub canvas_tilted: bool,
 	pub canvas_flipped: bool,
 	pub rulers_visible: bool,
+	pub guides_locked: bool,
+	pub has_guides: bool,
 	pub node_graph_open: bool,
 	pub has_selected_nodes: bool,
 	pub has_selected_layers: bool,
+	pub has_selected_components: bool,
+	pub has_selected_instances: bool,
+	pub has_exportable_selection: bool,
+	pub has_selected_library_nodes: bool,
+	pub has_library_updates: bool,
 	pub has_selection_history: (bool, bool),
 	pub message_logging_verbosity: MessageLoggingVerbosity,
 	pub reset_node_definitions_on_open: bool,
@@ -46,6 +53,11 @@ impl LayoutHolder for MenuBarMessageHandler {
 		let node_graph_open = self.node_graph_open;
 		let has_selected_nodes = self.has_selected_nodes;
 		let has_selected_layers = self.has_selected_layers;
+		let has_selected_components = self.has_selected_components;
+		let has_selected_instances = self.has_selected_instances;
+		let has_exportable_selection = self.has_exportable_selection;
+		let has_selected_library_nodes = self.has_selected_library_nodes;
+		let has_library_updates = self.has_library_updates;
 		let has_selection_history = self.has_selection_history;
 		let message_logging_verbosity_off = self.message_logging_verbosity == MessageLoggingVerbosity::Off;
 		let message_logging_verbosity_names = self.message_logging_verbosity == MessageLoggingVerbosity::Names;
@@ -269,6 +281,50 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.on_commit(|_| DocumentMessage::UngroupSelectedLayers.into())
 							.disabled(no_active_document || !has_selected_layers),
 					],
+					vec![
+						MenuListEntry::new("Create Component")
+							.label("Create Component")
+							.icon("NodeNodes")
+							.on_commit(|_| DocumentMessage::CreateComponent.into())
+							.disabled(no_active_document || !has_selected_layers),
+						MenuListEntry::new("Place Instance")
+							.label("Place Instance")
+							.icon("Copy")
+							.on_commit(|_| DocumentMessage::PlaceComponentInstance.into())
+							.disabled(no_active_document || !has_selected_components),
+						MenuListEntry::new("Select Master")
+							.label("Select Master")
+							.icon("Link")
+							.on_commit(|_| DocumentMessage::SelectComponentMaster.into())
+							.disabled(no_active_document || !has_selected_instances),
+						MenuListEntry::new("Detach Instance")
+							.label("Detach Instance")
+							.icon("Node")
+							.on_commit(|_| DocumentMessage::DetachComponentInstances.into())
+							.disabled(no_active_document || !has_selected_instances),
+						MenuListEntry::new("Reset Overrides")
+							.label("Reset Overrides")
+							.icon("Reset")
+							.on_commit(|_| DocumentMessage::ResetComponentOverrides.into())
+							.disabled(no_active_document || !has_selected_instances),
+					],
+					vec![
+						MenuListEntry::new("Export to Library")
+							.label("Export to Library")
+							.icon("NodeNodes")
+							.on_commit(|_| DocumentMessage::ToggleSelectedLibraryExports.into())
+							.disabled(no_active_document || !has_exportable_selection),
+						MenuListEntry::new("Pin/Unpin Library Version")
+							.label("Pin/Unpin Library Version")
+							.icon("PinActive")
+							.on_commit(|_| DocumentMessage::ToggleSelectedLibraryNodesPinned.into())
+							.disabled(no_active_document || !has_selected_library_nodes),
+						MenuListEntry::new("Update Library Nodes")
+							.label("Update Library Nodes")
+							.icon("Reload")
+							.on_commit(|_| DocumentMessage::UpdateLibraryNodes.into())
+							.disabled(no_active_document || !has_library_updates),
+					],
 					vec![
 						MenuListEntry::new("Hide/Show")
 							.label("Hide/Show")
@@ -635,6 +691,16 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.tooltip_shortcut(action_shortcut!(PortfolioMessageDiscriminant::ToggleRulers))
 							.on_commit(|_| PortfolioMessage::ToggleRulers.into())
 							.disabled(no_active_document),
+						MenuListEntry::new("Lock Guides")
+							.label("Lock Guides")
+							.icon(if self.guides_locked { "CheckboxChecked" } else { "CheckboxUnchecked" })
+							.on_commit(|_| GuideMessage::ToggleLock.into())
+							.disabled(no_active_document),
+						MenuListEntry::new("Clear Guides")
+							.label("Clear Guides")
+							.icon("Trash")
+							.on_commit(|_| GuideMessage::ClearAll.into())
+							.disabled(no_active_document || !self.has_guides),
 					],
 				])
 				.widget_instance(),
@@ -14,7 +14,8 @@ use graphene_std::gradient::Gradient;
 use graphene_std::list::{Item, List, NodeIdPath};
 use graphene_std::memo::IORecord;
 use graphene_std::raster::{
-	CellularDistanceFunction, CellularReturnType, DomainWarpType, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute, SelectiveColorChoice,
+	CellularDistanceFunction, CellularReturnType, DomainWarpType, EdgeDetectionOperator, EdgeHandling, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute,
+	ResamplingFilter, SelectiveColorChoice, StructuringElement,
 };
 use graphene_std::raster_types::{CPU, GPU, Raster};
 use graphene_std::text::TextAlign;
@@ -228,6 +229,8 @@ fn generate_layout(introspected_data: &Arc<dyn std::any::Any + Send + Sync + 'st
 		List<RedGreenBlueAlpha>,
 		List<RelativeAbsolute>,
 		List<SelectiveColorChoice>,
+		List<StructuringElement>,
+		List<ResamplingFilter>,
 		List<XY>,
 		List<ScaleType>,
 		List<ReferencePoint>,
@@ -238,6 +241,8 @@ fn generate_layout(introspected_data: &Arc<dyn std::any::Any + Send + Sync + 'st
 		List<CellularDistanceFunction>,
 		List<CellularReturnType>,
 		List<DomainWarpType>,
+		List<EdgeHandling>,
+		List<EdgeDetectionOperator>,
 		List<RealTimeMode>,
 		List<GridType>,
 		List<ArcType>,
@@ -281,6 +286,8 @@ fn generate_layout(introspected_data: &Arc<dyn std::any::Any + Send + Sync + 'st
 		Item<RedGreenBlueAlpha>,
 		Item<RelativeAbsolute>,
 		Item<SelectiveColorChoice>,
+		Item<StructuringElement>,
+		Item<ResamplingFilter>,
 		Item<XY>,
 		Item<ScaleType>,
 		Item<ReferencePoint>,
@@ -291,6 +298,8 @@ fn generate_layout(introspected_data: &Arc<dyn std::any::Any + Send + Sync + 'st
 		Item<CellularDistanceFunction>,
 		Item<CellularReturnType>,
 		Item<DomainWarpType>,
+		Item<EdgeHandling>,
+		Item<EdgeDetectionOperator>,
 		Item<RealTimeMode>,
 		Item<GridType>,
 		Item<ArcType>,
@@ -993,6 +1002,8 @@ impl_table_item_layout_for_choice_enum!(
 	RedGreenBlueAlpha,
 	RelativeAbsolute,
 	SelectiveColorChoice,
+	StructuringElement,
+	ResamplingFilter,
 	XY,
 	ScaleType,
 	CentroidType,
@@ -1002,6 +1013,8 @@ impl_table_item_layout_for_choice_enum!(
 	CellularDistanceFunction,
 	CellularReturnType,
 	DomainWarpType,
+	EdgeHandling,
+	EdgeDetectionOperator,
 	RealTimeMode,
 	GridType,
 	ArcType,
@@ -1205,6 +1218,8 @@ macro_rules! known_item_types {
 			RedGreenBlueAlpha,
 			RelativeAbsolute,
 			SelectiveColorChoice,
+			StructuringElement,
+			ResamplingFilter,
 			XY,
 			ScaleType,
 			ReferencePoint,
@@ -1215,6 +1230,8 @@ macro_rules! known_item_types {
 			CellularDistanceFunction,
 			CellularReturnType,
 			DomainWarpType,
+			EdgeHandling,
+			EdgeDetectionOperator,
 			RealTimeMode,
 			GridType,
 			ArcType,
@@ -6,7 +6,7 @@ use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::portfolio::document::data_panel::DataPanelMessage;
 use crate::messages::portfolio::document::overlays::utility_types::{OverlayContext, OverlaysType};
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
-use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GridSnapping};
+use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GridSnapping, LayoutGrid};
 use crate::messages::portfolio::utility_types::PanelType;
 use crate::messages::prelude::*;
 use glam::{DAffine2, IVec2};
@@ -30,6 +30,8 @@ pub enum DocumentMessage {
 	#[child]
 	GraphOperation(GraphOperationMessage),
 	#[child]
+	Guide(GuideMessage),
+	#[child]
 	Navigation(NavigationMessage),
 	#[child]
 	NodeGraph(NodeGraphMessage),
@@ -50,6 +52,19 @@ pub enum DocumentMessage {
 	RemoveArtboards,
 	ClearLayersPanel,
 	CreateEmptyFolder,
+	CreateComponent,
+	PlaceComponentInstance,
+	SelectComponentMaster,
+	DetachComponentInstances,
+	DetachComponentInstancesNoTransaction,
+	ResetComponentOverrides,
+	ResetComponentOverridesNoTransaction,
+	ToggleSelectedLibraryExports,
+	ToggleSelectedLibraryExportsNoTransaction,
+	ToggleSelectedLibraryNodesPinned,
+	ToggleSelectedLibraryNodesPinnedNoTransaction,
+	UpdateLibraryNodes,
+	UpdateLibraryNodesNoTransaction,
 	DeleteNode {
 		node_id: NodeId,
 	},
@@ -86,6 +101,14 @@ pub enum DocumentMessage {
 	GridOptions {
 		options: GridSnapping,
 	},
+	LayoutGridOptions {
+		artboard: LayerNodeIdentifier,
+		layout_grid: Option<LayoutGrid>,
+	},
+	LayoutGridOptionsNoTransaction {
+		artboard: LayerNodeIdentifier,
+		layout_grid: Option<LayoutGrid>,
+	},
 	GridOverlays {
 		context: OverlayContext,
 	},
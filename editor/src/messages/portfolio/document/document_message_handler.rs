@@ -2,7 +2,9 @@ use super::DocumentHistory;
 use super::document_diff::diff_networks;
 use super::node_graph::document_node_definitions;
 use super::utility_types::error::EditorError;
-use super::utility_types::misc::{GroupFolderType, SNAP_FUNCTIONS_FOR_BOUNDING_BOXES, SNAP_FUNCTIONS_FOR_PATHS, SnappingOptions, SnappingState};
+use super::utility_types::misc::{GroupFolderType, SNAP_FUNCTIONS_FOR_BOUNDING_BOXES, SNAP_FUNCTIONS_FOR_GUIDES, SNAP_FUNCTIONS_FOR_PATHS, SnappingOptions, SnappingState};
+use super::utility_types::network_interface::components::ComponentLink;
+use super::utility_types::network_interface::library::LibraryExport;
 use super::utility_types::network_interface::{self, NodeNetworkInterface, TransactionStatus};
 use super::utility_types::nodes::{CollapsedLayers, LayerStructureEntry, SelectedNodes};
 use crate::application::{GRAPHITE_GIT_COMMIT_HASH, generate_uuid};
@@ -17,12 +19,13 @@ use crate::messages::portfolio::document::graph_operation::utility_types::{Modif
 use crate::messages::portfolio::document::node_graph::NodeGraphMessageContext;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
 use crate::messages::portfolio::document::node_graph::utility_types::FrontendGraphDataType;
-use crate::messages::portfolio::document::overlays::grid_overlays::{grid_overlay, overlay_options};
+use crate::messages::portfolio::document::overlays::grid_overlays::{grid_overlay, layout_grid_overlay, overlay_options};
 use crate::messages::portfolio::document::overlays::utility_types::{OverlaysType, OverlaysVisibilitySettings, Pivot};
 use crate::messages::portfolio::document::properties_panel::properties_panel_message_handler::PropertiesPanelMessageContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
 use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, PTZ};
 use crate::messages::portfolio::document::utility_types::network_interface::{FlowType, InputConnector, NodeTemplate, OutputConnector};
+use crate::messages::portfolio::node_library::NodeLibraries;
 use crate::messages::portfolio::utility_types::PanelType;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::graph_modification_utils::{self, get_blend_mode, get_fill, get_opacity};
@@ -47,7 +50,7 @@ use graphene_std::vector::misc::dvec2_to_point;
 use graphene_std::vector::style::RenderMode;
 use graphene_std::vector::{PointId, graphic_types};
 use kurbo::{Affine, BezPath, Line, PathSeg};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -65,6 +68,7 @@ pub struct DocumentMessageContext<'a> {
 	pub viewport: &'a ViewportMessageHandler,
 	pub resource_storage: &'a ResourceStorageMessageHandler,
 	pub fonts: &'a FontsMessageHandler,
+	pub node_libraries: &'a NodeLibraries,
 }
 
 #[derive(derivative::Derivative, serde::Serialize, serde::Deserialize, ExtractField)]
@@ -114,6 +118,9 @@ pub struct DocumentMessageHandler {
 	pub overlays_visibility_settings: OverlaysVisibilitySettings,
 	/// Sets whether or not the rulers should be drawn along the top and left edges of the viewport area.
 	pub rulers_visible: bool,
+	/// The guides dragged out of the rulers, and whether they're locked in place.
+	#[serde(default, skip_serializing_if = "GuideMessageHandler::is_empty")]
+	pub guides: GuideMessageHandler,
 	/// The current user choices for snapping behavior, including whether snapping is enabled at all.
 	pub snapping_state: SnappingState,
 	/// Sets whether or not the node graph is drawn (as an overlay) on top of the viewport area, or otherwise if it's hidden.
@@ -137,6 +144,10 @@ pub struct DocumentMessageHandler {
 	/// The path of the to the document file.
 	#[serde(skip)]
 	pub(crate) path: Option<PathBuf>,
+	/// Where the node libraries linked by this document were imported from on this machine, by library ID, so they can be imported again when it's reopened.
+	/// Saved with the per-peer view settings.
+	#[serde(skip)]
+	pub(crate) library_paths: BTreeMap<u64, PathBuf>,
 	/// Path to network currently viewed in the node graph overlay. This will eventually be stored in each panel, so that multiple panels can refer to different networks
 	#[serde(skip)]
 	breadcrumb_network_path: Vec<NodeId>,
@@ -184,6 +195,7 @@ impl Default for DocumentMessageHandler {
 			render_mode: RenderMode::default(),
 			overlays_visibility_settings: OverlaysVisibilitySettings::default(),
 			rulers_visible: true,
+			guides: GuideMessageHandler::default(),
 			graph_view_overlay_open: false,
 			snapping_state: SnappingState::default(),
 			graph_fade_artwork_percentage: 80.,
@@ -194,6 +206,7 @@ impl Default for DocumentMessageHandler {
 			// =============================================
 			name: DEFAULT_DOCUMENT_NAME.to_string(),
 			path: None,
+			library_paths: BTreeMap::new(),
 			breadcrumb_network_path: Vec::new(),
 			selection_network_path: Vec::new(),
 			history: DocumentHistory::default(),
@@ -220,10 +233,21 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			properties_panel_open,
 			resource_storage,
 			fonts,
+			node_libraries,
 		} = context;
 
 		match message {
 			// Sub-messages
+			DocumentMessage::Guide(message) => {
+				let context = GuideMessageContext {
+					network_interface: &self.network_interface,
+					document_to_viewport: self.navigation_handler.calculate_offset_transform(viewport.center_in_viewport_space().into(), &self.document_ptz),
+					viewport,
+					graph_view_overlay_open: self.graph_view_overlay_open,
+				};
+
+				self.guides.process_message(message, responses, context);
+			}
 			DocumentMessage::Navigation(message) => {
 				let context = NavigationMessageContext {
 					network_interface: &mut self.network_interface,
@@ -285,6 +309,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 						preferences,
 						layers_panel_open,
 						viewport,
+						node_libraries,
 					},
 				);
 			}
@@ -384,6 +409,258 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				});
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![id] });
 			}
+			DocumentMessage::CreateComponent => {
+				if !self.selection_network_path.is_empty() {
+					log::error!("Creating a component is only supported for the document network");
+					return;
+				}
+
+				let layers = self.network_interface.shallowest_unique_layers_sorted(&[]);
+				let Some(parent) = layers.first().and_then(|layer| layer.parent(self.metadata())) else { return };
+				if layers
+					.iter()
+					.any(|layer| layer.parent(self.metadata()) != Some(parent) || self.network_interface.is_artboard(&layer.to_node(), &[]))
+				{
+					log::warn!("A component can only be created from sibling layers which aren't artboards");
+					return;
+				}
+
+				let insert_index = DocumentMessageHandler::get_calculated_insert_index(self.metadata(), &self.network_interface.selected_nodes(), parent);
+				let Some(master) = self.network_interface.component_template(&layers, generate_uuid()) else {
+					return;
+				};
+				let master_id = NodeId::new();
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(NodeGraphMessage::AddNodes {
+					nodes: vec![(NodeId(0), master)],
+					new_ids: HashMap::from([(NodeId(0), master_id)]),
+				});
+				responses.add(NodeGraphMessage::MoveLayerToStack {
+					layer: LayerNodeIdentifier::new_unchecked(master_id),
+					parent,
+					insert_index,
+				});
+				responses.add(NodeGraphMessage::DeleteNodes {
+					node_ids: layers.iter().map(|layer| layer.to_node()).collect(),
+					delete_children: true,
+				});
+				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![master_id] });
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+				responses.add(DocumentMessage::DocumentStructureChanged);
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			DocumentMessage::PlaceComponentInstance => {
+				if !self.selection_network_path.is_empty() {
+					log::error!("Placing a component instance is only supported for the document network");
+					return;
+				}
+
+				let layers = self.network_interface.shallowest_unique_layers_sorted(&[]);
+				let components = layers
+					.into_iter()
+					.filter_map(|layer| Some((layer, self.network_interface.component(&layer.to_node(), &[])?.component_id)))
+					.collect::<Vec<_>>();
+				if components.is_empty() {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+
+				let mut new_layers = Vec::new();
+				for (layer, component_id) in components {
+					let Some(parent) = layer.parent(self.metadata()) else { continue };
+					let insert_index = DocumentMessageHandler::get_calculated_insert_index(self.metadata(), &SelectedNodes(vec![layer.to_node()]), parent);
+					let Some(instance) = self.network_interface.component_instance_template(component_id) else {
+						continue;
+					};
+
+					let instance_id = NodeId::new();
+					new_layers.push(instance_id);
+					responses.add(NodeGraphMessage::AddNodes {
+						nodes: vec![(NodeId(0), instance)],
+						new_ids: HashMap::from([(NodeId(0), instance_id)]),
+					});
+					responses.add(NodeGraphMessage::MoveLayerToStack {
+						layer: LayerNodeIdentifier::new_unchecked(instance_id),
+						parent,
+						insert_index,
+					});
+				}
+
+				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: new_layers });
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+				responses.add(DocumentMessage::DocumentStructureChanged);
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			DocumentMessage::SelectComponentMaster => {
+				let Some(component_id) = self
+					.network_interface
+					.shallowest_unique_layers(&[])
+					.find_map(|layer| self.network_interface.component(&layer.to_node(), &[]).map(|component| component.component_id))
+				else {
+					return;
+				};
+
+				// Masters nested inside other networks can't be selected from the document network
+				match self.network_interface.component_master(component_id) {
+					Some((master_path, master_id)) if master_path.is_empty() => responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![master_id] }),
+					Some(_) => log::warn!("The master of this component is inside a nested network"),
+					None => log::warn!("The master of this component no longer exists"),
+				}
+			}
+			DocumentMessage::DetachComponentInstances => {
+				let has_instances = self
+					.network_interface
+					.shallowest_unique_layers(&[])
+					.any(|layer| self.network_interface.is_component_instance(&layer.to_node(), &[]));
+				if !has_instances {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::DetachComponentInstancesNoTransaction);
+			}
+			DocumentMessage::DetachComponentInstancesNoTransaction => {
+				// Mutates the network directly, so it must be queued to run after `AddTransaction` has snapshotted the document
+				let instances = self
+					.network_interface
+					.shallowest_unique_layers(&[])
+					.filter(|layer| self.network_interface.is_component_instance(&layer.to_node(), &[]))
+					.collect::<Vec<_>>();
+				for layer in instances {
+					self.network_interface.set_component(&layer.to_node(), &[], None);
+				}
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			DocumentMessage::ResetComponentOverrides => {
+				let has_instances = self
+					.network_interface
+					.shallowest_unique_layers(&[])
+					.any(|layer| self.network_interface.is_component_instance(&layer.to_node(), &[]));
+				if !has_instances {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::ResetComponentOverridesNoTransaction);
+			}
+			DocumentMessage::ResetComponentOverridesNoTransaction => {
+				// Mutates the network directly, so it must be queued to run after `AddTransaction` has snapshotted the document
+				let instances = self
+					.network_interface
+					.shallowest_unique_layers(&[])
+					.filter_map(|layer| {
+						let component = self.network_interface.component(&layer.to_node(), &[])?;
+						(!component.is_master()).then_some((layer, component.component_id))
+					})
+					.collect::<Vec<_>>();
+				for (layer, component_id) in instances {
+					self.network_interface.set_component(&layer.to_node(), &[], Some(ComponentLink::instance(component_id)));
+				}
+				// Once this message is processed, syncing the instances brings back the master's values for the inputs that are no longer overridden
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			DocumentMessage::ToggleSelectedLibraryExports => {
+				let has_exportable = self
+					.network_interface
+					.selected_nodes()
+					.selected_nodes()
+					.any(|node_id| self.network_interface.can_export_to_library(node_id, &[]));
+				if !has_exportable {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::ToggleSelectedLibraryExportsNoTransaction);
+			}
+			DocumentMessage::ToggleSelectedLibraryExportsNoTransaction => {
+				// Mutates the network directly, so it must be queued to run after `AddTransaction` has snapshotted the document
+				let mut exportable = self
+					.network_interface
+					.selected_nodes()
+					.selected_nodes()
+					.copied()
+					.filter(|node_id| self.network_interface.can_export_to_library(node_id, &[]))
+					.collect::<Vec<_>>();
+				exportable.sort_unstable();
+				exportable.dedup();
+
+				// Export every selected node unless all of them are already exported, in which case they are all withdrawn
+				let export = exportable.iter().any(|node_id| self.network_interface.library_export(node_id, &[]).is_none());
+
+				for node_id in exportable {
+					if self.network_interface.library_export(&node_id, &[]).is_some() == export {
+						continue;
+					}
+					let library_export = export.then(|| LibraryExport {
+						name: self.network_interface.display_name(&node_id, &[]),
+						category: String::new(),
+					});
+					self.network_interface.set_library_export(&node_id, &[], library_export);
+				}
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			DocumentMessage::ToggleSelectedLibraryNodesPinned => {
+				let has_linked = self
+					.network_interface
+					.selected_nodes()
+					.selected_nodes()
+					.any(|node_id| self.network_interface.library_link(node_id, &[]).is_some());
+				if !has_linked {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::ToggleSelectedLibraryNodesPinnedNoTransaction);
+			}
+			DocumentMessage::ToggleSelectedLibraryNodesPinnedNoTransaction => {
+				// Mutates the network directly, so it must be queued to run after `AddTransaction` has snapshotted the document
+				let mut linked = self
+					.network_interface
+					.selected_nodes()
+					.selected_nodes()
+					.copied()
+					.filter(|node_id| self.network_interface.library_link(node_id, &[]).is_some())
+					.collect::<Vec<_>>();
+				linked.sort_unstable();
+				linked.dedup();
+
+				let pinned = linked
+					.iter()
+					.any(|node_id| self.network_interface.library_link(node_id, &[]).is_some_and(|library_link| !library_link.pinned));
+
+				for node_id in linked {
+					self.network_interface.set_library_link_pinned(&node_id, &[], pinned);
+				}
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			DocumentMessage::UpdateLibraryNodes => {
+				if !node_libraries.has_updates(self.network_interface.library_nodes().iter().map(|(_, _, library_link)| library_link)) {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::UpdateLibraryNodesNoTransaction);
+			}
+			DocumentMessage::UpdateLibraryNodesNoTransaction => {
+				// Mutates the network directly, so it must be queued to run after `AddTransaction` has snapshotted the document
+				let updates = self
+					.network_interface
+					.library_nodes()
+					.iter()
+					.filter(|(_, _, library_link)| !library_link.pinned)
+					.filter_map(|(network_path, node_id, library_link)| Some((network_path.clone(), *node_id, node_libraries.update_for(library_link)?.clone())))
+					.collect::<Vec<_>>();
+
+				for (network_path, node_id, template) in updates {
+					self.network_interface.update_library_node(&node_id, &network_path, template);
+				}
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+				responses.add(NodeGraphMessage::SelectedNodesUpdated);
+				responses.add(NodeGraphMessage::SendGraph);
+			}
 			DocumentMessage::DeleteNode { node_id } => {
 				responses.add(DocumentMessage::StartTransaction);
 
@@ -547,6 +824,15 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 			}
 			DocumentMessage::EnterNestedNetwork { node_id } => {
+				if self.network_interface.is_component_instance(&node_id, &self.breadcrumb_network_path) {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Cannot edit a component instance".to_string(),
+						description: "Its contents are a copy of the component master and would overwrite any edit. Use Select Master to edit the master, or Detach Instance to edit it separately."
+							.to_string(),
+					});
+					return;
+				}
+
 				self.breadcrumb_network_path.push(node_id);
 				self.selection_network_path.clone_from(&self.breadcrumb_network_path);
 				responses.add(NodeGraphMessage::UnloadWires);
@@ -694,7 +980,22 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(OverlaysMessage::Draw);
 				responses.add(PortfolioMessage::UpdateDocumentWidgets);
 			}
+			DocumentMessage::LayoutGridOptions { artboard, layout_grid } => {
+				if self.network_interface.layout_grid(artboard) == layout_grid.as_ref() {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::LayoutGridOptionsNoTransaction { artboard, layout_grid });
+			}
+			DocumentMessage::LayoutGridOptionsNoTransaction { artboard, layout_grid } => {
+				// Mutates the network directly, so it must be queued to run after `AddTransaction` has snapshotted the document
+				self.network_interface.set_layout_grid(artboard, layout_grid);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(ArtboardToolMessage::UpdateSelectedArtboard);
+			}
 			DocumentMessage::GridOverlays { context: mut overlay_context } => {
+				layout_grid_overlay(self, &mut overlay_context);
 				if self.snapping_state.grid_snapping {
 					grid_overlay(self, &mut overlay_context)
 				}
@@ -1287,6 +1588,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				};
 				match overlays_type {
 					OverlaysType::ArtboardName => visibility_settings.artboard_name = visible,
+					OverlaysType::Guides => visibility_settings.guides = visible,
 					OverlaysType::CompassRose => visibility_settings.compass_rose = visible,
 					OverlaysType::QuickMeasurement => visibility_settings.quick_measurement = visible,
 					OverlaysType::TransformMeasurement => visibility_settings.transform_measurement = visible,
@@ -1359,6 +1661,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				self.network_interface.finish_transaction();
 				self.history.clear_redo();
 
+				self.record_library_paths(node_libraries);
 				self.commit_storage_snapshot(&resource_storage.resources_mut(), preferences.validate_storage_round_trip);
 
 				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
@@ -1615,6 +1918,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 							snap_angle: Key::Shift,
 							center: Key::Alt,
 							duplicate: Key::Alt,
+							rotate_guide: Key::Control,
 						},
 					});
 					responses.add(NodeGraphMessage::RunDocumentGraph);
@@ -1769,7 +2073,15 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				SelectedLayersRaise,
 				SelectedLayersRaiseToFront,
 				UngroupSelectedLayers,
-				ToggleSelectedLocked
+				ToggleSelectedLocked,
+				CreateComponent,
+				PlaceComponentInstance,
+				SelectComponentMaster,
+				DetachComponentInstances,
+				ResetComponentOverrides,
+				ToggleSelectedLibraryExports,
+				ToggleSelectedLibraryNodesPinned,
+				UpdateLibraryNodes
 			);
 			if !self.graph_view_overlay_open {
 				select.extend(actions!(DocumentMessageDiscriminant;
@@ -1795,6 +2107,14 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 }
 
 impl DocumentMessageHandler {
+	/// Copy the edits made to component masters onto their instances. Called after every message the document processes,
+	/// so however a master was edited, its instances follow within the same transaction.
+	pub fn sync_component_instances(&mut self, responses: &mut VecDeque<Message>) {
+		if self.network_interface.sync_modified_component_instances() {
+			responses.add(NodeGraphMessage::SendGraph);
+		}
+	}
+
 	/// Build a document handler from a `.gdd` working copy.
 	pub fn from_storage(interface: NodeNetworkInterface, storage: document_format::GddV1, name: String, path: Option<std::path::PathBuf>) -> Self {
 		let mut document = Self {
@@ -2019,6 +2339,15 @@ impl DocumentMessageHandler {
 		self.history.retire_storage_interaction();
 	}
 
+	/// Remember where the loaded libraries linked by this document were imported from, so the next snapshot saves their paths.
+	pub fn record_library_paths(&mut self, node_libraries: &NodeLibraries) {
+		for (_, _, library_link) in self.network_interface.library_nodes() {
+			if let Some(library) = node_libraries.library(library_link.library_id) {
+				self.library_paths.insert(library.library_id, library.path.clone());
+			}
+		}
+	}
+
 	/// Stages the runtime network into the `Gdd` working copy.
 	pub fn commit_storage_snapshot(&mut self, byte_store: &dyn graph_craft::application_io::resource::ResourceStorage, validate: bool) {
 		use crate::messages::portfolio::document::utility_types::network_interface::storage_metadata::DocumentSettings;
@@ -2032,8 +2361,10 @@ impl DocumentMessageHandler {
 			render_mode: &self.render_mode,
 			overlays_visibility: &self.overlays_visibility_settings,
 			rulers_visible: self.rulers_visible,
+			guides: &self.guides,
 			snapping_state: &self.snapping_state,
 			collapsed: &self.collapsed,
+			library_paths: &self.library_paths,
 		}
 		.to_view_map();
 
@@ -2067,12 +2398,18 @@ impl DocumentMessageHandler {
 		if let Some(value) = decode(view_settings, doc::RULERS_VISIBLE) {
 			self.rulers_visible = value;
 		}
+		if let Some(value) = decode(view_settings, doc::GUIDES) {
+			self.guides = value;
+		}
 		if let Some(value) = decode(view_settings, doc::SNAPPING) {
 			self.snapping_state = value;
 		}
 		if let Some(value) = decode(view_settings, doc::COLLAPSED) {
 			self.collapsed = value;
 		}
+		if let Some(value) = decode(view_settings, doc::LIBRARY_PATHS) {
+			self.library_paths = value;
+		}
 	}
 
 	/// Move the `Gdd` undo/redo cursor and spawn the async future that rebuilds the
@@ -2981,6 +3318,7 @@ impl DocumentMessageHandler {
 	pub fn update_document_widgets(&self, responses: &mut VecDeque<Message>, animation_is_playing: bool, time: Duration) {
 		let mut snapping_state = self.snapping_state.clone();
 		let mut snapping_state2 = self.snapping_state.clone();
+		let mut snapping_state3 = self.snapping_state.clone();
 
 		let mut widgets = vec![
 			IconButton::new("PlaybackToStart", 24)
@@ -3027,6 +3365,22 @@ impl DocumentMessageHandler {
 							TextLabel::new("Artboard Name".to_string()).for_checkbox(checkbox_id).widget_instance(),
 						]
 					}),
+					LayoutGroup::row({
+						let checkbox_id = CheckboxId::new();
+						vec![
+							CheckboxInput::new(self.overlays_visibility_settings.guides)
+								.on_update(|optional_input: &CheckboxInput| {
+									DocumentMessage::SetOverlaysVisibility {
+										visible: optional_input.checked,
+										overlays_type: Some(OverlaysType::Guides),
+									}
+									.into()
+								})
+								.for_label(checkbox_id)
+								.widget_instance(),
+							TextLabel::new("Guides".to_string()).for_checkbox(checkbox_id).widget_instance(),
+						]
+					}),
 					LayoutGroup::row({
 						let checkbox_id = CheckboxId::new();
 						vec![
@@ -3288,6 +3642,27 @@ impl DocumentMessageHandler {
 							]
 						})
 					}))
+					.chain([LayoutGroup::row(vec![TextLabel::new(SnappingOptions::Guides.to_string()).widget_instance()])])
+					.chain(SNAP_FUNCTIONS_FOR_GUIDES.into_iter().map(|(name, closure, description)| {
+						LayoutGroup::row({
+							let checkbox_id = CheckboxId::new();
+							vec![
+								CheckboxInput::new(*closure(&mut snapping_state3))
+									.on_update(move |input: &CheckboxInput| {
+										DocumentMessage::SetSnapping {
+											closure: Some(closure),
+											snapping_state: input.checked,
+										}
+										.into()
+									})
+									.tooltip_label(name)
+									.tooltip_description(description)
+									.for_label(checkbox_id)
+									.widget_instance(),
+								TextLabel::new(name).tooltip_label(name).tooltip_description(description).for_checkbox(checkbox_id).widget_instance(),
+							]
+						})
+					}))
 					.collect(),
 				))
 				.widget_instance(),
@@ -4349,4 +4724,116 @@ mod document_message_handler_tests {
 			.count();
 		assert_eq!(phantom_count, 0, "No stacked element should be a phantom None graphic");
 	}
+
+	/// Draw a rectangle, turn it into a component and place an instance of it, returning the master and instance node IDs
+	async fn master_and_instance(editor: &mut EditorTestUtils) -> (NodeId, NodeId) {
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+		editor.handle_message(DocumentMessage::CreateComponent).await;
+		editor.handle_message(DocumentMessage::PlaceComponentInstance).await;
+
+		let components = editor.active_document().network_interface.component_nodes();
+		let find = |master: bool| {
+			components
+				.iter()
+				.find(|(network_path, _, component)| network_path.is_empty() && component.is_master() == master)
+				.map(|(_, node_id, _)| *node_id)
+		};
+		(find(true).expect("The master should exist"), find(false).expect("The instance should exist"))
+	}
+
+	/// The number input with the lowest node ID inside the component's nested network, which is where editing the master makes a visible difference
+	fn number_input(document: &DocumentMessageHandler, component: NodeId) -> (NodeId, usize, f64) {
+		let network = document.network_interface.nested_network(&[component]).expect("The component should have a nested network");
+		network
+			.nodes
+			.iter()
+			.flat_map(|(node_id, node)| {
+				node.inputs.iter().enumerate().filter_map(|(index, input)| match input.as_value() {
+					Some(TaggedValue::F64(value)) => Some((*node_id, index, *value)),
+					_ => None,
+				})
+			})
+			.min_by_key(|(node_id, index, _)| (*node_id, *index))
+			.expect("The component should contain a number input")
+	}
+
+	async fn edit_master(editor: &mut EditorTestUtils, master: NodeId) -> f64 {
+		let (node_id, input_index, value) = number_input(editor.active_document(), master);
+		let new_value = value + 42.;
+
+		editor.handle_message(DocumentMessage::EnterNestedNetwork { node_id: master }).await;
+		editor
+			.handle_message(NodeGraphMessage::SetInputValue {
+				node_id,
+				input_index,
+				value: TaggedValue::F64(new_value),
+			})
+			.await;
+		editor.handle_message(DocumentMessage::ExitNestedNetwork { steps_back: 1 }).await;
+
+		new_value
+	}
+
+	#[tokio::test]
+	async fn editing_a_component_master_updates_its_instances() {
+		let mut editor = EditorTestUtils::create();
+		let (master, instance) = master_and_instance(&mut editor).await;
+
+		let new_value = edit_master(&mut editor, master).await;
+
+		let (_, _, instance_value) = number_input(editor.active_document(), instance);
+		assert_eq!(instance_value, new_value);
+	}
+
+	#[tokio::test]
+	async fn overridden_instance_inputs_survive_editing_the_master() {
+		let mut editor = EditorTestUtils::create();
+		let (master, instance) = master_and_instance(&mut editor).await;
+
+		let content = InputConnector::node(instance, 1);
+		editor
+			.handle_message(NodeGraphMessage::ExposeInput {
+				input_connector: content,
+				set_to_exposed: false,
+				start_transaction: true,
+			})
+			.await;
+
+		let new_value = edit_master(&mut editor, master).await;
+
+		let document = editor.active_document();
+		assert_eq!(number_input(document, instance).2, new_value);
+		let Some(NodeInput::Value { exposed, .. }) = document.network_interface.input_from_connector(&content, &[]) else {
+			panic!("The instance's content input should be a value");
+		};
+		assert!(!exposed, "The overridden input should keep the instance's value");
+	}
+
+	#[tokio::test]
+	async fn detached_instances_stop_following_the_master() {
+		let mut editor = EditorTestUtils::create();
+		let (master, instance) = master_and_instance(&mut editor).await;
+		let (_, _, original_value) = number_input(editor.active_document(), instance);
+
+		// Placing the instance left it selected
+		editor.handle_message(DocumentMessage::DetachComponentInstances).await;
+		assert!(editor.active_document().network_interface.component(&instance, &[]).is_none());
+
+		edit_master(&mut editor, master).await;
+
+		let (_, _, instance_value) = number_input(editor.active_document(), instance);
+		assert_eq!(instance_value, original_value);
+	}
+
+	#[tokio::test]
+	async fn undoing_a_detach_relinks_the_instance() {
+		let mut editor = EditorTestUtils::create();
+		let (_, instance) = master_and_instance(&mut editor).await;
+
+		editor.handle_message(DocumentMessage::DetachComponentInstances).await;
+		editor.handle_message(DocumentMessage::Undo).await;
+
+		assert!(editor.active_document().network_interface.is_component_instance(&instance, &[]));
+	}
 }
@@ -10,7 +10,7 @@ use graphene_std::raster::BlendMode;
 use graphene_std::raster_types::Image;
 use graphene_std::subpath::Subpath;
 use graphene_std::text::{Font, TypesettingConfig};
-use graphene_std::vector::style::{GradientSpreadMethod, GradientType, Stroke};
+use graphene_std::vector::style::{GradientMeshEdits, GradientSpreadMethod, GradientType, Stroke};
 use graphene_std::vector::{Gradient, PointId, VectorModificationType};
 
 #[impl_message(Message, DocumentMessage, GraphOperation)]
@@ -47,6 +47,10 @@ pub enum GraphOperationMessage {
 		layer: LayerNodeIdentifier,
 		spread_method: GradientSpreadMethod,
 	},
+	GradientMeshEditsSet {
+		layer: LayerNodeIdentifier,
+		edits: GradientMeshEdits,
+	},
 	OpacitySet {
 		layer: LayerNodeIdentifier,
 		opacity: f64,
@@ -15,7 +15,7 @@ use graphene_std::raster::BlendMode;
 use graphene_std::raster_types::Image;
 use graphene_std::subpath::Subpath;
 use graphene_std::text::{Font, TypesettingConfig};
-use graphene_std::vector::style::{GradientSpreadMethod, GradientType, Stroke};
+use graphene_std::vector::style::{GradientMeshEdits, GradientSpreadMethod, GradientType, Stroke};
 use graphene_std::vector::{Gradient, PointId, Vector, VectorModification, VectorModificationType};
 use graphene_std::{Artboard, Color, Graphic, NodeInputDecleration};
 
@@ -710,6 +710,21 @@ impl<'a> ModifyInputsContext<'a> {
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::GradientSpreadMethod(spread_method), false), false);
 	}
 
+	/// Write the vertex edits to the last 'Mesh Gradient' node in the chain, inserting one (with its default 2x2 grid) only when there are edits to store.
+	pub fn gradient_mesh_edits_set(&mut self, edits: GradientMeshEdits) {
+		let Some(output_layer) = self.get_output_layer() else { return };
+
+		let target_input = gradient_chain_target_input(output_layer, self.network_interface);
+		let identifier = graphene_std::math_nodes::mesh_gradient::IDENTIFIER;
+		let create_if_nonexistent = !edits.is_empty();
+		let Some(node_id) = self.existing_proto_node_id_at(&target_input, identifier, create_if_nonexistent) else {
+			return;
+		};
+
+		let input_connector = InputConnector::node(node_id, graphene_std::math_nodes::mesh_gradient::VertexEditsInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::GradientMeshEdits(edits), false), false);
+	}
+
 	pub fn clip_mode_toggle(&mut self, clip_mode: Option<bool>) {
 		let clip = !clip_mode.unwrap_or(false);
 		let Some(clip_node_id) = self.existing_proto_node_id(graphene_std::blending_nodes::clipping_mask::IDENTIFIER, true) else {
@@ -742,6 +757,8 @@ impl<'a> ModifyInputsContext<'a> {
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::DashPattern(stroke.dash_lengths.into()), false), true);
 		let input_connector = InputConnector::node(stroke_node_id, graphene_std::vector::stroke::DashOffsetInput::INDEX);
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::F64(stroke.dash_offset), false), true);
+		let input_connector = InputConnector::node(stroke_node_id, graphene_std::vector::stroke::WidthProfileInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::WidthProfile(stroke.width_profile), false), true);
 	}
 
 	/// Update the transform value of the upstream Transform node based a change to its existing value and the given parent transform.
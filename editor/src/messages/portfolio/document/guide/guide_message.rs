@@ -0,0 +1,17 @@
+use super::utility_types::GuideId;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::prelude::*;
+use glam::DVec2;
+
+#[impl_message(Message, DocumentMessage, Guide)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum GuideMessage {
+	ClearAll,
+	DragAbort,
+	DragStart { id: GuideId, position: DVec2 },
+	DragStop { position: DVec2 },
+	Overlays { context: OverlayContext },
+	PointerMove { position: DVec2, rotate: bool, snap_angle: bool },
+	RulerDragStart { horizontal: bool, position: DVec2 },
+	ToggleLock,
+}
@@ -0,0 +1,212 @@
+use super::utility_types::{Guide, GuideId, GuideLine, artboard_bounds};
+use crate::consts::{COLOR_OVERLAY_BLUE, COLOR_OVERLAY_CYAN, GUIDE_GRAB_DISTANCE, GUIDE_ROTATE_SNAP_ANGLE};
+use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
+use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
+use crate::messages::prelude::*;
+use glam::{DAffine2, DVec2};
+
+#[derive(ExtractField)]
+pub struct GuideMessageContext<'a> {
+	pub network_interface: &'a NodeNetworkInterface,
+	pub document_to_viewport: DAffine2,
+	pub viewport: &'a ViewportMessageHandler,
+	pub graph_view_overlay_open: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize, ExtractField)]
+#[serde(default)]
+pub struct GuideMessageHandler {
+	pub guides: Vec<Guide>,
+	/// Keeps the guides from being dragged, so they can't be moved or removed by accident, and from being snapped to.
+	pub locked: bool,
+	#[serde(skip)]
+	drag: Option<GuideDrag>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GuideDrag {
+	/// The guide being dragged, which is kept out of the list of guides until it's dropped.
+	guide: Guide,
+	/// The guide as it was before it was picked up, which is put back if the drag is aborted. This is `None` for a guide being dragged out of a ruler.
+	original: Option<Guide>,
+	/// Where the pointer was when the guide was last moved (rather than rotated), in document space.
+	start: DVec2,
+	/// The point on the guide under `start`, which it rotates around.
+	pivot: DVec2,
+}
+
+#[message_handler_data]
+impl MessageHandler<GuideMessage, GuideMessageContext<'_>> for GuideMessageHandler {
+	fn process_message(&mut self, message: GuideMessage, responses: &mut VecDeque<Message>, context: GuideMessageContext) {
+		let GuideMessageContext {
+			network_interface,
+			document_to_viewport,
+			viewport,
+			graph_view_overlay_open,
+		} = context;
+		let metadata = network_interface.document_metadata();
+		let viewport_to_document = document_to_viewport.inverse();
+
+		match message {
+			GuideMessage::ClearAll => {
+				self.guides.clear();
+				self.drag = None;
+
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			GuideMessage::DragAbort => {
+				if let Some(drag) = self.drag.take() {
+					self.guides.extend(drag.original);
+					responses.add(OverlaysMessage::Draw);
+				}
+			}
+			GuideMessage::DragStart { id, position } => {
+				if self.locked {
+					return;
+				}
+				let Some(index) = self.guides.iter().position(|guide| guide.id == id) else { return };
+				let Some(line) = self.guides[index].line(metadata) else { return };
+
+				let guide = self.guides.remove(index);
+				let start = viewport_to_document.transform_point2(position);
+				self.drag = Some(GuideDrag {
+					original: Some(guide.clone()),
+					guide,
+					start,
+					pivot: line.project(start),
+				});
+			}
+			GuideMessage::DragStop { position } => {
+				let Some(drag) = self.drag.take() else { return };
+
+				// Dropping a guide back onto a ruler, outside the viewport, removes it
+				let viewport_size: DVec2 = viewport.size().into();
+				let dropped_on_ruler = position.cmplt(DVec2::ZERO).any() || position.cmpgt(viewport_size).any();
+				if !dropped_on_ruler {
+					self.guides.push(drag.guide);
+				}
+
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			GuideMessage::Overlays { context: mut overlay_context } => {
+				let viewport_size: DVec2 = overlay_context.viewport.size().into();
+
+				if overlay_context.visibility_settings.guides() {
+					for line in self.lines(metadata) {
+						if let Some([start, end]) = line.viewport_segment(document_to_viewport, viewport_size) {
+							overlay_context.line(start, end, Some(COLOR_OVERLAY_CYAN), None);
+						}
+					}
+				}
+
+				if let Some(drag) = &self.drag
+					&& let Some([start, end]) = drag.guide.line(metadata).and_then(|line| line.viewport_segment(document_to_viewport, viewport_size))
+				{
+					overlay_context.line(start, end, Some(COLOR_OVERLAY_BLUE), None);
+				}
+			}
+			GuideMessage::PointerMove { position, rotate, snap_angle } => {
+				let Some(drag) = &mut self.drag else { return };
+				let pointer = viewport_to_document.transform_point2(position);
+
+				if rotate {
+					// Swing the guide around the point it was grabbed by, once the pointer is far enough away to give a clear direction
+					if position.distance(document_to_viewport.transform_point2(drag.pivot)) > GUIDE_GRAB_DISTANCE {
+						let mut angle = (pointer - drag.pivot).to_angle();
+						if snap_angle {
+							let snap_resolution = GUIDE_ROTATE_SNAP_ANGLE.to_radians();
+							angle = (angle / snap_resolution).round() * snap_resolution;
+						}
+						drag.guide.angle = angle;
+					}
+				} else {
+					drag.pivot += pointer - drag.start;
+				}
+				drag.start = pointer;
+
+				// The guide belongs to whichever artboard it's dropped onto
+				let artboard = artboard_at(network_interface, pointer);
+				drag.guide.place(metadata, drag.pivot, artboard);
+
+				responses.add(OverlaysMessage::Draw);
+			}
+			GuideMessage::RulerDragStart { horizontal, position } => {
+				if graph_view_overlay_open {
+					return;
+				}
+
+				// The new guide runs parallel to the ruler it's dragged out of, however the canvas is tilted
+				let direction = viewport_to_document.transform_vector2(if horizontal { DVec2::X } else { DVec2::Y });
+				let start = viewport_to_document.transform_point2(position);
+				self.drag = Some(GuideDrag {
+					guide: Guide {
+						id: GuideId::new(),
+						position: start,
+						angle: direction.to_angle(),
+						artboard: None,
+					},
+					original: None,
+					start,
+					pivot: start,
+				});
+			}
+			GuideMessage::ToggleLock => {
+				self.locked = !self.locked;
+
+				responses.add(MenuBarMessage::SendLayout);
+			}
+		}
+	}
+
+	advertise_actions!(GuideMessageDiscriminant;);
+}
+
+impl GuideMessageHandler {
+	pub fn is_empty(&self) -> bool {
+		self.guides.is_empty() && !self.locked
+	}
+
+	/// The placed guides in document space, skipping those whose artboard no longer exists.
+	pub fn lines<'a>(&'a self, metadata: &'a DocumentMetadata) -> impl Iterator<Item = GuideLine> + 'a {
+		self.guides.iter().filter_map(|guide| guide.line(metadata))
+	}
+
+	/// Finds the guide that can be grabbed at the given viewport position, along with its visible extent in viewport space.
+	pub fn grabbable_guide(&self, metadata: &DocumentMetadata, viewport_size: DVec2, position: DVec2) -> Option<(GuideId, [DVec2; 2])> {
+		if self.locked {
+			return None;
+		}
+
+		self.guides
+			.iter()
+			.filter_map(|guide| {
+				let segment = guide.line(metadata)?.viewport_segment(metadata.document_to_viewport, viewport_size)?;
+				Some((guide.id, segment, distance_to_segment(position, segment)))
+			})
+			.filter(|&(_, _, distance)| distance <= GUIDE_GRAB_DISTANCE)
+			.min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+			.map(|(id, segment, _)| (id, segment))
+	}
+}
+
+/// The topmost artboard containing the given point in document space.
+fn artboard_at(network_interface: &NodeNetworkInterface, point: DVec2) -> Option<LayerNodeIdentifier> {
+	let metadata = network_interface.document_metadata();
+
+	metadata
+		.all_layers()
+		.filter(|layer| network_interface.is_artboard(&layer.to_node(), &[]))
+		.find(|&layer| artboard_bounds(metadata, layer).is_some_and(|[min, max]| point.cmpge(min).all() && point.cmple(max).all()))
+}
+
+fn distance_to_segment(point: DVec2, [start, end]: [DVec2; 2]) -> f64 {
+	let length_squared = start.distance_squared(end);
+	if length_squared == 0. {
+		return point.distance(start);
+	}
+
+	let t = ((point - start).dot(end - start) / length_squared).clamp(0., 1.);
+	point.distance(start.lerp(end, t))
+}
@@ -0,0 +1,158 @@
+use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
+use glam::{DAffine2, DVec2};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GuideId(pub u64);
+
+impl GuideId {
+	pub fn new() -> Self {
+		Self(graphene_std::uuid::generate_uuid())
+	}
+}
+impl Default for GuideId {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A straight line across the canvas, dragged out of a ruler, which artwork can be lined up and snapped against.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Guide {
+	pub id: GuideId,
+	/// A point the guide passes through, in document space, or relative to the top left corner of its artboard if it belongs to one.
+	pub position: DVec2,
+	/// The direction the guide runs in, as an angle in radians from the document's X axis.
+	pub angle: f64,
+	/// The artboard the guide belongs to, which confines the guide to the artboard's bounds and carries it along as the artboard moves.
+	pub artboard: Option<LayerNodeIdentifier>,
+}
+
+impl Guide {
+	/// Places the guide in document space, or returns `None` if the artboard it belongs to no longer exists.
+	pub fn line(&self, metadata: &DocumentMetadata) -> Option<GuideLine> {
+		let direction = DVec2::from_angle(self.angle);
+
+		let Some(artboard) = self.artboard else {
+			return Some(GuideLine {
+				point: self.position,
+				direction,
+				bounds: None,
+			});
+		};
+
+		let [min, max] = artboard_bounds(metadata, artboard)?;
+		Some(GuideLine {
+			point: min + self.position,
+			direction,
+			bounds: Some([min, max]),
+		})
+	}
+
+	/// Moves the guide so it passes through the given document space point, belonging to the given artboard (if any).
+	pub fn place(&mut self, metadata: &DocumentMetadata, point: DVec2, artboard: Option<LayerNodeIdentifier>) {
+		let origin = artboard.and_then(|artboard| artboard_bounds(metadata, artboard)).map(|[min, _]| min);
+
+		self.artboard = origin.and(artboard);
+		self.position = point - origin.unwrap_or_default();
+	}
+}
+
+/// The bounds of an artboard in document space, ordered from the minimum to the maximum corner.
+pub fn artboard_bounds(metadata: &DocumentMetadata, artboard: LayerNodeIdentifier) -> Option<[DVec2; 2]> {
+	let [a, b] = metadata.bounding_box_document(artboard)?;
+	Some([a.min(b), a.max(b)])
+}
+
+/// A guide resolved into document space, ready to be drawn or snapped to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GuideLine {
+	pub point: DVec2,
+	/// The unit vector the line runs along.
+	pub direction: DVec2,
+	/// The bounds of the artboard the line is confined to, or `None` if it extends infinitely.
+	pub bounds: Option<[DVec2; 2]>,
+}
+
+impl GuideLine {
+	pub fn project(&self, point: DVec2) -> DVec2 {
+		self.point + (point - self.point).project_onto_normalized(self.direction)
+	}
+
+	/// Whether a point lies within the bounds of the line, allowing for the given tolerance.
+	pub fn contains(&self, point: DVec2, tolerance: f64) -> bool {
+		self.bounds.is_none_or(|[min, max]| point.cmpge(min - tolerance).all() && point.cmple(max + tolerance).all())
+	}
+
+	/// The part of the line which is visible in the viewport, as a start and end point in viewport space.
+	pub fn viewport_segment(&self, document_to_viewport: DAffine2, viewport_size: DVec2) -> Option<[DVec2; 2]> {
+		match self.bounds {
+			Some(bounds) => clip_line_to_box(self.point, self.direction, bounds).map(|segment| segment.map(|point| document_to_viewport.transform_point2(point))),
+			None => {
+				let point = document_to_viewport.transform_point2(self.point);
+				let direction = document_to_viewport.transform_vector2(self.direction);
+				clip_line_to_box(point, direction, [DVec2::ZERO, viewport_size])
+			}
+		}
+	}
+}
+
+/// Clips the infinite line through `point` along `direction` to the box spanning `min` to `max`, returning the start and end points of the part inside.
+pub fn clip_line_to_box(point: DVec2, direction: DVec2, [min, max]: [DVec2; 2]) -> Option<[DVec2; 2]> {
+	let mut entry = f64::NEG_INFINITY;
+	let mut exit = f64::INFINITY;
+
+	for axis in 0..2 {
+		if direction[axis].abs() < f64::EPSILON {
+			// Parallel to this pair of box edges, so the line is either between them everywhere or nowhere
+			if point[axis] < min[axis] || point[axis] > max[axis] {
+				return None;
+			}
+			continue;
+		}
+
+		let a = (min[axis] - point[axis]) / direction[axis];
+		let b = (max[axis] - point[axis]) / direction[axis];
+		entry = entry.max(a.min(b));
+		exit = exit.min(a.max(b));
+	}
+
+	(entry <= exit && entry.is_finite() && exit.is_finite()).then(|| [point + direction * entry, point + direction * exit])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clip_axis_aligned_line() {
+		let [start, end] = clip_line_to_box(DVec2::new(5., 3.), DVec2::X, [DVec2::ZERO, DVec2::splat(10.)]).unwrap();
+		assert_eq!(start, DVec2::new(0., 3.));
+		assert_eq!(end, DVec2::new(10., 3.));
+
+		assert!(clip_line_to_box(DVec2::new(5., 12.), DVec2::X, [DVec2::ZERO, DVec2::splat(10.)]).is_none());
+	}
+
+	#[test]
+	fn clip_diagonal_line() {
+		let [start, end] = clip_line_to_box(DVec2::ZERO, DVec2::ONE.normalize(), [DVec2::splat(2.), DVec2::splat(4.)]).unwrap();
+		assert!(start.abs_diff_eq(DVec2::splat(2.), 1e-9));
+		assert!(end.abs_diff_eq(DVec2::splat(4.), 1e-9));
+
+		assert!(clip_line_to_box(DVec2::new(0., 10.), DVec2::ONE.normalize(), [DVec2::splat(2.), DVec2::splat(4.)]).is_none());
+	}
+
+	#[test]
+	fn clip_to_artboard_bounds() {
+		let line = GuideLine {
+			point: DVec2::new(50., 20.),
+			direction: DVec2::Y,
+			bounds: Some([DVec2::new(0., 0.), DVec2::new(100., 80.)]),
+		};
+		let [start, end] = line.viewport_segment(DAffine2::from_scale(DVec2::splat(2.)), DVec2::splat(500.)).unwrap();
+		assert_eq!(start, DVec2::new(100., 0.));
+		assert_eq!(end, DVec2::new(100., 160.));
+
+		assert!(line.contains(DVec2::new(50., 82.), 5.));
+		assert!(!line.contains(DVec2::new(50., 90.), 5.));
+	}
+}
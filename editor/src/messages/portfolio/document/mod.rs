@@ -7,6 +7,7 @@ mod storage_tests;
 
 pub mod data_panel;
 pub mod graph_operation;
+pub mod guide;
 pub mod navigation;
 pub mod node_graph;
 pub mod overlays;
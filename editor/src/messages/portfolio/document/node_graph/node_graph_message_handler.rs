@@ -15,6 +15,7 @@ use crate::messages::portfolio::document::utility_types::misc::GroupFolderType;
 use crate::messages::portfolio::document::utility_types::network_interface::{self, FlowType, InputConnector, NodeNetworkInterface, NodeTypePersistentMetadata, OutputConnector, Previewing};
 use crate::messages::portfolio::document::utility_types::nodes::{CollapsedLayers, LayerPanelEntry};
 use crate::messages::portfolio::document::utility_types::wires::{GraphWireStyle, WirePath, WirePathUpdate, build_vector_wire};
+use crate::messages::portfolio::node_library::NodeLibraries;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::auto_panning::AutoPanning;
 use crate::messages::tool::common_functionality::graph_modification_utils::get_clip_mode;
@@ -47,6 +48,7 @@ pub struct NodeGraphMessageContext<'a> {
 	pub preferences: &'a PreferencesMessageHandler,
 	pub layers_panel_open: bool,
 	pub viewport: &'a ViewportMessageHandler,
+	pub node_libraries: &'a NodeLibraries,
 }
 
 #[derive(Debug, Clone, ExtractField)]
@@ -119,6 +121,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			preferences,
 			layers_panel_open,
 			viewport,
+			node_libraries,
 		} = context;
 
 		match message {
@@ -287,15 +290,17 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 
 				let node_id = node_id.unwrap_or_else(NodeId::new);
 
-				let Some(document_node_type) = resolve_document_node_type(&node_type) else {
+				// Nodes from imported libraries are placed as copies of the library's template
+				let Some(mut node_template) = resolve_document_node_type(&node_type)
+					.map(|document_node_type| document_node_type.default_node_template())
+					.or_else(|| node_libraries.node_template(&node_type))
+				else {
 					responses.add(DialogMessage::DisplayDialogError {
 						title: "Cannot insert node".to_string(),
 						description: format!("The document node '{node_type:?}' does not exist in the document node list"),
 					});
 					return;
 				};
-
-				let mut node_template = document_node_type.default_node_template();
 				self.context_menu = None;
 
 				// A freshly added Text node carries no font, so give it the default font (registered like the Text tool does)
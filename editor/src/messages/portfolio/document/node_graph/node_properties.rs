@@ -24,8 +24,8 @@ use graphene_std::color::SRGBA8;
 use graphene_std::extract_xy::XY;
 use graphene_std::list::List;
 use graphene_std::raster::{
-	BlendMode, CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute,
-	SelectiveColorChoice,
+	BlendMode, CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, EdgeDetectionOperator, EdgeHandling, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha,
+	RelativeAbsolute, ResamplingFilter, SelectiveColorChoice, StructuringElement,
 };
 use graphene_std::raster_types::Image;
 use graphene_std::text::{Font, TextAlign};
@@ -36,7 +36,8 @@ use graphene_std::vector::misc::{
 	ArcType, BoxCorners, CentroidType, ExtrudeJoiningAlgorithm, GridType, InterpolationDistribution, MergeByDistanceAlgorithm, PointSpacingType, RowsOrColumns, SpiralType,
 };
 use graphene_std::vector::style::{
-	DashPattern, FillChoiceUI, Gradient, GradientSpreadMethod, GradientType, GradientUI, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, build_transform_with_y_preservation,
+	DashPattern, FillChoiceUI, Gradient, GradientMeshEdits, GradientSpreadMethod, GradientType, GradientUI, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile, WidthProfilePreset,
+	build_transform_with_y_preservation,
 };
 use graphene_std::vector::{QRCodeErrorCorrectionLevel, VectorModification};
 
@@ -280,6 +281,7 @@ pub(crate) fn property_from_type(
 						Some(x) if id_is::<Font>(x) => font_widget(default_info),
 						Some(x) if id_is::<Footprint>(x) => footprint_widget(default_info, &mut extra_widgets),
 						Some(x) if id_is::<Box<VectorModification>>(x) => vector_modification_widget(default_info).into(),
+						Some(x) if id_is::<GradientMeshEdits>(x) => gradient_mesh_edits_widget(default_info).into(),
 						Some(x) if id_is::<Image<Color>>(x) => image_data_widget(default_info).into(),
 						// ===============================
 						// MANUALLY IMPLEMENTED ENUM TYPES
@@ -301,6 +303,10 @@ pub(crate) fn property_from_type(
 						Some(x) if id_is::<CellularDistanceFunction>(x) => enum_choice::<CellularDistanceFunction>().for_socket(default_info).disabled(false).property_row(),
 						Some(x) if id_is::<CellularReturnType>(x) => enum_choice::<CellularReturnType>().for_socket(default_info).disabled(false).property_row(),
 						Some(x) if id_is::<DomainWarpType>(x) => enum_choice::<DomainWarpType>().for_socket(default_info).disabled(false).property_row(),
+						Some(x) if id_is::<EdgeHandling>(x) => enum_choice::<EdgeHandling>().for_socket(default_info).property_row(),
+						Some(x) if id_is::<EdgeDetectionOperator>(x) => enum_choice::<EdgeDetectionOperator>().for_socket(default_info).property_row(),
+						Some(x) if id_is::<StructuringElement>(x) => enum_choice::<StructuringElement>().for_socket(default_info).property_row(),
+						Some(x) if id_is::<ResamplingFilter>(x) => enum_choice::<ResamplingFilter>().for_socket(default_info).property_row(),
 						Some(x) if id_is::<RelativeAbsolute>(x) => enum_choice::<RelativeAbsolute>().for_socket(default_info).disabled(false).property_row(),
 						Some(x) if id_is::<GridType>(x) => enum_choice::<GridType>().for_socket(default_info).property_row(),
 						Some(x) if id_is::<StrokeCap>(x) => enum_choice::<StrokeCap>().for_socket(default_info).property_row(),
@@ -461,6 +467,35 @@ pub fn vector_modification_widget(parameter_widgets_info: ParameterWidgetsInfo)
 	widgets
 }
 
+pub fn gradient_mesh_edits_widget(parameter_widgets_info: ParameterWidgetsInfo) -> Vec<WidgetInstance> {
+	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+
+	let mut widgets = start_widgets(parameter_widgets_info);
+
+	let Some(document_node) = document_node else { return widgets };
+	let Some(input) = document_node.inputs.get(index) else { return widgets };
+
+	if let Some(TaggedValue::GradientMeshEdits(edits)) = input.as_non_exposed_value() {
+		let label = format!("{} Moved, {} Recolored", edits.positions.len(), edits.colors.len());
+
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			TextLabel::new(label)
+				.tooltip_label("Vertex Edits")
+				.tooltip_description("The number of mesh vertices moved or recolored by hand with the Gradient tool.")
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			TextButton::new("Reset")
+				.tooltip_description("Return the moved and recolored vertices to where the grid places them and the colors it gives them.")
+				.disabled(edits.is_empty())
+				.on_update(update_value(|_| TaggedValue::GradientMeshEdits(GradientMeshEdits::default()), node_id, index))
+				.widget_instance(),
+		]);
+	}
+
+	widgets
+}
+
 pub fn brush_strokes_widget(parameter_widgets_info: ParameterWidgetsInfo) -> Vec<WidgetInstance> {
 	let ParameterWidgetsInfo { document_node, node_id: _, index, .. } = parameter_widgets_info;
 
@@ -892,6 +927,42 @@ pub fn dash_pattern_widget(parameter_widgets_info: ParameterWidgetsInfo, text_in
 	widgets
 }
 
+pub fn width_profile_widget(parameter_widgets_info: ParameterWidgetsInfo) -> LayoutGroup {
+	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+
+	let mut widgets = start_widgets(parameter_widgets_info);
+	let Some(document_node) = document_node else { return LayoutGroup::default() };
+	let Some(input) = document_node.inputs.get(index) else {
+		log::warn!("A widget failed to be built because its node's input index is invalid.");
+		return LayoutGroup::row(vec![]);
+	};
+	if let Some(TaggedValue::WidthProfile(width_profile)) = input.as_non_exposed_value() {
+		let presets = WidthProfilePreset::ALL
+			.into_iter()
+			.map(|preset| {
+				MenuListEntry::new(format!("{preset:?}"))
+					.label(preset.label())
+					.on_update(update_value(move |_| TaggedValue::WidthProfile(WidthProfile::from_preset(preset)), node_id, index))
+					.on_commit(commit_value)
+			})
+			.collect();
+
+		// A profile recorded by drawing matches none of the presets, so it's shown as a custom entry which can't be picked again once replaced
+		let selected_preset = width_profile.preset().and_then(|preset| WidthProfilePreset::ALL.iter().position(|&other| other == preset));
+		let mut entries = vec![presets];
+		if selected_preset.is_none() {
+			entries.push(vec![MenuListEntry::new("Custom").label("Custom").disabled(true)]);
+		}
+		let selected_index = selected_preset.unwrap_or(WidthProfilePreset::ALL.len());
+
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			DropdownInput::new(entries).selected_index(Some(selected_index as u32)).widget_instance(),
+		]);
+	}
+	LayoutGroup::row(widgets)
+}
+
 pub fn font_inputs(parameter_widgets_info: ParameterWidgetsInfo) -> (Vec<WidgetInstance>, Option<Vec<WidgetInstance>>) {
 	pub fn assign_font_message(node_id: NodeId, font: Font) -> Message {
 		let resource_id = ResourceId::new();
@@ -2500,7 +2571,9 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 	let mut widgets_first_row = start_widgets(ParameterWidgetsInfo::new(node_id, FillInput::<List<Graphic>>::INDEX, false, context));
 
 	if get_document_node(node_id, context).is_ok_and(|node| node.inputs.get(FillInput::<List<Graphic>>::INDEX).is_some_and(|input| input.is_exposed())) {
-		return vec![LayoutGroup::row(widgets_first_row)];
+		let mut widgets = vec![LayoutGroup::row(widgets_first_row)];
+		widgets.extend(fill_tiling_properties(node_id, context));
+		return widgets;
 	}
 
 	// A Fill node not attached to a layer (or living in a nested network) still shows its full fill UI; only the gradient's
@@ -2662,6 +2735,10 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 	};
 	widgets.push(fill_type_switch);
 
+	if matches!(fill, ResolvedFill::Other) {
+		widgets.extend(fill_tiling_properties(node_id, context));
+	}
+
 	if let ResolvedFill::Gradient {
 		gradient_type,
 		spread_method,
@@ -2670,11 +2747,11 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 		..
 	} = fill.clone()
 	{
-		// Linear/Radial radio: blank assist (the "Reverse Direction" button has been moved down to the spread method row)
+		// Gradient type radio: blank assist (the "Reverse Direction" button has been moved down to the spread method row)
 		let mut row = vec![TextLabel::new("").widget_instance()];
 		add_blank_assist(&mut row);
 
-		let entries = [GradientType::Linear, GradientType::Radial]
+		let entries = [GradientType::Linear, GradientType::Radial, GradientType::Conic, GradientType::Diamond, GradientType::Mesh]
 			.iter()
 			.map(|&grad_type| {
 				RadioEntryData::new(format!("{:?}", grad_type))
@@ -2704,7 +2781,7 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 
 			let reverse_direction_button = IconButton::new(if orientation_rightward { "ReverseRadialGradientToRight" } else { "ReverseRadialGradientToLeft" }, 24)
 				.tooltip_label("Reverse Direction")
-				.tooltip_description(if gradient_type == GradientType::Radial {
+				.tooltip_description(if matches!(gradient_type, GradientType::Radial | GradientType::Diamond) {
 					"Reverse which end the gradient radiates from."
 				} else {
 					"Swap the start and end points of the gradient line."
@@ -2753,6 +2830,25 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 	widgets
 }
 
+/// The rows for repeating a graphic paint of the Fill node as a pattern, which only apply to paints that aren't a color or gradient.
+fn fill_tiling_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::vector::fill::*;
+
+	let tile = bool_widget(ParameterWidgetsInfo::new(node_id, TileInput::INDEX, true, context), CheckboxInput::default());
+	let mut widgets = vec![LayoutGroup::row(tile)];
+
+	let tile_enabled = get_document_node(node_id, context).is_ok_and(|node| matches!(node.inputs[TileInput::INDEX].as_value(), Some(TaggedValue::Bool(true))));
+	if tile_enabled {
+		let mut extra_widgets = Vec::new();
+		widgets.push(transform_widget(ParameterWidgetsInfo::new(node_id, TileTransformInput::INDEX, true, context), &mut extra_widgets));
+		widgets.extend(extra_widgets);
+		widgets.push(vec2_widget(ParameterWidgetsInfo::new(node_id, TileSpacingInput::INDEX, true, context), "X", "Y", " px", None, false));
+		widgets.push(vec2_widget(ParameterWidgetsInfo::new(node_id, TileOffsetInput::INDEX, true, context), "X", "Y", " px", None, false));
+	}
+
+	widgets
+}
+
 pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::vector::stroke::*;
 
@@ -2798,6 +2894,7 @@ pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -
 	let dash_lengths = dash_pattern_widget(ParameterWidgetsInfo::new(node_id, DashPatternInput::INDEX, true, context), TextInput::default().centered(true));
 	let number_input = disabled_number_input;
 	let dash_offset = number_widget(ParameterWidgetsInfo::new(node_id, DashOffsetInput::INDEX, true, context), number_input);
+	let width_profile = width_profile_widget(ParameterWidgetsInfo::new(node_id, WidthProfileInput::INDEX, true, context));
 
 	vec![
 		color,
@@ -2809,6 +2906,7 @@ pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -
 		paint_order,
 		LayoutGroup::row(dash_lengths),
 		LayoutGroup::row(dash_offset),
+		width_profile,
 	]
 }
 
@@ -1,6 +1,9 @@
+use crate::consts::{COLOR_OVERLAY_RED_10, COLOR_OVERLAY_RED_25};
 use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::portfolio::document::guide::utility_types::artboard_bounds;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
-use crate::messages::portfolio::document::utility_types::misc::{GridSnapping, GridType};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::misc::{GridSnapping, GridType, LayoutGrid, LayoutGridAlignment, LayoutGridTracks};
 use crate::messages::prelude::*;
 use glam::DVec2;
 use graphene_std::color::SRGBA8;
@@ -182,6 +185,35 @@ fn grid_overlay_isometric_dot(document: &DocumentMessageHandler, overlay_context
 	}
 }
 
+pub fn layout_grid_overlay(document: &DocumentMessageHandler, overlay_context: &mut OverlayContext) {
+	let document_to_viewport = document
+		.navigation_handler
+		.calculate_offset_transform(overlay_context.viewport.center_in_viewport_space().into(), &document.document_ptz);
+
+	for (artboard, layout_grid) in document.network_interface.layout_grids() {
+		let Some([min, max]) = artboard_bounds(document.metadata(), artboard) else { continue };
+
+		for [start, end] in layout_grid.columns.tracks(min.x, max.x) {
+			let quad = Quad::from_box([DVec2::new(start, min.y), DVec2::new(end, max.y)]);
+			overlay_context.quad(document_to_viewport * quad, None, Some(COLOR_OVERLAY_RED_10));
+		}
+		for [start, end] in layout_grid.rows.tracks(min.y, max.y) {
+			let quad = Quad::from_box([DVec2::new(min.x, start), DVec2::new(max.x, end)]);
+			overlay_context.quad(document_to_viewport * quad, None, Some(COLOR_OVERLAY_RED_10));
+		}
+
+		// Skip the baselines when they're packed too closely together on screen to be told apart
+		if layout_grid.baseline.spacing * document.document_ptz.zoom() < 4. {
+			continue;
+		}
+		for y in layout_grid.baseline.lines(min.y, max.y) {
+			let start = document_to_viewport.transform_point2(DVec2::new(min.x, y));
+			let end = document_to_viewport.transform_point2(DVec2::new(max.x, y));
+			overlay_context.line(start, end, Some(COLOR_OVERLAY_RED_25), None);
+		}
+	}
+}
+
 pub fn grid_overlay(document: &DocumentMessageHandler, overlay_context: &mut OverlayContext) {
 	match document.snapping_state.grid.grid_type {
 		GridType::Rectangular { spacing } => {
@@ -355,3 +387,162 @@ pub fn overlay_options(grid: &GridSnapping) -> Vec<LayoutGroup> {
 
 	widgets
 }
+
+pub fn layout_grid_options(artboard: LayerNodeIdentifier, layout_grid: &LayoutGrid) -> Vec<LayoutGroup> {
+	let mut widgets = vec![LayoutGroup::row(vec![TextLabel::new("Layout Grid").bold(true).widget_instance()])];
+
+	widgets.extend(layout_grid_track_options(artboard, layout_grid, "Columns", ["Left", "Width"], |layout_grid| &mut layout_grid.columns));
+	widgets.extend(layout_grid_track_options(artboard, layout_grid, "Rows", ["Top", "Height"], |layout_grid| &mut layout_grid.rows));
+
+	let baseline = &layout_grid.baseline;
+	let checkbox_id = CheckboxId::new();
+	widgets.push(LayoutGroup::row(vec![
+		CheckboxInput::new(baseline.enabled)
+			.on_update(update_layout_grid(artboard, layout_grid, |layout_grid, input: &CheckboxInput| {
+				layout_grid.baseline.enabled = input.checked
+			}))
+			.for_label(checkbox_id)
+			.widget_instance(),
+		TextLabel::new("Baseline").for_checkbox(checkbox_id).widget_instance(),
+	]));
+	widgets.push(LayoutGroup::row(vec![
+		TextLabel::new("Spacing").min_width(60).max_width(60).table_align(true).widget_instance(),
+		Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		NumberInput::new(Some(baseline.spacing))
+			.unit(" px")
+			.min(1.)
+			.min_width(200)
+			.disabled(!baseline.enabled)
+			.on_update(update_layout_grid_number(artboard, layout_grid, |layout_grid| &mut layout_grid.baseline.spacing))
+			.widget_instance(),
+	]));
+	widgets.push(LayoutGroup::row(vec![
+		TextLabel::new("Offset").min_width(60).max_width(60).table_align(true).widget_instance(),
+		Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		NumberInput::new(Some(baseline.offset))
+			.unit(" px")
+			.min(0.)
+			.min_width(200)
+			.disabled(!baseline.enabled)
+			.on_update(update_layout_grid_number(artboard, layout_grid, |layout_grid| &mut layout_grid.baseline.offset))
+			.widget_instance(),
+	]));
+
+	widgets
+}
+
+fn layout_grid_track_options(
+	artboard: LayerNodeIdentifier,
+	layout_grid: &LayoutGrid,
+	name: &str,
+	[start_name, size_name]: [&str; 2],
+	tracks: fn(&mut LayoutGrid) -> &mut LayoutGridTracks,
+) -> Vec<LayoutGroup> {
+	let current = tracks(&mut layout_grid.clone()).clone();
+	let disabled = !current.enabled;
+	let checkbox_id = CheckboxId::new();
+
+	let alignment_entry = |alignment: LayoutGridAlignment, label: &str| {
+		RadioEntryData::new(label.to_lowercase())
+			.label(label)
+			.on_update(update_layout_grid(artboard, layout_grid, move |layout_grid, _: &()| tracks(layout_grid).alignment = alignment))
+	};
+
+	vec![
+		LayoutGroup::row(vec![
+			CheckboxInput::new(current.enabled)
+				.on_update(update_layout_grid(artboard, layout_grid, move |layout_grid, input: &CheckboxInput| {
+					tracks(layout_grid).enabled = input.checked
+				}))
+				.for_label(checkbox_id)
+				.widget_instance(),
+			TextLabel::new(name).for_checkbox(checkbox_id).widget_instance(),
+		]),
+		LayoutGroup::row(vec![
+			TextLabel::new("Count").min_width(60).max_width(60).table_align(true).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(current.count as f64))
+				.int()
+				.min(1.)
+				.min_width(200)
+				.disabled(disabled)
+				.on_update(update_layout_grid(artboard, layout_grid, move |layout_grid, input: &NumberInput| {
+					if let Some(value) = input.value {
+						tracks(layout_grid).count = value.max(1.) as u32;
+					}
+				}))
+				.widget_instance(),
+		]),
+		LayoutGroup::row(vec![
+			TextLabel::new("Align").min_width(60).max_width(60).table_align(true).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			RadioInput::new(vec![
+				alignment_entry(LayoutGridAlignment::Stretch, "Stretch"),
+				alignment_entry(LayoutGridAlignment::Start, start_name),
+				alignment_entry(LayoutGridAlignment::Center, "Center"),
+			])
+			.min_width(200)
+			.disabled(disabled)
+			.selected_index(Some(match current.alignment {
+				LayoutGridAlignment::Stretch => 0,
+				LayoutGridAlignment::Start => 1,
+				LayoutGridAlignment::Center => 2,
+			}))
+			.widget_instance(),
+		]),
+		LayoutGroup::row(vec![
+			TextLabel::new(size_name).min_width(60).max_width(60).table_align(true).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(current.size))
+				.unit(" px")
+				.min(1.)
+				.min_width(200)
+				.disabled(disabled || current.alignment == LayoutGridAlignment::Stretch)
+				.on_update(update_layout_grid_number(artboard, layout_grid, move |layout_grid| &mut tracks(layout_grid).size))
+				.widget_instance(),
+		]),
+		LayoutGroup::row(vec![
+			TextLabel::new("Gutter").min_width(60).max_width(60).table_align(true).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(current.gutter))
+				.unit(" px")
+				.min(0.)
+				.min_width(200)
+				.disabled(disabled)
+				.on_update(update_layout_grid_number(artboard, layout_grid, move |layout_grid| &mut tracks(layout_grid).gutter))
+				.widget_instance(),
+		]),
+		LayoutGroup::row(vec![
+			TextLabel::new("Margin").min_width(60).max_width(60).table_align(true).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			NumberInput::new(Some(current.margin))
+				.unit(" px")
+				.min(0.)
+				.min_width(200)
+				.disabled(disabled || current.alignment == LayoutGridAlignment::Center)
+				.on_update(update_layout_grid_number(artboard, layout_grid, move |layout_grid| &mut tracks(layout_grid).margin))
+				.widget_instance(),
+		]),
+	]
+}
+
+fn update_layout_grid<I, F: Fn(&mut LayoutGrid, &I)>(artboard: LayerNodeIdentifier, layout_grid: &LayoutGrid, update: F) -> impl Fn(&I) -> Message + use<I, F> {
+	let layout_grid = layout_grid.clone();
+	move |input: &I| {
+		let mut layout_grid = layout_grid.clone();
+		update(&mut layout_grid, input);
+		DocumentMessage::LayoutGridOptions {
+			artboard,
+			layout_grid: Some(layout_grid),
+		}
+		.into()
+	}
+}
+
+fn update_layout_grid_number<F: Fn(&mut LayoutGrid) -> &mut f64>(artboard: LayerNodeIdentifier, layout_grid: &LayoutGrid, field: F) -> impl Fn(&NumberInput) -> Message + use<F> {
+	update_layout_grid(artboard, layout_grid, move |layout_grid, input: &NumberInput| {
+		if let Some(value) = input.value {
+			*field(layout_grid) = value;
+		}
+	})
+}
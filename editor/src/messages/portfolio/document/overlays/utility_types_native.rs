@@ -48,6 +48,7 @@ pub enum GizmoEmphasis {
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum OverlaysType {
 	ArtboardName,
+	Guides,
 	CompassRose,
 	QuickMeasurement,
 	TransformMeasurement,
@@ -69,6 +70,7 @@ pub enum OverlaysType {
 pub struct OverlaysVisibilitySettings {
 	pub all: bool,
 	pub artboard_name: bool,
+	pub guides: bool,
 	pub compass_rose: bool,
 	pub quick_measurement: bool,
 	pub transform_measurement: bool,
@@ -89,6 +91,7 @@ impl Default for OverlaysVisibilitySettings {
 		Self {
 			all: true,
 			artboard_name: true,
+			guides: true,
 			compass_rose: true,
 			quick_measurement: true,
 			transform_measurement: true,
@@ -115,6 +118,10 @@ impl OverlaysVisibilitySettings {
 		self.all && self.artboard_name
 	}
 
+	pub fn guides(&self) -> bool {
+		self.all && self.guides
+	}
+
 	pub fn compass_rose(&self) -> bool {
 		self.all && self.compass_rose
 	}
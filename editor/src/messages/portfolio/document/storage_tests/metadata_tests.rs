@@ -10,8 +10,13 @@ use document_graph_storage::{NodeMetadataSource, PeerId, Registry};
 
 use super::test_support::{load_demo, node_paths};
 use crate::messages::portfolio::document::document_message_handler::DocumentMessageHandler;
+use crate::messages::portfolio::document::guide::utility_types::{Guide, GuideId};
+use crate::messages::portfolio::document::utility_types::misc::{LayoutGrid, LayoutGridAlignment};
+use crate::messages::portfolio::document::utility_types::network_interface::components::{ComponentLink, ComponentRole};
+use crate::messages::portfolio::document::utility_types::network_interface::library::{LibraryExport, LibraryLink};
 use crate::messages::portfolio::document::utility_types::network_interface::storage_metadata::{DocumentSettings, StorageMetadataView, build_interface_from_storage};
 use crate::messages::portfolio::document::utility_types::nodes::CollapsedLayers;
+use glam::DVec2;
 use graph_craft::document::NodeId;
 use graphene_std::vector::style::RenderMode;
 
@@ -262,6 +267,110 @@ fn editor_interface_rebuild_round_trip() {
 	assert!(checked_any_reference, "demo artwork produced no reference metadata: fixture is wrong or extraction is broken");
 }
 
+/// Component links survive the registry round-trip, including an instance's overridden inputs.
+#[test]
+fn component_links_round_trip() {
+	let mut document = load_demo("changing-seasons.graphite");
+	let interface = &mut document.network_interface;
+
+	let root_nodes = interface.document_network().nodes.keys().copied().collect::<Vec<_>>();
+	let [master, instance, ..] = root_nodes[..] else {
+		panic!("demo artwork has fewer than two root nodes");
+	};
+	interface.set_component(&master, &[], Some(ComponentLink::master(7)));
+	interface.set_component(
+		&instance,
+		&[],
+		Some(ComponentLink {
+			component_id: 7,
+			role: ComponentRole::Instance { overridden_inputs: vec![1] },
+		}),
+	);
+
+	let view = StorageMetadataView::new(interface);
+	let network = interface.document_network().clone();
+	let conversion = Registry::convert_from_runtime(&network, &view, &Default::default(), PeerId(0)).expect("convert_from_runtime failed");
+	let declarations = conversion.declarations().expect("rebuild declarations");
+	let (rebuilt_network, node_entries, network_entries) = conversion.registry.to_runtime_with_full_metadata(&declarations).expect("to_runtime_with_full_metadata failed");
+	let rebuilt = build_interface_from_storage(rebuilt_network, node_entries, network_entries).expect("build_interface_from_storage failed");
+
+	for (network_path, local_id) in node_paths(interface) {
+		assert_eq!(
+			rebuilt.component(&local_id, &network_path),
+			interface.component(&local_id, &network_path),
+			"component mismatch for node {local_id:?} in network {network_path:?}"
+		);
+	}
+	assert_eq!(rebuilt.component_master(7), Some((Vec::new(), master)));
+}
+
+/// A library export on a document network node is listed in the registry's exported nodes, and both exports and library links survive the round-trip.
+#[test]
+fn library_metadata_round_trip() {
+	let mut document = load_demo("changing-seasons.graphite");
+	let interface = &mut document.network_interface;
+
+	let root_nodes = interface.document_network().nodes.keys().copied().collect::<Vec<_>>();
+	let exported = root_nodes
+		.iter()
+		.copied()
+		.find(|node_id| interface.can_export_to_library(node_id, &[]))
+		.expect("demo artwork has no network node to export");
+	let linked = root_nodes.iter().copied().find(|node_id| *node_id != exported).expect("demo artwork has a single root node");
+	let library_export = LibraryExport {
+		name: "Scenery".into(),
+		category: "Seasons".into(),
+	};
+	interface.set_library_export(&exported, &[], Some(library_export));
+	let library_link = LibraryLink {
+		library_id: 3,
+		node: 11,
+		version: "0123abcd".into(),
+		pinned: true,
+	};
+	interface.set_library_link(&linked, &[], Some(library_link));
+
+	let view = StorageMetadataView::new(interface);
+	let network = interface.document_network().clone();
+	let conversion = Registry::convert_from_runtime(&network, &view, &Default::default(), PeerId(0)).expect("convert_from_runtime failed");
+	let exports = conversion.registry.library_exports();
+	assert_eq!(exports.len(), 1);
+	assert_eq!(exports[0].local_id, exported.0);
+	assert_eq!((exports[0].name.as_str(), exports[0].category.as_str()), ("Scenery", "Seasons"));
+
+	let declarations = conversion.declarations().expect("rebuild declarations");
+	let (rebuilt_network, node_entries, network_entries) = conversion.registry.to_runtime_with_full_metadata(&declarations).expect("to_runtime_with_full_metadata failed");
+	let rebuilt = build_interface_from_storage(rebuilt_network, node_entries, network_entries).expect("build_interface_from_storage failed");
+
+	for (network_path, local_id) in node_paths(interface) {
+		assert_eq!(rebuilt.library_export(&local_id, &network_path), interface.library_export(&local_id, &network_path));
+		assert_eq!(rebuilt.library_link(&local_id, &network_path), interface.library_link(&local_id, &network_path));
+	}
+}
+
+/// A layout grid laid over an artboard is stored on the artboard node, so it survives the round-trip with the rest of the document.
+#[test]
+fn layout_grid_round_trip() {
+	let mut document = load_demo("changing-seasons.graphite");
+	let interface = &mut document.network_interface;
+
+	let artboard = interface.all_artboards().into_iter().next().expect("demo artwork has no artboard");
+	let mut layout_grid = LayoutGrid::default();
+	layout_grid.columns.alignment = LayoutGridAlignment::Center;
+	layout_grid.baseline.enabled = true;
+	interface.set_layout_grid(artboard, Some(layout_grid.clone()));
+
+	let view = StorageMetadataView::new(interface);
+	let network = interface.document_network().clone();
+	let conversion = Registry::convert_from_runtime(&network, &view, &Default::default(), PeerId(0)).expect("convert_from_runtime failed");
+	let declarations = conversion.declarations().expect("rebuild declarations");
+	let (rebuilt_network, node_entries, network_entries) = conversion.registry.to_runtime_with_full_metadata(&declarations).expect("to_runtime_with_full_metadata failed");
+	let rebuilt = build_interface_from_storage(rebuilt_network, node_entries, network_entries).expect("build_interface_from_storage failed");
+
+	assert_eq!(rebuilt.layout_grid(artboard), Some(&layout_grid));
+	assert_eq!(rebuilt.layout_grids().count(), 1);
+}
+
 /// Per-peer view settings (`ui::doc::*`) survive the `session.json` round-trip: serialize them into
 /// the view map, then apply it onto a fresh handler and confirm each field matches.
 #[test]
@@ -272,14 +381,24 @@ fn document_settings_round_trip() {
 	document.render_mode = RenderMode::Outline;
 	document.rulers_visible = false;
 	document.collapsed = CollapsedLayers(vec![vec![NodeId(7)], vec![NodeId(7), NodeId(42)]]);
+	document.guides.guides.push(Guide {
+		id: GuideId(3),
+		position: DVec2::new(12., 34.),
+		angle: std::f64::consts::FRAC_PI_4,
+		artboard: None,
+	});
+	document.guides.locked = true;
+	document.library_paths.insert(3, "libraries/seasons.gdd".into());
 
 	let view_settings = DocumentSettings {
 		document_ptz: &document.document_ptz,
 		render_mode: &document.render_mode,
 		overlays_visibility: &document.overlays_visibility_settings,
 		rulers_visible: document.rulers_visible,
+		guides: &document.guides,
 		snapping_state: &document.snapping_state,
 		collapsed: &document.collapsed,
+		library_paths: &document.library_paths,
 	}
 	.to_view_map();
 
@@ -310,4 +429,6 @@ fn document_settings_round_trip() {
 		"snapping_state"
 	);
 	assert_eq!(serde_json::to_value(restored.collapsed).unwrap(), serde_json::to_value(document.collapsed).unwrap(), "collapsed");
+	assert_eq!(restored.guides, document.guides, "guides");
+	assert_eq!(restored.library_paths, document.library_paths, "library_paths");
 }
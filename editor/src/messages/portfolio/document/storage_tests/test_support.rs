@@ -70,8 +70,10 @@ pub async fn round_trip_through_gdd(document: &DocumentMessageHandler) -> RoundT
 		render_mode: &document.render_mode,
 		overlays_visibility: &document.overlays_visibility_settings,
 		rulers_visible: document.rulers_visible,
+		guides: &document.guides,
 		snapping_state: &document.snapping_state,
 		collapsed: &document.collapsed,
+		library_paths: &document.library_paths,
 	}
 	.to_view_map();
 	gdd.set_view_settings(view_settings).expect("set_view_settings");
@@ -67,6 +67,7 @@ pub struct SnappingState {
 	pub tolerance: f64,
 	pub bounding_box: BoundingBoxSnapping,
 	pub path: PathSnapping,
+	pub guide: GuideSnapping,
 	pub grid: GridSnapping,
 }
 
@@ -79,6 +80,7 @@ impl Default for SnappingState {
 			tolerance: 8.,
 			bounding_box: BoundingBoxSnapping::default(),
 			path: PathSnapping::default(),
+			guide: GuideSnapping::default(),
 			grid: GridSnapping::default(),
 		}
 	}
@@ -105,7 +107,11 @@ impl SnappingState {
 				PathSnapTarget::PerpendicularToEndpoint => self.path.perpendicular_from_endpoint,
 			},
 			SnapTarget::Artboard(_) => self.artboards,
+			// Layout grids belong to artboards, so they're shown and snapped to regardless of the document grid
+			SnapTarget::Grid(GridSnapTarget::LayoutGrid) => self.artboards,
 			SnapTarget::Grid(_) => self.grid_snapping,
+			SnapTarget::Guide(GuideSnapTarget::Line) => self.guide.along_guide,
+			SnapTarget::Guide(GuideSnapTarget::Intersection) => self.guide.intersection_point,
 			SnapTarget::Alignment(AlignmentSnapTarget::AlignWithAnchorPoint) => self.path.align_with_anchor_point,
 			SnapTarget::Alignment(_) => self.bounding_box.align_with_edges,
 			SnapTarget::DistributeEvenly(_) => self.bounding_box.distribute_evenly,
@@ -164,6 +170,22 @@ impl Default for PathSnapping {
 	}
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GuideSnapping {
+	pub along_guide: bool,
+	pub intersection_point: bool,
+}
+
+impl Default for GuideSnapping {
+	fn default() -> Self {
+		Self {
+			along_guide: true,
+			intersection_point: true,
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
 pub enum GridType {
 	#[serde(alias = "Rectangle")]
@@ -269,6 +291,152 @@ impl GridSnapping {
 	}
 }
 
+/// Columns, rows, and a baseline grid laid over an artboard, used to line up its contents.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LayoutGrid {
+	pub columns: LayoutGridTracks,
+	pub rows: LayoutGridTracks,
+	pub baseline: BaselineGrid,
+}
+
+impl Default for LayoutGrid {
+	fn default() -> Self {
+		Self {
+			columns: LayoutGridTracks { enabled: true, ..Default::default() },
+			rows: LayoutGridTracks { count: 4, ..Default::default() },
+			baseline: BaselineGrid::default(),
+		}
+	}
+}
+
+impl LayoutGrid {
+	/// The lines artwork can snap to within the given artboard bounds, as a point and direction pair for each column and row edge,
+	/// along with the baselines immediately above and below `near`.
+	pub fn snap_lines(&self, [min, max]: [DVec2; 2], near: DVec2) -> Vec<(DVec2, DVec2)> {
+		let columns = self.columns.tracks(min.x, max.x).into_iter().flatten().map(|x| (DVec2::new(x, min.y), DVec2::Y));
+		let rows = self.rows.tracks(min.y, max.y).into_iter().flatten().map(|y| (DVec2::new(min.x, y), DVec2::X));
+		let baselines = self.baseline.nearest(min.y, max.y, near.y).into_iter().flatten().map(|y| (DVec2::new(min.x, y), DVec2::X));
+
+		columns.chain(rows).chain(baselines).collect()
+	}
+}
+
+/// How the tracks of a layout grid are sized and positioned across the artboard.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum LayoutGridAlignment {
+	/// The tracks grow to fill the artboard between its margins.
+	#[default]
+	Stretch,
+	/// Fixed size tracks start at the left (or top) margin.
+	Start,
+	/// Fixed size tracks are centered on the artboard.
+	Center,
+}
+
+/// A set of evenly spaced columns or rows.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LayoutGridTracks {
+	pub enabled: bool,
+	pub count: u32,
+	pub alignment: LayoutGridAlignment,
+	/// The width of each column (or height of each row), which is ignored when the tracks are stretched.
+	pub size: f64,
+	pub gutter: f64,
+	/// The space between the artboard's edges and the outermost tracks, which is ignored when the tracks are centered.
+	pub margin: f64,
+}
+
+impl Default for LayoutGridTracks {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			count: 12,
+			alignment: LayoutGridAlignment::Stretch,
+			size: 60.,
+			gutter: 20.,
+			margin: 20.,
+		}
+	}
+}
+
+impl LayoutGridTracks {
+	/// The start and end of each track along an artboard side spanning from `start` to `end`, cut off where the tracks overflow the artboard.
+	pub fn tracks(&self, start: f64, end: f64) -> Vec<[f64; 2]> {
+		if !self.enabled || self.count == 0 {
+			return Vec::new();
+		}
+
+		let count = self.count as f64;
+		let gutter = self.gutter.max(0.);
+		let (first, size) = match self.alignment {
+			LayoutGridAlignment::Stretch => (start + self.margin, (end - start - self.margin * 2. - gutter * (count - 1.)) / count),
+			LayoutGridAlignment::Start => (start + self.margin, self.size),
+			LayoutGridAlignment::Center => (start + (end - start - self.size * count - gutter * (count - 1.)) / 2., self.size),
+		};
+		if !size.is_finite() || size <= 0. {
+			return Vec::new();
+		}
+
+		(0..self.count)
+			.map(|index| {
+				let track_start = first + index as f64 * (size + gutter);
+				[track_start.max(start), (track_start + size).min(end)]
+			})
+			.filter(|[track_start, track_end]| track_start < track_end)
+			.collect()
+	}
+}
+
+/// Horizontal lines at a regular spacing down the artboard, which lines of text sit on.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BaselineGrid {
+	pub enabled: bool,
+	pub spacing: f64,
+	/// The distance from the top of the artboard to the first baseline.
+	pub offset: f64,
+}
+
+impl Default for BaselineGrid {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			spacing: 8.,
+			offset: 0.,
+		}
+	}
+}
+
+impl BaselineGrid {
+	/// Every baseline between `start` and `end`.
+	pub fn lines(&self, start: f64, end: f64) -> impl Iterator<Item = f64> + use<> {
+		let (first, spacing) = (start + self.offset, self.spacing);
+		let count = if self.enabled && spacing > 0. && end >= first {
+			((end - first) / spacing).floor() as usize + 1
+		} else {
+			0
+		};
+
+		(0..count).map(move |index| first + index as f64 * spacing)
+	}
+
+	/// The baselines immediately above and below `position`, if they lie between `start` and `end`.
+	/// Above the first baseline, there's none above and the first baseline is the one below.
+	pub fn nearest(&self, start: f64, end: f64, position: f64) -> [Option<f64>; 2] {
+		let (first, spacing) = (start + self.offset, self.spacing);
+		if !self.enabled || spacing <= 0. {
+			return [None, None];
+		}
+
+		let index = ((position - first) / spacing).floor();
+		let above = (index >= 0.).then(|| first + index * spacing);
+		let below = Some(first + (index + 1.).max(0.) * spacing);
+		[above, below].map(|line| line.filter(|&line| line >= start && line <= end))
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BoundingBoxSnapSource {
 	CornerPoint,
@@ -472,6 +640,19 @@ pub const SNAP_FUNCTIONS_FOR_PATHS: [(&str, GetSnapState, &str); 7] = [
 	),
 ];
 
+pub const SNAP_FUNCTIONS_FOR_GUIDES: [(&str, GetSnapState, &str); 2] = [
+	(
+		"Along Guides",
+		(|snapping_state: &mut SnappingState| &mut snapping_state.guide.along_guide) as GetSnapState,
+		"Snaps along the length of any guide dragged out of the rulers.",
+	),
+	(
+		"Guide Intersection Points",
+		(|snapping_state: &mut SnappingState| &mut snapping_state.guide.intersection_point) as GetSnapState,
+		"Snaps to any points where guides cross each other.",
+	),
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum BoundingBoxSnapTarget {
 	CornerPoint,
@@ -537,6 +718,7 @@ pub enum GridSnapTarget {
 	Line,
 	LineNormal,
 	Intersection,
+	LayoutGrid,
 }
 
 impl fmt::Display for GridSnapTarget {
@@ -545,6 +727,22 @@ impl fmt::Display for GridSnapTarget {
 			GridSnapTarget::Line => write!(f, "Grid: Along Line"),
 			GridSnapTarget::LineNormal => write!(f, "Grid: Normal to Line"),
 			GridSnapTarget::Intersection => write!(f, "Grid: Intersection Point"),
+			GridSnapTarget::LayoutGrid => write!(f, "Grid: Along Layout Grid"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuideSnapTarget {
+	Line,
+	Intersection,
+}
+
+impl fmt::Display for GuideSnapTarget {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			GuideSnapTarget::Line => write!(f, "Guide: Along Guide"),
+			GuideSnapTarget::Intersection => write!(f, "Guide: Intersection Point"),
 		}
 	}
 }
@@ -616,6 +814,7 @@ pub enum SnapTarget {
 	Path(PathSnapTarget),
 	Artboard(ArtboardSnapTarget),
 	Grid(GridSnapTarget),
+	Guide(GuideSnapTarget),
 	Alignment(AlignmentSnapTarget),
 	DistributeEvenly(DistributionSnapTarget),
 }
@@ -637,6 +836,7 @@ impl fmt::Display for SnapTarget {
 			SnapTarget::Path(path_snap_target) => write!(f, "{path_snap_target}"),
 			SnapTarget::Artboard(artboard_snap_target) => write!(f, "{artboard_snap_target}"),
 			SnapTarget::Grid(grid_snap_target) => write!(f, "{grid_snap_target}"),
+			SnapTarget::Guide(guide_snap_target) => write!(f, "{guide_snap_target}"),
 			SnapTarget::Alignment(alignment_snap_target) => write!(f, "{alignment_snap_target}"),
 			SnapTarget::DistributeEvenly(distribution_snap_target) => write!(f, "{distribution_snap_target}"),
 		}
@@ -647,6 +847,7 @@ impl fmt::Display for SnapTarget {
 pub enum SnappingOptions {
 	BoundingBoxes,
 	Paths,
+	Guides,
 }
 
 impl fmt::Display for SnappingOptions {
@@ -654,6 +855,7 @@ impl fmt::Display for SnappingOptions {
 		match self {
 			SnappingOptions::BoundingBoxes => write!(f, "Bounding Boxes"),
 			SnappingOptions::Paths => write!(f, "Paths"),
+			SnappingOptions::Guides => write!(f, "Guides"),
 		}
 	}
 }
@@ -715,3 +917,85 @@ pub enum GroupFolderType {
 	Blend,
 	Morph,
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn tracks(alignment: LayoutGridAlignment, count: u32, margin: f64) -> LayoutGridTracks {
+		LayoutGridTracks {
+			enabled: true,
+			count,
+			alignment,
+			size: 20.,
+			gutter: 10.,
+			margin,
+		}
+	}
+
+	#[test]
+	fn layout_grid_tracks_fill_the_artboard() {
+		assert_eq!(tracks(LayoutGridAlignment::Stretch, 3, 10.).tracks(0., 100.), vec![[10., 30.], [40., 60.], [70., 90.]]);
+		assert_eq!(tracks(LayoutGridAlignment::Start, 2, 10.).tracks(100., 200.), vec![[110., 130.], [140., 160.]]);
+		assert_eq!(tracks(LayoutGridAlignment::Center, 2, 10.).tracks(0., 100.), vec![[25., 45.], [55., 75.]]);
+	}
+
+	#[test]
+	fn layout_grid_without_tracks() {
+		for alignment in [LayoutGridAlignment::Stretch, LayoutGridAlignment::Start, LayoutGridAlignment::Center] {
+			assert!(tracks(alignment, 0, 10.).tracks(0., 100.).is_empty(), "{alignment:?} with no tracks");
+		}
+		let disabled = LayoutGridTracks {
+			enabled: false,
+			..tracks(LayoutGridAlignment::Stretch, 3, 10.)
+		};
+		assert!(disabled.tracks(0., 100.).is_empty());
+	}
+
+	#[test]
+	fn layout_grid_margins_wider_than_the_artboard() {
+		// Stretched tracks have no room left between the margins
+		assert!(tracks(LayoutGridAlignment::Stretch, 3, 60.).tracks(0., 100.).is_empty());
+
+		// Fixed size tracks are cut off at the artboard's edge, and those beyond it are left out
+		assert_eq!(tracks(LayoutGridAlignment::Start, 3, 80.).tracks(0., 100.), vec![[80., 100.]]);
+		assert!(tracks(LayoutGridAlignment::Start, 3, 120.).tracks(0., 100.).is_empty());
+
+		// Centered tracks ignore the margins, but can still overflow a narrow artboard
+		assert_eq!(tracks(LayoutGridAlignment::Center, 3, 120.).tracks(0., 40.), vec![[10., 30.]]);
+	}
+
+	#[test]
+	fn baseline_grid_nearest_lines() {
+		let baseline = BaselineGrid {
+			enabled: true,
+			spacing: 8.,
+			offset: 4.,
+		};
+
+		assert_eq!(baseline.nearest(0., 100., 15.), [Some(12.), Some(20.)]);
+		assert_eq!(baseline.nearest(0., 100., 12.), [Some(12.), Some(20.)], "A position on a baseline is below it");
+
+		// Above the first baseline, only the first baseline is below
+		assert_eq!(baseline.nearest(0., 100., 2.), [None, Some(4.)]);
+		assert_eq!(baseline.nearest(0., 100., -50.), [None, Some(4.)]);
+
+		// Past the bottom of the artboard, there's no baseline below
+		assert_eq!(baseline.nearest(0., 95., 93.), [Some(92.), None]);
+		assert_eq!(baseline.nearest(0., 100., 150.), [None, None]);
+
+		assert_eq!(BaselineGrid { enabled: false, ..baseline }.nearest(0., 100., 15.), [None, None]);
+		assert_eq!(BaselineGrid { spacing: 0., ..baseline }.nearest(0., 100., 15.), [None, None]);
+	}
+
+	#[test]
+	fn baseline_grid_lines() {
+		let baseline = BaselineGrid {
+			enabled: true,
+			spacing: 30.,
+			offset: 10.,
+		};
+		assert_eq!(baseline.lines(0., 100.).collect::<Vec<_>>(), vec![10., 40., 70., 100.]);
+		assert_eq!(BaselineGrid { offset: 120., ..baseline }.lines(0., 100.).count(), 0, "An offset past the artboard leaves no baselines");
+	}
+}
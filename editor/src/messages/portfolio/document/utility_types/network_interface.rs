@@ -1,10 +1,12 @@
+pub mod components;
 mod deserialization;
+pub mod library;
 mod memo_network;
 mod resolved_types;
 pub mod storage_metadata;
 
 use super::document_metadata::{DocumentMetadata, LayerNodeIdentifier, NodeRelations};
-use super::misc::PTZ;
+use super::misc::{LayoutGrid, PTZ};
 use super::nodes::SelectedNodes;
 use crate::consts::{
 	EXPORTS_TO_RIGHT_EDGE_PIXEL_GAP, EXPORTS_TO_TOP_EDGE_PIXEL_GAP, GRID_SIZE, HALF_GRID_SIZE, IMPORTS_TO_LEFT_EDGE_PIXEL_GAP, IMPORTS_TO_TOP_EDGE_PIXEL_GAP, LAYER_INDENT_OFFSET, NODE_CHAIN_WIDTH,
@@ -57,6 +59,12 @@ pub struct NodeNetworkInterface {
 	pub resolved_types: ResolvedDocumentNodeTypes,
 	#[serde(skip)]
 	transaction_status: TransactionStatus,
+	/// Set by every modification until [`Self::sync_modified_component_instances`] brings the component instances back in line with their masters.
+	#[serde(skip)]
+	components_unsynced: bool,
+	/// Cache for [`Self::library_nodes`], cleared by every modification.
+	#[serde(skip)]
+	library_nodes: Option<Vec<(Vec<NodeId>, NodeId, library::LibraryLink)>>,
 }
 
 impl Clone for NodeNetworkInterface {
@@ -67,6 +75,8 @@ impl Clone for NodeNetworkInterface {
 			document_metadata: Default::default(),
 			resolved_types: Default::default(),
 			transaction_status: TransactionStatus::Finished,
+			components_unsynced: false,
+			library_nodes: None,
 		}
 	}
 }
@@ -1211,6 +1221,32 @@ impl NodeNetworkInterface {
 			.collect()
 	}
 
+	/// The layout grid laid over an artboard, if it has one.
+	pub fn layout_grid(&self, artboard: LayerNodeIdentifier) -> Option<&LayoutGrid> {
+		self.node_metadata(&artboard.to_node(), &[])?.persistent_metadata.layout_grid.as_ref()
+	}
+
+	/// Every artboard that has a layout grid laid over it.
+	pub fn layout_grids(&self) -> impl Iterator<Item = (LayerNodeIdentifier, &LayoutGrid)> {
+		self.document_network_metadata().persistent_metadata.node_metadata.iter().filter_map(|(node_id, node_metadata)| {
+			let layout_grid = node_metadata.persistent_metadata.layout_grid.as_ref()?;
+			self.is_artboard(node_id, &[]).then(|| (LayerNodeIdentifier::new(*node_id, self), layout_grid))
+		})
+	}
+
+	pub fn set_layout_grid(&mut self, artboard: LayerNodeIdentifier, layout_grid: Option<LayoutGrid>) {
+		let Some(node_metadata) = self.node_metadata_mut(&artboard.to_node(), &[]) else {
+			log::error!("Could not get node metadata in set_layout_grid");
+			return;
+		};
+		if node_metadata.persistent_metadata.layout_grid == layout_grid {
+			return;
+		}
+
+		node_metadata.persistent_metadata.layout_grid = layout_grid;
+		self.transaction_modified();
+	}
+
 	/// Folders sorted from most nested to least nested
 	pub fn folders_sorted_by_most_nested(&self, network_path: &[NodeId]) -> Vec<LayerNodeIdentifier> {
 		if !network_path.is_empty() {
@@ -1497,6 +1533,8 @@ impl NodeNetworkInterface {
 			document_metadata: DocumentMetadata::default(),
 			resolved_types: ResolvedDocumentNodeTypes::default(),
 			transaction_status: TransactionStatus::Finished,
+			components_unsynced: false,
+			library_nodes: None,
 		}
 	}
 }
@@ -1573,6 +1611,8 @@ impl NodeNetworkInterface {
 	}
 
 	pub fn transaction_modified(&mut self) {
+		self.components_unsynced = true;
+		self.library_nodes = None;
 		if self.transaction_status == TransactionStatus::Started {
 			self.transaction_status = TransactionStatus::Modified;
 		}
@@ -4231,6 +4271,11 @@ impl NodeNetworkInterface {
 
 		self.transaction_modified();
 
+		// A value set directly on a component instance stops following the master's value
+		if let (InputConnector::Node { node_id, input_index }, NodeInput::Value { .. }) = (input_connector, &new_input) {
+			self.mark_component_input_overridden(node_id, *input_index, network_path);
+		}
+
 		// Ensure layer is toggled to non layer if it is no longer eligible to be a layer
 		let layer_node_path = match input_connector {
 			InputConnector::Node { node_id, .. } => Some((node_id, network_path)),
@@ -4470,6 +4515,8 @@ impl NodeNetworkInterface {
 		for (old_node_id, mut node_template) in nodes {
 			// Get the new node template
 			node_template = self.map_ids(node_template, &old_node_id, &new_ids, network_path);
+			// A copy of a component master becomes another instance of that component
+			self.demote_duplicate_master(&mut node_template);
 			// Insert node into network
 			let node_id = *new_ids.get(&old_node_id).unwrap();
 			let Some(network) = self.network_mut(network_path) else {
@@ -6827,6 +6874,18 @@ pub struct DocumentNodePersistentMetadata {
 	pub node_type_metadata: NodeTypePersistentMetadata,
 	/// This should always be Some for nodes with a [`DocumentNodeImplementation::Network`], and none for [`DocumentNodeImplementation::ProtoNode`]
 	pub network_metadata: Option<NodeNetworkMetadata>,
+	/// Set when this node is the master or a linked instance of a reusable component.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub component: Option<components::ComponentLink>,
+	/// Set on document network nodes offered to other documents that import this one as a node library.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub library_export: Option<library::LibraryExport>,
+	/// Set when this node was placed from an imported node library, recording which library node and version it came from.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub library_link: Option<library::LibraryLink>,
+	/// The column, row, and baseline layout grid laid over this node when it's an artboard.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub layout_grid: Option<LayoutGrid>,
 }
 
 impl DocumentNodePersistentMetadata {
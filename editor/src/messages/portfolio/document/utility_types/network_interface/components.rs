@@ -0,0 +1,350 @@
+//! Reusable components: a master node whose nested network defines the component, and linked instances whose nested network
+//! is kept identical to the master's. Storage preserves that sharing by pointing every in-sync instance at the master's network.
+
+use std::collections::HashMap;
+
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeInput};
+use graph_craft::generic;
+
+use super::{DocumentNodeMetadata, DocumentNodePersistentMetadata, FlowType, InputConnector, NodeNetworkInterface, NodeTemplate, OutputConnector};
+use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_network_node_type;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+
+/// Links a node to a reusable component, either as its master or as one of its instances.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ComponentLink {
+	pub component_id: u64,
+	pub role: ComponentRole,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ComponentRole {
+	/// The node whose nested network defines the component. Edits made inside it propagate to every instance.
+	Master,
+	/// A linked copy of the master. Its inputs follow the master's values, except for the listed ones which were set on the instance itself.
+	Instance { overridden_inputs: Vec<usize> },
+}
+
+impl ComponentLink {
+	pub fn master(component_id: u64) -> Self {
+		Self {
+			component_id,
+			role: ComponentRole::Master,
+		}
+	}
+
+	pub fn instance(component_id: u64) -> Self {
+		Self {
+			component_id,
+			role: ComponentRole::Instance { overridden_inputs: Vec::new() },
+		}
+	}
+
+	pub fn is_master(&self) -> bool {
+		matches!(self.role, ComponentRole::Master)
+	}
+}
+
+/// What a linked node is kept in line with: a component's master, or the current version of a library node.
+pub(super) struct LinkedSource {
+	pub implementation: DocumentNodeImplementation,
+	/// The source's inputs, already resolved to values so they can be copied into the linked node's network.
+	pub inputs: Vec<NodeInput>,
+	pub metadata: DocumentNodePersistentMetadata,
+}
+
+impl NodeNetworkInterface {
+	pub fn component(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<&ComponentLink> {
+		self.node_metadata(node_id, network_path)?.persistent_metadata.component.as_ref()
+	}
+
+	pub fn set_component(&mut self, node_id: &NodeId, network_path: &[NodeId], component: Option<ComponentLink>) {
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node metadata in set_component");
+			return;
+		};
+		node_metadata.persistent_metadata.component = component;
+		self.transaction_modified();
+	}
+
+	/// Every component master and instance as `(network_path, node_id, link)`. Nested networks are searched, except those inside instances,
+	/// which are copies of their master's network.
+	pub fn component_nodes(&self) -> Vec<(Vec<NodeId>, NodeId, ComponentLink)> {
+		let mut found = Vec::new();
+		let mut network_paths = vec![Vec::new()];
+
+		while let Some(network_path) = network_paths.pop() {
+			let Some(network) = self.nested_network(&network_path) else { continue };
+
+			for (node_id, node) in &network.nodes {
+				let component = self.component(node_id, &network_path).cloned();
+				let is_instance = component.as_ref().is_some_and(|component| !component.is_master());
+				if let Some(component) = component {
+					found.push((network_path.clone(), *node_id, component));
+				}
+
+				if !is_instance && matches!(node.implementation, DocumentNodeImplementation::Network(_)) {
+					network_paths.push([network_path.as_slice(), &[*node_id]].concat());
+				}
+			}
+		}
+
+		found
+	}
+
+	/// The network path and node ID of the component's master, if it still exists.
+	pub fn component_master(&self, component_id: u64) -> Option<(Vec<NodeId>, NodeId)> {
+		self.component_nodes()
+			.into_iter()
+			.find(|(_, _, component)| component.component_id == component_id && component.is_master())
+			.map(|(network_path, node_id, _)| (network_path, node_id))
+	}
+
+	/// Whether the node is a component instance, whose nested network is a copy of its master's that any edit made inside would be overwritten by.
+	pub fn is_component_instance(&self, node_id: &NodeId, network_path: &[NodeId]) -> bool {
+		self.component(node_id, network_path).is_some_and(|component| !component.is_master())
+	}
+
+	/// Record that an instance's input was set directly, so it stops following the master's value for that input.
+	pub(super) fn mark_component_input_overridden(&mut self, node_id: &NodeId, input_index: usize, network_path: &[NodeId]) {
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else { return };
+		let Some(ComponentLink {
+			role: ComponentRole::Instance { overridden_inputs },
+			..
+		}) = node_metadata.persistent_metadata.component.as_mut()
+		else {
+			return;
+		};
+
+		if !overridden_inputs.contains(&input_index) {
+			overridden_inputs.push(input_index);
+			overridden_inputs.sort_unstable();
+		}
+	}
+
+	/// Copy a master pasted or duplicated next to an existing master of the same component into an instance, so each component keeps a single master.
+	pub(super) fn demote_duplicate_master(&self, node_template: &mut NodeTemplate) {
+		let Some(component) = node_template.persistent_node_metadata.component.as_mut() else { return };
+		if component.is_master() && self.component_master(component.component_id).is_some() {
+			*component = ComponentLink::instance(component.component_id);
+		}
+	}
+
+	/// Build the master node for a new component from sibling layers in the document network, ordered top to bottom.
+	/// The master is a layer shaped like a Merge layer whose nested network holds a copy of the layers and everything upstream of them,
+	/// stacked on top of the master's own content input. The caller inserts it and deletes the original layers.
+	pub fn component_template(&mut self, layers: &[LayerNodeIdentifier], component_id: u64) -> Option<NodeTemplate> {
+		let Some(merge_definition) = resolve_network_node_type("Merge") else {
+			log::error!("Could not get the Merge definition in component_template");
+			return None;
+		};
+		let mut template = merge_definition.default_node_template();
+
+		let mut copy_ids = HashMap::new();
+		for layer in layers {
+			copy_ids.insert(layer.to_node(), NodeId::new());
+			for upstream_id in self.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], FlowType::LayerChildrenUpstreamFlow) {
+				copy_ids.entry(upstream_id).or_insert_with(NodeId::new);
+			}
+		}
+		let mut copies = self.copy_nodes(&copy_ids, &[]).collect::<HashMap<_, _>>();
+
+		// Restack the copies in their original order, even if unselected layers sat between them, with the lowest resting on the master's content input
+		let new_layer_ids = layers.iter().filter_map(|layer| copy_ids.get(&layer.to_node()).copied()).collect::<Vec<_>>();
+		for (index, new_layer_id) in new_layer_ids.iter().enumerate() {
+			let below = match new_layer_ids.get(index + 1) {
+				Some(below_id) => NodeInput::node(*below_id, 0),
+				None => NodeInput::import(generic!(T), 1),
+			};
+			if let Some(input) = copies.get_mut(new_layer_id).and_then(|copy| copy.document_node.inputs.first_mut()) {
+				*input = below;
+			}
+		}
+		let top_layer_id = *new_layer_ids.first()?;
+
+		let DocumentNodeImplementation::Network(network) = &mut template.document_node.implementation else {
+			log::error!("The Merge definition should be implemented by a network");
+			return None;
+		};
+		let network_metadata = template.persistent_node_metadata.network_metadata.get_or_insert_with(Default::default);
+
+		// The Merge network's second node wraps the content input, which now comes from the copied layers instead
+		let Some(content) = network.nodes.get_mut(&NodeId(1)).and_then(|node| node.inputs.first_mut()) else {
+			log::error!("The Merge network should wrap its content in node 1");
+			return None;
+		};
+		*content = NodeInput::node(top_layer_id, 0);
+
+		for (node_id, copy) in copies {
+			network.nodes.insert(node_id, copy.document_node);
+			let node_metadata = DocumentNodeMetadata {
+				persistent_metadata: copy.persistent_node_metadata,
+				..Default::default()
+			};
+			network_metadata.persistent_metadata.node_metadata.insert(node_id, node_metadata);
+		}
+
+		// No longer a plain Merge, so it must not be reset to the definition
+		network_metadata.persistent_metadata.reference = None;
+
+		let display_name = match layers {
+			[layer] => self.display_name(&layer.to_node(), &[]),
+			_ => String::new(),
+		};
+		template.persistent_node_metadata.display_name = if display_name.is_empty() { "Component".to_string() } else { display_name };
+		template.persistent_node_metadata.component = Some(ComponentLink::master(component_id));
+
+		Some(template)
+	}
+
+	/// Build a new instance of a component from its master. Its inputs start out with the master's values.
+	pub fn component_instance_template(&mut self, component_id: u64) -> Option<NodeTemplate> {
+		let (master_path, master_id) = self.component_master(component_id)?;
+
+		let instance_id = NodeId::new();
+		let copy_ids = HashMap::from([(master_id, instance_id)]);
+		let (_, mut template) = self.copy_nodes(&copy_ids, &master_path).next()?;
+		template.persistent_node_metadata.component = Some(ComponentLink::instance(component_id));
+
+		Some(template)
+	}
+
+	/// Bring every instance in line with its master if the network was modified since they last were. The nested network and its metadata
+	/// are copied over, input slots are added or removed to match, and each input that isn't overridden takes the master's value.
+	/// Instances whose master no longer exists are left as they are. Returns whether anything changed.
+	pub fn sync_modified_component_instances(&mut self) -> bool {
+		if !std::mem::take(&mut self.components_unsynced) {
+			return false;
+		}
+
+		let components = self.component_nodes();
+		let masters = components
+			.iter()
+			.filter(|(_, _, component)| component.is_master())
+			.map(|(network_path, node_id, component)| (component.component_id, (network_path.clone(), *node_id)))
+			.collect::<HashMap<_, _>>();
+
+		let mut changed = false;
+		for (network_path, node_id, component) in &components {
+			let ComponentRole::Instance { overridden_inputs } = &component.role else { continue };
+			let Some((master_path, master_id)) = masters.get(&component.component_id) else { continue };
+
+			changed |= self.sync_component_instance(node_id, network_path, master_id, master_path, overridden_inputs);
+		}
+
+		// The instances were just synced, so modifying them doesn't call for another sync
+		self.components_unsynced = false;
+		changed
+	}
+
+	fn sync_component_instance(&mut self, node_id: &NodeId, network_path: &[NodeId], master_id: &NodeId, master_path: &[NodeId], overridden_inputs: &[usize]) -> bool {
+		let (Some(master), Some(master_metadata)) = (self.document_node(master_id, master_path), self.node_metadata(master_id, master_path)) else {
+			return false;
+		};
+		let implementation = master.implementation.clone();
+		let master_inputs = master.inputs.clone();
+		let metadata = master_metadata.persistent_metadata.clone();
+
+		let inputs = master_inputs
+			.iter()
+			.enumerate()
+			.map(|(input_index, master_input)| match master_input {
+				NodeInput::Value { .. } => master_input.clone(),
+				_ => NodeInput::value(self.tagged_value_from_input(&InputConnector::node(*master_id, input_index), master_path), true),
+			})
+			.collect();
+		let source = LinkedSource { implementation, inputs, metadata };
+
+		// Wired master inputs belong to where the master sits in its own network, so only its values are inherited
+		let inherit_value = |input_index: usize| matches!(master_inputs[input_index], NodeInput::Value { .. }) && !overridden_inputs.contains(&input_index);
+		self.sync_linked_node(node_id, network_path, source, inherit_value)
+	}
+
+	/// Bring a linked node in line with its source. The implementation and the input and output metadata are copied over, and input slots are
+	/// added or removed to match, with new slots taking the source's value. Existing value inputs take the source's value where `inherit_value` allows it.
+	/// Returns whether anything changed.
+	pub(super) fn sync_linked_node(&mut self, node_id: &NodeId, network_path: &[NodeId], source: LinkedSource, inherit_value: impl Fn(usize) -> bool) -> bool {
+		let LinkedSource {
+			implementation: source_implementation,
+			inputs: source_inputs,
+			metadata: source_metadata,
+		} = source;
+		let source_outputs = match &source_implementation {
+			DocumentNodeImplementation::Network(network) => network.exports.len(),
+			DocumentNodeImplementation::ProtoNode(_) | DocumentNodeImplementation::Extract => 1,
+		};
+
+		let Some(instance) = self.document_node(node_id, network_path) else { return false };
+		let implementation_changed = instance.implementation != source_implementation;
+		let instance_inputs = instance.inputs.len();
+
+		// Disconnect wires from outputs and inputs the source no longer has before those slots disappear
+		for output_index in source_outputs..self.number_of_outputs(node_id, network_path) {
+			let downstream = self
+				.outward_wires(network_path)
+				.and_then(|outward_wires| outward_wires.get(&OutputConnector::node(*node_id, output_index)))
+				.cloned()
+				.unwrap_or_default();
+			for input_connector in downstream {
+				self.disconnect_input(&input_connector, network_path);
+			}
+		}
+		for input_index in source_inputs.len()..instance_inputs {
+			if matches!(self.input_from_connector(&InputConnector::node(*node_id, input_index), network_path), Some(NodeInput::Node { .. })) {
+				self.disconnect_input(&InputConnector::node(*node_id, input_index), network_path);
+			}
+		}
+
+		let Some(mut inputs) = self.document_node(node_id, network_path).map(|instance| instance.inputs.clone()) else {
+			return false;
+		};
+		inputs.truncate(source_inputs.len());
+		for (input_index, source_input) in source_inputs.into_iter().enumerate() {
+			match inputs.get_mut(input_index) {
+				None => inputs.push(source_input),
+				Some(input @ NodeInput::Value { .. }) if inherit_value(input_index) => *input = source_input,
+				Some(_) => {}
+			}
+		}
+
+		let Some(instance_metadata) = self.node_metadata(node_id, network_path).map(|metadata| &metadata.persistent_metadata) else {
+			return false;
+		};
+		let inputs_changed = self.document_node(node_id, network_path).is_none_or(|instance| instance.inputs != inputs);
+		let metadata_changed = instance_metadata.input_metadata != source_metadata.input_metadata || instance_metadata.output_names != source_metadata.output_names;
+		if !implementation_changed && !inputs_changed && !metadata_changed {
+			return false;
+		}
+
+		let Some(instance) = self.network_mut(network_path).and_then(|network| network.nodes.get_mut(node_id)) else {
+			return false;
+		};
+		instance.inputs = inputs;
+		if implementation_changed {
+			instance.implementation = source_implementation;
+		}
+
+		let Some(instance_metadata) = self.node_metadata_mut(node_id, network_path) else { return false };
+		let persistent_metadata = &mut instance_metadata.persistent_metadata;
+		persistent_metadata.input_metadata = source_metadata.input_metadata;
+		persistent_metadata.output_names = source_metadata.output_names;
+		if implementation_changed {
+			persistent_metadata.network_metadata = source_metadata.network_metadata;
+		}
+
+		self.transaction_modified();
+
+		self.unload_outward_wires(network_path);
+		self.unload_node_click_targets(node_id, network_path);
+		self.unload_all_nodes_bounding_box(network_path);
+
+		let nested_path = [network_path, &[*node_id]].concat();
+		self.unload_outward_wires(&nested_path);
+		self.unload_import_export_ports(&nested_path);
+		self.unload_modify_import_export(&nested_path);
+		self.unload_all_nodes_click_targets(&nested_path);
+		self.unload_all_nodes_bounding_box(&nested_path);
+
+		true
+	}
+}
@@ -149,6 +149,10 @@ impl From<DocumentNodePersistentMetadataStringReference> for DocumentNodePersist
 			pinned: old.pinned,
 			node_type_metadata: old.node_type_metadata,
 			network_metadata: old.network_metadata,
+			component: None,
+			library_export: None,
+			library_link: None,
+			layout_grid: None,
 		}
 	}
 }
@@ -0,0 +1,162 @@
+//! Node libraries: a `.gdd` document offers some of its document network nodes to other documents through its exported nodes list,
+//! and a document importing it as a library places copies of them which stay linked to the library node and the version they came from.
+
+use glam::IVec2;
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeInput};
+
+use super::components::LinkedSource;
+use super::{InputConnector, NodeNetworkInterface, NodeTemplate, NodeTypePersistentMetadata};
+
+/// Marks a document network node as part of this document's library API, under the name and catalog category it is offered with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LibraryExport {
+	pub name: String,
+	/// Empty to list the node under the library's own name.
+	#[serde(default)]
+	pub category: String,
+}
+
+/// Links a node placed from an imported library back to the library node it is a copy of.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LibraryLink {
+	/// The library document's ID.
+	pub library_id: u64,
+	/// The stable storage ID of the exported node within the library document.
+	pub node: u64,
+	/// The library document's revision the node was last updated from, empty if the library had no saved history.
+	pub version: String,
+	/// Pinned nodes stay on their version when the library's updates are pulled in.
+	#[serde(default)]
+	pub pinned: bool,
+}
+
+impl NodeNetworkInterface {
+	pub fn library_export(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<&LibraryExport> {
+		self.node_metadata(node_id, network_path)?.persistent_metadata.library_export.as_ref()
+	}
+
+	/// Only document network nodes implemented by a network can be exported, since an importing document places them by copying their network.
+	pub fn can_export_to_library(&self, node_id: &NodeId, network_path: &[NodeId]) -> bool {
+		network_path.is_empty() && matches!(self.implementation(node_id, network_path), Some(DocumentNodeImplementation::Network(_)))
+	}
+
+	pub fn set_library_export(&mut self, node_id: &NodeId, network_path: &[NodeId], library_export: Option<LibraryExport>) {
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node metadata in set_library_export");
+			return;
+		};
+		node_metadata.persistent_metadata.library_export = library_export;
+		self.transaction_modified();
+	}
+
+	pub fn library_link(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<&LibraryLink> {
+		self.node_metadata(node_id, network_path)?.persistent_metadata.library_link.as_ref()
+	}
+
+	pub fn set_library_link(&mut self, node_id: &NodeId, network_path: &[NodeId], library_link: Option<LibraryLink>) {
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node metadata in set_library_link");
+			return;
+		};
+		node_metadata.persistent_metadata.library_link = library_link;
+		self.transaction_modified();
+	}
+
+	pub fn set_library_link_pinned(&mut self, node_id: &NodeId, network_path: &[NodeId], pinned: bool) {
+		let Some(library_link) = self
+			.node_metadata_mut(node_id, network_path)
+			.and_then(|node_metadata| node_metadata.persistent_metadata.library_link.as_mut())
+		else {
+			log::error!("Could not get library link in set_library_link_pinned");
+			return;
+		};
+		library_link.pinned = pinned;
+		self.transaction_modified();
+	}
+
+	/// Every node placed from a library as `(network_path, node_id, link)`. Nested networks are searched, except those inside library nodes,
+	/// which are copies of the library's network. The search is cached until the network is next modified.
+	pub fn library_nodes(&mut self) -> &[(Vec<NodeId>, NodeId, LibraryLink)] {
+		if self.library_nodes.is_none() {
+			self.library_nodes = Some(self.find_library_nodes());
+		}
+		self.library_nodes.as_deref().unwrap_or_default()
+	}
+
+	fn find_library_nodes(&self) -> Vec<(Vec<NodeId>, NodeId, LibraryLink)> {
+		let mut found = Vec::new();
+		let mut network_paths = vec![Vec::new()];
+
+		while let Some(network_path) = network_paths.pop() {
+			let Some(network) = self.nested_network(&network_path) else { continue };
+
+			for (node_id, node) in &network.nodes {
+				let library_link = self.library_link(node_id, &network_path).cloned();
+				let is_linked = library_link.is_some();
+				if let Some(library_link) = library_link {
+					found.push((network_path.clone(), *node_id, library_link));
+				}
+
+				if !is_linked && matches!(node.implementation, DocumentNodeImplementation::Network(_)) {
+					network_paths.push([network_path.as_slice(), &[*node_id]].concat());
+				}
+			}
+		}
+
+		found
+	}
+
+	/// Build the template another document places to use an exported document network node of this library document. Wired inputs become
+	/// values, since the node's upstream stays behind in the library, and the copy is linked back to the exported node through `library_link`.
+	pub fn library_node_template(&mut self, node_id: &NodeId, display_name: String, library_link: LibraryLink) -> Option<NodeTemplate> {
+		let mut template = self.create_node_template(node_id, &[])?;
+
+		for (input_index, input) in template.document_node.inputs.iter_mut().enumerate() {
+			if !matches!(input, NodeInput::Value { .. }) {
+				*input = NodeInput::value(self.tagged_value_from_input(&InputConnector::node(*node_id, input_index), &[]), true);
+			}
+		}
+
+		let metadata = &mut template.persistent_node_metadata;
+		metadata.node_type_metadata = if metadata.is_layer() {
+			NodeTypePersistentMetadata::layer(IVec2::ZERO)
+		} else {
+			NodeTypePersistentMetadata::node(IVec2::ZERO)
+		};
+		metadata.display_name = display_name;
+		metadata.component = None;
+		metadata.library_export = None;
+		metadata.library_link = Some(library_link);
+
+		Some(template)
+	}
+
+	/// Bring a library node in line with the library's current version of it, given as the template the library offers for placing it.
+	/// The implementation and input and output metadata are replaced, while the node keeps the values and wires of the inputs it already had.
+	/// Returns whether anything changed.
+	pub fn update_library_node(&mut self, node_id: &NodeId, network_path: &[NodeId], template: NodeTemplate) -> bool {
+		let Some(LibraryLink { version, .. }) = template.persistent_node_metadata.library_link.clone() else {
+			log::error!("Library node templates should carry their library link");
+			return false;
+		};
+
+		let source = LinkedSource {
+			implementation: template.document_node.implementation,
+			inputs: template.document_node.inputs,
+			metadata: template.persistent_node_metadata,
+		};
+		let mut changed = self.sync_linked_node(node_id, network_path, source, |_| false);
+
+		if let Some(library_link) = self
+			.node_metadata_mut(node_id, network_path)
+			.and_then(|node_metadata| node_metadata.persistent_metadata.library_link.as_mut())
+			&& library_link.version != version
+		{
+			library_link.version = version;
+			changed = true;
+			self.transaction_modified();
+		}
+
+		changed
+	}
+}
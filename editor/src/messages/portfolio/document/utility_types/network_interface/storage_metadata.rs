@@ -6,18 +6,22 @@
 //! resolves bare calls to the inherent ones.
 
 use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
 use document_graph_storage::attr::session;
-use document_graph_storage::{InputMetadataEntry, NetworkMetadataEntry, NodeMetadataEntry, NodeMetadataSource, Position};
+use document_graph_storage::{ComponentEntry, InputMetadataEntry, LibraryExportEntry, LibraryLinkEntry, NetworkMetadataEntry, NodeMetadataEntry, NodeMetadataSource, Position};
 use glam::IVec2;
 use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeNetwork};
 use graphene_std::vector::style::RenderMode;
 
+use super::components::{ComponentLink, ComponentRole};
+use super::library::{LibraryExport, LibraryLink};
 use super::memo_network::MemoNetwork;
 use super::{
 	DocumentNodePersistentMetadata, DocumentNodeTransientMetadata, InputMetadata, InputPersistentMetadata, LayerPosition, NavigationMetadata, NodeNetworkInterface, NodeNetworkMetadata,
 	NodePersistentMetadata, NodePosition, NodeTypePersistentMetadata, PTZ, Previewing,
 };
+use crate::messages::portfolio::document::guide::GuideMessageHandler;
 use crate::messages::portfolio::document::overlays::utility_types::OverlaysVisibilitySettings;
 use crate::messages::portfolio::document::utility_types::misc::SnappingState;
 use crate::messages::portfolio::document::utility_types::nodes::CollapsedLayers;
@@ -30,15 +34,17 @@ pub enum InterfaceRebuildError {
 }
 
 /// Per-peer view settings persisted in `session.json` under `ui::doc::*` (viewport view, render mode,
-/// overlay/ruler visibility, snapping, collapsed layers). Not part of the registry/CRDT/history; see
+/// overlay/ruler visibility, guides, snapping, collapsed layers, linked library paths). Not part of the registry/CRDT/history; see
 /// [`DocumentSettings::to_view_map`].
 pub struct DocumentSettings<'a> {
 	pub document_ptz: &'a PTZ,
 	pub render_mode: &'a RenderMode,
 	pub overlays_visibility: &'a OverlaysVisibilitySettings,
 	pub rulers_visible: bool,
+	pub guides: &'a GuideMessageHandler,
 	pub snapping_state: &'a SnappingState,
 	pub collapsed: &'a CollapsedLayers,
+	pub library_paths: &'a BTreeMap<u64, PathBuf>,
 }
 
 /// Adapts a `&NodeNetworkInterface` to `document-graph-storage`'s `NodeMetadataSource` (node/network metadata
@@ -114,6 +120,27 @@ impl NodeMetadataSource for StorageMetadataView<'_> {
 		self.persistent(network_path, local_id).map(|p| p.output_names.clone()).unwrap_or_default()
 	}
 
+	fn component(&self, network_path: &[NodeId], local_id: NodeId) -> Option<ComponentEntry> {
+		self.persistent(network_path, local_id)?.component.as_ref().map(component_link_to_entry)
+	}
+
+	fn library_export(&self, network_path: &[NodeId], local_id: NodeId) -> Option<LibraryExportEntry> {
+		let library_export = self.persistent(network_path, local_id)?.library_export.as_ref()?;
+		Some(LibraryExportEntry {
+			name: library_export.name.clone(),
+			category: library_export.category.clone(),
+		})
+	}
+
+	fn library_link(&self, network_path: &[NodeId], local_id: NodeId) -> Option<LibraryLinkEntry> {
+		self.persistent(network_path, local_id)?.library_link.as_ref().map(library_link_to_entry)
+	}
+
+	fn layout_grid(&self, network_path: &[NodeId], local_id: NodeId) -> Option<serde_json::Value> {
+		let layout_grid = self.persistent(network_path, local_id)?.layout_grid.as_ref()?;
+		serde_json::to_value(layout_grid).ok()
+	}
+
 	fn reference(&self, network_path: &[NodeId]) -> Option<&str> {
 		let network_metadata = self.interface.network_metadata.nested_metadata(network_path)?;
 		network_metadata.persistent_metadata.reference.as_deref()
@@ -129,8 +156,10 @@ impl DocumentSettings<'_> {
 			(session::doc::RENDER_MODE, serde_json::to_value(self.render_mode)),
 			(session::doc::OVERLAYS, serde_json::to_value(self.overlays_visibility)),
 			(session::doc::RULERS_VISIBLE, serde_json::to_value(self.rulers_visible)),
+			(session::doc::GUIDES, serde_json::to_value(self.guides)),
 			(session::doc::SNAPPING, serde_json::to_value(self.snapping_state)),
 			(session::doc::COLLAPSED, serde_json::to_value(self.collapsed)),
+			(session::doc::LIBRARY_PATHS, serde_json::to_value(self.library_paths)),
 		];
 
 		entries
@@ -360,6 +389,14 @@ fn apply_entries_into_tree(network: &NodeNetwork, metadata: &mut NodeNetworkMeta
 				persistent.output_names = entry.output_names;
 			}
 
+			persistent.component = entry.component.map(component_entry_to_runtime);
+			persistent.library_export = entry.library_export.map(|library_export| LibraryExport {
+				name: library_export.name,
+				category: library_export.category,
+			});
+			persistent.library_link = entry.library_link.map(library_link_entry_to_runtime);
+			persistent.layout_grid = entry.layout_grid.and_then(|layout_grid| serde_json::from_value(layout_grid).ok());
+
 			document_node_metadata.transient_metadata = DocumentNodeTransientMetadata::default();
 		}
 	}
@@ -378,6 +415,50 @@ fn nested_network_at<'a>(root: &'a NodeNetwork, path: &[NodeId]) -> Option<&'a N
 	Some(current)
 }
 
+fn component_link_to_entry(component: &ComponentLink) -> ComponentEntry {
+	let overridden_inputs = match &component.role {
+		ComponentRole::Master => Vec::new(),
+		ComponentRole::Instance { overridden_inputs } => overridden_inputs.iter().map(|&index| index as u32).collect(),
+	};
+	ComponentEntry {
+		component_id: component.component_id,
+		master: component.is_master(),
+		overridden_inputs,
+	}
+}
+
+fn component_entry_to_runtime(entry: ComponentEntry) -> ComponentLink {
+	let role = if entry.master {
+		ComponentRole::Master
+	} else {
+		ComponentRole::Instance {
+			overridden_inputs: entry.overridden_inputs.into_iter().map(|index| index as usize).collect(),
+		}
+	};
+	ComponentLink {
+		component_id: entry.component_id,
+		role,
+	}
+}
+
+fn library_link_to_entry(library_link: &LibraryLink) -> LibraryLinkEntry {
+	LibraryLinkEntry {
+		library_id: library_link.library_id,
+		node: document_graph_storage::NodeId(library_link.node),
+		version: library_link.version.clone(),
+		pinned: library_link.pinned,
+	}
+}
+
+fn library_link_entry_to_runtime(entry: LibraryLinkEntry) -> LibraryLink {
+	LibraryLink {
+		library_id: entry.library_id,
+		node: entry.node.0,
+		version: entry.version,
+		pinned: entry.pinned,
+	}
+}
+
 /// Storage-side `None` restores as `""` (the runtime's "unset" sentinel for `input_name` / `input_description`).
 fn input_metadata_entry_to_runtime(entry: InputMetadataEntry) -> InputMetadata {
 	InputMetadata {
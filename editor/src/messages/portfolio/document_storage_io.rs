@@ -1,17 +1,19 @@
 //! Asynchronous `.gdd` working-copy IO, spawned by `PortfolioMessageHandler` as `FutureMessage`s:
-//! building/opening containers, opening `.gdd` archives into documents, and rebuilding the interface
-//! from the undo/redo cursor. The `validate` flag is the `validate_storage_round_trip` preference; when
-//! set, the registry build is compared against the legacy oracle (logged, not fatal) for the soak.
+//! building/opening containers, opening `.gdd` archives into documents or reading them as node libraries,
+//! and rebuilding the interface from the undo/redo cursor. The `validate` flag is the `validate_storage_round_trip`
+//! preference; when set, the registry build is compared against the legacy oracle (logged, not fatal) for the soak.
 
 use document_container::AnyContainer;
 use document_format::{Error as DocumentFormatError, GddV1, GddV1Layout};
 use graph_craft::application_io::resource::{LoadResource, ResourceStorage};
-use graph_craft::document::NodeNetwork;
+use graph_craft::document::{NodeId, NodeNetwork};
 
 use super::document::DocumentMessageHandler;
 use super::document::diff_networks;
+use super::document::utility_types::network_interface::library::LibraryLink;
 use super::document::utility_types::network_interface::storage_metadata::{apply_network_view_settings, build_interface_from_storage, network_ids_from_entries};
 use super::document_migration::document_migration_string_preprocessing;
+use super::node_library::{LibraryNode, NodeLibrary};
 use super::portfolio_message::PortfolioMessage;
 use crate::messages::message::Message;
 use crate::messages::portfolio::document::utility_types::misc::DocumentId;
@@ -128,19 +130,7 @@ async fn build_document_from_gdd(path: Option<&std::path::Path>, content: &[u8],
 	};
 
 	// Extract archived resource bytes into the global cache so declarations + runtime resolve.
-	match gdd.resource_hashes().await {
-		Ok(hashes) => {
-			for hash in hashes {
-				match gdd.read_resource(&hash).await {
-					Ok(holder) => {
-						store_handle.store(holder.as_slice());
-					}
-					Err(error) => log::error!("Opening .gdd for {document_id:?}: failed to read resource {hash}: {error}"),
-				}
-			}
-		}
-		Err(error) => log::error!("Opening .gdd for {document_id:?}: failed to list resources: {error}"),
-	}
+	store_archived_resources(&gdd, store_handle, &format!("Opening .gdd for {document_id:?}")).await;
 
 	let legacy_document = gdd
 		.read_legacy_document()
@@ -194,6 +184,120 @@ async fn build_document_from_gdd(path: Option<&std::path::Path>, content: &[u8],
 	legacy_document
 }
 
+/// Copy every resource archived in `gdd` into the global cache. Resources are content-addressed, so bytes another document already
+/// stored (such as a proto-node declaration shared with an imported library) are kept once. `context` prefixes the logged errors.
+async fn store_archived_resources(gdd: &GddV1, store_handle: &impl ResourceStorage, context: &str) {
+	match gdd.resource_hashes().await {
+		Ok(hashes) => {
+			for hash in hashes {
+				match gdd.read_resource(&hash).await {
+					Ok(holder) => {
+						store_handle.store(holder.as_slice());
+					}
+					Err(error) => log::error!("{context}: failed to read resource {hash}: {error}"),
+				}
+			}
+		}
+		Err(error) => log::error!("{context}: failed to list resources: {error}"),
+	}
+}
+
+/// `FutureMessage` that reads a `.gdd` archive as a node library, delivered via [`PortfolioMessage::NodeLibraryLoaded`]
+/// (`None` library on failure, logged here). See [`build_node_library`] for the build itself.
+/// A document that exports no nodes isn't a library, so it's opened for editing instead.
+pub(super) async fn load_node_library(path: std::path::PathBuf, content: Vec<u8>, store_handle: ResourcesHandle) -> Message {
+	let library = build_node_library(&path, &content, &store_handle).await;
+
+	if library.as_ref().is_some_and(|library| library.nodes.is_empty()) {
+		let open = PortfolioMessage::OpenGddDocument {
+			document_name: None,
+			document_path: Some(path.clone()),
+			content,
+		};
+		let loaded = PortfolioMessage::NodeLibraryLoaded { path, library: library.map(Box::new) };
+		return Message::Batched {
+			messages: Box::new([loaded.into(), open.into()]),
+		};
+	}
+
+	Message::Portfolio(PortfolioMessage::NodeLibraryLoaded { path, library: library.map(Box::new) })
+}
+
+/// Open the archive in memory, rebuild its interface from the stored registry, and turn each exported document network node into a template
+/// linked to the library's ID and current revision. The library document itself isn't opened for editing.
+async fn build_node_library(path: &std::path::Path, content: &[u8], store_handle: &impl ResourceStorage) -> Option<NodeLibrary> {
+	let context = format!("Importing .gdd library {}", path.display());
+
+	let (container, _exists) = match build_per_document_container(None).await {
+		Ok(result) => result,
+		Err(error) => {
+			log::error!("{context}: failed to build container: {error}");
+			return None;
+		}
+	};
+	let gdd = match GddV1::open_from_archive(content, container, GddV1Layout).await {
+		Ok(gdd) => gdd,
+		Err(error) => {
+			log::error!("{context}: failed to open archive: {error}");
+			return None;
+		}
+	};
+
+	store_archived_resources(&gdd, store_handle, &context).await;
+
+	let declarations = gdd.declarations(store_handle).await;
+	let mut interface = match gdd.registry().to_runtime_with_full_metadata(&declarations) {
+		Ok((network, node_entries, network_entries)) => match build_interface_from_storage(network, node_entries, network_entries) {
+			Ok(interface) => interface,
+			Err(error) => {
+				log::error!("{context}: failed to build interface: {error}");
+				return None;
+			}
+		},
+		Err(error) => {
+			log::error!("{context}: failed to convert registry to runtime: {error}");
+			return None;
+		}
+	};
+
+	let library_id = gdd.manifest().document_id;
+	// Hex since a revision doesn't fit the JSON numbers node metadata is stored as
+	let revision_version = |rev: document_graph_storage::Rev| format!("{:032x}", rev.get());
+	let version = gdd.session().head_rev().map(revision_version).unwrap_or_default();
+	let ancestry = gdd.session().reachable_deltas().map(|delta| revision_version(delta.id)).collect();
+
+	let nodes = gdd
+		.registry()
+		.library_exports()
+		.into_iter()
+		.filter_map(|export| {
+			let library_link = LibraryLink {
+				library_id,
+				node: export.node.0,
+				version: version.clone(),
+				pinned: false,
+			};
+			let template = interface.library_node_template(&NodeId(export.local_id), export.name.clone(), library_link)?;
+			Some(LibraryNode {
+				node: export.node.0,
+				name: export.name,
+				category: export.category,
+				template,
+			})
+		})
+		.collect::<Vec<_>>();
+
+	let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+	Some(NodeLibrary {
+		library_id,
+		name,
+		version,
+		ancestry,
+		path: path.to_path_buf(),
+		nodes,
+	})
+}
+
 /// Soak check that the reopened `.gdd`'s stored registry, converted back to a runtime network, matches
 /// the legacy load. Logs divergence only (legacy stays authoritative); runs once per open.
 pub(super) async fn compare_storage_against_runtime(gdd: &GddV1, legacy_network: &NodeNetwork, byte_store: &dyn LoadResource, document_id: DocumentId) {
@@ -5,6 +5,7 @@ pub mod document;
 pub mod document_migration;
 pub mod document_storage_io;
 pub mod fonts;
+pub mod node_library;
 pub mod persistent_state;
 pub mod utility_types;
 
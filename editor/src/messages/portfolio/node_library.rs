@@ -0,0 +1,203 @@
+//! Node libraries imported from `.gdd` documents. Each library offers the nodes its document exports, which are listed in the node catalog
+//! alongside the built-in definitions and placed as copies linked back to the library node and version they came from.
+
+use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
+use crate::messages::portfolio::document::node_graph::utility_types::FrontendNodeType;
+use crate::messages::portfolio::document::utility_types::network_interface::NodeTemplate;
+use crate::messages::portfolio::document::utility_types::network_interface::library::LibraryLink;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Prefix of the catalog identifiers of library nodes, followed by the library ID and the exported node's storage ID.
+const LIBRARY_IDENTIFIER_PREFIX: &str = "library:";
+
+#[derive(Debug, Clone)]
+pub struct NodeLibrary {
+	/// The library document's ID, which stays the same across its saved versions.
+	pub library_id: u64,
+	/// Shown as the catalog category of exported nodes that don't name their own.
+	pub name: String,
+	/// The revision the library was loaded at, recorded by nodes placed from it.
+	pub version: String,
+	/// Every revision `version` descends from, including itself.
+	pub ancestry: HashSet<String>,
+	/// Where the library document was imported from on this machine.
+	pub path: PathBuf,
+	pub nodes: Vec<LibraryNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LibraryNode {
+	/// The exported node's stable storage ID within the library document.
+	pub node: u64,
+	pub name: String,
+	pub category: String,
+	pub template: NodeTemplate,
+}
+
+impl NodeLibrary {
+	pub fn identifier(&self, node: &LibraryNode) -> DefinitionIdentifier {
+		DefinitionIdentifier::Network(format!("{LIBRARY_IDENTIFIER_PREFIX}{:016x}:{}", self.library_id, node.node))
+	}
+
+	fn node(&self, node: u64) -> Option<&LibraryNode> {
+		self.nodes.iter().find(|library_node| library_node.node == node)
+	}
+
+	/// Whether the loaded revision is `version` or a later edit of it. Every revision descends from an empty version, recorded when the library had no saved history.
+	fn descends_from(&self, version: &str) -> bool {
+		version.is_empty() || self.ancestry.contains(version)
+	}
+}
+
+/// Every node library imported during this session, shared by all open documents.
+#[derive(Debug, Default)]
+pub struct NodeLibraries {
+	libraries: Vec<NodeLibrary>,
+}
+
+impl NodeLibraries {
+	/// Add a library, replacing a previously imported version of the same library document.
+	pub fn insert(&mut self, library: NodeLibrary) {
+		match self.libraries.iter_mut().find(|existing| existing.library_id == library.library_id) {
+			Some(existing) => *existing = library,
+			None => self.libraries.push(library),
+		}
+	}
+
+	pub fn library(&self, library_id: u64) -> Option<&NodeLibrary> {
+		self.libraries.iter().find(|library| library.library_id == library_id)
+	}
+
+	/// Catalog entries for every exported node, grouped under their own category or else the library's name.
+	pub fn node_types(&self) -> Vec<FrontendNodeType> {
+		self.libraries
+			.iter()
+			.flat_map(|library| {
+				library.nodes.iter().map(|node| {
+					let input_types = node
+						.template
+						.document_node
+						.inputs
+						.iter()
+						.map(|input| input.as_value().map(|value| value.ty().nested_type().to_string()).unwrap_or_default())
+						.collect();
+					FrontendNodeType {
+						identifier: library.identifier(node).serialized(),
+						name: node.name.clone(),
+						category: if node.category.is_empty() { library.name.clone() } else { node.category.clone() },
+						input_types,
+					}
+				})
+			})
+			.collect()
+	}
+
+	/// The template placed for a catalog identifier, or `None` if it doesn't name a node of a loaded library.
+	pub fn node_template(&self, identifier: &DefinitionIdentifier) -> Option<NodeTemplate> {
+		let DefinitionIdentifier::Network(identifier) = identifier else { return None };
+		let (library_id, node) = identifier.strip_prefix(LIBRARY_IDENTIFIER_PREFIX)?.split_once(':')?;
+		let library_id = u64::from_str_radix(library_id, 16).ok()?;
+		let node = node.parse().ok()?;
+
+		self.library(library_id)?.node(node).map(|library_node| library_node.template.clone())
+	}
+
+	/// The loaded library's current template for a placed library node, if the library is loaded, still exports the node, and was loaded at
+	/// a later revision than the one the node was placed or last updated from. An older or diverged copy of the library offers no update.
+	pub fn update_for(&self, library_link: &LibraryLink) -> Option<&NodeTemplate> {
+		let library = self.library(library_link.library_id)?;
+		if library.version == library_link.version || !library.descends_from(&library_link.version) {
+			return None;
+		}
+		library.node(library_link.node).map(|library_node| &library_node.template)
+	}
+
+	/// Whether any of the given placed library nodes is unpinned and has an update in its loaded library.
+	pub fn has_updates<'a>(&self, mut library_links: impl Iterator<Item = &'a LibraryLink>) -> bool {
+		library_links.any(|library_link| !library_link.pinned && self.update_for(library_link).is_some())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const LIBRARY_ID: u64 = 0xabc;
+
+	fn template(display_name: &str) -> NodeTemplate {
+		let mut template = NodeTemplate::default();
+		template.persistent_node_metadata.display_name = display_name.to_string();
+		template
+	}
+
+	/// A library loaded at revision `c`, which was saved after `a` and `b`, exporting a single node
+	fn libraries() -> NodeLibraries {
+		let mut libraries = NodeLibraries::default();
+		libraries.insert(NodeLibrary {
+			library_id: LIBRARY_ID,
+			name: "Shapes".to_string(),
+			version: "c".to_string(),
+			ancestry: ["a", "b", "c"].into_iter().map(String::from).collect(),
+			path: PathBuf::from("shapes.gdd"),
+			nodes: vec![LibraryNode {
+				node: 7,
+				name: "Star".to_string(),
+				category: String::new(),
+				template: template("Star"),
+			}],
+		});
+		libraries
+	}
+
+	fn link(node: u64, version: &str, pinned: bool) -> LibraryLink {
+		LibraryLink {
+			library_id: LIBRARY_ID,
+			node,
+			version: version.to_string(),
+			pinned,
+		}
+	}
+
+	#[test]
+	fn node_template_resolves_library_identifiers() {
+		let libraries = libraries();
+		let library = libraries.library(LIBRARY_ID).unwrap();
+
+		assert_eq!(libraries.node_template(&library.identifier(&library.nodes[0])), Some(template("Star")));
+		assert_eq!(libraries.node_template(&DefinitionIdentifier::Network("library:0000000000000abc:8".to_string())), None);
+		assert_eq!(libraries.node_template(&DefinitionIdentifier::Network("library:0000000000000def:7".to_string())), None);
+		assert_eq!(libraries.node_template(&DefinitionIdentifier::Network("Star".to_string())), None);
+	}
+
+	#[test]
+	fn update_for_requires_a_later_revision() {
+		let libraries = libraries();
+
+		assert_eq!(libraries.update_for(&link(7, "a", false)), Some(&template("Star")));
+		assert_eq!(libraries.update_for(&link(7, "", false)), Some(&template("Star")));
+		// The node is already at the loaded revision
+		assert_eq!(libraries.update_for(&link(7, "c", false)), None);
+		// The node came from a revision the loaded one doesn't descend from, like a newer or diverged copy of the library
+		assert_eq!(libraries.update_for(&link(7, "d", false)), None);
+		// The library no longer exports the node
+		assert_eq!(libraries.update_for(&link(8, "a", false)), None);
+		// The library isn't loaded
+		assert_eq!(
+			libraries.update_for(&LibraryLink {
+				library_id: 0xdef,
+				..link(7, "a", false)
+			}),
+			None
+		);
+	}
+
+	#[test]
+	fn has_updates_skips_pinned_nodes() {
+		let libraries = libraries();
+
+		assert!(!libraries.has_updates(std::iter::empty()));
+		assert!(!libraries.has_updates([link(7, "a", true), link(7, "c", false)].iter()));
+		assert!(libraries.has_updates([link(7, "a", true), link(7, "b", false)].iter()));
+	}
+}
@@ -119,6 +119,14 @@ pub enum PortfolioMessage {
 		#[derivative(Debug = "ignore", PartialEq = "ignore", Clone(clone_with = "clone_to_none"))]
 		document: Option<Box<DocumentMessageHandler>>,
 	},
+	/// Delivers a node library read asynchronously from an imported `.gdd` archive (`None` if it couldn't be read, logged at the source).
+	/// Travels once like [`DocumentStorageMounted`](Self::DocumentStorageMounted).
+	NodeLibraryLoaded {
+		path: PathBuf,
+		#[serde(skip, default)]
+		#[derivative(Debug = "ignore", PartialEq = "ignore", Clone(clone_with = "clone_to_none"))]
+		library: Option<Box<crate::messages::portfolio::node_library::NodeLibrary>>,
+	},
 	/// Delivers the interface rebuilt from the `Gdd` undo/redo cursor so the async rebuild can swap into the
 	/// live document. `interface` is `None` if the rebuild failed (logged at the source). `had_oracle` records
 	/// whether the legacy snapshot applied synchronously, so the swap can debug-compare against it. Travels
@@ -12,9 +12,11 @@ use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::DocumentMessageContext;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
 use crate::messages::portfolio::document::node_graph::document_node_definitions;
+use crate::messages::portfolio::document::node_graph::utility_types::FrontendNodeType;
 use crate::messages::portfolio::document::utility_types::network_interface::OutputConnector;
 use crate::messages::portfolio::document_migration::*;
-use crate::messages::portfolio::document_storage_io::{build_or_open_working_copy, compare_storage_against_runtime, open_gdd_document};
+use crate::messages::portfolio::document_storage_io::{build_or_open_working_copy, compare_storage_against_runtime, load_node_library, open_gdd_document};
+use crate::messages::portfolio::node_library::NodeLibraries;
 use crate::messages::portfolio::utility_types::FileContent;
 use crate::messages::preferences::SelectionMode;
 use crate::messages::prelude::*;
@@ -68,6 +70,8 @@ pub struct PortfolioMessageHandler {
 	working_copy_root: Option<PathBuf>,
 	/// Number of document not fully loaded. While non-zero, resource GC is skipped.
 	pending_opens: usize,
+	/// Node libraries imported from `.gdd` documents, whose exported nodes are offered in the node catalog of every document.
+	pub node_libraries: NodeLibraries,
 }
 
 #[message_handler_data]
@@ -99,11 +103,13 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						preferences,
 						viewport,
 						resource_storage,
+						node_libraries: &self.node_libraries,
 						data_panel_open: self.workspace_panel_layout.is_panel_visible(PanelType::Data) && !self.workspace_panel_layout.focus_document,
 						layers_panel_open: self.workspace_panel_layout.is_panel_visible(PanelType::Layers) && !self.workspace_panel_layout.focus_document,
 						properties_panel_open: self.workspace_panel_layout.is_panel_visible(PanelType::Properties) && !self.workspace_panel_layout.focus_document,
 					};
-					document.process_message(message, responses, document_inputs)
+					document.process_message(message, responses, document_inputs);
+					document.sync_component_instances(responses);
 				}
 			}
 			PortfolioMessage::PersistentState(message) => {
@@ -159,7 +165,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				// Send the information for tooltips and categories for each node/input.
 				responses.add(FrontendMessage::SendUIMetadata {
 					node_descriptions: document_node_definitions::collect_node_descriptions(),
-					node_types: document_node_definitions::collect_node_types(),
+					node_types: self.node_types(),
 				});
 			}
 			PortfolioMessage::DocumentPassMessage { document_id, message } => {
@@ -173,11 +179,13 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						preferences,
 						viewport,
 						resource_storage,
+						node_libraries: &self.node_libraries,
 						data_panel_open: self.workspace_panel_layout.is_panel_visible(PanelType::Data) && !self.workspace_panel_layout.focus_document,
 						layers_panel_open: self.workspace_panel_layout.is_panel_visible(PanelType::Layers) && !self.workspace_panel_layout.focus_document,
 						properties_panel_open: self.workspace_panel_layout.is_panel_visible(PanelType::Properties) && !self.workspace_panel_layout.focus_document,
 					};
-					document.process_message(message, responses, document_inputs)
+					document.process_message(message, responses, document_inputs);
+					document.sync_component_instances(responses);
 				}
 			}
 			PortfolioMessage::AutoSaveActiveDocument => {
@@ -204,6 +212,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				let validate = preferences.validate_storage_round_trip;
 				let Some(document) = self.documents.get_mut(&document_id) else { return };
 
+				document.record_library_paths(&self.node_libraries);
 				document.commit_storage_snapshot(&resource_storage.resources_mut(), validate);
 
 				responses.add(PersistentStateMessage::WriteDocument {
@@ -307,6 +316,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				};
 				document.set_storage(gdd);
 				if !reopened {
+					document.record_library_paths(&self.node_libraries);
 					document.commit_storage_snapshot(&resource_storage.resources_mut(), preferences.validate_storage_round_trip);
 					document.retire_storage_interaction();
 				}
@@ -696,8 +706,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				let name = path.file_stem().map(|n| n.to_string_lossy().to_string());
 				match Self::read_file(&path, content) {
 					FileContent::Document(content) => {
-						// TODO: Consider importing a document as a node into the current document
-						// For now treat importing a document as opening it
+						// Only `.gdd` documents list exported nodes, so a legacy document can't be a node library and is opened instead
 						responses.add(PortfolioMessage::OpenDocumentFile {
 							document_name: name,
 							document_path: Some(path),
@@ -705,11 +714,10 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						});
 					}
 					FileContent::GddDocument(content) => {
-						responses.add(PortfolioMessage::OpenGddDocument {
-							document_name: name,
-							document_path: Some(path),
-							content,
-						});
+						// Suppress resource GC until the library's resources are copied into the global cache
+						self.pending_opens += 1;
+
+						responses.add(load_node_library(path, content, resource_storage.resources_mut()));
 					}
 					FileContent::Svg(svg) => {
 						responses.add(PortfolioMessage::InsertSvg {
@@ -802,6 +810,29 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				self.load_document(document, document_id, resource_storage, preferences.validate_storage_round_trip, responses);
 				responses.add(PortfolioMessage::SelectDocument { document_id });
 			}
+			PortfolioMessage::NodeLibraryLoaded { path, library } => {
+				self.pending_opens = self.pending_opens.saturating_sub(1);
+
+				let Some(library) = library.map(|boxed| *boxed) else {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Cannot import library".into(),
+						description: format!("The document '{}' could not be read as a node library.", path.display()),
+					});
+					return;
+				};
+				// A document exporting no nodes is opened instead of being imported, see `load_node_library`
+				if library.nodes.is_empty() {
+					return;
+				}
+				self.node_libraries.insert(library);
+
+				// Refresh the node catalog with the library's exported nodes
+				responses.add(FrontendMessage::SendUIMetadata {
+					node_descriptions: document_node_definitions::collect_node_descriptions(),
+					node_types: self.node_types(),
+				});
+				responses.add(MenuBarMessage::SendLayout);
+			}
 			PortfolioMessage::GddUndoRedoRebuilt { document_id, had_oracle, interface } => {
 				let Some(document) = self.documents.get_mut(&document_id) else {
 					// Document was closed before its undo/redo rebuild completed; drop the payload.
@@ -1799,6 +1830,13 @@ impl PortfolioMessageHandler {
 		}
 	}
 
+	/// The node catalog: every built-in definition followed by the exported nodes of each imported node library.
+	fn node_types(&self) -> Vec<FrontendNodeType> {
+		let mut node_types = document_node_definitions::collect_node_types();
+		node_types.extend(self.node_libraries.node_types());
+		node_types
+	}
+
 	fn read_file(path: &PathBuf, content: Vec<u8>) -> FileContent {
 		let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_lowercase();
 		match extension.as_str() {
@@ -1880,6 +1918,31 @@ impl PortfolioMessageHandler {
 				validate,
 			));
 		}
+
+		self.import_linked_libraries(document_id, responses);
+	}
+
+	/// Ask the desktop app to import the libraries the document's library nodes were placed from, when they aren't loaded yet,
+	/// so the nodes can be updated after the editor restarts.
+	fn import_linked_libraries(&mut self, document_id: DocumentId, responses: &mut VecDeque<Message>) {
+		if !Editor::environment().is_desktop() {
+			return;
+		}
+		let Some(document) = self.documents.get_mut(&document_id) else { return };
+
+		let mut paths = document
+			.network_interface
+			.library_nodes()
+			.iter()
+			.filter(|(_, _, library_link)| self.node_libraries.library(library_link.library_id).is_none())
+			.filter_map(|(_, _, library_link)| document.library_paths.get(&library_link.library_id).cloned())
+			.collect::<Vec<_>>();
+		paths.sort_unstable();
+		paths.dedup();
+
+		if !paths.is_empty() {
+			responses.add(FrontendMessage::TriggerImportLibraries { paths });
+		}
 	}
 
 	/// With a configured root the working copy lives at `<root>/<id_hex>`; without one it is in-memory.
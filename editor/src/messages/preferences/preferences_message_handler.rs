@@ -103,7 +103,7 @@ impl MessageHandler<PreferencesMessage, PreferencesMessageContext<'_>> for Prefe
 			PreferencesMessage::BrushTool { enabled } => {
 				self.brush_tool = enabled;
 
-				if !enabled && tool_message_handler.tool_state.tool_data.active_tool_type == ToolType::Brush {
+				if !enabled && tool_message_handler.tool_state.tool_data.active_tool_type.is_brush_based() {
 					responses.add(ToolMessage::ActivateToolSelect);
 				}
 
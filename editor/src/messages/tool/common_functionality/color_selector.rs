@@ -6,7 +6,7 @@ use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::graph_modification_utils;
 use crate::messages::tool::utility_types::DocumentToolData;
 use graphene_std::Color;
-use graphene_std::vector::style::{FillChoice, FillChoiceUI, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::style::{FillChoice, FillChoiceUI, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile};
 
 /// Color selector widgets seen in [`LayoutTarget::ToolOptions`] bar.
 pub struct ToolColorOptions {
@@ -128,6 +128,8 @@ pub struct DrawingToolState {
 	pub dash_lengths: Option<Vec<f64>>,
 	/// Dash offset from the selection. `None` = mixed.
 	pub dash_offset: Option<f64>,
+	/// Width profile from the selection. `None` = mixed.
+	pub width_profile: Option<WidthProfile>,
 	/// Set of layers we last synced from, used to detect real selection changes vs. internal node toggles.
 	pub last_synced_selection: Vec<LayerNodeIdentifier>,
 	/// The fill swatch's color, checkbox, and mixed state.
@@ -151,6 +153,7 @@ impl DrawingToolState {
 			paint_order: Some(PaintOrder::default()),
 			dash_lengths: Some(Vec::new()),
 			dash_offset: Some(0.),
+			width_profile: Some(WidthProfile::default()),
 			last_synced_selection: Vec::new(),
 			fill: if fill_enabled { ToolColorOptions::new_enabled() } else { ToolColorOptions::new_disabled() },
 			stroke: ToolColorOptions::new_enabled(),
@@ -186,6 +189,7 @@ impl DrawingToolState {
 			dash_lengths: self.effective_dash_lengths(),
 			dash_offset: self.dash_offset.unwrap_or(0.),
 			transform: glam::DAffine2::IDENTITY,
+			width_profile: self.width_profile.clone().unwrap_or_default(),
 		};
 		responses.add(GraphOperationMessage::StrokeSet { layer, color, stroke });
 	}
@@ -300,7 +304,7 @@ pub fn sync_drawing_state(drawing: &mut DrawingToolState, natural_fill_enabled:
 	needs_refresh
 }
 
-/// Reads the stroke proto-node inputs (align, cap, join, miter limit, paint order, dash lengths, dash offset) across the selection and updates
+/// Reads the stroke proto-node inputs (align, cap, join, miter limit, paint order, dash lengths, dash offset, width profile) across the selection and updates
 /// the matching fields on `drawing`. Each field becomes `None` (mixed) when selected strokes disagree. With no selection, fields are left as-is.
 fn sync_stroke_options(drawing: &mut DrawingToolState, document: &DocumentMessageHandler) -> bool {
 	let strokes: Vec<_> = document
@@ -326,6 +330,7 @@ fn sync_stroke_options(drawing: &mut DrawingToolState, document: &DocumentMessag
 	let new_paint_order = unanimous(strokes.iter().map(|s| s.paint_order));
 	let new_dash_lengths = unanimous(strokes.iter().map(|s| &s.dash_lengths)).cloned();
 	let new_dash_offset = unanimous(strokes.iter().map(|s| s.dash_offset));
+	let new_width_profile = unanimous(strokes.iter().map(|s| &s.width_profile)).cloned();
 
 	let mut changed = false;
 
@@ -357,6 +362,10 @@ fn sync_stroke_options(drawing: &mut DrawingToolState, document: &DocumentMessag
 		drawing.dash_offset = new_dash_offset;
 		changed = true;
 	}
+	if drawing.width_profile != new_width_profile {
+		drawing.width_profile = new_width_profile;
+		changed = true;
+	}
 
 	changed
 }
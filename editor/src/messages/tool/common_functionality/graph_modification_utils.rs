@@ -14,7 +14,7 @@ use graphene_std::raster_types::Image;
 use graphene_std::subpath::Subpath;
 use graphene_std::text::{Font, TypesettingConfig};
 use graphene_std::vector::misc::ManipulatorPointId;
-use graphene_std::vector::style::{FillChoice, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, initial_gradient_transform_for_bounding_box};
+use graphene_std::vector::style::{FillChoice, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile, initial_gradient_transform_for_bounding_box};
 use graphene_std::vector::{Gradient, GradientSpreadMethod, GradientType, PointId, SegmentId, VectorModificationType};
 use graphene_std::{Color, Graphic};
 use std::collections::VecDeque;
@@ -540,6 +540,7 @@ pub struct StrokeOptionsState {
 	pub paint_order: PaintOrder,
 	pub dash_lengths: Vec<f64>,
 	pub dash_offset: f64,
+	pub width_profile: WidthProfile,
 }
 
 /// Reads the non-color stroke option inputs from a layer's Stroke proto node. Returns `None` when the layer has no Stroke node.
@@ -578,6 +579,10 @@ pub fn get_stroke_options(layer: LayerNodeIdentifier, network_interface: &NodeNe
 		Some(TaggedValue::F64(value)) => *value,
 		_ => 0.,
 	};
+	let width_profile = match read(graphene_std::vector::stroke::WidthProfileInput::INDEX) {
+		Some(TaggedValue::WidthProfile(value)) => value.clone(),
+		_ => WidthProfile::default(),
+	};
 
 	Some(StrokeOptionsState {
 		align,
@@ -587,6 +592,7 @@ pub fn get_stroke_options(layer: LayerNodeIdentifier, network_interface: &NodeNe
 		paint_order,
 		dash_lengths,
 		dash_offset,
+		width_profile,
 	})
 }
 
@@ -1,13 +1,14 @@
 mod alignment_snapper;
 mod distribution_snapper;
 mod grid_snapper;
+mod guide_snapper;
 mod layer_snapper;
 mod snap_results;
 
 use crate::consts::{COLOR_OVERLAY_BLACK_75, COLOR_OVERLAY_BLUE, COLOR_OVERLAY_WHITE};
 use crate::messages::portfolio::document::overlays::utility_types::{OverlayContext, Pivot};
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
-use crate::messages::portfolio::document::utility_types::misc::{GridSnapTarget, PathSnapTarget, SnapTarget};
+use crate::messages::portfolio::document::utility_types::misc::{GridSnapTarget, GuideSnapTarget, PathSnapTarget, SnapTarget};
 use crate::messages::prelude::*;
 pub use alignment_snapper::*;
 pub use distribution_snapper::*;
@@ -19,6 +20,7 @@ use graphene_std::vector::PointId;
 use graphene_std::vector::algorithms::intersection::filtered_segment_intersections;
 use graphene_std::vector::misc::point_to_dvec2;
 pub use grid_snapper::*;
+pub use guide_snapper::*;
 use kurbo::ParamCurve;
 pub use layer_snapper::*;
 pub use snap_results::*;
@@ -39,6 +41,7 @@ pub struct SnapManager {
 	indicator: Option<SnappedPoint>,
 	layer_snapper: LayerSnapper,
 	grid_snapper: GridSnapper,
+	guide_snapper: GuideSnapper,
 	alignment_snapper: AlignmentSnapper,
 	distribution_snapper: DistributionSnapper,
 	candidates: Option<Vec<LayerNodeIdentifier>>,
@@ -172,7 +175,7 @@ fn get_closest_intersection(snap_to: DVec2, curves: &[SnappedCurve]) -> Option<S
 	best
 }
 
-fn get_grid_intersection(snap_to: DVec2, lines: &[SnappedLine]) -> Option<SnappedPoint> {
+fn get_line_intersection(snap_to: DVec2, lines: &[SnappedLine], target: SnapTarget) -> Option<SnappedPoint> {
 	let mut best = None;
 	for line_i in lines {
 		for line_j in lines {
@@ -182,7 +185,7 @@ fn get_grid_intersection(snap_to: DVec2, lines: &[SnappedLine]) -> Option<Snappe
 					best = Some(SnappedPoint {
 						snapped_point_document,
 						distance,
-						target: SnapTarget::Grid(GridSnapTarget::Intersection),
+						target,
 						tolerance: line_i.point.tolerance,
 						source: line_i.point.source,
 						at_intersection: true,
@@ -284,11 +287,15 @@ impl SnapManager {
 			snapped_points.push(closest_curve.clone());
 		}
 
-		if document.snapping_state.target_enabled(SnapTarget::Grid(GridSnapTarget::Line))
-			&& let Some(closest_line) = get_closest_line(&snap_results.grid_lines)
-		{
+		let enabled_grid_lines = snap_results.grid_lines.iter().filter(|line| document.snapping_state.target_enabled(line.point.target));
+		if let Some(closest_line) = enabled_grid_lines.map(|line| &line.point).min_by(compare_points) {
 			snapped_points.push(closest_line.clone());
 		}
+		if document.snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Line))
+			&& let Some(closest_guide) = get_closest_line(&snap_results.guide_lines)
+		{
+			snapped_points.push(closest_guide.clone());
+		}
 
 		if !constrained {
 			if document.snapping_state.target_enabled(SnapTarget::Path(PathSnapTarget::IntersectionPoint))
@@ -297,10 +304,15 @@ impl SnapManager {
 				snapped_points.push(closest_curves_intersection);
 			}
 			if document.snapping_state.target_enabled(SnapTarget::Grid(GridSnapTarget::Intersection))
-				&& let Some(closest_grid_intersection) = get_grid_intersection(point.document_point, &snap_results.grid_lines)
+				&& let Some(closest_grid_intersection) = get_line_intersection(point.document_point, &snap_results.grid_lines, SnapTarget::Grid(GridSnapTarget::Intersection))
 			{
 				snapped_points.push(closest_grid_intersection);
 			}
+			if document.snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Intersection))
+				&& let Some(closest_guide_intersection) = get_line_intersection(point.document_point, &snap_results.guide_lines, SnapTarget::Guide(GuideSnapTarget::Intersection))
+			{
+				snapped_points.push(closest_guide_intersection);
+			}
 		}
 
 		if to_path {
@@ -397,6 +409,7 @@ impl SnapManager {
 
 		self.layer_snapper.free_snap(&mut snap_data, point, &mut snap_results, config);
 		self.grid_snapper.free_snap(&mut snap_data, point, &mut snap_results);
+		self.guide_snapper.free_snap(&mut snap_data, point, &mut snap_results);
 		self.alignment_snapper.free_snap(&mut snap_data, point, &mut snap_results, config);
 		self.distribution_snapper.free_snap(&mut snap_data, point, &mut snap_results, config);
 
@@ -423,6 +436,7 @@ impl SnapManager {
 
 		self.layer_snapper.constrained_snap(&mut snap_data, point, &mut snap_results, constraint, config);
 		self.grid_snapper.constrained_snap(&mut snap_data, point, &mut snap_results, constraint);
+		self.guide_snapper.constrained_snap(&mut snap_data, point, &mut snap_results, constraint);
 		self.alignment_snapper.constrained_snap(&mut snap_data, point, &mut snap_results, constraint, config);
 		self.distribution_snapper.constrained_snap(&mut snap_data, point, &mut snap_results, constraint, config);
 
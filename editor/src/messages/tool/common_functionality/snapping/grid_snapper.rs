@@ -1,4 +1,5 @@
 use super::*;
+use crate::messages::portfolio::document::guide::utility_types::artboard_bounds;
 use crate::messages::portfolio::document::utility_types::misc::{GridSnapTarget, GridSnapping, GridType, SnapTarget};
 use glam::DVec2;
 use graphene_std::renderer::Quad;
@@ -6,6 +7,7 @@ use graphene_std::renderer::Quad;
 struct Line {
 	pub point: DVec2,
 	pub direction: DVec2,
+	pub target: GridSnapTarget,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -25,10 +27,12 @@ impl GridSnapper {
 		for (direction, perpendicular) in [(DVec2::X, DVec2::Y), (DVec2::Y, DVec2::X)] {
 			lines.push(Line {
 				direction,
+				target: GridSnapTarget::Line,
 				point: perpendicular * (((document_point - origin) / spacing).ceil() * spacing + origin),
 			});
 			lines.push(Line {
 				direction,
+				target: GridSnapTarget::Line,
 				point: perpendicular * (((document_point - origin) / spacing).floor() * spacing + origin),
 			});
 		}
@@ -53,10 +57,12 @@ impl GridSnapper {
 		let x_max = ((document_point.x - origin.x) / spacing.x).ceil() * spacing.x + origin.x;
 		let x_min = ((document_point.x - origin.x) / spacing.x).floor() * spacing.x + origin.x;
 		lines.push(Line {
+			target: GridSnapTarget::Line,
 			point: DVec2::new(x_max, 0.),
 			direction: DVec2::Y,
 		});
 		lines.push(Line {
+			target: GridSnapTarget::Line,
 			point: DVec2::new(x_min, 0.),
 			direction: DVec2::Y,
 		});
@@ -65,10 +71,12 @@ impl GridSnapper {
 		let y_onto_x_max = ((y_projected_onto_x - origin.y) / spacing.y).ceil() * spacing.y + origin.y;
 		let y_onto_x_min = ((y_projected_onto_x - origin.y) / spacing.y).floor() * spacing.y + origin.y;
 		lines.push(Line {
+			target: GridSnapTarget::Line,
 			point: DVec2::new(origin.x, y_onto_x_max),
 			direction: DVec2::new(1., -tan_a),
 		});
 		lines.push(Line {
+			target: GridSnapTarget::Line,
 			point: DVec2::new(origin.x, y_onto_x_min),
 			direction: DVec2::new(1., -tan_a),
 		});
@@ -77,10 +85,12 @@ impl GridSnapper {
 		let y_onto_z_max = ((y_projected_onto_z - origin.y) / spacing.y).ceil() * spacing.y + origin.y;
 		let y_onto_z_min = ((y_projected_onto_z - origin.y) / spacing.y).floor() * spacing.y + origin.y;
 		lines.push(Line {
+			target: GridSnapTarget::Line,
 			point: DVec2::new(origin.x, y_onto_z_max),
 			direction: DVec2::new(1., tan_b),
 		});
 		lines.push(Line {
+			target: GridSnapTarget::Line,
 			point: DVec2::new(origin.x, y_onto_z_min),
 			direction: DVec2::new(1., tan_b),
 		});
@@ -88,11 +98,34 @@ impl GridSnapper {
 		lines
 	}
 
+	// Layout grids have a line along each column and row edge, and the baselines above and below the point, for each artboard the point is over.
+	fn get_snap_lines_layout(&self, document_point: DVec2, snap_data: &mut SnapData) -> Vec<Line> {
+		let document = snap_data.document;
+		let tolerance = snap_tolerance(document);
+
+		let mut lines = Vec::new();
+		for (artboard, layout_grid) in document.network_interface.layout_grids() {
+			let Some([min, max]) = artboard_bounds(document.metadata(), artboard) else { continue };
+			if document_point.cmplt(min - tolerance).any() || document_point.cmpgt(max + tolerance).any() {
+				continue;
+			}
+
+			lines.extend(layout_grid.snap_lines([min, max], document_point).into_iter().map(|(point, direction)| Line {
+				point,
+				direction,
+				target: GridSnapTarget::LayoutGrid,
+			}));
+		}
+		lines
+	}
+
 	fn get_snap_lines(&self, document_point: DVec2, snap_data: &mut SnapData) -> Vec<Line> {
-		match snap_data.document.snapping_state.grid.grid_type {
+		let mut lines = match snap_data.document.snapping_state.grid.grid_type {
 			GridType::Rectangular { spacing } => self.get_snap_lines_rectangular(document_point, snap_data, spacing),
 			GridType::Isometric { y_axis_spacing, angle_a, angle_b } => self.get_snap_lines_isometric(document_point, snap_data, y_axis_spacing, angle_a, angle_b),
-		}
+		};
+		lines.extend(self.get_snap_lines_layout(document_point, snap_data));
+		lines
 	}
 
 	pub fn free_snap(&mut self, snap_data: &mut SnapData, point: &SnapCandidatePoint, snap_results: &mut SnapResults) {
@@ -110,15 +143,13 @@ impl GridSnapper {
 				continue;
 			}
 
-			if snap_data.document.snapping_state.target_enabled(SnapTarget::Grid(GridSnapTarget::Line))
-				|| snap_data.document.snapping_state.target_enabled(SnapTarget::Grid(GridSnapTarget::Intersection))
-			{
+			if snap_data.document.snapping_state.target_enabled(SnapTarget::Grid(line.target)) || snap_data.document.snapping_state.target_enabled(SnapTarget::Grid(GridSnapTarget::Intersection)) {
 				snap_results.grid_lines.push(SnappedLine {
 					direction: line.direction,
 					point: SnappedPoint {
 						snapped_point_document: projected,
 						source: point.source,
-						target: SnapTarget::Grid(GridSnapTarget::Line),
+						target: SnapTarget::Grid(line.target),
 						source_bounds: point.quad,
 						distance,
 						tolerance,
@@ -163,11 +194,11 @@ impl GridSnapper {
 				continue;
 			};
 			let distance = intersection.distance(point.document_point);
-			if distance < tolerance && snap_data.document.snapping_state.target_enabled(SnapTarget::Grid(GridSnapTarget::Line)) {
+			if distance < tolerance && snap_data.document.snapping_state.target_enabled(SnapTarget::Grid(line.target)) {
 				snap_results.points.push(SnappedPoint {
 					snapped_point_document: intersection,
 					source: point.source,
-					target: SnapTarget::Grid(GridSnapTarget::Line),
+					target: SnapTarget::Grid(line.target),
 					at_intersection: false,
 					constrained: true,
 					source_bounds: point.quad,
@@ -0,0 +1,132 @@
+use super::*;
+use crate::messages::portfolio::document::utility_types::misc::{GuideSnapTarget, SnapTarget};
+use graphene_std::renderer::Quad;
+
+#[derive(Clone, Debug, Default)]
+pub struct GuideSnapper;
+
+impl GuideSnapper {
+	pub fn free_snap(&mut self, snap_data: &mut SnapData, point: &SnapCandidatePoint, snap_results: &mut SnapResults) {
+		let document = snap_data.document;
+		let snapping_state = &document.snapping_state;
+		if !snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Line)) && !snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Intersection)) {
+			return;
+		}
+		if !guides_snappable(document) {
+			return;
+		}
+
+		let tolerance = snap_tolerance(document);
+		for line in document.guides.lines(document.metadata()) {
+			let projected = line.project(point.document_point);
+			let distance = point.document_point.distance(projected);
+			if !distance.is_finite() || distance > tolerance || !line.contains(projected, tolerance) {
+				continue;
+			}
+
+			snap_results.guide_lines.push(SnappedLine {
+				direction: line.direction,
+				point: SnappedPoint {
+					snapped_point_document: projected,
+					source: point.source,
+					target: SnapTarget::Guide(GuideSnapTarget::Line),
+					source_bounds: point.quad,
+					distance,
+					tolerance,
+					..Default::default()
+				},
+			});
+		}
+	}
+
+	pub fn constrained_snap(&mut self, snap_data: &mut SnapData, point: &SnapCandidatePoint, snap_results: &mut SnapResults, constraint: SnapConstraint) {
+		let document = snap_data.document;
+		if !document.snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Line)) || !guides_snappable(document) {
+			return;
+		}
+
+		let tolerance = snap_tolerance(document);
+		let projected = constraint.projection(point.document_point);
+		let (constraint_start, constraint_direction) = match constraint {
+			SnapConstraint::Line { origin, direction } => (origin, direction.normalize_or_zero()),
+			SnapConstraint::Direction(direction) => (projected, direction.normalize_or_zero()),
+			_ => return,
+		};
+
+		for line in document.guides.lines(document.metadata()) {
+			let Some(intersection) = Quad::intersect_rays(line.point, line.direction, constraint_start, constraint_direction) else {
+				continue;
+			};
+			let distance = intersection.distance(point.document_point);
+			if distance < tolerance && line.contains(intersection, tolerance) {
+				snap_results.points.push(SnappedPoint {
+					snapped_point_document: intersection,
+					source: point.source,
+					target: SnapTarget::Guide(GuideSnapTarget::Line),
+					at_intersection: false,
+					constrained: true,
+					source_bounds: point.quad,
+					distance,
+					tolerance,
+					..Default::default()
+				})
+			}
+		}
+	}
+}
+
+/// Locking or hiding the guides sets them aside without deleting them, so they stop being snapped to as well.
+fn guides_snappable(document: &DocumentMessageHandler) -> bool {
+	!document.guides.locked && document.overlays_visibility_settings.guides()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::messages::portfolio::document::guide::utility_types::{Guide, GuideId};
+
+	fn document_with_vertical_guide(x: f64) -> DocumentMessageHandler {
+		let mut document = DocumentMessageHandler::default();
+		document.guides.guides.push(Guide {
+			id: GuideId::new(),
+			position: DVec2::new(x, 0.),
+			angle: std::f64::consts::FRAC_PI_2,
+			artboard: None,
+		});
+		document
+	}
+
+	fn free_snap(document: &DocumentMessageHandler, point: DVec2) -> SnapResults {
+		let input = InputPreprocessorMessageHandler::default();
+		let viewport = ViewportMessageHandler::default();
+		let mut snap_data = SnapData::new(document, &input, &viewport);
+		let mut snap_results = SnapResults::default();
+		GuideSnapper.free_snap(&mut snap_data, &SnapCandidatePoint::handle(point), &mut snap_results);
+		snap_results
+	}
+
+	#[test]
+	fn point_near_guide_snaps_to_it() {
+		let document = document_with_vertical_guide(100.);
+
+		let snap_results = free_snap(&document, DVec2::new(103., 40.));
+
+		assert_eq!(snap_results.guide_lines.len(), 1);
+		let snapped = &snap_results.guide_lines[0].point;
+		assert!(snapped.snapped_point_document.abs_diff_eq(DVec2::new(100., 40.), 1e-9));
+		assert_eq!(snapped.target, SnapTarget::Guide(GuideSnapTarget::Line));
+
+		assert!(free_snap(&document, DVec2::new(130., 40.)).guide_lines.is_empty());
+	}
+
+	#[test]
+	fn locked_or_hidden_guides_do_not_snap() {
+		let mut document = document_with_vertical_guide(100.);
+		document.guides.locked = true;
+		assert!(free_snap(&document, DVec2::new(103., 40.)).guide_lines.is_empty());
+
+		let mut document = document_with_vertical_guide(100.);
+		document.overlays_visibility_settings.guides = false;
+		assert!(free_snap(&document, DVec2::new(103., 40.)).guide_lines.is_empty());
+	}
+}
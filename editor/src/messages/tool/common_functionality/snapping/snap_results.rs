@@ -13,6 +13,7 @@ use std::collections::VecDeque;
 pub struct SnapResults {
 	pub points: Vec<SnappedPoint>,
 	pub grid_lines: Vec<SnappedLine>,
+	pub guide_lines: Vec<SnappedLine>,
 	pub curves: Vec<SnappedCurve>,
 }
 #[derive(Default, Debug, Clone)]
@@ -1,11 +1,13 @@
 use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::color_selector::{DrawingToolState, apply_line_weight};
 use crate::messages::tool::common_functionality::graph_modification_utils;
+use glam::DVec2;
 use graph_craft::document::value::TaggedValue;
 use graphene_std::NodeInputDecleration;
 use graphene_std::choice_type::ChoiceTypeStatic;
-use graphene_std::vector::style::{PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::style::{PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile, WidthProfilePreset};
 
 /// All non-color stroke-related options surfaced in the control bar popover.
 #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
@@ -19,9 +21,10 @@ pub enum StrokeOptionsUpdate {
 	PaintOrder(PaintOrder),
 	DashLengths(Vec<f64>),
 	DashOffset(f64),
+	WidthProfile(WidthProfilePreset),
 }
 
-/// Builds the control-bar popover button that opens the stroke options panel (weight, align, caps, joins, miter limit, paint order, dash).
+/// Builds the control-bar popover button that opens the stroke options panel (weight, profile, align, caps, joins, miter limit, paint order, dash).
 /// `to_message` adapts a [`StrokeOptionsUpdate`] into the calling tool's `UpdateOptions` message.
 pub fn create_stroke_options_popover_widget<F>(drawing: &DrawingToolState, disabled: bool, to_message: F) -> WidgetInstance
 where
@@ -45,6 +48,7 @@ pub fn apply_stroke_option(drawing: &mut DrawingToolState, update: StrokeOptions
 		StrokeOptionsUpdate::PaintOrder(order) => apply_paint_order(drawing, order, document, responses),
 		StrokeOptionsUpdate::DashLengths(lengths) => apply_dash_lengths(drawing, lengths, document, responses),
 		StrokeOptionsUpdate::DashOffset(offset) => apply_dash_offset(drawing, offset, document, responses),
+		StrokeOptionsUpdate::WidthProfile(preset) => apply_width_profile(drawing, WidthProfile::from_preset(preset), document, responses),
 	}
 }
 
@@ -60,6 +64,7 @@ where
 	let mut rows = vec![
 		LayoutGroup::row(vec![TextLabel::new("Stroke").bold(true).widget_instance()]),
 		LayoutGroup::row(weight_row(drawing.line_weight, to_message.clone())),
+		LayoutGroup::row(width_profile_row(drawing.width_profile.as_ref(), to_message.clone())),
 		LayoutGroup::row(dash_lengths_row(drawing.dash_lengths.as_deref(), to_message.clone())),
 	];
 	if has_dash {
@@ -104,6 +109,36 @@ where
 	]
 }
 
+fn width_profile_row<F>(current: Option<&WidthProfile>, to_message: F) -> Vec<WidgetInstance>
+where
+	F: Fn(StrokeOptionsUpdate) -> Message + 'static + Send + Sync + Clone,
+{
+	let entries = WidthProfilePreset::ALL
+		.into_iter()
+		.map(|preset| {
+			let to_message = to_message.clone();
+			MenuListEntry::new(format!("{preset:?}"))
+				.label(preset.label())
+				.on_update(move |_| to_message(StrokeOptionsUpdate::WidthProfile(preset)))
+				.on_commit(|_| DocumentMessage::StartTransaction.into())
+		})
+		.collect();
+	// A profile recorded by drawing (or mixed across the selection) matches none of the presets, so nothing is shown as selected
+	let selected_index = current
+		.and_then(WidthProfile::preset)
+		.and_then(|preset| WidthProfilePreset::ALL.iter().position(|&other| other == preset));
+
+	vec![
+		TextLabel::new("Profile").table_align(true).widget_instance(),
+		Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		DropdownInput::new(vec![entries])
+			.selected_index(selected_index.map(|index| index as u32))
+			.tooltip_label("Width Profile")
+			.tooltip_description("How the stroke's width varies along the length of the path.")
+			.widget_instance(),
+	]
+}
+
 fn miter_limit_row<F>(limit: Option<f64>, to_message: F) -> Vec<WidgetInstance>
 where
 	F: Fn(StrokeOptionsUpdate) -> Message + 'static + Send + Sync,
@@ -222,6 +257,89 @@ pub fn apply_dash_offset(drawing: &mut DrawingToolState, offset: f64, document:
 	set_stroke_input_for_selected(document, graphene_std::vector::stroke::DashOffsetInput::INDEX, TaggedValue::F64(offset), responses);
 }
 
+pub fn apply_width_profile(drawing: &mut DrawingToolState, width_profile: WidthProfile, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+	drawing.width_profile = Some(width_profile.clone());
+	set_stroke_input_for_selected(document, graphene_std::vector::stroke::WidthProfileInput::INDEX, TaggedValue::WidthProfile(width_profile), responses);
+}
+
+/// Sets the width profile of the new `layer`'s stroke to follow the stylus pressure recorded at each point along it, unless the pressure didn't vary.
+pub fn apply_pressure_width_profile(layer: LayerNodeIdentifier, samples: &[(DVec2, f64)], document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+	let Some(width_profile) = pressure_width_profile(samples) else { return };
+	let Some(node_id) = graph_modification_utils::get_stroke_id(layer, &document.network_interface) else {
+		return;
+	};
+
+	responses.add(NodeGraphMessage::SetInputValue {
+		node_id,
+		input_index: graphene_std::vector::stroke::WidthProfileInput::INDEX,
+		value: TaggedValue::WidthProfile(width_profile),
+	});
+}
+
+/// The width profile following the stylus pressure recorded at each point along a drawn path, or `None` if the pressure didn't vary (such as when drawing with a mouse).
+pub fn pressure_width_profile(samples: &[(DVec2, f64)]) -> Option<WidthProfile> {
+	/// How much the pressure must vary along the path before it's recorded, so the slight wobble of a steady hand doesn't taper the stroke.
+	const MIN_PRESSURE_VARIATION: f64 = 0.05;
+
+	let (min, max) = samples
+		.iter()
+		.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &(_, pressure)| (min.min(pressure), max.max(pressure)));
+	if samples.len() < 2 || max - min < MIN_PRESSURE_VARIATION {
+		return None;
+	}
+
+	let lengths = samples
+		.iter()
+		.scan((samples[0].0, 0.), |(previous, length), &(position, _)| {
+			*length += previous.distance(position);
+			*previous = position;
+			Some(*length)
+		})
+		.collect::<Vec<_>>();
+	let total_length = lengths[lengths.len() - 1];
+	if total_length <= 0. {
+		return None;
+	}
+
+	let width_profile = WidthProfile::from_samples(lengths.iter().zip(samples).map(|(length, &(_, pressure))| DVec2::new(length / total_length, pressure)));
+	(!width_profile.is_uniform()).then_some(width_profile)
+}
+
 fn set_stroke_input_for_selected(document: &DocumentMessageHandler, input_index: usize, value: TaggedValue, responses: &mut VecDeque<Message>) {
 	graph_modification_utils::set_proto_node_input_for_selected_layers(document, graphene_std::vector::stroke::IDENTIFIER, input_index, value, responses);
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pressure_width_profile_follows_the_pressure_along_the_path() {
+		let samples = [(DVec2::new(0., 0.), 0.2), (DVec2::new(1., 0.), 1.), (DVec2::new(1., 3.), 0.6)];
+		let width_profile = pressure_width_profile(&samples).expect("The varying pressure should be recorded");
+
+		// Each sample is placed at its distance along the path as a fraction of the path's length
+		assert_eq!(width_profile.points(), [DVec2::new(0., 0.2), DVec2::new(0.25, 1.), DVec2::new(1., 0.6)]);
+		assert!((width_profile.evaluate(0.125) - 0.6).abs() < 1e-9);
+	}
+
+	#[test]
+	fn pressure_width_profile_ignores_steady_pressure() {
+		// A mouse reports the same pressure throughout
+		let steady = [(DVec2::new(0., 0.), 0.5), (DVec2::new(5., 0.), 0.5), (DVec2::new(5., 5.), 0.5)];
+		assert_eq!(pressure_width_profile(&steady), None);
+
+		// The slight wobble of a steady hand
+		let wobbling = [(DVec2::new(0., 0.), 0.7), (DVec2::new(5., 0.), 0.72), (DVec2::new(5., 5.), 0.69)];
+		assert_eq!(pressure_width_profile(&wobbling), None);
+	}
+
+	#[test]
+	fn pressure_width_profile_needs_a_path_with_length() {
+		assert_eq!(pressure_width_profile(&[]), None);
+		assert_eq!(pressure_width_profile(&[(DVec2::ZERO, 0.2)]), None);
+
+		let in_place = [(DVec2::new(2., 2.), 0.2), (DVec2::new(2., 2.), 0.9)];
+		assert_eq!(pressure_width_profile(&in_place), None);
+	}
+}
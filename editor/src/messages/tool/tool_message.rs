@@ -38,12 +38,12 @@ pub enum ToolMessage {
 
 	#[child]
 	Brush(BrushToolMessage),
-	// 	// #[child]
-	// Heal(HealToolMessage),
+	#[child]
+	Heal(HealToolMessage),
 	// 	// #[child]
 	// Clone(CloneToolMessage),
-	// 	// #[child]
-	// Patch(PatchToolMessage),
+	#[child]
+	Patch(PatchToolMessage),
 	// 	// #[child]
 	// Relight(RelightToolMessage),
 	// 	// #[child]
@@ -70,6 +70,8 @@ pub enum ToolMessage {
 	ActivateToolText,
 	// Raster tools
 	ActivateToolBrush,
+	ActivateToolHeal,
+	ActivateToolPatch,
 
 	ActivateTool {
 		tool_type: ToolType,
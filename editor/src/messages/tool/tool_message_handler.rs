@@ -82,6 +82,8 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 				responses.add(ShapeToolMessage::HideShapeTypeWidget { hide: false })
 			}
 			ToolMessage::ActivateToolBrush => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Brush }),
+			ToolMessage::ActivateToolHeal => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Heal }),
+			ToolMessage::ActivateToolPatch => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Patch }),
 			ToolMessage::ActivateToolShapeLine | ToolMessage::ActivateToolShapeRectangle | ToolMessage::ActivateToolShapeEllipse => {
 				let shape = match message {
 					ToolMessage::ActivateToolShapeLine => Line,
@@ -407,6 +409,8 @@ impl ToolMessageHandler {
 		if preferences.brush_tool {
 			list.extend(actions!(ToolMessageDiscriminant;
 				ActivateToolBrush,
+				ActivateToolHeal,
+				ActivateToolPatch,
 			));
 		}
 
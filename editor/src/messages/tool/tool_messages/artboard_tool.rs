@@ -1,7 +1,9 @@
 use super::tool_prelude::*;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
+use crate::messages::portfolio::document::overlays::grid_overlays::layout_grid_options;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::misc::LayoutGrid;
 use crate::messages::tool::common_functionality::auto_panning::AutoPanning;
 use crate::messages::tool::common_functionality::measure;
 use crate::messages::tool::common_functionality::resize::Resize;
@@ -17,6 +19,8 @@ use graphene_std::renderer::{Quad, Rect};
 pub struct ArtboardTool {
 	fsm_state: ArtboardToolFsmState,
 	data: ArtboardToolData,
+	/// The selected artboard along with its layout grid (if it has one), which is edited from the tool options.
+	layout_grid: Option<(LayerNodeIdentifier, Option<LayoutGrid>)>,
 }
 
 #[impl_message(Message, ToolMessage, Artboard)]
@@ -53,6 +57,15 @@ impl ToolMetadata for ArtboardTool {
 impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for ArtboardTool {
 	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
 		self.fsm_state.process_event(message, &mut self.data, context, &(), responses, false);
+
+		let layout_grid = self
+			.data
+			.selected_artboard
+			.map(|artboard| (artboard, context.document.network_interface.layout_grid(artboard).cloned()));
+		if layout_grid != self.layout_grid {
+			self.layout_grid = layout_grid;
+			self.send_layout(responses, LayoutTarget::ToolOptions);
+		}
 	}
 
 	fn actions(&self) -> ActionList {
@@ -74,7 +87,32 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Artb
 
 impl LayoutHolder for ArtboardTool {
 	fn layout(&self) -> Layout {
-		Layout::default()
+		let Some((artboard, layout_grid)) = &self.layout_grid else {
+			return Layout::default();
+		};
+		let artboard = *artboard;
+
+		let widgets = vec![
+			CheckboxInput::new(layout_grid.is_some())
+				.icon("Grid")
+				.tooltip_label("Layout Grid")
+				.tooltip_description("Lay columns, rows, and a baseline grid over the selected artboard to line up its contents.")
+				.on_update(move |input: &CheckboxInput| {
+					DocumentMessage::LayoutGridOptions {
+						artboard,
+						layout_grid: input.checked.then(LayoutGrid::default),
+					}
+					.into()
+				})
+				.widget_instance(),
+			PopoverButton::new()
+				.popover_layout(Layout(layout_grid_options(artboard, &layout_grid.clone().unwrap_or_default())))
+				.popover_min_width(Some(320))
+				.disabled(layout_grid.is_none())
+				.widget_instance(),
+		];
+
+		Layout(vec![LayoutGroup::row(widgets)])
 	}
 }
 
@@ -570,6 +608,7 @@ impl Fsm for ArtboardToolFsmState {
 
 #[cfg(test)]
 mod test_artboard {
+	use crate::messages::portfolio::document::utility_types::misc::LayoutGrid;
 	pub use crate::test_utils::test_prelude::*;
 	use graphene_std::Artboard;
 	use graphene_std::list::List;
@@ -692,6 +731,59 @@ mod test_artboard {
 		has_artboards(&mut editor, vec![]).await;
 	}
 
+	async fn draw_artboard_with_layout_grid(editor: &mut EditorTestUtils) -> LayerNodeIdentifier {
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Artboard, 10., 10., 50., 50., ModifierKeys::default()).await;
+		let artboard = editor.get_selected_layer().await.expect("The drawn artboard should be selected");
+		editor
+			.handle_message(DocumentMessage::LayoutGridOptions {
+				artboard,
+				layout_grid: Some(LayoutGrid::default()),
+			})
+			.await;
+		assert_eq!(editor.active_document().network_interface.layout_grid(artboard), Some(&LayoutGrid::default()));
+		artboard
+	}
+
+	#[tokio::test]
+	async fn artboard_delete_removes_layout_grid() {
+		let mut editor = EditorTestUtils::create();
+		draw_artboard_with_layout_grid(&mut editor).await;
+
+		editor.press(Key::Delete, ModifierKeys::default()).await;
+
+		has_artboards(&mut editor, vec![]).await;
+		assert_eq!(
+			editor.active_document().network_interface.layout_grids().count(),
+			0,
+			"The deleted artboard's layout grid should be removed"
+		);
+	}
+
+	#[tokio::test]
+	async fn layout_grid_change_is_undoable() {
+		let mut editor = EditorTestUtils::create();
+		let artboard = draw_artboard_with_layout_grid(&mut editor).await;
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().network_interface.layout_grid(artboard), None);
+
+		editor.handle_message(DocumentMessage::Redo).await;
+		assert_eq!(editor.active_document().network_interface.layout_grid(artboard), Some(&LayoutGrid::default()));
+	}
+
+	#[tokio::test]
+	async fn duplicated_artboard_keeps_its_layout_grid() {
+		let mut editor = EditorTestUtils::create();
+		draw_artboard_with_layout_grid(&mut editor).await;
+
+		editor.handle_message(DocumentMessage::DuplicateSelectedLayers).await;
+
+		let layout_grids = editor.active_document().network_interface.layout_grids().collect::<Vec<_>>();
+		assert_eq!(layout_grids.len(), 2, "The duplicate should have its own copy of the layout grid");
+		assert!(layout_grids.iter().all(|(_, layout_grid)| **layout_grid == LayoutGrid::default()));
+	}
+
 	#[tokio::test]
 	async fn artboard_cancel() {
 		let mut editor = EditorTestUtils::create();
@@ -1,18 +1,22 @@
 use super::tool_prelude::*;
 use crate::consts::DEFAULT_BRUSH_SIZE;
+use crate::messages::input_mapper::utility_types::input_mouse::StylusState;
 use crate::messages::portfolio::document::graph_operation::transform_utils::get_current_transform;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::{DefinitionIdentifier, resolve_proto_node_type};
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
 use crate::messages::tool::common_functionality::color_selector::{ToolColorOptions, solid};
+use crate::messages::tool::common_functionality::graph_modification_utils::NodeGraphLayer;
 use graph_craft::document::NodeId;
 use graph_craft::document::value::TaggedValue;
 use graphene_std::Color;
-use graphene_std::brush::brush_stroke::{BrushInputSample, BrushStroke, BrushStyle};
+use graphene_std::brush::brush_stroke::{
+	BrushDynamics, BrushDynamicsCurve, BrushDynamicsInput, BrushInputSample, BrushJitter, BrushMode, BrushSampling, BrushStroke, BrushStyle, BrushTip, BrushTipShape,
+};
 use graphene_std::raster::BlendMode;
 use graphene_std::vector::style::{FillChoice, FillChoiceUI};
 
-const BRUSH_MAX_SIZE: f64 = 5000.;
+pub(super) const BRUSH_MAX_SIZE: f64 = 5000.;
 
 #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 #[derive(PartialEq, Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -22,6 +26,16 @@ pub enum DrawMode {
 	Restore,
 }
 
+/// The brush parameter which a dynamics curve varies with the stylus input.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum BrushDynamicsTarget {
+	Diameter,
+	Flow,
+	Opacity,
+	Hardness,
+}
+
 #[derive(Default, ExtractField)]
 pub struct BrushTool {
 	fsm_state: BrushToolFsmState,
@@ -37,6 +51,11 @@ pub struct BrushOptions {
 	color: ToolColorOptions,
 	blend_mode: BlendMode,
 	draw_mode: DrawMode,
+	dynamics: BrushDynamics,
+	tip: BrushTip,
+	jitter: BrushJitter,
+	mode: BrushMode,
+	strength: f64,
 }
 
 impl Default for BrushOptions {
@@ -49,6 +68,22 @@ impl Default for BrushOptions {
 			color: ToolColorOptions::default(),
 			blend_mode: BlendMode::Normal,
 			draw_mode: DrawMode::Draw,
+			dynamics: BrushDynamics::default(),
+			tip: BrushTip::default(),
+			jitter: BrushJitter::default(),
+			mode: BrushMode::Paint,
+			strength: 50.,
+		}
+	}
+}
+
+impl BrushOptions {
+	fn dynamics_curve_mut(&mut self, target: BrushDynamicsTarget) -> &mut BrushDynamicsCurve {
+		match target {
+			BrushDynamicsTarget::Diameter => &mut self.dynamics.diameter,
+			BrushDynamicsTarget::Flow => &mut self.dynamics.flow,
+			BrushDynamicsTarget::Opacity => &mut self.dynamics.opacity,
+			BrushDynamicsTarget::Hardness => &mut self.dynamics.hardness,
 		}
 	}
 }
@@ -62,7 +97,7 @@ pub enum BrushToolMessage {
 	WorkingColorChanged,
 
 	// Tool-specific messages
-	DragStart,
+	DragStart { set_clone_source: Key },
 	DragStop,
 	PointerMove,
 	UpdateOptions { options: BrushToolMessageOptionsUpdate },
@@ -76,9 +111,22 @@ pub enum BrushToolMessageOptionsUpdate {
 	Color(Option<Color>),
 	Diameter(f64),
 	DrawMode(DrawMode),
+	DualTexture(bool),
+	DualTextureScale(f64),
+	DynamicsInput(BrushDynamicsTarget, BrushDynamicsInput),
+	DynamicsMinimum(BrushDynamicsTarget, f64),
 	Flow(f64),
 	Hardness(f64),
+	JitterOpacity(f64),
+	JitterRotation(f64),
+	JitterScatter(f64),
+	JitterSize(f64),
+	Mode(BrushMode),
 	Spacing(f64),
+	Strength(f64),
+	TipAngle(f64),
+	TipFollowStroke(bool),
+	TipShape(BrushTipShape),
 	WorkingColorsChanged,
 }
 
@@ -166,6 +214,66 @@ impl LayoutHolder for BrushTool {
 				.widget_instance(),
 		];
 
+		widgets.push(Separator::new(SeparatorStyle::Related).widget_instance());
+
+		widgets.push(
+			PopoverButton::new()
+				.tooltip_label("Dynamics")
+				.tooltip_description("Vary the brush with the pressure, tilt, twist, or velocity of a stylus as each stroke is drawn.")
+				.popover_layout(self.dynamics_layout())
+				.widget_instance(),
+		);
+
+		widgets.push(
+			PopoverButton::new()
+				.tooltip_label("Tip")
+				.tooltip_description("Stamp a texture instead of a round tip, rotate it, and randomly vary each stamp along the stroke.")
+				.popover_layout(self.tip_layout())
+				.widget_instance(),
+		);
+
+		widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+
+		let mode_entries = vec![
+			BrushMode::list()
+				.into_iter()
+				.map(|mode| {
+					MenuListEntry::new(format!("{mode:?}")).label(format!("{mode:?}")).on_commit(move |_| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::Mode(mode),
+						}
+						.into()
+					})
+				})
+				.collect(),
+		];
+		widgets.push(
+			DropdownInput::new(mode_entries)
+				.selected_index(BrushMode::list().iter().position(|&mode| mode == self.options.mode).map(|index| index as u32))
+				.tooltip_description("Paint with the brush color, or rework the existing pixels of the layer. Alt-click to set the point which Clone copies from.")
+				.widget_instance(),
+		);
+
+		let samples_layer = self.options.mode.samples_layer();
+		if samples_layer {
+			widgets.push(Separator::new(SeparatorStyle::Related).widget_instance());
+			widgets.push(
+				NumberInput::new(Some(self.options.strength))
+					.label("Strength")
+					.min(1.)
+					.max(100.)
+					.mode_range()
+					.unit("%")
+					.on_update(|number_input: &NumberInput| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::Strength(number_input.value.unwrap()),
+						}
+						.into()
+					})
+					.widget_instance(),
+			);
+		}
+
 		widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
 
 		let draw_mode_entries: Vec<_> = [DrawMode::Draw, DrawMode::Erase, DrawMode::Restore]
@@ -179,7 +287,12 @@ impl LayoutHolder for BrushTool {
 				})
 			})
 			.collect();
-		widgets.push(RadioInput::new(draw_mode_entries).selected_index(Some(self.options.draw_mode as u32)).widget_instance());
+		widgets.push(
+			RadioInput::new(draw_mode_entries)
+				.selected_index(Some(self.options.draw_mode as u32))
+				.disabled(samples_layer)
+				.widget_instance(),
+		);
 
 		widgets.push(Separator::new(SeparatorStyle::Related).widget_instance());
 
@@ -203,7 +316,7 @@ impl LayoutHolder for BrushTool {
 			DropdownInput::new(blend_mode_entries)
 				.selected_index(self.options.blend_mode.index_in_list().map(|index| index as u32))
 				.tooltip_description("The blend mode used with the background when performing a brush stroke. Only used in draw mode.")
-				.disabled(self.options.draw_mode != DrawMode::Draw)
+				.disabled(self.options.draw_mode != DrawMode::Draw || samples_layer)
 				.widget_instance(),
 		);
 
@@ -211,6 +324,211 @@ impl LayoutHolder for BrushTool {
 	}
 }
 
+impl BrushTool {
+	fn dynamics_layout(&self) -> Layout {
+		let targets = [
+			(BrushDynamicsTarget::Diameter, self.options.dynamics.diameter),
+			(BrushDynamicsTarget::Flow, self.options.dynamics.flow),
+			(BrushDynamicsTarget::Opacity, self.options.dynamics.opacity),
+			(BrushDynamicsTarget::Hardness, self.options.dynamics.hardness),
+		];
+
+		let rows = targets.into_iter().map(|(target, curve)| {
+			let input_entries = vec![
+				BrushDynamicsInput::list()
+					.into_iter()
+					.map(|input| {
+						MenuListEntry::new(format!("{input:?}")).label(format!("{input:?}")).on_commit(move |_| {
+							BrushToolMessage::UpdateOptions {
+								options: BrushToolMessageOptionsUpdate::DynamicsInput(target, input),
+							}
+							.into()
+						})
+					})
+					.collect(),
+			];
+			let selected_index = BrushDynamicsInput::list().iter().position(|&input| input == curve.input).map(|index| index as u32);
+
+			LayoutGroup::row(vec![
+				TextLabel::new(format!("{target:?}")).table_align(true).min_width(80).widget_instance(),
+				DropdownInput::new(input_entries).selected_index(selected_index).widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				NumberInput::new(Some(curve.minimum))
+					.label("Minimum")
+					.min(0.)
+					.max(100.)
+					.mode_range()
+					.unit("%")
+					.disabled(curve.input == BrushDynamicsInput::Off)
+					.tooltip_description("How much of the parameter remains when the input is at its lowest.")
+					.on_update(move |number_input: &NumberInput| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::DynamicsMinimum(target, number_input.value.unwrap()),
+						}
+						.into()
+					})
+					.widget_instance(),
+			])
+		});
+
+		Layout(std::iter::once(LayoutGroup::row(vec![TextLabel::new("Dynamics").bold(true).widget_instance()])).chain(rows).collect())
+	}
+
+	fn tip_layout(&self) -> Layout {
+		let BrushTip {
+			shape,
+			angle,
+			follow_stroke,
+			dual_texture,
+			dual_texture_scale,
+		} = self.options.tip;
+		let is_round = shape == BrushTipShape::Round;
+
+		let shape_entries = vec![
+			BrushTipShape::list()
+				.into_iter()
+				.map(|shape| {
+					MenuListEntry::new(format!("{shape:?}")).label(format!("{shape:?}")).on_commit(move |_| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::TipShape(shape),
+						}
+						.into()
+					})
+				})
+				.collect(),
+		];
+		let shape_index = BrushTipShape::list().iter().position(|&entry| entry == shape).map(|index| index as u32);
+
+		let follow_stroke_id = CheckboxId::new();
+		let dual_texture_id = CheckboxId::new();
+
+		let jitter_row = |label: &str, value: f64, max: f64, unit: &str, description: &str, update: fn(f64) -> BrushToolMessageOptionsUpdate| {
+			LayoutGroup::row(vec![
+				TextLabel::new(label).table_align(true).min_width(80).widget_instance(),
+				NumberInput::new(Some(value))
+					.min(0.)
+					.max(max)
+					.mode_range()
+					.unit(unit)
+					.tooltip_description(description)
+					.on_update(move |number_input: &NumberInput| {
+						BrushToolMessage::UpdateOptions {
+							options: update(number_input.value.unwrap()),
+						}
+						.into()
+					})
+					.widget_instance(),
+			])
+		};
+		let BrushJitter { rotation, scatter, size, opacity, .. } = self.options.jitter;
+
+		Layout(vec![
+			LayoutGroup::row(vec![TextLabel::new("Tip").bold(true).widget_instance()]),
+			LayoutGroup::row(vec![
+				TextLabel::new("Shape").table_align(true).min_width(80).widget_instance(),
+				DropdownInput::new(shape_entries)
+					.selected_index(shape_index)
+					.tooltip_description("Texture stamps the image connected to the Brush node's tip texture input, where darker and more opaque pixels paint more strongly.")
+					.widget_instance(),
+			]),
+			LayoutGroup::row(vec![
+				TextLabel::new("Angle").table_align(true).min_width(80).widget_instance(),
+				NumberInput::new(Some(angle))
+					.min(-180.)
+					.max(180.)
+					.mode_range()
+					.unit("°")
+					.disabled(is_round)
+					.on_update(|number_input: &NumberInput| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::TipAngle(number_input.value.unwrap()),
+						}
+						.into()
+					})
+					.widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				CheckboxInput::new(follow_stroke)
+					.disabled(is_round)
+					.on_update(|&CheckboxInput { checked, .. }| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::TipFollowStroke(checked),
+						}
+						.into()
+					})
+					.for_label(follow_stroke_id)
+					.widget_instance(),
+				TextLabel::new("Follow Stroke")
+					.disabled(is_round)
+					.tooltip_description("Turn the tip to face the direction the stroke travels in, in addition to its angle.")
+					.for_checkbox(follow_stroke_id)
+					.widget_instance(),
+			]),
+			LayoutGroup::row(vec![
+				CheckboxInput::new(dual_texture)
+					.on_update(|&CheckboxInput { checked, .. }| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::DualTexture(checked),
+						}
+						.into()
+					})
+					.for_label(dual_texture_id)
+					.widget_instance(),
+				TextLabel::new("Dual Texture")
+					.tooltip_description("Multiply the stroke by the image connected to the Brush node's dual texture input, tiled across the layer like paper or canvas grain.")
+					.for_checkbox(dual_texture_id)
+					.widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				NumberInput::new(Some(dual_texture_scale))
+					.label("Scale")
+					.min(1.)
+					.max(1000.)
+					.unit("%")
+					.disabled(!dual_texture)
+					.on_update(|number_input: &NumberInput| {
+						BrushToolMessage::UpdateOptions {
+							options: BrushToolMessageOptionsUpdate::DualTextureScale(number_input.value.unwrap()),
+						}
+						.into()
+					})
+					.widget_instance(),
+			]),
+			LayoutGroup::row(vec![TextLabel::new("Jitter").bold(true).widget_instance()]),
+			jitter_row(
+				"Rotation",
+				rotation,
+				180.,
+				"°",
+				"The largest random rotation of each stamp in either direction.",
+				BrushToolMessageOptionsUpdate::JitterRotation,
+			),
+			jitter_row(
+				"Scatter",
+				scatter,
+				500.,
+				"%",
+				"How far each stamp may randomly stray to either side of the stroke, relative to the diameter.",
+				BrushToolMessageOptionsUpdate::JitterScatter,
+			),
+			jitter_row(
+				"Size",
+				size,
+				100.,
+				"%",
+				"The largest random reduction of the diameter of each stamp.",
+				BrushToolMessageOptionsUpdate::JitterSize,
+			),
+			jitter_row(
+				"Opacity",
+				opacity,
+				100.,
+				"%",
+				"The largest random reduction of the opacity of each stamp.",
+				BrushToolMessageOptionsUpdate::JitterOpacity,
+			),
+		])
+	}
+}
+
 #[message_handler_data]
 impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for BrushTool {
 	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
@@ -221,22 +539,27 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Brus
 		match options {
 			BrushToolMessageOptionsUpdate::BlendMode(blend_mode) => self.options.blend_mode = blend_mode,
 			BrushToolMessageOptionsUpdate::ChangeDiameter(change) => {
-				let needs_rounding = ((self.options.diameter + change.abs() / 2.) % change.abs() - change.abs() / 2.).abs() > 0.5;
-				if needs_rounding && change > 0. {
-					self.options.diameter = (self.options.diameter / change.abs()).ceil() * change.abs();
-				} else if needs_rounding && change < 0. {
-					self.options.diameter = (self.options.diameter / change.abs()).floor() * change.abs();
-				} else {
-					self.options.diameter = (self.options.diameter / change.abs()).round() * change.abs() + change;
-				}
-				self.options.diameter = self.options.diameter.max(1.);
+				self.options.diameter = change_diameter(self.options.diameter, change);
 				self.send_layout(responses, LayoutTarget::ToolOptions);
 			}
 			BrushToolMessageOptionsUpdate::Diameter(diameter) => self.options.diameter = diameter,
 			BrushToolMessageOptionsUpdate::DrawMode(draw_mode) => self.options.draw_mode = draw_mode,
+			BrushToolMessageOptionsUpdate::DualTexture(dual_texture) => self.options.tip.dual_texture = dual_texture,
+			BrushToolMessageOptionsUpdate::DualTextureScale(scale) => self.options.tip.dual_texture_scale = scale,
+			BrushToolMessageOptionsUpdate::DynamicsInput(target, input) => self.options.dynamics_curve_mut(target).input = input,
+			BrushToolMessageOptionsUpdate::DynamicsMinimum(target, minimum) => self.options.dynamics_curve_mut(target).minimum = minimum,
 			BrushToolMessageOptionsUpdate::Hardness(hardness) => self.options.hardness = hardness,
 			BrushToolMessageOptionsUpdate::Flow(flow) => self.options.flow = flow,
+			BrushToolMessageOptionsUpdate::JitterOpacity(opacity) => self.options.jitter.opacity = opacity,
+			BrushToolMessageOptionsUpdate::JitterRotation(rotation) => self.options.jitter.rotation = rotation,
+			BrushToolMessageOptionsUpdate::JitterScatter(scatter) => self.options.jitter.scatter = scatter,
+			BrushToolMessageOptionsUpdate::JitterSize(size) => self.options.jitter.size = size,
+			BrushToolMessageOptionsUpdate::Mode(mode) => self.options.mode = mode,
 			BrushToolMessageOptionsUpdate::Spacing(spacing) => self.options.spacing = spacing,
+			BrushToolMessageOptionsUpdate::Strength(strength) => self.options.strength = strength,
+			BrushToolMessageOptionsUpdate::TipAngle(angle) => self.options.tip.angle = angle,
+			BrushToolMessageOptionsUpdate::TipFollowStroke(follow_stroke) => self.options.tip.follow_stroke = follow_stroke,
+			BrushToolMessageOptionsUpdate::TipShape(shape) => self.options.tip.shape = shape,
 			BrushToolMessageOptionsUpdate::Color(color) => {
 				// User picked a color: push to the global primary working color (no tool-local customization).
 				if let Some(color) = color {
@@ -279,10 +602,14 @@ impl ToolTransition for BrushTool {
 }
 
 #[derive(Clone, Debug, Default)]
-struct BrushToolData {
-	strokes: Vec<BrushStroke>,
-	layer: Option<LayerNodeIdentifier>,
+pub(super) struct BrushToolData {
+	pub(super) strokes: Vec<BrushStroke>,
+	pub(super) layer: Option<LayerNodeIdentifier>,
 	transform: DAffine2,
+	/// The point in layer space which the Clone mode copies from, set by Alt-clicking.
+	clone_source: Option<DVec2>,
+	/// The offset from the stroke to the clone source, which is kept for every stroke after the first one so they stay aligned.
+	clone_offset: Option<DVec2>,
 }
 
 impl BrushToolData {
@@ -320,7 +647,44 @@ impl BrushToolData {
 		None
 	}
 
-	fn update_strokes(&self, responses: &mut VecDeque<Message>) {
+	/// Loads the strokes of the selected layer like [`Self::load_existing_strokes`], but also accepts a selected raster layer without a Brush node, which is given one when its first stroke is added.
+	pub(super) fn load_selected_raster_layer(&mut self, document: &mut DocumentMessageHandler) -> Option<LayerNodeIdentifier> {
+		if let Some(layer) = self.load_existing_strokes(document) {
+			return Some(layer);
+		}
+
+		self.layer = None;
+		self.strokes.clear();
+		let selected_layers: Vec<_> = document.network_interface.selected_nodes().selected_layers(document.metadata()).collect();
+		let [layer] = selected_layers[..] else { return None };
+		if !NodeGraphLayer::is_raster_layer(layer, &mut document.network_interface) {
+			return None;
+		}
+
+		self.layer = Some(layer);
+		Some(layer)
+	}
+
+	/// The position in the space of the strokes of the layer under a point in the viewport.
+	pub(super) fn layer_position(&self, document: &DocumentMessageHandler, layer: LayerNodeIdentifier, viewport_position: DVec2) -> DVec2 {
+		self.layer_to_viewport(document, layer).inverse().transform_point2(viewport_position)
+	}
+
+	/// Maps the space of the strokes of the layer into the viewport.
+	pub(super) fn layer_to_viewport(&self, document: &DocumentMessageHandler, layer: LayerNodeIdentifier) -> DAffine2 {
+		document.metadata().downstream_transform_to_viewport(layer) * self.transform
+	}
+
+	/// How many viewport pixels a pixel of the space of the strokes of the layer spans, which is used to keep the diameter of the brush the same on screen.
+	pub(super) fn layer_scale(&self, document: &DocumentMessageHandler, layer: LayerNodeIdentifier) -> f64 {
+		let layer_document_scale = self.layer_to_viewport(document, layer);
+
+		0.0001_f64 // Safety against division by zero
+			.max((layer_document_scale.matrix2 * glam::DVec2::X).length())
+			.max((layer_document_scale.matrix2 * glam::DVec2::Y).length())
+	}
+
+	pub(super) fn update_strokes(&self, responses: &mut VecDeque<Message>) {
 		let Some(layer) = self.layer else { return };
 		let strokes = self.strokes.clone();
 		responses.add(GraphOperationMessage::Brush { layer, strokes });
@@ -343,34 +707,42 @@ impl Fsm for BrushToolFsmState {
 
 		let ToolMessage::Brush(event) = event else { return self };
 		match (self, event) {
-			(BrushToolFsmState::Ready, BrushToolMessage::DragStart) => {
+			(BrushToolFsmState::Ready, BrushToolMessage::DragStart { set_clone_source }) => {
+				// Alt-clicking sets the point which the Clone mode copies from instead of drawing
+				if tool_options.mode == BrushMode::Clone && input.keyboard.key(set_clone_source) {
+					if let Some(layer) = tool_data.load_existing_strokes(document) {
+						tool_data.clone_source = Some(tool_data.layer_position(document, layer, input.mouse.position));
+						tool_data.clone_offset = None;
+					}
+					tool_data.strokes.clear();
+
+					return self;
+				}
+
 				responses.add(DocumentMessage::StartTransaction);
 				let loaded_layer = tool_data.load_existing_strokes(document);
 
 				if let Some(layer) = loaded_layer {
-					let pos = document
-						.network_interface
-						.document_metadata()
-						.downstream_transform_to_viewport(layer)
-						.inverse()
-						.transform_point2(input.mouse.position);
-					let layer_position = tool_data.transform.inverse().transform_point2(pos);
-					let layer_document_scale = document.metadata().downstream_transform_to_viewport(layer) * tool_data.transform;
+					let layer_position = tool_data.layer_position(document, layer, input.mouse.position);
 
 					// TODO: Also scale it based on the input image ('Background' input).
 					// TODO: Resizing the input image results in a different brush size from the chosen diameter.
-					let layer_scale = 0.0001_f64 // Safety against division by zero
-						.max((layer_document_scale.matrix2 * glam::DVec2::X).length())
-						.max((layer_document_scale.matrix2 * glam::DVec2::Y).length());
+					let layer_scale = tool_data.layer_scale(document, layer);
 
 					// Start a new stroke with a single sample
 					let blend_mode = match tool_options.draw_mode {
+						// Strokes which rework the pixels of the layer don't blend a color, so they can't erase or restore
+						_ if tool_options.mode.samples_layer() => BlendMode::Normal,
 						DrawMode::Draw => tool_options.blend_mode,
 						DrawMode::Erase => BlendMode::Erase,
 						DrawMode::Restore => BlendMode::Restore,
 					};
+					let source_offset = match (tool_options.mode, tool_data.clone_source) {
+						(BrushMode::Clone, Some(source)) => *tool_data.clone_offset.get_or_insert(source - layer_position),
+						_ => DVec2::ZERO,
+					};
 					tool_data.strokes.push(BrushStroke {
-						trace: vec![BrushInputSample { position: layer_position }],
+						trace: vec![input_sample(layer_position, &input.mouse.stylus)],
 						style: BrushStyle {
 							color: tool_options.color.active_color().unwrap_or_default(),
 							diameter: tool_options.diameter / layer_scale,
@@ -378,6 +750,19 @@ impl Fsm for BrushToolFsmState {
 							flow: tool_options.flow,
 							spacing: tool_options.spacing,
 							blend_mode,
+							dynamics: tool_options.dynamics,
+							tip: tool_options.tip,
+							// Each stroke of the layer gets its own seed, so overlapping strokes don't repeat the same variation
+							jitter: BrushJitter {
+								seed: tool_data.strokes.len() as u32,
+								..tool_options.jitter
+							},
+							mode: tool_options.mode,
+							sampling: BrushSampling {
+								strength: tool_options.strength,
+								source_offset,
+								..Default::default()
+							},
 						},
 					});
 
@@ -389,25 +774,19 @@ impl Fsm for BrushToolFsmState {
 					new_brush_layer(document, responses);
 					responses.add(NodeGraphMessage::RunDocumentGraph);
 					responses.add(DeferMessage::AfterGraphRun {
-						messages: vec![BrushToolMessage::DragStart.into()],
+						messages: vec![BrushToolMessage::DragStart { set_clone_source }.into()],
 					});
 					BrushToolFsmState::Ready
 				}
 			}
 
 			(BrushToolFsmState::Drawing, BrushToolMessage::PointerMove) => {
-				if let Some(layer) = tool_data.layer
-					&& let Some(stroke) = tool_data.strokes.last_mut()
-				{
-					let layer_position = document
-						.network_interface
-						.document_metadata()
-						.downstream_transform_to_viewport(layer)
-						.inverse()
-						.transform_point2(input.mouse.position);
-					let layer_position = tool_data.transform.inverse().transform_point2(layer_position);
-
-					stroke.trace.push(BrushInputSample { position: layer_position })
+				if let Some(layer) = tool_data.layer {
+					let layer_position = tool_data.layer_position(document, layer, input.mouse.position);
+
+					if let Some(stroke) = tool_data.strokes.last_mut() {
+						stroke.trace.push(input_sample(layer_position, &input.mouse.stylus))
+					}
 				}
 				tool_data.update_strokes(responses);
 
@@ -444,6 +823,7 @@ impl Fsm for BrushToolFsmState {
 		let hint_data = match self {
 			BrushToolFsmState::Ready => HintData(vec![
 				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Draw")]),
+				HintGroup(vec![HintInfo::keys_and_mouse([Key::Alt], MouseMotion::Lmb, "Set Clone Source")]),
 				HintGroup(vec![HintInfo::multi_keys([[Key::BracketLeft], [Key::BracketRight]], "Shrink/Grow Brush")]),
 			]),
 			BrushToolFsmState::Drawing => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
@@ -457,6 +837,29 @@ impl Fsm for BrushToolFsmState {
 	}
 }
 
+/// Grows or shrinks a brush diameter by `change`, landing on a multiple of it first if the diameter isn't one already.
+pub(super) fn change_diameter(diameter: f64, change: f64) -> f64 {
+	let needs_rounding = ((diameter + change.abs() / 2.) % change.abs() - change.abs() / 2.).abs() > 0.5;
+	let diameter = if needs_rounding && change > 0. {
+		(diameter / change.abs()).ceil() * change.abs()
+	} else if needs_rounding && change < 0. {
+		(diameter / change.abs()).floor() * change.abs()
+	} else {
+		(diameter / change.abs()).round() * change.abs() + change
+	};
+	diameter.max(1.)
+}
+
+pub(super) fn input_sample(position: DVec2, stylus: &StylusState) -> BrushInputSample {
+	BrushInputSample {
+		position,
+		pressure: stylus.pressure,
+		tilt: stylus.tilt,
+		twist: stylus.twist,
+		time: stylus.timestamp,
+	}
+}
+
 fn new_brush_layer(document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) -> LayerNodeIdentifier {
 	responses.add(DocumentMessage::DeselectAllLayers);
 
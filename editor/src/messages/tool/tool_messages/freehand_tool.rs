@@ -9,7 +9,7 @@ use crate::messages::tool::common_functionality::color_selector::{
 };
 use crate::messages::tool::common_functionality::graph_modification_utils;
 use crate::messages::tool::common_functionality::resize::translation_transform_set;
-use crate::messages::tool::common_functionality::stroke_options::{StrokeOptionsUpdate, apply_stroke_option, create_stroke_options_popover_widget};
+use crate::messages::tool::common_functionality::stroke_options::{StrokeOptionsUpdate, apply_pressure_width_profile, apply_stroke_option, create_stroke_options_popover_widget};
 use crate::messages::tool::common_functionality::utility_functions::should_extend;
 use glam::DVec2;
 use graph_craft::document::NodeId;
@@ -230,6 +230,9 @@ struct FreehandToolData {
 	/// Viewport-space start position for newly created layers, used to compute local-space
 	/// positions before the deferred TransformSet has been reflected in metadata.
 	new_layer_viewport_start: Option<DVec2>,
+	/// The layer-space position and stylus pressure of each point drawn into a newly created layer, recorded as the stroke's width profile when the pressure varied.
+	/// This is `None` while extending an existing path, whose length the recorded points would only be part of.
+	pressure_samples: Option<Vec<(DVec2, f64)>>,
 }
 
 impl Fsm for FreehandToolFsmState {
@@ -265,6 +268,7 @@ impl Fsm for FreehandToolFsmState {
 				tool_data.dragged = false;
 				tool_data.end_point = None;
 				tool_data.new_layer_viewport_start = None;
+				tool_data.pressure_samples = None;
 
 				// Extend an endpoint of the selected path
 				let selected_nodes = document.network_interface.selected_nodes();
@@ -306,6 +310,7 @@ impl Fsm for FreehandToolFsmState {
 				tool_options.drawing.fill.apply_fill(layer, responses);
 				tool_data.layer = Some(layer);
 				tool_data.new_layer_viewport_start = Some(input.mouse.position);
+				tool_data.pressure_samples = Some(Vec::new());
 
 				// Position the layer at the initial mouse position via Transform
 				responses.add(DeferMessage::AfterGraphRun {
@@ -333,12 +338,25 @@ impl Fsm for FreehandToolFsmState {
 						transform.inverse().transform_point2(input.mouse.position)
 					};
 
+					if let Some(samples) = &mut tool_data.pressure_samples
+						&& position.is_finite()
+						&& samples.last().is_none_or(|&(last, _)| last != position)
+					{
+						samples.push((position, input.mouse.stylus.pressure));
+					}
+
 					extend_path_with_next_segment(tool_data, position, true, responses);
 				}
 
 				FreehandToolFsmState::Drawing
 			}
 			(FreehandToolFsmState::Drawing, FreehandToolMessage::DragStop) => {
+				if let Some(layer) = tool_data.layer
+					&& let Some(samples) = tool_data.pressure_samples.take()
+				{
+					apply_pressure_width_profile(layer, &samples, document, responses);
+				}
+
 				if tool_data.dragged {
 					responses.add(DocumentMessage::CommitTransaction);
 				} else {
@@ -356,6 +374,7 @@ impl Fsm for FreehandToolFsmState {
 				tool_data.layer = None;
 				tool_data.end_point = None;
 				tool_data.new_layer_viewport_start = None;
+				tool_data.pressure_samples = None;
 
 				FreehandToolFsmState::Ready
 			}
@@ -532,6 +551,7 @@ mod test_freehand {
 					editor_position: last_initial_point,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -591,6 +611,7 @@ mod test_freehand {
 					editor_position: last_extension_point,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -645,6 +666,7 @@ mod test_freehand {
 					editor_position: last_initial_point,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -680,6 +702,7 @@ mod test_freehand {
 					editor_position: first_second_point,
 					mouse_keys: MouseKeys::LEFT,
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::SHIFT,
 			)
@@ -696,6 +719,7 @@ mod test_freehand {
 					editor_position: last_second_point,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::SHIFT,
 			)
@@ -769,6 +793,7 @@ mod test_freehand {
 					editor_position: last_point,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -13,10 +13,13 @@ use crate::messages::tool::common_functionality::graph_modification_utils::{
 };
 use crate::messages::tool::common_functionality::snapping::{SnapCandidatePoint, SnapConstraint, SnapData, SnapManager, SnapTypeConfiguration};
 use glam::DMat2;
+use graph_craft::document::NodeId;
 use graph_craft::document::value::TaggedValue;
 use graphene_std::color::SRGBA8;
 use graphene_std::raster::color::Color;
-use graphene_std::vector::style::{FillChoice, FillChoiceUI, Gradient, GradientSpreadMethod, GradientStop, GradientType, GradientUI, build_transform_with_y_preservation};
+use graphene_std::vector::style::{
+	FillChoice, FillChoiceUI, Gradient, GradientMesh, GradientMeshEdits, GradientSpreadMethod, GradientStop, GradientType, GradientUI, build_transform_with_y_preservation,
+};
 
 #[derive(Default, ExtractField)]
 pub struct GradientTool {
@@ -143,6 +146,12 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Grad
 					selected_gradient.gradient.color[stop_index] = color;
 					selected_gradient.render_gradient(responses);
 					responses.add(PropertiesPanelMessage::Refresh);
+				} else if let Some(vertex) = self.data.color_picker_editing_mesh_vertex
+					&& let Some(selected_gradient) = &mut self.data.selected_gradient
+					&& selected_gradient.dragging == GradientDragTarget::MeshVertex(vertex)
+				{
+					selected_gradient.mesh_edits.set_color(vertex as u32, color);
+					selected_gradient.render_gradient(responses);
 				}
 			}
 			ToolMessage::Gradient(GradientToolMessage::UpdateStops { stops }) => {
@@ -154,6 +163,7 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Grad
 					self.data.color_picker_transaction_open = false;
 				}
 				self.data.color_picker_editing_color_stop = None;
+				self.data.color_picker_editing_mesh_vertex = None;
 			}
 			ToolMessage::Gradient(GradientToolMessage::WorkingColorChanged) => {
 				let primary = context.global_tool_data.primary_color;
@@ -252,8 +262,26 @@ impl LayoutHolder for GradientTool {
 				}
 				.into()
 			}),
+			RadioEntryData::new("Conic").label("Conic").tooltip_label("Conic Gradient").on_update(move |_| {
+				GradientToolMessage::UpdateOptions {
+					options: GradientOptionsUpdate::Type(GradientType::Conic),
+				}
+				.into()
+			}),
+			RadioEntryData::new("Diamond").label("Diamond").tooltip_label("Diamond Gradient").on_update(move |_| {
+				GradientToolMessage::UpdateOptions {
+					options: GradientOptionsUpdate::Type(GradientType::Diamond),
+				}
+				.into()
+			}),
+			RadioEntryData::new("Mesh").label("Mesh").tooltip_label("Mesh Gradient").on_update(move |_| {
+				GradientToolMessage::UpdateOptions {
+					options: GradientOptionsUpdate::Type(GradientType::Mesh),
+				}
+				.into()
+			}),
 		])
-		.selected_index(Some((self.options.gradient_type == GradientType::Radial) as u32))
+		.selected_index(Some(self.options.gradient_type as u32))
 		.widget_instance();
 
 		// Display priority: the selected layer's stops, then any user-customized tool default, then the working colors
@@ -402,6 +430,8 @@ fn resolve_gradient(layer: LayerNodeIdentifier, network_interface: &NodeNetworkI
 					gradient_type: gradient.gradient_type,
 					spread_method: gradient.spread_method,
 					transform: gradient.transform,
+					mesh_grid: None,
+					mesh_node: None,
 				},
 				GradientSource::Direct,
 			));
@@ -420,10 +450,40 @@ struct GradientAppearance {
 	transform: DAffine2,
 	gradient_type: GradientType,
 	spread_method: GradientSpreadMethod,
+	/// The columns and rows of patches set by a 'Mesh Gradient' node, or `None` for the default mesh built from the stops.
+	mesh_grid: Option<(u32, u32)>,
+	/// The 'Mesh Gradient' node that sets the mesh grid, which stores the vertices moved and recolored with this tool.
+	mesh_node: Option<NodeId>,
 }
 
-/// Resolve the gradient transform, type, and spread method by walking the chain feeding the layer. Transform composes all
-/// 'Transform' nodes. Type and spread method come from the closest-to-layer node of each kind, or the type default.
+impl GradientAppearance {
+	/// The grid of patches drawn by a mesh gradient with these stops and vertex edits.
+	/// Colors given to the vertices by a wire into the node's vertex colors input aren't known here, so those vertices show the sampled colors.
+	fn mesh(&self, gradient: &Gradient, edits: &GradientMeshEdits) -> GradientMesh {
+		let mut mesh = match self.mesh_grid {
+			Some((columns, rows)) => GradientMesh::from_gradient(gradient, columns, rows),
+			None => GradientMesh::from(gradient),
+		};
+		edits.apply(&mut mesh);
+		mesh
+	}
+
+	/// The vertex edits stored on the 'Mesh Gradient' node, or none if there's no such node.
+	fn mesh_edits(&self, network_interface: &NodeNetworkInterface) -> GradientMeshEdits {
+		let input = self
+			.mesh_node
+			.and_then(|node_id| network_interface.document_network().nodes.get(&node_id))
+			.and_then(|node| node.inputs.get(graphene_std::math_nodes::mesh_gradient::VertexEditsInput::INDEX));
+
+		match input.and_then(|input| input.as_value()) {
+			Some(TaggedValue::GradientMeshEdits(edits)) => edits.clone(),
+			_ => GradientMeshEdits::default(),
+		}
+	}
+}
+
+/// Resolve the gradient transform, type, spread method, and mesh grid by walking the chain feeding the layer. Transform composes all
+/// 'Transform' nodes. Type, spread method, and mesh grid come from the closest-to-layer node of each kind, or the type default.
 fn read_gradient_chain_state(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> GradientAppearance {
 	let target_input = gradient_chain_target_input(layer, network_interface);
 	let walk_from = network_interface.upstream_output_connector(&target_input, &[]).and_then(|out| out.node_id()).unwrap_or(layer.to_node());
@@ -431,10 +491,13 @@ fn read_gradient_chain_state(layer: LayerNodeIdentifier, network_interface: &Nod
 	let transform_reference = DefinitionIdentifier::ProtoNode(graphene_std::transform_nodes::transform::IDENTIFIER);
 	let gradient_type_reference = DefinitionIdentifier::ProtoNode(graphene_std::math_nodes::gradient_type::IDENTIFIER);
 	let spread_method_reference = DefinitionIdentifier::ProtoNode(graphene_std::math_nodes::spread_method::IDENTIFIER);
+	let mesh_gradient_reference = DefinitionIdentifier::ProtoNode(graphene_std::math_nodes::mesh_gradient::IDENTIFIER);
 
 	let mut transforms_downstream_to_upstream: Vec<DAffine2> = Vec::new();
 	let mut gradient_type: Option<GradientType> = None;
 	let mut spread_method: Option<GradientSpreadMethod> = None;
+	let mut mesh_grid: Option<(u32, u32)> = None;
+	let mut mesh_node: Option<NodeId> = None;
 
 	for node_id in network_interface
 		.upstream_flow_back_from_nodes(vec![walk_from], &[], FlowType::HorizontalFlow)
@@ -458,6 +521,16 @@ fn read_gradient_chain_state(layer: LayerNodeIdentifier, network_interface: &Nod
 			&& let Some(TaggedValue::GradientSpreadMethod(value)) = document_node.inputs.get(1).and_then(|input| input.as_value())
 		{
 			spread_method = Some(*value);
+		} else if reference == mesh_gradient_reference && mesh_grid.is_none() {
+			// The node makes the gradient a mesh gradient, unless a closer node sets another type
+			gradient_type.get_or_insert(GradientType::Mesh);
+
+			let count = |index: usize| match document_node.inputs.get(index).and_then(|input| input.as_value()) {
+				Some(TaggedValue::U32(count)) => *count,
+				_ => 2,
+			};
+			mesh_grid = Some((count(1), count(2)));
+			mesh_node = Some(node_id);
 		}
 	}
 
@@ -468,6 +541,8 @@ fn read_gradient_chain_state(layer: LayerNodeIdentifier, network_interface: &Nod
 		transform: composed_transform,
 		gradient_type: gradient_type.unwrap_or_default(),
 		spread_method: spread_method.unwrap_or_default(),
+		mesh_grid,
+		mesh_node,
 	}
 }
 
@@ -511,6 +586,7 @@ pub enum GradientDragTarget {
 	End,
 	Stop(usize),
 	Midpoint(usize),
+	MeshVertex(usize),
 	New,
 }
 
@@ -527,6 +603,26 @@ struct SelectedGradient {
 	/// Transform from unit [0, 1] line to the geometry's local gradient space, the snapshot from `GradientAppearance.transform`.
 	initial_gradient_transform: DAffine2,
 	is_gradient_chain: bool,
+	/// The vertex edits stored on the mesh gradient's 'Mesh Gradient' node, changed when dragging or recoloring a vertex.
+	mesh_edits: GradientMeshEdits,
+	initial_mesh_edits: GradientMeshEdits,
+}
+
+/// The index of the mesh vertex closest to the mouse within the hit-test distance, if any.
+fn mesh_vertex_at(mouse: DVec2, mesh: &GradientMesh, unit_to_viewport: DAffine2) -> Option<usize> {
+	let tolerance = (MANIPULATOR_GROUP_MARKER_SIZE * 2.).powi(2);
+	mesh.points
+		.iter()
+		.map(|&point| unit_to_viewport.transform_point2(point).distance_squared(mouse))
+		.enumerate()
+		.filter(|&(_, distance_squared)| distance_squared < tolerance)
+		.min_by(|(_, a), (_, b)| a.total_cmp(b))
+		.map(|(index, _)| index)
+}
+
+/// Whether the vertices of a mesh gradient can be moved and recolored, which needs a gradient chain to hold the 'Mesh Gradient' node storing the edits.
+fn mesh_vertices_editable(appearance: &GradientAppearance, source: &GradientSource) -> bool {
+	appearance.gradient_type == GradientType::Mesh && *source == GradientSource::Chain
 }
 
 fn calculate_insertion(start: DVec2, end: DVec2, stops: &Gradient, mouse: DVec2) -> Option<f64> {
@@ -570,6 +666,7 @@ fn calculate_insertion(start: DVec2, end: DVec2, stops: &Gradient, mouse: DVec2)
 impl SelectedGradient {
 	pub fn new(gradient: Gradient, appearance: GradientAppearance, source: GradientSource, layer: LayerNodeIdentifier, document: &DocumentMessageHandler) -> Self {
 		let gradient_space_transform = gradient_space_transform(layer, document);
+		let mesh_edits = appearance.mesh_edits(&document.network_interface);
 		Self {
 			layer: Some(layer),
 			gradient_space_transform,
@@ -579,6 +676,8 @@ impl SelectedGradient {
 			initial_gradient: gradient,
 			initial_gradient_transform: appearance.transform,
 			is_gradient_chain: source == GradientSource::Chain,
+			mesh_edits: mesh_edits.clone(),
+			initial_mesh_edits: mesh_edits,
 		}
 	}
 
@@ -598,6 +697,7 @@ impl SelectedGradient {
 		if mouse.distance(drag_start) < DRAG_THRESHOLD {
 			self.gradient = self.initial_gradient.clone();
 			self.appearance.transform = self.initial_gradient_transform;
+			self.mesh_edits = self.initial_mesh_edits.clone();
 			self.render_gradient(responses);
 			return;
 		}
@@ -675,6 +775,12 @@ impl SelectedGradient {
 			GradientDragTarget::New => {
 				self.appearance.transform = create_new_gradient_transform(self.gradient_space_transform.inverse().transform_point2(drag_start), local_mouse);
 			}
+			GradientDragTarget::MeshVertex(vertex) => {
+				let unit_mouse = self.appearance.transform.inverse().transform_point2(local_mouse);
+				if unit_mouse.is_finite() {
+					self.mesh_edits.set_position(vertex as u32, unit_mouse);
+				}
+			}
 			GradientDragTarget::Stop(s) => {
 				let document_to_viewport = snap_data.document.metadata().document_to_viewport;
 
@@ -793,10 +899,15 @@ impl SelectedGradient {
 		self.render_gradient(responses);
 	}
 
-	/// Update the layer fill to the current gradient
+	/// Update the layer fill to the current gradient, or only the mesh's vertex edits while a mesh vertex is selected
 	pub fn render_gradient(&mut self, responses: &mut VecDeque<Message>) {
 		if let Some(layer) = self.layer {
-			if self.is_gradient_chain {
+			if let GradientDragTarget::MeshVertex(_) = self.dragging {
+				responses.add(GraphOperationMessage::GradientMeshEditsSet {
+					layer,
+					edits: self.mesh_edits.clone(),
+				});
+			} else if self.is_gradient_chain {
 				dispatch_gradient_chain_writes(layer, &self.gradient, self.appearance, responses);
 			} else {
 				responses.add(GraphOperationMessage::FillGradientSet {
@@ -880,6 +991,8 @@ struct GradientToolData {
 	primary_color: Color,
 	secondary_color: Color,
 	color_picker_editing_color_stop: Option<usize>,
+	/// The mesh vertex whose color is being edited by the color picker, which is shared with the color stops.
+	color_picker_editing_mesh_vertex: Option<usize>,
 	color_picker_transaction_open: bool,
 }
 
@@ -910,7 +1023,7 @@ impl Fsm for GradientToolFsmState {
 				let mouse = input.mouse.position;
 
 				for layer in document.network_interface.selected_nodes().selected_visible_layers(&document.network_interface) {
-					let Some((gradient, appearance, _source)) = resolve_gradient(layer, &document.network_interface) else {
+					let Some((gradient, appearance, source)) = resolve_gradient(layer, &document.network_interface) else {
 						continue;
 					};
 					let unit_to_viewport = gradient_space_transform(layer, document) * appearance.transform;
@@ -940,6 +1053,43 @@ impl Fsm for GradientToolFsmState {
 					let first_at_start = gradient.position.first().is_some_and(|&p| p.abs() < f64::EPSILON * 1000.);
 					let last_at_end = gradient.position.last().is_some_and(|&p| (1. - p).abs() < f64::EPSILON * 1000.);
 
+					// Outline the shapes of the gradient types whose colors spread out beyond what the gradient line shows
+					match appearance.gradient_type {
+						GradientType::Linear | GradientType::Radial | GradientType::Conic => {}
+						GradientType::Diamond => {
+							let corners = [DVec2::X, DVec2::Y, -DVec2::X, -DVec2::Y].map(|corner| unit_to_viewport.transform_point2(corner));
+							overlay_context.dashed_polygon(&corners, None, None, Some(4.), Some(4.), None);
+						}
+						GradientType::Mesh => {
+							let mesh_edits = match selected.filter(|s| s.layer == Some(layer) && matches!(s.dragging, GradientDragTarget::MeshVertex(_))) {
+								Some(selected_gradient) => selected_gradient.mesh_edits.clone(),
+								None => appearance.mesh_edits(&document.network_interface),
+							};
+							let mesh = appearance.mesh(gradient, &mesh_edits);
+							let vertex = |column: u32, row: u32| unit_to_viewport.transform_point2(mesh.points[(row * (mesh.columns + 1) + column) as usize]);
+							for row in 0..=mesh.rows {
+								for column in 0..=mesh.columns {
+									if column < mesh.columns {
+										overlay_context.dashed_line(vertex(column, row), vertex(column + 1, row), None, None, Some(4.), Some(4.), None);
+									}
+									if row < mesh.rows {
+										overlay_context.dashed_line(vertex(column, row), vertex(column, row + 1), None, None, Some(4.), Some(4.), None);
+									}
+								}
+							}
+
+							// Vertices that can be edited are drawn selected while dragged or hovered
+							let editable = mesh_vertices_editable(&appearance, &source);
+							let hovered_vertex = (editable && !matches!(self, GradientToolFsmState::Drawing { .. }))
+								.then(|| mesh_vertex_at(mouse, &mesh, unit_to_viewport))
+								.flatten();
+							for (index, point) in mesh.points.iter().enumerate() {
+								let emphasized = dragging == Some(GradientDragTarget::MeshVertex(index)) || hovered_vertex == Some(index);
+								overlay_context.manipulator_anchor(unit_to_viewport.transform_point2(*point), emphasized, None);
+							}
+						}
+					}
+
 					overlay_context.line(start, end, None, None);
 
 					// Determine which stop is selected (being dragged) and hovered (closest to mouse)
@@ -1107,17 +1257,22 @@ impl Fsm for GradientToolFsmState {
 						responses.add(FrontendMessage::UpdateGradientStopColorPickerPosition { color: color.into(), position });
 					}
 				}
+				if let Some(vertex) = tool_data.color_picker_editing_mesh_vertex
+					&& let Some(selected_gradient) = tool_data.selected_gradient.as_ref()
+					&& let Some(layer) = selected_gradient.layer
+				{
+					let transform = gradient_space_transform(layer, document) * selected_gradient.appearance.transform;
+					let mesh = selected_gradient.appearance.mesh(&selected_gradient.gradient, &selected_gradient.mesh_edits);
+					if let (Some(&point), Some(&color)) = (mesh.points.get(vertex), mesh.colors.get(vertex)) {
+						let position = transform.transform_point2(point).into();
+						responses.add(FrontendMessage::UpdateGradientStopColorPickerPosition { color: color.into(), position });
+					}
+				}
 
 				self
 			}
 			(GradientToolFsmState::Ready { .. }, GradientToolMessage::SelectionChanged) => {
-				if tool_data.color_picker_editing_color_stop.is_some() {
-					if tool_data.color_picker_transaction_open {
-						responses.add(DocumentMessage::EndTransaction);
-						tool_data.color_picker_transaction_open = false;
-					}
-					tool_data.color_picker_editing_color_stop = None;
-				}
+				dismiss_color_stop_color_picker(tool_data, responses);
 				tool_data.selected_gradient = None;
 				GradientToolFsmState::Ready {
 					hovering: GradientHoverTarget::None,
@@ -1149,10 +1304,11 @@ impl Fsm for GradientToolFsmState {
 								&& stop_index < selected_gradient.gradient.color.len()
 							{
 								// Dismiss any existing color picker first
-								if tool_data.color_picker_editing_color_stop.is_some() && tool_data.color_picker_transaction_open {
+								if (tool_data.color_picker_editing_color_stop.is_some() || tool_data.color_picker_editing_mesh_vertex.is_some()) && tool_data.color_picker_transaction_open {
 									responses.add(DocumentMessage::EndTransaction);
 									tool_data.color_picker_transaction_open = false;
 								}
+								tool_data.color_picker_editing_mesh_vertex = None;
 
 								let stop_pos = selected_gradient.gradient.position[stop_index];
 								let (start, end) = selected_gradient.viewport_handle_positions();
@@ -1163,6 +1319,21 @@ impl Fsm for GradientToolFsmState {
 								responses.add(FrontendMessage::UpdateGradientStopColorPickerPosition { color: color.into(), position });
 							}
 						}
+						GradientDragTarget::MeshVertex(vertex) => {
+							// Dismiss any existing color picker first
+							if (tool_data.color_picker_editing_color_stop.is_some() || tool_data.color_picker_editing_mesh_vertex.is_some()) && tool_data.color_picker_transaction_open {
+								responses.add(DocumentMessage::EndTransaction);
+								tool_data.color_picker_transaction_open = false;
+							}
+							tool_data.color_picker_editing_color_stop = None;
+
+							let mesh = selected_gradient.appearance.mesh(&selected_gradient.gradient, &selected_gradient.mesh_edits);
+							if let (Some(&point), Some(&color)) = (mesh.points.get(vertex), mesh.colors.get(vertex)) {
+								let position = selected_gradient.unit_to_viewport_transform().transform_point2(point).into();
+								tool_data.color_picker_editing_mesh_vertex = Some(vertex);
+								responses.add(FrontendMessage::UpdateGradientStopColorPickerPosition { color: color.into(), position });
+							}
+						}
 						_ => {}
 					}
 				}
@@ -1186,6 +1357,7 @@ impl Fsm for GradientToolFsmState {
 				// If we're in the middle of a drag, abort it first and revert to the initial gradient
 				if matches!(state, GradientToolFsmState::Drawing { .. }) {
 					selected_gradient.gradient = selected_gradient.initial_gradient.clone();
+					selected_gradient.mesh_edits = selected_gradient.initial_mesh_edits.clone();
 					selected_gradient.render_gradient(responses);
 					responses.add(DocumentMessage::AbortTransaction);
 					tool_data.snap_manager.cleanup(responses);
@@ -1227,6 +1399,15 @@ impl Fsm for GradientToolFsmState {
 						responses.add(DocumentMessage::CommitTransaction);
 						responses.add(PropertiesPanelMessage::Refresh);
 
+						return ready_default;
+					}
+					GradientDragTarget::MeshVertex(vertex) => {
+						// Return the vertex to where the grid places it, with the color the grid gives it
+						selected_gradient.mesh_edits.reset(vertex as u32);
+						selected_gradient.render_gradient(responses);
+
+						responses.add(DocumentMessage::CommitTransaction);
+
 						return ready_default;
 					}
 				};
@@ -1331,6 +1512,28 @@ impl Fsm for GradientToolFsmState {
 					let unit_to_viewport = gradient_space_transform * appearance.transform;
 					let is_gradient_chain = source == GradientSource::Chain;
 					let (start, end) = gradient_handle_positions(unit_to_viewport);
+					let mesh_edits = appearance.mesh_edits(&document.network_interface);
+
+					// Check for dragging a mesh vertex first, since the mesh is what a mesh gradient draws and its vertices can cover the gradient line's handles
+					if drag_hint.is_none()
+						&& mesh_vertices_editable(&appearance, &source)
+						&& let Some(vertex) = mesh_vertex_at(mouse, &appearance.mesh(&gradient, &mesh_edits), unit_to_viewport)
+					{
+						drag_hint = Some(GradientDragHintState::MeshVertex);
+
+						tool_data.selected_gradient = Some(SelectedGradient {
+							layer: Some(layer),
+							dragging: GradientDragTarget::MeshVertex(vertex),
+							gradient_space_transform,
+							gradient: gradient.clone(),
+							appearance,
+							initial_gradient: gradient.clone(),
+							initial_gradient_transform: appearance.transform,
+							is_gradient_chain,
+							mesh_edits: mesh_edits.clone(),
+							initial_mesh_edits: mesh_edits.clone(),
+						});
+					}
 
 					// Check for dragging a midpoint diamond
 					if drag_hint.is_none() {
@@ -1360,6 +1563,8 @@ impl Fsm for GradientToolFsmState {
 									dragging: GradientDragTarget::Midpoint(i),
 									initial_gradient: gradient.clone(),
 									is_gradient_chain,
+									mesh_edits: mesh_edits.clone(),
+									initial_mesh_edits: mesh_edits.clone(),
 								});
 
 								break;
@@ -1403,6 +1608,8 @@ impl Fsm for GradientToolFsmState {
 								initial_gradient: gradient.clone(),
 								initial_gradient_transform: appearance.transform,
 								is_gradient_chain,
+								mesh_edits: mesh_edits.clone(),
+								initial_mesh_edits: mesh_edits.clone(),
 							});
 						}
 					}
@@ -1421,6 +1628,8 @@ impl Fsm for GradientToolFsmState {
 									initial_gradient: gradient.clone(),
 									initial_gradient_transform: appearance.transform,
 									is_gradient_chain,
+									mesh_edits: mesh_edits.clone(),
+									initial_mesh_edits: mesh_edits.clone(),
 								})
 							}
 						}
@@ -1512,6 +1721,8 @@ impl Fsm for GradientToolFsmState {
 									transform: DAffine2::IDENTITY,
 									gradient_type: tool_options.gradient_type,
 									spread_method: tool_options.spread_method,
+									mesh_grid: None,
+									mesh_node: None,
 								},
 								GradientSource::Direct,
 							),
@@ -1689,6 +1900,10 @@ impl Fsm for GradientToolFsmState {
 							groups.push(HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDouble, "Reset Midpoint")]));
 						}
 					}
+					GradientHoverTarget::MeshVertex => {
+						groups.push(HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Move Mesh Vertex")]));
+						groups.push(HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDouble, "Set Vertex Color")]));
+					}
 				}
 
 				// Delete/reset hint based on selection
@@ -1699,6 +1914,9 @@ impl Fsm for GradientToolFsmState {
 					GradientSelectedTarget::Midpoint { resettable: true } => {
 						groups.push(HintGroup(vec![HintInfo::keys([Key::Backspace], "Reset Midpoint")]));
 					}
+					GradientSelectedTarget::MeshVertex => {
+						groups.push(HintGroup(vec![HintInfo::keys([Key::Backspace], "Reset Mesh Vertex")]));
+					}
 					_ => {}
 				}
 
@@ -1723,6 +1941,9 @@ impl Fsm for GradientToolFsmState {
 					GradientDragHintState::Midpoint { resettable: true } => {
 						groups.push(HintGroup(vec![HintInfo::keys([Key::Backspace], "Reset Midpoint")]));
 					}
+					GradientDragHintState::MeshVertex => {
+						groups.push(HintGroup(vec![HintInfo::keys([Key::Backspace], "Reset Mesh Vertex")]));
+					}
 					_ => {}
 				}
 
@@ -1745,12 +1966,13 @@ fn insert_stop_at_point(gradient: &mut Gradient, point: DVec2, unit_to_viewport:
 }
 
 fn dismiss_color_stop_color_picker(tool_data: &mut GradientToolData, responses: &mut VecDeque<Message>) {
-	if tool_data.color_picker_editing_color_stop.is_some() {
+	if tool_data.color_picker_editing_color_stop.is_some() || tool_data.color_picker_editing_mesh_vertex.is_some() {
 		if tool_data.color_picker_transaction_open {
 			responses.add(DocumentMessage::EndTransaction);
 			tool_data.color_picker_transaction_open = false;
 		}
 		tool_data.color_picker_editing_color_stop = None;
+		tool_data.color_picker_editing_mesh_vertex = None;
 	}
 }
 
@@ -1759,7 +1981,7 @@ fn detect_hover_target(mouse: DVec2, document: &DocumentMessageHandler) -> Gradi
 	let midpoint_tolerance = GRADIENT_MIDPOINT_DIAMOND_RADIUS.powi(2);
 
 	for layer in document.network_interface.selected_nodes().selected_visible_layers(&document.network_interface) {
-		let Some((gradient, appearance, _source)) = resolve_gradient(layer, &document.network_interface) else {
+		let Some((gradient, appearance, source)) = resolve_gradient(layer, &document.network_interface) else {
 			continue;
 		};
 		let gradient_space_transform = gradient_space_transform(layer, document);
@@ -1767,6 +1989,14 @@ fn detect_hover_target(mouse: DVec2, document: &DocumentMessageHandler) -> Gradi
 		let (start, end) = gradient_handle_positions(unit_to_viewport);
 		let line_length = start.distance(end);
 
+		// Check mesh vertices first, matching the click detection priority
+		if mesh_vertices_editable(&appearance, &source) {
+			let mesh = appearance.mesh(&gradient, &appearance.mesh_edits(&document.network_interface));
+			if mesh_vertex_at(mouse, &mesh, unit_to_viewport).is_some() {
+				return GradientHoverTarget::MeshVertex;
+			}
+		}
+
 		// Check midpoint diamonds first (smaller hit area, higher priority)
 		for i in 0..gradient.position.len().saturating_sub(1) {
 			let left = gradient.position[i];
@@ -1823,6 +2053,7 @@ fn compute_selected_target(tool_data: &GradientToolData) -> GradientSelectedTarg
 			let resettable = selected_gradient.gradient.midpoint.get(i).is_some_and(|&midpoint_value| midpoint_is_resettable(midpoint_value));
 			GradientSelectedTarget::Midpoint { resettable }
 		}
+		GradientDragTarget::MeshVertex(_) => GradientSelectedTarget::MeshVertex,
 		GradientDragTarget::New => GradientSelectedTarget::None,
 	}
 }
@@ -1976,6 +2207,7 @@ enum GradientHoverTarget {
 	Midpoint {
 		resettable: bool,
 	},
+	MeshVertex,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -1986,6 +2218,7 @@ enum GradientSelectedTarget {
 	Midpoint {
 		resettable: bool,
 	},
+	MeshVertex,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -1998,6 +2231,7 @@ enum GradientDragHintState {
 	Midpoint {
 		resettable: bool,
 	},
+	MeshVertex,
 }
 
 #[cfg(test)]
@@ -2399,6 +2633,7 @@ mod test_gradient {
 					editor_position: end_pos,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -2453,6 +2688,7 @@ mod test_gradient {
 					editor_position: click_position,
 					mouse_keys: MouseKeys::LEFT,
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -2467,6 +2703,7 @@ mod test_gradient {
 					editor_position: drag_position,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -2532,6 +2769,7 @@ mod test_gradient {
 					editor_position: position2,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -2697,6 +2935,7 @@ mod test_gradient {
 					editor_position: new_viewport_end,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
@@ -2774,6 +3013,7 @@ mod test_gradient {
 					editor_position: new_viewport_end,
 					mouse_keys: MouseKeys::empty(),
 					scroll_delta: ScrollDelta::default(),
+					..Default::default()
 				},
 				ModifierKeys::empty(),
 			)
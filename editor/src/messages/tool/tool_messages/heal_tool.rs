@@ -0,0 +1,273 @@
+use super::brush_tool::{BRUSH_MAX_SIZE, BrushToolData, change_diameter, input_sample};
+use super::tool_prelude::*;
+use crate::consts::DEFAULT_BRUSH_SIZE;
+use graphene_std::brush::brush_stroke::{BrushMode, BrushSampling, BrushStroke, BrushStyle};
+
+#[derive(Default, ExtractField)]
+pub struct HealTool {
+	fsm_state: HealToolFsmState,
+	data: HealToolData,
+	options: HealOptions,
+}
+
+pub struct HealOptions {
+	diameter: f64,
+	hardness: f64,
+	strength: f64,
+}
+
+impl Default for HealOptions {
+	fn default() -> Self {
+		Self {
+			diameter: DEFAULT_BRUSH_SIZE,
+			hardness: 0.,
+			strength: 100.,
+		}
+	}
+}
+
+#[impl_message(Message, ToolMessage, Heal)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum HealToolMessage {
+	// Standard messages
+	Abort,
+
+	// Tool-specific messages
+	DragStart { set_source: Key },
+	DragStop,
+	PointerMove,
+	UpdateOptions { options: HealToolMessageOptionsUpdate },
+}
+
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum HealToolMessageOptionsUpdate {
+	ChangeDiameter(f64),
+	Diameter(f64),
+	Hardness(f64),
+	Strength(f64),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum HealToolFsmState {
+	#[default]
+	Ready,
+	Healing,
+}
+
+impl ToolMetadata for HealTool {
+	fn icon_name(&self) -> String {
+		"RasterHealTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Heal Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::Heal
+	}
+}
+
+impl LayoutHolder for HealTool {
+	fn layout(&self) -> Layout {
+		let widgets = vec![
+			NumberInput::new(Some(self.options.diameter))
+				.label("Diameter")
+				.min(1.)
+				.max(BRUSH_MAX_SIZE)
+				.unit(" px")
+				.on_update(|number_input: &NumberInput| {
+					HealToolMessage::UpdateOptions {
+						options: HealToolMessageOptionsUpdate::Diameter(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			NumberInput::new(Some(self.options.hardness))
+				.label("Hardness")
+				.min(0.)
+				.max(100.)
+				.mode_range()
+				.unit("%")
+				.on_update(|number_input: &NumberInput| {
+					HealToolMessage::UpdateOptions {
+						options: HealToolMessageOptionsUpdate::Hardness(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			NumberInput::new(Some(self.options.strength))
+				.label("Strength")
+				.min(1.)
+				.max(100.)
+				.mode_range()
+				.unit("%")
+				.tooltip_description("How much of the healed result replaces the original pixels.")
+				.on_update(|number_input: &NumberInput| {
+					HealToolMessage::UpdateOptions {
+						options: HealToolMessageOptionsUpdate::Strength(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+		];
+
+		Layout(vec![LayoutGroup::row(widgets)])
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for HealTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		let ToolMessage::Heal(HealToolMessage::UpdateOptions { options }) = message else {
+			self.fsm_state.process_event(message, &mut self.data, context, &self.options, responses, true);
+			return;
+		};
+		match options {
+			HealToolMessageOptionsUpdate::ChangeDiameter(change) => self.options.diameter = change_diameter(self.options.diameter, change),
+			HealToolMessageOptionsUpdate::Diameter(diameter) => self.options.diameter = diameter,
+			HealToolMessageOptionsUpdate::Hardness(hardness) => self.options.hardness = hardness,
+			HealToolMessageOptionsUpdate::Strength(strength) => self.options.strength = strength,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			HealToolFsmState::Ready => actions!(HealToolMessageDiscriminant;
+				DragStart,
+				DragStop,
+				UpdateOptions,
+			),
+			HealToolFsmState::Healing => actions!(HealToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+				UpdateOptions,
+			),
+		}
+	}
+}
+
+impl ToolTransition for HealTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(HealToolMessage::Abort.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct HealToolData {
+	brush: BrushToolData,
+	/// The point in layer space which the healing copies its texture from, set by Alt-clicking.
+	source: Option<DVec2>,
+	/// The offset from the stroke to the source, which is kept for every stroke after the first one so they stay aligned.
+	source_offset: Option<DVec2>,
+}
+
+impl Fsm for HealToolFsmState {
+	type ToolData = HealToolData;
+	type ToolOptions = HealOptions;
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::Heal(event) = event else { return self };
+		match (self, event) {
+			(HealToolFsmState::Ready, HealToolMessage::DragStart { set_source }) => {
+				let Some(layer) = tool_data.brush.load_selected_raster_layer(document) else { return self };
+				let layer_position = tool_data.brush.layer_position(document, layer, input.mouse.position);
+
+				// Alt-clicking sets the point which the texture is copied from instead of healing
+				if input.keyboard.key(set_source) {
+					tool_data.source = Some(layer_position);
+					tool_data.source_offset = None;
+					tool_data.brush.strokes.clear();
+
+					return self;
+				}
+				let Some(source) = tool_data.source else {
+					tool_data.brush.strokes.clear();
+					return self;
+				};
+
+				responses.add(DocumentMessage::StartTransaction);
+
+				let source_offset = *tool_data.source_offset.get_or_insert(source - layer_position);
+				let diameter = tool_options.diameter / tool_data.brush.layer_scale(document, layer);
+				tool_data.brush.strokes.push(BrushStroke {
+					trace: vec![input_sample(layer_position, &input.mouse.stylus)],
+					style: BrushStyle {
+						diameter,
+						hardness: tool_options.hardness,
+						spacing: 20.,
+						mode: BrushMode::Heal,
+						sampling: BrushSampling {
+							strength: tool_options.strength,
+							source_offset,
+							..Default::default()
+						},
+						..Default::default()
+					},
+				});
+
+				tool_data.brush.update_strokes(responses);
+				HealToolFsmState::Healing
+			}
+			(HealToolFsmState::Healing, HealToolMessage::PointerMove) => {
+				if let Some(layer) = tool_data.brush.layer {
+					let layer_position = tool_data.brush.layer_position(document, layer, input.mouse.position);
+
+					if let Some(stroke) = tool_data.brush.strokes.last_mut() {
+						stroke.trace.push(input_sample(layer_position, &input.mouse.stylus));
+					}
+				}
+				tool_data.brush.update_strokes(responses);
+
+				HealToolFsmState::Healing
+			}
+			(HealToolFsmState::Healing, HealToolMessage::DragStop) => {
+				responses.add(DocumentMessage::EndTransaction);
+				tool_data.brush.strokes.clear();
+
+				HealToolFsmState::Ready
+			}
+			(HealToolFsmState::Healing, HealToolMessage::Abort) => {
+				responses.add(DocumentMessage::AbortTransaction);
+				tool_data.brush.strokes.clear();
+
+				HealToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			HealToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![HintInfo::keys_and_mouse([Key::Alt], MouseMotion::Lmb, "Set Source")]),
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Heal")]),
+				HintGroup(vec![HintInfo::multi_keys([[Key::BracketLeft], [Key::BracketRight]], "Shrink/Grow Brush")]),
+			]),
+			HealToolFsmState::Healing => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Default });
+	}
+}
@@ -4,7 +4,9 @@ pub mod eyedropper_tool;
 pub mod fill_tool;
 pub mod freehand_tool;
 pub mod gradient_tool;
+pub mod heal_tool;
 pub mod navigate_tool;
+pub mod patch_tool;
 pub mod path_tool;
 pub mod pen_tool;
 pub mod select_tool;
@@ -0,0 +1,301 @@
+use super::brush_tool::BrushToolData;
+use super::select_tool::extend_lasso;
+use super::tool_prelude::*;
+use crate::consts::{COLOR_OVERLAY_BLUE_05, COLOR_OVERLAY_BLUE_50};
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use graphene_std::brush::brush_stroke::{BrushInputSample, BrushMode, BrushSampling, BrushStroke, BrushStyle, polygon_contains};
+
+#[derive(Default, ExtractField)]
+pub struct PatchTool {
+	fsm_state: PatchToolFsmState,
+	data: PatchToolData,
+	options: PatchOptions,
+}
+
+pub struct PatchOptions {
+	feather: f64,
+}
+
+impl Default for PatchOptions {
+	fn default() -> Self {
+		Self { feather: 4. }
+	}
+}
+
+#[impl_message(Message, ToolMessage, Patch)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum PatchToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DragStart,
+	DragStop,
+	PointerMove,
+	UpdateOptions { options: PatchToolMessageOptionsUpdate },
+}
+
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum PatchToolMessageOptionsUpdate {
+	Feather(f64),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum PatchToolFsmState {
+	#[default]
+	Ready,
+	Selecting,
+	Dragging,
+}
+
+impl ToolMetadata for PatchTool {
+	fn icon_name(&self) -> String {
+		"RasterPatchTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Patch Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::Patch
+	}
+}
+
+impl LayoutHolder for PatchTool {
+	fn layout(&self) -> Layout {
+		let widgets = vec![
+			NumberInput::new(Some(self.options.feather))
+				.label("Feather")
+				.min(0.)
+				.max(1000.)
+				.unit(" px")
+				.tooltip_description("The width of the soft edge within the patched region over which it fades into the original pixels.")
+				.on_update(|number_input: &NumberInput| {
+					PatchToolMessage::UpdateOptions {
+						options: PatchToolMessageOptionsUpdate::Feather(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+		];
+
+		Layout(vec![LayoutGroup::row(widgets)])
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for PatchTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		let ToolMessage::Patch(PatchToolMessage::UpdateOptions { options }) = message else {
+			self.fsm_state.process_event(message, &mut self.data, context, &self.options, responses, true);
+			return;
+		};
+		match options {
+			PatchToolMessageOptionsUpdate::Feather(feather) => self.options.feather = feather,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			PatchToolFsmState::Ready => actions!(PatchToolMessageDiscriminant;
+				DragStart,
+				Abort,
+				UpdateOptions,
+			),
+			PatchToolFsmState::Selecting | PatchToolFsmState::Dragging => actions!(PatchToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+				UpdateOptions,
+			),
+		}
+	}
+}
+
+impl ToolTransition for PatchTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(PatchToolMessage::Abort.into()),
+			overlay_provider: Some(|context: OverlayContext| PatchToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct PatchToolData {
+	brush: BrushToolData,
+	/// The outline being drawn around the region to patch, in viewport space.
+	lasso_polygon: Vec<DVec2>,
+	/// The outline of the region to patch, in the layer space of `region_layer`.
+	region: Vec<DVec2>,
+	region_layer: Option<LayerNodeIdentifier>,
+	/// Where the region was picked up from, in layer space.
+	drag_start: DVec2,
+	/// How far the region has been dragged onto the area it's patched from, in layer space.
+	drag_offset: DVec2,
+}
+
+impl PatchToolData {
+	fn clear_region(&mut self) {
+		self.region.clear();
+		self.region_layer = None;
+		self.drag_offset = DVec2::ZERO;
+	}
+}
+
+impl Fsm for PatchToolFsmState {
+	type ToolData = PatchToolData;
+	type ToolOptions = PatchOptions;
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::Patch(event) = event else { return self };
+		match (self, event) {
+			(_, PatchToolMessage::Overlays { context: mut overlay_context }) => {
+				if self == PatchToolFsmState::Selecting {
+					overlay_context.dashed_polygon(&tool_data.lasso_polygon, None, Some(COLOR_OVERLAY_BLUE_05), Some(4.), Some(4.), Some(0.5));
+				}
+
+				if let Some(layer) = tool_data.region_layer
+					&& document.network_interface.selected_nodes().selected_layers(document.metadata()).any(|selected| selected == layer)
+				{
+					let layer_to_viewport = tool_data.brush.layer_to_viewport(document, layer);
+					let region: Vec<_> = tool_data.region.iter().map(|&point| layer_to_viewport.transform_point2(point)).collect();
+					overlay_context.dashed_polygon(&region, None, Some(COLOR_OVERLAY_BLUE_05), Some(4.), Some(4.), Some(0.5));
+
+					// The area the region is patched from follows the pointer while dragging
+					if self == PatchToolFsmState::Dragging {
+						let source: Vec<_> = tool_data.region.iter().map(|&point| layer_to_viewport.transform_point2(point + tool_data.drag_offset)).collect();
+						overlay_context.polygon(&source, Some(COLOR_OVERLAY_BLUE_50), None);
+					}
+				}
+
+				self
+			}
+			(PatchToolFsmState::Ready, PatchToolMessage::DragStart) => {
+				let Some(layer) = tool_data.brush.load_selected_raster_layer(document) else { return self };
+				let layer_position = tool_data.brush.layer_position(document, layer, input.mouse.position);
+
+				// Dragging from inside the region picks it up to choose where it's patched from, while dragging elsewhere outlines a new region
+				if tool_data.region_layer == Some(layer) && polygon_contains(&tool_data.region, layer_position) {
+					tool_data.drag_start = layer_position;
+					tool_data.drag_offset = DVec2::ZERO;
+
+					return PatchToolFsmState::Dragging;
+				}
+
+				tool_data.clear_region();
+				tool_data.lasso_polygon = vec![input.mouse.position];
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Selecting
+			}
+			(PatchToolFsmState::Selecting, PatchToolMessage::PointerMove) => {
+				extend_lasso(&mut tool_data.lasso_polygon, input.mouse.position);
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Selecting
+			}
+			(PatchToolFsmState::Selecting, PatchToolMessage::DragStop) => {
+				if let Some(layer) = tool_data.brush.layer
+					&& tool_data.lasso_polygon.len() >= 3
+				{
+					let viewport_to_layer = tool_data.brush.layer_to_viewport(document, layer).inverse();
+					tool_data.region = tool_data.lasso_polygon.iter().map(|&point| viewport_to_layer.transform_point2(point)).collect();
+					tool_data.region_layer = Some(layer);
+				}
+				tool_data.lasso_polygon.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Ready
+			}
+			(PatchToolFsmState::Dragging, PatchToolMessage::PointerMove) => {
+				if let Some(layer) = tool_data.region_layer {
+					tool_data.drag_offset = tool_data.brush.layer_position(document, layer, input.mouse.position) - tool_data.drag_start;
+				}
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Dragging
+			}
+			(PatchToolFsmState::Dragging, PatchToolMessage::DragStop) => {
+				if let Some(layer) = tool_data.region_layer
+					&& tool_data.drag_offset != DVec2::ZERO
+				{
+					responses.add(DocumentMessage::StartTransaction);
+
+					// The region keeps its place, and is filled from where it was dragged to
+					let trace = tool_data.region.iter().map(|&position| BrushInputSample { position, ..Default::default() }).collect();
+					let feather = tool_options.feather / tool_data.brush.layer_scale(document, layer);
+					tool_data.brush.strokes.push(BrushStroke {
+						trace,
+						style: BrushStyle {
+							mode: BrushMode::Patch,
+							sampling: BrushSampling {
+								strength: 100.,
+								source_offset: tool_data.drag_offset,
+								feather,
+							},
+							..Default::default()
+						},
+					});
+					tool_data.brush.update_strokes(responses);
+
+					responses.add(DocumentMessage::EndTransaction);
+				}
+				tool_data.brush.strokes.clear();
+				tool_data.drag_offset = DVec2::ZERO;
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Ready
+			}
+			(PatchToolFsmState::Ready, PatchToolMessage::Abort) => {
+				tool_data.clear_region();
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Ready
+			}
+			(_, PatchToolMessage::Abort) => {
+				tool_data.lasso_polygon.clear();
+				tool_data.drag_offset = DVec2::ZERO;
+				tool_data.brush.strokes.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			PatchToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Outline Region")]),
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Drag Region onto Source")]),
+				HintGroup(vec![HintInfo::keys([Key::Escape], "Deselect Region")]),
+			]),
+			PatchToolFsmState::Selecting | PatchToolFsmState::Dragging => {
+				HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])])
+			}
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Default });
+	}
+}
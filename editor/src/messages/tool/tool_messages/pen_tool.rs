@@ -14,7 +14,9 @@ use crate::messages::tool::common_functionality::graph_modification_utils::{self
 use crate::messages::tool::common_functionality::resize::translation_transform_set;
 use crate::messages::tool::common_functionality::shape_editor::ShapeState;
 use crate::messages::tool::common_functionality::snapping::{SnapCache, SnapCandidatePoint, SnapConstraint, SnapData, SnapManager, SnapTypeConfiguration};
-use crate::messages::tool::common_functionality::stroke_options::{StrokeOptionsUpdate, apply_stroke_option, create_stroke_options_popover_widget};
+use crate::messages::tool::common_functionality::stroke_options::{
+	StrokeOptionsUpdate, apply_pressure_width_profile, apply_stroke_option, create_stroke_options_popover_widget, pressure_width_profile,
+};
 use crate::messages::tool::common_functionality::utility_functions::{calculate_segment_angle, closest_point, should_extend};
 use graph_craft::document::NodeId;
 use graphene_std::Color;
@@ -436,6 +438,10 @@ struct PenToolData {
 	handle_start_offset: Option<DVec2>,
 	handle_end_offset: Option<DVec2>,
 
+	/// The layer-space position and stylus pressure of each anchor placed into a newly created layer, recorded as the stroke's width profile once the path is finished.
+	/// This is `None` while extending an existing path, whose length the placed anchors would only be part of.
+	pressure_samples: Option<Vec<(DVec2, f64)>>,
+
 	snap_cache: SnapCache,
 }
 
@@ -739,6 +745,7 @@ impl PenToolData {
 		let next_handle_start = self.next_handle_start;
 		let handle_start = self.latest_point()?.handle_start;
 		let mouse = snap_data.input.mouse.position;
+		let pressure = snap_data.input.mouse.stylus.pressure;
 		self.handle_swapped = false;
 		self.handle_end_offset = None;
 		self.handle_start_offset = None;
@@ -771,6 +778,13 @@ impl PenToolData {
 		}
 		let close_subpath = end.is_some();
 
+		// Record the pressure at the segment's end, dropping any anchors that were undone before it, and finish recording once the path is closed
+		if let Some(samples) = &mut self.pressure_samples {
+			samples.truncate(self.point_index + 1);
+			samples.push((next_point, pressure));
+		}
+		let closed_path_pressure_samples = if close_subpath { self.pressure_samples.take() } else { None };
+
 		// Generate new point if not closing
 		let end = end.unwrap_or_else(|| {
 			let end = PointId::generate();
@@ -842,6 +856,9 @@ impl PenToolData {
 		self.path_closed = false;
 		self.prior_segment_endpoint = None;
 		responses.add(DocumentMessage::EndTransaction);
+		if let Some(samples) = closed_path_pressure_samples {
+			self.record_pressure_width_profile(samples, document, responses);
+		}
 		Some(if close_subpath { PenToolFsmState::Ready } else { PenToolFsmState::PlacingAnchor })
 	}
 
@@ -1295,6 +1312,7 @@ impl PenToolData {
 
 		let selected_nodes = document.network_interface.selected_nodes();
 		self.handle_end = None;
+		self.pressure_samples = None;
 
 		let tolerance = crate::consts::SNAP_POINT_TOLERANCE;
 		let extension_choice = should_extend(document, viewport_vec, tolerance, selected_nodes.selected_layers(document.metadata()));
@@ -1366,6 +1384,7 @@ impl PenToolData {
 			in_segment: None,
 			handle_start: DVec2::ZERO,
 		});
+		self.pressure_samples = Some(vec![(DVec2::ZERO, input.mouse.stylus.pressure)]);
 		self.next_point = DVec2::ZERO;
 		self.next_handle_start = DVec2::ZERO;
 		self.handle_end = None;
@@ -1514,6 +1533,24 @@ impl PenToolData {
 		self.next_handle_start = pos;
 		self.handle_end = None;
 	}
+
+	/// Finishes recording the pressure when a new path is ended without closing it, leaving out any anchors that were undone.
+	fn finish_pressure_width_profile(&mut self, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some(mut samples) = self.pressure_samples.take() else { return };
+		samples.truncate(self.point_index + 1);
+		self.record_pressure_width_profile(samples, document, responses);
+	}
+
+	/// Sets the finished new path's stroke width profile in its own history step to follow the stylus pressure recorded at each anchor.
+	/// Segments are measured as the straight distance between their anchors, which is close enough for the pressure's position along curves.
+	fn record_pressure_width_profile(&self, samples: Vec<(DVec2, f64)>, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some(layer) = self.current_layer else { return };
+
+		if pressure_width_profile(&samples).is_some() {
+			responses.add(DocumentMessage::AddTransaction);
+			apply_pressure_width_profile(layer, &samples, document, responses);
+		}
+	}
 }
 
 impl Fsm for PenToolFsmState {
@@ -1942,6 +1979,7 @@ impl Fsm for PenToolFsmState {
 							.or(tool_data.current_layer.filter(|layer| *layer != other_layer))
 						{
 							merge_layers(document, current_layer, other_layer, responses);
+							tool_data.pressure_samples = None;
 						}
 					}
 
@@ -2193,6 +2231,7 @@ impl Fsm for PenToolFsmState {
 						responses.add(PenToolMessage::Abort);
 					} else {
 						responses.add(DocumentMessage::EndTransaction);
+						tool_data.finish_pressure_width_profile(document, responses);
 					}
 				}
 				tool_data.cleanup(responses);
@@ -2204,6 +2243,7 @@ impl Fsm for PenToolFsmState {
 			}
 			(PenToolFsmState::PlacingAnchor, PenToolMessage::Confirm) => {
 				responses.add(DocumentMessage::EndTransaction);
+				tool_data.finish_pressure_width_profile(document, responses);
 				tool_data.cleanup(responses);
 				tool_data.cleanup_target_selections(shape_editor, layer, document, responses);
 
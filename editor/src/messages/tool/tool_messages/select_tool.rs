@@ -4,6 +4,7 @@ use super::tool_prelude::*;
 use crate::consts::*;
 use crate::messages::input_mapper::utility_types::input_mouse::ViewportPosition;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
+use crate::messages::portfolio::document::guide::utility_types::GuideId;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
 use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GroupFolderType};
@@ -97,6 +98,7 @@ pub struct SelectToolPointerKeys {
 	pub snap_angle: Key,
 	pub center: Key,
 	pub duplicate: Key,
+	pub rotate_guide: Key,
 }
 
 #[impl_message(Message, ToolMessage, Select)]
@@ -477,6 +479,7 @@ enum SelectToolFsmState {
 	},
 	RotatingBounds,
 	DraggingPivot,
+	DraggingGuide,
 }
 
 impl Default for SelectToolFsmState {
@@ -1184,6 +1187,11 @@ impl Fsm for SelectToolFsmState {
 					tool_data.get_snap_candidates(document, input, viewport);
 					SelectToolFsmState::SkewingBounds { skew: Key::Control }
 				}
+				// Dragging a guide to move, rotate, or remove it
+				else if let Some((id, _)) = grabbable_guide(document, input, viewport) {
+					responses.add(GuideMessage::DragStart { id, position: input.mouse.position });
+					SelectToolFsmState::DraggingGuide
+				}
 				// Dragging the selected layers around to transform them
 				else if can_grab_compass_rose || intersection.is_some_and(|intersection| selected.iter().any(|selected_layer| intersection.starts_with(*selected_layer, document.metadata()))) {
 					responses.add(DocumentMessage::StartTransaction);
@@ -1260,6 +1268,27 @@ impl Fsm for SelectToolFsmState {
 
 				state
 			}
+			(SelectToolFsmState::DraggingGuide, SelectToolMessage::PointerMove { modifier_keys }) => {
+				responses.add(GuideMessage::PointerMove {
+					position: input.mouse.position,
+					rotate: input.keyboard.key(modifier_keys.rotate_guide),
+					snap_angle: input.keyboard.key(modifier_keys.snap_angle),
+				});
+
+				SelectToolFsmState::DraggingGuide
+			}
+			(SelectToolFsmState::DraggingGuide, SelectToolMessage::DragStop { .. } | SelectToolMessage::Enter) => {
+				responses.add(GuideMessage::DragStop { position: input.mouse.position });
+
+				let selection = tool_data.nested_selection_behavior;
+				SelectToolFsmState::Ready { selection }
+			}
+			(SelectToolFsmState::DraggingGuide, SelectToolMessage::Abort) => {
+				responses.add(GuideMessage::DragAbort);
+
+				let selection = tool_data.nested_selection_behavior;
+				SelectToolFsmState::Ready { selection }
+			}
 			(SelectToolFsmState::DraggingPivot, SelectToolMessage::Abort) => {
 				responses.add(DocumentMessage::AbortTransaction);
 
@@ -1449,6 +1478,19 @@ impl Fsm for SelectToolFsmState {
 				if tool_data.state_from_pivot_gizmo(input.mouse.position).is_some() {
 					cursor = MouseCursorIcon::Move;
 				}
+				// Otherwise show which way a guide under the pointer can be dragged
+				else if cursor == MouseCursorIcon::Default
+					&& let Some((_, [start, end])) = grabbable_guide(document, input, viewport)
+				{
+					let direction = (end - start).normalize_or_zero();
+					cursor = if direction.y.abs() < 1e-6 {
+						MouseCursorIcon::NSResize
+					} else if direction.x.abs() < 1e-6 {
+						MouseCursorIcon::EWResize
+					} else {
+						MouseCursorIcon::Move
+					};
+				}
 
 				// Generate the hover outline
 				responses.add(OverlaysMessage::Draw);
@@ -1903,6 +1945,13 @@ impl Fsm for SelectToolFsmState {
 				let hint_data = HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]);
 				hint_data.send_layout(responses);
 			}
+			SelectToolFsmState::DraggingGuide => {
+				let hint_data = HintData(vec![
+					HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()]),
+					HintGroup(vec![HintInfo::keys([Key::Control], "Rotate Guide"), HintInfo::keys([Key::Shift], "15° Increments").prepend_plus()]),
+				]);
+				hint_data.send_layout(responses);
+			}
 		}
 	}
 
@@ -1911,6 +1960,15 @@ impl Fsm for SelectToolFsmState {
 	}
 }
 
+/// Finds the guide under the pointer which can be grabbed, if guides are shown and not locked.
+fn grabbable_guide(document: &DocumentMessageHandler, input: &InputPreprocessorMessageHandler, viewport: &ViewportMessageHandler) -> Option<(GuideId, [DVec2; 2])> {
+	if !document.overlays_visibility_settings.guides() {
+		return None;
+	}
+
+	document.guides.grabbable_guide(document.metadata(), viewport.size().into(), input.mouse.position)
+}
+
 fn not_artboard(document: &DocumentMessageHandler) -> impl Fn(&LayerNodeIdentifier) -> bool + '_ {
 	|&layer| layer != LayerNodeIdentifier::ROOT_PARENT && !document.network_interface.is_artboard(&layer.to_node(), &[])
 }
@@ -254,7 +254,7 @@ impl ToolData {
 				tool_group
 					.iter()
 					.filter_map(|tool_availability| {
-						if !brush_tool && let ToolRole::Normal(tool) = tool_availability && tool.tool_type() == ToolType::Brush {
+						if !brush_tool && let ToolRole::Normal(tool) = tool_availability && tool.tool_type().is_brush_based() {
 							return None;
 						}
 
@@ -399,6 +399,11 @@ impl ToolType {
 	pub fn get_tool(self) -> Self {
 		if self.get_shape().is_some() { ToolType::Shape } else { self }
 	}
+
+	/// Whether the tool paints with the Brush node, so it's only available when the Brush tool is enabled in the preferences.
+	pub fn is_brush_based(&self) -> bool {
+		matches!(self, Self::Brush | Self::Heal | Self::Patch)
+	}
 }
 
 enum ToolRole {
@@ -433,19 +438,13 @@ fn list_tools_in_groups() -> Vec<Vec<ToolRole>> {
 		vec![
 			// Raster tool group
 			ToolRole::Normal(Box::<brush_tool::BrushTool>::default()),
-			// ToolRole::Normal(
-			// 	ToolEntry::new(ToolType::Heal, "RasterHealTool")
-			// 		.tooltip_label("Heal Tool")
-			// 		.tooltip_shortcut(action_shortcut_manual!(Key::KeyJ)),
-			// ),
+			ToolRole::Normal(Box::<heal_tool::HealTool>::default()),
 			// ToolRole::Normal(
 			// 	ToolEntry::new(ToolType::Clone, "RasterCloneTool")
 			// 		.tooltip_label("Clone Tool")
 			// 		.tooltip_shortcut(action_shortcut_manual!(Key::KeyC)),
 			// ),
-			// ToolRole::Normal(ToolEntry::new(ToolType::Patch, "RasterPatchTool")
-			// 		.tooltip_label("Patch Tool"),
-			// ),
+			ToolRole::Normal(Box::<patch_tool::PatchTool>::default()),
 			// ToolRole::Normal(
 			// 	ToolEntry::new(ToolType::Detail, "RasterDetailTool")
 			// 		.tooltip_label("Detail Tool")
@@ -480,9 +479,9 @@ pub fn tool_message_to_tool_type(tool_message: &ToolMessage) -> ToolType {
 
 		// Raster tool group
 		ToolMessage::Brush(_) => ToolType::Brush,
-		// ToolMessage::Heal(_) => ToolType::Heal,
+		ToolMessage::Heal(_) => ToolType::Heal,
 		// ToolMessage::Clone(_) => ToolType::Clone,
-		// ToolMessage::Patch(_) => ToolType::Patch,
+		ToolMessage::Patch(_) => ToolType::Patch,
 		// ToolMessage::Detail(_) => ToolType::Detail,
 		// ToolMessage::Relight(_) => ToolType::Relight,
 		_ => panic!("Conversion from ToolMessage to ToolType impossible because the given ToolMessage does not have a matching ToolType. Got: {tool_message:?}"),
@@ -512,9 +511,9 @@ pub fn tool_type_to_activate_tool_message(tool_type: ToolType) -> ToolMessageDis
 
 		// Raster tool group
 		ToolType::Brush => ToolMessageDiscriminant::ActivateToolBrush,
-		// ToolType::Heal => ToolMessageDiscriminant::ActivateToolHeal,
+		ToolType::Heal => ToolMessageDiscriminant::ActivateToolHeal,
 		// ToolType::Clone => ToolMessageDiscriminant::ActivateToolClone,
-		// ToolType::Patch => ToolMessageDiscriminant::ActivateToolPatch,
+		ToolType::Patch => ToolMessageDiscriminant::ActivateToolPatch,
 		// ToolType::Detail => ToolMessageDiscriminant::ActivateToolDetail,
 		// ToolType::Relight => ToolMessageDiscriminant::ActivateToolRelight,
 		_ => panic!("Conversion from ToolType to ToolMessage impossible because the given ToolType does not have a matching ToolMessage. Got: {tool_type:?}"),
@@ -136,6 +136,7 @@ impl EditorTestUtils {
 				editor_position: (x2, y2).into(),
 				mouse_keys: MouseKeys::empty(),
 				scroll_delta: ScrollDelta::default(),
+				..Default::default()
 			},
 			modifier_keys,
 		)
@@ -156,6 +157,7 @@ impl EditorTestUtils {
 				editor_position: (100., 100.).into(),
 				mouse_keys: MouseKeys::LEFT | MouseKeys::RIGHT,
 				scroll_delta: ScrollDelta::default(),
+				..Default::default()
 			},
 			ModifierKeys::default(),
 		)
@@ -209,6 +211,7 @@ impl EditorTestUtils {
 				editor_position: (x, y).into(),
 				mouse_keys: MouseKeys::LEFT,
 				scroll_delta: ScrollDelta::default(),
+				..Default::default()
 			},
 			modifier_keys,
 		)
@@ -221,6 +224,7 @@ impl EditorTestUtils {
 				editor_position: (x, y).into(),
 				mouse_keys: MouseKeys::empty(),
 				scroll_delta: ScrollDelta::default(),
+				..Default::default()
 			},
 			modifier_keys,
 		)
@@ -277,6 +281,7 @@ impl EditorTestUtils {
 				editor_position: position,
 				mouse_keys: MouseKeys::LEFT,
 				scroll_delta: ScrollDelta::default(),
+				..Default::default()
 			},
 			modifier_keys: ModifierKeys::empty(),
 		})
@@ -301,6 +306,7 @@ impl EditorTestUtils {
 				editor_position: points[points.len() - 1],
 				mouse_keys: MouseKeys::empty(),
 				scroll_delta: ScrollDelta::default(),
+				..Default::default()
 			},
 			modifier_keys,
 		)
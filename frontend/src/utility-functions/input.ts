@@ -123,7 +123,7 @@ export function onPointerMove(e: PointerEvent, editor: EditorWrapper, documentSt
 
 	const modifiers = makeKeyboardModifiersBitfield(e);
 	if (detectShake(e)) editor.onMouseShake(e.clientX, e.clientY, e.buttons, modifiers);
-	editor.onMouseMove(e.clientX, e.clientY, e.buttons, modifiers);
+	editor.onMouseMove(e.clientX, e.clientY, e.buttons, modifiers, ...stylusReadings(e));
 }
 
 export function onPointerDown(e: PointerEvent, editor: EditorWrapper, dialogStore: DialogStore) {
@@ -152,10 +152,16 @@ export function onPointerDown(e: PointerEvent, editor: EditorWrapper, dialogStor
 
 	if (viewportPointerInteractionOngoing && isTargetingCanvas instanceof Element) {
 		const modifiers = makeKeyboardModifiersBitfield(e);
-		editor.onMouseDown(e.clientX, e.clientY, e.buttons, modifiers);
+		editor.onMouseDown(e.clientX, e.clientY, e.buttons, modifiers, ...stylusReadings(e));
 	}
 }
 
+// The pressure, tilt, twist, and timestamp of a pointer event, where only pens report a pressure since other pointers give 0.5 whenever a button is pressed
+function stylusReadings(e: PointerEvent): [number, number, number, number, number] {
+	const pressure = e.pointerType === "pen" ? e.pressure : 1;
+	return [pressure, e.tiltX, e.tiltY, e.twist, e.timeStamp];
+}
+
 export function onPointerUp(e: PointerEvent, editor: EditorWrapper) {
 	potentiallyRestoreCanvasFocus(e);
 
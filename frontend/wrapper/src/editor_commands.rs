@@ -16,7 +16,7 @@ mod editor_commands {
 	use crate::helpers::translate_key;
 	use editor::messages::clipboard::utility_types::ClipboardContentRaw;
 	use editor::messages::input_mapper::utility_types::input_keyboard::ModifierKeys;
-	use editor::messages::input_mapper::utility_types::input_mouse::{EditorMouseState, ScrollDelta};
+	use editor::messages::input_mapper::utility_types::input_mouse::{EditorMouseState, ScrollDelta, StylusState};
 	use editor::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
 	use editor::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 	use editor::messages::portfolio::document::utility_types::network_interface::ImportOrExport;
@@ -299,8 +299,14 @@ mod editor_commands {
 	}
 
 	/// Mouse movement within the screenspace bounds of the viewport
-	fn on_mouse_move(x: f64, y: f64, mouse_keys: u8, modifiers: u8) -> Message {
-		let editor_mouse_state = EditorMouseState::from_keys_and_editor_position(mouse_keys, (x, y).into());
+	fn on_mouse_move(x: f64, y: f64, mouse_keys: u8, modifiers: u8, pressure: f64, tilt_x: f64, tilt_y: f64, twist: f64, timestamp: f64) -> Message {
+		let mut editor_mouse_state = EditorMouseState::from_keys_and_editor_position(mouse_keys, (x, y).into());
+		editor_mouse_state.stylus = StylusState {
+			pressure,
+			tilt: (tilt_x, tilt_y).into(),
+			twist,
+			timestamp,
+		};
 		let modifier_keys = ModifierKeys::from_bits(modifiers).expect("Invalid modifier keys");
 		InputPreprocessorMessage::PointerMove { editor_mouse_state, modifier_keys }.into()
 	}
@@ -314,8 +320,14 @@ mod editor_commands {
 	}
 
 	/// A mouse button depressed within screenspace the bounds of the viewport
-	fn on_mouse_down(x: f64, y: f64, mouse_keys: u8, modifiers: u8) -> Message {
-		let editor_mouse_state = EditorMouseState::from_keys_and_editor_position(mouse_keys, (x, y).into());
+	fn on_mouse_down(x: f64, y: f64, mouse_keys: u8, modifiers: u8, pressure: f64, tilt_x: f64, tilt_y: f64, twist: f64, timestamp: f64) -> Message {
+		let mut editor_mouse_state = EditorMouseState::from_keys_and_editor_position(mouse_keys, (x, y).into());
+		editor_mouse_state.stylus = StylusState {
+			pressure,
+			tilt: (tilt_x, tilt_y).into(),
+			twist,
+			timestamp,
+		};
 		let modifier_keys = ModifierKeys::from_bits(modifiers).expect("Invalid modifier keys");
 		InputPreprocessorMessage::PointerDown { editor_mouse_state, modifier_keys }.into()
 	}
@@ -505,6 +517,31 @@ mod editor_commands {
 		NavigationMessage::CanvasPanByViewportFraction { delta: (delta_x, delta_y).into() }.into()
 	}
 
+	/// Begins dragging a new guide out of the horizontal or vertical ruler (in viewport coords)
+	fn guide_ruler_drag_start(horizontal: bool, x: f64, y: f64) -> Message {
+		GuideMessage::RulerDragStart { horizontal, position: (x, y).into() }.into()
+	}
+
+	/// Moves the guide being dragged out of a ruler (in viewport coords)
+	fn guide_pointer_move(x: f64, y: f64, rotate: bool, snap_angle: bool) -> Message {
+		GuideMessage::PointerMove {
+			position: (x, y).into(),
+			rotate,
+			snap_angle,
+		}
+		.into()
+	}
+
+	/// Places the guide being dragged out of a ruler, or discards it if released outside the viewport (in viewport coords)
+	fn guide_drag_stop(x: f64, y: f64) -> Message {
+		GuideMessage::DragStop { position: (x, y).into() }.into()
+	}
+
+	/// Cancels dragging the guide out of a ruler
+	fn guide_drag_abort() -> Message {
+		GuideMessage::DragAbort.into()
+	}
+
 	/// Merge the selected nodes into a subnetwork
 	fn merge_selected_nodes() -> Message {
 		NodeGraphMessage::MergeSelectedNodes.into()
@@ -23,6 +23,7 @@ pub fn decode_a100<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage
 		height: image_height,
 		cfa_pattern: todo!(),
 		#[allow(unreachable_code)]
+		x_trans_pattern: None,
 		maximum: (1 << 12) - 1,
 		black: SubtractBlack::None,
 		orientation: OrientationValue::Horizontal,
@@ -30,6 +31,7 @@ pub fn decode_a100<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage
 		camera_white_balance: None,
 		white_balance: None,
 		camera_to_rgb: None,
+		xyz_to_camera: None,
 	}
 }
 
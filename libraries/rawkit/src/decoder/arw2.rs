@@ -47,6 +47,7 @@ pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage {
 		width: image_width,
 		height: image_height,
 		cfa_pattern: ifd.cfa_pattern.try_into().unwrap(),
+		x_trans_pattern: None,
 		maximum: (1 << 14) - 1,
 		black: SubtractBlack::CfaGrid([512, 512, 512, 512]), // TODO: Find the correct way to do this
 		orientation: OrientationValue::Horizontal,
@@ -54,6 +55,7 @@ pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage {
 		camera_white_balance: ifd.white_balance_levels.map(|arr| arr.map(|x| x as f64)),
 		white_balance: None,
 		camera_to_rgb: None,
+		xyz_to_camera: None,
 	}
 }
 
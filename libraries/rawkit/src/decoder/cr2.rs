@@ -0,0 +1,154 @@
+use super::{ljpeg, read_block};
+use crate::tiff::file::TiffRead;
+use crate::tiff::tags::{CanonColorData, CanonSensorInfo, Cr2Slices, ExifIfd, MakerNote, StripByteCounts, StripOffsets, Tag};
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Read, Seek};
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct Cr2RawIfd {
+	strip_offsets: StripOffsets,
+	strip_byte_counts: StripByteCounts,
+	slices: Option<Cr2Slices>,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct CanonMakerNote {
+	sensor_info: Option<CanonSensorInfo>,
+	color_data: Option<CanonColorData>,
+}
+
+/// Decodes a Canon CR2 file, whose first IFD is `ifd`.
+///
+/// The raw data is stored in the fourth IFD as a lossless JPEG image, which is cut into vertical slices of the sensor.
+/// The black level is measured from the masked pixels at the left border of the sensor, which are then cropped away.
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let maker_note = canon_maker_note(&ifd, file)?.map(|maker_note| maker_note.get_value::<CanonMakerNote, _>(file)).transpose()?;
+
+	let raw_ifd = ifd.next_ifd(file)?.next_ifd(file)?.next_ifd(file)?;
+	let raw_ifd = raw_ifd.get_value::<Cr2RawIfd, _>(file)?;
+
+	let mut data = Vec::new();
+	for (&offset, &byte_count) in raw_ifd.strip_offsets.iter().zip(&raw_ifd.strip_byte_counts) {
+		data.extend(read_block(file, offset, byte_count)?);
+	}
+	let jpeg = ljpeg::decode(&data)?;
+
+	let slice_widths: Vec<usize> = match raw_ifd.slices {
+		Some([count, slice_width, last_slice_width]) => std::iter::repeat_n(slice_width.into(), count.into()).chain([last_slice_width.into()]).collect(),
+		None => vec![jpeg.width * jpeg.components],
+	};
+	let width: usize = slice_widths.iter().sum();
+	let height = jpeg.data.len().checked_div(width).unwrap_or(0);
+	if height == 0 {
+		return Err(DecoderError::CorruptData);
+	}
+
+	let mut raw_image = RawImage {
+		data: unslice(&jpeg.data, &slice_widths, height)?,
+		width,
+		height,
+		cfa_pattern: [0, 1, 1, 2],
+		x_trans_pattern: None,
+		maximum: u16::MAX >> (16 - jpeg.precision.min(16)),
+		black: SubtractBlack::None,
+		orientation: OrientationValue::Horizontal,
+		camera_model: None,
+		camera_white_balance: None,
+		white_balance: None,
+		camera_to_rgb: None,
+		xyz_to_camera: None,
+	};
+
+	// The sensor info holds the borders of the visible area (inclusive), next to which is a band of masked pixels that only record the black level
+	if let Some(&[_, _, _, _, _, left, top, right, bottom, ..]) = maker_note.as_ref().and_then(|maker_note| maker_note.sensor_info.as_deref()) {
+		let [left, top, right, bottom] = [left, top, right, bottom].map(usize::from);
+
+		if left >= 4 {
+			let mut sums = [0_u64; 4];
+			let mut counts = [0_u64; 4];
+			for row in top..=bottom.min(height - 1) {
+				for column in 0..left.min(width) {
+					let index = 2 * (row % 2) + column % 2;
+					sums[index] += raw_image.data[row * width + column] as u64;
+					counts[index] += 1;
+				}
+			}
+
+			if counts.iter().all(|&count| count > 0) {
+				raw_image.black = SubtractBlack::CfaGrid(std::array::from_fn(|index| (sums[index] / counts[index]) as u16));
+			}
+		}
+
+		raw_image.crop(left, top, (right + 1).saturating_sub(left), (bottom + 1).saturating_sub(top));
+		// Canon sensors start with a red pixel at the top left corner of the visible area
+		raw_image.cfa_pattern = [0, 1, 1, 2];
+	}
+
+	if let Some(color_data) = maker_note.and_then(|maker_note| maker_note.color_data) {
+		// The as shot white balance levels (RGGB) are found at a different position depending on the version of the color data, which is identified by its length
+		let offset = match color_data.len() {
+			582 => 25,
+			653 => 34,
+			_ => 63,
+		};
+
+		if let Some(&[red, green_1, green_2, blue]) = color_data.get(offset..offset + 4)
+			&& red > 0
+			&& blue > 0
+		{
+			let green = (green_1 as f64 + green_2 as f64) / 2.;
+			raw_image.camera_white_balance = Some(raw_image.cfa_white_balance([red as f64, green, blue as f64]));
+		}
+	}
+
+	Ok(raw_image)
+}
+
+/// Reassembles the sensor image from its vertical slices, each of which covers the full height of the sensor and is stored one after another from left to right.
+fn unslice(samples: &[u16], slice_widths: &[usize], height: usize) -> Result<Vec<u16>, DecoderError> {
+	let width: usize = slice_widths.iter().sum();
+	let mut image = vec![0_u16; width * height];
+	let mut samples = samples;
+	let mut slice_left = 0;
+	for &slice_width in slice_widths {
+		for row in 0..height {
+			let (slice_row, rest) = samples.split_at_checked(slice_width).ok_or(DecoderError::CorruptData)?;
+			image[row * width + slice_left..][..slice_width].copy_from_slice(slice_row);
+			samples = rest;
+		}
+		slice_left += slice_width;
+	}
+
+	Ok(image)
+}
+
+fn canon_maker_note<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Result<Option<Ifd>, TiffError> {
+	let Some(exif_ifd) = ifd.get_value::<Option<ExifIfd>, _>(file)? else { return Ok(None) };
+	let Some(maker_note) = exif_ifd.get_value::<Option<MakerNote>, _>(file)? else { return Ok(None) };
+
+	// Canon's maker note is an IFD which uses offsets relative to the start of the file
+	Ok(Some(Ifd::new_from_offset(file, maker_note.offset)?))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn slices_are_placed_side_by_side() {
+		// Two slices 2 samples wide and a last one 1 sample wide, over 2 rows
+		let samples = [1, 2, 5, 6, 3, 4, 7, 8, 9, 10];
+		#[rustfmt::skip]
+		let expected = [
+			1, 2, 3, 4, 9,
+			5, 6, 7, 8, 10,
+		];
+		assert_eq!(unslice(&samples, &[2, 2, 1], 2).unwrap(), expected);
+
+		assert!(matches!(unslice(&samples[..9], &[2, 2, 1], 2), Err(DecoderError::CorruptData)));
+	}
+}
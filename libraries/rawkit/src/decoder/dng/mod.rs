@@ -0,0 +1,304 @@
+mod opcodes;
+
+use super::{find_raw_ifd, ljpeg, read_block, unpack};
+use crate::tiff::file::TiffRead;
+use crate::tiff::tags::{
+	ActiveArea, AsShotNeutral, BitsPerSample, CalibrationIlluminant1, CalibrationIlluminant2, CfaPattern, CfaPatternDim, ColorMatrix1, ColorMatrix2, Compression, DefaultCropOrigin, DefaultCropSize,
+	DngBlackLevel, DngBlackLevelRepeatDim, ImageLength, ImageWidth, LinearizationTable, OpcodeList1, OpcodeList2, RowsPerStrip, StripByteCounts, StripOffsets, Tag, TileByteCounts, TileLength,
+	TileOffsets, TileWidth, WhiteLevel,
+};
+use crate::tiff::values::{CompressionValue, DataBlock, Rational, ToFloat};
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use opcodes::Domain;
+use rawkit_proc_macros::Tag;
+use std::io::{Read, Seek};
+
+/// The value of `CalibrationIlluminant1` and `CalibrationIlluminant2` for the D65 standard illuminant.
+const ILLUMINANT_D65: u16 = 21;
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct DngMainIfd {
+	color_matrix_1: Option<ColorMatrix1>,
+	color_matrix_2: Option<ColorMatrix2>,
+	calibration_illuminant_1: Option<CalibrationIlluminant1>,
+	calibration_illuminant_2: Option<CalibrationIlluminant2>,
+	as_shot_neutral: Option<AsShotNeutral>,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct DngRawIfd {
+	image_width: ImageWidth,
+	image_height: ImageLength,
+	bits_per_sample: BitsPerSample,
+	compression: Compression,
+	cfa_pattern_dim: CfaPatternDim,
+	cfa_pattern: CfaPattern,
+	linearization_table: Option<LinearizationTable>,
+	black_level_repeat_dim: Option<DngBlackLevelRepeatDim>,
+	black_level: Option<DngBlackLevel>,
+	white_level: Option<WhiteLevel>,
+	active_area: Option<ActiveArea>,
+	default_crop_origin: Option<DefaultCropOrigin>,
+	default_crop_size: Option<DefaultCropSize>,
+	opcode_list_1: Option<OpcodeList1>,
+	opcode_list_2: Option<OpcodeList2>,
+}
+
+/// The layout of the raw data in the file, where strips are treated as tiles which span the width of the image.
+struct Tiles {
+	width: usize,
+	height: usize,
+	offsets: Vec<u32>,
+	byte_counts: Vec<u32>,
+}
+
+/// Decodes the raw CFA image of a DNG file, whose first IFD is `ifd`.
+///
+/// The raw data may be uncompressed or compressed with lossless JPEG, and stored in strips or tiles.
+/// The linearization table, black and white levels, active area and default crop are applied, along with the opcodes of `OpcodeList1` and `OpcodeList2`.
+/// `OpcodeList3` is ignored as it applies after demosaicing, which only consists of geometric corrections like lens distortion.
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let main_ifd = ifd.get_value::<DngMainIfd, _>(file)?;
+	let Some(raw_ifd) = find_raw_ifd(&ifd, file)? else {
+		return Err(DecoderError::UnsupportedFeature("DNG without CFA data"));
+	};
+	let ifd = raw_ifd.get_value::<DngRawIfd, _>(file)?;
+
+	let width: usize = ifd.image_width.try_into()?;
+	let height: usize = ifd.image_height.try_into()?;
+	if ifd.cfa_pattern_dim != [2, 2] && ifd.cfa_pattern_dim != [6, 6] {
+		return Err(DecoderError::UnsupportedFeature("CFA pattern other than Bayer or X-Trans"));
+	}
+
+	let tiles = tiles(&raw_ifd, file, width, height)?;
+	let mut data = vec![0_u16; width * height];
+
+	for (index, (&offset, &byte_count)) in tiles.offsets.iter().zip(&tiles.byte_counts).enumerate() {
+		let block = read_block(file, offset, byte_count)?;
+		let samples = match ifd.compression {
+			CompressionValue::Uncompressed => unpack(&block, ifd.bits_per_sample, file.endian())?,
+			CompressionValue::JPEG => ljpeg::decode(&block)?.data,
+			_ => return Err(DecoderError::UnsupportedFeature("DNG compression")),
+		};
+
+		tiles.place(&mut data, width, height, index, &samples);
+	}
+
+	let mut raw_image = RawImage {
+		data,
+		width,
+		height,
+		cfa_pattern: [0, 1, 1, 2],
+		x_trans_pattern: None,
+		maximum: u16::MAX,
+		black: SubtractBlack::None,
+		orientation: OrientationValue::Horizontal,
+		camera_model: None,
+		camera_white_balance: None,
+		white_balance: None,
+		camera_to_rgb: None,
+		xyz_to_camera: None,
+	};
+
+	// Opcodes in the first list see the raw values of the file
+	if let Some(opcode_list) = ifd.opcode_list_1 {
+		let domain = Domain {
+			black: [0.; 4],
+			white: u16::MAX as f64,
+		};
+		opcodes::apply(&mut raw_image, &opcodes::parse(&read_data_block(file, opcode_list)?)?, &domain)?;
+	}
+
+	if let Some(table) = ifd.linearization_table.filter(|table| !table.is_empty()) {
+		let last = table.len() - 1;
+		raw_image.data.iter_mut().for_each(|x| *x = table[(*x as usize).min(last)]);
+	}
+
+	if let Some([top, left, bottom, right]) = ifd.active_area {
+		let [top, left, bottom, right] = [top, left, bottom, right].map(|x| x as usize);
+		raw_image.crop(left, top, right.saturating_sub(left), bottom.saturating_sub(top));
+	}
+
+	// The CFA pattern and black level are relative to the top left corner of the active area
+	if ifd.cfa_pattern_dim == [6, 6] {
+		let pattern: [u8; 36] = ifd.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?;
+		raw_image.x_trans_pattern = Some(std::array::from_fn(|row| std::array::from_fn(|column| pattern[6 * row + column])));
+	} else {
+		raw_image.cfa_pattern = ifd.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?;
+	}
+
+	let black = match (ifd.black_level, ifd.black_level_repeat_dim.unwrap_or([1, 1])) {
+		(Some(black_level), [rows, columns]) if rows > 0 && columns > 0 => {
+			let (rows, columns) = (rows as usize, columns as usize);
+			std::array::from_fn(|index| black_level.get((index / 2 % rows) * columns + index % 2 % columns).copied().unwrap_or_default())
+		}
+		_ => [0.; 4],
+	};
+	let white = ifd
+		.white_level
+		.and_then(|white_level| white_level.first().copied())
+		.unwrap_or_else(|| default_white_level(ifd.bits_per_sample));
+
+	raw_image.black = SubtractBlack::CfaGrid(black.map(|x| x.round().clamp(0., u16::MAX as f64) as u16));
+	raw_image.maximum = white.min(u16::MAX.into()) as u16;
+
+	// Opcodes in the second list see linear values, where 0 is the black level and 1 is the white level
+	if let Some(opcode_list) = ifd.opcode_list_2 {
+		let domain = Domain { black, white: white as f64 };
+		opcodes::apply(&mut raw_image, &opcodes::parse(&read_data_block(file, opcode_list)?)?, &domain)?;
+	}
+
+	if let (Some([left, top]), Some([crop_width, crop_height])) = (ifd.default_crop_origin, ifd.default_crop_size) {
+		let [left, top, crop_width, crop_height] = [left, top, crop_width, crop_height].map(|x| x.round().max(0.) as usize);
+		raw_image.crop(left, top, crop_width, crop_height);
+	}
+
+	raw_image.xyz_to_camera = xyz_to_camera(main_ifd.calibration_illuminant_1, main_ifd.color_matrix_1, main_ifd.color_matrix_2);
+	raw_image.camera_white_balance = main_ifd
+		.as_shot_neutral
+		.filter(|neutral| neutral.len() >= 3 && neutral.iter().all(|&x| x > 0.))
+		.map(|neutral| raw_image.cfa_white_balance([1. / neutral[0], 1. / neutral[1], 1. / neutral[2]]));
+
+	Ok(raw_image)
+}
+
+impl Tiles {
+	/// Copies the samples of the tile at `index` (counting across, then down) into the image, clipping whatever extends past its right or bottom edge.
+	fn place(&self, data: &mut [u16], width: usize, height: usize, index: usize, samples: &[u16]) {
+		let tiles_across = width.div_ceil(self.width).max(1);
+		let (tile_left, tile_top) = ((index % tiles_across) * self.width, (index / tiles_across) * self.height);
+
+		// Samples fill the tile row by row, whatever shape the lossless JPEG frame has
+		for (row, samples) in samples.chunks(self.width).take(self.height).enumerate() {
+			let y = tile_top + row;
+			if y >= height {
+				break;
+			}
+
+			let columns = samples.len().min(width.saturating_sub(tile_left));
+			data[y * width + tile_left..][..columns].copy_from_slice(&samples[..columns]);
+		}
+	}
+}
+
+fn tiles<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>, width: usize, height: usize) -> Result<Tiles, DecoderError> {
+	let tiles = if let Some(tile_width) = ifd.get_value::<Option<TileWidth>, _>(file)? {
+		Tiles {
+			width: tile_width.try_into()?,
+			height: ifd.get_value::<TileLength, _>(file)?.try_into()?,
+			offsets: ifd.get_value::<TileOffsets, _>(file)?,
+			byte_counts: ifd.get_value::<TileByteCounts, _>(file)?,
+		}
+	} else {
+		Tiles {
+			width,
+			height: ifd.get_value::<Option<RowsPerStrip>, _>(file)?.map_or(Ok(height), usize::try_from)?,
+			offsets: ifd.get_value::<StripOffsets, _>(file)?,
+			byte_counts: ifd.get_value::<StripByteCounts, _>(file)?,
+		}
+	};
+
+	if tiles.width == 0 || tiles.height == 0 || tiles.offsets.len() != tiles.byte_counts.len() {
+		return Err(TiffError::InvalidValue.into());
+	}
+
+	Ok(tiles)
+}
+
+/// The largest value samples of the given bit depth can hold, which is the white level when the file doesn't give one.
+/// A depth of 0 or over 16 bits, which decoded samples can't have, falls back to the full 16 bit range.
+fn default_white_level(bits_per_sample: u16) -> u32 {
+	16_u16.checked_sub(bits_per_sample).and_then(|shift| u16::MAX.checked_shr(shift.into())).unwrap_or(u16::MAX).into()
+}
+
+fn read_data_block<R: Read + Seek>(file: &mut TiffRead<R>, block: DataBlock) -> Result<Vec<u8>, DecoderError> {
+	read_block(file, block.offset, block.length)
+}
+
+/// Picks the color matrix calibrated for daylight (D65) when there are two, as that's closest to what the rest of the processing assumes.
+fn xyz_to_camera(illuminant_1: Option<u16>, matrix_1: Option<Vec<Rational<i32>>>, matrix_2: Option<Vec<Rational<i32>>>) -> Option<[f64; 9]> {
+	let to_matrix = |matrix: Vec<Rational<i32>>| -> Option<[f64; 9]> { matrix.get(..9)?.iter().map(ToFloat::to_float).collect::<Vec<_>>().try_into().ok() };
+
+	let matrix_1 = matrix_1.and_then(to_matrix);
+	let matrix_2 = matrix_2.and_then(to_matrix);
+
+	if illuminant_1 == Some(ILLUMINANT_D65) && matrix_1.is_some() {
+		matrix_1
+	} else {
+		matrix_2.or(matrix_1)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn layout(width: usize, height: usize) -> Tiles {
+		Tiles {
+			width,
+			height,
+			offsets: Vec::new(),
+			byte_counts: Vec::new(),
+		}
+	}
+
+	/// Places tiles whose every sample holds the index of the tile, so the result shows which tile covers each pixel.
+	fn place_all(tiles: &Tiles, count: usize, width: usize, height: usize) -> Vec<u16> {
+		let mut data = vec![u16::MAX; width * height];
+		for index in 0..count {
+			tiles.place(&mut data, width, height, index, &vec![index as u16; tiles.width * tiles.height]);
+		}
+		data
+	}
+
+	#[test]
+	fn tiles_are_clipped_to_the_image() {
+		// 2x2 tiles over a 5x3 image, so the last column and row of tiles only partly overlap it
+		#[rustfmt::skip]
+		let expected = [
+			0, 0, 1, 1, 2,
+			0, 0, 1, 1, 2,
+			3, 3, 4, 4, 5,
+		];
+		assert_eq!(place_all(&layout(2, 2), 6, 5, 3), expected);
+	}
+
+	#[test]
+	fn strips_span_the_width_of_the_image() {
+		// Strips of 2 rows, the last of which is cut short
+		#[rustfmt::skip]
+		let expected = [
+			0, 0, 0,
+			0, 0, 0,
+			1, 1, 1,
+		];
+		assert_eq!(place_all(&layout(3, 2), 2, 3, 3), expected);
+	}
+
+	#[test]
+	fn samples_fill_tiles_row_by_row() {
+		// A lossless JPEG tile may be coded as a frame of half the width with two components, which still fills the tile row by row
+		let tiles = layout(4, 2);
+		let mut data = vec![0; 4 * 2];
+		tiles.place(&mut data, 4, 2, 0, &[1, 2, 3, 4, 5, 6, 7, 8]);
+		assert_eq!(data, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+		// Short data leaves the rest of the tile untouched
+		let mut data = vec![0; 4 * 2];
+		tiles.place(&mut data, 4, 2, 0, &[1, 2, 3, 4, 5]);
+		assert_eq!(data, [1, 2, 3, 4, 5, 0, 0, 0]);
+	}
+
+	#[test]
+	fn default_white_level_follows_the_bit_depth() {
+		assert_eq!(default_white_level(12), 4095);
+		assert_eq!(default_white_level(16), 65535);
+
+		// Invalid depths use the full range instead of overflowing the shift
+		assert_eq!(default_white_level(0), 65535);
+		assert_eq!(default_white_level(32), 65535);
+	}
+}
@@ -0,0 +1,455 @@
+//! Opcode lists, which DNG files use to describe corrections (like lens shading gain maps or bad pixel fixes) that should be applied during processing.
+
+use crate::{DecoderError, RawImage};
+
+const FIX_BAD_PIXELS_CONSTANT: u32 = 4;
+const FIX_BAD_PIXELS_LIST: u32 = 5;
+const TRIM_BOUNDS: u32 = 6;
+const MAP_TABLE: u32 = 7;
+const MAP_POLYNOMIAL: u32 = 8;
+const GAIN_MAP: u32 = 9;
+const DELTA_PER_ROW: u32 = 10;
+const DELTA_PER_COLUMN: u32 = 11;
+const SCALE_PER_ROW: u32 = 12;
+const SCALE_PER_COLUMN: u32 = 13;
+
+/// Set in the flags of opcodes which may be skipped by readers that don't support them.
+const FLAG_OPTIONAL: u32 = 1;
+
+pub struct Opcode {
+	id: u32,
+	flags: u32,
+	parameters: Vec<u8>,
+}
+
+/// Reads the big endian values which make up an opcode list and the parameters of each opcode.
+struct OpcodeReader<'a> {
+	data: &'a [u8],
+	position: usize,
+}
+
+impl OpcodeReader<'_> {
+	fn bytes<const N: usize>(&mut self) -> Result<[u8; N], DecoderError> {
+		let bytes = self.data.get(self.position..self.position + N).ok_or(DecoderError::CorruptData)?;
+		self.position += N;
+		Ok(bytes.try_into().unwrap())
+	}
+
+	fn read_u16(&mut self) -> Result<u16, DecoderError> {
+		Ok(u16::from_be_bytes(self.bytes()?))
+	}
+
+	fn read_u32(&mut self) -> Result<u32, DecoderError> {
+		Ok(u32::from_be_bytes(self.bytes()?))
+	}
+
+	fn read_usize(&mut self) -> Result<usize, DecoderError> {
+		Ok(self.read_u32()?.try_into()?)
+	}
+
+	fn read_f32(&mut self) -> Result<f32, DecoderError> {
+		Ok(f32::from_be_bytes(self.bytes()?))
+	}
+
+	fn read_f64(&mut self) -> Result<f64, DecoderError> {
+		Ok(f64::from_be_bytes(self.bytes()?))
+	}
+
+	fn read_area(&mut self) -> Result<Area, DecoderError> {
+		let [top, left, bottom, right] = [self.read_usize()?, self.read_usize()?, self.read_usize()?, self.read_usize()?];
+		// The plane and number of planes don't matter for CFA images, which only have one
+		let _ = [self.read_u32()?, self.read_u32()?];
+		let [row_pitch, column_pitch] = [self.read_usize()?.max(1), self.read_usize()?.max(1)];
+
+		Ok(Area {
+			top,
+			left,
+			bottom,
+			right,
+			row_pitch,
+			column_pitch,
+		})
+	}
+}
+
+/// The pixels an opcode is applied to, which are the pixels within the rectangle whose row and column are multiples of the pitch away from its top left corner.
+struct Area {
+	top: usize,
+	left: usize,
+	bottom: usize,
+	right: usize,
+	row_pitch: usize,
+	column_pitch: usize,
+}
+
+impl Area {
+	fn rows(&self, image: &RawImage) -> impl Iterator<Item = usize> + use<> {
+		(self.top..self.bottom.min(image.height)).step_by(self.row_pitch)
+	}
+
+	fn columns(&self, image: &RawImage) -> impl Iterator<Item = usize> + use<> {
+		(self.left..self.right.min(image.width)).step_by(self.column_pitch)
+	}
+}
+
+pub fn parse(data: &[u8]) -> Result<Vec<Opcode>, DecoderError> {
+	let mut reader = OpcodeReader { data, position: 0 };
+
+	let count = reader.read_u32()?;
+	(0..count)
+		.map(|_| {
+			let id = reader.read_u32()?;
+			// The minimum DNG version needed to understand the opcode
+			let _ = reader.read_u32()?;
+			let flags = reader.read_u32()?;
+			let length = reader.read_usize()?;
+			let parameters = data.get(reader.position..reader.position + length).ok_or(DecoderError::CorruptData)?.to_vec();
+			reader.position += length;
+
+			Ok(Opcode { id, flags, parameters })
+		})
+		.collect()
+}
+
+/// The range of pixel values that opcodes see as 0 to 1 at a particular stage of processing.
+pub struct Domain {
+	/// The black level of each pixel of the 2x2 CFA grid.
+	pub black: [f64; 4],
+	pub white: f64,
+}
+
+impl Domain {
+	fn black(&self, row: usize, column: usize) -> f64 {
+		self.black[2 * (row % 2) + (column % 2)]
+	}
+
+	fn map(&self, image: &mut RawImage, row: usize, column: usize, function: impl Fn(f64) -> f64) {
+		let black = self.black(row, column);
+		let range = self.white - black;
+
+		let value = &mut image.data[row * image.width + column];
+		let normalized = (*value as f64 - black) / range;
+		*value = (function(normalized) * range + black).round().clamp(0., u16::MAX as f64) as u16;
+	}
+}
+
+/// Applies the opcodes of a list to the raw image, whose pixel values are interpreted within `domain`.
+pub fn apply(image: &mut RawImage, opcodes: &[Opcode], domain: &Domain) -> Result<(), DecoderError> {
+	for opcode in opcodes {
+		let mut reader = OpcodeReader {
+			data: &opcode.parameters,
+			position: 0,
+		};
+
+		match opcode.id {
+			FIX_BAD_PIXELS_CONSTANT => {
+				let constant = reader.read_u32()?;
+				let bad_pixels: Vec<_> = (0..image.height)
+					.flat_map(|row| (0..image.width).map(move |column| (row, column)))
+					.filter(|&(row, column)| u32::from(image.data[row * image.width + column]) == constant)
+					.collect();
+				fix_bad_pixels(image, &bad_pixels);
+			}
+			FIX_BAD_PIXELS_LIST => {
+				let _bayer_phase = reader.read_u32()?;
+				let point_count = reader.read_u32()?;
+				let rectangle_count = reader.read_u32()?;
+
+				let mut bad_pixels = Vec::new();
+				for _ in 0..point_count {
+					bad_pixels.push((reader.read_usize()?, reader.read_usize()?));
+				}
+				for _ in 0..rectangle_count {
+					let [top, left, bottom, right] = [reader.read_usize()?, reader.read_usize()?, reader.read_usize()?, reader.read_usize()?];
+					bad_pixels.extend((top..bottom).flat_map(|row| (left..right).map(move |column| (row, column))));
+				}
+				bad_pixels.retain(|&(row, column)| row < image.height && column < image.width);
+				fix_bad_pixels(image, &bad_pixels);
+			}
+			TRIM_BOUNDS => {
+				let [top, left, bottom, right] = [reader.read_usize()?, reader.read_usize()?, reader.read_usize()?, reader.read_usize()?];
+				image.crop(left, top, right.saturating_sub(left), bottom.saturating_sub(top));
+			}
+			MAP_TABLE => {
+				let area = reader.read_area()?;
+				let table = (0..reader.read_u32()?).map(|_| reader.read_u16()).collect::<Result<Vec<_>, _>>()?;
+				let last = table.len().checked_sub(1).ok_or(DecoderError::CorruptData)?;
+
+				for row in area.rows(image) {
+					for column in area.columns(image) {
+						domain.map(image, row, column, |x| table[((x * u16::MAX as f64).round().max(0.) as usize).min(last)] as f64 / u16::MAX as f64);
+					}
+				}
+			}
+			MAP_POLYNOMIAL => {
+				let area = reader.read_area()?;
+				let coefficients = (0..=reader.read_u32()?).map(|_| reader.read_f64()).collect::<Result<Vec<_>, _>>()?;
+
+				for row in area.rows(image) {
+					for column in area.columns(image) {
+						domain.map(image, row, column, |x| coefficients.iter().rev().fold(0., |sum, coefficient| sum * x + coefficient));
+					}
+				}
+			}
+			GAIN_MAP => {
+				let area = reader.read_area()?;
+				let [points_vertical, points_horizontal] = [reader.read_usize()?, reader.read_usize()?];
+				let [spacing_vertical, spacing_horizontal] = [reader.read_f64()?, reader.read_f64()?];
+				let [origin_vertical, origin_horizontal] = [reader.read_f64()?, reader.read_f64()?];
+				let planes = reader.read_usize()?;
+				if points_vertical == 0 || points_horizontal == 0 || planes == 0 {
+					return Err(DecoderError::CorruptData);
+				}
+
+				let mut gains = Vec::with_capacity(points_vertical * points_horizontal);
+				for _ in 0..points_vertical * points_horizontal {
+					// Only the first plane applies to CFA images
+					gains.push(reader.read_f32()?);
+					for _ in 1..planes {
+						reader.read_f32()?;
+					}
+				}
+
+				// Converts a position relative to the image into a fractional index into the map
+				let map_index = |relative: f64, origin: f64, spacing: f64, points: usize| {
+					let index = if spacing > 0. { (relative - origin) / spacing } else { 0. };
+					index.clamp(0., (points - 1) as f64)
+				};
+				let (image_width, image_height) = (image.width as f64, image.height as f64);
+
+				for row in area.rows(image) {
+					let y = map_index(row as f64 / image_height, origin_vertical, spacing_vertical, points_vertical);
+					let (y0, y1, ty) = (y.floor() as usize, (y.floor() as usize + 1).min(points_vertical - 1), y.fract() as f32);
+
+					for column in area.columns(image) {
+						let x = map_index(column as f64 / image_width, origin_horizontal, spacing_horizontal, points_horizontal);
+						let (x0, x1, tx) = (x.floor() as usize, (x.floor() as usize + 1).min(points_horizontal - 1), x.fract() as f32);
+
+						let gain = |y: usize, x: usize| gains[y * points_horizontal + x];
+						let top = gain(y0, x0) * (1. - tx) + gain(y0, x1) * tx;
+						let bottom = gain(y1, x0) * (1. - tx) + gain(y1, x1) * tx;
+						let gain = (top * (1. - ty) + bottom * ty) as f64;
+
+						domain.map(image, row, column, |x| x * gain);
+					}
+				}
+			}
+			DELTA_PER_ROW | DELTA_PER_COLUMN | SCALE_PER_ROW | SCALE_PER_COLUMN => {
+				let area = reader.read_area()?;
+				let values = (0..reader.read_u32()?).map(|_| reader.read_f32()).collect::<Result<Vec<_>, _>>()?;
+
+				for row in area.rows(image) {
+					for column in area.columns(image) {
+						let index = match opcode.id {
+							DELTA_PER_ROW | SCALE_PER_ROW => (row - area.top) / area.row_pitch,
+							_ => (column - area.left) / area.column_pitch,
+						};
+						let Some(&value) = values.get(index) else { continue };
+						let value = value as f64;
+
+						match opcode.id {
+							DELTA_PER_ROW | DELTA_PER_COLUMN => domain.map(image, row, column, |x| x + value),
+							_ => domain.map(image, row, column, |x| x * value),
+						}
+					}
+				}
+			}
+			_ if opcode.flags & FLAG_OPTIONAL != 0 => {}
+			_ => return Err(DecoderError::UnsupportedFeature("DNG opcode")),
+		}
+	}
+
+	Ok(())
+}
+
+/// Replaces each bad pixel with the average of its nearest neighbors of the same color which aren't bad themselves.
+fn fix_bad_pixels(image: &mut RawImage, bad_pixels: &[(usize, usize)]) {
+	let width = image.width;
+
+	let mut bad_pixels = bad_pixels.to_vec();
+	bad_pixels.sort_unstable();
+	let bad_pixels = bad_pixels.as_slice();
+
+	for &(row, column) in bad_pixels {
+		let neighbors = [(0, -2), (0, 2), (-2, 0), (2, 0), (-2, -2), (-2, 2), (2, -2), (2, 2)]
+			.into_iter()
+			.filter_map(|(dy, dx)| Some((row.checked_add_signed(dy)?, column.checked_add_signed(dx)?)))
+			.filter(|&(y, x)| y < image.height && x < width && bad_pixels.binary_search(&(y, x)).is_err());
+
+		let (sum, count) = neighbors.fold((0, 0), |(sum, count), (y, x)| (sum + image.data[y * width + x] as u32, count + 1));
+		if let Some(average) = sum.checked_div(count) {
+			image.data[row * width + column] = average as u16;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{OrientationValue, SubtractBlack};
+
+	fn image(width: usize, height: usize, data: Vec<u16>) -> RawImage {
+		RawImage {
+			data,
+			width,
+			height,
+			cfa_pattern: [0, 1, 1, 2],
+			x_trans_pattern: None,
+			maximum: u16::MAX,
+			black: SubtractBlack::None,
+			orientation: OrientationValue::Horizontal,
+			camera_model: None,
+			camera_white_balance: None,
+			white_balance: None,
+			camera_to_rgb: None,
+			xyz_to_camera: None,
+		}
+	}
+
+	/// Builds an opcode list from the id, flags and big endian parameters of each opcode.
+	fn opcode_list(opcodes: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+		let mut data = (opcodes.len() as u32).to_be_bytes().to_vec();
+		for (id, flags, parameters) in opcodes {
+			for value in [*id, 0x01030000, *flags, parameters.len() as u32] {
+				data.extend(value.to_be_bytes());
+			}
+			data.extend(parameters);
+		}
+		data
+	}
+
+	fn area([top, left, bottom, right]: [u32; 4], [row_pitch, column_pitch]: [u32; 2]) -> Vec<u8> {
+		[top, left, bottom, right, 0, 1, row_pitch, column_pitch].iter().flat_map(|x| x.to_be_bytes()).collect()
+	}
+
+	/// The raw values of the file, where 0 maps to 0 and 1 to 65535.
+	const RAW: Domain = Domain { black: [0.; 4], white: 65535. };
+
+	fn apply_list(image: &mut RawImage, opcodes: &[(u32, u32, Vec<u8>)], domain: &Domain) -> Result<(), DecoderError> {
+		apply(image, &parse(&opcode_list(opcodes))?, domain)
+	}
+
+	#[test]
+	fn parses_each_opcode_with_its_parameters() {
+		let opcodes = parse(&opcode_list(&[(TRIM_BOUNDS, 0, vec![1, 2, 3]), (GAIN_MAP, FLAG_OPTIONAL, vec![])])).unwrap();
+
+		assert_eq!(opcodes.len(), 2);
+		assert_eq!((opcodes[0].id, opcodes[0].flags, opcodes[0].parameters.as_slice()), (TRIM_BOUNDS, 0, [1, 2, 3].as_slice()));
+		assert_eq!((opcodes[1].id, opcodes[1].flags, opcodes[1].parameters.len()), (GAIN_MAP, FLAG_OPTIONAL, 0));
+
+		// Parameters running past the end of the list
+		let mut truncated = opcode_list(&[(TRIM_BOUNDS, 0, vec![0; 16])]);
+		truncated.truncate(truncated.len() - 1);
+		assert!(matches!(parse(&truncated), Err(DecoderError::CorruptData)));
+	}
+
+	#[test]
+	fn unknown_opcodes_are_skipped_only_if_optional() {
+		let mut raw_image = image(1, 1, vec![7]);
+		apply_list(&mut raw_image, &[(100, FLAG_OPTIONAL, vec![])], &RAW).unwrap();
+		assert_eq!(raw_image.data, [7]);
+
+		assert!(matches!(apply_list(&mut raw_image, &[(100, 0, vec![])], &RAW), Err(DecoderError::UnsupportedFeature(_))));
+	}
+
+	#[test]
+	fn trim_bounds() {
+		let mut raw_image = image(3, 3, (0..9).collect());
+		let bounds = [1_u32, 1, 3, 3].iter().flat_map(|x| x.to_be_bytes()).collect();
+		apply_list(&mut raw_image, &[(TRIM_BOUNDS, 0, bounds)], &RAW).unwrap();
+
+		assert_eq!((raw_image.width, raw_image.height), (2, 2));
+		assert_eq!(raw_image.data, [4, 5, 7, 8]);
+		// The top left pixel is now a blue one
+		assert_eq!(raw_image.cfa_pattern, [2, 1, 1, 0]);
+	}
+
+	#[test]
+	fn map_table_applies_to_the_area_at_its_pitch() {
+		// A table which inverts the values, applied to every other column
+		let mut parameters = area([0, 0, 2, 4], [1, 2]);
+		parameters.extend(2_u32.to_be_bytes());
+		parameters.extend([u16::MAX, 0].iter().flat_map(|x| x.to_be_bytes()));
+
+		let mut raw_image = image(4, 2, vec![0, 0, 0, 0, u16::MAX, u16::MAX, u16::MAX, u16::MAX]);
+		apply_list(&mut raw_image, &[(MAP_TABLE, 0, parameters)], &RAW).unwrap();
+		assert_eq!(raw_image.data, [u16::MAX, 0, u16::MAX, 0, 0, u16::MAX, 0, u16::MAX]);
+	}
+
+	#[test]
+	fn map_polynomial_sees_values_relative_to_the_domain() {
+		// 0.1 + 0.5x
+		let mut parameters = area([0, 0, 1, 2], [1, 1]);
+		parameters.extend(1_u32.to_be_bytes());
+		parameters.extend([0.1_f64, 0.5].iter().flat_map(|x| x.to_be_bytes()));
+
+		// Black at 100 and white at 1100, so 600 is halfway
+		let domain = Domain { black: [100.; 4], white: 1100. };
+		let mut raw_image = image(2, 1, vec![100, 600]);
+		apply_list(&mut raw_image, &[(MAP_POLYNOMIAL, 0, parameters)], &domain).unwrap();
+		assert_eq!(raw_image.data, [200, 450]);
+	}
+
+	#[test]
+	fn delta_and_scale_per_row_and_column() {
+		let domain = Domain { black: [0.; 4], white: 1000. };
+		let values = |values: &[f32]| {
+			let mut parameters = area([0, 0, 2, 2], [1, 1]);
+			parameters.extend((values.len() as u32).to_be_bytes());
+			parameters.extend(values.iter().flat_map(|x| x.to_be_bytes()));
+			parameters
+		};
+
+		let mut raw_image = image(2, 2, vec![100; 4]);
+		apply_list(&mut raw_image, &[(DELTA_PER_ROW, 0, values(&[0.1, 0.2]))], &domain).unwrap();
+		assert_eq!(raw_image.data, [200, 200, 300, 300]);
+
+		let mut raw_image = image(2, 2, vec![100; 4]);
+		apply_list(&mut raw_image, &[(DELTA_PER_COLUMN, 0, values(&[0.1, 0.2]))], &domain).unwrap();
+		assert_eq!(raw_image.data, [200, 300, 200, 300]);
+
+		let mut raw_image = image(2, 2, vec![100; 4]);
+		apply_list(&mut raw_image, &[(SCALE_PER_ROW, 0, values(&[2., 3.]))], &domain).unwrap();
+		assert_eq!(raw_image.data, [200, 200, 300, 300]);
+
+		// Missing values leave the rest of the area untouched
+		let mut raw_image = image(2, 2, vec![100; 4]);
+		apply_list(&mut raw_image, &[(SCALE_PER_COLUMN, 0, values(&[2.]))], &domain).unwrap();
+		assert_eq!(raw_image.data, [200, 100, 200, 100]);
+	}
+
+	#[test]
+	fn gain_map_interpolates_between_points() {
+		// A 1x2 map from a gain of 1 at the left edge to 3 at the right edge
+		let mut parameters = area([0, 0, 1, 4], [1, 1]);
+		parameters.extend([1_u32, 2].iter().flat_map(|x| x.to_be_bytes()));
+		parameters.extend([1_f64, 0.5, 0., 0.].iter().flat_map(|x| x.to_be_bytes()));
+		parameters.extend(1_u32.to_be_bytes());
+		parameters.extend([1_f32, 3.].iter().flat_map(|x| x.to_be_bytes()));
+
+		let domain = Domain { black: [0.; 4], white: 1000. };
+		let mut raw_image = image(4, 1, vec![100; 4]);
+		apply_list(&mut raw_image, &[(GAIN_MAP, 0, parameters)], &domain).unwrap();
+		assert_eq!(raw_image.data, [100, 200, 300, 300]);
+	}
+
+	#[test]
+	fn bad_pixels_are_replaced_by_same_colored_neighbors() {
+		// A list with one point, and a rectangle which covers the pixel to its right
+		let mut parameters = Vec::new();
+		for value in [0_u32, 1, 1, 2, 2, 2, 3, 3, 4] {
+			parameters.extend(value.to_be_bytes());
+		}
+
+		let mut raw_image = image(5, 5, vec![10; 25]);
+		raw_image.data[2 * 5 + 2] = 1000;
+		raw_image.data[2 * 5 + 3] = 2000;
+		apply_list(&mut raw_image, &[(FIX_BAD_PIXELS_LIST, 0, parameters)], &RAW).unwrap();
+		assert_eq!(raw_image.data[2 * 5 + 2..][..2], [10, 10]);
+
+		let mut raw_image = image(5, 5, vec![10; 25]);
+		raw_image.data[12] = 0;
+		apply_list(&mut raw_image, &[(FIX_BAD_PIXELS_CONSTANT, 0, 0_u32.to_be_bytes().to_vec())], &RAW).unwrap();
+		assert_eq!(raw_image.data, vec![10; 25]);
+	}
+}
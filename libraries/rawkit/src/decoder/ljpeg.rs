@@ -0,0 +1,504 @@
+//! Lossless JPEG (ITU T.81 process 14) as used to compress the raw data of DNG and Canon CR2 files, along with the bit and Huffman readers shared with other decoders.
+
+use crate::DecoderError;
+
+/// Reads bits, most significant bit first, from a byte buffer. Reading past the end of the data yields zeros.
+pub(crate) struct BitPump<'a> {
+	data: &'a [u8],
+	position: usize,
+	buffer: u64,
+	bits: u32,
+	/// Whether the data uses JPEG byte stuffing, where each 0xFF byte is followed by a 0x00 byte to distinguish it from a marker.
+	jpeg_stuffing: bool,
+	marker_reached: bool,
+}
+
+impl<'a> BitPump<'a> {
+	pub fn new(data: &'a [u8], jpeg_stuffing: bool) -> Self {
+		Self {
+			data,
+			position: 0,
+			buffer: 0,
+			bits: 0,
+			jpeg_stuffing,
+			marker_reached: false,
+		}
+	}
+
+	fn fill(&mut self) {
+		while self.bits <= 56 {
+			let byte = match self.data.get(self.position) {
+				_ if self.marker_reached => 0,
+				Some(0xff) if self.jpeg_stuffing => {
+					if self.data.get(self.position + 1) == Some(&0) {
+						self.position += 2;
+						0xff
+					} else {
+						self.marker_reached = true;
+						0
+					}
+				}
+				Some(&byte) => {
+					self.position += 1;
+					byte
+				}
+				None => 0,
+			};
+
+			self.buffer |= (byte as u64) << (56 - self.bits);
+			self.bits += 8;
+		}
+	}
+
+	/// Returns the next `count` bits (at most 32) without consuming them.
+	pub fn peek(&mut self, count: u32) -> u32 {
+		if count == 0 {
+			return 0;
+		}
+		if self.bits < count {
+			self.fill();
+		}
+
+		(self.buffer >> (64 - count)) as u32
+	}
+
+	/// Consumes `count` bits, which must have been peeked before.
+	pub fn skip(&mut self, count: u32) {
+		self.buffer <<= count;
+		self.bits -= count;
+	}
+
+	pub fn get(&mut self, count: u32) -> u32 {
+		let value = self.peek(count);
+		self.skip(count);
+		value
+	}
+
+	/// Discards the buffered bits and continues reading after the next restart marker.
+	pub fn restart(&mut self) {
+		while self.position + 1 < self.data.len() && !(self.data[self.position] == 0xff && (0xd0..=0xd7).contains(&self.data[self.position + 1])) {
+			self.position += 1;
+		}
+
+		self.position += 2;
+		self.buffer = 0;
+		self.bits = 0;
+		self.marker_reached = false;
+	}
+}
+
+/// A canonical Huffman code, as stored in a JPEG DHT segment.
+pub(crate) struct HuffmanTable {
+	/// Maps every possible 16 bit prefix of the input to the symbol and the length of the code it starts with. A length of 0 marks an invalid code.
+	lookup: Vec<(u8, u8)>,
+}
+
+impl HuffmanTable {
+	/// Builds the table from the number of codes of each length from 1 to 16 bits and the symbols in order of their code.
+	pub fn new(counts: &[u8], symbols: &[u8]) -> Result<Self, DecoderError> {
+		let mut lookup = vec![(0, 0); 1 << 16];
+		let mut symbols = symbols.iter();
+		let mut code = 0_u32;
+
+		for (index, &count) in counts.iter().take(16).enumerate() {
+			let length = index as u32 + 1;
+			for _ in 0..count {
+				let symbol = *symbols.next().ok_or(DecoderError::CorruptData)?;
+				if code >= 1 << length {
+					return Err(DecoderError::CorruptData);
+				}
+
+				let shift = 16 - length;
+				let start = (code << shift) as usize;
+				lookup[start..start + (1 << shift)].fill((symbol, length as u8));
+				code += 1;
+			}
+			code <<= 1;
+		}
+
+		Ok(Self { lookup })
+	}
+
+	pub fn decode(&self, bits: &mut BitPump) -> Result<u8, DecoderError> {
+		let (symbol, length) = self.lookup[bits.peek(16) as usize];
+		if length == 0 {
+			return Err(DecoderError::CorruptData);
+		}
+
+		bits.skip(length.into());
+		Ok(symbol)
+	}
+
+	/// Decodes a difference from the prediction, which is a Huffman coded bit length followed by that many bits of the value.
+	pub fn decode_difference(&self, bits: &mut BitPump) -> Result<i32, DecoderError> {
+		let length = self.decode(bits)?.into();
+
+		Ok(match length {
+			0 => 0,
+			// A length of 16 is used for a difference of 32768 and isn't followed by any extra bits
+			16 => -32768,
+			1..16 => {
+				let value = bits.get(length) as i32;
+				if value < 1 << (length - 1) { value - (1 << length) + 1 } else { value }
+			}
+			_ => return Err(DecoderError::CorruptData),
+		})
+	}
+}
+
+/// The decoded samples of a lossless JPEG image.
+pub(crate) struct LosslessJpeg {
+	pub width: usize,
+	pub components: usize,
+	pub precision: u8,
+
+	/// Samples stored row by row with the components of each pixel interleaved, so each row holds `width * components` samples.
+	pub data: Vec<u16>,
+}
+
+struct Frame {
+	width: usize,
+	height: usize,
+	precision: u8,
+	component_ids: Vec<u8>,
+}
+
+fn read_u16(data: &[u8], position: usize) -> Result<u16, DecoderError> {
+	let bytes = data.get(position..position + 2).ok_or(DecoderError::CorruptData)?;
+	Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+pub(crate) fn decode(data: &[u8]) -> Result<LosslessJpeg, DecoderError> {
+	if data.get(0..2) != Some(&[0xff, 0xd8]) {
+		return Err(DecoderError::CorruptData);
+	}
+
+	let mut frame = None;
+	let mut tables: [Option<HuffmanTable>; 4] = Default::default();
+	let mut restart_interval = 0;
+
+	let mut position = 2;
+	loop {
+		// Skip any fill bytes before the marker
+		while data.get(position) == Some(&0xff) && data.get(position + 1) == Some(&0xff) {
+			position += 1;
+		}
+
+		let marker = read_u16(data, position)?;
+		if marker >> 8 != 0xff || marker == 0xffd9 {
+			return Err(DecoderError::CorruptData);
+		}
+
+		let length: usize = read_u16(data, position + 2)?.into();
+		let segment = data.get(position + 4..position + 2 + length).ok_or(DecoderError::CorruptData)?;
+		position += 2 + length;
+
+		match marker {
+			// Start of frame for lossless, Huffman coded JPEG
+			0xffc3 => {
+				let [precision, height_high, height_low, width_high, width_low, count, ..] = *segment else {
+					return Err(DecoderError::CorruptData);
+				};
+				let components = segment.get(6..6 + 3 * count as usize).ok_or(DecoderError::CorruptData)?;
+				if components.chunks_exact(3).any(|component| component[1] != 0x11) {
+					return Err(DecoderError::UnsupportedFeature("subsampled lossless JPEG"));
+				}
+
+				frame = Some(Frame {
+					width: u16::from_be_bytes([width_high, width_low]).into(),
+					height: u16::from_be_bytes([height_high, height_low]).into(),
+					precision,
+					component_ids: components.chunks_exact(3).map(|component| component[0]).collect(),
+				});
+			}
+			// Any other kind of start of frame (except DHT, JPG and DAC which share the range)
+			0xffc0..=0xffcf if !matches!(marker, 0xffc4 | 0xffc8 | 0xffcc) => return Err(DecoderError::UnsupportedFeature("lossy JPEG")),
+			// Define Huffman tables
+			0xffc4 => {
+				let mut segment = segment;
+				while let [class_and_id, ref rest @ ..] = *segment {
+					let counts = rest.get(..16).ok_or(DecoderError::CorruptData)?;
+					let count = counts.iter().map(|&x| x as usize).sum::<usize>();
+					let symbols = rest.get(16..16 + count).ok_or(DecoderError::CorruptData)?;
+
+					*tables.get_mut((class_and_id & 0xf) as usize).ok_or(DecoderError::CorruptData)? = Some(HuffmanTable::new(counts, symbols)?);
+					segment = &rest[16 + count..];
+				}
+			}
+			// Define restart interval
+			0xffdd => restart_interval = read_u16(segment, 0)?.into(),
+			// Start of scan, which is followed by the entropy coded data
+			0xffda => {
+				let frame = frame.ok_or(DecoderError::CorruptData)?;
+				return decode_scan(&frame, segment, &tables, restart_interval, &data[position..]);
+			}
+			_ => {}
+		}
+	}
+}
+
+fn decode_scan(frame: &Frame, header: &[u8], tables: &[Option<HuffmanTable>; 4], restart_interval: usize, data: &[u8]) -> Result<LosslessJpeg, DecoderError> {
+	let count = *header.first().ok_or(DecoderError::CorruptData)? as usize;
+	if count != frame.component_ids.len() {
+		return Err(DecoderError::UnsupportedFeature("non-interleaved lossless JPEG"));
+	}
+
+	let component_tables = header
+		.get(1..1 + 2 * count)
+		.ok_or(DecoderError::CorruptData)?
+		.chunks_exact(2)
+		.map(|component| tables.get((component[1] >> 4) as usize).and_then(Option::as_ref).ok_or(DecoderError::CorruptData))
+		.collect::<Result<Vec<_>, _>>()?;
+	let predictor = *header.get(1 + 2 * count).ok_or(DecoderError::CorruptData)?;
+	let point_transform = header.get(3 + 2 * count).ok_or(DecoderError::CorruptData)? & 0xf;
+	if !(1..=7).contains(&predictor) || point_transform >= frame.precision {
+		return Err(DecoderError::CorruptData);
+	}
+
+	let Frame { width, height, precision, .. } = *frame;
+	let row_length = width * count;
+	let initial_prediction = 1 << (precision - point_transform - 1);
+
+	let mut output = vec![0_u16; row_length * height];
+	let mut bits = BitPump::new(data, true);
+	let mut units_until_restart = restart_interval;
+	// The row in which the current restart interval starts, whose remaining samples are predicted from the left only
+	let mut first_row = 0;
+	// Whether the next unit starts the scan or a restart interval, which may fall anywhere in a row, and so is predicted from the initial prediction
+	let mut interval_start = true;
+
+	for row in 0..height {
+		for column in 0..width {
+			if restart_interval > 0 {
+				if units_until_restart == 0 {
+					bits.restart();
+					units_until_restart = restart_interval;
+					first_row = row;
+					interval_start = true;
+				}
+				units_until_restart -= 1;
+			}
+
+			for (component, table) in component_tables.iter().enumerate() {
+				let index = row * row_length + column * count + component;
+				let left = || output[index - count] as i32;
+				let above = || output[index - row_length] as i32;
+				let above_left = || output[index - row_length - count] as i32;
+
+				let prediction = match (row == first_row, column == 0) {
+					_ if interval_start => initial_prediction,
+					(true, _) => left(),
+					(false, true) => above(),
+					(false, false) => match predictor {
+						1 => left(),
+						2 => above(),
+						3 => above_left(),
+						4 => left() + above() - above_left(),
+						5 => left() + ((above() - above_left()) >> 1),
+						6 => above() + ((left() - above_left()) >> 1),
+						_ => (left() + above()) >> 1,
+					},
+				};
+
+				output[index] = (prediction + table.decode_difference(&mut bits)?) as u16;
+			}
+			interval_start = false;
+		}
+	}
+
+	if point_transform > 0 {
+		output.iter_mut().for_each(|x| *x <<= point_transform);
+	}
+
+	Ok(LosslessJpeg {
+		width,
+		components: count,
+		precision,
+		data: output,
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Writes bits most significant bit first, with JPEG byte stuffing.
+	#[derive(Default)]
+	struct BitWriter {
+		data: Vec<u8>,
+		buffer: u32,
+		bits: u32,
+	}
+
+	impl BitWriter {
+		fn put(&mut self, value: u32, count: u32) {
+			for bit in (0..count).rev() {
+				self.buffer = (self.buffer << 1) | ((value >> bit) & 1);
+				self.bits += 1;
+				if self.bits == 8 {
+					self.data.push(self.buffer as u8);
+					if self.buffer == 0xff {
+						self.data.push(0);
+					}
+					(self.buffer, self.bits) = (0, 0);
+				}
+			}
+		}
+
+		/// Pads the last byte with ones, as required before a marker.
+		fn flush(&mut self) {
+			if self.bits > 0 {
+				self.put(u32::MAX, 8 - self.bits);
+			}
+		}
+	}
+
+	/// A Huffman table giving every difference length from 0 to 16 a 5 bit code equal to the length.
+	const COUNTS: [u8; 16] = [0, 0, 0, 0, 17, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+	fn segment(output: &mut Vec<u8>, marker: u8, contents: &[u8]) {
+		output.extend([0xff, marker]);
+		output.extend(((contents.len() + 2) as u16).to_be_bytes());
+		output.extend(contents);
+	}
+
+	/// Encodes interleaved samples as a lossless JPEG image, following T.81 independently of the decoder.
+	fn encode(samples: &[u16], width: usize, height: usize, components: usize, precision: u8, predictor: u8, restart_interval: u16) -> Vec<u8> {
+		let mut output = vec![0xff, 0xd8];
+
+		let mut frame = vec![precision];
+		frame.extend((height as u16).to_be_bytes());
+		frame.extend((width as u16).to_be_bytes());
+		frame.push(components as u8);
+		for id in 0..components as u8 {
+			frame.extend([id + 1, 0x11, 0]);
+		}
+		segment(&mut output, 0xc3, &frame);
+
+		let mut huffman = vec![0];
+		huffman.extend(COUNTS);
+		huffman.extend(0..=16);
+		segment(&mut output, 0xc4, &huffman);
+
+		if restart_interval > 0 {
+			segment(&mut output, 0xdd, &restart_interval.to_be_bytes());
+		}
+
+		let mut scan = vec![components as u8];
+		for id in 0..components as u8 {
+			scan.extend([id + 1, 0]);
+		}
+		scan.extend([predictor, 0, 0]);
+		segment(&mut output, 0xda, &scan);
+
+		let mut bits = BitWriter::default();
+		let sample = |row: usize, column: usize, component: usize| samples[(row * width + column) * components + component] as i32;
+		let (mut interval_row, mut interval_column) = (0, 0);
+		for unit in 0..width * height {
+			let (row, column) = (unit / width, unit % width);
+			if restart_interval > 0 && unit > 0 && unit % restart_interval as usize == 0 {
+				bits.flush();
+				bits.data.extend([0xff, 0xd0 + ((unit / restart_interval as usize - 1) % 8) as u8]);
+				(interval_row, interval_column) = (row, column);
+			}
+
+			for component in 0..components {
+				let x = |row: usize, column: usize| sample(row, column, component);
+				let prediction = if (row, column) == (interval_row, interval_column) {
+					1 << (precision - 1)
+				} else if row == interval_row {
+					x(row, column - 1)
+				} else if column == 0 {
+					x(row - 1, column)
+				} else {
+					let (a, b, c) = (x(row, column - 1), x(row - 1, column), x(row - 1, column - 1));
+					match predictor {
+						1 => a,
+						2 => b,
+						3 => c,
+						4 => a + b - c,
+						5 => a + ((b - c) >> 1),
+						6 => b + ((a - c) >> 1),
+						_ => (a + b) >> 1,
+					}
+				};
+
+				let difference = x(row, column) - prediction;
+				let length = 32 - difference.unsigned_abs().leading_zeros();
+				bits.put(length, 5);
+				let value = if difference < 0 { difference + (1 << length) - 1 } else { difference };
+				bits.put(value as u32, length);
+			}
+		}
+		bits.flush();
+
+		output.extend(bits.data);
+		output.extend([0xff, 0xd9]);
+		output
+	}
+
+	/// Samples which vary in every direction, with some 0xFF bytes in the coded data to exercise byte stuffing.
+	fn test_samples(width: usize, height: usize, components: usize, precision: u8) -> Vec<u16> {
+		(0..width * height * components).map(|index| ((index * 2654435761) % (1 << precision)) as u16).collect()
+	}
+
+	#[test]
+	fn predictors() {
+		let (width, height, components, precision) = (7, 5, 2, 12);
+		let samples = test_samples(width, height, components, precision);
+
+		for predictor in 1..=7 {
+			let jpeg = decode(&encode(&samples, width, height, components, precision, predictor, 0)).unwrap();
+			assert_eq!((jpeg.width, jpeg.components, jpeg.precision), (width, components, precision));
+			assert_eq!(jpeg.data, samples, "predictor {predictor}");
+		}
+	}
+
+	#[test]
+	fn restart_intervals() {
+		let (width, height, components, precision) = (7, 5, 2, 14);
+		let samples = test_samples(width, height, components, precision);
+
+		// Intervals which end at the end of a row, in the middle of one, and which span several rows
+		for restart_interval in [7, 3, 10, 1] {
+			for predictor in 1..=7 {
+				let jpeg = decode(&encode(&samples, width, height, components, precision, predictor, restart_interval)).unwrap();
+				assert_eq!(jpeg.data, samples, "predictor {predictor} with restart interval {restart_interval}");
+			}
+		}
+	}
+
+	#[test]
+	fn restart_in_the_middle_of_a_row_resets_the_prediction() {
+		let mut data = vec![0xff, 0xd8];
+		segment(&mut data, 0xc3, &[8, 0, 1, 0, 3, 1, 1, 0x11, 0]);
+		let mut huffman = vec![0];
+		huffman.extend(COUNTS);
+		huffman.extend(0..=16);
+		segment(&mut data, 0xc4, &huffman);
+		segment(&mut data, 0xdd, &[0, 2]);
+		segment(&mut data, 0xda, &[1, 1, 0, 1, 0, 0]);
+
+		// Differences of +5 and +1, then a restart marker followed by a difference of 0
+		let mut bits = BitWriter::default();
+		bits.put(3, 5);
+		bits.put(5, 3);
+		bits.put(1, 5);
+		bits.put(1, 1);
+		bits.flush();
+		bits.put(0, 5);
+		bits.flush();
+		data.extend(&bits.data[..2]);
+		data.extend([0xff, 0xd0]);
+		data.extend(&bits.data[2..]);
+		data.extend([0xff, 0xd9]);
+
+		assert_eq!(decode(&data).unwrap().data, [133, 134, 128]);
+	}
+
+	#[test]
+	fn huffman_table_rejects_oversubscribed_codes() {
+		assert!(HuffmanTable::new(&[3], &[0, 1, 2]).is_err());
+		assert!(HuffmanTable::new(&[1], &[]).is_err());
+	}
+}
@@ -1,3 +1,107 @@
 pub mod arw1;
 pub mod arw2;
+pub mod cr2;
+pub mod dng;
+mod ljpeg;
+pub mod nef;
+pub mod raf;
 pub mod uncompressed;
+
+use crate::tiff::file::{Endian, TiffRead};
+use crate::tiff::tags::{NewSubfileType, PhotometricInterpretation, SubIfdOffsets};
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, RawImage, SubtractBlack};
+use ljpeg::BitPump;
+use std::io::{Read, Seek};
+
+/// The `PhotometricInterpretation` of an IFD which stores the raw data of a color filter array sensor.
+const PHOTOMETRIC_CFA: u16 = 32803;
+
+/// Searches `ifd` and its SubIFDs (depth first) for the full resolution raw CFA image, skipping over any previews.
+pub(crate) fn find_raw_ifd<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Result<Option<Ifd>, TiffError> {
+	let is_full_resolution = ifd.get_value::<Option<NewSubfileType>, _>(file)?.unwrap_or(0) == 0;
+	if is_full_resolution && ifd.get_value::<Option<PhotometricInterpretation>, _>(file)? == Some(PHOTOMETRIC_CFA) {
+		return Ok(Some(ifd.clone()));
+	}
+
+	for offset in ifd.get_value::<Option<SubIfdOffsets>, _>(file)?.unwrap_or_default() {
+		let sub_ifd = Ifd::new_from_offset(file, offset)?;
+		if let Some(raw_ifd) = find_raw_ifd(&sub_ifd, file)? {
+			return Ok(Some(raw_ifd));
+		}
+	}
+
+	Ok(None)
+}
+
+/// Reads `length` bytes starting at `offset` from the beginning of the file.
+pub(crate) fn read_block<R: Read + Seek>(file: &mut R, offset: u32, length: u32) -> Result<Vec<u8>, DecoderError> {
+	file.seek(std::io::SeekFrom::Start(offset.into()))?;
+
+	let mut data = vec![0; length.try_into()?];
+	file.read_exact(&mut data)?;
+	Ok(data)
+}
+
+/// Unpacks as many uncompressed samples of `bits_per_sample` bits as `data` holds.
+///
+/// Byte and 16 bit samples are read directly (the latter in the endianness of the file), while any other bit depth is treated as a tightly packed, most significant bit first stream.
+pub(crate) fn unpack(data: &[u8], bits_per_sample: u16, endian: Endian) -> Result<Vec<u16>, DecoderError> {
+	Ok(match bits_per_sample {
+		8 => data.iter().map(|&x| x.into()).collect(),
+		16 => data
+			.chunks_exact(2)
+			.map(|x| match endian {
+				Endian::Little => u16::from_le_bytes([x[0], x[1]]),
+				Endian::Big => u16::from_be_bytes([x[0], x[1]]),
+			})
+			.collect(),
+		1..16 => {
+			let bits_per_sample = bits_per_sample.into();
+			let mut bits = BitPump::new(data, false);
+			(0..data.len() * 8 / bits_per_sample as usize).map(|_| bits.get(bits_per_sample) as u16).collect()
+		}
+		_ => return Err(DecoderError::UnsupportedFeature("bits per sample above 16")),
+	})
+}
+
+/// Reorders the 4 values of a 2x2 CFA grid so they start at (`left`, `top`) instead of the origin.
+fn shift_cfa_grid<T: Copy>(grid: [T; 4], left: usize, top: usize) -> [T; 4] {
+	std::array::from_fn(|index| grid[2 * ((index / 2 + top) % 2) + (index % 2 + left) % 2])
+}
+
+impl RawImage {
+	/// Crops the raw pixel data to the given rectangle, keeping the CFA pattern, black levels and white balance aligned with the new origin.
+	pub(crate) fn crop(&mut self, left: usize, top: usize, width: usize, height: usize) {
+		let left = left.min(self.width);
+		let top = top.min(self.height);
+		let width = width.min(self.width - left);
+		let height = height.min(self.height - top);
+
+		let mut data = Vec::with_capacity(width * height);
+		for row in top..top + height {
+			data.extend_from_slice(&self.data[row * self.width + left..][..width]);
+		}
+
+		self.data = data;
+		self.width = width;
+		self.height = height;
+		self.cfa_pattern = shift_cfa_grid(self.cfa_pattern, left, top);
+		self.x_trans_pattern = self
+			.x_trans_pattern
+			.map(|pattern| std::array::from_fn(|row| std::array::from_fn(|column| pattern[(row + top) % 6][(column + left) % 6])));
+		if let SubtractBlack::CfaGrid(black) = self.black {
+			self.black = SubtractBlack::CfaGrid(shift_cfa_grid(black, left, top));
+		}
+		self.camera_white_balance = self.camera_white_balance.map(|white_balance| shift_cfa_grid(white_balance, left, top));
+	}
+
+	/// Maps the white balance multipliers of the red, green and blue channels onto the CFA grid.
+	pub(crate) fn cfa_white_balance(&self, [red, green, blue]: [f64; 3]) -> [f64; 4] {
+		self.cfa_pattern.map(|color| match color {
+			0 => red,
+			1 => green,
+			_ => blue,
+		})
+	}
+}
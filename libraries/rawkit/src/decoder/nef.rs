@@ -0,0 +1,363 @@
+use super::ljpeg::{BitPump, HuffmanTable};
+use super::{find_raw_ifd, read_block, unpack};
+use crate::tiff::file::{Endian, TiffRead};
+use crate::tiff::tags::{
+	BitsPerSample, CfaPattern, CfaPatternDim, Compression, ExifIfd, ImageLength, ImageWidth, MakerNote, NikonBlackLevel, NikonLinearizationTable, NikonWhiteBalanceRbLevels, StripByteCounts,
+	StripOffsets, Tag,
+};
+use crate::tiff::values::CompressionValue;
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Cursor, Read, Seek};
+
+/// The Huffman tables used by Nikon's compression, given as the number of codes of each length from 1 to 16 bits followed by the symbols.
+///
+/// Each symbol holds the bit length of the difference in its lower 4 bits and the number of low bits left out of it (for lossy compression) in its upper 4 bits.
+const NIKON_TREE: [[u8; 32]; 6] = [
+	// 12 bit lossy
+	[0, 1, 5, 1, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 5, 4, 3, 6, 2, 7, 1, 0, 8, 9, 11, 10, 12, 0, 0, 0],
+	// 12 bit lossy after split
+	[0, 1, 5, 1, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0x39, 0x5a, 0x38, 0x27, 0x16, 5, 4, 3, 2, 1, 0, 11, 12, 12, 0, 0],
+	// 12 bit lossless
+	[0, 1, 4, 2, 3, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5, 4, 6, 3, 7, 2, 8, 1, 9, 0, 10, 11, 12, 0, 0, 0],
+	// 14 bit lossy
+	[0, 1, 4, 3, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 5, 6, 4, 7, 8, 3, 9, 2, 1, 0, 10, 11, 12, 13, 14, 0],
+	// 14 bit lossy after split
+	[0, 1, 5, 1, 1, 1, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 8, 0x5c, 0x4b, 0x3a, 0x29, 7, 6, 5, 4, 3, 2, 1, 0, 13, 14, 0],
+	// 14 bit lossless
+	[0, 1, 4, 2, 2, 3, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 7, 6, 8, 5, 9, 4, 10, 3, 11, 12, 2, 0, 1, 13, 14, 0],
+];
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct NefIfd {
+	image_width: ImageWidth,
+	image_height: ImageLength,
+	bits_per_sample: BitsPerSample,
+	compression: Compression,
+	cfa_pattern_dim: CfaPatternDim,
+	cfa_pattern: CfaPattern,
+	strip_offsets: StripOffsets,
+	strip_byte_counts: StripByteCounts,
+}
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct NikonMakerNote {
+	white_balance_levels: Option<NikonWhiteBalanceRbLevels>,
+	black_level: Option<NikonBlackLevel>,
+	linearization_table: Option<NikonLinearizationTable>,
+}
+
+/// Decodes a Nikon NEF file, whose first IFD is `ifd`.
+///
+/// The raw data is either uncompressed or uses Nikon's Huffman coded compression, which needs the linearization table from the maker note.
+pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> Result<RawImage, DecoderError> {
+	let metadata = nikon_metadata(&ifd, file)?;
+	let Some(raw_ifd) = find_raw_ifd(&ifd, file)? else {
+		return Err(DecoderError::UnsupportedFeature("NEF without CFA data"));
+	};
+	let ifd = raw_ifd.get_value::<NefIfd, _>(file)?;
+
+	let width: usize = ifd.image_width.try_into()?;
+	let height: usize = ifd.image_height.try_into()?;
+	if ifd.cfa_pattern_dim != [2, 2] || ifd.strip_offsets.is_empty() || ifd.strip_offsets.len() != ifd.strip_byte_counts.len() {
+		return Err(TiffError::InvalidValue.into());
+	}
+
+	let mut data = Vec::new();
+	for (&offset, &byte_count) in ifd.strip_offsets.iter().zip(&ifd.strip_byte_counts) {
+		data.extend(read_block(file, offset, byte_count)?);
+	}
+
+	let (image, maximum) = match ifd.compression {
+		CompressionValue::Uncompressed => {
+			// Uncompressed data is either stored in 16 bit containers or tightly packed
+			let bits_per_sample = if data.len() >= width * height * 2 { 16 } else { ifd.bits_per_sample };
+			let image = unpack(&data, bits_per_sample, file.endian())?;
+			(image, u16::MAX >> (16 - ifd.bits_per_sample.min(16)))
+		}
+		CompressionValue::NikonNEFCompressed => {
+			let Some((linearization_table, endian)) = metadata.as_ref().and_then(|metadata| Some((metadata.linearization_table.as_ref()?, metadata.endian))) else {
+				return Err(DecoderError::UnsupportedFeature("NEF without linearization table"));
+			};
+			nikon_decompress(&data, width, height, ifd.bits_per_sample, linearization_table, endian)?
+		}
+		_ => return Err(DecoderError::UnsupportedFeature("NEF compression")),
+	};
+	if image.len() < width * height {
+		return Err(DecoderError::CorruptData);
+	}
+
+	let mut raw_image = RawImage {
+		data: image,
+		width,
+		height,
+		cfa_pattern: ifd.cfa_pattern.try_into().map_err(|_| TiffError::InvalidCount)?,
+		x_trans_pattern: None,
+		maximum,
+		black: SubtractBlack::None,
+		orientation: OrientationValue::Horizontal,
+		camera_model: None,
+		camera_white_balance: None,
+		white_balance: None,
+		camera_to_rgb: None,
+		xyz_to_camera: None,
+	};
+	raw_image.data.truncate(width * height);
+
+	if let Some(metadata) = metadata {
+		if let Some(black_level) = metadata.black_level {
+			raw_image.black = SubtractBlack::CfaGrid(black_level);
+		}
+
+		if let Some(&[red, blue, ..]) = metadata.white_balance_levels.as_deref()
+			&& red > 0.
+			&& blue > 0.
+		{
+			raw_image.camera_white_balance = Some(raw_image.cfa_white_balance([red, 1., blue]));
+		}
+	}
+
+	Ok(raw_image)
+}
+
+/// The values from Nikon's maker note which are needed to decode the raw data.
+struct NikonMetadata {
+	/// The red and blue white balance multipliers, relative to green.
+	white_balance_levels: Option<Vec<f64>>,
+	black_level: Option<[u16; 4]>,
+	/// The contents of the linearization table, which also holds the parameters of the compression.
+	linearization_table: Option<Vec<u8>>,
+	/// The endianness of the maker note, which is used for the values in the linearization table.
+	endian: Endian,
+}
+
+/// Reads the maker note, which Nikon stores as a complete TIFF file of its own (with offsets relative to its start) after a short header.
+fn nikon_metadata<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Result<Option<NikonMetadata>, DecoderError> {
+	let Some(exif_ifd) = ifd.get_value::<Option<ExifIfd>, _>(file)? else { return Ok(None) };
+	let Some(maker_note) = exif_ifd.get_value::<Option<MakerNote>, _>(file)? else { return Ok(None) };
+
+	let data = read_block(file, maker_note.offset, maker_note.length)?;
+	let Some(tiff) = data.strip_prefix(b"Nikon\0").and_then(|data| data.get(4..)) else {
+		return Ok(None);
+	};
+
+	let mut maker_note_file = TiffRead::new(Cursor::new(tiff))?;
+	let maker_note_ifd = Ifd::new_first_ifd(&mut maker_note_file)?;
+	let maker_note = maker_note_ifd.get_value::<NikonMakerNote, _>(&mut maker_note_file)?;
+
+	let linearization_table = match maker_note.linearization_table {
+		Some(table) => Some(read_block(&mut maker_note_file, table.offset, table.length)?),
+		None => None,
+	};
+
+	Ok(Some(NikonMetadata {
+		white_balance_levels: maker_note.white_balance_levels,
+		black_level: maker_note.black_level,
+		linearization_table,
+		endian: maker_note_file.endian(),
+	}))
+}
+
+/// Decompresses Nikon's Huffman coded raw data, returning the pixel values and the white level.
+///
+/// Each pixel is predicted from the previous pixel of the same color in its row, and the first two pixels of each row from those of the row two above.
+/// The linearization table holds the initial predictions, the tone curve the decoded values are mapped through, and (for lossy compression) the row at which the Huffman table changes.
+fn nikon_decompress(data: &[u8], width: usize, height: usize, bits_per_sample: u16, table: &[u8], endian: Endian) -> Result<(Vec<u16>, u16), DecoderError> {
+	let read_u16 = |position: usize| -> Result<u16, DecoderError> {
+		let bytes = table.get(position..position + 2).ok_or(DecoderError::CorruptData)?;
+		Ok(match endian {
+			Endian::Little => u16::from_le_bytes([bytes[0], bytes[1]]),
+			Endian::Big => u16::from_be_bytes([bytes[0], bytes[1]]),
+		})
+	};
+
+	let [version_0, version_1, ..] = *table else { return Err(DecoderError::CorruptData) };
+	let mut position = if version_0 == 0x49 || version_1 == 0x58 { 2 + 2110 } else { 2 };
+
+	let mut tree = if version_0 == 0x46 { 2 } else { 0 };
+	if bits_per_sample == 14 {
+		tree += 3;
+	}
+
+	let mut vertical_predictions = [[read_u16(position)?, read_u16(position + 2)?], [read_u16(position + 4)?, read_u16(position + 6)?]].map(|row| row.map(i32::from));
+	position += 8;
+
+	let mut curve: Vec<u16> = (0..=u16::MAX).collect();
+	let mut max = (1_usize << bits_per_sample) & 0x7fff;
+	let curve_size: usize = read_u16(position)?.into();
+	position += 2;
+	let step = if curve_size > 1 { max / (curve_size - 1) } else { 0 };
+
+	let mut split_row = 0;
+	if version_0 == 0x44 && version_1 == 0x20 && step > 0 {
+		// The curve is given by evenly spaced points, which are linearly interpolated
+		for i in 0..curve_size {
+			curve[i * step] = read_u16(position + 2 * i)?;
+		}
+		for i in 0..max {
+			let (low, fraction) = (i - i % step, i % step);
+			curve[i] = ((curve[low] as usize * (step - fraction) + curve[(low + step).min(u16::MAX as usize)] as usize * fraction) / step) as u16;
+		}
+		split_row = read_u16(562)?.into();
+	} else if version_0 != 0x46 && curve_size <= 0x4001 {
+		for (i, value) in curve.iter_mut().take(curve_size).enumerate() {
+			*value = read_u16(position + 2 * i)?;
+		}
+		max = curve_size;
+	}
+	while max > 2 && curve[max - 2] == curve[max - 1] {
+		max -= 1;
+	}
+
+	let huffman_table = |tree: usize| HuffmanTable::new(&NIKON_TREE[tree][..16], &NIKON_TREE[tree][16..]);
+	let mut huffman = huffman_table(tree)?;
+	let mut bits = BitPump::new(data, false);
+
+	let mut image = vec![0_u16; width * height];
+	let mut horizontal_predictions = [0_i32; 2];
+	for row in 0..height {
+		if split_row > 0 && row == split_row {
+			huffman = huffman_table(tree + 1)?;
+		}
+
+		for column in 0..width {
+			let symbol: u32 = huffman.decode(&mut bits)?.into();
+			let (length, shift) = (symbol & 15, symbol >> 4);
+
+			let difference = if length == 0 {
+				0
+			} else {
+				let value = bits.get(length.checked_sub(shift).ok_or(DecoderError::CorruptData)?);
+				let difference = ((((value << 1) + 1) << shift) >> 1) as i32;
+				if difference & (1 << (length - 1)) == 0 {
+					difference - (1 << length) + (shift == 0) as i32
+				} else {
+					difference
+				}
+			};
+
+			if column < 2 {
+				vertical_predictions[row & 1][column] += difference;
+				horizontal_predictions[column] = vertical_predictions[row & 1][column];
+			} else {
+				horizontal_predictions[column & 1] += difference;
+			}
+
+			image[row * width + column] = curve[horizontal_predictions[column & 1].clamp(0, 0x3fff) as usize];
+		}
+	}
+
+	Ok((image, curve[max - 1]))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// The canonical code and its length for each symbol of a Huffman table given like [`NIKON_TREE`].
+	fn codes(tree: &[u8; 32]) -> Vec<(u8, u32, u32)> {
+		let (counts, symbols) = tree.split_at(16);
+		let mut symbols = symbols.iter();
+		let mut codes = Vec::new();
+		let mut code = 0;
+		for (index, &count) in counts.iter().enumerate() {
+			for _ in 0..count {
+				codes.push((*symbols.next().unwrap(), code, index as u32 + 1));
+				code += 1;
+			}
+			code <<= 1;
+		}
+		codes
+	}
+
+	#[test]
+	fn huffman_tables_decode_every_symbol() {
+		for tree in &NIKON_TREE {
+			let huffman = HuffmanTable::new(&tree[..16], &tree[16..]).unwrap();
+			for (symbol, code, length) in codes(tree) {
+				let data = (code << (32 - length)).to_be_bytes();
+				let mut bits = BitPump::new(&data, false);
+				assert_eq!(huffman.decode(&mut bits).unwrap(), symbol);
+				assert_eq!(bits.get(32 - length), 0);
+			}
+		}
+	}
+
+	/// Writes bits most significant bit first, without any byte stuffing.
+	#[derive(Default)]
+	struct BitWriter {
+		data: Vec<u8>,
+		buffer: u8,
+		bits: u32,
+	}
+
+	impl BitWriter {
+		fn put(&mut self, value: u32, count: u32) {
+			for bit in (0..count).rev() {
+				self.buffer = (self.buffer << 1) | ((value >> bit) & 1) as u8;
+				self.bits += 1;
+				if self.bits == 8 {
+					self.data.push(self.buffer);
+					(self.buffer, self.bits) = (0, 0);
+				}
+			}
+		}
+
+		fn finish(mut self) -> Vec<u8> {
+			self.put(0, (8 - self.bits) % 8);
+			self.data
+		}
+	}
+
+	/// Encodes an image with a lossless Nikon table, predicting each pixel from the previous one of the same color in its row and the first two of each row from the row two above.
+	fn compress(image: &[u16], width: usize, height: usize, tree: &[u8; 32], initial: [[u16; 2]; 2]) -> Vec<u8> {
+		let codes = codes(tree);
+		let mut bits = BitWriter::default();
+
+		for row in 0..height {
+			for column in 0..width {
+				let prediction = match (row, column) {
+					(0 | 1, 0 | 1) => initial[row][column],
+					(_, 0 | 1) => image[(row - 2) * width + column],
+					_ => image[row * width + column - 2],
+				};
+				let difference = image[row * width + column] as i32 - prediction as i32;
+				let length = 32 - difference.unsigned_abs().leading_zeros();
+
+				let &(_, code, code_length) = codes.iter().find(|&&(symbol, ..)| u32::from(symbol) == length).unwrap();
+				bits.put(code, code_length);
+				bits.put(if difference < 0 { difference + (1 << length) - 1 } else { difference } as u32, length);
+			}
+		}
+
+		bits.finish()
+	}
+
+	/// A linearization table of the version which keeps the values as they are, holding just the initial predictions.
+	fn linearization_table(initial: [[u16; 2]; 2]) -> Vec<u8> {
+		let mut table = vec![0x46, 0x30];
+		table.extend(initial.as_flattened().iter().flat_map(|x| x.to_be_bytes()));
+		table.extend(0_u16.to_be_bytes());
+		table
+	}
+
+	#[test]
+	fn lossless_decompression() {
+		let (width, height) = (6, 4);
+		let initial = [[2048, 2000], [1900, 2100]];
+
+		for (bits_per_sample, tree) in [(12, 2), (14, 5)] {
+			let image: Vec<u16> = (0..width * height).map(|index| ((index * 2654435761) % (1 << bits_per_sample)) as u16).collect();
+			let data = compress(&image, width, height, &NIKON_TREE[tree], initial);
+
+			// The bit depth picks between the 12 and 14 bit trees
+			let table = linearization_table(initial);
+			let (decoded, maximum) = nikon_decompress(&data, width, height, bits_per_sample, &table, Endian::Big).unwrap();
+			assert_eq!(decoded, image, "{bits_per_sample} bit");
+			assert_eq!(maximum, (1 << bits_per_sample) - 1);
+		}
+	}
+}
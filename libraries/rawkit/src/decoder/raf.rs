@@ -0,0 +1,200 @@
+use super::{read_block, unpack};
+use crate::metadata::identify::CameraModel;
+use crate::tiff::file::{Endian, TiffRead};
+use crate::tiff::tags::{RafBitsPerSample, RafBlackLevel, RafRawHeight, RafRawIfd, RafRawWidth, RafStripByteCount, RafStripOffset, Tag};
+use crate::tiff::{Ifd, TiffError};
+use crate::{DecoderError, OrientationValue, RawImage, SubtractBlack};
+use rawkit_proc_macros::Tag;
+use std::io::{Cursor, Read, Seek};
+
+/// The first bytes of every Fujifilm RAF file.
+pub const MAGIC: &[u8; 16] = b"FUJIFILMCCD-RAW ";
+
+const HEADER_LENGTH: usize = 108;
+
+/// The tags of the records in the CFA header.
+const RECORD_RAW_DIMENSIONS: u16 = 0x100;
+const RECORD_XTRANS_PATTERN: u16 = 0x131;
+const RECORD_WHITE_BALANCE: u16 = 0x2ff0;
+
+#[allow(dead_code)]
+#[derive(Tag)]
+struct RafIfd {
+	width: RafRawWidth,
+	height: RafRawHeight,
+	bits_per_sample: RafBitsPerSample,
+	strip_offset: RafStripOffset,
+	strip_byte_count: RafStripByteCount,
+	black_level: Option<RafBlackLevel>,
+}
+
+/// Decodes a Fujifilm RAF file.
+///
+/// RAF files start with a big endian header pointing to an embedded JPEG preview, a CFA header of tagged records, and the CFA data, which is itself a TIFF file in newer models.
+/// Only uncompressed data is supported, from either Bayer or X-Trans sensors.
+pub fn decode<R: Read + Seek>(reader: &mut R) -> Result<RawImage, DecoderError> {
+	let header = read_block(reader, 0, HEADER_LENGTH as u32)?;
+	if !header.starts_with(MAGIC) {
+		return Err(DecoderError::CorruptData);
+	}
+	let read_u32 = |position: usize| u32::from_be_bytes(header[position..position + 4].try_into().unwrap());
+
+	let model = header[28..60].iter().take_while(|&&x| x != 0).map(|&x| x as char).collect::<String>();
+	let (cfa_header_offset, cfa_header_length) = (read_u32(92), read_u32(96));
+	let (cfa_offset, cfa_length) = (read_u32(100), read_u32(104));
+
+	let mut dimensions = None;
+	let mut white_balance = None;
+	let mut x_trans_pattern = None;
+
+	let cfa_header = read_block(reader, cfa_header_offset, cfa_header_length)?;
+	let record_count = cfa_header.get(..4).map(|x| u32::from_be_bytes(x.try_into().unwrap())).ok_or(DecoderError::CorruptData)?;
+	let mut records = &cfa_header[4..];
+	for _ in 0..record_count {
+		let [tag_high, tag_low, length_high, length_low, ref rest @ ..] = *records else {
+			return Err(DecoderError::CorruptData);
+		};
+		let (tag, length) = (u16::from_be_bytes([tag_high, tag_low]), u16::from_be_bytes([length_high, length_low]) as usize);
+		let record = rest.get(..length).ok_or(DecoderError::CorruptData)?;
+		let read_u16 = |index: usize| record.get(2 * index..2 * index + 2).map(|x| u16::from_be_bytes([x[0], x[1]]));
+
+		match tag {
+			RECORD_RAW_DIMENSIONS => dimensions = read_u16(0).zip(read_u16(1)).map(|(height, width)| (width as usize, height as usize)),
+			// Stored from the last pixel of the 6x6 grid to the first
+			RECORD_XTRANS_PATTERN => {
+				let pattern = record.get(..36).ok_or(DecoderError::CorruptData)?;
+				x_trans_pattern = Some(std::array::from_fn(|row| std::array::from_fn(|column| pattern[35 - (6 * row + column)] & 3)));
+			}
+			// Stored as green, red, green, blue
+			RECORD_WHITE_BALANCE => white_balance = (0..4).map(read_u16).collect::<Option<Vec<_>>>(),
+			_ => {}
+		}
+
+		records = &rest[length..];
+	}
+
+	let cfa = read_block(reader, cfa_offset, cfa_length)?;
+	let (image, width, height, bits_per_sample, black) = if cfa.starts_with(b"II*\0") || cfa.starts_with(b"MM\0*") {
+		// Newer models store the CFA data in a TIFF file whose offsets are relative to its start
+		let mut file = TiffRead::new(Cursor::new(cfa.as_slice()))?;
+		let ifd = Ifd::new_first_ifd(&mut file)?.get_value::<RafRawIfd, _>(&mut file)?.get_value::<RafIfd, _>(&mut file)?;
+
+		let (width, height): (usize, usize) = (ifd.width.try_into()?, ifd.height.try_into()?);
+		let start: usize = ifd.strip_offset.try_into()?;
+		let data = cfa.get(start..start + ifd.strip_byte_count as usize).ok_or(TiffError::InvalidOffset)?;
+		if data.len() < width * height * 2 {
+			return Err(DecoderError::UnsupportedFeature("compressed or packed RAF"));
+		}
+
+		let black = match ifd.black_level.as_deref() {
+			Some(&[first, second, third, fourth, ..]) => SubtractBlack::CfaGrid([first, second, third, fourth].map(|x| x.min(u16::MAX.into()) as u16)),
+			Some(&[first, ..]) => SubtractBlack::Value(first.min(u16::MAX.into()) as u16),
+			_ => SubtractBlack::None,
+		};
+		(unpack(data, 16, file.endian())?, width, height, ifd.bits_per_sample, black)
+	} else {
+		// Older models store the uncompressed data directly, with its dimensions in the CFA header
+		let (width, height) = dimensions.ok_or(DecoderError::CorruptData)?;
+		if cfa.len() < width * height * 2 {
+			return Err(DecoderError::UnsupportedFeature("compressed or packed RAF"));
+		}
+		(unpack(&cfa, 16, Endian::Big)?, width, height, 14, SubtractBlack::None)
+	};
+
+	let mut raw_image = RawImage {
+		data: image,
+		width,
+		height,
+		// Bayer sensors of Fujifilm cameras start with a red pixel
+		cfa_pattern: [0, 1, 1, 2],
+		x_trans_pattern,
+		maximum: u16::MAX >> (16 - bits_per_sample.min(16)),
+		black,
+		orientation: OrientationValue::Horizontal,
+		camera_model: Some(CameraModel { make: "Fujifilm".to_string(), model }),
+		camera_white_balance: None,
+		white_balance: None,
+		camera_to_rgb: None,
+		xyz_to_camera: None,
+	};
+	raw_image.data.truncate(width * height);
+
+	if let Some(&[green, red, _, blue]) = white_balance.as_deref()
+		&& red > 0
+		&& blue > 0
+	{
+		raw_image.camera_white_balance = Some(raw_image.cfa_white_balance([red as f64, green as f64, blue as f64]));
+	}
+
+	Ok(raw_image)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Builds a RAF file of an older model, which stores big endian 16 bit samples directly after the CFA header.
+	fn raf_file(records: &[(u16, Vec<u8>)], samples: &[u16]) -> Vec<u8> {
+		let mut cfa_header = (records.len() as u32).to_be_bytes().to_vec();
+		for (tag, record) in records {
+			cfa_header.extend(tag.to_be_bytes());
+			cfa_header.extend((record.len() as u16).to_be_bytes());
+			cfa_header.extend(record);
+		}
+		let cfa: Vec<u8> = samples.iter().flat_map(|x| x.to_be_bytes()).collect();
+
+		let mut file = vec![0; HEADER_LENGTH];
+		file[..16].copy_from_slice(MAGIC);
+		file[28..28 + 6].copy_from_slice(b"X-T100");
+		let cfa_header_offset = HEADER_LENGTH as u32;
+		let cfa_offset = cfa_header_offset + cfa_header.len() as u32;
+		for (position, value) in [(92, cfa_header_offset), (96, cfa_header.len() as u32), (100, cfa_offset), (104, cfa.len() as u32)] {
+			file[position..position + 4].copy_from_slice(&value.to_be_bytes());
+		}
+
+		file.extend(cfa_header);
+		file.extend(cfa);
+		file
+	}
+
+	fn u16s(values: &[u16]) -> Vec<u8> {
+		values.iter().flat_map(|x| x.to_be_bytes()).collect()
+	}
+
+	#[test]
+	fn unpacks_uncompressed_data() {
+		let (width, height) = (6, 2);
+		let samples: Vec<u16> = (0..(width * height) as u16).map(|x| x * 1000).collect();
+		let records = [(RECORD_RAW_DIMENSIONS, u16s(&[height as u16, width as u16])), (RECORD_WHITE_BALANCE, u16s(&[300, 600, 300, 450]))];
+
+		let raw_image = decode(&mut Cursor::new(raf_file(&records, &samples))).unwrap();
+		assert_eq!((raw_image.width, raw_image.height), (width, height));
+		assert_eq!(raw_image.data, samples);
+		assert_eq!(raw_image.maximum, (1 << 14) - 1);
+		assert_eq!(raw_image.camera_model.map(|camera| camera.model).as_deref(), Some("X-T100"));
+		// Red, green, green and blue
+		assert_eq!(raw_image.camera_white_balance, Some([600., 300., 300., 450.]));
+	}
+
+	#[test]
+	fn x_trans_pattern_is_stored_backwards() {
+		let pattern: Vec<u8> = (0..36).map(|index| index % 3).collect();
+		let records = [(RECORD_RAW_DIMENSIONS, u16s(&[1, 1])), (RECORD_XTRANS_PATTERN, pattern.clone())];
+
+		let raw_image = decode(&mut Cursor::new(raf_file(&records, &[0]))).unwrap();
+		let x_trans_pattern = raw_image.x_trans_pattern.unwrap();
+		assert_eq!(x_trans_pattern[0][0], pattern[35]);
+		assert_eq!(x_trans_pattern[5][5], pattern[0]);
+		assert_eq!(x_trans_pattern[1][2], pattern[35 - 8]);
+	}
+
+	#[test]
+	fn packed_data_is_unsupported() {
+		// Fewer than 2 bytes per pixel
+		let records = [(RECORD_RAW_DIMENSIONS, u16s(&[2, 2]))];
+		assert!(matches!(decode(&mut Cursor::new(raf_file(&records, &[0; 3]))), Err(DecoderError::UnsupportedFeature(_))));
+
+		// No dimensions at all
+		assert!(matches!(decode(&mut Cursor::new(raf_file(&[], &[0; 4]))), Err(DecoderError::CorruptData)));
+	}
+}
@@ -56,6 +56,7 @@ pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage {
 		width: image_width,
 		height: image_height,
 		cfa_pattern: ifd.cfa_pattern.try_into().unwrap(),
+		x_trans_pattern: None,
 		maximum: if bits_per_sample == 16 { u16::MAX } else { (1 << bits_per_sample) - 1 },
 		black: SubtractBlack::CfaGrid(ifd.black_level),
 		orientation: OrientationValue::Horizontal,
@@ -63,5 +64,6 @@ pub fn decode<R: Read + Seek>(ifd: Ifd, file: &mut TiffRead<R>) -> RawImage {
 		camera_white_balance: ifd.white_balance_levels.map(|arr| arr.map(|x| x as f64)),
 		white_balance: None,
 		camera_to_rgb: None,
+		xyz_to_camera: None,
 	}
 }
@@ -0,0 +1,161 @@
+use super::Band;
+use crate::metadata::camera_data::RGB_TO_XYZ;
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The number of pixels each step of the algorithm reads around a pixel, added up.
+const MARGIN: usize = 6;
+
+/// The horizontal and vertical interpolations of a band.
+const DIRECTIONS: usize = 2;
+
+impl RawImage {
+	/// Demosaics a Bayer image using Adaptive Homogeneity-Directed interpolation, as described by Keigo Hirakawa and Thomas W. Parks.
+	///
+	/// Green is interpolated along rows and along columns, then red and blue are interpolated from their difference to green in both resulting images.
+	/// For each pixel, the image whose neighborhood is the most homogeneous in the CIELAB color space is picked, which avoids interpolating across edges.
+	pub fn ahd_demosaic(&self) -> Vec<u16> {
+		let cielab = CieLab::new(self.camera_to_rgb);
+		self.demosaic_bands(MARGIN, |band, output| ahd_demosaic_band(band, &cielab, output))
+	}
+}
+
+fn ahd_demosaic_band(band: &Band, cielab: &CieLab, output: &mut [u16]) {
+	let Band { width, height, margin, .. } = *band;
+	let cfa = &band.data;
+
+	let mut rgb = [vec![[0_f32; CHANNELS_IN_RGB]; width * height], vec![[0_f32; CHANNELS_IN_RGB]; width * height]];
+	let steps = [1, width];
+
+	// Interpolate green at red and blue pixels along each direction, limited to the range of the adjacent green pixels
+	for row in 2..height - 2 {
+		for column in 2..width - 2 {
+			let index = row * width + column;
+			let color = band.color(row, column);
+
+			for (rgb, step) in rgb.iter_mut().zip(steps) {
+				rgb[index][color] = cfa[index];
+
+				if color != 1 {
+					let (before, after) = (cfa[index - step], cfa[index + step]);
+					let green = ((before + cfa[index] + after) * 2. - cfa[index - 2 * step] - cfa[index + 2 * step]) / 4.;
+					rgb[index][1] = green.clamp(before.min(after), before.max(after));
+				} else {
+					rgb[index][1] = cfa[index];
+				}
+			}
+		}
+	}
+
+	// Interpolate red and blue from their difference to green in each direction, and convert the results to CIELAB
+	let mut lab = [vec![[0_f32; 3]; width * height], vec![[0_f32; 3]; width * height]];
+	for (rgb, lab) in rgb.iter_mut().zip(lab.iter_mut()) {
+		for row in 3..height - 3 {
+			for column in 3..width - 3 {
+				let index = row * width + column;
+				let color = band.color(row, column);
+				let difference = |index: usize, color: usize| rgb[index][color] - rgb[index][1];
+
+				if color == 1 {
+					let horizontal_color = band.color(row, column + 1);
+					let vertical_color = band.color(row + 1, column);
+					let horizontal = rgb[index][1] + (difference(index - 1, horizontal_color) + difference(index + 1, horizontal_color)) / 2.;
+					let vertical = rgb[index][1] + (difference(index - width, vertical_color) + difference(index + width, vertical_color)) / 2.;
+
+					rgb[index][horizontal_color] = horizontal.clamp(0., u16::MAX as f32);
+					rgb[index][vertical_color] = vertical.clamp(0., u16::MAX as f32);
+				} else {
+					let other_color = 2 - color;
+					let diagonals = [index - width - 1, index - width + 1, index + width - 1, index + width + 1];
+					let value = rgb[index][1] + diagonals.into_iter().map(|index| difference(index, other_color)).sum::<f32>() / 4.;
+
+					rgb[index][other_color] = value.clamp(0., u16::MAX as f32);
+				}
+
+				lab[index] = cielab.convert(rgb[index]);
+			}
+		}
+	}
+
+	// Count the neighbors of each pixel whose color is close to its own, with the thresholds set by the direction which is the least homogeneous
+	let mut homogeneity = [vec![0_u8; width * height], vec![0_u8; width * height]];
+	let neighbors = [-1, 1, -(width as isize), width as isize];
+	for row in 4..height - 4 {
+		for column in 4..width - 4 {
+			let index = row * width + column;
+
+			let mut lightness_differences = [[0_f32; 4]; DIRECTIONS];
+			let mut chroma_differences = [[0_f32; 4]; DIRECTIONS];
+			for direction in 0..DIRECTIONS {
+				let [lightness, a, b] = lab[direction][index];
+				for (neighbor, offset) in neighbors.into_iter().enumerate() {
+					let [neighbor_lightness, neighbor_a, neighbor_b] = lab[direction][index.wrapping_add_signed(offset)];
+					lightness_differences[direction][neighbor] = (lightness - neighbor_lightness).abs();
+					chroma_differences[direction][neighbor] = (a - neighbor_a).powi(2) + (b - neighbor_b).powi(2);
+				}
+			}
+
+			// Horizontal interpolation is compared with its horizontal neighbors and vertical interpolation with its vertical neighbors
+			let epsilon = |differences: &[[f32; 4]; DIRECTIONS]| differences[0][0].max(differences[0][1]).min(differences[1][2].max(differences[1][3]));
+			let lightness_epsilon = epsilon(&lightness_differences);
+			let chroma_epsilon = epsilon(&chroma_differences);
+
+			for direction in 0..DIRECTIONS {
+				homogeneity[direction][index] = (0..4)
+					.filter(|&neighbor| lightness_differences[direction][neighbor] <= lightness_epsilon && chroma_differences[direction][neighbor] <= chroma_epsilon)
+					.count() as u8;
+			}
+		}
+	}
+
+	// Pick the direction which is the most homogeneous in the 3x3 neighborhood of each pixel, or average both when they're equally homogeneous
+	for (row, output) in output.chunks_exact_mut((width - 2 * margin) * CHANNELS_IN_RGB).enumerate() {
+		let row = row + margin;
+
+		for (column, output) in output.chunks_exact_mut(CHANNELS_IN_RGB).enumerate() {
+			let column = column + margin;
+			let index = row * width + column;
+
+			let [horizontal, vertical] = homogeneity.each_ref().map(|homogeneity| {
+				(row - 1..=row + 1)
+					.flat_map(|row| homogeneity[row * width + column - 1..=row * width + column + 1].iter())
+					.map(|&x| x as u32)
+					.sum::<u32>()
+			});
+
+			let values: [f32; CHANNELS_IN_RGB] = match horizontal.cmp(&vertical) {
+				std::cmp::Ordering::Greater => rgb[0][index],
+				std::cmp::Ordering::Less => rgb[1][index],
+				std::cmp::Ordering::Equal => std::array::from_fn(|channel| (rgb[0][index][channel] + rgb[1][index][channel]) / 2.),
+			};
+
+			output.copy_from_slice(&values.map(|x| x.round().clamp(0., u16::MAX as f32) as u16));
+		}
+	}
+}
+
+/// Converts camera RGB values to the CIELAB color space, which is used to measure how different the colors of nearby pixels look.
+struct CieLab {
+	/// Maps camera RGB values (scaled to the 16 bit range) to XYZ values relative to the D65 white point.
+	camera_to_xyz: [[f32; 3]; 3],
+}
+
+impl CieLab {
+	fn new(camera_to_rgb: Option<[[f64; 3]; 3]>) -> Self {
+		// Without a color matrix, the camera's color space is treated as sRGB
+		let camera_to_rgb = camera_to_rgb.unwrap_or([[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]);
+
+		let camera_to_xyz = std::array::from_fn(|i| {
+			let white: f64 = RGB_TO_XYZ[i].iter().sum();
+			std::array::from_fn(|j| ((0..3).map(|k| RGB_TO_XYZ[i][k] * camera_to_rgb[k][j]).sum::<f64>() / white / u16::MAX as f64) as f32)
+		});
+
+		Self { camera_to_xyz }
+	}
+
+	fn convert(&self, rgb: [f32; CHANNELS_IN_RGB]) -> [f32; 3] {
+		let f = |t: f32| if t > 0.008856 { t.cbrt() } else { 7.787 * t + 16. / 116. };
+		let [x, y, z] = self.camera_to_xyz.map(|row| f(row.iter().zip(rgb).map(|(&coefficient, value)| coefficient * value).sum()));
+
+		[116. * y - 16., 500. * (x - y), 200. * (y - z)]
+	}
+}
@@ -1,81 +1,48 @@
 use crate::{Pixel, RawImage};
 
-fn average(data: &[u16], indexes: impl Iterator<Item = i64>) -> u16 {
-	let mut sum = 0;
-	let mut count = 0;
-	for index in indexes {
-		if index >= 0 && (index as usize) < data.len() {
-			sum += data[index as usize] as u32;
-			count += 1;
-		}
-	}
-
-	(sum / count) as u16
-}
-
 impl RawImage {
+	/// Interpolates each missing color of a pixel as the average of the surrounding pixels of that color.
+	///
+	/// Works with any CFA pattern, including X-Trans, as the search widens from the 8 adjacent pixels to the 5x5 block around the pixel when none of them has the color.
 	pub fn linear_demosaic_iter(&self) -> impl Iterator<Item = Pixel> + use<'_> {
-		match self.cfa_pattern {
-			[0, 1, 1, 2] => self.linear_demosaic_rggb_iter(),
-			_ => todo!(),
-		}
-	}
-
-	fn linear_demosaic_rggb_iter(&self) -> impl Iterator<Item = Pixel> + use<'_> {
-		let width = self.width as i64;
-		let height = self.height as i64;
+		let colors = self.cfa_colors();
 
-		(0..height).flat_map(move |row| {
-			let row_by_width = row * width;
+		(0..self.height).flat_map(move |row| {
+			(0..self.width).map(move |column| {
+				let own_color = colors[row % 6][column % 6] as usize;
 
-			(0..width).map(move |column| {
-				let pixel_index = row_by_width + column;
+				let values = std::array::from_fn(|color| {
+					if color == own_color {
+						self.data[row * self.width + column]
+					} else {
+						self.average_of_color(&colors, row, column, color as u8)
+					}
+				});
 
-				let vertical_indexes = [pixel_index + width, pixel_index - width];
-				let horizontal_indexes = [pixel_index + 1, pixel_index - 1];
-				let cross_indexes = [pixel_index + width, pixel_index - width, pixel_index + 1, pixel_index - 1];
-				let diagonal_indexes = [pixel_index + width + 1, pixel_index - width + 1, pixel_index + width - 1, pixel_index - width - 1];
-
-				let pixel_index = pixel_index as usize;
-				match (row % 2 == 0, column % 2 == 0) {
-					(true, true) => Pixel {
-						values: [
-							self.data[pixel_index],
-							average(&self.data, cross_indexes.into_iter()),
-							average(&self.data, diagonal_indexes.into_iter()),
-						],
-						row: row as usize,
-						column: column as usize,
-					},
-					(true, false) => Pixel {
-						values: [
-							average(&self.data, horizontal_indexes.into_iter()),
-							self.data[pixel_index],
-							average(&self.data, vertical_indexes.into_iter()),
-						],
-						row: row as usize,
-						column: column as usize,
-					},
-					(false, true) => Pixel {
-						values: [
-							average(&self.data, vertical_indexes.into_iter()),
-							self.data[pixel_index],
-							average(&self.data, horizontal_indexes.into_iter()),
-						],
-						row: row as usize,
-						column: column as usize,
-					},
-					(false, false) => Pixel {
-						values: [
-							average(&self.data, diagonal_indexes.into_iter()),
-							average(&self.data, cross_indexes.into_iter()),
-							self.data[pixel_index],
-						],
-						row: row as usize,
-						column: column as usize,
-					},
-				}
+				Pixel { values, row, column }
 			})
 		})
 	}
+
+	fn average_of_color(&self, colors: &[[u8; 6]; 6], row: usize, column: usize, color: u8) -> u16 {
+		for radius in 1..=2 {
+			let mut sum = 0;
+			let mut count = 0;
+
+			for y in row.saturating_sub(radius)..(row + radius + 1).min(self.height) {
+				for x in column.saturating_sub(radius)..(column + radius + 1).min(self.width) {
+					if colors[y % 6][x % 6] == color {
+						sum += self.data[y * self.width + x] as u32;
+						count += 1;
+					}
+				}
+			}
+
+			if let Some(average) = sum.checked_div(count) {
+				return average as u16;
+			}
+		}
+
+		0
+	}
 }
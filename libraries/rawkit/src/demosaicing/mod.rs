@@ -1 +1,139 @@
+pub mod ahd_demosaicing;
 pub mod linear_demosaicing;
+pub mod rcd_demosaicing;
+
+use crate::{CHANNELS_IN_RGB, Image, RawImage};
+
+/// The number of image rows the directional algorithms process at once, which bounds the memory used for their intermediate buffers.
+const BAND_ROWS: usize = 64;
+
+/// The algorithm used to interpolate the two color channels each pixel of the raw image is missing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DemosaicAlgorithm {
+	/// Averages the nearest pixels of each color.
+	///
+	/// It's the fastest algorithm but leaves zipper artifacts along edges and moiré in fine detail.
+	Linear,
+
+	/// Adaptive Homogeneity-Directed demosaicing, which interpolates every pixel both horizontally and vertically and keeps the result that's most homogeneous in the CIELAB color space.
+	#[default]
+	Ahd,
+
+	/// Ratio Corrected Demosaicing, which blends the horizontal and vertical interpolations by their local gradients and interpolates using color ratios.
+	///
+	/// It's slightly softer than AHD but avoids its artifacts along round edges and in noisy images.
+	Rcd,
+}
+
+impl RawImage {
+	/// Interpolates the missing color channels of every pixel with `algorithm`.
+	///
+	/// AHD and RCD are only defined for Bayer patterns, so X-Trans images fall back to [`DemosaicAlgorithm::Linear`].
+	pub fn demosaic(&self, algorithm: DemosaicAlgorithm) -> Image<u16> {
+		let data = match algorithm {
+			DemosaicAlgorithm::Ahd if self.is_bayer() => self.ahd_demosaic(),
+			DemosaicAlgorithm::Rcd if self.is_bayer() => self.rcd_demosaic(),
+			_ => self.linear_demosaic_iter().flat_map(|pixel| pixel.values).collect(),
+		};
+
+		Image {
+			data,
+			width: self.width,
+			height: self.height,
+			channels: CHANNELS_IN_RGB as u8,
+			orientation: self.orientation,
+		}
+	}
+
+	/// The color of each pixel in a 6x6 grid which repeats across the image, covering both the 2x2 Bayer and 6x6 X-Trans patterns.
+	pub fn cfa_colors(&self) -> [[u8; 6]; 6] {
+		self.x_trans_pattern
+			.unwrap_or_else(|| std::array::from_fn(|row| std::array::from_fn(|column| self.cfa_pattern[2 * (row % 2) + column % 2])))
+	}
+
+	/// For each pixel in a 6x6 grid which repeats across the image, the index into the 2x2 CFA grid holding its black level and white balance.
+	pub(crate) fn cfa_grid_indexes(&self) -> [[usize; 6]; 6] {
+		let colors = self.cfa_colors();
+
+		std::array::from_fn(|row| {
+			std::array::from_fn(|column| match self.x_trans_pattern {
+				Some(_) => self.cfa_pattern.iter().position(|&color| color == colors[row][column]).unwrap_or_default(),
+				None => 2 * (row % 2) + column % 2,
+			})
+		})
+	}
+
+	/// Whether the CFA pattern is one of the 4 phases of the Bayer pattern, which has two green pixels on one diagonal and red and blue on the other.
+	fn is_bayer(&self) -> bool {
+		let mut colors = self.cfa_pattern;
+		colors.sort_unstable();
+
+		self.x_trans_pattern.is_none() && colors == [0, 1, 1, 2] && (self.cfa_pattern[0] == self.cfa_pattern[3] || self.cfa_pattern[1] == self.cfa_pattern[2]) && self.width > 1 && self.height > 1
+	}
+
+	/// Demosaics a Bayer image band by band, where `demosaic_band` fills the output rows of each [`Band`] with interleaved RGB values.
+	fn demosaic_bands(&self, margin: usize, mut demosaic_band: impl FnMut(&Band, &mut [u16])) -> Vec<u16> {
+		let row_length = self.width * CHANNELS_IN_RGB;
+		let mut output = vec![0; row_length * self.height];
+
+		for (index, rows) in output.chunks_mut(row_length * BAND_ROWS).enumerate() {
+			let band = Band::new(self, index * BAND_ROWS, rows.len() / row_length, margin);
+			demosaic_band(&band, rows);
+		}
+
+		output
+	}
+}
+
+/// A horizontal band of a Bayer image, extended by `margin` pixels on every side.
+///
+/// Pixels beyond the edges of the image are mirrored from inside it in a way that keeps the Bayer pattern intact, so the algorithms can look around any pixel without bounds checks.
+pub(crate) struct Band {
+	/// Width of the band, including the margin on both sides.
+	pub width: usize,
+
+	/// Height of the band, including the margin on both sides.
+	pub height: usize,
+
+	/// The number of pixels added to every side of the image rows covered by the band.
+	pub margin: usize,
+
+	/// Raw pixel values stored row by row.
+	pub data: Vec<f32>,
+
+	/// The color of the pixels in the 2x2 grid starting at the top left corner of the band.
+	colors: [usize; 4],
+}
+
+impl Band {
+	fn new(raw_image: &RawImage, top: usize, rows: usize, margin: usize) -> Self {
+		let width = raw_image.width + 2 * margin;
+		let height = rows + 2 * margin;
+
+		let mut data = Vec::with_capacity(width * height);
+		for row in 0..height {
+			let image_row = mirror((top + row) as isize - margin as isize, raw_image.height);
+			data.extend((0..width).map(|column| raw_image.data[image_row * raw_image.width + mirror(column as isize - margin as isize, raw_image.width)] as f32));
+		}
+
+		Self {
+			width,
+			height,
+			margin,
+			data,
+			colors: std::array::from_fn(|index| raw_image.cfa_pattern[2 * ((index / 2 + top + margin) % 2) + (index % 2 + margin) % 2] as usize),
+		}
+	}
+
+	pub fn color(&self, row: usize, column: usize) -> usize {
+		self.colors[2 * (row % 2) + column % 2]
+	}
+}
+
+/// Reflects `position` into `0..length` by mirroring around the first and last pixel, which keeps the parity of the position unchanged.
+fn mirror(position: isize, length: usize) -> usize {
+	let period = 2 * (length as isize - 1);
+	let position = position.rem_euclid(period);
+
+	(if position < length as isize { position } else { period - position }) as usize
+}
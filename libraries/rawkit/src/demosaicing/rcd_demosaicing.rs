@@ -0,0 +1,198 @@
+use super::Band;
+use crate::{CHANNELS_IN_RGB, RawImage};
+
+/// The number of pixels each step of the algorithm reads around a pixel, added up.
+const MARGIN: usize = 10;
+
+/// Keeps the ratios and weights of the algorithm finite in flat, black areas.
+const EPSILON: f32 = 1e-5;
+const EPSILON_SQUARED: f32 = 1e-10;
+
+impl RawImage {
+	/// Demosaics a Bayer image using Ratio Corrected Demosaicing, as designed by Luis Sanz Rodríguez.
+	///
+	/// Green is interpolated from the color ratios of its cardinal neighbors, blending the vertical and horizontal estimates by the strength of the edges along each direction.
+	/// Red and blue are then interpolated from their difference to green, first at the opposite color along the diagonals and then at green pixels along rows and columns.
+	pub fn rcd_demosaic(&self) -> Vec<u16> {
+		self.demosaic_bands(MARGIN, rcd_demosaic_band)
+	}
+}
+
+/// Picks the local discrimination between two directions of a pixel, or the average of its 4 diagonal neighbors when theirs is more decisive.
+fn refined_discrimination(discrimination: &[f32], index: usize, width: usize) -> f32 {
+	let central = discrimination[index];
+	let neighborhood = (discrimination[index - width - 1] + discrimination[index - width + 1] + discrimination[index + width - 1] + discrimination[index + width + 1]) / 4.;
+
+	if (0.5 - central).abs() < (0.5 - neighborhood).abs() { neighborhood } else { central }
+}
+
+/// Interpolates between `b` (when `a` is 1) and `c` (when `a` is 0).
+fn interpolate(a: f32, b: f32, c: f32) -> f32 {
+	a * (b - c) + c
+}
+
+fn rcd_demosaic_band(band: &Band, output: &mut [u16]) {
+	let Band { width, height, margin, .. } = *band;
+	let cfa: Vec<f32> = band.data.iter().map(|&x| x / u16::MAX as f32).collect();
+	let [w1, w2, w3, w4] = [width, 2 * width, 3 * width, 4 * width];
+	let is_green = |index: usize| band.color(index / width, index % width) == 1;
+
+	let mut rgb = [vec![0_f32; width * height], vec![0_f32; width * height], vec![0_f32; width * height]];
+	for (index, &value) in cfa.iter().enumerate() {
+		rgb[band.color(index / width, index % width)][index] = value;
+	}
+
+	// Step 1: Find the vertical and horizontal interpolation directions from a squared high pass filter along each
+	let high_pass = |index: usize, step: usize| {
+		((cfa[index - 3 * step] - cfa[index - step] - cfa[index + step] + cfa[index + 3 * step]) - 3. * (cfa[index - 2 * step] + cfa[index + 2 * step]) + 6. * cfa[index]).powi(2)
+	};
+	let mut vertical_high_pass = vec![0_f32; width * height];
+	let mut horizontal_high_pass = vec![0_f32; width * height];
+	for row in 3..height - 3 {
+		for index in row * width + 3..row * width + width - 3 {
+			vertical_high_pass[index] = high_pass(index, w1);
+			horizontal_high_pass[index] = high_pass(index, 1);
+		}
+	}
+
+	let mut vertical_horizontal_direction = vec![0_f32; width * height];
+	for row in 4..height - 4 {
+		for index in row * width + 4..row * width + width - 4 {
+			let vertical = (vertical_high_pass[index - w1] + vertical_high_pass[index] + vertical_high_pass[index + w1]).max(EPSILON_SQUARED);
+			let horizontal = (horizontal_high_pass[index - 1] + horizontal_high_pass[index] + horizontal_high_pass[index + 1]).max(EPSILON_SQUARED);
+			vertical_horizontal_direction[index] = vertical / (vertical + horizontal);
+		}
+	}
+
+	// Step 2: Low pass filter the red and blue pixels together with the surrounding green, red and blue pixels
+	let mut low_pass = vec![0_f32; width * height];
+	for row in 1..height - 1 {
+		for index in (row * width + 1..row * width + width - 1).filter(|&index| !is_green(index)) {
+			low_pass[index] = cfa[index]
+				+ 0.5 * (cfa[index - w1] + cfa[index + w1] + cfa[index - 1] + cfa[index + 1])
+				+ 0.25 * (cfa[index - w1 - 1] + cfa[index - w1 + 1] + cfa[index + w1 - 1] + cfa[index + w1 + 1]);
+		}
+	}
+
+	// Step 3: Populate the green channel at red and blue pixels
+	for row in 5..height - 5 {
+		for index in (row * width + 5..row * width + width - 5).filter(|&index| !is_green(index)) {
+			let value = cfa[index];
+
+			// Cardinal gradients
+			let north_gradient =
+				EPSILON + (cfa[index - w1] - cfa[index + w1]).abs() + (value - cfa[index - w2]).abs() + (cfa[index - w1] - cfa[index - w3]).abs() + (cfa[index - w2] - cfa[index - w4]).abs();
+			let south_gradient =
+				EPSILON + (cfa[index + w1] - cfa[index - w1]).abs() + (value - cfa[index + w2]).abs() + (cfa[index + w1] - cfa[index + w3]).abs() + (cfa[index + w2] - cfa[index + w4]).abs();
+			let west_gradient = EPSILON + (cfa[index - 1] - cfa[index + 1]).abs() + (value - cfa[index - 2]).abs() + (cfa[index - 1] - cfa[index - 3]).abs() + (cfa[index - 2] - cfa[index - 4]).abs();
+			let east_gradient = EPSILON + (cfa[index + 1] - cfa[index - 1]).abs() + (value - cfa[index + 2]).abs() + (cfa[index + 1] - cfa[index + 3]).abs() + (cfa[index + 2] - cfa[index + 4]).abs();
+
+			// Cardinal pixel estimations, scaling each green neighbor by the ratio of the low pass filtered color between this pixel and the next one of its color
+			let low_pass_value = low_pass[index];
+			let estimate = |green: f32, other: usize| green * 2. * low_pass_value / (EPSILON + low_pass_value + low_pass[other]);
+			let north_estimate = estimate(cfa[index - w1], index - w2);
+			let south_estimate = estimate(cfa[index + w1], index + w2);
+			let west_estimate = estimate(cfa[index - 1], index - 2);
+			let east_estimate = estimate(cfa[index + 1], index + 2);
+
+			// Vertical and horizontal estimations, weighted towards the side with the smaller gradient
+			let vertical_estimate = (south_gradient * north_estimate + north_gradient * south_estimate) / (north_gradient + south_gradient);
+			let horizontal_estimate = (west_gradient * east_estimate + east_gradient * west_estimate) / (east_gradient + west_gradient);
+
+			let discrimination = refined_discrimination(&vertical_horizontal_direction, index, width);
+			rgb[1][index] = interpolate(discrimination, horizontal_estimate, vertical_estimate).clamp(0., 1.);
+		}
+	}
+
+	// Step 4.1: Find the diagonal interpolation directions from a squared high pass filter along the P (north west to south east) and Q (north east to south west) diagonals
+	let mut p_high_pass = vec![0_f32; width * height];
+	let mut q_high_pass = vec![0_f32; width * height];
+	for row in 3..height - 3 {
+		for index in row * width + 3..row * width + width - 3 {
+			p_high_pass[index] = high_pass(index, w1 + 1);
+			q_high_pass[index] = high_pass(index, w1 - 1);
+		}
+	}
+
+	let mut diagonal_direction = vec![0_f32; width * height];
+	for row in 4..height - 4 {
+		for index in (row * width + 4..row * width + width - 4).filter(|&index| !is_green(index)) {
+			let [p, q] = [w1 + 1, w1 - 1];
+			let p_statistic = (p_high_pass[index - p] + p_high_pass[index] + p_high_pass[index + p]).max(EPSILON_SQUARED);
+			let q_statistic = (q_high_pass[index - q] + q_high_pass[index] + q_high_pass[index + q]).max(EPSILON_SQUARED);
+			diagonal_direction[index] = p_statistic / (p_statistic + q_statistic);
+		}
+	}
+
+	// Step 4.2: Populate the red channel at blue pixels and the blue channel at red pixels
+	for row in 7..height - 7 {
+		for index in (row * width + 7..row * width + width - 7).filter(|&index| !is_green(index)) {
+			let color = 2 - band.color(index / width, index % width);
+			let (channel, green) = (&rgb[color], &rgb[1]);
+
+			// Diagonal gradients
+			let north_west_gradient =
+				EPSILON + (channel[index - w1 - 1] - channel[index + w1 + 1]).abs() + (channel[index - w1 - 1] - channel[index - w3 - 3]).abs() + (green[index] - green[index - w2 - 2]).abs();
+			let north_east_gradient =
+				EPSILON + (channel[index - w1 + 1] - channel[index + w1 - 1]).abs() + (channel[index - w1 + 1] - channel[index - w3 + 3]).abs() + (green[index] - green[index - w2 + 2]).abs();
+			let south_west_gradient =
+				EPSILON + (channel[index - w1 + 1] - channel[index + w1 - 1]).abs() + (channel[index + w1 - 1] - channel[index + w3 - 3]).abs() + (green[index] - green[index + w2 - 2]).abs();
+			let south_east_gradient =
+				EPSILON + (channel[index - w1 - 1] - channel[index + w1 + 1]).abs() + (channel[index + w1 + 1] - channel[index + w3 + 3]).abs() + (green[index] - green[index + w2 + 2]).abs();
+
+			// Diagonal color differences
+			let difference = |index: usize| channel[index] - green[index];
+			let north_west_estimate = difference(index - w1 - 1);
+			let north_east_estimate = difference(index - w1 + 1);
+			let south_west_estimate = difference(index + w1 - 1);
+			let south_east_estimate = difference(index + w1 + 1);
+
+			let p_estimate = (north_west_gradient * south_east_estimate + south_east_gradient * north_west_estimate) / (north_west_gradient + south_east_gradient);
+			let q_estimate = (north_east_gradient * south_west_estimate + south_west_gradient * north_east_estimate) / (north_east_gradient + south_west_gradient);
+
+			let discrimination = refined_discrimination(&diagonal_direction, index, width);
+			let value = green[index] + interpolate(discrimination, q_estimate, p_estimate);
+			rgb[color][index] = value.clamp(0., 1.);
+		}
+	}
+
+	// Step 4.3: Populate the red and blue channels at green pixels
+	for row in margin..height - margin {
+		for index in (row * width + margin..row * width + width - margin).filter(|&index| is_green(index)) {
+			let discrimination = refined_discrimination(&vertical_horizontal_direction, index, width);
+			let green = &rgb[1];
+			let green_value = green[index];
+
+			let [red, blue] = [&rgb[0], &rgb[2]].map(|channel| {
+				// Cardinal gradients
+				let north_gradient = EPSILON + (green_value - green[index - w2]).abs() + (channel[index - w1] - channel[index + w1]).abs() + (channel[index - w1] - channel[index - w3]).abs();
+				let south_gradient = EPSILON + (green_value - green[index + w2]).abs() + (channel[index + w1] - channel[index - w1]).abs() + (channel[index + w1] - channel[index + w3]).abs();
+				let west_gradient = EPSILON + (green_value - green[index - 2]).abs() + (channel[index - 1] - channel[index + 1]).abs() + (channel[index - 1] - channel[index - 3]).abs();
+				let east_gradient = EPSILON + (green_value - green[index + 2]).abs() + (channel[index + 1] - channel[index - 1]).abs() + (channel[index + 1] - channel[index + 3]).abs();
+
+				// Cardinal color differences
+				let difference = |index: usize| channel[index] - green[index];
+				let north_estimate = difference(index - w1);
+				let south_estimate = difference(index + w1);
+				let west_estimate = difference(index - 1);
+				let east_estimate = difference(index + 1);
+
+				let vertical_estimate = (north_gradient * south_estimate + south_gradient * north_estimate) / (north_gradient + south_gradient);
+				let horizontal_estimate = (east_gradient * west_estimate + west_gradient * east_estimate) / (east_gradient + west_gradient);
+
+				(green_value + interpolate(discrimination, horizontal_estimate, vertical_estimate)).clamp(0., 1.)
+			});
+
+			rgb[0][index] = red;
+			rgb[2][index] = blue;
+		}
+	}
+
+	for (row, output) in output.chunks_exact_mut((width - 2 * margin) * CHANNELS_IN_RGB).enumerate() {
+		let start = (row + margin) * width + margin;
+
+		for (index, output) in (start..).zip(output.chunks_exact_mut(CHANNELS_IN_RGB)) {
+			output.copy_from_slice(&rgb.each_ref().map(|channel| (channel[index] * u16::MAX as f32).round() as u16));
+		}
+	}
+}
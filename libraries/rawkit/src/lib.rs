@@ -7,12 +7,13 @@ pub mod processing;
 pub mod tiff;
 
 use crate::metadata::identify::CameraModel;
+use demosaicing::DemosaicAlgorithm;
 use processing::{Pixel, PixelTransform, RawPixel, RawPixelTransform};
 use rawkit_proc_macros::Tag;
 use std::io::{Read, Seek};
 use thiserror::Error;
 use tiff::file::TiffRead;
-use tiff::tags::{Compression, ImageLength, ImageWidth, Orientation, StripByteCounts, SubIfd, Tag, ThumbnailLength, ThumbnailOffset};
+use tiff::tags::{Compression, DngVersion, ImageLength, ImageWidth, Orientation, StripByteCounts, SubIfd, Tag, ThumbnailLength, ThumbnailOffset};
 use tiff::values::{CompressionValue, OrientationValue};
 use tiff::{Ifd, TiffError};
 
@@ -42,6 +43,17 @@ pub enum SubtractBlack {
 	CfaGrid([u16; 4]),
 }
 
+impl SubtractBlack {
+	/// The black level of each pixel in the Bayer CFA Grid.
+	pub fn cfa_grid(&self) -> [u16; 4] {
+		match *self {
+			SubtractBlack::None => [0; 4],
+			SubtractBlack::Value(x) => [x; 4],
+			SubtractBlack::CfaGrid(x) => x,
+		}
+	}
+}
+
 /// Represents a Raw Image along with its metadata.
 pub struct RawImage {
 	/// Raw pixel data stored in linear fashion.
@@ -55,9 +67,14 @@ pub struct RawImage {
 
 	/// Bayer CFA pattern used to arrange pixels in [`RawImage::data`].
 	///
-	/// It encodes Red, Blue and Green as 0, 1, and 2 respectively.
+	/// It encodes Red, Green and Blue as 0, 1, and 2 respectively.
 	pub cfa_pattern: [u8; 4],
 
+	/// The 6x6 CFA pattern of Fujifilm's X-Trans sensors, which takes the place of [`RawImage::cfa_pattern`] when demosaicing.
+	///
+	/// It uses the same encoding as [`RawImage::cfa_pattern`], which is then `[0, 1, 1, 2]` and only maps the 4 values of the black level and white balance onto the colors.
+	pub x_trans_pattern: Option<[[u8; 6]; 6]>,
+
 	/// Transformation to be applied to negate the orientation of camera.
 	pub orientation: OrientationValue,
 
@@ -87,6 +104,11 @@ pub struct RawImage {
 
 	/// Color space conversion matrix to convert from camera's color space to sRGB.
 	pub camera_to_rgb: Option<[[f64; 3]; 3]>,
+
+	/// Color space conversion matrix to convert from XYZ to the camera's color space, stored row by row.
+	///
+	/// It is only specified by raw files which carry their own color calibration (like DNG files), otherwise it's looked up in the camera data.
+	pub xyz_to_camera: Option<[f64; 9]>,
 }
 
 /// Represents the final RGB Image.
@@ -125,33 +147,63 @@ impl RawImage {
 	/// Create a [`RawImage`] from an input stream.
 	///
 	/// Decodes the contents of `reader` and extracts raw pixel data and metadata.
+	/// Supports DNG files as well as Sony ARW, Canon CR2, Nikon NEF and Fujifilm RAF files.
 	pub fn decode<R: Read + Seek>(reader: &mut R) -> Result<RawImage, DecoderError> {
+		let mut magic = [0; 16];
+		reader.read_exact(&mut magic)?;
+		reader.rewind()?;
+
+		let mut raw_image = if magic == *decoder::raf::MAGIC {
+			let mut raw_image = decoder::raf::decode(reader)?;
+			raw_image.apply_camera_levels();
+			raw_image
+		} else if magic[4..12] == *b"ftypcrx " {
+			return Err(DecoderError::UnsupportedFeature("Canon CR3"));
+		} else {
+			Self::decode_tiff(reader)?
+		};
+
+		raw_image.calculate_conversion_matrices();
+
+		Ok(raw_image)
+	}
+
+	fn decode_tiff<R: Read + Seek>(reader: &mut R) -> Result<RawImage, DecoderError> {
 		let mut file = TiffRead::new(reader)?;
 		let ifd = Ifd::new_first_ifd(&mut file)?;
 
-		let camera_model = metadata::identify::identify_camera_model(&ifd, &mut file).unwrap();
-		let orientation = ifd.get_value::<Orientation, _>(&mut file)?;
-
-		let mut raw_image = if camera_model.model == "DSLR-A100" {
-			decoder::arw1::decode_a100(ifd, &mut file)
-		} else {
-			let sub_ifd = ifd.get_value::<SubIfd, _>(&mut file)?;
-			let arw_ifd = sub_ifd.get_value::<ArwIfd, _>(&mut file)?;
-
-			if arw_ifd.compression == CompressionValue::Uncompressed {
-				decoder::uncompressed::decode(sub_ifd, &mut file)
-			} else if arw_ifd.strip_byte_counts[0] == arw_ifd.image_width * arw_ifd.image_height {
-				decoder::arw2::decode(sub_ifd, &mut file)
-			} else {
-				// TODO: implement for arw 1.
-				todo!()
+		let camera_model = metadata::identify::identify_camera_model(&ifd, &mut file);
+		let orientation = ifd.get_value::<Option<Orientation>, _>(&mut file)?.unwrap_or(OrientationValue::Horizontal);
+		let is_dng = ifd.get_value::<Option<DngVersion>, _>(&mut file)?.is_some();
+
+		let mut raw_image = match camera_model.as_ref().map(|camera_model| camera_model.make.as_str()) {
+			_ if is_dng => decoder::dng::decode(ifd, &mut file)?,
+			Some("Canon") => decoder::cr2::decode(ifd, &mut file)?,
+			Some("Nikon") => decoder::nef::decode(ifd, &mut file)?,
+			Some("Sony") if camera_model.as_ref().is_some_and(|camera_model| camera_model.model == "DSLR-A100") => decoder::arw1::decode_a100(ifd, &mut file),
+			Some("Sony") => {
+				let sub_ifd = ifd.get_value::<SubIfd, _>(&mut file)?;
+				let arw_ifd = sub_ifd.get_value::<ArwIfd, _>(&mut file)?;
+
+				if arw_ifd.compression == CompressionValue::Uncompressed {
+					decoder::uncompressed::decode(sub_ifd, &mut file)
+				} else if arw_ifd.strip_byte_counts[0] == arw_ifd.image_width * arw_ifd.image_height {
+					decoder::arw2::decode(sub_ifd, &mut file)
+				} else {
+					// TODO: implement for arw 1.
+					todo!()
+				}
 			}
+			_ => return Err(DecoderError::UnsupportedCamera),
 		};
 
-		raw_image.camera_model = Some(camera_model);
+		raw_image.camera_model = camera_model;
 		raw_image.orientation = orientation;
 
-		raw_image.calculate_conversion_matrices();
+		// DNG files specify their own levels, which take precedence
+		if !is_dng {
+			raw_image.apply_camera_levels();
+		}
 
 		Ok(raw_image)
 	}
@@ -185,7 +237,12 @@ impl RawImage {
 	///
 	/// Applies all the processing steps to finally get RGB pixel data.
 	pub fn process_8bit(self) -> Image<u8> {
-		let image = self.process_16bit();
+		self.process_8bit_with(DemosaicAlgorithm::default())
+	}
+
+	/// Converts the [`RawImage`] to an [`Image`] with 8 bit resolution for each channel, using the given demosaicing algorithm.
+	pub fn process_8bit_with(self, demosaic_algorithm: DemosaicAlgorithm) -> Image<u8> {
+		let image = self.process_16bit_with(demosaic_algorithm);
 
 		Image {
 			channels: image.channels,
@@ -200,6 +257,11 @@ impl RawImage {
 	///
 	/// Applies all the processing steps to finally get RGB pixel data.
 	pub fn process_16bit(self) -> Image<u16> {
+		self.process_16bit_with(DemosaicAlgorithm::default())
+	}
+
+	/// Converts the [`RawImage`] to an [`Image`] with 16 bit resolution for each channel, using the given demosaicing algorithm.
+	pub fn process_16bit_with(self, demosaic_algorithm: DemosaicAlgorithm) -> Image<u16> {
 		let subtract_black = self.subtract_black_fn();
 		let scale_white_balance = self.scale_white_balance_fn();
 		let scale_to_16bit = self.scale_to_16bit_fn();
@@ -207,7 +269,7 @@ impl RawImage {
 
 		let convert_to_rgb = raw_image.convert_to_rgb_fn();
 		let mut record_histogram = raw_image.record_histogram_fn();
-		let image = raw_image.demosaic_and_apply((convert_to_rgb, &mut record_histogram));
+		let image = raw_image.demosaic_and_apply(demosaic_algorithm, (convert_to_rgb, &mut record_histogram));
 
 		let gamma_correction = image.gamma_correction_fn(&record_histogram.histogram);
 		if image.orientation == OrientationValue::Horizontal {
@@ -232,23 +294,8 @@ impl RawImage {
 		self
 	}
 
-	pub fn demosaic_and_apply(self, mut transform: impl PixelTransform) -> Image<u16> {
-		let mut image = vec![0; self.width * self.height * 3];
-		for Pixel { values, row, column } in self.linear_demosaic_iter().map(|mut pixel| {
-			pixel.values = transform.apply(pixel);
-			pixel
-		}) {
-			let pixel_index = row * self.width + column;
-			image[3 * pixel_index..3 * (pixel_index + 1)].copy_from_slice(&values);
-		}
-
-		Image {
-			channels: 3,
-			data: image,
-			width: self.width,
-			height: self.height,
-			orientation: self.orientation,
-		}
+	pub fn demosaic_and_apply(self, algorithm: DemosaicAlgorithm, transform: impl PixelTransform) -> Image<u16> {
+		self.demosaic(algorithm).apply(transform)
 	}
 }
 
@@ -297,4 +344,10 @@ pub enum DecoderError {
 	IoError(#[from] std::io::Error),
 	#[error("The thumbnail format is unsupported")]
 	UnsupportedThumbnailFormat,
+	#[error("The camera that made the raw file is unsupported")]
+	UnsupportedCamera,
+	#[error("The raw file uses an unsupported feature: {0}")]
+	UnsupportedFeature(&'static str),
+	#[error("The compressed raw data is corrupt")]
+	CorruptData,
 }
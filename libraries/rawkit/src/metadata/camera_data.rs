@@ -1,4 +1,4 @@
-use crate::RawImage;
+use crate::{RawImage, SubtractBlack};
 use rawkit_proc_macros::build_camera_data;
 
 pub struct CameraData {
@@ -15,9 +15,9 @@ impl CameraData {
 	};
 }
 
-const CAMERA_DATA: [(&str, CameraData); 40] = build_camera_data!();
+const CAMERA_DATA: [(&str, CameraData); 50] = build_camera_data!();
 
-const RGB_TO_XYZ: [[f64; 3]; 3] = [
+pub(crate) const RGB_TO_XYZ: [[f64; 3]; 3] = [
 	// Matrix:
 	[0.412453, 0.357580, 0.180423],
 	[0.212671, 0.715160, 0.072169],
@@ -25,14 +25,27 @@ const RGB_TO_XYZ: [[f64; 3]; 3] = [
 ];
 
 impl RawImage {
-	pub fn calculate_conversion_matrices(&mut self) {
-		let Some(ref camera_model) = self.camera_model else { return };
+	fn camera_data(&self) -> Option<&'static CameraData> {
+		let camera_model = self.camera_model.as_ref()?;
 		let camera_name_needle = camera_model.make.to_owned() + " " + &camera_model.model;
 
-		let xyz_to_camera = CAMERA_DATA
-			.iter()
-			.find(|(camera_name_haystack, _)| camera_name_needle == *camera_name_haystack)
-			.map(|(_, data)| data.xyz_to_camera.map(|x| (x as f64) / 10_000.));
+		CAMERA_DATA.iter().find(|(camera_name_haystack, _)| camera_name_needle == *camera_name_haystack).map(|(_, data)| data)
+	}
+
+	/// Applies the black and white levels from the camera data, for formats whose metadata doesn't reliably specify them.
+	pub fn apply_camera_levels(&mut self) {
+		let Some(camera_data) = self.camera_data() else { return };
+
+		if camera_data.maximum != 0 {
+			self.maximum = camera_data.maximum;
+		}
+		if camera_data.black != 0 && matches!(self.black, SubtractBlack::None) {
+			self.black = SubtractBlack::Value(camera_data.black);
+		}
+	}
+
+	pub fn calculate_conversion_matrices(&mut self) {
+		let xyz_to_camera = self.xyz_to_camera.or_else(|| self.camera_data().map(|data| data.xyz_to_camera.map(|x| (x as f64) / 10_000.)));
 		let Some(xyz_to_camera) = xyz_to_camera else { return };
 
 		let mut rgb_to_camera = [[0.; 3]; 3];
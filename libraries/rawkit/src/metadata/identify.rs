@@ -42,15 +42,21 @@ pub struct CameraModel {
 }
 
 pub fn identify_camera_model<R: Read + Seek>(ifd: &Ifd, file: &mut TiffRead<R>) -> Option<CameraModel> {
-	let mut ifd = ifd.get_value::<CameraModelIfd, _>(file).unwrap();
+	let mut ifd = ifd.get_value::<CameraModelIfd, _>(file).ok()?;
 
 	ifd.make.make_ascii_lowercase();
 	for company_name in COMPANY_NAMES {
 		let lowercase_company_name = company_name.to_ascii_lowercase();
 		if ifd.make.contains(&lowercase_company_name) {
+			// Some companies (like Canon and Nikon) repeat their name at the start of the model
+			let model = match ifd.model.get(..company_name.len()) {
+				Some(prefix) if prefix.eq_ignore_ascii_case(company_name) => ifd.model[company_name.len()..].trim_start().to_string(),
+				_ => ifd.model,
+			};
+
 			return Some(CameraModel {
 				make: company_name.to_string(),
-				model: ifd.model,
+				model,
 			});
 		}
 	}
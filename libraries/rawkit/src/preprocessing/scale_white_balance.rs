@@ -23,8 +23,9 @@ impl RawImage {
 			[1., 1., 1., 1.]
 		};
 
+		let cfa_grid_indexes = self.cfa_grid_indexes();
 		move |pixel: RawPixel| {
-			let cfa_index = 2 * (pixel.row % 2) + (pixel.column % 2);
+			let cfa_index = cfa_grid_indexes[pixel.row % 6][pixel.column % 6];
 			((pixel.value as f64) * normalized_white_balance[cfa_index]).min(u16::MAX as f64).max(0.) as u16
 		}
 	}
@@ -13,11 +13,17 @@ use thiserror::Error;
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
 #[repr(u16)]
 pub enum TagId {
+	NikonWhiteBalanceRbLevels = 0xc,
+	NikonBlackLevel = 0x3d,
+	NikonCompression = 0x93,
+	NikonLinearizationTable = 0x96,
+	CanonSensorInfo = 0xe0,
+	NewSubfileType = 0xfe,
 	ImageWidth = 0x100,
 	ImageLength = 0x101,
 	BitsPerSample = 0x102,
 	Compression = 0x103,
-	PhotometricInterpretation = 0x104,
+	PhotometricInterpretation = 0x106,
 	Make = 0x10f,
 	Model = 0x110,
 	StripOffsets = 0x111,
@@ -25,16 +31,45 @@ pub enum TagId {
 	SamplesPerPixel = 0x115,
 	RowsPerStrip = 0x116,
 	StripByteCounts = 0x117,
+	TileWidth = 0x142,
+	TileLength = 0x143,
+	TileOffsets = 0x144,
+	TileByteCounts = 0x145,
 	SubIfd = 0x14a,
 	ThumbnailOffset = 0x201,
 	ThumbnailLength = 0x202,
+	CanonColorData = 0x4001,
 	SonyToneCurve = 0x7010,
 	BlackLevel = 0x7310,
 	WhiteBalanceRggbLevels = 0x7313,
 	CfaPatternDim = 0x828d,
 	CfaPattern = 0x828e,
+	ExifIfd = 0x8769,
+	MakerNote = 0x927c,
+	DngVersion = 0xc612,
+	LinearizationTable = 0xc618,
+	DngBlackLevelRepeatDim = 0xc619,
+	DngBlackLevel = 0xc61a,
+	WhiteLevel = 0xc61d,
+	DefaultCropOrigin = 0xc61f,
+	DefaultCropSize = 0xc620,
 	ColorMatrix1 = 0xc621,
 	ColorMatrix2 = 0xc622,
+	AsShotNeutral = 0xc628,
+	Cr2Slices = 0xc640,
+	CalibrationIlluminant1 = 0xc65a,
+	CalibrationIlluminant2 = 0xc65b,
+	ActiveArea = 0xc68d,
+	OpcodeList1 = 0xc740,
+	OpcodeList2 = 0xc741,
+	OpcodeList3 = 0xc74e,
+	RafRawIfd = 0xf000,
+	RafRawWidth = 0xf001,
+	RafRawHeight = 0xf002,
+	RafBitsPerSample = 0xf003,
+	RafStripOffset = 0xf007,
+	RafStripByteCount = 0xf008,
+	RafBlackLevel = 0xf00a,
 
 	#[num_enum(catch_all)]
 	Unknown(u16),
@@ -110,7 +145,7 @@ impl Ifd {
 		})
 	}
 
-	fn _next_ifd<R: Read + Seek>(&self, file: &mut TiffRead<R>) -> Result<Self, TiffError> {
+	pub fn next_ifd<R: Read + Seek>(&self, file: &mut TiffRead<R>) -> Result<Self, TiffError> {
 		Ifd::new_from_offset(file, self.next_ifd_offset.unwrap_or(0))
 	}
 
@@ -1,4 +1,6 @@
-use super::types::{Array, ConstArray, TagType, TypeByte, TypeCompression, TypeIfd, TypeLong, TypeNumber, TypeOrientation, TypeSRational, TypeSShort, TypeShort, TypeSonyToneCurve, TypeString};
+use super::types::{
+	Array, ConstArray, TagType, TypeByte, TypeCompression, TypeDataBlock, TypeIfd, TypeLong, TypeNumber, TypeOrientation, TypeReal, TypeSRational, TypeSShort, TypeShort, TypeSonyToneCurve, TypeString,
+};
 use super::{Ifd, TagId, TiffError, TiffRead};
 use std::io::{Read, Seek};
 
@@ -32,6 +34,42 @@ pub struct CfaPatternDim;
 pub struct CfaPattern;
 pub struct ColorMatrix1;
 pub struct ColorMatrix2;
+pub struct NewSubfileType;
+pub struct TileWidth;
+pub struct TileLength;
+pub struct TileOffsets;
+pub struct TileByteCounts;
+pub struct SubIfdOffsets;
+pub struct ExifIfd;
+pub struct MakerNote;
+pub struct DngVersion;
+pub struct LinearizationTable;
+pub struct DngBlackLevelRepeatDim;
+pub struct DngBlackLevel;
+pub struct WhiteLevel;
+pub struct DefaultCropOrigin;
+pub struct DefaultCropSize;
+pub struct AsShotNeutral;
+pub struct CalibrationIlluminant1;
+pub struct CalibrationIlluminant2;
+pub struct ActiveArea;
+pub struct OpcodeList1;
+pub struct OpcodeList2;
+pub struct OpcodeList3;
+pub struct Cr2Slices;
+pub struct CanonSensorInfo;
+pub struct CanonColorData;
+pub struct NikonWhiteBalanceRbLevels;
+pub struct NikonBlackLevel;
+pub struct NikonCompression;
+pub struct NikonLinearizationTable;
+pub struct RafRawIfd;
+pub struct RafRawWidth;
+pub struct RafRawHeight;
+pub struct RafBitsPerSample;
+pub struct RafStripOffset;
+pub struct RafStripByteCount;
+pub struct RafBlackLevel;
 
 impl SimpleTag for ImageWidth {
 	type Type = TypeNumber;
@@ -194,6 +232,258 @@ impl SimpleTag for WhiteBalanceRggbLevels {
 	const NAME: &'static str = "White Balance Levels (RGGB)";
 }
 
+impl SimpleTag for NewSubfileType {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::NewSubfileType;
+	const NAME: &'static str = "New Subfile Type";
+}
+
+impl SimpleTag for TileWidth {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::TileWidth;
+	const NAME: &'static str = "Tile Width";
+}
+
+impl SimpleTag for TileLength {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::TileLength;
+	const NAME: &'static str = "Tile Length";
+}
+
+impl SimpleTag for TileOffsets {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::TileOffsets;
+	const NAME: &'static str = "Tile Offsets";
+}
+
+impl SimpleTag for TileByteCounts {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::TileByteCounts;
+	const NAME: &'static str = "Tile Byte Counts";
+}
+
+impl SimpleTag for SubIfdOffsets {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::SubIfd;
+	const NAME: &'static str = "SubIFD Offsets";
+}
+
+impl SimpleTag for ExifIfd {
+	type Type = TypeIfd;
+
+	const ID: TagId = TagId::ExifIfd;
+	const NAME: &'static str = "Exif IFD";
+}
+
+impl SimpleTag for MakerNote {
+	type Type = TypeDataBlock;
+
+	const ID: TagId = TagId::MakerNote;
+	const NAME: &'static str = "Maker Note";
+}
+
+impl SimpleTag for DngVersion {
+	type Type = ConstArray<TypeByte, 4>;
+
+	const ID: TagId = TagId::DngVersion;
+	const NAME: &'static str = "DNG Version";
+}
+
+impl SimpleTag for LinearizationTable {
+	type Type = Array<TypeShort>;
+
+	const ID: TagId = TagId::LinearizationTable;
+	const NAME: &'static str = "Linearization Table";
+}
+
+impl SimpleTag for DngBlackLevelRepeatDim {
+	type Type = ConstArray<TypeShort, 2>;
+
+	const ID: TagId = TagId::DngBlackLevelRepeatDim;
+	const NAME: &'static str = "Black Level Repeat Dimension";
+}
+
+impl SimpleTag for DngBlackLevel {
+	type Type = Array<TypeReal>;
+
+	const ID: TagId = TagId::DngBlackLevel;
+	const NAME: &'static str = "Black Level (DNG)";
+}
+
+impl SimpleTag for WhiteLevel {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::WhiteLevel;
+	const NAME: &'static str = "White Level";
+}
+
+impl SimpleTag for DefaultCropOrigin {
+	type Type = ConstArray<TypeReal, 2>;
+
+	const ID: TagId = TagId::DefaultCropOrigin;
+	const NAME: &'static str = "Default Crop Origin";
+}
+
+impl SimpleTag for DefaultCropSize {
+	type Type = ConstArray<TypeReal, 2>;
+
+	const ID: TagId = TagId::DefaultCropSize;
+	const NAME: &'static str = "Default Crop Size";
+}
+
+impl SimpleTag for AsShotNeutral {
+	type Type = Array<TypeReal>;
+
+	const ID: TagId = TagId::AsShotNeutral;
+	const NAME: &'static str = "As Shot Neutral";
+}
+
+impl SimpleTag for CalibrationIlluminant1 {
+	type Type = TypeShort;
+
+	const ID: TagId = TagId::CalibrationIlluminant1;
+	const NAME: &'static str = "Calibration Illuminant 1";
+}
+
+impl SimpleTag for CalibrationIlluminant2 {
+	type Type = TypeShort;
+
+	const ID: TagId = TagId::CalibrationIlluminant2;
+	const NAME: &'static str = "Calibration Illuminant 2";
+}
+
+impl SimpleTag for ActiveArea {
+	type Type = ConstArray<TypeNumber, 4>;
+
+	const ID: TagId = TagId::ActiveArea;
+	const NAME: &'static str = "Active Area";
+}
+
+impl SimpleTag for OpcodeList1 {
+	type Type = TypeDataBlock;
+
+	const ID: TagId = TagId::OpcodeList1;
+	const NAME: &'static str = "Opcode List 1";
+}
+
+impl SimpleTag for OpcodeList2 {
+	type Type = TypeDataBlock;
+
+	const ID: TagId = TagId::OpcodeList2;
+	const NAME: &'static str = "Opcode List 2";
+}
+
+impl SimpleTag for OpcodeList3 {
+	type Type = TypeDataBlock;
+
+	const ID: TagId = TagId::OpcodeList3;
+	const NAME: &'static str = "Opcode List 3";
+}
+
+impl SimpleTag for Cr2Slices {
+	type Type = ConstArray<TypeShort, 3>;
+
+	const ID: TagId = TagId::Cr2Slices;
+	const NAME: &'static str = "CR2 Slices";
+}
+
+impl SimpleTag for CanonSensorInfo {
+	type Type = Array<TypeShort>;
+
+	const ID: TagId = TagId::CanonSensorInfo;
+	const NAME: &'static str = "Canon Sensor Info";
+}
+
+impl SimpleTag for CanonColorData {
+	type Type = Array<TypeShort>;
+
+	const ID: TagId = TagId::CanonColorData;
+	const NAME: &'static str = "Canon Color Data";
+}
+
+impl SimpleTag for NikonWhiteBalanceRbLevels {
+	type Type = Array<TypeReal>;
+
+	const ID: TagId = TagId::NikonWhiteBalanceRbLevels;
+	const NAME: &'static str = "Nikon White Balance Levels (RB)";
+}
+
+impl SimpleTag for NikonBlackLevel {
+	type Type = ConstArray<TypeShort, 4>;
+
+	const ID: TagId = TagId::NikonBlackLevel;
+	const NAME: &'static str = "Nikon Black Level";
+}
+
+impl SimpleTag for NikonCompression {
+	type Type = TypeShort;
+
+	const ID: TagId = TagId::NikonCompression;
+	const NAME: &'static str = "Nikon Compression";
+}
+
+impl SimpleTag for NikonLinearizationTable {
+	type Type = TypeDataBlock;
+
+	const ID: TagId = TagId::NikonLinearizationTable;
+	const NAME: &'static str = "Nikon Linearization Table";
+}
+
+impl SimpleTag for RafRawIfd {
+	type Type = TypeIfd;
+
+	const ID: TagId = TagId::RafRawIfd;
+	const NAME: &'static str = "RAF Raw IFD";
+}
+
+impl SimpleTag for RafRawWidth {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::RafRawWidth;
+	const NAME: &'static str = "RAF Raw Width";
+}
+
+impl SimpleTag for RafRawHeight {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::RafRawHeight;
+	const NAME: &'static str = "RAF Raw Height";
+}
+
+impl SimpleTag for RafBitsPerSample {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::RafBitsPerSample;
+	const NAME: &'static str = "RAF Bits per Sample";
+}
+
+impl SimpleTag for RafStripOffset {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::RafStripOffset;
+	const NAME: &'static str = "RAF Strip Offset";
+}
+
+impl SimpleTag for RafStripByteCount {
+	type Type = TypeNumber;
+
+	const ID: TagId = TagId::RafStripByteCount;
+	const NAME: &'static str = "RAF Strip Byte Count";
+}
+
+impl SimpleTag for RafBlackLevel {
+	type Type = Array<TypeNumber>;
+
+	const ID: TagId = TagId::RafBlackLevel;
+	const NAME: &'static str = "RAF Black Level";
+}
+
 pub trait Tag {
 	type Output;
 
@@ -1,5 +1,5 @@
 use super::file::TiffRead;
-use super::values::{CompressionValue, CurveLookupTable, OrientationValue, Rational};
+use super::values::{CompressionValue, CurveLookupTable, DataBlock, OrientationValue, Rational, ToFloat};
 use super::{Ifd, IfdTagType, TiffError};
 use std::io::{Read, Seek};
 
@@ -18,6 +18,7 @@ pub struct TypeUndefined;
 
 pub struct TypeNumber;
 pub struct TypeSNumber;
+pub struct TypeReal;
 pub struct TypeIfd;
 
 pub trait PrimitiveType {
@@ -256,6 +257,34 @@ impl PrimitiveType for TypeSNumber {
 	}
 }
 
+impl PrimitiveType for TypeReal {
+	type Output = f64;
+
+	fn get_size(the_type: IfdTagType) -> Option<u32> {
+		match the_type {
+			IfdTagType::Byte | IfdTagType::Short | IfdTagType::Long => TypeNumber::get_size(the_type),
+			IfdTagType::SByte | IfdTagType::SShort | IfdTagType::SLong => TypeSNumber::get_size(the_type),
+			IfdTagType::Rational => TypeRational::get_size(the_type),
+			IfdTagType::SRational => TypeSRational::get_size(the_type),
+			IfdTagType::Float => TypeFloat::get_size(the_type),
+			IfdTagType::Double => TypeDouble::get_size(the_type),
+			_ => None,
+		}
+	}
+
+	fn read_primitive<R: Read + Seek>(the_type: IfdTagType, file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
+		Ok(match the_type {
+			IfdTagType::Byte | IfdTagType::Short | IfdTagType::Long => TypeNumber::read_primitive(the_type, file)?.into(),
+			IfdTagType::SByte | IfdTagType::SShort | IfdTagType::SLong => TypeSNumber::read_primitive(the_type, file)?.into(),
+			IfdTagType::Rational => TypeRational::read_primitive(the_type, file)?.to_float(),
+			IfdTagType::SRational => TypeSRational::read_primitive(the_type, file)?.to_float(),
+			IfdTagType::Float => TypeFloat::read_primitive(the_type, file)?.into(),
+			IfdTagType::Double => TypeDouble::read_primitive(the_type, file)?,
+			_ => unreachable!(),
+		})
+	}
+}
+
 impl PrimitiveType for TypeIfd {
 	type Output = Ifd;
 
@@ -351,6 +380,7 @@ impl<T: PrimitiveType, const N: usize> TagType for ConstArray<T, N> {
 }
 
 pub struct TypeCompression;
+pub struct TypeDataBlock;
 pub struct TypeString;
 pub struct TypeSonyToneCurve;
 pub struct TypeOrientation;
@@ -367,6 +397,24 @@ impl TagType for TypeString {
 	}
 }
 
+impl TagType for TypeDataBlock {
+	type Output = DataBlock;
+
+	fn read<R: Read + Seek>(file: &mut TiffRead<R>) -> Result<Self::Output, TiffError> {
+		let the_type = IfdTagType::from(file.read_u16()?);
+		let length = file.read_u32()?;
+
+		if !matches!(the_type, IfdTagType::Byte | IfdTagType::Undefined) {
+			return Err(TiffError::InvalidType);
+		}
+
+		// Blocks of at most 4 bytes are stored inline, where the offset would otherwise be
+		let offset = if length > 4 { file.read_u32()? } else { file.stream_position()?.try_into()? };
+
+		Ok(DataBlock { offset, length })
+	}
+}
+
 impl TagType for TypeSonyToneCurve {
 	type Output = CurveLookupTable;
 
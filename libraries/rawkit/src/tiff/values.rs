@@ -27,6 +27,13 @@ impl<T: ToFloat> ToFloat for Rational<T> {
 	}
 }
 
+/// The location of a tag's value within the file, used for values which are themselves structured data (like maker notes and opcode lists).
+#[derive(Copy, Clone, Debug)]
+pub struct DataBlock {
+	pub offset: u32,
+	pub length: u32,
+}
+
 pub struct CurveLookupTable {
 	table: Vec<u16>,
 }
@@ -0,0 +1,155 @@
+//! Compares the output of each demosaicing algorithm against the full color scenes the raw images were sampled from.
+
+use rawkit::demosaicing::DemosaicAlgorithm;
+use rawkit::tiff::values::OrientationValue;
+use rawkit::{RawImage, SubtractBlack};
+
+const WIDTH: usize = 96;
+const HEIGHT: usize = 72;
+
+const ALGORITHMS: [DemosaicAlgorithm; 3] = [DemosaicAlgorithm::Linear, DemosaicAlgorithm::Ahd, DemosaicAlgorithm::Rcd];
+
+/// The 4 phases of the Bayer pattern: RGGB, BGGR, GRBG and GBRG.
+const BAYER_PATTERNS: [[u8; 4]; 4] = [[0, 1, 1, 2], [2, 1, 1, 0], [1, 0, 2, 1], [1, 2, 0, 1]];
+
+const X_TRANS_PATTERN: [[u8; 6]; 6] = [[1, 1, 0, 1, 1, 2], [1, 1, 2, 1, 1, 0], [2, 0, 1, 0, 2, 1], [1, 1, 2, 1, 1, 0], [1, 1, 0, 1, 1, 2], [0, 2, 1, 2, 0, 1]];
+
+/// Pixels this close to the edges of the image are left out of the comparisons.
+const BORDER: usize = 4;
+
+/// A scene of smooth color gradients crossed by a sharp diagonal edge.
+fn reference_scene() -> Vec<[u16; 3]> {
+	(0..HEIGHT)
+		.flat_map(|row| {
+			(0..WIDTH).map(move |column| {
+				let (x, y) = (column as f64, row as f64);
+				let brightness = if x + 0.6 * y > 70. { 1. } else { 0.35 };
+				let color = [0.5 + 0.3 * (x / 17.).sin(), 0.55 + 0.3 * (y / 13.).cos(), 0.45 + 0.3 * ((x + y) / 23.).sin()];
+
+				color.map(|value| (value * brightness * u16::MAX as f64).round() as u16)
+			})
+		})
+		.collect()
+}
+
+fn flat_scene() -> Vec<[u16; 3]> {
+	vec![[20_000, 30_000, 10_000]; WIDTH * HEIGHT]
+}
+
+/// Samples the color of each pixel of the scene that the CFA pattern lets through.
+fn mosaic(scene: &[[u16; 3]], cfa_pattern: [u8; 4], x_trans_pattern: Option<[[u8; 6]; 6]>) -> RawImage {
+	let mut raw_image = RawImage {
+		data: Vec::new(),
+		width: WIDTH,
+		height: HEIGHT,
+		cfa_pattern,
+		x_trans_pattern,
+		orientation: OrientationValue::Horizontal,
+		maximum: u16::MAX,
+		black: SubtractBlack::None,
+		camera_model: None,
+		camera_white_balance: None,
+		white_balance: None,
+		camera_to_rgb: None,
+		xyz_to_camera: None,
+	};
+
+	let colors = raw_image.cfa_colors();
+	raw_image.data = scene
+		.iter()
+		.enumerate()
+		.map(|(index, pixel)| pixel[colors[(index / WIDTH) % 6][(index % WIDTH) % 6] as usize])
+		.collect();
+	raw_image
+}
+
+/// The largest difference of any channel between the demosaiced image and the scene.
+fn max_error(raw_image: &RawImage, algorithm: DemosaicAlgorithm, scene: &[[u16; 3]]) -> u16 {
+	let image = raw_image.demosaic(algorithm);
+
+	image
+		.data
+		.chunks_exact(3)
+		.zip(scene)
+		.map(|(pixel, reference)| (0..3).map(|channel| pixel[channel].abs_diff(reference[channel])).max().unwrap())
+		.max()
+		.unwrap()
+}
+
+/// The peak signal-to-noise ratio of the demosaiced image compared to the scene, in decibels.
+fn psnr(raw_image: &RawImage, algorithm: DemosaicAlgorithm, scene: &[[u16; 3]]) -> f64 {
+	let image = raw_image.demosaic(algorithm);
+	assert_eq!((image.width, image.height, image.channels), (WIDTH, HEIGHT, 3));
+
+	let mut squared_error = 0.;
+	let mut count = 0;
+	for row in BORDER..HEIGHT - BORDER {
+		for column in BORDER..WIDTH - BORDER {
+			let index = row * WIDTH + column;
+			for (&value, &reference) in image.data[3 * index..3 * index + 3].iter().zip(&scene[index]) {
+				squared_error += (value as f64 - reference as f64).powi(2);
+				count += 1;
+			}
+		}
+	}
+
+	10. * (u16::MAX as f64).powi(2).log10() - 10. * (squared_error / count as f64).log10()
+}
+
+#[test]
+fn flat_color_is_reproduced_for_every_pattern() {
+	let scene = flat_scene();
+
+	for cfa_pattern in BAYER_PATTERNS {
+		let raw_image = mosaic(&scene, cfa_pattern, None);
+		for algorithm in ALGORITHMS {
+			let error = max_error(&raw_image, algorithm, &scene);
+			assert!(error <= 1, "{algorithm:?} is off by {error} for the CFA pattern {cfa_pattern:?}");
+		}
+	}
+
+	let raw_image = mosaic(&scene, [0, 1, 1, 2], Some(X_TRANS_PATTERN));
+	for algorithm in ALGORITHMS {
+		let error = max_error(&raw_image, algorithm, &scene);
+		assert!(error <= 1, "{algorithm:?} is off by {error} for the X-Trans pattern");
+	}
+}
+
+#[test]
+fn bayer_patterns_match_reference_scene() {
+	let scene = reference_scene();
+
+	for cfa_pattern in BAYER_PATTERNS {
+		let raw_image = mosaic(&scene, cfa_pattern, None);
+
+		let linear = psnr(&raw_image, DemosaicAlgorithm::Linear, &scene);
+		let ahd = psnr(&raw_image, DemosaicAlgorithm::Ahd, &scene);
+		let rcd = psnr(&raw_image, DemosaicAlgorithm::Rcd, &scene);
+
+		assert!(linear > 30., "Linear has a PSNR of {linear:.2} dB for the CFA pattern {cfa_pattern:?}");
+		assert!(ahd > linear + 3., "AHD has a PSNR of {ahd:.2} dB against {linear:.2} dB for Linear for the CFA pattern {cfa_pattern:?}");
+		assert!(rcd > linear + 3., "RCD has a PSNR of {rcd:.2} dB against {linear:.2} dB for Linear for the CFA pattern {cfa_pattern:?}");
+	}
+}
+
+#[test]
+fn bayer_phases_give_consistent_results() {
+	let scene = reference_scene();
+
+	for algorithm in ALGORITHMS {
+		let results = BAYER_PATTERNS.map(|cfa_pattern| psnr(&mosaic(&scene, cfa_pattern, None), algorithm, &scene));
+		let (worst, best) = results.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(worst, best), &x| (worst.min(x), best.max(x)));
+		assert!(best - worst < 3., "{algorithm:?} ranges from {worst:.2} dB to {best:.2} dB across the Bayer phases");
+	}
+}
+
+#[test]
+fn x_trans_pattern_matches_reference_scene() {
+	let scene = reference_scene();
+	let raw_image = mosaic(&scene, [0, 1, 1, 2], Some(X_TRANS_PATTERN));
+
+	for algorithm in ALGORITHMS {
+		let psnr = psnr(&raw_image, algorithm, &scene);
+		assert!(psnr > 25., "{algorithm:?} has a PSNR of {psnr:.2} dB for the X-Trans pattern");
+	}
+}
@@ -505,8 +505,12 @@ tagged_value! {
 	CellularDistanceFunction(raster_nodes::adjustments::CellularDistanceFunction),
 	CellularReturnType(raster_nodes::adjustments::CellularReturnType),
 	DomainWarpType(raster_nodes::adjustments::DomainWarpType),
+	EdgeHandling(raster_nodes::adjustments::EdgeHandling),
+	EdgeDetectionOperator(raster_nodes::adjustments::EdgeDetectionOperator),
 	RelativeAbsolute(raster_nodes::adjustments::RelativeAbsolute),
 	SelectiveColorChoice(raster_nodes::adjustments::SelectiveColorChoice),
+	StructuringElement(raster_nodes::adjustments::StructuringElement),
+	ResamplingFilter(raster_nodes::adjustments::ResamplingFilter),
 	GridType(vector::misc::GridType),
 	ArcType(vector::misc::ArcType),
 	RowsOrColumns(vector::misc::RowsOrColumns),
@@ -522,9 +526,11 @@ tagged_value! {
 	StrokeAlign(vector::style::StrokeAlign),
 	PaintOrder(vector::style::PaintOrder),
 	DashPattern(vector::style::DashPattern),
+	WidthProfile(vector::style::WidthProfile),
 	BoxCorners(vector::misc::BoxCorners),
 	GradientType(vector::style::GradientType),
 	GradientSpreadMethod(vector::style::GradientSpreadMethod),
+	GradientMeshEdits(vector::style::GradientMeshEdits),
 	ReferencePoint(vector::ReferencePoint),
 	CentroidType(vector::misc::CentroidType),
 	BooleanOperation(vector::misc::BooleanOperation),
@@ -0,0 +1,545 @@
+//! Batch and template rendering for `graphene-cli export-batch`.
+//!
+//! A manifest lists export jobs, each naming a document, the values to substitute into some of its node inputs, an optional
+//! artboard to frame, and an output file. Every job runs in the same process against one GPU context, and the graph executor is
+//! compiled once and then updated incrementally, so rendering hundreds of variants of one template only recompiles the nodes
+//! whose inputs actually change between jobs.
+//!
+//! Manifests are JSON (a list of jobs, or an object with a `jobs` list) or CSV (one job per row). A CSV's `document`, `output`,
+//! `artboard`, `scale`, `width`, `height`, `transparent`, `fps`, `frames` and `duration` columns set the job options of the same
+//! name, and every other column is an input override keyed by its header; empty cells leave that option or input alone.
+//! Relative paths are resolved against the manifest's directory.
+//!
+//! Inputs are addressed as `<node>:<input>`. The node is either its display name in the document (which must be unique, and is
+//! looked up at any depth of the graph) or a `/`-separated path of node ids from the root network; the input is its name or its
+//! index. Only inputs holding a value can be overridden, and the new value is parsed as the type the input already holds: text as
+//! is, numbers and booleans from JSON or CSV literals, vectors as `[x, y]` or `"x, y"`, and colors as hex strings like `"ff0000ff"`.
+
+use crate::export::{self, AnimationParams, FileType};
+use document_format::{GddV1, GddV1Layout, ResourceProxy};
+use document_graph_storage::NodeMetadataEntry;
+use graph_craft::application_io::resource::{LoadResource, ResourceFuture, ResourceHash};
+use graph_craft::application_io::{EditorPreferences, PlatformApplicationIo, PlatformEditorApi};
+use graph_craft::document::value::{DVec2, TaggedValue};
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use graph_craft::util::load_network;
+use graphene_std::NodeInputDecleration;
+use graphene_std::application_io::ApplicationIo;
+use graphene_std::artboard::create_artboard;
+use interpreted_executor::dynamic_executor::DynamicExecutor;
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// One render in a batch manifest.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchJob {
+	/// The `.gdd` or legacy `.graphite` document to render.
+	pub document: PathBuf,
+	/// Output file path (extension determines format: .svg, .png, .jpg, .gif).
+	pub output: PathBuf,
+	/// Display name or node id of the artboard to export. The whole document is exported if absent.
+	#[serde(default)]
+	pub artboard: Option<String>,
+	#[serde(default = "default_scale")]
+	pub scale: f64,
+	/// Output width in pixels. For an artboard, picks the scale that makes the artboard this wide.
+	#[serde(default)]
+	pub width: Option<u32>,
+	/// Output height in pixels. For an artboard, picks the scale that makes the artboard this tall (if no width is given).
+	#[serde(default)]
+	pub height: Option<u32>,
+	#[serde(default)]
+	pub transparent: bool,
+	/// Frames per second for GIF animation.
+	#[serde(default = "default_fps")]
+	pub fps: f64,
+	/// Total number of frames for GIF animation.
+	#[serde(default)]
+	pub frames: Option<u32>,
+	/// Animation duration in seconds for GIF (takes precedence over `frames`).
+	#[serde(default)]
+	pub duration: Option<f64>,
+	/// Values to substitute into node inputs, keyed by `<node>:<input>` address.
+	#[serde(default)]
+	pub inputs: BTreeMap<String, serde_json::Value>,
+}
+
+fn default_scale() -> f64 {
+	1.
+}
+
+fn default_fps() -> f64 {
+	30.
+}
+
+/// The columns of a CSV manifest that set job options rather than input overrides.
+const CSV_OPTION_COLUMNS: [&str; 10] = ["document", "output", "artboard", "scale", "width", "height", "transparent", "fps", "frames", "duration"];
+
+/// Read the jobs of a JSON or CSV manifest (by extension), resolving their relative paths against the manifest's directory.
+pub fn read_manifest(path: &Path) -> Result<Vec<BatchJob>, Box<dyn Error>> {
+	let contents = std::fs::read_to_string(path).map_err(|error| format!("Failed to read manifest {}: {error}", path.display()))?;
+
+	let is_csv = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+	let mut jobs = if is_csv { csv_jobs(&contents)? } else { json_jobs(&contents)? };
+
+	let base = path.parent().unwrap_or(Path::new(""));
+	for job in &mut jobs {
+		job.document = base.join(&job.document);
+		job.output = base.join(&job.output);
+	}
+	Ok(jobs)
+}
+
+fn json_jobs(contents: &str) -> Result<Vec<BatchJob>, Box<dyn Error>> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Manifest {
+		Jobs(Vec<BatchJob>),
+		Wrapped { jobs: Vec<BatchJob> },
+	}
+
+	let manifest: Manifest = serde_json::from_str(contents).map_err(|error| format!("Invalid JSON manifest: {error}"))?;
+	Ok(match manifest {
+		Manifest::Jobs(jobs) | Manifest::Wrapped { jobs } => jobs,
+	})
+}
+
+fn csv_jobs(contents: &str) -> Result<Vec<BatchJob>, Box<dyn Error>> {
+	let mut rows = parse_csv(contents)?.into_iter();
+	let Some(header) = rows.next() else { return Ok(Vec::new()) };
+
+	rows.enumerate()
+		.map(|(index, row)| {
+			// Data rows start on line 2, after the header
+			let line = index + 2;
+			if row.len() != header.len() {
+				return Err(format!("Manifest row {line} has {} cells, but the header has {} columns", row.len(), header.len()).into());
+			}
+
+			let mut job = serde_json::Map::new();
+			let mut inputs = serde_json::Map::new();
+			for (column, cell) in header.iter().zip(row) {
+				if cell.is_empty() {
+					continue;
+				}
+				let column = column.trim();
+				match column {
+					"document" | "output" | "artboard" => {
+						job.insert(column.to_string(), serde_json::Value::String(cell));
+					}
+					// Numbers and booleans are JSON literals; anything else is left as a string for deserialization to reject
+					_ if CSV_OPTION_COLUMNS.contains(&column) => {
+						job.insert(column.to_string(), serde_json::from_str(cell.trim()).unwrap_or(serde_json::Value::String(cell)));
+					}
+					_ => {
+						inputs.insert(column.to_string(), serde_json::Value::String(cell));
+					}
+				}
+			}
+			job.insert("inputs".to_string(), serde_json::Value::Object(inputs));
+
+			serde_json::from_value(serde_json::Value::Object(job)).map_err(|error| format!("Invalid manifest row {line}: {error}").into())
+		})
+		.collect()
+}
+
+/// Split CSV text into rows of cells. Cells may be quoted to contain commas, line breaks, and (doubled) quotes. Blank lines are skipped.
+fn parse_csv(contents: &str) -> Result<Vec<Vec<String>>, String> {
+	let mut rows = Vec::new();
+	let mut row = Vec::new();
+	let mut cell = String::new();
+	let mut in_quotes = false;
+
+	let mut chars = contents.chars().peekable();
+	while let Some(char) = chars.next() {
+		match char {
+			'"' if in_quotes && chars.peek() == Some(&'"') => {
+				chars.next();
+				cell.push('"');
+			}
+			'"' if in_quotes => in_quotes = false,
+			'"' if cell.is_empty() => in_quotes = true,
+			',' if !in_quotes => row.push(std::mem::take(&mut cell)),
+			'\r' if !in_quotes => {}
+			'\n' if !in_quotes => {
+				row.push(std::mem::take(&mut cell));
+				if row.iter().any(|cell| !cell.is_empty()) {
+					rows.push(std::mem::take(&mut row));
+				}
+				row.clear();
+			}
+			_ => cell.push(char),
+		}
+	}
+	if in_quotes {
+		return Err("Manifest has an unterminated quoted cell".to_string());
+	}
+	row.push(cell);
+	if row.iter().any(|cell| !cell.is_empty()) {
+		rows.push(row);
+	}
+	Ok(rows)
+}
+
+/// A document loaded once and shared by every job that renders it.
+struct Template {
+	gdd: Option<GddV1>,
+	network: NodeNetwork,
+	/// Editor metadata (display names, input names) used to resolve input addresses. Empty for legacy documents.
+	metadata: Vec<NodeMetadataEntry>,
+}
+
+impl Template {
+	async fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+		let is_gdd = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gdd"));
+		if !is_gdd {
+			let document_string = std::fs::read_to_string(path).map_err(|error| format!("Failed to read document {}: {error}", path.display()))?;
+			return Ok(Self {
+				gdd: None,
+				network: load_network(&document_string),
+				metadata: Vec::new(),
+			});
+		}
+
+		let gdd = crate::gdd::open_gdd(path).await?;
+		let declarations = gdd.declarations(&gdd).await;
+		let (network, metadata) = gdd.registry().to_runtime_with_metadata(&declarations)?;
+		Ok(Self { gdd: Some(gdd), network, metadata })
+	}
+}
+
+/// Serves resources from the stores of every document in the batch, since the application IO holds a single resource proxy.
+/// Resources are content-addressed, so whichever document has the bytes for a hash serves them.
+struct DocumentResources(Vec<ResourceProxy<GddV1Layout>>);
+
+impl LoadResource for DocumentResources {
+	fn load(&self, hash: ResourceHash) -> ResourceFuture<'_> {
+		Box::pin(async move {
+			for resources in &self.0 {
+				if let Some(resource) = resources.load(hash).await {
+					return Some(resource);
+				}
+			}
+			None
+		})
+	}
+}
+
+/// Render every job of the manifest at `manifest_path`, rasterizing on the CPU if `cpu` is set or there is no GPU. A failing job
+/// doesn't stop the others; the batch fails afterwards listing every job that did.
+pub async fn export_batch(manifest_path: &Path, cpu: bool) -> Result<(), Box<dyn Error>> {
+	let jobs = read_manifest(manifest_path)?;
+	if jobs.is_empty() {
+		return Err("The manifest has no jobs".into());
+	}
+
+	// Load each document once, before creating the application IO, so its resources can be served to every job. A document that
+	// fails to load only fails the jobs rendering it.
+	let mut templates: HashMap<PathBuf, Result<Template, String>> = HashMap::new();
+	for job in &jobs {
+		if let Entry::Vacant(entry) = templates.entry(job.document.clone()) {
+			entry.insert(Template::load(&job.document).await.map_err(|error| error.to_string()));
+		}
+	}
+
+	log::info!("Creating GPU context");
+	let mut application_io = PlatformApplicationIo::new().await;
+	let resources = templates.values().filter_map(|template| template.as_ref().ok()?.gdd.as_ref().map(|gdd| gdd.resource_proxy())).collect();
+	application_io.inject_resource_proxy(Box::new(DocumentResources(resources)));
+	let application_io = Arc::new(application_io);
+
+	let wgpu_executor = application_io.gpu_executor().filter(|_| !cpu);
+	match wgpu_executor {
+		Some(wgpu_executor) => crate::spawn_device_poll_thread(wgpu_executor),
+		None => log::info!("Rasterizing on the CPU"),
+	}
+
+	let editor_api = Arc::new(PlatformEditorApi {
+		application_io: Some(application_io.clone()),
+		node_graph_message_sender: Box::new(crate::UpdateLogger {}),
+		editor_preferences: Box::new(EditorPreferences::default()),
+	});
+
+	let mut executor = None;
+	let mut failures = Vec::new();
+	for (index, job) in jobs.iter().enumerate() {
+		eprintln!("Rendering job {}/{}: {}", index + 1, jobs.len(), job.output.display());
+		let result = match &templates[&job.document] {
+			Ok(template) => run_job(job, template, &editor_api, &mut executor, wgpu_executor).await,
+			Err(error) => Err(error.clone().into()),
+		};
+		if let Err(error) = result {
+			eprintln!("Job {} failed: {error}", index + 1);
+			failures.push(index + 1);
+		}
+	}
+
+	if !failures.is_empty() {
+		let failed = failures.iter().map(|job| job.to_string()).collect::<Vec<_>>().join(", ");
+		return Err(format!("{} of {} jobs failed (jobs {failed})", failures.len(), jobs.len()).into());
+	}
+	eprintln!("Rendered {} jobs", jobs.len());
+	Ok(())
+}
+
+async fn run_job(
+	job: &BatchJob,
+	template: &Template,
+	editor_api: &Arc<PlatformEditorApi>,
+	cached_executor: &mut Option<DynamicExecutor>,
+	wgpu_executor: Option<&wgpu_executor::WgpuExecutor>,
+) -> Result<(), Box<dyn Error>> {
+	let file_type = export::detect_file_type(&job.output)?;
+	if job.fps <= 0. {
+		return Err("Fps number must be positive".into());
+	}
+
+	let mut network = template.network.clone();
+	for (address, value) in &job.inputs {
+		override_input(&mut network, &template.metadata, address, value)?;
+	}
+	// Resolved after the overrides, so a job may move or resize the artboard it exports
+	let bounds = job.artboard.as_deref().map(|artboard| artboard_bounds(&network, &template.metadata, artboard)).transpose()?;
+
+	let proto_network = crate::compile_graph(network, editor_api.clone(), template.gdd.as_ref())?;
+
+	// Reuse the executor compiled for the previous job, so only nodes that changed get rebuilt. One whose update failed is
+	// dropped rather than kept half-updated, and the next job compiles a fresh one.
+	let executor = match cached_executor.take() {
+		Some(mut executor) => {
+			executor.update(proto_network).await.map_err(|(_, errors)| format_graph_errors(&errors))?;
+			executor
+		}
+		None => DynamicExecutor::new(proto_network).await.map_err(|errors| format_graph_errors(&errors))?,
+	};
+	let executor = &*cached_executor.insert(executor);
+
+	let output = job.output.clone();
+	if file_type == FileType::Gif {
+		let animation = AnimationParams::new(job.fps, job.frames, job.duration);
+		export::export_gif(executor, wgpu_executor, output, job.scale, (job.width, job.height), bounds, animation).await
+	} else {
+		export::export_document(executor, wgpu_executor, output, file_type, job.scale, (job.width, job.height), bounds, job.transparent).await
+	}
+}
+
+fn format_graph_errors(errors: &[graph_craft::proto::GraphError]) -> String {
+	errors.iter().map(|e| format!("{e:?}")).reduce(|acc, e| format!("{acc}\n{e}")).unwrap_or_default()
+}
+
+/// Find a node by display name (unique, at any depth) or by a `/`-separated path of node ids, returning the path of the network
+/// containing it and its id there.
+fn resolve_node(metadata: &[NodeMetadataEntry], node: &str) -> Result<(Vec<NodeId>, NodeId), String> {
+	let ids = node.split('/').map(|id| id.trim().parse().ok().map(NodeId)).collect::<Option<Vec<_>>>();
+	if let Some((&id, path)) = ids.as_deref().and_then(|ids| ids.split_last()) {
+		return Ok((path.to_vec(), id));
+	}
+
+	let mut named = metadata.iter().filter(|entry| entry.display_name.as_deref() == Some(node));
+	match (named.next(), named.next()) {
+		(Some(entry), None) => Ok((entry.network_path.clone(), entry.local_id)),
+		(None, _) => Err(format!("No node is named '{node}'")),
+		(Some(_), Some(_)) => Err(format!("Several nodes are named '{node}'; address it by its node id path instead")),
+	}
+}
+
+fn find_node<'a>(network: &'a NodeNetwork, (path, id): &(Vec<NodeId>, NodeId)) -> Option<&'a DocumentNode> {
+	network.nested_network(path)?.nodes.get(id)
+}
+
+/// Replace the value of the node input at `address` (`<node>:<input>`) with `value`, parsed as the type of the value it holds.
+fn override_input(network: &mut NodeNetwork, metadata: &[NodeMetadataEntry], address: &str, value: &serde_json::Value) -> Result<(), String> {
+	let (node, input) = address.rsplit_once(':').ok_or_else(|| format!("Input '{address}' must be addressed as <node>:<input>"))?;
+	let (path, id) = resolve_node(metadata, node)?;
+
+	let index = match input.trim().parse::<usize>() {
+		Ok(index) => index,
+		Err(_) => metadata
+			.iter()
+			.find(|entry| entry.network_path == path && entry.local_id == id)
+			.and_then(|entry| entry.input_metadata.iter().position(|input_metadata| input_metadata.input_name.as_deref() == Some(input)))
+			.ok_or_else(|| format!("Node '{node}' has no input named '{input}'"))?,
+	};
+
+	let document_node = network
+		.nested_network_mut(&path)
+		.and_then(|network| network.nodes.get_mut(&id))
+		.ok_or_else(|| format!("Node '{node}' doesn't exist"))?;
+	let node_input = document_node.inputs.get_mut(index).ok_or_else(|| format!("Node '{node}' has no input {index}"))?;
+	let mut tagged_value = node_input
+		.as_value_mut()
+		.ok_or_else(|| format!("Input '{address}' is connected to another node rather than holding a value"))?;
+
+	let replacement = parse_value(&tagged_value, value).ok_or_else(|| format!("Input '{address}' can't be set to {value}: expected a {:?}", tagged_value.ty()))?;
+	*tagged_value = replacement;
+	Ok(())
+}
+
+/// Parse a manifest value as the same type as `existing`.
+fn parse_value(existing: &TaggedValue, value: &serde_json::Value) -> Option<TaggedValue> {
+	let text = match value {
+		serde_json::Value::String(text) => text.clone(),
+		serde_json::Value::Number(number) => number.to_string(),
+		serde_json::Value::Bool(bool) => bool.to_string(),
+		serde_json::Value::Array(items) => items.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(","),
+		serde_json::Value::Null | serde_json::Value::Object(_) => return None,
+	};
+	// Colors are only parsed from hex when quoted, which distinguishes them from constants like `Color::BLACK`
+	let text = if matches!(existing, TaggedValue::Color(_)) {
+		format!("\"{}\"", text.trim_matches('"'))
+	} else {
+		text
+	};
+
+	TaggedValue::from_primitive_string(&text, &existing.ty())
+}
+
+/// The document-space top-left and bottom-right corners of the artboard named (or with the node id) `artboard`.
+fn artboard_bounds(network: &NodeNetwork, metadata: &[NodeMetadataEntry], artboard: &str) -> Result<[DVec2; 2], String> {
+	let layer = find_node(network, &resolve_node(metadata, artboard)?).ok_or_else(|| format!("Artboard '{artboard}' doesn't exist"))?;
+
+	// An artboard layer wraps the `create_artboard` proto node, whose inputs are imported from the layer's own inputs
+	let artboard_node = layer
+		.implementation
+		.get_network()
+		.and_then(|network| {
+			network
+				.nodes
+				.values()
+				.find(|node| matches!(&node.implementation, DocumentNodeImplementation::ProtoNode(identifier) if *identifier == create_artboard::IDENTIFIER))
+		})
+		.ok_or_else(|| format!("'{artboard}' isn't an artboard"))?;
+	let dvec2_input = |index: usize| {
+		let input = match artboard_node.inputs.get(index)? {
+			NodeInput::Import { import_index, .. } => layer.inputs.get(*import_index)?,
+			input => input,
+		};
+		match input.as_value()? {
+			TaggedValue::DVec2(value) => Some(*value),
+			_ => None,
+		}
+	};
+	let location = dvec2_input(create_artboard::LocationInput::INDEX).ok_or_else(|| format!("Artboard '{artboard}' has no fixed location"))?;
+	let dimensions = dvec2_input(create_artboard::DimensionsInput::INDEX).ok_or_else(|| format!("Artboard '{artboard}' has no fixed dimensions"))?;
+
+	// Normalized the way the artboard node itself does, so negative dimensions extend from the opposite corner
+	let min = location.min(location + dimensions);
+	Ok([min, min + dimensions.abs().max(DVec2::ONE)])
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn csv_cells_can_be_quoted() {
+		let rows = parse_csv("document,output\n\"a, b.gdd\",\"say \"\"hi\"\"\"\n\"multi\nline\",\"\"\n").unwrap();
+		assert_eq!(rows, vec![vec!["document", "output"], vec!["a, b.gdd", "say \"hi\""], vec!["multi\nline", ""]]);
+	}
+
+	#[test]
+	fn csv_rows_end_in_lf_or_crlf() {
+		let rows = parse_csv("a,b\r\n1,2\r\n\r\n3,\"x\r\ny\"").unwrap();
+		assert_eq!(rows, vec![vec!["a", "b"], vec!["1", "2"], vec!["3", "x\r\ny"]], "blank lines are skipped and quoted line breaks kept");
+	}
+
+	#[test]
+	fn csv_rejects_an_unterminated_quote() {
+		assert!(parse_csv("a,\"b\n1,2").is_err());
+	}
+
+	#[test]
+	fn csv_columns_become_options_or_inputs() {
+		let jobs = csv_jobs("document,output,scale,transparent,Title:text,Blur:1\nin.gdd,out.png,2,true,\"Hello, world\",\n").unwrap();
+		let [job] = &jobs[..] else { panic!("expected one job") };
+		assert_eq!((job.document.as_path(), job.output.as_path()), (Path::new("in.gdd"), Path::new("out.png")));
+		assert_eq!((job.scale, job.transparent), (2., true));
+		assert_eq!(job.inputs.len(), 1, "an empty cell leaves its input alone");
+		assert_eq!(job.inputs["Title:text"], serde_json::json!("Hello, world"));
+
+		assert!(csv_jobs("document,output\nin.gdd\n").is_err(), "a short row is rejected");
+		assert!(csv_jobs("document,output,scale\nin.gdd,out.png,large\n").is_err(), "a non-numeric scale is rejected");
+	}
+
+	fn value_node(values: Vec<TaggedValue>) -> (NodeNetwork, Vec<NodeMetadataEntry>) {
+		let input_metadata = ["amount", "label", "offset", "tint", "enabled"]
+			.into_iter()
+			.map(|name| document_graph_storage::InputMetadataEntry {
+				input_name: Some(name.to_string()),
+				..Default::default()
+			})
+			.collect();
+		let node = DocumentNode {
+			inputs: values.into_iter().map(|value| NodeInput::value(value, false)).collect(),
+			..Default::default()
+		};
+		let network = NodeNetwork {
+			nodes: [(NodeId(7), node)].into_iter().collect(),
+			..Default::default()
+		};
+		let metadata = vec![NodeMetadataEntry {
+			network_path: Vec::new(),
+			local_id: NodeId(7),
+			position: None,
+			is_layer: false,
+			display_name: Some("Settings".to_string()),
+			locked: false,
+			pinned: false,
+			input_metadata,
+			output_names: Vec::new(),
+			component: None,
+			library_export: None,
+			library_link: None,
+			layout_grid: None,
+		}];
+		(network, metadata)
+	}
+
+	fn input(network: &NodeNetwork, index: usize) -> &TaggedValue {
+		network.nodes[&NodeId(7)].inputs[index].as_value().unwrap()
+	}
+
+	#[test]
+	fn overrides_parse_as_the_existing_type() {
+		let values = vec![
+			TaggedValue::F64(1.),
+			TaggedValue::String("old".to_string()),
+			TaggedValue::DVec2(DVec2::ZERO),
+			TaggedValue::Color(graphene_std::Color::BLACK),
+			TaggedValue::Bool(false),
+		];
+		let (mut network, metadata) = value_node(values);
+
+		override_input(&mut network, &metadata, "Settings:amount", &serde_json::json!(2.5)).unwrap();
+		override_input(&mut network, &metadata, "Settings:label", &serde_json::json!("new")).unwrap();
+		override_input(&mut network, &metadata, "Settings:offset", &serde_json::json!([3, 4])).unwrap();
+		override_input(&mut network, &metadata, "7:3", &serde_json::json!("ff0000ff")).unwrap();
+		override_input(&mut network, &metadata, "Settings:enabled", &serde_json::json!("true")).unwrap();
+
+		assert_eq!(input(&network, 0), &TaggedValue::F64(2.5));
+		assert_eq!(input(&network, 1), &TaggedValue::String("new".to_string()));
+		assert_eq!(input(&network, 2), &TaggedValue::DVec2(DVec2::new(3., 4.)));
+		assert_eq!(input(&network, 3), &TaggedValue::Color(graphene_std::Color::RED));
+		assert_eq!(input(&network, 4), &TaggedValue::Bool(true));
+
+		override_input(&mut network, &metadata, "Settings:offset", &serde_json::json!("5, 6")).unwrap();
+		assert_eq!(input(&network, 2), &TaggedValue::DVec2(DVec2::new(5., 6.)), "vectors can also be written as \"x, y\"");
+	}
+
+	#[test]
+	fn invalid_overrides_are_rejected() {
+		let (mut network, metadata) = value_node(vec![TaggedValue::F64(1.)]);
+
+		assert!(override_input(&mut network, &metadata, "Settings", &serde_json::json!(1)).is_err(), "missing input");
+		assert!(override_input(&mut network, &metadata, "Missing:amount", &serde_json::json!(1)).is_err(), "unknown node");
+		assert!(override_input(&mut network, &metadata, "Settings:missing", &serde_json::json!(1)).is_err(), "unknown input name");
+		assert!(override_input(&mut network, &metadata, "Settings:4", &serde_json::json!(1)).is_err(), "input index out of range");
+		assert!(override_input(&mut network, &metadata, "Settings:amount", &serde_json::json!("many")).is_err(), "not a number");
+		assert_eq!(input(&network, 0), &TaggedValue::F64(1.), "a rejected override leaves the input alone");
+	}
+}
@@ -1,4 +1,4 @@
-use graph_craft::document::value::{RenderOutputType, TaggedValue, UVec2};
+use graph_craft::document::value::{DAffine2, DVec2, RenderOutputType, TaggedValue, UVec2};
 use graph_craft::graphene_compiler::Executor;
 use graphene_std::application_io::{ExportFormat, RenderConfig, TimingInformation};
 use graphene_std::core_types::ops::Convert;
@@ -30,47 +30,46 @@ pub fn detect_file_type(path: &Path) -> Result<FileType, String> {
 
 pub async fn export_document(
 	executor: &DynamicExecutor,
-	wgpu_executor: &wgpu_executor::WgpuExecutor,
+	wgpu_executor: Option<&wgpu_executor::WgpuExecutor>,
 	output_path: PathBuf,
 	file_type: FileType,
 	scale: f64,
 	(width, height): (Option<u32>, Option<u32>),
+	bounds: Option<[DVec2; 2]>,
 	transparent: bool,
 ) -> Result<(), Box<dyn Error>> {
-	// Determine export format based on file type
-	let export_format = match file_type {
-		FileType::Svg => ExportFormat::Svg,
-		_ => ExportFormat::Raster,
-	};
+	let export_format = export_format(file_type, wgpu_executor);
 
 	// Create render config with export settings
-	let mut render_config = RenderConfig {
+	let (viewport, scale) = viewport(scale, (width, height), bounds);
+	let render_config = RenderConfig {
+		viewport,
 		scale,
 		export_format,
 		for_export: true,
 		..Default::default()
 	};
 
-	// Set viewport dimensions if specified
-	if let (Some(w), Some(h)) = (width, height) {
-		render_config.viewport.resolution = UVec2::new(w, h);
-	}
-
 	// Execute the graph
 	let result = executor.execute(render_config.into_context()).await?;
 
 	// Handle the result based on output type
 	match result {
 		TaggedValue::RenderOutput(output) => match output.data {
-			RenderOutputType::Svg { svg, .. } => {
+			RenderOutputType::Svg { svg, .. } if file_type == FileType::Svg => {
 				// Write SVG directly to file
 				std::fs::write(&output_path, svg)?;
 				log::info!("Exported SVG to: {}", output_path.display());
 			}
+			RenderOutputType::Svg { svg, .. } => {
+				// Rasterize the SVG on the CPU when there is no GPU to render with
+				let (data, width, height) = rasterize_on_cpu(&svg, viewport.resolution)?;
+				write_raster_image(output_path, file_type, data, width, height, transparent)?;
+			}
 			RenderOutputType::Texture(texture) => {
 				// Convert GPU texture to CPU buffer
 				let gpu_raster = Raster::<GPU>::new_gpu(texture);
-				let cpu_raster: Raster<CPU> = gpu_raster.convert(Footprint::BOUNDLESS, wgpu_executor).await;
+				let cpu_raster: Raster<CPU> = gpu_raster.convert(Footprint::BOUNDLESS, wgpu_executor.ok_or("Received a GPU texture without a GPU")?).await;
 				let (data, width, height) = cpu_raster.to_flat_u8();
 
 				// Encode and write raster image
@@ -91,6 +90,48 @@ pub async fn export_document(
 	Ok(())
 }
 
+/// The format the graph renders to for `file_type`. Raster formats render on the GPU when there is one, and otherwise render to
+/// SVG for [`rasterize_on_cpu`] to rasterize.
+fn export_format(file_type: FileType, wgpu_executor: Option<&wgpu_executor::WgpuExecutor>) -> ExportFormat {
+	match (file_type, wgpu_executor) {
+		(FileType::Svg, _) | (_, None) => ExportFormat::Svg,
+		_ => ExportFormat::Raster,
+	}
+}
+
+/// Rasterize an SVG render at the viewport's `resolution` on the CPU, returning unpremultiplied sRGB RGBA8 pixels.
+fn rasterize_on_cpu(svg: &str, resolution: UVec2) -> Result<(Vec<u8>, u32, u32), Box<dyn Error>> {
+	let image = graphene_std::platform_application_io::rasterize_svg(svg, resolution)?;
+	Ok(image.to_flat_u8())
+}
+
+/// The viewport an export renders through, and the scale it renders at. With `bounds` (document-space top-left and bottom-right
+/// corners, e.g. of an artboard) it frames exactly that region the way the editor exports an artboard: at `scale`, or if a `width`
+/// or `height` is given, at whichever scale makes the region that wide or tall (the width winning if both are). Without `bounds`
+/// the whole document renders, at `width` × `height` if both are given.
+fn viewport(scale: f64, (width, height): (Option<u32>, Option<u32>), bounds: Option<[DVec2; 2]>) -> (Footprint, f64) {
+	let Some([min, max]) = bounds else {
+		let mut viewport = Footprint::default();
+		if let (Some(w), Some(h)) = (width, height) {
+			viewport.resolution = UVec2::new(w, h);
+		}
+		return (viewport, scale);
+	};
+
+	let size = max - min;
+	let scale = match (width, height) {
+		(Some(w), _) => w as f64 / size.x,
+		(None, Some(h)) => h as f64 / size.y,
+		(None, None) => scale,
+	};
+	let viewport = Footprint {
+		resolution: (size * scale).round().as_uvec2(),
+		transform: DAffine2::from_translation(min).inverse(),
+		..Default::default()
+	};
+	(viewport, scale)
+}
+
 fn write_raster_image(output_path: PathBuf, file_type: FileType, data: Vec<u8>, width: u32, height: u32, transparent: bool) -> Result<(), Box<dyn Error>> {
 	use image::{ImageFormat, RgbaImage};
 
@@ -151,10 +192,11 @@ impl AnimationParams {
 /// Export an animated GIF by rendering multiple frames at different animation times
 pub async fn export_gif(
 	executor: &DynamicExecutor,
-	wgpu_executor: &wgpu_executor::WgpuExecutor,
+	wgpu_executor: Option<&wgpu_executor::WgpuExecutor>,
 	output_path: PathBuf,
 	scale: f64,
 	(width, height): (Option<u32>, Option<u32>),
+	bounds: Option<[DVec2; 2]>,
 	animation: AnimationParams,
 ) -> Result<(), Box<dyn Error>> {
 	use image::codecs::gif::{GifEncoder, Repeat};
@@ -168,6 +210,8 @@ pub async fn export_gif(
 	encoder.set_repeat(Repeat::Infinite)?;
 
 	let frame_delay = animation.frame_delay_centiseconds();
+	let export_format = export_format(FileType::Gif, wgpu_executor);
+	let (viewport, scale) = viewport(scale, (width, height), bounds);
 
 	for frame_idx in 0..animation.frames {
 		let animation_time = Duration::from_secs_f64(frame_idx as f64 / animation.fps);
@@ -178,9 +222,10 @@ pub async fn export_gif(
 		log::debug!("Rendering frame {}/{} at time {:?}", frame_idx + 1, animation.frames, animation_time);
 
 		// Create render config with animation time
-		let mut render_config = RenderConfig {
+		let render_config = RenderConfig {
+			viewport,
 			scale,
-			export_format: ExportFormat::Raster,
+			export_format,
 			for_export: true,
 			time: TimingInformation {
 				time: animation_time.as_secs_f64(),
@@ -189,11 +234,6 @@ pub async fn export_gif(
 			..Default::default()
 		};
 
-		// Set viewport dimensions if specified
-		if let (Some(w), Some(h)) = (width, height) {
-			render_config.viewport.resolution = UVec2::new(w, h);
-		}
-
 		// Execute the graph for this frame
 		let result = executor.execute(render_config.into_context()).await?;
 
@@ -202,12 +242,14 @@ pub async fn export_gif(
 			TaggedValue::RenderOutput(output) => match output.data {
 				RenderOutputType::Texture(texture) => {
 					let gpu_raster = Raster::<GPU>::new_gpu(texture);
-					let cpu_raster: Raster<CPU> = gpu_raster.convert(Footprint::BOUNDLESS, wgpu_executor).await;
+					let cpu_raster: Raster<CPU> = gpu_raster.convert(Footprint::BOUNDLESS, wgpu_executor.ok_or("Received a GPU texture without a GPU")?).await;
 					cpu_raster.to_flat_u8()
 				}
+				RenderOutputType::Svg { svg, .. } => rasterize_on_cpu(&svg, viewport.resolution)?,
 				RenderOutputType::Buffer { data, width, height } => (data, width, height),
+				#[cfg(target_family = "wasm")]
 				other => {
-					return Err(format!("Unexpected render output type for GIF frame: {:?}. Expected Texture or Buffer.", other).into());
+					return Err(format!("Unexpected render output type for GIF frame: {:?}. Expected Texture, Buffer or Svg.", other).into());
 				}
 			},
 			other => return Err(format!("Expected RenderOutput for GIF frame, got: {:?}", other).into()),
@@ -0,0 +1,274 @@
+use document_container::AnyContainer;
+use document_container::backends::memory::MemoryBackend;
+use document_format::{ExportFormat, ExportOptions, GddV1, GddV1Layout};
+use document_graph_storage::{CommitIssue, Delta, RegistryDelta, Rev, Side, TimestampKey};
+use std::error::Error;
+use std::path::Path;
+
+/// Read a single-file `.gdd` archive at `path` and open it in memory.
+pub async fn open_gdd(path: &Path) -> Result<GddV1, Box<dyn Error>> {
+	let archive = std::fs::read(path).map_err(|error| format!("Failed to read document {}: {error}", path.display()))?;
+	let container = AnyContainer::Memory(MemoryBackend::new());
+	let gdd = document_format::Gdd::open_from_archive(archive.as_ref(), container, GddV1Layout)
+		.await
+		.map_err(|error| format!("Failed to open document {}: {error}", path.display()))?;
+	Ok(gdd)
+}
+
+/// Re-wrap an opened `.gdd` as a single-file archive and write it to `path`, carrying the embedded legacy
+/// `.graphite` blob over unchanged so older builds can still open the result.
+pub async fn save_gdd(gdd: &GddV1, path: &Path) -> Result<(), Box<dyn Error>> {
+	let legacy_document = gdd.read_legacy_document().await;
+	let bytes = gdd
+		.export_to_bytes(ExportFormat::Xz, ExportOptions::default(), gdd, legacy_document.as_ref().map(|bytes| bytes.as_slice()))
+		.await
+		.map_err(|error| format!("Failed to build .gdd archive: {error}"))?;
+	std::fs::write(path, bytes).map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+	Ok(())
+}
+
+/// Drop history branches nothing can reach any more, or (unless `prune_only`) collapse the reachable history
+/// into a single chain. Pruning keeps every surviving commit's `Rev`; linearizing re-addresses them.
+pub async fn linearize(mut gdd: GddV1, output: &Path, prune_only: bool) -> Result<(), Box<dyn Error>> {
+	let before = gdd.session().history().count();
+
+	if prune_only {
+		let removed = gdd.prune_history().map_err(|error| format!("Failed to prune history: {error}"))?;
+		eprintln!("Pruned {removed} unreachable of {before} commits");
+	} else {
+		let remapped = gdd.linearize_history().map_err(|error| format!("Failed to linearize history: {error}"))?;
+		let after = gdd.session().history().count();
+		eprintln!(
+			"Linearized {before} commits into a chain of {after} ({} re-addressed)",
+			remapped.iter().filter(|(old, new)| old != new).count()
+		);
+	}
+
+	save_gdd(&gdd, output).await?;
+	eprintln!("Saved file to {}", output.display());
+	Ok(())
+}
+
+/// Check every commit's `Rev` and its author's signature, printing each tampered, unsigned, or badly signed
+/// one. Fails if any commit doesn't verify, except that unsigned commits (e.g. made before their author had
+/// a key) are only reported when `allow_unsigned` is set.
+pub fn verify(gdd: &GddV1, allow_unsigned: bool) -> Result<(), Box<dyn Error>> {
+	let session = gdd.session();
+	let audit = session.verify_signed_history().map_err(|error| format!("History is corrupt: {error}"))?;
+
+	for (rev, issue) in &audit.issues {
+		let author = session.delta(*rev).map(|delta| delta.author.0).unwrap_or_default();
+		println!("{rev} (peer {author}): {issue}");
+	}
+
+	let total = session.history().count();
+	let failed = audit.issues.iter().filter(|(_, issue)| !(allow_unsigned && *issue == CommitIssue::Unsigned)).count();
+	eprintln!("{} of {total} commits verified", audit.verified);
+	if failed > 0 {
+		return Err(format!("{failed} of {total} commits failed verification").into());
+	}
+	Ok(())
+}
+
+/// Merge the diverged copy at `other` into `gdd` and save the result to `output`. Each value both copies
+/// changed differently since they forked is printed with the side whose value was kept (the later edit), or
+/// the whole report is printed as JSON when `json` is set.
+pub async fn merge(mut gdd: GddV1, other: &Path, output: &Path, json: bool) -> Result<(), Box<dyn Error>> {
+	let other = open_gdd(other).await?;
+	let report = gdd.merge_from(&other).await.map_err(|error| format!("Failed to merge: {error}"))?;
+
+	if json {
+		println!("{}", serde_json::to_string_pretty(&report)?);
+	} else {
+		for conflict in &report.conflicts {
+			let winner = match conflict.winner {
+				Side::Ours => "ours",
+				Side::Theirs => "theirs",
+			};
+			println!("{}: ours {} / theirs {}, keeping {winner}", conflict.slot, conflict.ours, conflict.theirs);
+		}
+	}
+
+	let bases: Vec<String> = report.bases.iter().map(ToString::to_string).collect();
+	eprintln!(
+		"Merged {} new commits from common ancestor {} with {} conflicts",
+		report.imported,
+		if bases.is_empty() { "(none)".to_string() } else { bases.join(", ") },
+		report.conflicts.len()
+	);
+
+	save_gdd(&gdd, output).await?;
+	eprintln!("Saved file to {}", output.display());
+	Ok(())
+}
+
+/// Resolve a revision argument: `head` for the cursor, otherwise a `Rev` or a prefix of one matching exactly
+/// one commit.
+pub fn resolve_rev(gdd: &GddV1, argument: &str) -> Result<Rev, Box<dyn Error>> {
+	let session = gdd.session();
+	if argument.eq_ignore_ascii_case("head") {
+		return Ok(session.head_rev().ok_or("The document has no commits")?);
+	}
+	if argument.is_empty() || !argument.bytes().all(|byte| byte.is_ascii_digit()) {
+		return Err(format!("Invalid revision '{argument}'").into());
+	}
+
+	let matches: Vec<Rev> = session.history().map(|delta| delta.id).filter(|rev| rev.to_string().starts_with(argument)).collect();
+	if let Some(&rev) = matches.iter().find(|rev| rev.to_string() == argument) {
+		return Ok(rev);
+	}
+	match matches.as_slice() {
+		[rev] => Ok(*rev),
+		[] => Err(format!("No commit matches revision '{argument}'").into()),
+		_ => Err(format!("Revision '{argument}' is ambiguous ({} commits match)", matches.len()).into()),
+	}
+}
+
+/// One-line description of an op, naming the slot or entity it touches.
+fn describe(op: &RegistryDelta) -> String {
+	let set_or_clear = |value: &Option<serde_json::Value>| if value.is_some() { "set" } else { "clear" };
+	match op {
+		RegistryDelta::AddNode { id, .. } => format!("add node {id}"),
+		RegistryDelta::RemoveNode { id, .. } => format!("remove node {id}"),
+		RegistryDelta::ChangeNodeInput { id, index, .. } => format!("set {}", TimestampKey::NodeInput(*id, *index as usize)),
+		RegistryDelta::ChangeNodeAttribute { id, delta } => format!("{} {}", set_or_clear(&delta.value), TimestampKey::NodeAttribute(*id, delta.key.clone())),
+		RegistryDelta::ChangeNodeInputAttribute { id, index, delta } => format!("{} {}", set_or_clear(&delta.value), TimestampKey::NodeInputAttribute(*id, *index as usize, delta.key.clone())),
+		RegistryDelta::SetNetworkExport { id, index, export } => format!("{} {}", if export.is_some() { "set" } else { "remove" }, TimestampKey::NetworkExport(*id, *index as usize)),
+		RegistryDelta::ChangeNetworkAttribute { id, delta } => format!("{} {}", set_or_clear(&delta.value), TimestampKey::NetworkAttribute(*id, delta.key.clone())),
+		RegistryDelta::AddNetwork { id, .. } => format!("add network {id}"),
+		RegistryDelta::RemoveNetwork { id, .. } => format!("remove network {id}"),
+		RegistryDelta::AddResource { id, entry } => match entry.hash {
+			Some(hash) => format!("add resource {id} ({hash})"),
+			None => format!("add resource {id}"),
+		},
+		RegistryDelta::SetResourceHash { id, hash: Some(hash) } => format!("set resource {id} hash to {hash}"),
+		RegistryDelta::SetResourceHash { id, hash: None } => format!("clear resource {id} hash"),
+		RegistryDelta::RemoveResource { id, .. } => format!("remove resource {id}"),
+		RegistryDelta::AddSource { id, key, .. } => format!("add {}", TimestampKey::ResourceSource(*id, *key)),
+		RegistryDelta::RemoveSource { id, key } => format!("remove {}", TimestampKey::ResourceSource(*id, *key)),
+		RegistryDelta::RegisterPeer { peer, user } => format!("register peer {} as user {}", peer.0, user.0),
+		RegistryDelta::RegisterPeerKey { peer, key } => format!("register peer {} key {key}", peer.0),
+		RegistryDelta::ChangeDocumentAttribute { delta } => format!("{} {}", set_or_clear(&delta.value), TimestampKey::DocumentAttribute(delta.key.clone())),
+		RegistryDelta::Merge { extra_parents } => format!("merge {} branches", extra_parents.len() + 1),
+		RegistryDelta::Other(_) => "unknown op".to_string(),
+	}
+}
+
+fn parents(delta: &Delta) -> String {
+	let parents: Vec<String> = delta.all_parents().map(|rev| rev.to_string()).collect();
+	if parents.is_empty() { "root".to_string() } else { parents.join(", ") }
+}
+
+/// Print the commit DAG newest first, one commit per line: its `Rev`, parents, author, Lamport timestamp and
+/// op, with the cursor and interaction-end (undo step) markers. Commits outside the cursor's ancestry (undone
+/// or abandoned branches) are marked too.
+pub fn log(gdd: &GddV1) {
+	let session = gdd.session();
+	let head = session.head_rev();
+	let reachable: std::collections::HashSet<Rev> = session.reachable_deltas().map(|delta| delta.id).collect();
+	let deltas: Vec<&Delta> = session.history().collect();
+
+	for delta in deltas.into_iter().rev() {
+		let mut markers = Vec::new();
+		if Some(delta.id) == head {
+			markers.push("head");
+		}
+		if delta.is_interaction_end() {
+			markers.push("end");
+		}
+		if !reachable.contains(&delta.id) {
+			markers.push("unreachable");
+		}
+		let markers = if markers.is_empty() { String::new() } else { format!(" [{}]", markers.join(", ")) };
+		println!(
+			"{} <- {}  peer {} @{}{markers}  {}",
+			delta.id,
+			parents(delta),
+			delta.author.0,
+			delta.timestamp.counter,
+			describe(&delta.kind)
+		);
+	}
+}
+
+/// Print one commit in full: its header fields and annotations, then the op and its reverse as JSON.
+pub fn show(gdd: &GddV1, rev: Rev) -> Result<(), Box<dyn Error>> {
+	let session = gdd.session();
+	let delta = session.delta(rev).ok_or_else(|| format!("Commit {rev} not found"))?;
+	let user = session.registry().peer_users.get(&delta.author).map(|user| format!(" (user {})", user.0)).unwrap_or_default();
+
+	println!("commit {}", delta.id);
+	println!("parents: {}", parents(delta));
+	println!("author: peer {}{user}", delta.author.0);
+	println!("timestamp: {} (peer {})", delta.timestamp.counter, delta.timestamp.peer.0);
+	for (key, value) in &delta.attributes {
+		println!("{key}: {}", value.value);
+	}
+	println!("\n{}", describe(&delta.kind));
+	println!("{}", serde_json::to_string_pretty(&delta.kind)?);
+	println!("\nreverse: {}", describe(&delta.reverse));
+	println!("{}", serde_json::to_string_pretty(&delta.reverse)?);
+	Ok(())
+}
+
+/// Print the minimal ops taking the registry at `from` to the registry at `to`, one per line, or in full as
+/// JSON when `json` is set. Compares materialized states, so it works across branches as well as along one.
+pub fn diff(gdd: &GddV1, from: Rev, to: Rev, json: bool) -> Result<(), Box<dyn Error>> {
+	let session = gdd.session();
+	let from_registry = session.registry_at(from)?;
+	let to_registry = session.registry_at(to)?;
+	let ops = document_graph_storage::delta::compute_deltas(&from_registry, &to_registry);
+
+	if json {
+		println!("{}", serde_json::to_string_pretty(&ops)?);
+	} else {
+		for op in &ops {
+			println!("{}", describe(op));
+		}
+	}
+	eprintln!("{} changes from {from} to {to}", ops.len());
+	Ok(())
+}
+
+/// Materialize the registry as of `rev` and write it as JSON to `output`, or to stdout.
+pub fn checkout(gdd: &GddV1, rev: Rev, output: Option<&Path>) -> Result<(), Box<dyn Error>> {
+	let registry = gdd.session().registry_at(rev)?;
+	let json = serde_json::to_string_pretty(&registry)?;
+
+	match output {
+		Some(output) => {
+			std::fs::write(output, json).map_err(|error| format!("Failed to write {}: {error}", output.display()))?;
+			eprintln!("Saved the registry at {rev} to {}", output.display());
+		}
+		None => println!("{json}"),
+	}
+	Ok(())
+}
+
+/// Delete the resource blobs nothing in the document references any more, listing each, and save the result
+/// to `output` unless `dry_run` is set. The input `document` is only rewritten when something was removed.
+pub async fn gc(gdd: GddV1, document: &Path, output: &Path, dry_run: bool) -> Result<(), Box<dyn Error>> {
+	let removed = if dry_run {
+		let referenced = gdd.all_referenced_resource_hashes();
+		let mut unreferenced: Vec<_> = gdd.resource_hashes().await?.into_iter().filter(|hash| !referenced.contains(hash)).collect();
+		unreferenced.sort_unstable();
+		unreferenced
+	} else {
+		gdd.remove_unreferenced_resources().await?
+	};
+
+	for hash in &removed {
+		println!("{hash}");
+	}
+	if dry_run {
+		eprintln!("{} unreferenced resources would be removed", removed.len());
+		return Ok(());
+	}
+
+	eprintln!("Removed {} unreferenced resources", removed.len());
+	if !removed.is_empty() || output != document {
+		save_gdd(&gdd, output).await?;
+		eprintln!("Saved file to {}", output.display());
+	}
+	Ok(())
+}
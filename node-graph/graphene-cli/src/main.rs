@@ -1,9 +1,9 @@
+mod batch;
 mod export;
+mod gdd;
 
 use clap::{Args, Parser, Subcommand};
-use document_container::AnyContainer;
-use document_container::backends::memory::MemoryBackend;
-use document_format::{GddV1, GddV1Layout};
+use document_format::GddV1;
 use fern::colors::{Color, ColoredLevelConfig};
 use futures::executor::block_on;
 use graph_craft::application_io::EditorPreferences;
@@ -85,6 +85,19 @@ enum Command {
 		/// Animation duration in seconds for GIF (takes precedence over --frames)
 		#[clap(long)]
 		duration: Option<f64>,
+
+		/// Rasterize PNG, JPG and GIF exports on the CPU even if a GPU is available (the default without one)
+		#[clap(long)]
+		cpu: bool,
+	},
+	/// Export many renders of one or more documents, with per-render input overrides, from a JSON or CSV manifest
+	ExportBatch {
+		/// Path to the manifest listing the jobs (see the `batch` module docs for its format)
+		manifest: PathBuf,
+
+		/// Rasterize PNG, JPG and GIF exports on the CPU even if a GPU is available (the default without one)
+		#[clap(long)]
+		cpu: bool,
 	},
 	ListNodeIdentifiers,
 
@@ -92,6 +105,105 @@ enum Command {
 	ExtractLegacyDoc {
 		document: PathBuf,
 	},
+
+	/// Rewrite a .gdd document's history into a single chain, dropping merges and abandoned undo branches
+	Linearize {
+		/// Path to the .gdd document
+		document: PathBuf,
+
+		/// Output file path (default: overwrite the input document)
+		#[clap(long, short = 'o')]
+		output: Option<PathBuf>,
+
+		/// Only drop unreachable branches, keeping every surviving commit's Rev (safe for shared history)
+		#[clap(long)]
+		prune_only: bool,
+	},
+
+	/// Check a .gdd document's history for tampered, unsigned, or forged commits
+	Verify {
+		/// Path to the .gdd document
+		document: PathBuf,
+
+		/// Report unsigned commits without failing (e.g. history from before signing was enabled)
+		#[clap(long)]
+		allow_unsigned: bool,
+	},
+
+	/// Merge the edits of a diverged copy of a .gdd document, reporting the values both copies changed differently
+	Merge {
+		/// Path to the .gdd document to merge into
+		document: PathBuf,
+
+		/// Path to the diverged copy whose edits are merged in
+		other: PathBuf,
+
+		/// Output file path (default: overwrite the first document)
+		#[clap(long, short = 'o')]
+		output: Option<PathBuf>,
+
+		/// Print the merge report as JSON instead of one line per conflict
+		#[clap(long)]
+		json: bool,
+	},
+
+	/// Print a .gdd document's commit history, newest first
+	Log {
+		/// Path to the .gdd document
+		document: PathBuf,
+	},
+
+	/// Print one commit of a .gdd document in full
+	Show {
+		/// Path to the .gdd document
+		document: PathBuf,
+
+		/// Revision to show: `head`, or a Rev or unique prefix of one
+		rev: String,
+	},
+
+	/// Print the changes between the states of a .gdd document at two revisions
+	Diff {
+		/// Path to the .gdd document
+		document: PathBuf,
+
+		/// Revision to diff from: `head`, or a Rev or unique prefix of one
+		from: String,
+
+		/// Revision to diff to: `head`, or a Rev or unique prefix of one
+		to: String,
+
+		/// Print the changes as JSON ops instead of one line per change
+		#[clap(long)]
+		json: bool,
+	},
+
+	/// Write the registry of a .gdd document as of a revision, as JSON
+	Checkout {
+		/// Path to the .gdd document
+		document: PathBuf,
+
+		/// Revision to materialize: `head`, or a Rev or unique prefix of one
+		rev: String,
+
+		/// Output file path (default: print to stdout)
+		#[clap(long, short = 'o')]
+		output: Option<PathBuf>,
+	},
+
+	/// Delete resource blobs a .gdd document no longer references
+	Gc {
+		/// Path to the .gdd document
+		document: PathBuf,
+
+		/// Output file path (default: overwrite the input document)
+		#[clap(long, short = 'o')]
+		output: Option<PathBuf>,
+
+		/// Only list the unreferenced resources
+		#[clap(long)]
+		dry_run: bool,
+	},
 }
 
 #[derive(Debug, Args)]
@@ -113,6 +225,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		Command::Compile { ref document, .. } => document,
 		Command::Export { ref document, .. } => document,
 		Command::ExtractLegacyDoc { ref document } => document,
+		Command::Linearize { ref document, .. } => document,
+		Command::Verify { ref document, .. } => document,
+		Command::Merge { ref document, .. } => document,
+		Command::Log { ref document } => document,
+		Command::Show { ref document, .. } => document,
+		Command::Diff { ref document, .. } => document,
+		Command::Checkout { ref document, .. } => document,
+		Command::Gc { ref document, .. } => document,
+		Command::ExportBatch { ref manifest, cpu } => return batch::export_batch(manifest, cpu).await,
 		Command::ListNodeIdentifiers => {
 			let mut nodes: Vec<_> = graphene_std::registry::NODE_METADATA.lock().unwrap().keys().cloned().collect();
 			nodes.sort_by_key(|x| x.as_str().to_string());
@@ -127,16 +248,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	// legacy `.graphite` document. The legacy path has no `Gdd`, so resources fall back to the default registry.
 	let is_gdd = document_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gdd"));
 
-	let gdd = if is_gdd {
-		let archive = std::fs::read(document_path).map_err(|error| format!("Failed to read document {}: {error}", document_path.display()))?;
-		let container = AnyContainer::Memory(MemoryBackend::new());
-		let gdd = document_format::Gdd::open_from_archive(archive.as_ref(), container, GddV1Layout)
-			.await
-			.map_err(|error| format!("Failed to open document: {error}"))?;
-		Some(gdd)
-	} else {
-		None
-	};
+	let gdd = if is_gdd { Some(gdd::open_gdd(document_path).await?) } else { None };
 
 	if let Command::ExtractLegacyDoc { ref document } = app.command {
 		let Some(gdd) = &gdd else { return Err("ExtractLegacyDoc requires a .gdd document".into()) };
@@ -150,6 +262,52 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		return Ok(());
 	}
 
+	if let Command::Linearize { ref document, ref output, prune_only } = app.command {
+		let Some(gdd) = gdd else { return Err("Linearize requires a .gdd document".into()) };
+		return gdd::linearize(gdd, output.as_ref().unwrap_or(document), prune_only).await;
+	}
+
+	if let Command::Verify { allow_unsigned, .. } = app.command {
+		let Some(gdd) = &gdd else { return Err("Verify requires a .gdd document".into()) };
+		return gdd::verify(gdd, allow_unsigned);
+	}
+
+	if let Command::Merge {
+		ref document,
+		ref other,
+		ref output,
+		json,
+	} = app.command
+	{
+		let Some(gdd) = gdd else { return Err("Merge requires a .gdd document".into()) };
+		return gdd::merge(gdd, other, output.as_ref().unwrap_or(document), json).await;
+	}
+
+	match app.command {
+		Command::Log { .. } => {
+			let Some(gdd) = &gdd else { return Err("Log requires a .gdd document".into()) };
+			gdd::log(gdd);
+			return Ok(());
+		}
+		Command::Show { ref rev, .. } => {
+			let Some(gdd) = &gdd else { return Err("Show requires a .gdd document".into()) };
+			return gdd::show(gdd, gdd::resolve_rev(gdd, rev)?);
+		}
+		Command::Diff { ref from, ref to, json, .. } => {
+			let Some(gdd) = &gdd else { return Err("Diff requires a .gdd document".into()) };
+			return gdd::diff(gdd, gdd::resolve_rev(gdd, from)?, gdd::resolve_rev(gdd, to)?, json);
+		}
+		Command::Checkout { ref rev, ref output, .. } => {
+			let Some(gdd) = &gdd else { return Err("Checkout requires a .gdd document".into()) };
+			return gdd::checkout(gdd, gdd::resolve_rev(gdd, rev)?, output.as_deref());
+		}
+		Command::Gc { ref document, ref output, dry_run } => {
+			let Some(gdd) = gdd else { return Err("Gc requires a .gdd document".into()) };
+			return gdd::gc(gdd, document, output.as_ref().unwrap_or(document), dry_run).await;
+		}
+		_ => {}
+	}
+
 	// Build the runtime network: from the `.gdd` registry, or by loading a legacy `.graphite` document.
 	let node_network = match &gdd {
 		Some(gdd) => {
@@ -175,9 +333,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	// Clone the application_io Arc before borrowing to extract executor
 	let application_io_for_api = application_io_arc.clone();
 
-	// Get reference to wgpu executor and clone device handle
-	let wgpu_executor_ref = application_io_arc.gpu_executor().unwrap();
-	let device = wgpu_executor_ref.context().device.clone();
+	// Get reference to wgpu executor, absent on machines without a GPU
+	let wgpu_executor_ref = application_io_arc.gpu_executor();
 
 	let preferences = EditorPreferences {
 		max_render_region_size: EditorPreferences::default().max_render_region_size,
@@ -204,15 +361,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
 			fps,
 			frames,
 			duration,
+			cpu,
 			..
 		} => {
-			// Spawn thread to poll GPU device
-			std::thread::spawn(move || {
-				loop {
-					std::thread::sleep(std::time::Duration::from_nanos(10));
-					device.poll(wgpu::PollType::Poll).unwrap();
-				}
-			});
+			let wgpu_executor_ref = wgpu_executor_ref.filter(|_| !cpu);
+			match wgpu_executor_ref {
+				Some(wgpu_executor) => spawn_device_poll_thread(wgpu_executor),
+				None => log::info!("Rasterizing on the CPU"),
+			}
 
 			// Detect output file type
 			let file_type = export::detect_file_type(&output)?;
@@ -227,9 +383,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
 			// Perform export based on file type
 			if file_type == export::FileType::Gif {
 				let animation = export::AnimationParams::new(fps, frames, duration);
-				export::export_gif(&executor, wgpu_executor_ref, output, scale, (width, height), animation).await?;
+				export::export_gif(&executor, wgpu_executor_ref, output, scale, (width, height), None, animation).await?;
 			} else {
-				export::export_document(&executor, wgpu_executor_ref, output, file_type, scale, (width, height), transparent).await?;
+				export::export_document(&executor, wgpu_executor_ref, output, file_type, scale, (width, height), None, transparent).await?;
 			}
 		}
 		_ => unreachable!("All other commands should be handled before this match statement is run"),
@@ -285,6 +441,17 @@ fn compile_graph(network: NodeNetwork, editor_api: Arc<PlatformEditorApi>, gdd:
 	compiler.compile_single(network).map_err(|x| x.into())
 }
 
+/// Spawn a thread that keeps polling the GPU device, so GPU work submitted during execution completes.
+fn spawn_device_poll_thread(wgpu_executor: &wgpu_executor::WgpuExecutor) {
+	let device = wgpu_executor.context().device.clone();
+	std::thread::spawn(move || {
+		loop {
+			std::thread::sleep(std::time::Duration::from_nanos(10));
+			device.poll(wgpu::PollType::Poll).unwrap();
+		}
+	});
+}
+
 fn create_executor(proto_network: ProtoNetwork) -> Result<DynamicExecutor, Box<dyn Error>> {
 	let executor = block_on(DynamicExecutor::new(proto_network)).map_err(|errors| errors.iter().map(|e| format!("{e:?}")).reduce(|acc, e| format!("{acc}\n{e}")).unwrap_or_default())?;
 	Ok(executor)
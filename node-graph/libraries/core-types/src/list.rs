@@ -23,6 +23,8 @@ pub const ATTR_OPACITY: &str = "opacity";
 pub const ATTR_OPACITY_FILL: &str = "opacity_fill";
 /// `bool` for whether an item inherits the alpha of the content beneath it (clipping mask).
 pub const ATTR_CLIPPING_MASK: &str = "clipping_mask";
+/// Item's `Vec<LayerEffect>` stack of shadows and glows, drawn behind or inside the item in the order they were added.
+pub const ATTR_LAYER_EFFECTS: &str = "layer_effects";
 /// `NodeIdPath` path from the root network to the layer node owning this item.
 /// Used by editor tools to route clicks/selection back to the originating layer.
 pub const ATTR_EDITOR_LAYER_PATH: &str = "editor:layer_path";
@@ -57,12 +59,16 @@ pub const ATTR_BACKGROUND: &str = "background";
 pub const ATTR_CLIP: &str = "clip";
 /// Gradient's `GradientSpreadMethod` (`Pad`, `Reflect`, or `Repeat`).
 pub const ATTR_SPREAD_METHOD: &str = "spread_method";
-/// Gradient's `GradientType` (`Linear` or `Radial`).
+/// Gradient's `GradientType` (`Linear`, `Radial`, `Conic`, `Diamond`, or `Mesh`).
 pub const ATTR_GRADIENT_TYPE: &str = "gradient_type";
+/// Gradient's `GradientMesh`, the grid of colored vertices drawn by `Mesh` gradients in place of one built from the gradient's stops.
+pub const ATTR_GRADIENT_MESH: &str = "gradient_mesh";
 /// Vector graphics object's filled area paint, of type List<T> where T is any graphic type.
 pub const ATTR_FILL: &str = "fill";
 /// Vector graphics object's stroke paint, of type List<T> where T is any graphic type.
 pub const ATTR_STROKE: &str = "stroke";
+/// Paint's `PatternTiling`, which repeats the paint's graphic across the area it paints instead of stretching it once over the path's bounds.
+pub const ATTR_PATTERN: &str = "pattern";
 /// Text item's font size in document-space units (`f64`, implicit default `24.`).
 pub const ATTR_FONT_SIZE: &str = "font_size";
 /// Text item's font, as a `Resource` of the loaded font file.
@@ -0,0 +1,51 @@
+/// Picks the extremum of the window of `2 * half_width + 1` values centered on each value, where `outside` lies beyond the values, in constant time per value with the van Herk/Gil-Werman algorithm.
+///
+/// `pick` chooses the extremum of two values, like [`f32::min`] for an erosion or [`f32::max`] for a dilation.
+pub fn running_extremum(values: &[f32], half_width: usize, outside: f32, pick: impl Fn(f32, f32) -> f32) -> Vec<f32> {
+	if half_width == 0 {
+		return values.to_vec();
+	}
+
+	// Split the padded values into blocks as long as the window, so each window spans the end of one block and the start of the next
+	let window = 2 * half_width + 1;
+	let length = (values.len() + 2 * half_width).div_ceil(window) * window;
+	let padded: Vec<f32> = (0..length)
+		.map(|index| index.checked_sub(half_width).and_then(|index| values.get(index)).copied().unwrap_or(outside))
+		.collect();
+
+	let mut from_block_start = padded.clone();
+	let mut to_block_end = padded.clone();
+	for block in (0..length).step_by(window) {
+		for index in block + 1..block + window {
+			from_block_start[index] = pick(from_block_start[index - 1], padded[index]);
+		}
+		for index in (block..block + window - 1).rev() {
+			to_block_end[index] = pick(to_block_end[index + 1], padded[index]);
+		}
+	}
+
+	(0..values.len()).map(|index| pick(to_block_end[index], from_block_start[index + window - 1])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn matches_the_extremum_of_each_window() {
+		let values = [0.3, 0.9, 0.1, 0.5, 0.7, 0.2, 0.8, 0.4, 0.6];
+		for half_width in 0..=values.len() {
+			for (outside, pick) in [(f32::NEG_INFINITY, f32::max as fn(f32, f32) -> f32), (f32::INFINITY, f32::min), (0., f32::max), (0., f32::min)] {
+				let expected: Vec<f32> = (0..values.len() as isize)
+					.map(|center| {
+						(center - half_width as isize..=center + half_width as isize)
+							.map(|index| usize::try_from(index).ok().and_then(|index| values.get(index)).copied().unwrap_or(outside))
+							.reduce(pick)
+							.unwrap()
+					})
+					.collect();
+				assert_eq!(running_extremum(&values, half_width, outside, pick), expected, "half width {half_width}, outside {outside}");
+			}
+		}
+	}
+}
@@ -1,4 +1,5 @@
 pub mod bbox;
+pub mod extremum;
 pub mod polynomial;
 pub mod quad;
 pub mod rect;
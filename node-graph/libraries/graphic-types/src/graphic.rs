@@ -1,15 +1,19 @@
+use crate::pattern::PatternTiling;
 use core_types::bounds::{BoundingBox, RenderBoundingBox};
 use core_types::graphene_hash::CacheHash;
 use core_types::list::{ATTR_FILL, ATTR_STROKE, Item, ItemAttributeValues, List, NodeIdPath};
 use core_types::ops::FromAnchorPosition;
 use core_types::render_complexity::RenderComplexity;
-use core_types::{ATTR_CLIPPING_MASK, ATTR_EDITOR_LAYER_PATH, ATTR_OPACITY, ATTR_OPACITY_FILL, ATTR_TRANSFORM, Color};
+use core_types::{ATTR_CLIPPING_MASK, ATTR_EDITOR_LAYER_PATH, ATTR_OPACITY, ATTR_OPACITY_FILL, ATTR_PATTERN, ATTR_TRANSFORM, Color};
 use dyn_any::DynAny;
 use glam::{DAffine2, DVec2};
 use raster_types::{CPU, GPU, Raster};
 use std::borrow::Cow;
 use vector_types::Gradient;
 pub use vector_types::Vector;
+use vector_types::vector::VectorExt;
+use vector_types::vector::algorithms::stroke_outline::solidify_stroke;
+use vector_types::vector::style::PaintOrder;
 
 /// The possible forms of graphical content that can be rendered by the Render node into either an image or SVG syntax.
 #[derive(Clone, Debug, Default, CacheHash, PartialEq, DynAny)]
@@ -226,7 +230,15 @@ pub fn bake_paint_transforms(attributes: &mut ItemAttributeValues, transform: DA
 	}
 
 	fn bake_graphic_paint_transform(graphics: &mut List<Graphic>, transform: DAffine2) {
-		for graphic in graphics.iter_element_values_mut() {
+		for index in 0..graphics.len() {
+			// A tiled paint keeps its graphic in place and moves the grid of tiles as a whole, so the spacing between them is transformed too
+			if let Some(mut tiling) = graphics.attribute::<PatternTiling>(ATTR_PATTERN, index).copied() {
+				tiling.transform = transform * tiling.transform;
+				graphics.set_attribute(ATTR_PATTERN, index, tiling);
+				continue;
+			}
+
+			let Some(graphic) = graphics.element_mut(index) else { continue };
 			match graphic {
 				Graphic::None => {}
 				Graphic::Graphic(list) => bake_list_transform(list, transform),
@@ -247,6 +259,40 @@ pub fn bake_paint_transforms(attributes: &mut ItemAttributeValues, transform: DA
 	}
 }
 
+/// Splits a vector item into its stroke expanded into a filled outline, painted with the stroke's paint, and (if it has a fill) the original geometry with its stroke removed.
+///
+/// The items are returned in the order they're drawn in, from the bottom up, following the stroke's paint order.
+pub fn solidify_stroke_item(item: Item<Vector>, has_fill: bool) -> Vec<Item<Vector>> {
+	let (mut vector, attributes) = item.into_parts();
+
+	let stroke = vector.stroke.clone().unwrap_or_default();
+	let mut solidified_stroke = Vector::default();
+	for path in vector.stroke_bezpath_iter() {
+		solidified_stroke.append_bezpath(solidify_stroke(path, &stroke));
+	}
+
+	// If the original vector has a fill, preserve it as a separate item with the stroke cleared.
+	let fill_item = has_fill.then(|| {
+		vector.stroke = None;
+		let mut fill_attributes = attributes.clone();
+		// No stroke remains on the fill item
+		fill_attributes.remove::<List<Graphic>>(ATTR_STROKE);
+		Item::from_parts(vector, fill_attributes)
+	});
+
+	let mut stroke_attributes = attributes;
+	// Drop the original fill and use the stroke paint to fill the outlined stroke
+	stroke_attributes.remove::<List<Graphic>>(ATTR_FILL);
+	stroke_attributes.rename(ATTR_STROKE, ATTR_FILL);
+
+	let stroke_item = Item::from_parts(solidified_stroke, stroke_attributes);
+
+	match stroke.paint_order {
+		PaintOrder::StrokeAbove => fill_item.into_iter().chain(std::iter::once(stroke_item)).collect(),
+		PaintOrder::StrokeBelow => std::iter::once(stroke_item).chain(fill_item).collect(),
+	}
+}
+
 /// Maps from a concrete element type to its corresponding `Graphic` enum variant,
 /// enabling type-directed casting of typed `List`s from a `Graphic` value.
 pub trait TryFromGraphic: Clone + Sized {
@@ -0,0 +1,53 @@
+use core_types::Color;
+use core_types::graphene_hash::CacheHash;
+use dyn_any::DynAny;
+use glam::DVec2;
+
+/// The kinds of layer effect, which decide whether the effect is drawn behind or inside the graphic, and whether it's cast by the graphic's alpha or by the area around it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerEffectType {
+	/// A shadow cast by the graphic, drawn behind it.
+	#[default]
+	DropShadow,
+	/// A shadow cast onto the graphic by the area around it, drawn inside its edges.
+	InnerShadow,
+	/// A glow radiating outwards from the graphic, drawn behind it.
+	OuterGlow,
+	/// A glow radiating inwards from the edges of the graphic, drawn inside them.
+	InnerGlow,
+}
+
+/// A shadow or glow made from the alpha of a graphic, which is grown or shrunk, blurred, offset and tinted with a color.
+///
+/// Lengths are measured in the local space of the item the effect is attached to through the `layer_effects` attribute.
+#[derive(Debug, Default, Clone, Copy, PartialEq, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerEffect {
+	pub effect_type: LayerEffectType,
+	/// The color of the effect, with its alpha setting the effect's opacity.
+	pub color: Color,
+	/// How far the effect is moved from the graphic. Glows aren't moved.
+	pub offset: DVec2,
+	/// How much the edges of the effect are softened, which is twice the standard deviation of its Gaussian blur.
+	pub blur: f64,
+	/// How far the graphic's alpha is grown (for outer effects) or shrunk (for inner effects) before it's blurred.
+	pub spread: f64,
+}
+
+impl LayerEffect {
+	/// Whether the effect is drawn inside the edges of the graphic rather than behind it.
+	pub fn is_inner(&self) -> bool {
+		matches!(self.effect_type, LayerEffectType::InnerShadow | LayerEffectType::InnerGlow)
+	}
+
+	pub fn standard_deviation(&self) -> f64 {
+		self.blur.max(0.) / 2.
+	}
+
+	/// How far beyond the bounds of the graphic the effect reaches, either to be drawn there (for outer effects) or to read the area around the graphic (for inner effects).
+	pub fn reach(&self) -> f64 {
+		// Beyond three standard deviations the blur fades below the precision of 8-bit color
+		self.offset.length() + self.spread.max(0.) + 3. * self.standard_deviation()
+	}
+}
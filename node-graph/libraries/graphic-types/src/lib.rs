@@ -1,5 +1,7 @@
 pub mod artboard;
 pub mod graphic;
+pub mod layer_effects;
+pub mod pattern;
 
 // Re-export all transitive dependencies so downstream crates only need to depend on graphic-types
 pub use core_types;
@@ -9,6 +11,8 @@ pub use vector_types;
 // Re-export commonly used types at the crate root
 pub use artboard::Artboard;
 pub use graphic::{Graphic, IntoGraphicList, TryFromGraphic, Vector};
+pub use layer_effects::{LayerEffect, LayerEffectType};
+pub use pattern::PatternTiling;
 
 pub mod migrations {
 	use crate::Vector;
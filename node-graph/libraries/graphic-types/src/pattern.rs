@@ -0,0 +1,112 @@
+use core_types::graphene_hash::CacheHash;
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2};
+
+/// How a paint's graphic is repeated as tiles across the area it paints, attached to the paint through the `pattern` attribute.
+///
+/// Each tile is a cell the size of the graphic's bounds plus the spacing, with the graphic placed in its top left corner.
+/// The grid of cells is laid out in pattern space, which is mapped into the local space of the painted path by the offset and then the transform.
+#[derive(Debug, Default, Clone, Copy, PartialEq, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PatternTiling {
+	/// The rotation, scale and skew (and any further translation) applied to the whole grid of tiles.
+	pub transform: DAffine2,
+	/// The gap left between neighboring tiles, which may be negative to make them overlap.
+	pub spacing: DVec2,
+	/// How far the grid of tiles is moved before it's transformed.
+	pub offset: DVec2,
+}
+
+impl PatternTiling {
+	/// The size of each cell of the grid for a graphic with the given bounds, or `None` if the cells would have no area.
+	pub fn cell_size(&self, [min, max]: [DVec2; 2]) -> Option<DVec2> {
+		let size = max - min + self.spacing;
+		(size.x > 0. && size.y > 0. && size.is_finite()).then_some(size)
+	}
+
+	/// Maps pattern space, where the cell at column and row `(i, j)` spans from `(i, j) * cell_size` to `(i + 1, j + 1) * cell_size`, into the local space of the painted path.
+	pub fn pattern_to_local(&self) -> DAffine2 {
+		self.transform * DAffine2::from_translation(self.offset)
+	}
+
+	/// Maps the graphic into the local space of the painted path for the tile in the cell at the given column and row.
+	pub fn tile_to_local(&self, [min, _]: [DVec2; 2], cell_size: DVec2, column: i64, row: i64) -> DAffine2 {
+		self.pattern_to_local() * DAffine2::from_translation(DVec2::new(column as f64, row as f64) * cell_size - min)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The bounds of a 4x2 graphic away from the origin.
+	const TILE_BOUNDS: [DVec2; 2] = [DVec2::new(2., 3.), DVec2::new(6., 5.)];
+
+	fn assert_near(actual: DVec2, expected: DVec2) {
+		assert!(actual.abs_diff_eq(expected, 1e-9), "{actual} should be {expected}");
+	}
+
+	#[test]
+	fn cells_are_the_graphic_plus_the_spacing() {
+		let tiling = PatternTiling {
+			spacing: DVec2::new(1., -0.5),
+			..Default::default()
+		};
+		assert_eq!(tiling.cell_size(TILE_BOUNDS), Some(DVec2::new(5., 1.5)));
+
+		let overlapping_entirely = PatternTiling {
+			spacing: DVec2::new(-4., 0.),
+			..Default::default()
+		};
+		assert_eq!(overlapping_entirely.cell_size(TILE_BOUNDS), None, "Cells without area can't be tiled");
+	}
+
+	#[test]
+	fn tiles_start_at_the_top_left_of_their_cells() {
+		let tiling = PatternTiling {
+			spacing: DVec2::new(1., 2.),
+			..Default::default()
+		};
+		let cell_size = tiling.cell_size(TILE_BOUNDS).unwrap();
+
+		for (column, row) in [(0, 0), (1, 0), (0, 1), (-2, 3)] {
+			let tile_to_local = tiling.tile_to_local(TILE_BOUNDS, cell_size, column, row);
+			let cell_corner = DVec2::new(column as f64, row as f64) * cell_size;
+			assert_near(tile_to_local.transform_point2(TILE_BOUNDS[0]), cell_corner);
+			assert_near(tile_to_local.transform_point2(TILE_BOUNDS[1]), cell_corner + TILE_BOUNDS[1] - TILE_BOUNDS[0]);
+		}
+	}
+
+	#[test]
+	fn offset_moves_the_grid_before_it_is_transformed() {
+		let tiling = PatternTiling {
+			transform: DAffine2::from_scale(DVec2::splat(2.)),
+			offset: DVec2::new(1., -1.),
+			..Default::default()
+		};
+		let cell_size = tiling.cell_size(TILE_BOUNDS).unwrap();
+
+		// The offset is scaled along with the grid
+		assert_near(tiling.pattern_to_local().transform_point2(DVec2::ZERO), DVec2::new(2., -2.));
+		assert_near(tiling.tile_to_local(TILE_BOUNDS, cell_size, 1, 0).transform_point2(TILE_BOUNDS[0]), DVec2::new(10., -2.));
+	}
+
+	#[test]
+	fn scale_and_rotation_apply_to_the_whole_grid() {
+		let tiling = PatternTiling {
+			transform: DAffine2::from_angle(std::f64::consts::FRAC_PI_2) * DAffine2::from_scale(DVec2::new(2., 3.)),
+			..Default::default()
+		};
+		let cell_size = tiling.cell_size(TILE_BOUNDS).unwrap();
+
+		// Neighboring cells are a scaled cell apart along the rotated axes, a quarter turn mapping +X to +Y and +Y to -X
+		let tile_corner = |column, row| tiling.tile_to_local(TILE_BOUNDS, cell_size, column, row).transform_point2(TILE_BOUNDS[0]);
+		assert_near(tile_corner(0, 0), DVec2::ZERO);
+		assert_near(tile_corner(1, 0), DVec2::new(0., 8.));
+		assert_near(tile_corner(0, 1), DVec2::new(-6., 0.));
+
+		// Each tile is scaled and rotated the same as the grid
+		let tile_to_local = tiling.tile_to_local(TILE_BOUNDS, cell_size, 0, 0);
+		assert_near(tile_to_local.transform_vector2(TILE_BOUNDS[1] - TILE_BOUNDS[0]), DVec2::new(-6., 8.));
+	}
+}
@@ -0,0 +1,561 @@
+use crate::renderer::{format_transform_matrix, gradient_placement, transform_is_invertible};
+use crate::to_peniko::ToPenikoColor;
+use core_types::color::SRGBA8;
+use core_types::list::List;
+use core_types::uuid::generate_uuid;
+use core_types::{ATTR_GRADIENT_MESH, ATTR_GRADIENT_TYPE, ATTR_SPREAD_METHOD, ATTR_TRANSFORM, Color};
+use glam::{DAffine2, DVec2};
+use graphic_types::raster_types::Image;
+use graphic_types::vector_types::gradient::{Gradient, GradientMesh, GradientSpreadMethod, GradientType};
+use std::f64::consts::{PI, TAU};
+use std::fmt::Write;
+use vello::{Scene, peniko};
+
+/// The number of flat-colored wedges approximating a conic gradient in SVG, which has no paint server for it.
+const CONIC_SVG_WEDGES: usize = 360;
+/// The width and height in pixels of the image a mesh gradient is rasterized to in SVG.
+const MESH_SVG_RESOLUTION: u32 = 256;
+/// The most pixels along each axis of the image a mesh gradient is rasterized to in the Vello renderer, which otherwise matches the displayed size.
+const MESH_VELLO_MAX_RESOLUTION: u32 = 1024;
+
+/// One brush of a gradient's paint in the Vello renderer, which only covers the gradient's `clip` rectangle when there is one.
+pub(crate) struct GradientBrush {
+	pub brush: peniko::Brush,
+	/// Maps the space of the brush into the gradient's unit space.
+	pub brush_to_gradient: DAffine2,
+	/// The rectangle of the gradient's unit space the brush is drawn within.
+	pub clip: Option<kurbo::Rect>,
+}
+
+/// The mesh drawn by a mesh gradient: the one it was given, or otherwise one built from its stops.
+pub(crate) fn gradient_mesh(gradient_list: &List<Gradient>, index: usize) -> Option<GradientMesh> {
+	let mesh = gradient_list.attribute::<GradientMesh>(ATTR_GRADIENT_MESH, index).filter(|mesh| mesh.is_valid()).cloned();
+	mesh.or_else(|| gradient_list.element(index).map(GradientMesh::from))
+}
+
+/// The corners of a rectangle once it's transformed, which can be given as the area a gradient needs to paint.
+pub(crate) fn transformed_corners(transform: DAffine2, rect: kurbo::Rect) -> [DVec2; 4] {
+	[(rect.x0, rect.y0), (rect.x1, rect.y0), (rect.x1, rect.y1), (rect.x0, rect.y1)].map(|(x, y)| transform.transform_point2(DVec2::new(x, y)))
+}
+
+/// The bounding box in the gradient's unit space of an area, given by its corners in the space the gradient is placed into.
+fn coverage_in_gradient_space(gradient_transform: DAffine2, corners: [DVec2; 4]) -> Option<[DVec2; 2]> {
+	if !transform_is_invertible(gradient_transform) {
+		return None;
+	}
+
+	let inverse = gradient_transform.inverse();
+	let corners = corners.map(|corner| inverse.transform_point2(corner));
+	let bounds = corners.iter().fold([corners[0], corners[0]], |[min, max], &corner| [min.min(corner), max.max(corner)]);
+
+	// Reach slightly further so the edges of the area are never left unpainted by rounding
+	let margin = (bounds[1] - bounds[0]) * 0.01 + 1e-6;
+	Some([bounds[0] - margin, bounds[1] + margin])
+}
+
+/// The color at a position along the gradient, blended between its samples in gamma-encoded sRGB with premultiplied alpha like its SVG and Vello paint servers do.
+fn sample_stops(samples: &[(f64, Color, Option<f64>)], position: f64) -> Color {
+	let Some(&(_, first, _)) = samples.first() else { return Color::TRANSPARENT };
+
+	let next = samples.iter().position(|&(sample_position, _, _)| sample_position >= position);
+	let (before, after) = match next {
+		None => return samples[samples.len() - 1].1,
+		Some(0) => return first,
+		Some(index) => (samples[index - 1], samples[index]),
+	};
+
+	let span = after.0 - before.0;
+	let factor = if span > 0. { ((position - before.0) / span) as f32 } else { 1. };
+
+	let premultiplied = |color: Color| {
+		let [red, green, blue, alpha] = color.to_gamma_srgb_channels();
+		[red * alpha, green * alpha, blue * alpha, alpha]
+	};
+	let (from, to) = (premultiplied(before.1), premultiplied(after.1));
+	let [red, green, blue, alpha] = std::array::from_fn(|channel| from[channel] + (to[channel] - from[channel]) * factor);
+	if alpha <= 0. {
+		return Color::TRANSPARENT;
+	}
+	Color::from_gamma_srgb_channels(red / alpha, green / alpha, blue / alpha, alpha)
+}
+
+/// Rasterizes the mesh across the given area of the gradient's unit space, leaving the pixels outside the mesh transparent.
+fn rasterize_mesh(mesh: &GradientMesh, [min, max]: [DVec2; 2], width: u32, height: u32) -> Image<Color> {
+	let mut image = Image::new(width, height, Color::TRANSPARENT);
+	let pixel_size = (max - min) / DVec2::new(width as f64, height as f64);
+
+	for y in 0..height {
+		for x in 0..width {
+			let point = min + (DVec2::new(x as f64, y as f64) + 0.5) * pixel_size;
+			if let Some(color) = mesh.color_at(point) {
+				image.data[(y * width + x) as usize] = Color::new_from_unassociated_rgba(color.r(), color.g(), color.b(), color.a());
+			}
+		}
+	}
+
+	image
+}
+
+/// The flat-colored wedges approximating a conic gradient across an area of its unit space, given by its bounding box.
+/// Each is given by the corners of its outer edge, which reaches far enough for the wedge to cover its part of the area, and the position along the gradient its color is sampled at.
+fn conic_wedges([min, max]: [DVec2; 2]) -> impl Iterator<Item = ([DVec2; 2], f64)> {
+	let radius = [min, max, DVec2::new(min.x, max.y), DVec2::new(max.x, min.y)].into_iter().map(DVec2::length).fold(0., f64::max) / (PI / CONIC_SVG_WEDGES as f64).cos();
+	let angle = |step: usize| TAU * step as f64 / CONIC_SVG_WEDGES as f64;
+
+	(0..CONIC_SVG_WEDGES).map(move |wedge| {
+		(
+			[angle(wedge), angle(wedge + 1)].map(|angle| DVec2::from_angle(angle) * radius),
+			(wedge as f64 + 0.5) / CONIC_SVG_WEDGES as f64,
+		)
+	})
+}
+
+/// The linear gradients that draw a diamond gradient exactly, one within each quadrant of its unit space, reaching as far from the center as `extent`.
+/// Each is given by the end of its gradient line, which starts at the center, and the rectangle of its quadrant.
+fn diamond_quadrants(extent: DVec2) -> [(DVec2, kurbo::Rect); 4] {
+	[DVec2::new(1., 1.), DVec2::new(-1., 1.), DVec2::new(-1., -1.), DVec2::new(1., -1.)].map(|sign| {
+		let corner = sign * extent;
+		(sign * 0.5, kurbo::Rect::new(0., 0., corner.x, corner.y).abs())
+	})
+}
+
+fn format_color_attributes(paint: &str, color: Color) -> String {
+	let mut attributes = format!(r##" {paint}="#{}""##, SRGBA8::from(color).to_rgb_hex());
+	if color.a() < 1. {
+		let _ = write!(attributes, r#" {paint}-opacity="{}""#, (color.a() * 1000.).round() / 1000.);
+	}
+	attributes
+}
+
+/// Writes an SVG `<pattern>` to the defs drawing the gradient types SVG has no paint server for, returning the ID to reference it by.
+///
+/// `gradient_transform` places the gradient's unit space into the user space of the element the pattern paints,
+/// and `coverage` gives the corners of the area of that user space which needs to be painted.
+/// `stops` are the `<stop>` elements of the gradient, which the diamond gradient is built from.
+pub(crate) fn write_svg_pattern(svg_defs: &mut String, gradient_list: &List<Gradient>, index: usize, stops: &str, gradient_transform: DAffine2, coverage: [DVec2; 4]) -> u64 {
+	let pattern_id = generate_uuid();
+	let Some(stop_list) = gradient_list.element(index) else { return pattern_id };
+	let gradient_type: GradientType = gradient_list.attribute_cloned_or_default(ATTR_GRADIENT_TYPE, index);
+	let spread_method: GradientSpreadMethod = gradient_list.attribute_cloned_or_default(ATTR_SPREAD_METHOD, index);
+
+	// A degenerate placement of the gradient leaves the pattern empty, which paints nothing
+	let Some([mut min, mut max]) = coverage_in_gradient_space(gradient_transform, coverage) else {
+		let _ = write!(svg_defs, r#"<pattern id="{pattern_id}" width="0" height="0" />"#);
+		return pattern_id;
+	};
+
+	let mut content = String::new();
+	match gradient_type {
+		GradientType::Linear | GradientType::Radial => {}
+		GradientType::Conic => {
+			let samples = stop_list.interpolated_samples();
+
+			content.push_str(r#"<g shape-rendering="crispEdges">"#);
+			for ([start, end], position) in conic_wedges([min, max]) {
+				let fill = format_color_attributes("fill", sample_stops(&samples, position));
+				let _ = write!(content, r#"<polygon points="0,0 {},{} {},{}"{fill} />"#, start.x, start.y, end.x, end.y);
+			}
+			content.push_str("</g>");
+		}
+		GradientType::Diamond => {
+			// Within each quadrant the diamond is a linear gradient running diagonally, so it's drawn exactly by four of them
+			let extent = min.abs().max(max.abs());
+			let spread_method = if spread_method == GradientSpreadMethod::Pad {
+				String::new()
+			} else {
+				format!(r#" spreadMethod="{}""#, spread_method.svg_name())
+			};
+
+			content.push_str(r#"<g shape-rendering="crispEdges">"#);
+			for (quadrant, (end, rect)) in diamond_quadrants(extent).into_iter().enumerate() {
+				let _ = write!(
+					svg_defs,
+					r#"<linearGradient id="{pattern_id}-{quadrant}" gradientUnits="userSpaceOnUse" x1="0" y1="0" x2="{}" y2="{}"{spread_method}>{stops}</linearGradient>"#,
+					end.x, end.y
+				);
+				let _ = write!(
+					content,
+					r##"<rect x="{}" y="{}" width="{}" height="{}" fill="url(#{pattern_id}-{quadrant})" />"##,
+					rect.x0,
+					rect.y0,
+					rect.width(),
+					rect.height()
+				);
+			}
+			content.push_str("</g>");
+		}
+		GradientType::Mesh => {
+			let mesh = gradient_mesh(gradient_list, index);
+			let [mesh_min, mesh_max] = mesh.as_ref().and_then(GradientMesh::bounding_box).unwrap_or([min, min]);
+			let size = mesh_max - mesh_min;
+
+			if let Some(mesh) = mesh.filter(|_| size.x > 0. && size.y > 0.) {
+				use base64::Engine;
+
+				let image = rasterize_mesh(&mesh, [mesh_min, mesh_max], MESH_SVG_RESOLUTION, MESH_SVG_RESOLUTION);
+				let mut href = "data:image/png;base64,".to_string();
+				base64::engine::general_purpose::STANDARD.encode_string(image.to_png(), &mut href);
+				let _ = write!(
+					content,
+					r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" href="{href}" />"#,
+					mesh_min.x, mesh_min.y, size.x, size.y
+				);
+			}
+
+			// Keep the whole mesh within one tile so it isn't repeated
+			min = min.min(mesh_min);
+			max = max.max(mesh_max);
+		}
+	}
+
+	let size = max - min;
+	let _ = write!(
+		svg_defs,
+		r#"<pattern id="{pattern_id}" patternUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}" patternTransform="{}">{content}</pattern>"#,
+		min.x,
+		min.y,
+		size.x,
+		size.y,
+		format_transform_matrix(gradient_transform)
+	);
+
+	pattern_id
+}
+
+/// Builds the brushes drawing the gradient in the Vello renderer, returning them with the transform placing the gradient's unit space on the device.
+///
+/// `transform` maps the space the gradient's own transform is relative to onto the device, and `coverage` gives the corners of the area of the device which needs to be painted.
+pub(crate) fn vello_brushes(gradient_list: &List<Gradient>, index: usize, transform: DAffine2, coverage: [DVec2; 4]) -> (Vec<GradientBrush>, DAffine2) {
+	let gradient_type: GradientType = gradient_list.attribute_cloned_or_default(ATTR_GRADIENT_TYPE, index);
+	let gradient_transform: DAffine2 = gradient_list.attribute_cloned_or_default(ATTR_TRANSFORM, index);
+	let gradient_to_device = gradient_placement(transform * gradient_transform, gradient_type);
+
+	(gradient_brushes(gradient_list, index, gradient_type, gradient_to_device, coverage), gradient_to_device)
+}
+
+/// Draws each of the brushes with `draw`, within a clip to the brush's rectangle if it has one.
+///
+/// `draw` is given the brush and its transform relative to `shape_transform`, the transform of the shape it paints.
+pub(crate) fn draw_vello_brushes(
+	scene: &mut Scene,
+	brushes: &[GradientBrush],
+	gradient_to_device: DAffine2,
+	shape_transform: DAffine2,
+	mut draw: impl FnMut(&mut Scene, &peniko::Brush, kurbo::Affine),
+) {
+	// Cancel out the shape's transform, which Vello applies to the brush as well
+	let inverse_shape_transform = if transform_is_invertible(shape_transform) {
+		shape_transform.inverse()
+	} else {
+		DAffine2::IDENTITY
+	};
+
+	for GradientBrush { brush, brush_to_gradient, clip } in brushes {
+		if let Some(clip) = clip {
+			scene.push_clip_layer(peniko::Fill::NonZero, kurbo::Affine::new(gradient_to_device.to_cols_array()), clip);
+		}
+
+		let brush_transform = inverse_shape_transform * gradient_to_device * *brush_to_gradient;
+		draw(scene, brush, kurbo::Affine::new(brush_transform.to_cols_array()));
+
+		if clip.is_some() {
+			scene.pop_layer();
+		}
+	}
+}
+
+fn gradient_brushes(gradient_list: &List<Gradient>, index: usize, gradient_type: GradientType, gradient_to_device: DAffine2, coverage: [DVec2; 4]) -> Vec<GradientBrush> {
+	let Some(stops) = gradient_list.element(index) else { return Vec::new() };
+	let spread_method: GradientSpreadMethod = gradient_list.attribute_cloned_or_default(ATTR_SPREAD_METHOD, index);
+
+	let mut peniko_stops = peniko::ColorStops::new();
+	for (position, color, _) in stops.interpolated_samples() {
+		peniko_stops.push(peniko::ColorStop {
+			offset: position as f32,
+			color: peniko::color::DynamicColor::from_alpha_color(SRGBA8::from(color).to_peniko_color()),
+		});
+	}
+
+	let extend = match spread_method {
+		GradientSpreadMethod::Pad => peniko::Extend::Pad,
+		GradientSpreadMethod::Reflect => peniko::Extend::Reflect,
+		GradientSpreadMethod::Repeat => peniko::Extend::Repeat,
+	};
+	let gradient_brush = |kind: peniko::GradientKind, extend: peniko::Extend| {
+		peniko::Brush::Gradient(peniko::Gradient {
+			kind,
+			extend,
+			stops: peniko_stops.clone(),
+			interpolation_alpha_space: peniko::InterpolationAlphaSpace::Premultiplied,
+			..Default::default()
+		})
+	};
+	let unclipped = |brush: peniko::Brush| GradientBrush {
+		brush,
+		brush_to_gradient: DAffine2::IDENTITY,
+		clip: None,
+	};
+
+	// The unit gradient line is the +X unit vector, and for radial gradients the unit-radius circle at the origin
+	match gradient_type {
+		GradientType::Linear => vec![unclipped(gradient_brush(
+			peniko::LinearGradientPosition {
+				start: kurbo::Point::ZERO,
+				end: kurbo::Point::new(1., 0.),
+			}
+			.into(),
+			extend,
+		))],
+		GradientType::Radial => vec![unclipped(gradient_brush(
+			peniko::RadialGradientPosition {
+				start_center: kurbo::Point::ZERO,
+				start_radius: 0.,
+				end_center: kurbo::Point::ZERO,
+				end_radius: 1.,
+			}
+			.into(),
+			extend,
+		))],
+		GradientType::Conic => vec![unclipped(gradient_brush(
+			peniko::SweepGradientPosition {
+				center: kurbo::Point::ZERO,
+				start_angle: 0.,
+				end_angle: std::f32::consts::TAU,
+			}
+			.into(),
+			peniko::Extend::Pad,
+		))],
+		GradientType::Diamond => {
+			// Within each quadrant the diamond is a linear gradient running diagonally, so it's drawn exactly by four of them
+			let Some([min, max]) = coverage_in_gradient_space(gradient_to_device, coverage) else {
+				return Vec::new();
+			};
+			let extent = min.abs().max(max.abs());
+
+			diamond_quadrants(extent)
+				.into_iter()
+				.map(|(end, rect)| GradientBrush {
+					brush: gradient_brush(
+						peniko::LinearGradientPosition {
+							start: kurbo::Point::ZERO,
+							end: kurbo::Point::new(end.x, end.y),
+						}
+						.into(),
+						extend,
+					),
+					brush_to_gradient: DAffine2::IDENTITY,
+					clip: Some(rect),
+				})
+				.collect()
+		}
+		GradientType::Mesh => {
+			let Some(mesh) = gradient_mesh(gradient_list, index) else { return Vec::new() };
+			let Some([min, max]) = mesh.bounding_box() else { return Vec::new() };
+			let size = max - min;
+			if size.x <= 0. || size.y <= 0. {
+				return Vec::new();
+			}
+
+			// Match the displayed size of the mesh, so it's rasterized no finer than needed
+			let displayed_size = |axis: DVec2| gradient_to_device.transform_vector2(axis).length().ceil() as u32;
+			let width = displayed_size(DVec2::X * size.x).clamp(2, MESH_VELLO_MAX_RESOLUTION);
+			let height = displayed_size(DVec2::Y * size.y).clamp(2, MESH_VELLO_MAX_RESOLUTION);
+			let image = rasterize_mesh(&mesh, [min, max], width, height);
+
+			let brush = peniko::ImageBrush::new(peniko::ImageData {
+				data: image.to_flat_u8().0.into(),
+				format: peniko::ImageFormat::Rgba8,
+				width,
+				height,
+				alpha_type: peniko::ImageAlphaType::Alpha,
+			});
+			vec![GradientBrush {
+				brush: peniko::Brush::Image(brush),
+				brush_to_gradient: DAffine2::from_translation(min) * DAffine2::from_scale(size / DVec2::new(width as f64, height as f64)),
+				clip: Some(kurbo::Rect::new(min.x, min.y, max.x, max.y)),
+			}]
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// The corners of an area of the gradient's unit space, reaching further from the center on some sides than others.
+	const COVERAGE: [DVec2; 4] = [DVec2::new(-1., -0.5), DVec2::new(2., -0.5), DVec2::new(2., 1.5), DVec2::new(-1., 1.5)];
+
+	fn gradient_list(gradient_type: GradientType) -> List<Gradient> {
+		let mut gradient_list = List::new_from_element(Gradient::default());
+		gradient_list.set_attribute(ATTR_GRADIENT_TYPE, 0, gradient_type);
+		gradient_list
+	}
+
+	fn svg_defs(gradient_list: &List<Gradient>) -> String {
+		let mut svg_defs = String::new();
+		write_svg_pattern(&mut svg_defs, gradient_list, 0, "", DAffine2::IDENTITY, COVERAGE);
+		svg_defs
+	}
+
+	/// The value of a numeric attribute on the first element with the given opening, like `<pattern`.
+	fn attribute(svg: &str, element: &str, name: &str) -> f64 {
+		let tag = &svg[svg.find(element).expect("The element should be written")..];
+		let tag = &tag[..tag.find('>').unwrap()];
+		let value = &tag[tag.find(&format!(r#" {name}=""#)).expect("The attribute should be written") + name.len() + 3..];
+		value[..value.find('"').unwrap()].parse().unwrap()
+	}
+
+	#[test]
+	fn stop_samples_blend_with_premultiplied_alpha() {
+		let red = Color::from_gamma_srgb_channels(1., 0., 0., 1.);
+		let transparent_blue = Color::from_gamma_srgb_channels(0., 0., 1., 0.);
+		let samples = [(0.25, red, None), (0.75, transparent_blue, None)];
+
+		assert_eq!(sample_stops(&samples, 0.), red, "Positions before the first sample take its color");
+		assert_eq!(sample_stops(&samples, 1.), transparent_blue, "Positions after the last sample take its color");
+
+		// The transparent blue adds no blue, so halfway only the alpha is halved
+		let [red_channel, green, blue, alpha] = sample_stops(&samples, 0.5).to_gamma_srgb_channels();
+		assert!((red_channel - 1.).abs() < 1e-4 && green.abs() < 1e-4 && blue.abs() < 1e-4);
+		assert!((alpha - 0.5).abs() < 1e-4);
+	}
+
+	#[test]
+	fn conic_wedges_sample_the_angle_around_the_center() {
+		let bounds = coverage_in_gradient_space(DAffine2::IDENTITY, COVERAGE).unwrap();
+		let wedges: Vec<_> = conic_wedges(bounds).collect();
+		assert_eq!(wedges.len(), CONIC_SVG_WEDGES);
+
+		// Like Vello's sweep gradient, the position is the angle from the +X axis toward the +Y axis as a fraction of a turn
+		for &([start, end], position) in &wedges {
+			let middle = (start + end) / 2.;
+			assert!((middle.y.atan2(middle.x).rem_euclid(TAU) / TAU - position).abs() < 1e-9);
+		}
+
+		// Every point of the area is within the wedge at its angle, so none is left unpainted
+		for x in 0..=30 {
+			for y in 0..=20 {
+				let point = bounds[0] + (bounds[1] - bounds[0]) * DVec2::new(x as f64 / 30., y as f64 / 20.);
+				let wedge = ((point.y.atan2(point.x).rem_euclid(TAU) / TAU * CONIC_SVG_WEDGES as f64) as usize).min(CONIC_SVG_WEDGES - 1);
+				let [start, end] = wedges[wedge].0;
+				assert!((end - start).perp_dot(point - start) >= -1e-9, "{point} should be on the center's side of its wedge's outer edge");
+			}
+		}
+	}
+
+	#[test]
+	fn conic_svg_fallback_fills_each_wedge_with_its_sampled_color() {
+		let svg_defs = svg_defs(&gradient_list(GradientType::Conic));
+		assert_eq!(svg_defs.matches("<polygon").count(), CONIC_SVG_WEDGES);
+
+		let samples = Gradient::default().interpolated_samples();
+		let bounds = coverage_in_gradient_space(DAffine2::IDENTITY, COVERAGE).unwrap();
+		for ([start, end], position) in conic_wedges(bounds) {
+			let fill = format_color_attributes("fill", sample_stops(&samples, position));
+			assert!(svg_defs.contains(&format!(r#"<polygon points="0,0 {},{} {},{}"{fill} />"#, start.x, start.y, end.x, end.y)));
+		}
+
+		let tile = ["x", "y", "width", "height"].map(|name| attribute(&svg_defs, "<pattern", name));
+		assert_eq!(
+			tile,
+			[bounds[0].x, bounds[0].y, bounds[1].x - bounds[0].x, bounds[1].y - bounds[0].y],
+			"The pattern's tile should be the area"
+		);
+	}
+
+	#[test]
+	fn diamond_quadrants_sample_the_distance_from_the_center() {
+		let quadrants = diamond_quadrants(DVec2::new(2., 1.5));
+
+		for x in -19..20 {
+			for y in -14..15 {
+				let point = DVec2::new(x as f64, y as f64) / 10.;
+				let covering: Vec<_> = quadrants.iter().filter(|(_, rect)| rect.contains(kurbo::Point::new(point.x, point.y))).collect();
+				assert!(!covering.is_empty(), "{point} should be covered by a quadrant");
+
+				// The diamond's position is the distance from the center measured along the axes, which each quadrant's linear gradient projects onto its line
+				for (end, _) in covering {
+					let position = point.dot(*end) / end.length_squared();
+					assert!((position - (point.x.abs() + point.y.abs())).abs() < 1e-12);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn diamond_is_drawn_by_the_same_quadrants_in_svg_and_vello() {
+		let gradient_list = gradient_list(GradientType::Diamond);
+		let [min, max] = coverage_in_gradient_space(DAffine2::IDENTITY, COVERAGE).unwrap();
+		let quadrants = diamond_quadrants(min.abs().max(max.abs()));
+
+		let brushes = gradient_brushes(&gradient_list, 0, GradientType::Diamond, DAffine2::IDENTITY, COVERAGE);
+		let clips: Vec<_> = brushes.iter().map(|brush| brush.clip).collect();
+		assert_eq!(clips, quadrants.map(|(_, rect)| Some(rect)));
+
+		let svg_defs = svg_defs(&gradient_list);
+		assert_eq!(svg_defs.matches("<linearGradient").count(), 4);
+		for (end, rect) in quadrants {
+			assert!(svg_defs.contains(&format!(r#"x1="0" y1="0" x2="{}" y2="{}""#, end.x, end.y)));
+			assert!(svg_defs.contains(&format!(r#"<rect x="{}" y="{}" width="{}" height="{}""#, rect.x0, rect.y0, rect.width(), rect.height())));
+		}
+	}
+
+	#[test]
+	fn mesh_falls_back_to_one_built_from_the_stops() {
+		let mut gradient_list = gradient_list(GradientType::Mesh);
+		let from_stops = GradientMesh::from(&Gradient::default());
+		assert_eq!(gradient_mesh(&gradient_list, 0), Some(from_stops.clone()), "A gradient without a mesh draws one built from its stops");
+
+		let mut invalid = GradientMesh::from_gradient(&Gradient::default(), 3, 1);
+		invalid.colors.pop();
+		gradient_list.set_attribute(ATTR_GRADIENT_MESH, 0, invalid);
+		assert_eq!(
+			gradient_mesh(&gradient_list, 0),
+			Some(from_stops),
+			"A mesh missing a vertex color is replaced by one built from the stops"
+		);
+
+		let valid = GradientMesh::from_gradient(&Gradient::default(), 3, 1);
+		gradient_list.set_attribute(ATTR_GRADIENT_MESH, 0, valid.clone());
+		assert_eq!(gradient_mesh(&gradient_list, 0), Some(valid));
+	}
+
+	#[test]
+	fn mesh_svg_fallback_places_the_rasterized_mesh_over_its_bounds() {
+		let mut mesh = GradientMesh::from_gradient(&Gradient::default(), 1, 1);
+		mesh.points = vec![DVec2::new(-2., -1.), DVec2::new(3., -1.), DVec2::new(-2., 2.), DVec2::new(3., 2.)];
+		let mut gradient_list = gradient_list(GradientType::Mesh);
+		gradient_list.set_attribute(ATTR_GRADIENT_MESH, 0, mesh);
+
+		let svg_defs = svg_defs(&gradient_list);
+		let image = ["x", "y", "width", "height"].map(|name| attribute(&svg_defs, "<image", name));
+		assert_eq!(image, [-2., -1., 5., 3.]);
+
+		// The mesh reaches past the area on every side, so the tile is the mesh's bounds and it isn't repeated
+		let tile = ["x", "y", "width", "height"].map(|name| attribute(&svg_defs, "<pattern", name));
+		assert_eq!(tile, image);
+	}
+
+	#[test]
+	fn rasterized_mesh_is_transparent_outside_it() {
+		let mut mesh = GradientMesh::from_gradient(&Gradient::default(), 1, 1);
+		mesh.points = vec![DVec2::new(0., 0.), DVec2::new(1., 0.), DVec2::new(0., 2.), DVec2::new(1., 2.)];
+		mesh.colors = vec![Color::BLACK, Color::WHITE, Color::BLACK, Color::WHITE];
+
+		// The mesh covers the left half of the area, blending from black to white across it
+		let image = rasterize_mesh(&mesh, [DVec2::ZERO, DVec2::splat(2.)], 4, 4);
+		for y in 0..4 {
+			for x in 0..4 {
+				let pixel = image.data[y * 4 + x];
+				match x {
+					0 | 1 => {
+						assert_eq!(pixel.a(), 1.);
+						let expected = if x == 0 { 0.25 } else { 0.75 };
+						assert!((pixel.to_gamma_srgb_channels()[0] - expected).abs() < 1e-4);
+					}
+					_ => assert_eq!(pixel, Color::TRANSPARENT),
+				}
+			}
+		}
+	}
+}
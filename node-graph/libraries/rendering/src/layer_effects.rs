@@ -0,0 +1,631 @@
+use crate::renderer::{LAZY_ARC_VEC_ZERO_U8, RenderContext, RenderParams, singular_values};
+use core_types::CacheHash;
+use core_types::bounds::RenderBoundingBox;
+use core_types::color::{Color, SRGBA8};
+use core_types::math::extremum::running_extremum;
+use core_types::uuid::generate_uuid;
+use glam::{DAffine2, DVec2, UVec2};
+use graphic_types::layer_effects::LayerEffect;
+use graphic_types::raster_types::Image;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Write;
+use std::hash::Hasher;
+use std::ops::Deref;
+use vello::{Scene, peniko};
+
+/// The largest width or height, in pixels, of the image each effect is drawn into by the Vello renderer. Effects spanning more pixels are drawn at a lower resolution.
+const MAX_EFFECT_IMAGE_SIZE: f64 = 4096.;
+
+/// How far beyond the bounds of the graphic its effects reach, in its local space.
+pub(crate) fn effects_reach(effects: &[LayerEffect]) -> f64 {
+	effects.iter().map(LayerEffect::reach).fold(0., f64::max)
+}
+
+/// Writes an SVG `<filter>` to the defs which draws the effects behind and inside the `SourceGraphic`, returning the URL to reference it by.
+///
+/// The filter works in the local space of the element it's applied to, where `bounds` are the bounds of the graphic.
+/// Colors are interpolated in sRGB to match the Vello renderer's compositing.
+pub(crate) fn write_svg_filter(svg_defs: &mut String, effects: &[LayerEffect], bounds: RenderBoundingBox) -> Option<String> {
+	let region = match bounds {
+		RenderBoundingBox::None => return None,
+		RenderBoundingBox::Infinite => String::new(),
+		RenderBoundingBox::Rectangle([min, max]) => {
+			let reach = effects_reach(effects);
+			let (start, size) = (min - reach, max - min + 2. * reach);
+			format!(r#" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}""#, start.x, start.y, size.x, size.y)
+		}
+	};
+
+	let id = format!("effects-{}", generate_uuid());
+	let mut primitives = String::new();
+	for (index, effect) in effects.iter().enumerate() {
+		write_svg_effect(&mut primitives, effect, &format!("effect-{index}"));
+	}
+
+	let merge_node = |index: usize| format!(r#"<feMergeNode in="effect-{index}" />"#);
+	let outer = effects.iter().enumerate().filter(|(_, effect)| !effect.is_inner()).map(|(index, _)| merge_node(index));
+	let inner = effects.iter().enumerate().filter(|(_, effect)| effect.is_inner()).map(|(index, _)| merge_node(index));
+	let merge_nodes = outer.chain([r#"<feMergeNode in="SourceGraphic" />"#.to_string()]).chain(inner).collect::<String>();
+
+	write!(
+		svg_defs,
+		r#"<filter id="{id}"{region} color-interpolation-filters="sRGB">{primitives}<feMerge>{merge_nodes}</feMerge></filter>"#
+	)
+	.unwrap();
+
+	Some(format!("url(#{id})"))
+}
+
+/// Writes the filter primitives producing the tinted shape of one effect, ending with the result named `result`.
+fn write_svg_effect(primitives: &mut String, effect: &LayerEffect, result: &str) {
+	let mut input = "SourceAlpha".to_string();
+	let mut step = 0;
+	// Writes a primitive reading from the result of the previous one
+	let mut chain = |primitives: &mut String, input: &mut String, tag: &str, attributes: String, children: &str| {
+		let name = format!("{result}-{step}");
+		if children.is_empty() {
+			write!(primitives, r#"<{tag} in="{input}"{attributes} result="{name}" />"#).unwrap();
+		} else {
+			write!(primitives, r#"<{tag} in="{input}"{attributes} result="{name}">{children}</{tag}>"#).unwrap();
+		}
+		*input = name;
+		step += 1;
+	};
+
+	// Grow the alpha for outer effects, or shrink it for inner effects so the area around the graphic grows into it
+	if effect.spread > 0. {
+		let operator = if effect.is_inner() { "erode" } else { "dilate" };
+		chain(primitives, &mut input, "feMorphology", format!(r#" operator="{operator}" radius="{}""#, effect.spread), "");
+	}
+	if effect.offset != DVec2::ZERO {
+		chain(primitives, &mut input, "feOffset", format!(r#" dx="{}" dy="{}""#, effect.offset.x, effect.offset.y), "");
+	}
+
+	// Inner effects are cast by the area around the graphic, which is the inverse of its alpha
+	if effect.is_inner() {
+		chain(primitives, &mut input, "feComponentTransfer", String::new(), r#"<feFuncA type="table" tableValues="1 0" />"#);
+	}
+
+	let standard_deviation = effect.standard_deviation();
+	if standard_deviation > 0. {
+		chain(primitives, &mut input, "feGaussianBlur", format!(r#" stdDeviation="{standard_deviation}""#), "");
+	}
+
+	let tinted = if effect.is_inner() { format!("{result}-tinted") } else { result.to_string() };
+	write!(
+		primitives,
+		r##"<feFlood flood-color="#{}" flood-opacity="{}" /><feComposite in2="{input}" operator="in" result="{tinted}" />"##,
+		SRGBA8::from(effect.color).to_rgb_hex(),
+		(effect.color.a() * 1000.).round() / 1000.
+	)
+	.unwrap();
+
+	// Keep inner effects within the graphic
+	if effect.is_inner() {
+		write!(primitives, r#"<feComposite in="{tinted}" in2="SourceAlpha" operator="in" result="{result}" />"#).unwrap();
+	}
+}
+
+/// An effect drawn into a Vello scene as a placeholder image, whose pixels the GPU executor makes by rendering `scene` at the size of the image and running it through `filter`.
+#[derive(Clone)]
+pub struct EffectLayer {
+	/// The placeholder drawn into the scene in place of the effect.
+	pub image: peniko::ImageBrush,
+	/// The graphic casting the effect, moved by its offset and drawn in the pixel space of the image.
+	pub scene: Scene,
+	pub size: UVec2,
+	pub filter: EffectFilter,
+	/// Effect layers with the same key have the same pixels, so the GPU executor can reuse them while the graphic, its placement, and the filter are unchanged.
+	/// `None` when the graphic draws GPU textures, whose contents can change without the graphic changing.
+	pub key: Option<u64>,
+}
+
+impl std::fmt::Debug for EffectLayer {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("EffectLayer").field("size", &self.size).field("filter", &self.filter).finish_non_exhaustive()
+	}
+}
+
+/// The steps of an effect's SVG filter which follow moving the graphic by its offset, applied to the image of an [`EffectLayer`]. Lengths are in pixels of the image.
+#[derive(Clone, Copy, Debug, PartialEq, CacheHash)]
+pub struct EffectFilter {
+	/// How far the alpha is grown, or shrunk for inner effects, like the radius of `feMorphology`.
+	pub spread: f64,
+	/// The standard deviation of the blur, like that of `feGaussianBlur`.
+	pub standard_deviation: f64,
+	/// Whether the effect is cast by the area around the graphic, which is the inverse of its alpha.
+	pub inner: bool,
+	/// The color the alpha is tinted with, whose alpha sets the opacity of the effect.
+	pub color: Color,
+}
+
+impl EffectFilter {
+	/// The filter of the effect when a unit of its local space spans `pixels_per_unit` pixels.
+	pub fn new(effect: &LayerEffect, pixels_per_unit: f64) -> Self {
+		Self {
+			spread: effect.spread.max(0.) * pixels_per_unit,
+			standard_deviation: effect.standard_deviation() * pixels_per_unit,
+			inner: effect.is_inner(),
+			color: effect.color,
+		}
+	}
+
+	/// Makes the tinted effect from the alpha of the image of its graphic. Like the SVG filter, everything beyond the image is transparent.
+	pub fn apply(&self, graphic: &Image<Color>) -> Image<Color> {
+		let (width, height) = (graphic.width as usize, graphic.height as usize);
+		let mut alpha: Vec<f32> = graphic.data.iter().map(Color::a).collect();
+
+		// `feMorphology` works in whole pixels
+		let radius = self.spread.round() as usize;
+		if radius > 0 && width > 0 && height > 0 {
+			let extremum = if self.inner { f32::min } else { f32::max };
+			alpha = separable(&alpha, width, height, |line| running_extremum(line, radius, 0., extremum));
+		}
+
+		// The area around the graphic also covers everything beyond the image
+		let outside = if self.inner {
+			alpha.iter_mut().for_each(|alpha| *alpha = 1. - *alpha);
+			1.
+		} else {
+			0.
+		};
+
+		if self.standard_deviation > 0. && width > 0 && height > 0 {
+			alpha = separable(&alpha, width, height, |line| gaussian_blur(line, self.standard_deviation, outside));
+		}
+
+		let color = self.color;
+		Image {
+			width: graphic.width,
+			height: graphic.height,
+			data: alpha
+				.into_iter()
+				.map(|alpha| Color::new_from_unassociated_rgba(color.r(), color.g(), color.b(), color.a() * alpha))
+				.collect(),
+			base64_string: None,
+		}
+	}
+}
+
+/// Where the image of an effect covering an area of the scene is drawn: its size, and the transform from its pixels to the scene.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct EffectImagePlacement {
+	size: UVec2,
+	image_to_scene: DAffine2,
+	/// How many pixels of the image a unit of the scene spans.
+	pixels_per_unit: f64,
+}
+
+impl EffectImagePlacement {
+	fn new([min, max]: [DVec2; 2], pixels_per_unit: f64) -> Option<Self> {
+		let extent = max - min;
+		if !extent.is_finite() || extent.max_element() <= 0. {
+			return None;
+		}
+
+		// Line the pixels up with those of the scene, unless that makes the image too large
+		let pixels_per_unit = pixels_per_unit.min(MAX_EFFECT_IMAGE_SIZE / extent.max_element());
+		let start = (min * pixels_per_unit).floor();
+		let end = (max * pixels_per_unit).ceil();
+
+		Some(Self {
+			size: (end - start).as_uvec2().max(UVec2::ONE),
+			image_to_scene: DAffine2::from_scale(DVec2::splat(1. / pixels_per_unit)) * DAffine2::from_translation(start),
+			pixels_per_unit,
+		})
+	}
+}
+
+/// How many pixels a unit of the scene spans once it's displayed, or one for renders without a viewport like exports.
+fn scene_pixels_per_unit(render_params: &RenderParams) -> f64 {
+	[render_params.viewport_zoom, render_params.scale]
+		.into_iter()
+		.filter(|factor| factor.is_finite() && *factor > 0.)
+		.product()
+}
+
+/// Draws the graphic with its effects into the Vello scene, calling `draw` to draw the graphic at a given transform.
+///
+/// Vello has no filters, so each effect is drawn as an [`EffectLayer`]: the offset graphic is drawn into a scene of its own covering the effect at the
+/// resolution it's displayed at, which the GPU executor renders and runs through the same steps as the SVG filter before drawing it into this scene.
+/// `bounds` are the bounds of the graphic in the space of the scene, and `graphic` is what `draw` draws, which identifies the pixels of its effects.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_to_vello(
+	scene: &mut Scene,
+	graphic: &impl CacheHash,
+	effects: &[LayerEffect],
+	transform: DAffine2,
+	bounds: [DVec2; 2],
+	render_params: &RenderParams,
+	context: &mut RenderContext,
+	mut draw: impl FnMut(&mut Scene, DAffine2, &mut RenderContext),
+) {
+	for effect in effects.iter().filter(|effect| !effect.is_inner()) {
+		render_effect_to_vello(scene, graphic, effect, transform, bounds, render_params, context, &mut draw);
+	}
+
+	draw(scene, transform, context);
+
+	for effect in effects.iter().filter(|effect| effect.is_inner()) {
+		render_effect_to_vello(scene, graphic, effect, transform, bounds, render_params, context, &mut draw);
+	}
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_effect_to_vello(
+	scene: &mut Scene,
+	graphic: &impl CacheHash,
+	effect: &LayerEffect,
+	transform: DAffine2,
+	bounds: [DVec2; 2],
+	render_params: &RenderParams,
+	context: &mut RenderContext,
+	draw: &mut impl FnMut(&mut Scene, DAffine2, &mut RenderContext),
+) {
+	let scale = singular_values(transform).0;
+	let reach = effect.reach() * scale;
+	let Some(placement) = EffectImagePlacement::new([bounds[0] - reach, bounds[1] + reach], scene_pixels_per_unit(render_params)) else {
+		return;
+	};
+
+	// Nested effects of the graphic are pushed to the context first, so they're ready by the time this one is drawn
+	let (resource_overrides, effect_layers) = (context.resource_overrides.len(), context.effect_layers.len());
+	let mut effect_scene = Scene::new();
+	let graphic_transform = placement.image_to_scene.inverse() * transform * DAffine2::from_translation(effect.offset);
+	draw(&mut effect_scene, graphic_transform, context);
+
+	// Fills with an infinite transform cover the whole image, like they cover the viewport once the main scene is rendered
+	let cover_image = vello_encoding::Transform::from_kurbo(&kurbo::Affine::scale_non_uniform(placement.size.x as f64, placement.size.y as f64));
+	for transform in effect_scene.encoding_mut().transforms.iter_mut() {
+		if !transform.matrix[0].is_finite() {
+			*transform = cover_image;
+		}
+	}
+
+	let image = peniko::ImageBrush::new(peniko::ImageData {
+		data: peniko::Blob::new(LAZY_ARC_VEC_ZERO_U8.deref().clone()),
+		format: peniko::ImageFormat::Rgba8,
+		width: placement.size.x,
+		height: placement.size.y,
+		alpha_type: peniko::ImageAlphaType::Alpha,
+	});
+	let filter = EffectFilter::new(effect, scale * placement.pixels_per_unit);
+
+	// Drawing GPU textures or nested effects that can't be reused leaves nothing to identify the pixels by
+	let reusable = context.resource_overrides.len() == resource_overrides && context.effect_layers[effect_layers..].iter().all(|effect_layer| effect_layer.key.is_some());
+	let key = reusable.then(|| {
+		let mut hasher = DefaultHasher::new();
+		graphic.cache_hash(&mut hasher);
+		render_params.cache_hash(&mut hasher);
+		graphic_transform.cache_hash(&mut hasher);
+		placement.size.cache_hash(&mut hasher);
+		filter.cache_hash(&mut hasher);
+		hasher.finish()
+	});
+
+	context.effect_layers.push(EffectLayer {
+		image: image.clone(),
+		scene: effect_scene,
+		size: placement.size,
+		filter,
+		key,
+	});
+
+	let image_transform = kurbo::Affine::new(placement.image_to_scene.to_cols_array());
+	if effect.is_inner() {
+		// Keep the effect within the graphic
+		let rect = kurbo::Rect::new(bounds[0].x, bounds[0].y, bounds[1].x, bounds[1].y);
+		let identity = kurbo::Affine::IDENTITY;
+		scene.push_layer(peniko::Fill::NonZero, peniko::Mix::Normal, 1., identity, &rect);
+		draw(scene, transform, context);
+		scene.push_layer(peniko::Fill::NonZero, peniko::BlendMode::new(peniko::Mix::Normal, peniko::Compose::SrcIn), 1., identity, &rect);
+		scene.draw_image(&image, image_transform);
+		scene.pop_layer();
+		scene.pop_layer();
+	} else {
+		scene.draw_image(&image, image_transform);
+	}
+}
+
+/// Applies a filter to each row and then to each column of the values of an image.
+fn separable(values: &[f32], width: usize, height: usize, filter: impl Fn(&[f32]) -> Vec<f32>) -> Vec<f32> {
+	let rows: Vec<f32> = values.chunks_exact(width).flat_map(&filter).collect();
+	let columns: Vec<f32> = transpose(&rows, width, height).chunks_exact(height).flat_map(&filter).collect();
+	transpose(&columns, height, width)
+}
+
+fn transpose(values: &[f32], width: usize, height: usize) -> Vec<f32> {
+	(0..width * height).map(|index| values[(index % height) * width + index / height]).collect()
+}
+
+/// The value at the index, or `outside` beyond the values.
+fn sample(values: &[f32], index: isize, outside: f32) -> f32 {
+	usize::try_from(index).ok().and_then(|index| values.get(index)).copied().unwrap_or(outside)
+}
+
+/// Blurs the values like `feGaussianBlur`: with the Gaussian kernel for a standard deviation below two, and otherwise with the three box blurs the SVG specification approximates it by.
+fn gaussian_blur(values: &[f32], standard_deviation: f64, outside: f32) -> Vec<f32> {
+	if standard_deviation < 2. {
+		let radius = (3. * standard_deviation).ceil() as isize;
+		let weights: Vec<f64> = (-radius..=radius)
+			.map(|offset| (-(offset * offset) as f64 / (2. * standard_deviation * standard_deviation)).exp())
+			.collect();
+		let total: f64 = weights.iter().sum();
+
+		return (0..values.len() as isize)
+			.map(|index| {
+				let sum: f64 = (-radius..=radius).zip(&weights).map(|(offset, weight)| weight * sample(values, index + offset, outside) as f64).sum();
+				(sum / total) as f32
+			})
+			.collect();
+	}
+
+	let size = (standard_deviation * 3. * (2. * std::f64::consts::PI).sqrt() / 4. + 0.5).floor() as usize;
+	let half = size / 2;
+	let boxes = if size % 2 == 1 {
+		[(half, half); 3]
+	} else {
+		// Two boxes centered on the boundaries before and after each value, and one a value longer centered on it
+		[(half, half - 1), (half - 1, half), (half, half)]
+	};
+
+	boxes.into_iter().fold(values.to_vec(), |values, (before, after)| box_blur(&values, before, after, outside))
+}
+
+/// Averages the window of values from `before` values before each one to `after` values after it.
+fn box_blur(values: &[f32], before: usize, after: usize, outside: f32) -> Vec<f32> {
+	let value = |index: isize| sample(values, index, outside) as f64;
+	let length = (before + after + 1) as f64;
+
+	// Slide a running sum along the values
+	let mut sum: f64 = (-(before as isize)..=after as isize).map(value).sum();
+	let mut blurred = Vec::with_capacity(values.len());
+	for index in 0..values.len() as isize {
+		blurred.push((sum / length) as f32);
+		sum += value(index + after as isize + 1) - value(index - before as isize);
+	}
+
+	blurred
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphic_types::layer_effects::LayerEffectType;
+
+	const SIZE: usize = 24;
+
+	/// An opaque square in the middle of a transparent image.
+	fn square(square: std::ops::Range<usize>) -> Image<Color> {
+		let mut image = Image::new(SIZE as u32, SIZE as u32, Color::TRANSPARENT);
+		for y in square.clone() {
+			for x in square.clone() {
+				image.data[y * SIZE + x] = Color::BLACK;
+			}
+		}
+		image
+	}
+
+	fn alpha(image: &Image<Color>) -> Vec<f32> {
+		image.data.iter().map(Color::a).collect()
+	}
+
+	/// What `feMorphology` computes: the extremum of the square window around each pixel, where transparency lies beyond the image.
+	fn reference_morphology(alpha: &[f32], radius: isize, pick: fn(f32, f32) -> f32) -> Vec<f32> {
+		let at = |x: isize, y: isize| {
+			if (0..SIZE as isize).contains(&x) && (0..SIZE as isize).contains(&y) {
+				alpha[y as usize * SIZE + x as usize]
+			} else {
+				0.
+			}
+		};
+		(0..SIZE * SIZE)
+			.map(|index| {
+				let (x, y) = ((index % SIZE) as isize, (index / SIZE) as isize);
+				let window = (-radius..=radius).flat_map(|dy| (-radius..=radius).map(move |dx| (x + dx, y + dy)));
+				window.map(|(x, y)| at(x, y)).reduce(pick).unwrap()
+			})
+			.collect()
+	}
+
+	/// Convolves the alpha with a kernel along both axes, where `outside` lies beyond the image.
+	fn convolve(alpha: &[f32], kernel: &[(isize, f64)], outside: f32) -> Vec<f32> {
+		let at = |x: isize, y: isize| {
+			if (0..SIZE as isize).contains(&x) && (0..SIZE as isize).contains(&y) {
+				alpha[y as usize * SIZE + x as usize]
+			} else {
+				outside
+			}
+		};
+		let total: f64 = kernel.iter().map(|(_, weight)| weight).sum();
+
+		(0..SIZE * SIZE)
+			.map(|index| {
+				let (x, y) = ((index % SIZE) as isize, (index / SIZE) as isize);
+				let mut sum = 0.;
+				for (dy, row_weight) in kernel {
+					for (dx, column_weight) in kernel {
+						sum += row_weight * column_weight * at(x + dx, y + dy) as f64;
+					}
+				}
+				(sum / (total * total)) as f32
+			})
+			.collect()
+	}
+
+	/// A Gaussian wide enough to reach across the whole image.
+	fn gaussian(alpha: &[f32], standard_deviation: f64, outside: f32) -> Vec<f32> {
+		let kernel: Vec<(isize, f64)> = (-(SIZE as isize)..=SIZE as isize)
+			.map(|offset| (offset, (-(offset * offset) as f64 / (2. * standard_deviation * standard_deviation)).exp()))
+			.collect();
+		convolve(alpha, &kernel, outside)
+	}
+
+	/// What the SVG specification says `feGaussianBlur` does for a standard deviation of two or more: three box blurs of the size `d`.
+	fn reference_box_blurs(alpha: &[f32], d: isize, outside: f32) -> Vec<f32> {
+		let box_kernel = |range: std::ops::RangeInclusive<isize>| range.map(|offset| (offset, 1.)).collect::<Vec<_>>();
+		let boxes = if d % 2 == 1 {
+			vec![box_kernel(-d / 2..=d / 2); 3]
+		} else {
+			vec![box_kernel(-d / 2..=d / 2 - 1), box_kernel(-d / 2 + 1..=d / 2), box_kernel(-d / 2..=d / 2)]
+		};
+		boxes.iter().fold(alpha.to_vec(), |alpha, kernel| convolve(&alpha, kernel, outside))
+	}
+
+	fn max_difference(a: &[f32], b: &[f32]) -> f32 {
+		a.iter().zip(b).map(|(a, b)| (a - b).abs()).fold(0., f32::max)
+	}
+
+	fn filter(spread: f64, standard_deviation: f64, inner: bool) -> EffectFilter {
+		EffectFilter {
+			spread,
+			standard_deviation,
+			inner,
+			color: Color::BLACK,
+		}
+	}
+
+	#[test]
+	fn spread_matches_fe_morphology() {
+		let mut graphic = square(8..16);
+		graphic.data[3 * SIZE + 20] = Color::BLACK;
+
+		let dilated = filter(3., 0., false).apply(&graphic);
+		assert_eq!(alpha(&dilated), reference_morphology(&alpha(&graphic), 3, f32::max));
+
+		// Inner effects are cast by the inverse of the eroded alpha
+		let eroded = filter(2., 0., true).apply(&graphic);
+		let expected: Vec<f32> = reference_morphology(&alpha(&graphic), 2, f32::min).iter().map(|alpha| 1. - alpha).collect();
+		assert_eq!(alpha(&eroded), expected);
+	}
+
+	#[test]
+	fn blur_matches_fe_gaussian_blur() {
+		let graphic = square(8..16);
+
+		// Small deviations use the Gaussian itself
+		let blurred = filter(0., 1.5, false).apply(&graphic);
+		assert!(max_difference(&alpha(&blurred), &gaussian(&alpha(&graphic), 1.5, 0.)) < 1e-3);
+
+		// Larger ones use the box blurs of the sizes the SVG specification gives, odd and even
+		for (standard_deviation, d) in [(2., 4), (2.5, 5), (3., 6), (5., 9)] {
+			let blurred = filter(0., standard_deviation, false).apply(&graphic);
+			let expected = reference_box_blurs(&alpha(&graphic), d, 0.);
+			assert!(max_difference(&alpha(&blurred), &expected) < 1e-5, "standard deviation {standard_deviation}");
+			assert!(max_difference(&alpha(&blurred), &gaussian(&alpha(&graphic), standard_deviation, 0.)) < 0.06);
+		}
+
+		// The area around the graphic continues beyond the image
+		let blurred = filter(0., 3., true).apply(&graphic);
+		let inverse: Vec<f32> = alpha(&graphic).iter().map(|alpha| 1. - alpha).collect();
+		assert!(max_difference(&alpha(&blurred), &reference_box_blurs(&inverse, 6, 1.)) < 1e-5);
+	}
+
+	#[test]
+	fn effect_is_tinted_with_its_color() {
+		let effect = EffectFilter {
+			color: Color::from_rgbaf32_unchecked(1., 0., 0., 0.5),
+			..filter(0., 0., false)
+		};
+		let tinted = effect.apply(&square(8..16));
+
+		assert_eq!(tinted.data[12 * SIZE + 12], Color::from_rgbaf32_unchecked(0.5, 0., 0., 0.5));
+		assert_eq!(tinted.data[0], Color::TRANSPARENT);
+	}
+
+	#[test]
+	fn svg_filter_has_the_same_lengths() {
+		let effect = LayerEffect {
+			effect_type: LayerEffectType::InnerShadow,
+			color: Color::BLACK,
+			offset: DVec2::new(2., 1.),
+			blur: 6.,
+			spread: 1.5,
+		};
+		let mut svg_defs = String::new();
+		write_svg_filter(&mut svg_defs, &[effect], RenderBoundingBox::Rectangle([DVec2::ZERO, DVec2::splat(10.)])).unwrap();
+
+		let attribute = |name: &str| -> f64 {
+			let start = svg_defs.find(&format!(r#"{name}=""#)).unwrap() + name.len() + 2;
+			svg_defs[start..].split('"').next().unwrap().parse().unwrap()
+		};
+		assert!(svg_defs.contains(r#"operator="erode""#));
+
+		// The SVG filter works in local space, which spans two pixels per unit here
+		let filter = EffectFilter::new(&effect, 2.);
+		assert_eq!(filter.spread, attribute("radius") * 2.);
+		assert_eq!(filter.standard_deviation, attribute("stdDeviation") * 2.);
+		assert!(filter.inner);
+	}
+
+	#[test]
+	fn effect_images_cover_whole_pixels() {
+		let placement = EffectImagePlacement::new([DVec2::new(0.3, -1.2), DVec2::new(10.1, 4.)], 2.).unwrap();
+		assert_eq!(placement.size, UVec2::new(21, 11));
+		assert_eq!(placement.image_to_scene.transform_point2(DVec2::ZERO), DVec2::new(0., -1.5));
+		assert_eq!(placement.image_to_scene.transform_point2(placement.size.as_dvec2()), DVec2::new(10.5, 4.));
+
+		// Huge effects are drawn at a lower resolution
+		let placement = EffectImagePlacement::new([DVec2::ZERO, DVec2::new(2. * MAX_EFFECT_IMAGE_SIZE, 10.)], 1.).unwrap();
+		assert_eq!(placement.size, UVec2::new(MAX_EFFECT_IMAGE_SIZE as u32, 5));
+
+		assert_eq!(EffectImagePlacement::new([DVec2::ZERO, DVec2::ZERO], 1.), None);
+	}
+
+	#[test]
+	fn effect_layers_are_keyed_by_what_they_draw() {
+		let effect = LayerEffect {
+			effect_type: LayerEffectType::DropShadow,
+			color: Color::BLACK,
+			offset: DVec2::new(2., 1.),
+			blur: 4.,
+			spread: 1.,
+		};
+		let key = |graphic: u64, transform: DAffine2, nested_key: Option<Option<u64>>| {
+			let mut context = RenderContext::default();
+			let draw = |_: &mut Scene, _: DAffine2, context: &mut RenderContext| {
+				if let Some(key) = nested_key {
+					let image = peniko::ImageBrush::new(peniko::ImageData {
+						data: peniko::Blob::new(LAZY_ARC_VEC_ZERO_U8.deref().clone()),
+						format: peniko::ImageFormat::Rgba8,
+						width: 1,
+						height: 1,
+						alpha_type: peniko::ImageAlphaType::Alpha,
+					});
+					context.effect_layers.push(EffectLayer {
+						image,
+						scene: Scene::new(),
+						size: UVec2::ONE,
+						filter: filter(0., 0., false),
+						key,
+					});
+				}
+			};
+			render_to_vello(
+				&mut Scene::new(),
+				&graphic,
+				&[effect],
+				transform,
+				[DVec2::ZERO, DVec2::splat(10.)],
+				&RenderParams::default(),
+				&mut context,
+				draw,
+			);
+
+			let effect_layer = context.effect_layers.iter().find(|effect_layer| effect_layer.filter.standard_deviation > 0.).unwrap();
+			effect_layer.key
+		};
+
+		let unchanged = key(1, DAffine2::IDENTITY, None);
+		assert!(unchanged.is_some());
+		assert_eq!(key(1, DAffine2::IDENTITY, None), unchanged);
+		assert!(key(1, DAffine2::IDENTITY, Some(Some(7))).is_some());
+
+		// A different graphic, or the same graphic drawn elsewhere, makes different pixels
+		assert_ne!(key(2, DAffine2::IDENTITY, None), unchanged);
+		assert_ne!(key(1, DAffine2::from_translation(DVec2::new(0.5, 0.)), None), unchanged);
+
+		// Nested effects which can't be reused make the effect drawn from them unusable too
+		assert_eq!(key(1, DAffine2::IDENTITY, Some(None)), None);
+	}
+}
@@ -1,6 +1,10 @@
 pub mod convert_usvg_path;
+mod gradient_paint;
+mod layer_effects;
+mod pattern_paint;
 pub mod render_ext;
 mod renderer;
 pub mod to_peniko;
 
+pub use layer_effects::{EffectFilter, EffectLayer};
 pub use renderer::*;
@@ -0,0 +1,209 @@
+use crate::Render;
+use crate::renderer::{RenderContext, RenderParams, transform_is_invertible};
+use core_types::blending::BlendMode;
+use core_types::bounds::{BoundingBox, RenderBoundingBox};
+use core_types::{ATTR_BLEND_MODE, ATTR_OPACITY, ATTR_OPACITY_FILL, ATTR_TRANSFORM, Color};
+use glam::{DAffine2, DVec2};
+use graphic_types::raster_types::Image;
+use graphic_types::{Graphic, PatternTiling};
+use std::ops::RangeInclusive;
+use vello::{Scene, peniko};
+
+/// The most tiles of a pattern drawn in the Vello renderer, beyond which they're far too small to make out individually.
+const MAX_VELLO_TILES: usize = 4096;
+/// The most pixels along each axis of the image brush an image tile is padded out to for its spacing in the Vello renderer.
+const MAX_VELLO_IMAGE_SIZE: u32 = 8192;
+
+/// Draws the tile graphic repeated across the grid laid out by the tiling into the Vello scene, which should already be clipped to the painted area.
+///
+/// `local_to_device` maps the local space of the painted path to the viewport, and `coverage` is the painted area's corners in viewport space.
+/// An image tile is drawn with a single repeating image brush, while any other graphic is drawn once for each cell covering the painted area.
+pub(crate) fn render_to_vello(scene: &mut Scene, tile: &Graphic, tiling: PatternTiling, local_to_device: DAffine2, coverage: [DVec2; 4], context: &mut RenderContext, render_params: &RenderParams) {
+	let RenderBoundingBox::Rectangle(tile_bounds) = tile.bounding_box(DAffine2::IDENTITY, false) else {
+		return;
+	};
+	let Some(cell_size) = tiling.cell_size(tile_bounds) else { return };
+	let pattern_to_device = local_to_device * tiling.pattern_to_local();
+	if !transform_is_invertible(pattern_to_device) {
+		return;
+	}
+
+	if let Some((brush, brush_to_pattern)) = image_brush(tile, tiling, cell_size) {
+		let [min, max] = corners_bounds(coverage);
+		let rect = kurbo::Rect::new(min.x, min.y, max.x, max.y);
+		let brush_transform = kurbo::Affine::new((pattern_to_device * brush_to_pattern).to_cols_array());
+		scene.fill(peniko::Fill::NonZero, kurbo::Affine::IDENTITY, &peniko::Brush::Image(brush), Some(brush_transform), &rect);
+		return;
+	}
+
+	let Some((columns, rows)) = covering_cells(tile_bounds, cell_size, pattern_to_device, coverage) else {
+		return;
+	};
+	for (row, column) in rows.flat_map(|row| columns.clone().map(move |column| (row, column))).take(MAX_VELLO_TILES) {
+		tile.render_to_vello(scene, local_to_device * tiling.tile_to_local(tile_bounds, cell_size, column, row), context, render_params);
+	}
+}
+
+/// The columns and rows of the cells whose tiles overlap the painted area, including those overflowing into it from beyond when the spacing is negative.
+fn covering_cells(tile_bounds: [DVec2; 2], cell_size: DVec2, pattern_to_device: DAffine2, coverage: [DVec2; 4]) -> Option<(RangeInclusive<i64>, RangeInclusive<i64>)> {
+	let device_to_pattern = pattern_to_device.inverse();
+	let [min, max] = corners_bounds(coverage.map(|corner| device_to_pattern.transform_point2(corner)));
+	let tile_size = tile_bounds[1] - tile_bounds[0];
+	let first = ((min - tile_size) / cell_size).floor();
+	let last = (max / cell_size).floor();
+	if !first.is_finite() || !last.is_finite() {
+		return None;
+	}
+	Some(((first.x as i64)..=(last.x as i64), (first.y as i64)..=(last.y as i64)))
+}
+
+/// Builds a repeating image brush for a tile made of a single unrotated image without its own blending, along with the transform from its pixels into pattern space.
+///
+/// The image is padded with transparent pixels to leave the spacing between tiles, rounded to whole pixels, so overlapping tiles from a negative spacing can't be drawn with it.
+fn image_brush(tile: &Graphic, tiling: PatternTiling, cell_size: DVec2) -> Option<(peniko::ImageBrush, DAffine2)> {
+	let Graphic::RasterCPU(list) = tile else { return None };
+	if list.len() != 1 || tiling.spacing.min_element() < 0. {
+		return None;
+	}
+
+	let blend_mode: BlendMode = list.attribute_cloned_or_default(ATTR_BLEND_MODE, 0);
+	let opacity: f64 = list.attribute_cloned_or(ATTR_OPACITY, 0, 1.) * list.attribute_cloned_or(ATTR_OPACITY_FILL, 0, 1.);
+	let transform: DAffine2 = list.attribute_cloned_or_default(ATTR_TRANSFORM, 0);
+	let axis_aligned = transform.matrix2.x_axis.y == 0. && transform.matrix2.y_axis.x == 0. && transform.matrix2.x_axis.x > 0. && transform.matrix2.y_axis.y > 0.;
+	if blend_mode != BlendMode::default() || opacity < 1. || !axis_aligned {
+		return None;
+	}
+
+	let image = list.element(0)?;
+	if image.data.is_empty() {
+		return None;
+	}
+
+	let pixel_size = DVec2::new(transform.matrix2.x_axis.x, transform.matrix2.y_axis.y) / DVec2::new(image.width as f64, image.height as f64);
+	let padding = (tiling.spacing / pixel_size).round();
+	let padded_width = image.width as f64 + padding.x;
+	let padded_height = image.height as f64 + padding.y;
+	if padded_width > MAX_VELLO_IMAGE_SIZE as f64 || padded_height > MAX_VELLO_IMAGE_SIZE as f64 {
+		return None;
+	}
+	let (padded_width, padded_height) = (padded_width as u32, padded_height as u32);
+
+	let mut padded = Image::new(padded_width, padded_height, Color::TRANSPARENT);
+	for y in 0..image.height as usize {
+		let (source, target) = (y * image.width as usize, y * padded_width as usize);
+		padded.data[target..target + image.width as usize].copy_from_slice(&image.data[source..source + image.width as usize]);
+	}
+
+	let brush = peniko::ImageBrush::new(peniko::ImageData {
+		data: padded.to_flat_u8().0.into(),
+		format: peniko::ImageFormat::Rgba8,
+		width: padded_width,
+		height: padded_height,
+		alpha_type: peniko::ImageAlphaType::Alpha,
+	})
+	.with_extend(peniko::Extend::Repeat);
+
+	// Stretch the padded image over the cell, which it only differs from by the rounding of the padding
+	Some((brush, DAffine2::from_scale(cell_size / DVec2::new(padded_width as f64, padded_height as f64))))
+}
+
+fn corners_bounds(corners: [DVec2; 4]) -> [DVec2; 2] {
+	let min = corners.into_iter().reduce(DVec2::min).unwrap_or_default();
+	let max = corners.into_iter().reduce(DVec2::max).unwrap_or_default();
+	[min, max]
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::render_ext::render_svg_tiled_pattern;
+	use graphic_types::Vector;
+	use graphic_types::vector_types::subpath::Subpath;
+	use graphic_types::vector_types::vector::PointId;
+
+	/// The bounds of the tile graphic, a 4x2 rectangle away from the origin.
+	const TILE_BOUNDS: [DVec2; 2] = [DVec2::new(2., 3.), DVec2::new(6., 5.)];
+
+	fn tile() -> Graphic {
+		Vector::from_subpath(Subpath::<PointId>::new_rectangle(TILE_BOUNDS[0], TILE_BOUNDS[1])).into()
+	}
+
+	/// A tiling exercising every part of the layout: spacing, offset, scale and rotation.
+	fn tiling() -> PatternTiling {
+		PatternTiling {
+			transform: DAffine2::from_angle(0.3) * DAffine2::from_scale(DVec2::new(1.5, 0.5)),
+			spacing: DVec2::new(1., 2.),
+			offset: DVec2::new(0.5, -1.),
+		}
+	}
+
+	/// The value of an attribute on the first element with the given opening, like `<pattern`.
+	fn attribute<'a>(svg: &'a str, element: &str, name: &str) -> &'a str {
+		let tag = &svg[svg.find(element).expect("The element should be written")..];
+		let tag = &tag[..tag.find('>').unwrap()];
+		let value = &tag[tag.find(&format!(r#" {name}=""#)).expect("The attribute should be written") + name.len() + 3..];
+		&value[..value.find('"').unwrap()]
+	}
+
+	fn parse_matrix(matrix: &str) -> DAffine2 {
+		let values: Vec<f64> = matrix.trim_start_matches("matrix(").trim_end_matches(')').split(',').map(|value| value.parse().unwrap()).collect();
+		DAffine2::from_cols_array(&values.try_into().unwrap())
+	}
+
+	#[test]
+	fn svg_pattern_places_tiles_where_vello_draws_them() {
+		let (tile, tiling) = (tile(), tiling());
+		let stroke_transform = DAffine2::from_scale_angle_translation(DVec2::splat(2.), -0.1, DVec2::new(10., 20.));
+
+		let mut svg_defs = String::new();
+		render_svg_tiled_pattern(&mut svg_defs, &tile, tiling, stroke_transform, &RenderParams::default()).unwrap();
+
+		// The pattern's cell is the grid's cell
+		let cell_size = tiling.cell_size(TILE_BOUNDS).unwrap();
+		let pattern_size = DVec2::new(attribute(&svg_defs, "<pattern", "width").parse().unwrap(), attribute(&svg_defs, "<pattern", "height").parse().unwrap());
+		assert_eq!(pattern_size, cell_size);
+
+		// SVG repeats the first cell's content in every cell of pattern space, which must land on the tile Vello draws for that cell
+		let pattern_transform = parse_matrix(attribute(&svg_defs, "<pattern", "patternTransform"));
+		let content_shift = parse_matrix(attribute(&svg_defs, "<g", "transform"));
+		for (column, row) in [(0, 0), (1, 0), (0, 1), (-3, 2), (4, -5)] {
+			let svg_tile = pattern_transform * DAffine2::from_translation(DVec2::new(column as f64, row as f64) * pattern_size) * content_shift;
+			let vello_tile = stroke_transform * tiling.tile_to_local(TILE_BOUNDS, cell_size, column, row);
+			assert!(svg_tile.abs_diff_eq(vello_tile, 1e-6), "The tile in cell ({column}, {row}) should be placed the same in SVG and Vello");
+		}
+	}
+
+	#[test]
+	fn vello_draws_every_tile_overlapping_the_painted_area() {
+		let local_to_device = DAffine2::from_scale_angle_translation(DVec2::splat(2.), -0.1, DVec2::new(10., 20.));
+		let coverage = [DVec2::new(0., 0.), DVec2::new(120., 0.), DVec2::new(120., 80.), DVec2::new(0., 80.)];
+		let tile_size = TILE_BOUNDS[1] - TILE_BOUNDS[0];
+
+		// Overlapping tiles from a negative spacing reach into the painted area from cells further away
+		for spacing in [DVec2::new(1., 2.), DVec2::new(-2.5, -1.5)] {
+			let tiling = PatternTiling { spacing, ..tiling() };
+			let cell_size = tiling.cell_size(TILE_BOUNDS).unwrap();
+			let pattern_to_device = local_to_device * tiling.pattern_to_local();
+			let (columns, rows) = covering_cells(TILE_BOUNDS, cell_size, pattern_to_device, coverage).unwrap();
+
+			let device_to_pattern = pattern_to_device.inverse();
+			for x in 0..=60 {
+				for y in 0..=40 {
+					let point = device_to_pattern.transform_point2(DVec2::new(x as f64 * 2., y as f64 * 2.));
+					let nearest = (point / cell_size).floor();
+					for column in (nearest.x as i64 - 3)..=(nearest.x as i64 + 3) {
+						for row in (nearest.y as i64 - 3)..=(nearest.y as i64 + 3) {
+							let within_tile = point - DVec2::new(column as f64, row as f64) * cell_size;
+							if within_tile.cmpge(DVec2::ZERO).all() && within_tile.cmple(tile_size).all() {
+								assert!(
+									columns.contains(&column) && rows.contains(&row),
+									"The tile in cell ({column}, {row}) covers {point} and should be drawn"
+								);
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+}
@@ -1,13 +1,15 @@
+use crate::gradient_paint;
 use crate::renderer::{RenderParams, format_transform_matrix, gradient_placement, transform_is_invertible};
 use crate::{Render, RenderSvgSegmentList, SvgRender};
+use core_types::bounds::{BoundingBox, RenderBoundingBox};
 use core_types::color::SRGBA8;
 use core_types::list::List;
 use core_types::uuid::generate_uuid;
-use core_types::{ATTR_GRADIENT_TYPE, ATTR_SPREAD_METHOD, ATTR_TRANSFORM, Color};
+use core_types::{ATTR_GRADIENT_TYPE, ATTR_PATTERN, ATTR_SPREAD_METHOD, ATTR_TRANSFORM, Color};
 use glam::{DAffine2, DVec2};
-use graphic_types::Graphic;
 use graphic_types::vector_types::gradient::GradientType;
 use graphic_types::vector_types::vector::style::{PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+use graphic_types::{Graphic, PatternTiling};
 use std::fmt::Write;
 use vector_types::Gradient;
 use vector_types::gradient::GradientSpreadMethod;
@@ -85,8 +87,8 @@ impl RenderExt for List<Gradient> {
 		svg_defs: &mut String,
 		item_transform: DAffine2,
 		element_transform: DAffine2,
-		_stroke_transform: DAffine2,
-		_bounds: DAffine2,
+		stroke_transform: DAffine2,
+		bounds: DAffine2,
 		_render_params: &RenderParams,
 		_target: PaintTarget,
 	) -> Self::Output {
@@ -152,6 +154,11 @@ impl RenderExt for List<Gradient> {
 					gradient_id, stop
 				);
 			}
+			// SVG has no paint server for these types, so they're drawn by a pattern reaching across the painted area (the item's bounds in the paint's user space)
+			GradientType::Conic | GradientType::Diamond | GradientType::Mesh => {
+				let coverage = [DVec2::ZERO, DVec2::X, DVec2::ONE, DVec2::Y].map(|corner| (stroke_transform * bounds).transform_point2(corner));
+				return gradient_paint::write_svg_pattern(svg_defs, self, 0, &stop, element_transform_inverse * placement, coverage);
+			}
 		}
 
 		gradient_id
@@ -242,28 +249,32 @@ impl RenderExt for List<Graphic> {
 				format!(r##" {paint_attr}="url(#{gradient_id})""##)
 			}
 			Some(Graphic::None) => format!(r#" {paint_attr}="none""#),
-			Some(Graphic::Vector(_)) | Some(Graphic::RasterCPU(_)) | Some(Graphic::RasterGPU(_)) | Some(Graphic::Graphic(_)) | Some(Graphic::Text(_)) => {
-				let bounds = if target == PaintTarget::Stroke {
-					// To prevent a wraparound artefact occurring when the tile boundary and the stroke region are perfectly aligned, the local coordinate is expanded slightly.
-					let inverse = |len: f64| if len > 0. { 1. / len } else { 0. };
-					let inflate = DVec2::new(inverse(item_transform.matrix2.x_axis.length()), inverse(item_transform.matrix2.y_axis.length()));
-					let min = bounds.transform_point2(DVec2::ZERO) - inflate;
-					let max = bounds.transform_point2(DVec2::ONE) + inflate;
-					DAffine2::from_scale_angle_translation(max - min, 0., min)
+			Some(tile @ (Graphic::Vector(_) | Graphic::RasterCPU(_) | Graphic::RasterGPU(_) | Graphic::Graphic(_) | Graphic::Text(_))) => {
+				let pattern_id = if let Some(&tiling) = self.attribute::<PatternTiling>(ATTR_PATTERN, 0) {
+					render_svg_tiled_pattern(svg_defs, tile, tiling, stroke_transform, render_params)
 				} else {
-					bounds
+					let bounds = if target == PaintTarget::Stroke {
+						// To prevent a wraparound artefact occurring when the tile boundary and the stroke region are perfectly aligned, the local coordinate is expanded slightly.
+						let inverse = |len: f64| if len > 0. { 1. / len } else { 0. };
+						let inflate = DVec2::new(inverse(item_transform.matrix2.x_axis.length()), inverse(item_transform.matrix2.y_axis.length()));
+						let min = bounds.transform_point2(DVec2::ZERO) - inflate;
+						let max = bounds.transform_point2(DVec2::ONE) + inflate;
+						DAffine2::from_scale_angle_translation(max - min, 0., min)
+					} else {
+						bounds
+					};
+					render_svg_pattern(svg_defs, self, stroke_transform, bounds, render_params)
 				};
-				render_svg_pattern(svg_defs, self, stroke_transform, bounds, render_params)
-					.map(|id| format!(r##" {paint_attr}="url(#{id})""##))
-					.unwrap_or_else(|| format!(r#" {paint_attr}="none""#))
+
+				pattern_id.map(|id| format!(r##" {paint_attr}="url(#{id})""##)).unwrap_or_else(|| format!(r#" {paint_attr}="none""#))
 			}
 			None => format!(r#" {paint_attr}="none""#),
 		}
 	}
 }
 
-/// Emits an SVG `<pattern>` paint server into `svg_defs` that renders the given graphic list as the paint content, and returns the pattern ID.
-/// Currently, this function is only used for clipping-based filling and stroking, not considering tiling yet.
+/// Emits an SVG `<pattern>` paint server into `svg_defs` that renders the given graphic list once over the paint target's bounds, and returns the pattern ID.
+/// This is used for clipping-based filling and stroking by paints without a `PatternTiling`, which are tiled by `render_svg_tiled_pattern` instead.
 fn render_svg_pattern(svg_defs: &mut String, fill_graphic_list: &List<Graphic>, stroke_transform: DAffine2, bounds: DAffine2, render_params: &RenderParams) -> Option<String> {
 	let min = bounds.transform_point2(DVec2::ZERO);
 	let max = bounds.transform_point2(DVec2::ONE);
@@ -300,3 +311,36 @@ fn render_svg_pattern(svg_defs: &mut String, fill_graphic_list: &List<Graphic>,
 
 	Some(pattern_id)
 }
+
+/// Emits an SVG `<pattern>` paint server into `svg_defs` that repeats the tile graphic across a grid laid out by the tiling, and returns the pattern ID.
+pub(crate) fn render_svg_tiled_pattern(svg_defs: &mut String, tile: &Graphic, tiling: PatternTiling, stroke_transform: DAffine2, render_params: &RenderParams) -> Option<String> {
+	let RenderBoundingBox::Rectangle(tile_bounds) = tile.bounding_box(DAffine2::IDENTITY, false) else {
+		return None;
+	};
+	let cell_size = tiling.cell_size(tile_bounds)?;
+
+	let mut content = SvgRender::new();
+	tile.render_svg(&mut content, &render_params.for_pattern());
+	write!(svg_defs, "{}", content.svg_defs).unwrap();
+
+	let transform_str = format_transform_matrix(stroke_transform * tiling.pattern_to_local());
+	let transform_attr = if transform_str.is_empty() {
+		String::new()
+	} else {
+		format!(r#" patternTransform="{transform_str}""#)
+	};
+
+	let pattern_id = format!("pattern-{}", generate_uuid());
+	write!(
+		svg_defs,
+		r##"<pattern id="{pattern_id}" patternUnits="userSpaceOnUse" x="0" y="0" width="{}" height="{}"{transform_attr}>"##,
+		cell_size.x, cell_size.y,
+	)
+	.unwrap();
+
+	// Place the tile graphic in the top left corner of the first cell
+	let content_shift = format_transform_matrix(DAffine2::from_translation(-tile_bounds[0]));
+	write!(svg_defs, r##"<g transform="{content_shift}">{}</g></pattern>"##, content.svg.to_svg_string()).unwrap();
+
+	Some(pattern_id)
+}
@@ -1,3 +1,6 @@
+use crate::gradient_paint;
+use crate::layer_effects::EffectLayer;
+use crate::pattern_paint;
 use crate::render_ext::{PaintTarget, RenderExt};
 use crate::to_peniko::{BlendModeExt, ToPenikoColor};
 use core_types::CacheHash;
@@ -14,20 +17,21 @@ use core_types::transform::Footprint;
 use core_types::uuid::{NodeId, generate_uuid};
 use core_types::{
 	ATTR_BACKGROUND, ATTR_BLEND_MODE, ATTR_CLIP, ATTR_CLIPPING_MASK, ATTR_DIMENSIONS, ATTR_EDITOR_CLICK_TARGET, ATTR_EDITOR_LAYER_PATH, ATTR_EDITOR_MERGED_LAYERS, ATTR_EDITOR_TEXT_FRAME, ATTR_FONT,
-	ATTR_FONT_SIZE, ATTR_GRADIENT_TYPE, ATTR_LETTER_SPACING, ATTR_LETTER_TILT, ATTR_LINE_HEIGHT, ATTR_LOCATION, ATTR_MAX_HEIGHT, ATTR_MAX_WIDTH, ATTR_OPACITY, ATTR_OPACITY_FILL, ATTR_SPREAD_METHOD,
-	ATTR_TEXT_ALIGN, ATTR_TRANSFORM,
+	ATTR_FONT_SIZE, ATTR_GRADIENT_TYPE, ATTR_LAYER_EFFECTS, ATTR_LETTER_SPACING, ATTR_LETTER_TILT, ATTR_LINE_HEIGHT, ATTR_LOCATION, ATTR_MAX_HEIGHT, ATTR_MAX_WIDTH, ATTR_OPACITY, ATTR_OPACITY_FILL,
+	ATTR_PATTERN, ATTR_SPREAD_METHOD, ATTR_TEXT_ALIGN, ATTR_TRANSFORM,
 };
 use dyn_any::DynAny;
 use glam::{DAffine2, DMat2, DVec2};
 use graphene_hash::CacheHashWrapper;
 use graphene_resource::Resource;
-use graphic_types::graphic::{graphic_list_at, has_paint_at, is_paint_present, set_paint_attribute};
+use graphic_types::graphic::{graphic_list_at, has_paint_at, is_paint_present, set_paint_attribute, solidify_stroke_item};
+use graphic_types::layer_effects::LayerEffect;
 use graphic_types::raster_types::{BitmapMut, CPU, GPU, Image, Raster, Texture};
 use graphic_types::vector_types::gradient::{Gradient, GradientType};
 use graphic_types::vector_types::subpath::Subpath;
 use graphic_types::vector_types::vector::click_target::{ClickTarget, FreePoint};
 use graphic_types::vector_types::vector::style::{PaintOrder, RenderMode, StrokeAlign, StrokeCap, StrokeJoin};
-use graphic_types::{Artboard, Graphic, Vector};
+use graphic_types::{Artboard, Graphic, PatternTiling, Vector};
 use kurbo::{Affine, BezPath, Cap, Join, Shape, StrokeOpts};
 use num_traits::Zero;
 use skrifa::instance::{LocationRef, NormalizedCoord, Size};
@@ -199,6 +203,8 @@ impl Default for SvgRender {
 #[derive(Clone, Debug, Default)]
 pub struct RenderContext {
 	pub resource_overrides: Vec<(peniko::ImageBrush, Texture)>,
+	/// Layer effects drawn into the scene as placeholder images, in the order they need to be rendered so nested effects come first.
+	pub effect_layers: Vec<EffectLayer>,
 }
 
 #[derive(Default, Clone, Copy, Hash, graphene_hash::CacheHash)]
@@ -269,7 +275,7 @@ pub fn format_transform_matrix(transform: DAffine2) -> String {
 /// `(max, min)` factors by which a unit vector is stretched under `transform`'s linear part — the
 /// principal and minor singular values, equal to the semi-axes of the ellipse a unit circle maps to.
 /// Equivalent to `(max(sx, sy), min(sx, sy))` for axis-aligned scales, but accounts for shear.
-fn singular_values(transform: DAffine2) -> (f64, f64) {
+pub(crate) fn singular_values(transform: DAffine2) -> (f64, f64) {
 	let m = transform.matrix2;
 	let a = m.x_axis.x;
 	let b = m.x_axis.y;
@@ -310,10 +316,6 @@ pub fn to_transform(transform: DAffine2) -> usvg::Transform {
 	usvg::Transform::from_row(cols[0] as f32, cols[1] as f32, cols[2] as f32, cols[3] as f32, cols[4] as f32, cols[5] as f32)
 }
 
-fn to_point(p: DVec2) -> kurbo::Point {
-	kurbo::Point::new(p.x, p.y)
-}
-
 fn get_outline_styles(render_params: &RenderParams) -> (kurbo::Stroke, peniko::Color) {
 	use core_types::consts::LAYER_OUTLINE_STROKE_WEIGHT;
 
@@ -375,13 +377,13 @@ pub(crate) fn transform_is_invertible(transform: DAffine2) -> bool {
 	transform.matrix2.determinant().recip().is_finite()
 }
 
-/// Maps a gradient's `transform` into the frame handed to the renderer: radial keeps the full matrix (so a
-/// non-uniform transform makes an ellipse), while linear is reduced to the equivalent non-sheared gradient line (the
-/// axis projected onto the band normal) so the iso-color bands keep following a sheared transform, which Vello can
-/// represent since it stores only two endpoints.
+/// Maps a gradient's `transform` into the frame handed to the renderer: radial, conic, diamond, and mesh keep the full
+/// matrix (so a non-uniform transform makes an ellipse), while linear is reduced to the equivalent non-sheared gradient
+/// line (the axis projected onto the band normal) so the iso-color bands keep following a sheared transform, which
+/// Vello can represent since it stores only two endpoints.
 pub(crate) fn gradient_placement(transform: DAffine2, gradient_type: GradientType) -> DAffine2 {
 	match gradient_type {
-		GradientType::Radial => transform,
+		GradientType::Radial | GradientType::Conic | GradientType::Diamond | GradientType::Mesh => transform,
 		GradientType::Linear => {
 			let axis = transform.matrix2.x_axis;
 			let band_normal = transform.matrix2.y_axis.perp();
@@ -394,52 +396,6 @@ pub(crate) fn gradient_placement(transform: DAffine2, gradient_type: GradientTyp
 	}
 }
 
-fn create_peniko_gradient_brush(gradient_list: &List<Gradient>, multiplied_transform: &DAffine2) -> Option<(peniko::Brush, DAffine2)> {
-	let stops = gradient_list.element(0)?;
-
-	let gradient_type: GradientType = gradient_list.attribute_cloned_or_default(ATTR_GRADIENT_TYPE, 0);
-	let gradient_transform: DAffine2 = gradient_list.attribute_cloned_or_default(ATTR_TRANSFORM, 0);
-	let spread_method: GradientSpreadMethod = gradient_list.attribute_cloned_or_default(ATTR_SPREAD_METHOD, 0);
-
-	let mut peniko_stops = peniko::ColorStops::new();
-	for (position, color, _) in stops.interpolated_samples() {
-		peniko_stops.push(peniko::ColorStop {
-			offset: position as f32,
-			color: peniko::color::DynamicColor::from_alpha_color(SRGBA8::from(color).to_peniko_color()),
-		});
-	}
-
-	// The unit gradient is placed by the desheared frame so a non-uniform transform produces the intended ellipse
-	let (start, end, gradient_to_device) = (DVec2::ZERO, DVec2::X, gradient_placement(multiplied_transform * gradient_transform, gradient_type));
-
-	let brush = peniko::Brush::Gradient(peniko::Gradient {
-		kind: match gradient_type {
-			GradientType::Linear => peniko::LinearGradientPosition {
-				start: to_point(start),
-				end: to_point(end),
-			}
-			.into(),
-			GradientType::Radial => peniko::RadialGradientPosition {
-				start_center: to_point(start),
-				start_radius: 0.,
-				end_center: to_point(start),
-				end_radius: start.distance(end) as f32,
-			}
-			.into(),
-		},
-		extend: match spread_method {
-			GradientSpreadMethod::Pad => peniko::Extend::Pad,
-			GradientSpreadMethod::Reflect => peniko::Extend::Reflect,
-			GradientSpreadMethod::Repeat => peniko::Extend::Repeat,
-		},
-		stops: peniko_stops,
-		interpolation_alpha_space: peniko::InterpolationAlphaSpace::Premultiplied,
-		..Default::default()
-	});
-
-	Some((brush, gradient_to_device))
-}
-
 // TODO: Click targets can be removed from the render output, since the vector data is available in the vector modify data from Monitor nodes.
 // This will require that the transform for child layers into that layer space be calculated, or it could be returned from the RenderOutput instead of click targets.
 #[derive(Debug, Default, Clone, PartialEq, DynAny)]
@@ -843,6 +799,7 @@ impl Render for List<Graphic> {
 			let blend_mode: BlendMode = self.attribute_cloned_or_default(ATTR_BLEND_MODE, index);
 			let opacity_attr: f64 = self.attribute_cloned_or(ATTR_OPACITY, index, 1.);
 			let opacity_fill_attr: f64 = self.attribute_cloned_or(ATTR_OPACITY_FILL, index, 1.);
+			let effects: Vec<LayerEffect> = self.attribute_cloned_or_default(ATTR_LAYER_EFFECTS, index);
 			let element = self.element(index).unwrap();
 
 			render.parent_tag(
@@ -862,6 +819,13 @@ impl Render for List<Graphic> {
 						attributes.push("style", blend_mode.render());
 					}
 
+					if !effects.is_empty() && !render_params.for_mask && render_params.render_mode != RenderMode::Outline {
+						let bounds = element.bounding_box(DAffine2::IDENTITY, true);
+						if let Some(filter) = crate::layer_effects::write_svg_filter(&mut attributes.0.svg_defs, &effects, bounds) {
+							attributes.push("filter", filter);
+						}
+					}
+
 					let next_clips = index + 1 < self.len() && self.element(index + 1).unwrap().had_clip_enabled();
 
 					if next_clips && mask_state.is_none() {
@@ -900,10 +864,29 @@ impl Render for List<Graphic> {
 			let blend_mode_attr: BlendMode = self.attribute_cloned_or_default(ATTR_BLEND_MODE, index);
 			let opacity_attr: f64 = self.attribute_cloned_or(ATTR_OPACITY, index, 1.);
 			let opacity_fill_attr: f64 = self.attribute_cloned_or(ATTR_OPACITY_FILL, index, 1.);
+			let effects: Vec<LayerEffect> = self.attribute_cloned_or_default(ATTR_LAYER_EFFECTS, index);
 			let element = self.element(index).unwrap();
 
 			let mut layer = false;
 
+			// Layer effects draw beyond the bounds of the element, so the layers it's drawn into are grown to fit them
+			let has_effects = !effects.is_empty() && !render_params.for_mask && render_params.render_mode != RenderMode::Outline;
+			let scale = singular_values(transform).0;
+			let effects_bounds = |bounds: RenderBoundingBox| match bounds {
+				RenderBoundingBox::Rectangle([min, max]) if has_effects => {
+					let reach = crate::layer_effects::effects_reach(&effects) * scale;
+					RenderBoundingBox::Rectangle([min - reach, max + reach])
+				}
+				bounds => bounds,
+			};
+			let element_bounds = if has_effects { element.bounding_box(transform, true) } else { RenderBoundingBox::None };
+			let render_element = |scene: &mut Scene, context: &mut RenderContext| match element_bounds {
+				RenderBoundingBox::Rectangle(bounds) => crate::layer_effects::render_to_vello(scene, element, &effects, transform, bounds, render_params, context, |scene, transform, context| {
+					element.render_to_vello(scene, transform, context, render_params)
+				}),
+				_ => element.render_to_vello(scene, transform, context, render_params),
+			};
+
 			let blend_mode = match render_params.render_mode {
 				RenderMode::Outline => peniko::Mix::Normal,
 				_ => blend_mode_attr.to_peniko(),
@@ -912,7 +895,7 @@ impl Render for List<Graphic> {
 
 			let opacity = (opacity_attr * if render_params.for_mask { 1. } else { opacity_fill_attr }) as f32;
 			if opacity < 1. || (render_params.render_mode != RenderMode::Outline && blend_mode_attr != BlendMode::default()) {
-				bounds = element.bounding_box(transform, true);
+				bounds = effects_bounds(element.bounding_box(transform, true));
 
 				if let RenderBoundingBox::Rectangle(bounds) = bounds {
 					scene.push_layer(
@@ -930,13 +913,13 @@ impl Render for List<Graphic> {
 			if next_clips && mask_element_and_transform.is_none() {
 				mask_element_and_transform = Some((element, transform));
 
-				element.render_to_vello(scene, transform, context, render_params);
+				render_element(scene, context);
 			} else if let Some((mask_element, transform_mask)) = mask_element_and_transform {
 				if !next_clips {
 					mask_element_and_transform = None;
 				}
 				if !layer {
-					bounds = element.bounding_box(transform, true);
+					bounds = effects_bounds(element.bounding_box(transform, true));
 				}
 
 				if let RenderBoundingBox::Rectangle(bounds) = bounds {
@@ -953,14 +936,14 @@ impl Render for List<Graphic> {
 					);
 				}
 
-				element.render_to_vello(scene, transform, context, render_params);
+				render_element(scene, context);
 
 				if matches!(bounds, RenderBoundingBox::Rectangle(_)) {
 					scene.pop_layer();
 					scene.pop_layer();
 				}
 			} else {
-				element.render_to_vello(scene, transform, context, render_params);
+				render_element(scene, context);
 			}
 
 			if layer {
@@ -1061,9 +1044,26 @@ impl Render for List<Graphic> {
 	}
 }
 
+/// Expands a vector item whose stroke varies in width along its path into its stroke outline and its fill, so the stroke is drawn by filling its outline.
+/// Returns `None` for an ordinary stroke, or in outline mode where only the path itself is drawn.
+fn solidify_width_profile_stroke(list: &List<Vector>, index: usize, render_params: &RenderParams) -> Option<List<Vector>> {
+	let stroke = list.element(index)?.stroke.as_ref()?;
+	if !stroke.has_width_profile() || !stroke.has_renderable_stroke() || render_params.render_mode == RenderMode::Outline {
+		return None;
+	}
+
+	let item = list.clone_item(index)?;
+	Some(solidify_stroke_item(item, has_paint_at(list, index, ATTR_FILL)).into_iter().collect())
+}
+
 impl Render for List<Vector> {
 	fn render_svg(&self, render: &mut SvgRender, render_params: &RenderParams) {
 		for index in 0..self.len() {
+			if let Some(solidified) = solidify_width_profile_stroke(self, index, render_params) {
+				solidified.render_svg(render, render_params);
+				continue;
+			}
+
 			let Some(vector) = self.element(index) else { continue };
 			let item_transform: DAffine2 = self.attribute_cloned_or_default(ATTR_TRANSFORM, index);
 			let blend_mode_attr: BlendMode = self.attribute_cloned_or_default(ATTR_BLEND_MODE, index);
@@ -1216,10 +1216,10 @@ impl Render for List<Vector> {
 					stroke_graphic_list
 						.as_deref()
 						.map(|list| {
-							// Gradient should align with the fill path bbox so that a shared gradient lines up across fill and stroke.
-							// Only clipping-based paints need the stroke-inclusive bbox.
+							// Gradients are placed by their own transform, so the bbox only sets how far the pattern drawing some gradient types must reach.
+							// Those and clipping-based paints need the stroke-inclusive bbox.
 							let paint_bounds = match list.element(0) {
-								Some(Graphic::Color(_)) | Some(Graphic::Gradient(_)) => bounds_matrix,
+								Some(Graphic::Color(_)) => bounds_matrix,
 								_ => stroke_bounds_matrix,
 							};
 							list.render(defs, item_transform, element_transform, applied_stroke_transform, paint_bounds, &render_params, PaintTarget::Stroke)
@@ -1280,6 +1280,11 @@ impl Render for List<Vector> {
 		for index in 0..self.len() {
 			use graphic_types::vector_types::vector;
 
+			if let Some(solidified) = solidify_width_profile_stroke(self, index, render_params) {
+				solidified.render_to_vello(scene, parent_transform, context, render_params);
+				continue;
+			}
+
 			let Some(element) = self.element(index) else { continue };
 			let item_transform: DAffine2 = self.attribute_cloned_or_default(ATTR_TRANSFORM, index);
 			let blend_mode_attr: BlendMode = self.attribute_cloned_or_default(ATTR_BLEND_MODE, index);
@@ -1364,21 +1369,21 @@ impl Render for List<Vector> {
 							scene.fill(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), &fill, None, path);
 						}
 						Graphic::Gradient(list) => {
-							let Some((brush, gradient_to_device)) = create_peniko_gradient_brush(list, &multiplied_transform) else {
-								continue;
-							};
+							let coverage = gradient_paint::transformed_corners(element_transform, path.bounding_box());
+							let (brushes, gradient_to_device) = gradient_paint::vello_brushes(list, 0, multiplied_transform, coverage);
 
-							let inverse_element_transform = if transform_is_invertible(element_transform) {
-								element_transform.inverse()
-							} else {
-								Default::default()
-							};
-							let brush_transform = kurbo::Affine::new((inverse_element_transform * gradient_to_device).to_cols_array());
-							scene.fill(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), &brush, Some(brush_transform), path);
+							gradient_paint::draw_vello_brushes(scene, &brushes, gradient_to_device, element_transform, |scene, brush, brush_transform| {
+								scene.fill(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), brush, Some(brush_transform), path);
+							});
 						}
 						Graphic::Vector(_) | Graphic::RasterCPU(_) | Graphic::RasterGPU(_) | Graphic::Graphic(_) | Graphic::Text(_) => {
 							scene.push_clip_layer(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), path);
-							paint.render_to_vello(scene, multiplied_transform, context, render_params);
+							if let Some(&tiling) = fill_graphic.attribute::<PatternTiling>(ATTR_PATTERN, paint_index) {
+								let coverage = gradient_paint::transformed_corners(element_transform, path.bounding_box());
+								pattern_paint::render_to_vello(scene, paint, tiling, multiplied_transform, coverage, context, render_params);
+							} else {
+								paint.render_to_vello(scene, multiplied_transform, context, render_params);
+							}
 							scene.pop_layer();
 						}
 					};
@@ -1447,23 +1452,25 @@ impl Render for List<Vector> {
 							scene.stroke(&stroke, kurbo::Affine::new(element_transform.to_cols_array()), &brush, None, &path);
 						}
 						Graphic::Gradient(list) => {
-							let Some((brush, gradient_to_device)) = create_peniko_gradient_brush(list, &multiplied_transform) else {
-								continue;
-							};
-							let inverse_element_transform = if transform_is_invertible(element_transform) {
-								element_transform.inverse()
-							} else {
-								Default::default()
-							};
-							let brush_transform = kurbo::Affine::new((inverse_element_transform * gradient_to_device).to_cols_array());
-
-							scene.stroke(&stroke, kurbo::Affine::new(element_transform.to_cols_array()), &brush, Some(brush_transform), &path);
+							// The stroke reaches at most half its width beyond the path, or further at its miter joins
+							let stroke_reach = stroke.width / 2. * stroke.miter_limit.max(1.);
+							let coverage = gradient_paint::transformed_corners(element_transform, path.bounding_box().inflate(stroke_reach, stroke_reach));
+							let (brushes, gradient_to_device) = gradient_paint::vello_brushes(list, 0, multiplied_transform, coverage);
+
+							gradient_paint::draw_vello_brushes(scene, &brushes, gradient_to_device, element_transform, |scene, brush, brush_transform| {
+								scene.stroke(&stroke, kurbo::Affine::new(element_transform.to_cols_array()), brush, Some(brush_transform), &path);
+							});
 						}
 						Graphic::Vector(_) | Graphic::RasterCPU(_) | Graphic::RasterGPU(_) | Graphic::Graphic(_) | Graphic::Text(_) => {
 							let stroked = peniko::kurbo::stroke(path.iter(), &stroke, &StrokeOpts::default(), 0.01);
 
 							scene.push_clip_layer(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), &stroked);
-							stroke_graphic.render_to_vello(scene, multiplied_transform, context, render_params);
+							if let Some(&tiling) = stroke_graphic_list.attribute::<PatternTiling>(ATTR_PATTERN, paint_index) {
+								let coverage = gradient_paint::transformed_corners(element_transform, stroked.bounding_box());
+								pattern_paint::render_to_vello(scene, stroke_graphic, tiling, multiplied_transform, coverage, context, render_params);
+							} else {
+								stroke_graphic.render_to_vello(scene, multiplied_transform, context, render_params);
+							}
 							scene.pop_layer();
 						}
 					};
@@ -1896,7 +1903,7 @@ impl Render for List<Raster<CPU>> {
 	}
 }
 
-static LAZY_ARC_VEC_ZERO_U8: LazyLock<Arc<Vec<u8>>> = LazyLock::new(|| Arc::new(Vec::new()));
+pub(crate) static LAZY_ARC_VEC_ZERO_U8: LazyLock<Arc<Vec<u8>>> = LazyLock::new(|| Arc::new(Vec::new()));
 
 impl Render for List<Raster<GPU>> {
 	fn render_svg(&self, _render: &mut SvgRender, _render_params: &RenderParams) {
@@ -2121,20 +2128,29 @@ impl Render for List<Gradient> {
 				};
 
 				// The unit gradient line is the +X unit vector in local space, before the item's transform is applied
-				match gradient_type {
+				let gradient_id = match gradient_type {
 					GradientType::Linear => {
 						let _ = write!(
 							&mut attributes.0.svg_defs,
 							r#"<linearGradient id="{gradient_id}" gradientUnits="userSpaceOnUse" x1="0" y1="0" x2="1" y2="0"{spread_method_attribute}{gradient_transform_attribute}>{stop_string}</linearGradient>"#
 						);
+						gradient_id
 					}
 					GradientType::Radial => {
 						let _ = write!(
 							&mut attributes.0.svg_defs,
 							r#"<radialGradient id="{gradient_id}" gradientUnits="userSpaceOnUse" cx="0" cy="0" r="1"{spread_method_attribute}{gradient_transform_attribute}>{stop_string}</radialGradient>"#
 						);
+						gradient_id
 					}
-				}
+					GradientType::Conic | GradientType::Diamond | GradientType::Mesh => {
+						// The pattern only needs to reach across the visible area rather than the whole stand-in for an infinite background
+						let (min, size) = thumbnail_rect.unwrap_or((DVec2::ZERO, render_params.footprint.resolution.as_dvec2()));
+						let coverage = gradient_paint::transformed_corners(DAffine2::IDENTITY, kurbo::Rect::new(min.x, min.y, min.x + size.x, min.y + size.y));
+						let placement = gradient_placement(gradient_transform, gradient_type);
+						gradient_paint::write_svg_pattern(&mut attributes.0.svg_defs, self, index, &stop_string, placement, coverage)
+					}
+				};
 
 				attributes.push("fill", format!("url('#{gradient_id}')"));
 
@@ -2157,60 +2173,20 @@ impl Render for List<Gradient> {
 			return;
 		}
 
-		for (((index, gradient), spread_method), gradient_type) in self
-			.iter_element_values()
-			.enumerate()
-			.zip(self.iter_attribute_values_or_default::<GradientSpreadMethod>(ATTR_SPREAD_METHOD))
-			.zip(self.iter_attribute_values_or_default::<GradientType>(ATTR_GRADIENT_TYPE))
-		{
-			let transform: DAffine2 = self.attribute_cloned_or_default(ATTR_TRANSFORM, index);
+		// The visible area of the viewport, which the gradients fill
+		let resolution = render_params.footprint.resolution.as_dvec2();
+		let coverage = gradient_paint::transformed_corners(DAffine2::IDENTITY, kurbo::Rect::new(0., 0., resolution.x, resolution.y));
+
+		for index in 0..self.len() {
 			let blend_mode_attr: BlendMode = self.attribute_cloned_or_default(ATTR_BLEND_MODE, index);
 			let opacity_attr: f64 = self.attribute_cloned_or(ATTR_OPACITY, index, 1.);
 			let opacity_fill_attr: f64 = self.attribute_cloned_or(ATTR_OPACITY_FILL, index, 1.);
-			let gradient_transform = parent_transform * transform;
 
 			let blend_mode = blend_mode_attr.to_peniko();
 			let opacity = (opacity_attr * if render_params.for_mask { 1. } else { opacity_fill_attr }) as f32;
 
-			let mut stops: peniko::ColorStops = peniko::ColorStops::new();
-			for (position, color, _) in gradient.interpolated_samples() {
-				stops.push(peniko::ColorStop {
-					offset: position as f32,
-					color: peniko::color::DynamicColor::from_alpha_color(SRGBA8::from(color).to_peniko_color()),
-				})
-			}
-
-			let extend = match spread_method {
-				GradientSpreadMethod::Pad => peniko::Extend::Pad,
-				GradientSpreadMethod::Reflect => peniko::Extend::Reflect,
-				GradientSpreadMethod::Repeat => peniko::Extend::Repeat,
-			};
-
-			// The unit gradient line is the +X unit vector in local space, before the item's transform is applied.
-			// For radial, the unit-radius circle at the origin scales out to the line's length once the brush transform applies.
-			let kind = match gradient_type {
-				GradientType::Linear => peniko::LinearGradientPosition {
-					start: to_point(DVec2::ZERO),
-					end: to_point(DVec2::X),
-				}
-				.into(),
-				GradientType::Radial => peniko::RadialGradientPosition {
-					start_center: to_point(DVec2::ZERO),
-					start_radius: 0.,
-					end_center: to_point(DVec2::ZERO),
-					end_radius: 1.,
-				}
-				.into(),
-			};
-
-			let fill = peniko::Brush::Gradient(peniko::Gradient {
-				kind,
-				stops,
-				extend,
-				interpolation_alpha_space: peniko::InterpolationAlphaSpace::Premultiplied,
-				..Default::default()
-			});
-			let brush_transform = kurbo::Affine::new(gradient_placement(gradient_transform, gradient_type).to_cols_array());
+			// The unit gradient line is the +X unit vector in local space, before the item's transform is applied
+			let (brushes, gradient_to_device) = gradient_paint::vello_brushes(self, index, parent_transform, coverage);
 			let rect = kurbo::Rect::from_origin_size(kurbo::Point::ZERO, kurbo::Size::new(1., 1.));
 
 			let mut layer = false;
@@ -2221,14 +2197,25 @@ impl Render for List<Gradient> {
 				layer = true;
 			}
 
-			// Encode shape and brush manually instead of Scene.fill(), which would multiply brush_transform by the path transform
-			scene.encoding_mut().encode_transform(vello_encoding::Transform::from_kurbo(&kurbo::Affine::scale(f64::INFINITY)));
-			scene.encoding_mut().encode_fill_style(peniko::Fill::NonZero);
-			scene.encoding_mut().encode_shape(&rect, true);
+			for gradient_paint::GradientBrush { brush, brush_to_gradient, clip } in &brushes {
+				let brush_to_device = kurbo::Affine::new((gradient_to_device * *brush_to_gradient).to_cols_array());
 
-			scene.encoding_mut().encode_transform(vello_encoding::Transform::from_kurbo(&brush_transform));
-			scene.encoding_mut().swap_last_path_tags();
-			scene.encoding_mut().encode_brush(&fill, 1.);
+				// A brush limited to a rectangle of the gradient is drawn by filling just that rectangle
+				if let Some(clip) = clip {
+					let brush_transform = kurbo::Affine::new(brush_to_gradient.to_cols_array());
+					scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(gradient_to_device.to_cols_array()), brush, Some(brush_transform), clip);
+					continue;
+				}
+
+				// Encode shape and brush manually instead of Scene.fill(), which would multiply brush_transform by the path transform
+				scene.encoding_mut().encode_transform(vello_encoding::Transform::from_kurbo(&kurbo::Affine::scale(f64::INFINITY)));
+				scene.encoding_mut().encode_fill_style(peniko::Fill::NonZero);
+				scene.encoding_mut().encode_shape(&rect, true);
+
+				scene.encoding_mut().encode_transform(vello_encoding::Transform::from_kurbo(&brush_to_device));
+				scene.encoding_mut().swap_last_path_tags();
+				scene.encoding_mut().encode_brush(brush, 1.);
+			}
 
 			if layer {
 				scene.pop_layer();
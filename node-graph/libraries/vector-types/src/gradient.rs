@@ -12,6 +12,9 @@ pub enum GradientType {
 	#[default]
 	Linear,
 	Radial,
+	Conic,
+	Diamond,
+	Mesh,
 }
 
 // TODO: Someday we could switch this to a Box[T] to avoid over-allocation
@@ -469,6 +472,180 @@ impl Gradient {
 	}
 }
 
+/// A grid of patches in a gradient's unit space, each blending the colors at its four corner vertices, which is drawn by [`GradientType::Mesh`] gradients.
+///
+/// Vertices are stored row by row, with `columns + 1` vertices in each of the `rows + 1` rows.
+#[derive(Debug, Clone, PartialEq, graphene_hash::CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientMesh {
+	/// The number of patches along each row.
+	pub columns: u32,
+	/// The number of rows of patches.
+	pub rows: u32,
+	/// The position of each vertex in the gradient's unit space.
+	pub points: Vec<DVec2>,
+	/// The color (linear, unassociated alpha) of each vertex.
+	pub colors: Vec<Color>,
+}
+
+impl Default for GradientMesh {
+	fn default() -> Self {
+		Self::from(&Gradient::default())
+	}
+}
+
+/// The mesh drawn for a mesh gradient that hasn't been given one: a 2x2 grid of patches colored by its stops.
+impl From<&Gradient> for GradientMesh {
+	fn from(gradient: &Gradient) -> Self {
+		Self::from_gradient(gradient, 2, 2)
+	}
+}
+
+impl GradientMesh {
+	/// Build a grid of evenly sized patches over the square that runs from 0 to 1 along the gradient line, reaching half as far to either side of it.
+	/// The vertices are colored by sampling the stops diagonally across the square, from its top left to bottom right corner.
+	pub fn from_gradient(gradient: &Gradient, columns: u32, rows: u32) -> Self {
+		let (columns, rows) = (columns.max(1), rows.max(1));
+
+		let (points, colors) = (0..=rows)
+			.flat_map(|row| (0..=columns).map(move |column| DVec2::new(column as f64 / columns as f64, row as f64 / rows as f64)))
+			.map(|uv| (DVec2::new(uv.x, uv.y - 0.5), gradient.evaluate((uv.x + uv.y) / 2.)))
+			.unzip();
+
+		Self { columns, rows, points, colors }
+	}
+
+	/// Whether there's a position and a color for every vertex of the grid.
+	pub fn is_valid(&self) -> bool {
+		let vertex_count = (self.columns as usize + 1) * (self.rows as usize + 1);
+		self.columns > 0 && self.rows > 0 && self.points.len() == vertex_count && self.colors.len() == vertex_count
+	}
+
+	/// The indices of the corner vertices of the patch at the given column and row, in order around it from the top left: top left, top right, bottom right, bottom left.
+	pub fn patch_vertices(&self, column: u32, row: u32) -> [usize; 4] {
+		let stride = self.columns as usize + 1;
+		let top_left = row as usize * stride + column as usize;
+		[top_left, top_left + 1, top_left + stride + 1, top_left + stride]
+	}
+
+	pub fn bounding_box(&self) -> Option<[DVec2; 2]> {
+		let first = *self.points.first()?;
+		Some(self.points.iter().fold([first, first], |[min, max], &point| [min.min(point), max.max(point)]))
+	}
+
+	/// The color at a point in the gradient's unit space, from the last patch covering it, or `None` outside the mesh.
+	///
+	/// Colors are blended in gamma-encoded sRGB, matching how the stops of the other gradient types are interpolated.
+	pub fn color_at(&self, point: DVec2) -> Option<Color> {
+		if !self.is_valid() {
+			return None;
+		}
+
+		let patches = (0..self.rows).rev().flat_map(|row| (0..self.columns).rev().map(move |column| (column, row)));
+		patches.into_iter().find_map(|(column, row)| {
+			let vertices = self.patch_vertices(column, row);
+			let (u, v) = inverse_bilinear(point, vertices.map(|index| self.points[index]))?;
+
+			let [top_left, top_right, bottom_right, bottom_left] = vertices.map(|index| self.colors[index].to_gamma_srgb_channels());
+			let [u, v] = [u as f32, v as f32];
+			let channel = |index: usize| {
+				let top = top_left[index] + (top_right[index] - top_left[index]) * u;
+				let bottom = bottom_left[index] + (bottom_right[index] - bottom_left[index]) * u;
+				top + (bottom - top) * v
+			};
+			Some(Color::from_gamma_srgb_channels(channel(0), channel(1), channel(2), channel(3)))
+		})
+	}
+}
+
+/// Changes made by hand to individual vertices of a [`GradientMesh`], kept apart from the grid so they survive the grid being rebuilt from new stops.
+///
+/// Each edit is keyed by the vertex's index, row by row, and edits to vertices the grid doesn't have are ignored.
+#[derive(Debug, Clone, Default, PartialEq, graphene_hash::CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientMeshEdits {
+	/// The moved vertices and their positions in the gradient's unit space, sorted by vertex index.
+	pub positions: Vec<(u32, DVec2)>,
+	/// The recolored vertices and their colors (linear, unassociated alpha), sorted by vertex index.
+	pub colors: Vec<(u32, Color)>,
+}
+
+impl GradientMeshEdits {
+	pub fn is_empty(&self) -> bool {
+		self.positions.is_empty() && self.colors.is_empty()
+	}
+
+	pub fn set_position(&mut self, vertex: u32, position: DVec2) {
+		Self::set(&mut self.positions, vertex, position);
+	}
+
+	pub fn set_color(&mut self, vertex: u32, color: Color) {
+		Self::set(&mut self.colors, vertex, color);
+	}
+
+	/// Return the vertex to the position and color the grid gives it.
+	pub fn reset(&mut self, vertex: u32) {
+		self.positions.retain(|&(index, _)| index != vertex);
+		self.colors.retain(|&(index, _)| index != vertex);
+	}
+
+	fn set<T>(edits: &mut Vec<(u32, T)>, vertex: u32, value: T) {
+		match edits.binary_search_by_key(&vertex, |&(index, _)| index) {
+			Ok(index) => edits[index].1 = value,
+			Err(index) => edits.insert(index, (vertex, value)),
+		}
+	}
+
+	/// Move and recolor the edited vertices of the mesh.
+	pub fn apply(&self, mesh: &mut GradientMesh) {
+		for &(vertex, position) in &self.positions {
+			if let Some(point) = mesh.points.get_mut(vertex as usize) {
+				*point = position;
+			}
+		}
+		for &(vertex, color) in &self.colors {
+			if let Some(vertex_color) = mesh.colors.get_mut(vertex as usize) {
+				*vertex_color = color;
+			}
+		}
+	}
+}
+
+/// Find the coordinates from 0 to 1 across a bilinear patch of a point inside it, given the corners of the patch in order around it from the one at `(0, 0)` to the one at `(1, 0)`.
+fn inverse_bilinear(point: DVec2, [a, b, c, d]: [DVec2; 4]) -> Option<(f64, f64)> {
+	const EPSILON: f64 = 1e-9;
+
+	let (e, f, g, h) = (b - a, d - a, a - b + c - d, point - a);
+	let (k2, k1, k0) = (g.perp_dot(f), e.perp_dot(f) + h.perp_dot(g), h.perp_dot(e));
+
+	// The second coordinate solves a quadratic, which becomes linear when the patch's top and bottom edges are parallel
+	let candidates = if k2.abs() < EPSILON {
+		if k1.abs() < EPSILON {
+			return None;
+		}
+		[-k0 / k1, f64::NAN]
+	} else {
+		let discriminant = k1 * k1 - 4. * k0 * k2;
+		if discriminant < 0. {
+			return None;
+		}
+		let root = discriminant.sqrt();
+		[(-k1 - root) / (2. * k2), (-k1 + root) / (2. * k2)]
+	};
+
+	let in_range = |value: f64| (-EPSILON..=1. + EPSILON).contains(&value);
+	candidates.into_iter().filter(|&v| in_range(v)).find_map(|v| {
+		// Solve for the first coordinate along whichever axis is better conditioned
+		let denominator = e + g * v;
+		let u = if denominator.x.abs() > denominator.y.abs() {
+			(h.x - f.x * v) / denominator.x
+		} else {
+			(h.y - f.y * v) / denominator.y
+		};
+		in_range(u).then_some((u.clamp(0., 1.), v.clamp(0., 1.)))
+	})
+}
+
 #[repr(C)]
 #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, graphene_hash::CacheHash, DynAny, node_macro::ChoiceType)]
@@ -575,3 +752,57 @@ impl core_types::bounds::BoundingBox for Gradient {
 		core_types::bounds::RenderBoundingBox::Rectangle([start.min(end), start.max(end)])
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn mesh_colors_blend_between_vertices() {
+		let mut mesh = GradientMesh::from_gradient(&Gradient::default(), 1, 1);
+		mesh.points = vec![DVec2::new(0., 0.), DVec2::new(2., 0.), DVec2::new(3., 2.), DVec2::new(-1., 2.)];
+		mesh.colors = vec![Color::BLACK, Color::WHITE, Color::WHITE, Color::BLACK];
+
+		for (point, color) in mesh.points.clone().into_iter().zip(mesh.colors.clone()) {
+			let sampled = mesh.color_at(point).unwrap();
+			assert!((sampled.r() - color.r()).abs() < 1e-4, "Vertex at {point} should keep its color");
+		}
+
+		// Halfway across the patch, the gamma-encoded value lands halfway between black and white
+		let middle = mesh.color_at(DVec2::new(1., 1.)).unwrap();
+		assert!((middle.to_gamma_srgb_channels()[0] - 0.5).abs() < 1e-4);
+
+		assert!(mesh.color_at(DVec2::new(-1., 0.5)).is_none());
+		assert!(mesh.color_at(DVec2::new(1., 2.5)).is_none());
+	}
+
+	#[test]
+	fn mesh_edits_replace_vertices() {
+		let mut edits = GradientMeshEdits::default();
+		edits.set_position(4, DVec2::new(0.6, 0.1));
+		edits.set_color(8, Color::RED);
+		edits.set_position(0, DVec2::new(-0.1, -0.6));
+		edits.set_position(4, DVec2::new(0.4, -0.1));
+		edits.set_color(9, Color::BLUE);
+		assert_eq!(
+			edits.positions,
+			vec![(0, DVec2::new(-0.1, -0.6)), (4, DVec2::new(0.4, -0.1))],
+			"Edits stay sorted and a later edit replaces an earlier one"
+		);
+
+		let mut mesh = GradientMesh::from_gradient(&Gradient::default(), 2, 2);
+		let original = mesh.clone();
+		edits.apply(&mut mesh);
+		assert_eq!(mesh.points[0], DVec2::new(-0.1, -0.6));
+		assert_eq!(mesh.points[4], DVec2::new(0.4, -0.1));
+		assert_eq!(mesh.colors[8], Color::RED);
+		assert_eq!(mesh.points[1..4], original.points[1..4]);
+		assert_eq!(mesh.colors[..8], original.colors[..8]);
+		assert_eq!(mesh.points.len(), 9, "The edit of a vertex past the end of the grid is ignored");
+
+		edits.reset(4);
+		edits.reset(8);
+		assert_eq!(edits.positions, vec![(0, DVec2::new(-0.1, -0.6))]);
+		assert_eq!(edits.colors, vec![(9, Color::BLUE)]);
+	}
+}
@@ -5,4 +5,5 @@ pub mod merge_by_distance;
 pub mod offset_subpath;
 pub mod poisson_disk;
 pub mod spline;
+pub mod stroke_outline;
 pub mod util;
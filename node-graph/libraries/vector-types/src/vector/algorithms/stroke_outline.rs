@@ -0,0 +1,441 @@
+use crate::vector::misc::point_to_dvec2;
+use crate::vector::style::{Stroke, StrokeCap, StrokeJoin};
+use glam::DVec2;
+use kurbo::{Affine, BezPath, PathEl};
+
+/// How far the outline may stray from the exact curve, balanced between performance and accuracy.
+const STROKE_TOLERANCE: f64 = 0.25;
+/// Points of the flattened path closer together than this are merged, since no direction can be found between them.
+const MIN_SEGMENT_LENGTH: f64 = 1e-9;
+/// Turns flatter than this (the cosine of the angle between the directions on either side) are offset to a single point on each side rather than joined.
+const SMOOTH_TURN_COSINE: f64 = 0.985;
+/// The most dashes cut from each subpath, beyond which a dash pattern far too small to make out is ignored.
+const MAX_DASHES: usize = 100_000;
+
+/// Expands a subpath into the outline of its stroke, which fills the same area the stroke paints.
+///
+/// An ordinary stroke is outlined by `kurbo::stroke`, while one with a width profile is outlined by [`width_profile_outline`].
+/// The outline is made in the stroke's own space given by its transform, then mapped back into the space of the path.
+pub fn solidify_stroke(mut path: BezPath, stroke: &Stroke) -> BezPath {
+	path.apply_affine(Affine::new(stroke.transform.to_cols_array()));
+
+	let mut solidified = if stroke.has_width_profile() {
+		width_profile_outline(&path, stroke)
+	} else {
+		let join = match stroke.join {
+			StrokeJoin::Miter => kurbo::Join::Miter,
+			StrokeJoin::Bevel => kurbo::Join::Bevel,
+			StrokeJoin::Round => kurbo::Join::Round,
+		};
+		let cap = match stroke.cap {
+			StrokeCap::Butt => kurbo::Cap::Butt,
+			StrokeCap::Round => kurbo::Cap::Round,
+			StrokeCap::Square => kurbo::Cap::Square,
+		};
+		let stroke_style = kurbo::Stroke::new(stroke.weight)
+			.with_caps(cap)
+			.with_join(join)
+			.with_dashes(stroke.dash_offset, stroke.dash_lengths.iter().copied())
+			.with_miter_limit(stroke.join_miter_limit);
+
+		// Closed subpaths use the default merge so the seam-spanning dash matches Vello/SVG renderers, while open subpaths use stable order so dashes are emitted in path-length sequence
+		let is_closed = matches!(path.elements().last(), Some(PathEl::ClosePath));
+		let stroke_options = kurbo::StrokeOpts::default().stable_dash_order(!is_closed);
+
+		kurbo::stroke(path, &stroke_style, &stroke_options, STROKE_TOLERANCE)
+	};
+
+	if stroke.transform.matrix2.determinant() != 0. {
+		solidified.apply_affine(Affine::new(stroke.transform.inverse().to_cols_array()));
+	}
+
+	solidified
+}
+
+/// Expands a path into the outline of a stroke whose width varies along each subpath by the stroke's width profile.
+///
+/// Each subpath is flattened into a polyline and offset to either side by half the width found at each point from its normalized length along the subpath.
+/// An open run of the stroke becomes a single closed outline with its caps, while a closed subpath without dashes becomes an outer and an inner loop
+/// wound in opposite directions, so the outline should be filled with the nonzero fill rule. The stroke's alignment is ignored.
+pub fn width_profile_outline(path: &BezPath, stroke: &Stroke) -> BezPath {
+	let mut outline = BezPath::new();
+	if stroke.weight <= 0. {
+		return outline;
+	}
+
+	for (points, closed) in flatten_subpaths(path) {
+		// The cumulative length along the subpath at each point, including the closing segment back to the start of a closed subpath
+		let mut samples = Vec::<(DVec2, f64)>::with_capacity(points.len() + 1);
+		let mut length = 0.;
+		for (index, &point) in points.iter().chain(closed.then_some(&points[0])).enumerate() {
+			if index > 0 {
+				length += samples[index - 1].0.distance(point);
+			}
+			samples.push((point, length));
+		}
+		let total_length = samples[samples.len() - 1].1;
+		if total_length <= MIN_SEGMENT_LENGTH {
+			continue;
+		}
+
+		// Cut the polyline where the profile bends so its widths can be interpolated linearly between the points
+		let profile_cuts = stroke.width_profile.points().iter().map(|point| point.x * total_length).collect::<Vec<_>>();
+		let samples = insert_cuts(&samples, &profile_cuts);
+
+		let half_width = |length: f64| stroke.weight / 2. * stroke.width_profile.evaluate(length / total_length);
+		let to_run = |samples: &[(DVec2, f64)]| samples.iter().map(|&(point, length)| (point, half_width(length))).collect::<Vec<_>>();
+
+		let Some(dashes) = dash_intervals(&stroke.dash_lengths, stroke.dash_offset, total_length) else {
+			if closed {
+				outline_closed_run(&mut outline, &to_run(&samples[..samples.len() - 1]), stroke);
+			} else {
+				outline_open_run(&mut outline, &to_run(&samples), stroke);
+			}
+			continue;
+		};
+
+		let mut runs = dashes.iter().map(|&(start, end)| to_run(&slice_samples(&samples, start, end))).collect::<Vec<_>>();
+
+		// The dash spanning the seam of a closed subpath continues across it rather than being capped there
+		if closed && runs.len() > 1 && dashes[0].0 <= 0. && dashes[dashes.len() - 1].1 >= total_length {
+			let first = runs.remove(0);
+			if let Some(last) = runs.last_mut() {
+				last.extend(first.into_iter().skip(1));
+			}
+		}
+
+		for run in runs {
+			outline_open_run(&mut outline, &run, stroke);
+		}
+	}
+
+	outline
+}
+
+/// Flattens each subpath of the path into its points without repeats, and whether it's closed.
+fn flatten_subpaths(path: &BezPath) -> Vec<(Vec<DVec2>, bool)> {
+	let mut subpaths = Vec::new();
+	let mut current = Vec::<DVec2>::new();
+
+	let mut finish = |current: &mut Vec<DVec2>, closed: bool| {
+		let mut points = std::mem::take(current);
+		if closed && points.len() > 1 && points[0].distance(points[points.len() - 1]) < MIN_SEGMENT_LENGTH {
+			points.pop();
+		}
+		if points.len() > 1 {
+			let closed = closed && points.len() > 2;
+			subpaths.push((points, closed));
+		}
+	};
+
+	kurbo::flatten(path.iter(), STROKE_TOLERANCE, |element| match element {
+		PathEl::MoveTo(point) => {
+			finish(&mut current, false);
+			current.push(point_to_dvec2(point));
+		}
+		PathEl::LineTo(point) => {
+			let point = point_to_dvec2(point);
+			if current.last().is_none_or(|&last| last.distance(point) >= MIN_SEGMENT_LENGTH) {
+				current.push(point);
+			}
+		}
+		PathEl::ClosePath => finish(&mut current, true),
+		_ => {}
+	});
+	finish(&mut current, false);
+
+	subpaths
+}
+
+/// Inserts points into the samples at each of the sorted lengths along them which don't already have one.
+fn insert_cuts(samples: &[(DVec2, f64)], cuts: &[f64]) -> Vec<(DVec2, f64)> {
+	let mut result = Vec::with_capacity(samples.len() + cuts.len());
+	let mut cuts = cuts.iter().copied().peekable();
+
+	for pair in samples.windows(2) {
+		let [(start, start_length), (end, end_length)] = [pair[0], pair[1]];
+		result.push((start, start_length));
+
+		while let Some(&cut) = cuts.peek() {
+			if cut >= end_length {
+				break;
+			}
+			cuts.next();
+			if cut > start_length {
+				let factor = (cut - start_length) / (end_length - start_length);
+				result.push((start.lerp(end, factor), cut));
+			}
+		}
+	}
+	result.extend(samples.last().copied());
+
+	result
+}
+
+/// The samples between two lengths along them, with interpolated points at both ends.
+fn slice_samples(samples: &[(DVec2, f64)], start: f64, end: f64) -> Vec<(DVec2, f64)> {
+	let point_at = |length: f64| {
+		let index = samples.partition_point(|&(_, sample_length)| sample_length <= length).clamp(1, samples.len() - 1);
+		let ((a, a_length), (b, b_length)) = (samples[index - 1], samples[index]);
+		let factor = if b_length > a_length { ((length - a_length) / (b_length - a_length)).clamp(0., 1.) } else { 0. };
+		(a.lerp(b, factor), length)
+	};
+
+	let inner = samples.iter().copied().filter(|&(_, length)| length > start && length < end);
+	std::iter::once(point_at(start)).chain(inner).chain(std::iter::once(point_at(end))).collect()
+}
+
+/// The start and end lengths of each dash along a subpath of the given length, or `None` if the stroke has no dashes.
+///
+/// Like SVG's `stroke-dasharray`, a pattern with an odd number of lengths is repeated to make it even, and the offset shifts the pattern back along the subpath.
+fn dash_intervals(dash_lengths: &[f64], dash_offset: f64, total_length: f64) -> Option<Vec<(f64, f64)>> {
+	let mut pattern = dash_lengths.to_vec();
+	if pattern.len() % 2 == 1 {
+		pattern.extend_from_slice(dash_lengths);
+	}
+	let period = pattern.iter().sum::<f64>();
+	if pattern.is_empty() || !period.is_finite() || period <= 0. || pattern.iter().any(|&length| length < 0.) || total_length / period > MAX_DASHES as f64 {
+		return None;
+	}
+
+	let mut intervals = Vec::new();
+	let mut position = -dash_offset.rem_euclid(period);
+	for (index, &length) in pattern.iter().enumerate().cycle() {
+		if position >= total_length {
+			break;
+		}
+		let (start, end) = (position.max(0.), (position + length).min(total_length));
+		if index % 2 == 0 && end > start {
+			intervals.push((start, end));
+		}
+		position += length;
+	}
+
+	Some(intervals)
+}
+
+/// The unit normal to the left of a direction.
+fn left_normal(direction: DVec2) -> DVec2 {
+	direction.perp()
+}
+
+/// Appends the outline of an open run of `(point, half width)` pairs: its left side forward, the end cap, its right side backward, then the start cap.
+fn outline_open_run(outline: &mut BezPath, run: &[(DVec2, f64)], stroke: &Stroke) {
+	let run = without_repeats(run);
+	if run.len() < 2 {
+		return;
+	}
+	let last = run.len() - 1;
+
+	let mut left = Vec::new();
+	let mut right = Vec::new();
+	let start_direction = (run[1].0 - run[0].0).normalize();
+	let end_direction = (run[last].0 - run[last - 1].0).normalize();
+
+	left.push(run[0].0 + left_normal(start_direction) * run[0].1);
+	right.push(run[0].0 - left_normal(start_direction) * run[0].1);
+	for index in 1..last {
+		let incoming = (run[index].0 - run[index - 1].0).normalize();
+		let outgoing = (run[index + 1].0 - run[index].0).normalize();
+		offset_vertex(&mut left, &mut right, run[index], incoming, outgoing, stroke);
+	}
+	left.push(run[last].0 + left_normal(end_direction) * run[last].1);
+	right.push(run[last].0 - left_normal(end_direction) * run[last].1);
+
+	let mut points = left;
+	add_cap(&mut points, run[last], end_direction, stroke.cap);
+	points.extend(right.into_iter().rev());
+	add_cap(&mut points, run[0], -start_direction, stroke.cap);
+
+	push_polygon(outline, &points);
+}
+
+/// Appends the outline of a closed run of `(point, half width)` pairs as its left side forward and its right side backward.
+fn outline_closed_run(outline: &mut BezPath, run: &[(DVec2, f64)], stroke: &Stroke) {
+	let run = without_repeats(run);
+	if run.len() < 3 {
+		return;
+	}
+
+	let mut left = Vec::new();
+	let mut right = Vec::new();
+	for index in 0..run.len() {
+		let previous = run[(index + run.len() - 1) % run.len()].0;
+		let next = run[(index + 1) % run.len()].0;
+		let incoming = (run[index].0 - previous).normalize();
+		let outgoing = (next - run[index].0).normalize();
+		offset_vertex(&mut left, &mut right, run[index], incoming, outgoing, stroke);
+	}
+
+	push_polygon(outline, &left);
+	right.reverse();
+	push_polygon(outline, &right);
+}
+
+/// Merges neighboring pairs at the same point, keeping the first.
+fn without_repeats(run: &[(DVec2, f64)]) -> Vec<(DVec2, f64)> {
+	let mut result = Vec::<(DVec2, f64)>::with_capacity(run.len());
+	for &(point, half_width) in run {
+		if result.last().is_none_or(|&(last, _)| last.distance(point) >= MIN_SEGMENT_LENGTH) {
+			result.push((point, half_width));
+		}
+	}
+	result
+}
+
+/// Adds the offset points to either side of a vertex where the run turns from the incoming to the outgoing direction.
+///
+/// A gentle turn is offset to the single point along the averaged normal on each side. A sharp turn gets the stroke's join on its outer side,
+/// while its inner side pivots through the vertex itself so the overlapping offsets of the two segments still cover it under the nonzero fill rule.
+fn offset_vertex(left: &mut Vec<DVec2>, right: &mut Vec<DVec2>, (point, half_width): (DVec2, f64), incoming: DVec2, outgoing: DVec2, stroke: &Stroke) {
+	let (incoming_normal, outgoing_normal) = (left_normal(incoming), left_normal(outgoing));
+	let cosine = incoming.dot(outgoing);
+
+	if cosine >= SMOOTH_TURN_COSINE {
+		let miter = (incoming_normal + outgoing_normal) / (1. + incoming_normal.dot(outgoing_normal)) * half_width;
+		left.push(point + miter);
+		right.push(point - miter);
+		return;
+	}
+
+	// Turning toward the left puts the left side on the inside of the turn
+	let turns_left = incoming.perp_dot(outgoing) > 0.;
+	let (outer, inner, side) = if turns_left { (right, left, -1.) } else { (left, right, 1.) };
+
+	inner.push(point + side * -incoming_normal * half_width);
+	inner.push(point);
+	inner.push(point + side * -outgoing_normal * half_width);
+
+	let (from, to) = (side * incoming_normal, side * outgoing_normal);
+	match stroke.join {
+		StrokeJoin::Miter => {
+			let miter_ratio = (2. / (1. + from.dot(to))).sqrt();
+			if miter_ratio <= stroke.join_miter_limit {
+				outer.push(point + (from + to) / (1. + from.dot(to)) * half_width);
+			} else {
+				outer.extend([point + from * half_width, point + to * half_width]);
+			}
+		}
+		StrokeJoin::Bevel => outer.extend([point + from * half_width, point + to * half_width]),
+		StrokeJoin::Round => add_arc(outer, point, half_width, from, from.perp_dot(to).atan2(from.dot(to))),
+	}
+}
+
+/// Adds the cap at the end of a run, facing out along the given direction, between the left and right offset points already at either side of it.
+fn add_cap(points: &mut Vec<DVec2>, (point, half_width): (DVec2, f64), direction: DVec2, cap: StrokeCap) {
+	let normal = left_normal(direction);
+	match cap {
+		StrokeCap::Butt => {}
+		StrokeCap::Square => points.extend([point + (normal + direction) * half_width, point + (direction - normal) * half_width]),
+		StrokeCap::Round => {
+			// The ends of the arc are the offset points already on either side
+			let mut arc = Vec::new();
+			add_arc(&mut arc, point, half_width, normal, -std::f64::consts::PI);
+			points.extend(&arc[1..arc.len() - 1]);
+		}
+	}
+}
+
+/// Adds the points along a circular arc from the unit direction `from` around the center, sweeping through the signed angle, including both its ends.
+fn add_arc(points: &mut Vec<DVec2>, center: DVec2, radius: f64, from: DVec2, sweep: f64) {
+	// The angle between points which keeps the chords within the tolerance of the arc
+	let step = if radius > STROKE_TOLERANCE {
+		2. * (1. - STROKE_TOLERANCE / radius).acos()
+	} else {
+		std::f64::consts::FRAC_PI_2
+	};
+	let steps = (sweep.abs() / step).ceil().clamp(1., 256.) as usize;
+
+	for index in 0..=steps {
+		let direction = DVec2::from_angle(sweep * index as f64 / steps as f64).rotate(from);
+		points.push(center + direction * radius);
+	}
+}
+
+fn push_polygon(outline: &mut BezPath, points: &[DVec2]) {
+	let Some((&first, rest)) = points.split_first() else { return };
+	outline.move_to((first.x, first.y));
+	for point in rest {
+		outline.line_to((point.x, point.y));
+	}
+	outline.close_path();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::vector::style::{WidthProfile, WidthProfilePreset};
+	use kurbo::Shape;
+
+	fn profiled_stroke(preset: WidthProfilePreset) -> Stroke {
+		Stroke::new(10.).with_width_profile(WidthProfile::from_preset(preset))
+	}
+
+	fn covers(outline: &BezPath, x: f64, y: f64) -> bool {
+		outline.winding((x, y).into()) != 0
+	}
+
+	#[test]
+	fn tapered_line() {
+		let mut path = BezPath::new();
+		path.move_to((0., 0.));
+		path.line_to((100., 0.));
+
+		let outline = width_profile_outline(&path, &profiled_stroke(WidthProfilePreset::TaperBoth));
+
+		// Full width across the middle half, tapering to nothing over each quarter at the ends
+		assert!((outline.area().abs() - (10. * 50. + 10. * 25.)).abs() < 1e-6);
+		assert!(covers(&outline, 50., 4.9));
+		assert!(!covers(&outline, 5., 4.));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn unsorted_profile_is_sorted_when_deserialized() {
+		let profile: WidthProfile = serde_json::from_str(r#"{ "points": [[1.0, 0.5], [-1.0, 0.0], [0.5, 1.0]] }"#).unwrap();
+
+		assert_eq!(profile.points(), [DVec2::new(0., 0.), DVec2::new(0.5, 1.), DVec2::new(1., 0.5)]);
+		assert!((profile.evaluate(0.25) - 0.5).abs() < 1e-9);
+	}
+
+	#[test]
+	fn closed_path_leaves_its_inside_unfilled() {
+		let circle = kurbo::Circle::new((0., 0.), 50.).to_path(0.1);
+
+		let outline = width_profile_outline(&circle, &profiled_stroke(WidthProfilePreset::Bulge));
+
+		assert!(covers(&outline, -50., 0.));
+		assert!(!covers(&outline, 0., 0.));
+	}
+
+	#[test]
+	fn dashes_are_outlined_separately() {
+		let circle = kurbo::Circle::new((0., 0.), 50.).to_path(0.1);
+		let mut stroke = profiled_stroke(WidthProfilePreset::Pinch);
+		stroke.dash_lengths = vec![20., 10.];
+
+		let outline = width_profile_outline(&circle, &stroke);
+
+		// The dash spanning the seam of the circle is joined into one, leaving 10 of the 10.47 periods around its length
+		assert_eq!(outline.elements().iter().filter(|element| matches!(element, PathEl::MoveTo(_))).count(), 10);
+	}
+
+	#[test]
+	fn sharp_turns_are_joined() {
+		let mut path = BezPath::new();
+		path.move_to((0., 0.));
+		path.line_to((100., 0.));
+		path.line_to((0., 10.));
+
+		for join in [StrokeJoin::Miter, StrokeJoin::Bevel, StrokeJoin::Round] {
+			let mut stroke = profiled_stroke(WidthProfilePreset::TaperStart);
+			stroke.join = join;
+
+			let outline = width_profile_outline(&path, &stroke);
+
+			assert!(covers(&outline, 100., 0.));
+			assert!(covers(&outline, 50., 5.));
+			assert!(!covers(&outline, 50., 20.));
+		}
+	}
+}
@@ -6,7 +6,7 @@ use core_types::color::SRGBA8;
 use core_types::list::{Item, List};
 use core_types::transform::Transform;
 use dyn_any::DynAny;
-use glam::DAffine2;
+use glam::{DAffine2, DVec2};
 use std::f64::consts::{PI, TAU};
 
 /// The editor's in-memory paint picker state, storing color or gradient stops without gradient placement metadata.
@@ -262,6 +262,145 @@ impl From<String> for DashPattern {
 	}
 }
 
+/// The shapes a stroke's width profile can be set to without drawing it by hand.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WidthProfilePreset {
+	/// The full width along the whole path.
+	#[default]
+	Uniform,
+	/// Grows from nothing at the start of the path to the full width.
+	TaperStart,
+	/// Shrinks from the full width to nothing at the end of the path.
+	TaperEnd,
+	/// Grows from nothing at the start and shrinks back to nothing at the end.
+	TaperBoth,
+	/// Swells from thin ends to the full width in the middle.
+	Bulge,
+	/// Narrows from the full width at the ends to thin in the middle.
+	Pinch,
+}
+
+impl WidthProfilePreset {
+	pub const ALL: [Self; 6] = [Self::Uniform, Self::TaperStart, Self::TaperEnd, Self::TaperBoth, Self::Bulge, Self::Pinch];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::Uniform => "Uniform",
+			Self::TaperStart => "Taper Start",
+			Self::TaperEnd => "Taper End",
+			Self::TaperBoth => "Taper Both",
+			Self::Bulge => "Bulge",
+			Self::Pinch => "Pinch",
+		}
+	}
+}
+
+/// A curve of a stroke's width along its path, as a fraction of the stroke's weight against the normalized length along each subpath.
+///
+/// The points are `(position, width)` pairs sorted by position, both from 0 to 1, and the width is interpolated linearly between them.
+/// An empty profile keeps the stroke at its full weight everywhere, which is drawn as an ordinary stroke rather than expanded into an outline.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(Default, Debug, Clone, PartialEq, graphene_hash::CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WidthProfile {
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_width_profile_points"))]
+	points: Vec<DVec2>,
+}
+
+/// Restores the sorting and clamping that [`WidthProfile::evaluate`] relies on to profiles read from documents, which may have been written by hand or by other tools.
+#[cfg(feature = "serde")]
+fn deserialize_width_profile_points<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<DVec2>, D::Error> {
+	let points: Vec<DVec2> = serde::Deserialize::deserialize(deserializer)?;
+	Ok(WidthProfile::sanitize(points))
+}
+
+impl WidthProfile {
+	/// How many points the curved presets are sampled with.
+	const CURVED_PRESET_SAMPLES: usize = 17;
+	/// How thin the thin parts of the curved presets are, as a fraction of the weight.
+	const CURVED_PRESET_MIN_WIDTH: f64 = 0.2;
+
+	/// Builds a profile from `(position, width)` samples in any order, such as pressure readings recorded while drawing, clamping both into the 0 to 1 range.
+	pub fn from_samples(samples: impl IntoIterator<Item = DVec2>) -> Self {
+		let profile = Self { points: Self::sanitize(samples) };
+		if profile.is_uniform() { Self::default() } else { profile }
+	}
+
+	/// Drops non-finite samples, clamps the rest into the 0 to 1 range and sorts them by position.
+	fn sanitize(samples: impl IntoIterator<Item = DVec2>) -> Vec<DVec2> {
+		let mut points = samples
+			.into_iter()
+			.filter(|sample| sample.is_finite())
+			.map(|sample| sample.clamp(DVec2::ZERO, DVec2::ONE))
+			.collect::<Vec<_>>();
+		points.sort_by(|a, b| a.x.total_cmp(&b.x));
+		points
+	}
+
+	/// The `(position, width)` points of the profile, sorted by position.
+	pub fn points(&self) -> &[DVec2] {
+		&self.points
+	}
+
+	pub fn from_preset(preset: WidthProfilePreset) -> Self {
+		let curved = |width: &dyn Fn(f64) -> f64| {
+			let samples = (0..Self::CURVED_PRESET_SAMPLES).map(|index| index as f64 / (Self::CURVED_PRESET_SAMPLES - 1) as f64);
+			Self {
+				points: samples.map(|position| DVec2::new(position, width(position))).collect(),
+			}
+		};
+		let swell = |position: f64| (position * PI).sin();
+		let thin = Self::CURVED_PRESET_MIN_WIDTH;
+
+		match preset {
+			WidthProfilePreset::Uniform => Self::default(),
+			WidthProfilePreset::TaperStart => Self {
+				points: vec![DVec2::new(0., 0.), DVec2::new(0.25, 1.), DVec2::new(1., 1.)],
+			},
+			WidthProfilePreset::TaperEnd => Self {
+				points: vec![DVec2::new(0., 1.), DVec2::new(0.75, 1.), DVec2::new(1., 0.)],
+			},
+			WidthProfilePreset::TaperBoth => Self {
+				points: vec![DVec2::new(0., 0.), DVec2::new(0.25, 1.), DVec2::new(0.75, 1.), DVec2::new(1., 0.)],
+			},
+			WidthProfilePreset::Bulge => curved(&|position| thin + (1. - thin) * swell(position)),
+			WidthProfilePreset::Pinch => curved(&|position| 1. - (1. - thin) * swell(position)),
+		}
+	}
+
+	/// The preset this profile was made from, if any.
+	pub fn preset(&self) -> Option<WidthProfilePreset> {
+		if self.is_uniform() {
+			return Some(WidthProfilePreset::Uniform);
+		}
+		WidthProfilePreset::ALL.into_iter().find(|&preset| Self::from_preset(preset) == *self)
+	}
+
+	/// Whether the profile keeps the full width along the whole path.
+	pub fn is_uniform(&self) -> bool {
+		self.points.iter().all(|point| point.y >= 1.)
+	}
+
+	/// The width as a fraction of the stroke's weight at the given normalized position along the path.
+	pub fn evaluate(&self, position: f64) -> f64 {
+		let Some(first) = self.points.first() else { return 1. };
+		let last = self.points[self.points.len() - 1];
+		if position <= first.x {
+			return first.y.clamp(0., 1.);
+		}
+		if position >= last.x {
+			return last.y.clamp(0., 1.);
+		}
+
+		let after = self.points.partition_point(|point| point.x <= position).clamp(1, self.points.len() - 1);
+		let (start, end) = (self.points[after - 1], self.points[after]);
+		let factor = if end.x > start.x { (position - start.x) / (end.x - start.x) } else { 1. };
+		(start.y + (end.y - start.y) * factor).clamp(0., 1.)
+	}
+}
+
 #[repr(C)]
 #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 #[derive(Debug, Clone, PartialEq, graphene_hash::CacheHash, DynAny)]
@@ -284,10 +423,13 @@ pub struct Stroke {
 	pub transform: DAffine2,
 	#[cfg_attr(feature = "serde", serde(default))]
 	pub paint_order: PaintOrder,
+	/// How the width varies along the path, as a fraction of the weight.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub width_profile: WidthProfile,
 }
 
 impl Stroke {
-	pub const fn new(weight: f64) -> Self {
+	pub fn new(weight: f64) -> Self {
 		Self {
 			weight,
 			dash_lengths: Vec::new(),
@@ -298,6 +440,7 @@ impl Stroke {
 			align: StrokeAlign::Center,
 			transform: DAffine2::IDENTITY,
 			paint_order: PaintOrder::StrokeAbove,
+			width_profile: WidthProfile::default(),
 		}
 	}
 
@@ -335,6 +478,7 @@ impl Stroke {
 				trs * skew
 			},
 			paint_order: if time < 0.5 { self.paint_order } else { other.paint_order },
+			width_profile: if time < 0.5 { self.width_profile.clone() } else { other.width_profile.clone() },
 		}
 	}
 
@@ -446,6 +590,16 @@ impl Stroke {
 		self
 	}
 
+	pub fn with_width_profile(mut self, width_profile: WidthProfile) -> Self {
+		self.width_profile = width_profile;
+		self
+	}
+
+	/// Whether the stroke's width varies along the path, so it's drawn by filling its outline rather than as an ordinary stroke.
+	pub fn has_width_profile(&self) -> bool {
+		!self.width_profile.is_uniform()
+	}
+
 	pub fn has_renderable_stroke(&self) -> bool {
 		self.weight > 0.
 	}
@@ -463,6 +617,7 @@ impl Default for Stroke {
 			align: StrokeAlign::Center,
 			transform: DAffine2::IDENTITY,
 			paint_order: PaintOrder::default(),
+			width_profile: WidthProfile::default(),
 		}
 	}
 }
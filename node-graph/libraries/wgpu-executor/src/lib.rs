@@ -9,11 +9,16 @@ use crate::texture_cache::TextureCache;
 use anyhow::Result;
 use core_types::Color;
 use core_types::color::SRGBA8;
+use core_types::ops::Convert;
+use core_types::transform::Footprint;
 use futures::lock::Mutex;
 use glam::UVec2;
 use graphene_application_io::{ApplicationIo, EditorApi};
-use raster_types::Texture;
+use raster_types::{CPU, GPU, Raster, Texture};
+use rendering::EffectLayer;
+use std::collections::HashMap;
 use std::sync::Arc;
+use vello::peniko::ImageBrush;
 use vello::{AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
 use wgpu::{Origin3d, TextureAspect};
 
@@ -52,6 +57,8 @@ pub struct WgpuExecutorInner {
 	context: WgpuContext,
 	texture_cache: Mutex<TextureCache>,
 	vello_renderer: Mutex<Renderer>,
+	/// The filtered images of the layer effects in the last rendered scene, by the keys of their effect layers.
+	effect_cache: Mutex<HashMap<u64, Texture>>,
 	shader_runtime: ShaderRuntime,
 }
 
@@ -69,8 +76,42 @@ impl<'a, T: ApplicationIo<Executor = WgpuExecutor>> From<&'a EditorApi<T>> for &
 
 impl WgpuExecutor {
 	pub async fn render_vello_scene(&self, scene: &Scene, size: UVec2, context: &RenderContext, background: Option<Color>) -> Result<Texture> {
+		// Each layer effect is drawn from a render of its graphic, which may include the images of the effects nested inside it.
+		// Reading the render back to filter it stalls the GPU, so the images of effects that haven't changed since the last scene are reused.
+		let previous_effects = std::mem::take(&mut *self.inner.effect_cache.lock().await);
+		let mut effects = HashMap::new();
+		let mut overrides = context.resource_overrides.clone();
+		for effect_layer in &context.effect_layers {
+			let reused = effect_layer.key.and_then(|key| effects.get(&key).or_else(|| previous_effects.get(&key)).cloned());
+			let texture = match reused {
+				Some(texture) => texture,
+				None => self.render_effect_layer(effect_layer, &overrides).await?,
+			};
+			if let Some(key) = effect_layer.key {
+				effects.insert(key, texture.clone());
+			}
+			overrides.push((effect_layer.image.clone(), texture));
+		}
+		*self.inner.effect_cache.lock().await = effects;
+
 		let texture = self.request_texture(size).await;
+		self.render_to_texture(scene, &texture, size, &overrides, background).await?;
+
+		Ok(texture)
+	}
+
+	/// Renders the graphic of a layer effect and runs it through the effect's filter, returning the image to draw in place of its placeholder.
+	async fn render_effect_layer(&self, effect_layer: &EffectLayer, overrides: &[(ImageBrush, Texture)]) -> Result<Texture> {
+		let graphic = self.request_texture(effect_layer.size).await;
+		self.render_to_texture(&effect_layer.scene, &graphic, effect_layer.size, overrides, None).await?;
+
+		let graphic: Raster<CPU> = Raster::new_gpu(graphic).convert(Footprint::default(), self).await;
+		let effect: Raster<GPU> = Raster::new_cpu(effect_layer.filter.apply(graphic.data())).convert(Footprint::default(), self).await;
+
+		Ok(effect.texture.clone())
+	}
 
+	async fn render_to_texture(&self, scene: &Scene, texture: &Texture, size: UVec2, overrides: &[(ImageBrush, Texture)], background: Option<Color>) -> Result<()> {
 		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
 		let SRGBA8 { red, green, blue, alpha } = background.unwrap_or(Color::TRANSPARENT).into();
@@ -81,28 +122,26 @@ impl WgpuExecutor {
 			antialiasing_method: AaConfig::Msaa16,
 		};
 
-		{
-			let mut renderer = self.inner.vello_renderer.lock().await;
-			for (image_brush, texture) in context.resource_overrides.iter() {
-				let texture_view = wgpu::TexelCopyTextureInfoBase {
-					texture: (**texture).clone(),
-					mip_level: 0,
-					origin: Origin3d::ZERO,
-					aspect: TextureAspect::All,
-				};
-				renderer.override_image(&image_brush.image, Some(texture_view));
-			}
+		let mut renderer = self.inner.vello_renderer.lock().await;
+		for (image_brush, texture) in overrides.iter() {
+			let texture_view = wgpu::TexelCopyTextureInfoBase {
+				texture: (**texture).clone(),
+				mip_level: 0,
+				origin: Origin3d::ZERO,
+				aspect: TextureAspect::All,
+			};
+			renderer.override_image(&image_brush.image, Some(texture_view));
+		}
 
-			{
-				let queue = self.context().queue.lock();
-				renderer.render_to_texture(&self.context().device, &queue, scene, &texture_view, &render_params)?;
-			}
-			for (image_brush, _) in context.resource_overrides.iter() {
-				renderer.override_image(&image_brush.image, None);
-			}
+		{
+			let queue = self.context().queue.lock();
+			renderer.render_to_texture(&self.context().device, &queue, scene, &texture_view, &render_params)?;
+		}
+		for (image_brush, _) in overrides.iter() {
+			renderer.override_image(&image_brush.image, None);
 		}
 
-		Ok(texture)
+		Ok(())
 	}
 
 	pub fn pipeline_init<P: WgpuPipeline>(&self, pipeline: &WgpuPipelineCache) {
@@ -141,6 +180,7 @@ impl WgpuExecutor {
 				context,
 				texture_cache: texture_cache.into(),
 				vello_renderer: vello_renderer.into(),
+				effect_cache: Default::default(),
 				shader_runtime,
 			}),
 		})
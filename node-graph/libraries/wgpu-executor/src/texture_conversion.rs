@@ -9,12 +9,24 @@ use raster_types::{CPU, GPU, Raster};
 use wgpu::util::{DeviceExt, TextureDataOrder};
 use wgpu::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages};
 
+/// The texel holding a pixel of an `Image<Color>` in a texture. Pixels are linear-light with associated (premultiplied) alpha,
+/// while texels are sRGB-encoded with unassociated (straight) alpha like the textures Vello renders.
+fn to_texel(pixel: Color) -> SRGBA8 {
+	pixel.to_unassociated_alpha().into()
+}
+
+/// The `Image<Color>` pixel held by a texel, undoing [`to_texel`].
+fn from_texel(texel: SRGBA8) -> Color {
+	let straight = Color::from(texel);
+	Color::new_from_unassociated_rgba(straight.r(), straight.g(), straight.b(), straight.a())
+}
+
 /// Uploads CPU image data to a GPU texture
 ///
 /// Creates a new WGPU texture with RGBA8UnormSrgb format and uploads the provided
 /// image data. The texture is configured for binding, copying, and source operations.
 fn upload_to_texture(device: &wgpu::Device, queue: &wgpu::Queue, image: &Raster<CPU>) -> wgpu::Texture {
-	let rgba8_data: Vec<SRGBA8> = image.data.iter().map(|x| (*x).into()).collect();
+	let rgba8_data: Vec<SRGBA8> = image.data.iter().copied().map(to_texel).collect();
 
 	device.create_texture_with_data(
 		queue,
@@ -122,9 +134,7 @@ impl RasterGpuToRasterCpuConverter {
 			let start = row * row_stride;
 			let row_slice = &view[start..start + row_bytes];
 			for px in row_slice.chunks_exact(4) {
-				// `Image<Color>` pixels are stored linear-light with associated (premultiplied) alpha
-				let srgba = SRGBA8::new(px[0], px[1], px[2], px[3]);
-				cpu_data.push(Color::from(srgba).apply_opacity(px[3] as f32 / 255.));
+				cpu_data.push(from_texel(SRGBA8::new(px[0], px[1], px[2], px[3])));
 			}
 		}
 
@@ -246,3 +256,34 @@ impl<'i> Convert<Raster<CPU>, &'i WgpuExecutor> for Raster<GPU> {
 		converter.convert(device).await.expect("Failed to download texture data")
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn texels_hold_unassociated_alpha() {
+		assert_eq!(to_texel(Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 0.5)), SRGBA8::new(255, 255, 255, 127));
+		assert_eq!(to_texel(Color::TRANSPARENT), SRGBA8::TRANSPARENT);
+	}
+
+	#[test]
+	fn pixels_round_trip_through_texels() {
+		let pixels = [
+			Color::from_rgbaf32_unchecked(1., 0.5, 0.25, 1.),
+			Color::from_rgbaf32_unchecked(0.25, 0.1, 0., 0.5),
+			Color::from_rgbaf32_unchecked(0.05, 0.05, 0.05, 0.1),
+			Color::TRANSPARENT,
+		];
+		for pixel in pixels {
+			let round_tripped = from_texel(to_texel(pixel));
+			let error = [
+				round_tripped.r() - pixel.r(),
+				round_tripped.g() - pixel.g(),
+				round_tripped.b() - pixel.b(),
+				round_tripped.a() - pixel.a(),
+			];
+			assert!(error.iter().all(|error| error.abs() < 0.01), "{pixel:?} came back as {round_tripped:?}");
+		}
+	}
+}
@@ -0,0 +1,165 @@
+use core_types::list::{Item, List};
+use core_types::registry::types::{Percentage, PixelLength};
+use core_types::{ATTR_LAYER_EFFECTS, ATTR_TRANSFORM, Color, Ctx};
+use glam::{DAffine2, DVec2};
+use graphic_types::Graphic;
+use graphic_types::layer_effects::{LayerEffect, LayerEffectType};
+
+/// Casts a shadow of the content behind it, made from its alpha offset, blurred and tinted with a color.
+#[node_macro::node(category("Blending"))]
+fn drop_shadow(
+	_: impl Ctx,
+	/// The content that casts the shadow.
+	content: List<Graphic>,
+	/// The color of the shadow, whose alpha sets its opacity.
+	#[default(Color::from_rgbaf32_unchecked(0., 0., 0., 0.5))]
+	color: Item<Color>,
+	/// How far the shadow is moved from the content.
+	#[default(4., 4.)]
+	offset: Item<DVec2>,
+	/// How much the edges of the shadow are softened.
+	#[default(8.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	blur: Item<PixelLength>,
+	/// How far the shadow is grown beyond the edges of the content before it's blurred.
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	spread: Item<PixelLength>,
+) -> List<Graphic> {
+	let effect = LayerEffect {
+		effect_type: LayerEffectType::DropShadow,
+		color: *color.element(),
+		offset: *offset.element(),
+		blur: *blur.element(),
+		spread: *spread.element(),
+	};
+
+	push_layer_effect(content, effect)
+}
+
+/// Casts a shadow onto the content from the area around it, drawn inside the edges of the content as if they were cut out of the surface.
+#[node_macro::node(category("Blending"))]
+fn inner_shadow(
+	_: impl Ctx,
+	/// The content that receives the shadow.
+	content: List<Graphic>,
+	/// The color of the shadow, whose alpha sets its opacity.
+	#[default(Color::from_rgbaf32_unchecked(0., 0., 0., 0.5))]
+	color: Item<Color>,
+	/// How far the shadow is moved from the edges of the content.
+	#[default(4., 4.)]
+	offset: Item<DVec2>,
+	/// How much the edges of the shadow are softened.
+	#[default(8.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	blur: Item<PixelLength>,
+	/// How far the shadow reaches into the content before it's blurred.
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	choke: Item<PixelLength>,
+) -> List<Graphic> {
+	let effect = LayerEffect {
+		effect_type: LayerEffectType::InnerShadow,
+		color: *color.element(),
+		offset: *offset.element(),
+		blur: *blur.element(),
+		spread: *choke.element(),
+	};
+
+	push_layer_effect(content, effect)
+}
+
+/// Surrounds the content with a soft glow of color radiating outwards from its edges, drawn behind it.
+#[node_macro::node(category("Blending"))]
+fn outer_glow(
+	_: impl Ctx,
+	/// The content that glows.
+	content: List<Graphic>,
+	/// The color of the glow, whose alpha sets its opacity.
+	#[default(Color::WHITE)]
+	color: Item<Color>,
+	/// How far the glow fades out beyond the edges of the content.
+	#[default(10.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	size: Item<PixelLength>,
+	/// How much of the glow's size is solid color before it starts fading out.
+	#[range]
+	#[hard(0..100)]
+	spread: Item<Percentage>,
+) -> List<Graphic> {
+	let (size, spread) = (*size.element(), *spread.element() / 100.);
+
+	let effect = LayerEffect {
+		effect_type: LayerEffectType::OuterGlow,
+		color: *color.element(),
+		offset: DVec2::ZERO,
+		blur: size * (1. - spread),
+		spread: size * spread,
+	};
+
+	push_layer_effect(content, effect)
+}
+
+/// Fills the content with a soft glow of color radiating inwards from its edges.
+#[node_macro::node(category("Blending"))]
+fn inner_glow(
+	_: impl Ctx,
+	/// The content that glows.
+	content: List<Graphic>,
+	/// The color of the glow, whose alpha sets its opacity.
+	#[default(Color::WHITE)]
+	color: Item<Color>,
+	/// How far the glow fades out inside the edges of the content.
+	#[default(10.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	size: Item<PixelLength>,
+	/// How much of the glow's size is solid color before it starts fading out.
+	#[range]
+	#[hard(0..100)]
+	choke: Item<Percentage>,
+) -> List<Graphic> {
+	let (size, choke) = (*size.element(), *choke.element() / 100.);
+
+	let effect = LayerEffect {
+		effect_type: LayerEffectType::InnerGlow,
+		color: *color.element(),
+		offset: DVec2::ZERO,
+		blur: size * (1. - choke),
+		spread: size * choke,
+	};
+
+	push_layer_effect(content, effect)
+}
+
+/// Adds the effect to the top of the stack of layer effects on the content as a whole.
+///
+/// The content is wrapped in a new item for the effect to be attached to, unless it's already a single item carrying effects whose lengths are measured in the same space.
+fn push_layer_effect(content: List<Graphic>, effect: LayerEffect) -> List<Graphic> {
+	if content.is_empty() {
+		return content;
+	}
+
+	let has_effects = content.len() == 1 && content.attribute::<Vec<LayerEffect>>(ATTR_LAYER_EFFECTS, 0).is_some_and(|effects| !effects.is_empty());
+	let untransformed = content.attribute_cloned_or_default::<DAffine2>(ATTR_TRANSFORM, 0) == DAffine2::IDENTITY;
+	let mut content = if has_effects && untransformed {
+		content
+	} else {
+		List::new_from_element(Graphic::Graphic(content))
+	};
+
+	let mut effects: Vec<LayerEffect> = content.attribute_cloned_or_default(ATTR_LAYER_EFFECTS, 0);
+	effects.push(effect);
+	content.set_attribute(ATTR_LAYER_EFFECTS, 0, effects);
+
+	content
+}
@@ -1,3 +1,5 @@
+pub mod layer_effects;
+
 use core_types::list::Item;
 use core_types::registry::types::Percentage;
 use core_types::{ATTR_BLEND_MODE, ATTR_CLIPPING_MASK, ATTR_OPACITY, ATTR_OPACITY_FILL, BlendMode, Color, Ctx};
@@ -1,5 +1,5 @@
 use crate::brush_cache::BrushCache;
-use crate::brush_stroke::{BrushStyle, BrushTrace};
+use crate::brush_stroke::{BrushInputSample, BrushMode, BrushStamp, BrushStroke, BrushStyle, BrushTipShape, BrushTrace, polygon_contains};
 use core_types::ATTR_TRANSFORM;
 use core_types::blending::BlendMode;
 use core_types::bounds::{BoundingBox, RenderBoundingBox};
@@ -18,6 +18,18 @@ use raster_types::BitmapMut;
 use raster_types::Image;
 use raster_types::{CPU, Raster};
 
+/// The radius of the blur and sharpen brush modes, as a fraction of the diameter.
+const BLUR_RADIUS_FRACTION: f64 = 0.05;
+
+/// Limits the radius of the blur and sharpen brush modes, in pixels, to keep large brushes responsive.
+const MAX_BLUR_RADIUS: i64 = 8;
+
+/// The solver which heals and patches stops once a pass changes no color channel by more than this.
+const POISSON_TOLERANCE: f32 = 1e-5;
+
+/// Limits how many passes the solver which heals and patches makes over the area it covers, to keep large areas responsive.
+const MAX_POISSON_ITERATIONS: usize = 500;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct BrushStampGenerator<P: Pixel + Alpha> {
 	color: P,
@@ -134,7 +146,12 @@ where
 	target
 }
 
-pub async fn create_brush_texture(brush_style: &BrushStyle) -> Raster<CPU> {
+/// Renders the tip of a brush style, which is textured when the style asks for it and `tip_texture` isn't empty, or round otherwise.
+pub async fn create_brush_texture(brush_style: &BrushStyle, tip_texture: &Raster<CPU>) -> Raster<CPU> {
+	if brush_style.tip.shape == BrushTipShape::Texture && tip_texture.width > 0 && tip_texture.height > 0 {
+		return create_textured_tip(brush_style, tip_texture);
+	}
+
 	let stamp = brush_stamp_generator(brush_style.diameter, brush_style.color, brush_style.hardness, brush_style.flow);
 	let transform = DAffine2::from_scale_angle_translation(DVec2::splat(brush_style.diameter), 0., -DVec2::splat(brush_style.diameter / 2.));
 	let blank_texture = empty_image((), Item::new_from_element(transform), Item::new_from_element(Color::TRANSPARENT));
@@ -143,6 +160,90 @@ pub async fn create_brush_texture(brush_style: &BrushStyle) -> Raster<CPU> {
 	image.into_element()
 }
 
+/// Renders a tip from `tip_texture`, scaled so its longest side spans the diameter and rotated by the angle of the tip.
+fn create_textured_tip(brush_style: &BrushStyle, tip_texture: &Image<Color>) -> Raster<CPU> {
+	let coverage = texture_coverage(tip_texture);
+	let (texture_width, texture_height) = (tip_texture.width as i64, tip_texture.height as i64);
+	let texture_size = DVec2::new(tip_texture.width as f64, tip_texture.height as f64);
+
+	// The tip is square and large enough to fit the texture at any angle
+	let size = (2. * brush_style.tip.radius(brush_style.diameter)).ceil().max(1.) as u32;
+	let scale = brush_style.diameter / texture_size.max_element();
+	let tip_to_texture = DAffine2::from_translation(texture_size / 2.)
+		* DAffine2::from_angle(-brush_style.tip.angle.to_radians())
+		* DAffine2::from_scale(DVec2::splat(1. / scale))
+		* DAffine2::from_translation(-DVec2::splat(size as f64 / 2.));
+
+	let color = brush_style.color.apply_opacity(brush_style.flow as f32 / 100.);
+	let data = (0..size)
+		.flat_map(|y| (0..size).map(move |x| DVec2::new(x as f64, y as f64) + 0.5))
+		.map(|position| {
+			let strength = sample_bilinear(tip_to_texture.transform_point2(position), |x, y| {
+				if (0..texture_width).contains(&x) && (0..texture_height).contains(&y) {
+					coverage[(y * texture_width + x) as usize]
+				} else {
+					0.
+				}
+			});
+			color.apply_opacity(strength)
+		})
+		.collect();
+
+	Raster::new_cpu(Image {
+		width: size,
+		height: size,
+		data,
+		..Default::default()
+	})
+}
+
+/// Multiplies the opacity of a stroke texture by `dual_texture`, which is tiled across the layer with its tiles scaled by the `scale` percentage.
+fn apply_dual_texture(mut stroke_texture: Item<Raster<CPU>>, dual_texture: &Image<Color>, scale: f64) -> Item<Raster<CPU>> {
+	if dual_texture.width == 0 || dual_texture.height == 0 || scale <= 0. {
+		return stroke_texture;
+	}
+
+	let coverage = texture_coverage(dual_texture);
+	let (texture_width, texture_height) = (dual_texture.width as i64, dual_texture.height as i64);
+
+	let transform: DAffine2 = stroke_texture.attribute_cloned_or_default(ATTR_TRANSFORM);
+	let image = stroke_texture.element_mut().data_mut();
+	let image_size = DVec2::new(image.width as f64, image.height as f64);
+	let pixel_to_texture = DAffine2::from_scale(DVec2::splat(100. / scale)) * transform * DAffine2::from_scale(1. / image_size);
+
+	let width = image.width;
+	for (index, pixel) in image.data.iter_mut().enumerate() {
+		let position = DVec2::new((index as u32 % width) as f64, (index as u32 / width) as f64) + 0.5;
+		let strength = sample_bilinear(pixel_to_texture.transform_point2(position), |x, y| {
+			coverage[(y.rem_euclid(texture_height) * texture_width + x.rem_euclid(texture_width)) as usize]
+		});
+		*pixel = pixel.apply_opacity(strength);
+	}
+
+	stroke_texture
+}
+
+/// How strongly each pixel of a texture paints, where darker and more opaque pixels paint more strongly, like the grayscale brush tips of other painting apps.
+fn texture_coverage(texture: &Image<Color>) -> Vec<f32> {
+	texture
+		.data
+		.iter()
+		.map(|color| color.a() * (1. - color.to_unassociated_alpha().luminance_perceptual()).clamp(0., 1.))
+		.collect()
+}
+
+/// Bilinearly interpolates the values of the pixels around `position`, where `value` gives the value of the pixel at integer coordinates.
+fn sample_bilinear(position: DVec2, value: impl Fn(i64, i64) -> f32) -> f32 {
+	let position = position - 0.5;
+	let (x, y) = (position.x.floor() as i64, position.y.floor() as i64);
+	let t = position - position.floor();
+	let (tx, ty) = (t.x as f32, t.y as f32);
+
+	let top = value(x, y) * (1. - tx) + value(x + 1, y) * tx;
+	let bottom = value(x, y + 1) * (1. - tx) + value(x + 1, y + 1) * tx;
+	top * (1. - ty) + bottom * ty
+}
+
 pub fn blend_with_mode(background: Item<Raster<CPU>>, foreground: Item<Raster<CPU>>, blend_mode: BlendMode, opacity: f64) -> Item<Raster<CPU>> {
 	let opacity = opacity as f32 / 100.;
 	match std::hint::black_box(blend_mode) {
@@ -194,11 +295,17 @@ async fn brush(
 	background: Item<Raster<CPU>>,
 	/// The list of brush stroke paths drawn by the Brush tool, with each including both its coordinates and styles.
 	trace: Item<BrushTrace>,
+	/// The image stamped by strokes with a textured tip. Darker and more opaque pixels paint more strongly.
+	tip_texture: Item<Raster<CPU>>,
+	/// The image multiplied into strokes which use a dual texture, tiled across the layer to give them a grain like paper or canvas.
+	dual_texture: Item<Raster<CPU>>,
 	/// Internal cache data used to accelerate rendering of the brush content.
 	#[data]
 	cache: BrushCache,
 ) -> Item<Raster<CPU>> {
 	let trace = trace.into_element().0;
+	let tip_texture = tip_texture.into_element();
+	let dual_texture = dual_texture.into_element();
 
 	let list_item = background;
 	let mut result_item = list_item.clone();
@@ -220,53 +327,46 @@ async fn brush(
 		.cloned()
 		.collect();
 
-	let mut brush_plan = cache.compute_brush_plan(list_item, &draw_strokes);
+	let mut brush_plan = cache.compute_brush_plan(list_item, &draw_strokes, &tip_texture, &dual_texture);
 
 	let mut actual_image = extend_image_to_bounds((), brush_plan.background, Item::new_from_element(background_bounds));
 
 	let final_stroke_idx = brush_plan.strokes.len().saturating_sub(1);
 	for (idx, stroke) in brush_plan.strokes.into_iter().enumerate() {
-		// Create brush texture.
-		// TODO: apply rotation from layer to stamp for non-rotationally-symmetric brushes.
-		let mut brush_texture = cache.get_cached_brush(&stroke.style);
-		if brush_texture.is_none() {
-			let tex = create_brush_texture(&stroke.style).await;
-			cache.store_brush(stroke.style.clone(), tex.clone());
-			brush_texture = Some(tex);
+		// Strokes which rework the pixels of the layer are applied straight to the image, since each stamp depends on what the stamps before it did
+		if stroke.style.mode.samples_layer() {
+			if idx == final_stroke_idx {
+				cache.cache_results(core::mem::take(&mut draw_strokes), actual_image.clone(), Item::default());
+			}
+			actual_image = apply_sampling_stroke(actual_image, &stroke, &tip_texture, cache).await;
+			continue;
 		}
-		let brush_texture = brush_texture.unwrap();
 
 		// Compute transformation from stroke texture space into layer space, and create the stroke texture.
+		// TODO: apply rotation from layer to stamp for non-rotationally-symmetric brushes.
 		let skip = if idx == 0 { brush_plan.first_stroke_point_skip } else { 0 };
-		let positions: Vec<_> = stroke.compute_blit_points().into_iter().skip(skip).collect();
-		let stroke_texture = if idx == 0 && positions.is_empty() {
+		let stamps: Vec<_> = stroke.compute_blit_stamps().into_iter().skip(skip).collect();
+		let stroke_texture = if idx == 0 && stamps.is_empty() {
 			core::mem::take(&mut brush_plan.first_stroke_texture)
 		} else {
 			let mut bbox = stroke.bounding_box();
 			bbox.start = bbox.start.floor();
 			bbox.end = bbox.end.floor();
-			let stroke_size = bbox.size() + DVec2::splat(stroke.style.diameter);
+			let tip_size = 2. * stroke.style.tip.radius(stroke.style.diameter);
+			let stroke_size = bbox.size() + DVec2::splat(tip_size);
 			// For numerical stability we want to place the first blit point at a stable, integer offset in layer space.
-			let snap_offset = positions[0].floor() - positions[0];
-			let stroke_origin_in_layer = bbox.start - snap_offset - DVec2::splat(stroke.style.diameter / 2.);
+			let snap_offset = stamps[0].position.floor() - stamps[0].position;
+			let stroke_origin_in_layer = bbox.start - snap_offset - DVec2::splat(tip_size / 2.);
 			let stroke_to_layer = DAffine2::from_translation(stroke_origin_in_layer) * DAffine2::from_scale(stroke_size);
 
-			let normal_blend = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::Normal, 1.));
-			let blit_node = BlitNode::new(
-				FutureWrapperNode::new(ClonedNode::new(brush_texture)),
-				FutureWrapperNode::new(ClonedNode::new(positions)),
-				FutureWrapperNode::new(ClonedNode::new(normal_blend)),
-			);
 			let blit_target = if idx == 0 {
 				let target = core::mem::take(&mut brush_plan.first_stroke_texture);
-				List::new_from_item(extend_image_to_bounds((), target, Item::new_from_element(stroke_to_layer)))
+				extend_image_to_bounds((), target, Item::new_from_element(stroke_to_layer))
 			} else {
-				List::new_from_item(empty_image((), Item::new_from_element(stroke_to_layer), Item::new_from_element(Color::TRANSPARENT)))
+				empty_image((), Item::new_from_element(stroke_to_layer), Item::new_from_element(Color::TRANSPARENT))
 			};
 
-			let list = blit_node.eval(blit_target).await;
-			assert_eq!(list.len(), 1);
-			list.into_iter().next().unwrap_or_default()
+			blit_stamps(blit_target, &stroke.style, &stamps, &tip_texture, cache, blend_within_stroke).await
 		};
 
 		// Cache image before doing final blend, and store final stroke texture.
@@ -274,6 +374,13 @@ async fn brush(
 			cache.cache_results(core::mem::take(&mut draw_strokes), actual_image.clone(), stroke_texture.clone());
 		}
 
+		// The dual texture is left out of the cached stroke texture, since it's tiled across the layer rather than stamped
+		let stroke_texture = if stroke.style.tip.dual_texture {
+			apply_dual_texture(stroke_texture, &dual_texture, stroke.style.tip.dual_texture_scale)
+		} else {
+			stroke_texture
+		};
+
 		// TODO: Is this the correct way to do opacity in blending?
 		actual_image = blend_with_mode(actual_image, stroke_texture, stroke.style.blend_mode, (stroke.style.color.a() * 100.) as f64);
 	}
@@ -283,15 +390,9 @@ async fn brush(
 		let opaque_image = Image::new(bbox.size().x as u32, bbox.size().y as u32, Color::WHITE);
 		let mut erase_restore_mask = Item::new_from_element(Raster::new_cpu(opaque_image)).with_attribute(ATTR_TRANSFORM, background_bounds);
 
-		for stroke in trace.into_iter().map(|row| row.into_element()) {
-			let mut brush_texture = cache.get_cached_brush(&stroke.style);
-			if brush_texture.is_none() {
-				let tex = create_brush_texture(&stroke.style).await;
-				cache.store_brush(stroke.style.clone(), tex.clone());
-				brush_texture = Some(tex);
-			}
-			let brush_texture = brush_texture.unwrap();
-			let positions: Vec<_> = stroke.compute_blit_points().into_iter().collect();
+		// Strokes which rework the pixels of the layer neither erase nor restore them
+		for stroke in trace.into_iter().map(|row| row.into_element()).filter(|stroke| !stroke.style.mode.samples_layer()) {
+			let stamps = stroke.compute_blit_stamps();
 
 			// For mask composition: Erase subtracts alpha, Restore adds alpha, and Draw acts like Restore to allow repainting erased areas.
 			let mask_blend_mode = match stroke.style.blend_mode {
@@ -300,13 +401,11 @@ async fn brush(
 				_ => BlendMode::Restore,
 			};
 
-			let blend_params = FnNode::new(move |(a, b)| blend_colors(a, b, mask_blend_mode, 1.));
-			let blit_node = BlitNode::new(
-				FutureWrapperNode::new(ClonedNode::new(brush_texture)),
-				FutureWrapperNode::new(ClonedNode::new(positions)),
-				FutureWrapperNode::new(ClonedNode::new(blend_params)),
-			);
-			erase_restore_mask = blit_node.eval(List::new_from_item(erase_restore_mask)).await.into_iter().next().unwrap_or_default();
+			// The mask has no notion of a stroke building up, so the opacity of each stamp simply scales its strength.
+			erase_restore_mask = blit_stamps(erase_restore_mask, &stroke.style, &stamps, &tip_texture, cache, move |a: Color, b: Color, opacity: f32| {
+				blend_colors(a.apply_opacity(opacity), b, mask_blend_mode, 1.)
+			})
+			.await;
 		}
 
 		let blend_params = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::MultiplyAlpha, 1.));
@@ -323,6 +422,366 @@ async fn brush(
 	result_item
 }
 
+/// Blits the brush tip of each stamp onto `target`, blending with `blend` which also receives the opacity of the stamp.
+/// Consecutive stamps which share a tip are blitted together with a single texture.
+async fn blit_stamps(
+	mut target: Item<Raster<CPU>>,
+	style: &BrushStyle,
+	stamps: &[BrushStamp],
+	tip_texture: &Raster<CPU>,
+	cache: &BrushCache,
+	blend: impl Fn(Color, Color, f32) -> Color + Copy + Send + Sync + 'static,
+) -> Item<Raster<CPU>> {
+	for stamps in stamps.chunk_by(|a, b| a.same_tip(b)) {
+		let brush_texture = cached_brush_texture(stamps[0].style(style), tip_texture, cache).await;
+
+		let opacity = stamps[0].opacity as f32;
+		let positions: Vec<_> = stamps.iter().map(|stamp| stamp.position).collect();
+		let blit_node = BlitNode::new(
+			FutureWrapperNode::new(ClonedNode::new(brush_texture)),
+			FutureWrapperNode::new(ClonedNode::new(positions)),
+			FutureWrapperNode::new(ClonedNode::new(FnNode::new(move |(a, b): (Color, Color)| blend(a, b, opacity)))),
+		);
+
+		let list = blit_node.eval(List::new_from_item(target)).await;
+		assert_eq!(list.len(), 1);
+		target = list.into_iter().next().unwrap_or_default();
+	}
+
+	target
+}
+
+/// Looks up the texture of a brush tip in the cache, or renders and stores it if it's not there yet.
+async fn cached_brush_texture(tip_style: BrushStyle, tip_texture: &Raster<CPU>, cache: &BrushCache) -> Raster<CPU> {
+	match cache.get_cached_brush(&tip_style) {
+		Some(texture) => texture,
+		None => {
+			let texture = create_brush_texture(&tip_style, tip_texture).await;
+			cache.store_brush(tip_style, texture.clone());
+			texture
+		}
+	}
+}
+
+/// Applies a stroke whose mode reworks the existing pixels of the layer straight onto `image`, one stamp after another.
+/// Each stamp changes the pixels under its tip, weighted by the opacity of the tip and the strength of the stroke.
+async fn apply_sampling_stroke(mut image: Item<Raster<CPU>>, stroke: &BrushStroke, tip_texture: &Raster<CPU>, cache: &BrushCache) -> Item<Raster<CPU>> {
+	let style = &stroke.style;
+	let stamps = stroke.compute_blit_stamps();
+
+	let transform: DAffine2 = image.attribute_cloned_or_default(ATTR_TRANSFORM);
+	let raster = image.element_mut().data_mut();
+	let (width, height) = (raster.width as i64, raster.height as i64);
+	if width == 0 || height == 0 {
+		return image;
+	}
+	let layer_to_pixel = DAffine2::from_scale(DVec2::new(width as f64, height as f64)) * transform.inverse();
+
+	let strength = (style.sampling.strength / 100.).clamp(0., 1.) as f32;
+	let blur_radius = ((style.diameter * BLUR_RADIUS_FRACTION).round() as i64).clamp(1, MAX_BLUR_RADIUS);
+	// Cloning reads the image as it was before the stroke, so the stroke never clones what it has just painted
+	let clone_source = (style.mode == BrushMode::Clone).then(|| raster.data.clone());
+	let source_offset = layer_to_pixel.transform_vector2(style.sampling.source_offset).round();
+
+	// Healing and patching solve for everything the stroke covers at once, so the copied texture meets its surroundings seamlessly
+	if matches!(style.mode, BrushMode::Heal | BrushMode::Patch) {
+		let mask = if style.mode == BrushMode::Patch {
+			let feather = layer_to_pixel.transform_vector2(DVec2::new(style.sampling.feather, 0.)).length();
+			patch_mask(&stroke.trace, feather, strength, width, height, layer_to_pixel)
+		} else {
+			heal_mask(style, &stamps, strength, width, height, layer_to_pixel, tip_texture, cache).await
+		};
+		poisson_blend(&mut raster.data, width, height, &mask, (source_offset.x as i64, source_offset.y as i64));
+		return image;
+	}
+
+	let mut previous_center = None;
+	for stamp in stamps {
+		let texture = cached_brush_texture(stamp.style(style), tip_texture, cache).await;
+		let (texture_width, texture_height) = (texture.width as i64, texture.height as i64);
+
+		let center = layer_to_pixel.transform_point2(stamp.position);
+		let start = (center - DVec2::new(texture_width as f64, texture_height as f64) / 2.).round();
+		// Smudging pulls the colors from where the previous stamp was
+		let drag = previous_center.map_or(DVec2::ZERO, |previous| center - previous).round();
+		previous_center = Some(center);
+
+		let pixel = |data: &[Color], x: i64, y: i64| data[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize];
+
+		let mut changes = Vec::new();
+		for texture_y in 0..texture_height {
+			for texture_x in 0..texture_width {
+				let (x, y) = (start.x as i64 + texture_x, start.y as i64 + texture_y);
+				if !(0..width).contains(&x) || !(0..height).contains(&y) {
+					continue;
+				}
+
+				let amount = (texture.data[(texture_y * texture_width + texture_x) as usize].a() * stamp.opacity as f32 * strength).clamp(0., 1.);
+				if amount == 0. {
+					continue;
+				}
+
+				let under = pixel(&raster.data, x, y);
+				let result = match style.mode {
+					BrushMode::Paint | BrushMode::Heal | BrushMode::Patch => continue,
+					BrushMode::Smudge => under.lerp(&pixel(&raster.data, x - drag.x as i64, y - drag.y as i64), amount),
+					BrushMode::Blur => under.lerp(&box_blur(&raster.data, width, height, x, y, blur_radius), amount),
+					BrushMode::Sharpen => {
+						let blurred = box_blur(&raster.data, width, height, x, y, blur_radius);
+						let sharpen = |channel: f32, blurred: f32| (channel + (channel - blurred) * amount).clamp(0., under.a());
+						Color::from_rgbaf32_unchecked(sharpen(under.r(), blurred.r()), sharpen(under.g(), blurred.g()), sharpen(under.b(), blurred.b()), under.a())
+					}
+					BrushMode::Dodge => dodge_burn(under, amount),
+					BrushMode::Burn => dodge_burn(under, -amount),
+					BrushMode::Clone => {
+						let Some(source) = &clone_source else { continue };
+						let (source_x, source_y) = (x + source_offset.x as i64, y + source_offset.y as i64);
+						if !(0..width).contains(&source_x) || !(0..height).contains(&source_y) {
+							continue;
+						}
+						under.lerp(&source[(source_y * width + source_x) as usize], amount)
+					}
+				};
+				changes.push(((y * width + x) as usize, result));
+			}
+		}
+
+		// Every pixel of a stamp is computed from the image as it was before the stamp, so blurring doesn't smear in the direction the pixels are visited
+		for (index, color) in changes {
+			raster.data[index] = color;
+		}
+	}
+
+	image
+}
+
+/// How strongly a heal stroke changes each pixel of the image, which is the strongest any of its stamps covers the pixel.
+#[allow(clippy::too_many_arguments)]
+async fn heal_mask(style: &BrushStyle, stamps: &[BrushStamp], strength: f32, width: i64, height: i64, layer_to_pixel: DAffine2, tip_texture: &Raster<CPU>, cache: &BrushCache) -> Vec<f32> {
+	let mut mask = vec![0_f32; (width * height) as usize];
+
+	for stamp in stamps {
+		let texture = cached_brush_texture(stamp.style(style), tip_texture, cache).await;
+		let (texture_width, texture_height) = (texture.width as i64, texture.height as i64);
+
+		let center = layer_to_pixel.transform_point2(stamp.position);
+		let start = (center - DVec2::new(texture_width as f64, texture_height as f64) / 2.).round();
+
+		for texture_y in 0..texture_height {
+			for texture_x in 0..texture_width {
+				let (x, y) = (start.x as i64 + texture_x, start.y as i64 + texture_y);
+				if !(0..width).contains(&x) || !(0..height).contains(&y) {
+					continue;
+				}
+
+				let amount = (texture.data[(texture_y * texture_width + texture_x) as usize].a() * stamp.opacity as f32 * strength).clamp(0., 1.);
+				let covered = &mut mask[(y * width + x) as usize];
+				*covered = covered.max(amount);
+			}
+		}
+	}
+
+	mask
+}
+
+/// How strongly a patch changes each pixel of the image, which is full strength inside the region its trace outlines, fading in over `feather` pixels from the outline.
+fn patch_mask(trace: &[BrushInputSample], feather: f64, strength: f32, width: i64, height: i64, layer_to_pixel: DAffine2) -> Vec<f32> {
+	let mut mask = vec![0.; (width * height) as usize];
+
+	let polygon: Vec<_> = trace.iter().map(|sample| layer_to_pixel.transform_point2(sample.position)).collect();
+	if polygon.len() < 3 {
+		return mask;
+	}
+
+	let min = polygon.iter().copied().reduce(DVec2::min).unwrap_or_default().floor().max(DVec2::ZERO);
+	let max = polygon.iter().copied().reduce(DVec2::max).unwrap_or_default().ceil().min(DVec2::new(width as f64, height as f64));
+
+	for y in min.y as i64..max.y as i64 {
+		for x in min.x as i64..max.x as i64 {
+			let point = DVec2::new(x as f64, y as f64) + 0.5;
+			if !polygon_contains(&polygon, point) {
+				continue;
+			}
+
+			let amount = if feather > 0. {
+				let distance = polygon
+					.iter()
+					.zip(polygon.iter().cycle().skip(1))
+					.map(|(&a, &b)| {
+						let along = ((point - a).dot(b - a) / (b - a).length_squared().max(f64::EPSILON)).clamp(0., 1.);
+						point.distance(a + (b - a) * along)
+					})
+					.fold(f64::INFINITY, f64::min);
+				(distance / feather).min(1.) as f32
+			} else {
+				1.
+			};
+			mask[(y * width + x) as usize] = (amount * strength).clamp(0., 1.);
+		}
+	}
+
+	mask
+}
+
+/// Replaces the pixels covered by `mask` with the pixels `source_offset` away from them, as they were before, solving the Poisson equation so the result
+/// keeps the detail of the copied pixels while taking on the colors and luminance of the pixels around the covered area. Partly covered pixels are mixed with what they were.
+fn poisson_blend(data: &mut [Color], width: i64, height: i64, mask: &[f32], source_offset: (i64, i64)) {
+	let covered_pixels: Vec<(i64, i64)> = (0..height)
+		.flat_map(|y| (0..width).map(move |x| (x, y)))
+		.filter(|&(x, y)| mask[(y * width + x) as usize] > 0.)
+		.collect();
+	let (Some(min), Some(max)) = (
+		covered_pixels.iter().copied().reduce(|(a_x, a_y), (b_x, b_y)| (a_x.min(b_x), a_y.min(b_y))),
+		covered_pixels.iter().copied().reduce(|(a_x, a_y), (b_x, b_y)| (a_x.max(b_x), a_y.max(b_y))),
+	) else {
+		return;
+	};
+
+	// Where each covered pixel within their bounding box is listed, so the solver can find the neighbors it solves for
+	let area_width = max.0 - min.0 + 1;
+	let mut listed = vec![None; (area_width * (max.1 - min.1 + 1)) as usize];
+	for (index, &(x, y)) in covered_pixels.iter().enumerate() {
+		listed[((y - min.1) * area_width + (x - min.0)) as usize] = Some(index);
+	}
+	let listed_at = |x: i64, y: i64| {
+		((min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y))
+			.then(|| listed[((y - min.1) * area_width + (x - min.0)) as usize])
+			.flatten()
+	};
+
+	let channels = |color: Color| [color.r(), color.g(), color.b(), color.a()];
+	let target = |x: i64, y: i64| channels(data[(y * width + x) as usize]);
+	let source = |x: i64, y: i64| channels(data[((y + source_offset.1).clamp(0, height - 1) * width + (x + source_offset.0).clamp(0, width - 1)) as usize]);
+
+	// Everything about a covered pixel that stays the same while solving: the detail of the copied pixel, which is how much it differs from those around it,
+	// plus the uncovered neighbors, which keep the pixels of the target. Pixels beyond the edges of the image are left out, so the edges of the image don't pull on the result.
+	struct Unknown {
+		fixed: [f32; 4],
+		solved_neighbors: Vec<usize>,
+		neighbor_count: f32,
+	}
+	// Starting from the copied pixels shifted by how much they differ from the surroundings on average leaves the solver only the gradual differences to work out
+	let (mut edge_difference, mut edge_count) = ([0.; 4], 0);
+	let unknowns: Vec<Unknown> = covered_pixels
+		.iter()
+		.map(|&(x, y)| {
+			let here = source(x, y);
+			let mut unknown = Unknown {
+				fixed: [0.; 4],
+				solved_neighbors: Vec::new(),
+				neighbor_count: 0.,
+			};
+
+			for (neighbor_x, neighbor_y) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+				if !(0..width).contains(&neighbor_x) || !(0..height).contains(&neighbor_y) {
+					continue;
+				}
+				unknown.neighbor_count += 1.;
+
+				let there = source(neighbor_x, neighbor_y);
+				for ((fixed, here), there) in unknown.fixed.iter_mut().zip(here).zip(there) {
+					*fixed += here - there;
+				}
+
+				match listed_at(neighbor_x, neighbor_y) {
+					Some(index) => unknown.solved_neighbors.push(index),
+					None => {
+						let surrounding = target(neighbor_x, neighbor_y);
+						for (((fixed, difference), surrounding), there) in unknown.fixed.iter_mut().zip(edge_difference.iter_mut()).zip(surrounding).zip(there) {
+							*fixed += surrounding;
+							*difference += surrounding - there;
+						}
+						edge_count += 1;
+					}
+				}
+			}
+
+			unknown
+		})
+		.collect();
+	let shift = edge_difference.map(|difference| if edge_count > 0 { difference / edge_count as f32 } else { 0. });
+
+	let mut solution: Vec<[f32; 4]> = covered_pixels
+		.iter()
+		.map(|&(x, y)| {
+			let copied = source(x, y);
+			std::array::from_fn(|channel| copied[channel] + shift[channel])
+		})
+		.collect();
+
+	// Successive over-relaxation, where each pass moves every covered pixel towards the average of its neighbors plus the detail it should keep,
+	// with the over-relaxation factor which converges fastest for an area the size of the covered one
+	let span = area_width.max(max.1 - min.1 + 1) as f32;
+	let relaxation = 2. / (1. + (std::f32::consts::PI / (span + 1.)).sin());
+	for _ in 0..MAX_POISSON_ITERATIONS {
+		let mut largest_change = 0_f32;
+
+		for (index, unknown) in unknowns.iter().enumerate() {
+			if unknown.neighbor_count == 0. {
+				continue;
+			}
+
+			let mut sum = unknown.fixed;
+			for &neighbor in &unknown.solved_neighbors {
+				for (sum, value) in sum.iter_mut().zip(solution[neighbor]) {
+					*sum += value;
+				}
+			}
+
+			for (solved, sum) in solution[index].iter_mut().zip(sum) {
+				let change = relaxation * (sum / unknown.neighbor_count - *solved);
+				*solved += change;
+				largest_change = largest_change.max(change.abs());
+			}
+		}
+
+		if largest_change < POISSON_TOLERANCE {
+			break;
+		}
+	}
+
+	for (&(x, y), [red, green, blue, alpha]) in covered_pixels.iter().zip(solution) {
+		let alpha = alpha.clamp(0., 1.);
+		let solved = Color::from_rgbaf32_unchecked(red.clamp(0., alpha), green.clamp(0., alpha), blue.clamp(0., alpha), alpha);
+
+		let index = (y * width + x) as usize;
+		data[index] = data[index].lerp(&solved, mask[index]);
+	}
+}
+
+/// The average color of the pixels within `radius` of a pixel, where pixels beyond the edges of the image repeat the nearest edge pixel.
+fn box_blur(data: &[Color], width: i64, height: i64, x: i64, y: i64, radius: i64) -> Color {
+	let mut sum = [0.; 4];
+	for y in y - radius..=y + radius {
+		for x in x - radius..=x + radius {
+			let color = data[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize];
+			for (sum, channel) in sum.iter_mut().zip([color.r(), color.g(), color.b(), color.a()]) {
+				*sum += channel;
+			}
+		}
+	}
+
+	let [red, green, blue, alpha] = sum.map(|sum| sum / ((2 * radius + 1) * (2 * radius + 1)) as f32);
+	Color::from_rgbaf32_unchecked(red, green, blue, alpha)
+}
+
+/// Lightens a color when `amount` is positive, or darkens it when negative, by raising its gamma-encoded channels to a power, which keeps black and white unchanged.
+fn dodge_burn(color: Color, amount: f32) -> Color {
+	let exponent = if amount >= 0. { 1. / (1. + amount) } else { 1. - amount };
+	let alpha = color.a();
+	let adjusted = color.to_unassociated_alpha().map_gamma_rgb(|channel| channel.clamp(0., 1.).powf(exponent));
+
+	Color::new_from_unassociated_rgba(adjusted.r(), adjusted.g(), adjusted.b(), alpha)
+}
+
+/// Composites a stamp onto the stroke texture, without letting it make the stroke more opaque than `opacity` unless it already was.
+fn blend_within_stroke(foreground: Color, background: Color, opacity: f32) -> Color {
+	let blended = blend_colors(foreground, background, BlendMode::Normal, 1.);
+	let limit = background.a().max(opacity);
+
+	if blended.a() > limit { blended.apply_opacity(limit / blended.a()) } else { blended }
+}
+
 pub fn blend_image_closure(foreground: Item<Raster<CPU>>, mut background: Item<Raster<CPU>>, map_fn: impl Fn(Color, Color) -> Color) -> Item<Raster<CPU>> {
 	let foreground_size = DVec2::new(foreground.element().width as f64, foreground.element().height as f64);
 	let background_size = DVec2::new(background.element().width as f64, background.element().height as f64);
@@ -391,7 +850,7 @@ pub fn blend_stamp_closure(foreground: BrushStampGenerator<Color>, mut backgroun
 #[cfg(test)]
 mod test {
 	use super::*;
-	use crate::brush_stroke::BrushStroke;
+	use crate::brush_stroke::{BrushInputSample, BrushSampling, BrushTip};
 	use core_types::transform::Transform;
 	use glam::DAffine2;
 
@@ -411,7 +870,7 @@ mod test {
 			&BrushCache::default(),
 			Item::new_from_element(Raster::new_cpu(Image::<Color>::default())),
 			Item::new_from_element(BrushTrace::from(vec![BrushStroke {
-				trace: vec![crate::brush_stroke::BrushInputSample { position: DVec2::ZERO }],
+				trace: vec![BrushInputSample::default()],
 				style: BrushStyle {
 					color: Color::BLACK,
 					diameter: 20.,
@@ -419,10 +878,208 @@ mod test {
 					flow: 20.,
 					spacing: 20.,
 					blend_mode: BlendMode::Normal,
+					dynamics: Default::default(),
+					tip: Default::default(),
+					jitter: Default::default(),
+					mode: BrushMode::Paint,
+					sampling: Default::default(),
 				},
 			}])),
+			Item::default(),
+			Item::default(),
 		)
 		.await;
 		assert_eq!(image.element().width, 20);
 	}
+
+	#[tokio::test]
+	async fn textured_tip_is_rotated() {
+		// Paints only on its left half
+		let tip_texture = Raster::new_cpu(Image {
+			width: 2,
+			height: 1,
+			data: vec![Color::BLACK, Color::WHITE],
+			..Default::default()
+		});
+		let mut style = BrushStyle {
+			diameter: 20.,
+			tip: BrushTip {
+				shape: BrushTipShape::Texture,
+				..Default::default()
+			},
+			..Default::default()
+		};
+		let alpha_at = |tip: &Raster<CPU>, x: u32, y: u32| tip.data[(y * tip.width + x) as usize].a();
+
+		let tip = create_brush_texture(&style, &tip_texture).await;
+		assert_eq!((tip.width, tip.height), (29, 29));
+		assert!(alpha_at(&tip, 9, 14) > 0.9 && alpha_at(&tip, 19, 14) < 0.1);
+
+		style.tip.angle = 180.;
+		let tip = create_brush_texture(&style, &tip_texture).await;
+		assert!(alpha_at(&tip, 9, 14) < 0.1 && alpha_at(&tip, 19, 14) > 0.9);
+
+		// Without a texture, the tip falls back to being round
+		let round_tip = create_brush_texture(&style, &Raster::default()).await;
+		assert_eq!(round_tip.width, 20);
+	}
+
+	#[tokio::test]
+	async fn clone_mode_copies_from_the_source_offset() {
+		// Black on the left half, white on the right half
+		let data = (0..20).flat_map(|_| (0..40).map(|x| if x < 20 { Color::BLACK } else { Color::WHITE })).collect();
+		let image = Item::new_from_element(Raster::new_cpu(Image {
+			width: 40,
+			height: 20,
+			data,
+			..Default::default()
+		}))
+		.with_attribute(ATTR_TRANSFORM, DAffine2::from_scale(DVec2::new(40., 20.)));
+		let stroke = BrushStroke {
+			trace: vec![BrushInputSample {
+				position: DVec2::new(30., 10.),
+				..Default::default()
+			}],
+			style: BrushStyle {
+				diameter: 8.,
+				hardness: 100.,
+				mode: BrushMode::Clone,
+				sampling: BrushSampling {
+					strength: 100.,
+					source_offset: DVec2::new(-20., 0.),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		};
+
+		let image = apply_sampling_stroke(image, &stroke, &Raster::default(), &BrushCache::default()).await;
+		assert_eq!(image.element().data[10 * 40 + 30], Color::BLACK);
+		assert_eq!(image.element().data[10 * 40 + 38], Color::WHITE);
+	}
+
+	#[tokio::test]
+	async fn heal_mode_matches_the_surrounding_colors() {
+		// Dark gray on the left half, light gray on the right half
+		let (dark, light) = (Color::from_rgbaf32_unchecked(0.2, 0.2, 0.2, 1.), Color::from_rgbaf32_unchecked(0.8, 0.8, 0.8, 1.));
+		let data = (0..20).flat_map(|_| (0..40).map(move |x| if x < 20 { dark } else { light })).collect();
+		let image = Item::new_from_element(Raster::new_cpu(Image {
+			width: 40,
+			height: 20,
+			data,
+			..Default::default()
+		}))
+		.with_attribute(ATTR_TRANSFORM, DAffine2::from_scale(DVec2::new(40., 20.)));
+		let stroke = BrushStroke {
+			trace: vec![BrushInputSample {
+				position: DVec2::new(30., 10.),
+				..Default::default()
+			}],
+			style: BrushStyle {
+				diameter: 8.,
+				hardness: 100.,
+				mode: BrushMode::Heal,
+				sampling: BrushSampling {
+					strength: 100.,
+					source_offset: DVec2::new(-20., 0.),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		};
+
+		// Unlike cloning, the copied pixels take on the color of the pixels around the stroke
+		let image = apply_sampling_stroke(image, &stroke, &Raster::default(), &BrushCache::default()).await;
+		assert!((image.element().data[10 * 40 + 30].r() - light.r()).abs() < 0.01);
+	}
+
+	#[tokio::test]
+	async fn patch_mode_replaces_the_region_seamlessly() {
+		// A gradient from dark to light with a white blemish, which is patched with the gradient further down
+		let gray = |x: i64| {
+			let value = (x as f32 + 0.5) / 20.;
+			Color::from_rgbaf32_unchecked(value, value, value, 1.)
+		};
+		let data = (0..40).flat_map(|y| (0..20).map(move |x| if (x, y) == (10, 10) { Color::WHITE } else { gray(x) })).collect();
+		let image = Item::new_from_element(Raster::new_cpu(Image {
+			width: 20,
+			height: 40,
+			data,
+			..Default::default()
+		}))
+		.with_attribute(ATTR_TRANSFORM, DAffine2::from_scale(DVec2::new(20., 40.)));
+		let corners = [DVec2::new(6., 6.), DVec2::new(14., 6.), DVec2::new(14., 14.), DVec2::new(6., 14.)];
+		let stroke = BrushStroke {
+			trace: corners.into_iter().map(|position| BrushInputSample { position, ..Default::default() }).collect(),
+			style: BrushStyle {
+				mode: BrushMode::Patch,
+				sampling: BrushSampling {
+					strength: 100.,
+					source_offset: DVec2::new(0., 20.),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		};
+		let bounds = stroke.bounding_box();
+		assert_eq!((bounds.start, bounds.end), (corners[0], corners[2]));
+
+		let image = apply_sampling_stroke(image, &stroke, &Raster::default(), &BrushCache::default()).await;
+		for x in 6..14 {
+			assert!((image.element().data[10 * 20 + x].r() - gray(x as i64).r()).abs() < 0.01);
+		}
+		// Outside the region, the pixels are left alone
+		assert_eq!(image.element().data[20 * 20 + 10], gray(10));
+	}
+
+	#[tokio::test]
+	async fn patch_mode_converges_over_large_regions() {
+		// A gradient from dark to light in the top half, patched with the flat gray of the bottom half, which has no detail to keep
+		const SIZE: i64 = 160;
+		let gray = |x: i64| {
+			let value = (x as f32 + 0.5) / SIZE as f32;
+			Color::from_rgbaf32_unchecked(value, value, value, 1.)
+		};
+		let flat = Color::from_rgbaf32_unchecked(0.1, 0.1, 0.1, 1.);
+		let data = (0..2 * SIZE).flat_map(|y| (0..SIZE).map(move |x| if y < SIZE { gray(x) } else { flat })).collect();
+		let image = Item::new_from_element(Raster::new_cpu(Image {
+			width: SIZE as u32,
+			height: 2 * SIZE as u32,
+			data,
+			..Default::default()
+		}))
+		.with_attribute(ATTR_TRANSFORM, DAffine2::from_scale(DVec2::new(SIZE as f64, 2. * SIZE as f64)));
+		let (start, end) = (10., SIZE as f64 - 10.);
+		let corners = [DVec2::new(start, start), DVec2::new(end, start), DVec2::new(end, end), DVec2::new(start, end)];
+		let stroke = BrushStroke {
+			trace: corners.into_iter().map(|position| BrushInputSample { position, ..Default::default() }).collect(),
+			style: BrushStyle {
+				mode: BrushMode::Patch,
+				sampling: BrushSampling {
+					strength: 100.,
+					source_offset: DVec2::new(0., SIZE as f64),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		};
+
+		// Without any detail to keep, the solved region blends smoothly between its surroundings, which recreates the gradient
+		let image = apply_sampling_stroke(image, &stroke, &Raster::default(), &BrushCache::default()).await;
+		for y in [11, SIZE / 2, SIZE - 11] {
+			for x in 10..SIZE - 10 {
+				assert!((image.element().data[(y * SIZE + x) as usize].r() - gray(x).r()).abs() < 0.01, "pixel ({x}, {y})");
+			}
+		}
+	}
+
+	#[test]
+	fn dodge_and_burn_keep_black_and_white() {
+		let gray = Color::from_gamma_srgb_channels(0.5, 0.5, 0.5, 1.);
+
+		assert!(dodge_burn(gray, 0.5).r() > gray.r());
+		assert!(dodge_burn(gray, -0.5).r() < gray.r());
+		assert!((dodge_burn(Color::WHITE, 1.).r() - 1.).abs() < 1e-5);
+		assert_eq!(dodge_burn(Color::BLACK, -1.), Color::BLACK);
+	}
 }
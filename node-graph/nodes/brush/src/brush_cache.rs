@@ -18,12 +18,29 @@ struct BrushCacheImpl {
 	blended_image: Item<Raster<CPU>>,
 	last_stroke_texture: Item<Raster<CPU>>,
 
+	// The textures of the brush node that the cached images were drawn with.
+	tip_texture: Raster<CPU>,
+	dual_texture: Raster<CPU>,
+
 	// A cache for brush textures.
 	brush_texture_cache: HashMap<CacheHashWrapper<BrushStyle>, Raster<CPU>>,
 }
 
 impl BrushCacheImpl {
-	fn compute_brush_plan(&mut self, mut background: Item<Raster<CPU>>, input: &[BrushStroke]) -> BrushPlan {
+	fn compute_brush_plan(&mut self, mut background: Item<Raster<CPU>>, input: &[BrushStroke], tip_texture: &Raster<CPU>, dual_texture: &Raster<CPU>) -> BrushPlan {
+		// Do texture invalidation, which also discards the brush tips made from the previous tip texture.
+		if *tip_texture != self.tip_texture || *dual_texture != self.dual_texture {
+			self.tip_texture = tip_texture.clone();
+			self.dual_texture = dual_texture.clone();
+			self.brush_texture_cache.clear();
+			self.background = background.clone();
+			return BrushPlan {
+				strokes: input.to_vec(),
+				background,
+				..Default::default()
+			};
+		}
+
 		// Do background invalidation.
 		if background != self.background {
 			self.background = background.clone();
@@ -57,12 +74,12 @@ impl BrushCacheImpl {
 		if !strokes.is_empty() && self.prev_input.len() > num_blended_strokes {
 			let last_stroke = &self.prev_input[num_blended_strokes];
 			let same_style = strokes[0].style == last_stroke.style;
-			let prev_points = last_stroke.compute_blit_points();
-			let new_points = strokes[0].compute_blit_points();
-			let is_point_prefix = new_points.get(..prev_points.len()) == Some(&prev_points);
-			if same_style && is_point_prefix {
+			let prev_stamps = last_stroke.compute_blit_stamps();
+			let new_stamps = strokes[0].compute_blit_stamps();
+			let is_stamp_prefix = new_stamps.get(..prev_stamps.len()) == Some(&prev_stamps);
+			if same_style && is_stamp_prefix {
 				first_stroke_texture = std::mem::take(&mut self.last_stroke_texture);
-				first_stroke_point_skip = prev_points.len();
+				first_stroke_point_skip = prev_stamps.len();
 			}
 		}
 
@@ -94,9 +111,9 @@ pub struct BrushPlan {
 pub struct BrushCache(Arc<Mutex<BrushCacheImpl>>);
 
 impl BrushCache {
-	pub fn compute_brush_plan(&self, background: Item<Raster<CPU>>, input: &[BrushStroke]) -> BrushPlan {
+	pub fn compute_brush_plan(&self, background: Item<Raster<CPU>>, input: &[BrushStroke], tip_texture: &Raster<CPU>, dual_texture: &Raster<CPU>) -> BrushPlan {
 		let mut inner = self.0.lock().unwrap();
-		inner.compute_brush_plan(background, input)
+		inner.compute_brush_plan(background, input, tip_texture, dual_texture)
 	}
 
 	pub fn cache_results(&self, input: Vec<BrushStroke>, blended_image: Item<Raster<CPU>>, last_stroke_texture: Item<Raster<CPU>>) {
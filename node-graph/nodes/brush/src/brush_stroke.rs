@@ -5,6 +5,18 @@ use core_types::list::{Item, List};
 use core_types::math::bbox::AxisAlignedBbox;
 use dyn_any::DynAny;
 use glam::DVec2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The speed of the stylus, in pixels per millisecond, at which velocity dynamics take full effect.
+const FULL_VELOCITY: f64 = 5.;
+
+/// The number of steps each dynamics factor is rounded to, which limits how many distinct brush textures a stroke needs.
+const DYNAMICS_STEPS: f64 = 64.;
+
+/// The number of degrees each stamp's rotation is rounded to, which limits how many rotated copies of a textured tip a stroke needs.
+const ANGLE_STEP: f64 = 2.;
+
 /// The style of a brush.
 #[derive(Clone, Debug, CacheHash, DynAny)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -15,6 +27,16 @@ pub struct BrushStyle {
 	pub flow: f64,
 	pub spacing: f64, // Spacing as a fraction of the diameter.
 	pub blend_mode: BlendMode,
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub dynamics: BrushDynamics,
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub tip: BrushTip,
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub jitter: BrushJitter,
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub mode: BrushMode,
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub sampling: BrushSampling,
 }
 
 impl Default for BrushStyle {
@@ -26,6 +48,11 @@ impl Default for BrushStyle {
 			flow: 100.,
 			spacing: 50., // Percentage of diameter.
 			blend_mode: BlendMode::Normal,
+			dynamics: BrushDynamics::default(),
+			tip: BrushTip::default(),
+			jitter: BrushJitter::default(),
+			mode: BrushMode::default(),
+			sampling: BrushSampling::default(),
 		}
 	}
 }
@@ -40,14 +67,357 @@ impl PartialEq for BrushStyle {
 			&& self.flow.to_bits() == other.flow.to_bits()
 			&& self.spacing.to_bits() == other.spacing.to_bits()
 			&& self.blend_mode == other.blend_mode
+			&& self.dynamics == other.dynamics
+			&& self.tip == other.tip
+			&& self.jitter == other.jitter
+			&& self.mode == other.mode
+			&& self.sampling == other.sampling
+	}
+}
+
+/// How a brush stroke changes the layer.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BrushMode {
+	/// Paints with the brush color, using the blend mode.
+	#[default]
+	Paint,
+	/// Drags the colors under the brush along the stroke.
+	Smudge,
+	/// Softens the detail under the brush.
+	Blur,
+	/// Brings out the detail under the brush.
+	Sharpen,
+	/// Lightens the colors under the brush.
+	Dodge,
+	/// Darkens the colors under the brush.
+	Burn,
+	/// Copies the colors found at the source offset from the brush, as they were before the stroke.
+	Clone,
+	/// Copies the texture found at the source offset from the brush, while matching the colors and luminance around the stroke.
+	Heal,
+	/// Replaces the region outlined by the trace with the texture found at the source offset from it, while matching the colors and luminance around the region.
+	Patch,
+}
+
+impl BrushMode {
+	/// The modes offered by the Brush tool, leaving out Heal and Patch which have tools of their own.
+	pub fn list() -> [Self; 7] {
+		[Self::Paint, Self::Smudge, Self::Blur, Self::Sharpen, Self::Dodge, Self::Burn, Self::Clone]
+	}
+
+	/// Whether strokes rework the existing pixels of the layer, rather than painting the brush color onto it.
+	pub fn samples_layer(&self) -> bool {
+		*self != Self::Paint
+	}
+}
+
+/// The parameters of the brush modes which rework the existing pixels of the layer.
+#[derive(Clone, Copy, Debug, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BrushSampling {
+	/// How strongly each stamp changes the pixels under it, as a percentage.
+	pub strength: f64,
+	/// Where a clone, heal, or patch stroke copies from, relative to each stamp or to the patched region, in layer space.
+	pub source_offset: DVec2,
+	/// How far a patch fades in from the outline of its region, in layer space.
+	pub feather: f64,
+}
+
+impl Default for BrushSampling {
+	fn default() -> Self {
+		Self {
+			strength: 50.,
+			source_offset: DVec2::ZERO,
+			feather: 0.,
+		}
+	}
+}
+
+impl Eq for BrushSampling {}
+
+impl PartialEq for BrushSampling {
+	fn eq(&self, other: &Self) -> bool {
+		self.strength.to_bits() == other.strength.to_bits() && self.source_offset == other.source_offset && self.feather.to_bits() == other.feather.to_bits()
+	}
+}
+
+/// The shape which is stamped along a brush stroke.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BrushTipShape {
+	/// A round tip whose edge is softened according to the hardness.
+	#[default]
+	Round,
+	/// The tip texture of the Brush node, scaled so its longest side spans the diameter. Darker and more opaque pixels paint more strongly.
+	Texture,
+}
+
+impl BrushTipShape {
+	pub fn list() -> [Self; 2] {
+		[Self::Round, Self::Texture]
+	}
+}
+
+/// The shape and orientation of a brush tip.
+#[derive(Clone, Copy, Debug, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BrushTip {
+	pub shape: BrushTipShape,
+	/// The clockwise rotation of the tip, in degrees.
+	pub angle: f64,
+	/// Whether the tip also turns to face the direction the stroke travels in.
+	pub follow_stroke: bool,
+	/// Whether the stroke is multiplied by the dual texture of the Brush node, which is tiled across the layer.
+	pub dual_texture: bool,
+	/// The size of the dual texture's tiles, as a percentage of the size of the texture.
+	pub dual_texture_scale: f64,
+}
+
+impl Default for BrushTip {
+	fn default() -> Self {
+		Self {
+			shape: BrushTipShape::Round,
+			angle: 0.,
+			follow_stroke: false,
+			dual_texture: false,
+			dual_texture_scale: 100.,
+		}
+	}
+}
+
+impl Eq for BrushTip {}
+
+impl PartialEq for BrushTip {
+	fn eq(&self, other: &Self) -> bool {
+		self.shape == other.shape
+			&& self.angle.to_bits() == other.angle.to_bits()
+			&& self.follow_stroke == other.follow_stroke
+			&& self.dual_texture == other.dual_texture
+			&& self.dual_texture_scale.to_bits() == other.dual_texture_scale.to_bits()
+	}
+}
+
+impl BrushTip {
+	/// The farthest a stamp of this tip reaches from its center, since a textured tip's corners stick out of the diameter when it's rotated.
+	pub fn radius(&self, diameter: f64) -> f64 {
+		match self.shape {
+			BrushTipShape::Round => diameter / 2.,
+			BrushTipShape::Texture => diameter * core::f64::consts::SQRT_2 / 2.,
+		}
+	}
+}
+
+/// The random variation of each stamp along a stroke.
+#[derive(Clone, Copy, Debug, Default, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BrushJitter {
+	/// The largest rotation of a stamp in either direction, in degrees.
+	pub rotation: f64,
+	/// The farthest a stamp strays to either side of the stroke, as a percentage of the diameter.
+	pub scatter: f64,
+	/// The largest reduction of a stamp's diameter, as a percentage.
+	pub size: f64,
+	/// The largest reduction of a stamp's opacity, as a percentage.
+	pub opacity: f64,
+	/// Seeds the random variation, so the stroke looks the same every time it's drawn.
+	pub seed: u32,
+}
+
+impl Eq for BrushJitter {}
+
+impl PartialEq for BrushJitter {
+	fn eq(&self, other: &Self) -> bool {
+		self.rotation.to_bits() == other.rotation.to_bits()
+			&& self.scatter.to_bits() == other.scatter.to_bits()
+			&& self.size.to_bits() == other.size.to_bits()
+			&& self.opacity.to_bits() == other.opacity.to_bits()
+			&& self.seed == other.seed
+	}
+}
+
+/// The stylus input which drives a [`BrushDynamicsCurve`].
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BrushDynamicsInput {
+	/// The brush parameter stays constant.
+	#[default]
+	Off,
+	/// How hard the stylus is pressed onto the tablet.
+	Pressure,
+	/// How far the stylus leans away from upright, reaching its maximum when it lies flat.
+	Tilt,
+	/// The rotation of the stylus around its own axis, over one full turn.
+	Twist,
+	/// How fast the stylus moves, reaching its maximum at [`FULL_VELOCITY`].
+	Velocity,
+}
+
+impl BrushDynamicsInput {
+	pub fn list() -> [Self; 5] {
+		[Self::Off, Self::Pressure, Self::Tilt, Self::Twist, Self::Velocity]
+	}
+}
+
+/// Maps a stylus input to a factor that a brush parameter is multiplied by at each stamp.
+#[derive(Clone, Copy, Debug, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BrushDynamicsCurve {
+	pub input: BrushDynamicsInput,
+	/// The percentage of the parameter which remains when the input is at its lowest.
+	pub minimum: f64,
+	/// The exponent the input is raised to, where values above 1 need a stronger input before the parameter grows and values below 1 need a weaker one.
+	pub gamma: f64,
+	/// Whether the parameter shrinks, rather than grows, as the input increases.
+	pub invert: bool,
+}
+
+impl Default for BrushDynamicsCurve {
+	fn default() -> Self {
+		Self {
+			input: BrushDynamicsInput::Off,
+			minimum: 0.,
+			gamma: 1.,
+			invert: false,
+		}
+	}
+}
+
+impl Eq for BrushDynamicsCurve {}
+
+impl PartialEq for BrushDynamicsCurve {
+	fn eq(&self, other: &Self) -> bool {
+		self.input == other.input && self.minimum.to_bits() == other.minimum.to_bits() && self.gamma.to_bits() == other.gamma.to_bits() && self.invert == other.invert
+	}
+}
+
+impl BrushDynamicsCurve {
+	/// The factor, from the minimum up to 1, for the stylus state at a point of the stroke.
+	pub fn factor(&self, state: &BrushInputSample, velocity: f64) -> f64 {
+		let input = match self.input {
+			BrushDynamicsInput::Off => return 1.,
+			BrushDynamicsInput::Pressure => state.pressure,
+			BrushDynamicsInput::Tilt => state.tilt.abs().max_element() / 90.,
+			BrushDynamicsInput::Twist => state.twist.rem_euclid(360.) / 360.,
+			BrushDynamicsInput::Velocity => velocity / FULL_VELOCITY,
+		};
+		let input = input.clamp(0., 1.);
+		let input = if self.invert { 1. - input } else { input };
+
+		let minimum = (self.minimum / 100.).clamp(0., 1.);
+		let factor = minimum + (1. - minimum) * input.powf(self.gamma.max(0.));
+
+		(factor * DYNAMICS_STEPS).round() / DYNAMICS_STEPS
+	}
+}
+
+/// The curves which vary each brush parameter along a stroke according to the stylus input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CacheHash, DynAny)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BrushDynamics {
+	pub diameter: BrushDynamicsCurve,
+	pub flow: BrushDynamicsCurve,
+	/// Limits how opaque the stroke can build up to where each stamp lands, unlike flow which sets the opacity of the individual stamps.
+	pub opacity: BrushDynamicsCurve,
+	pub hardness: BrushDynamicsCurve,
+}
+
+impl BrushDynamics {
+	/// Whether every parameter ignores the stylus input, so all stamps of a stroke are identical.
+	pub fn is_constant(&self) -> bool {
+		[self.diameter, self.flow, self.opacity, self.hardness].iter().all(|curve| curve.input == BrushDynamicsInput::Off)
 	}
 }
 
 /// A single sample of brush parameters across the brush stroke.
 #[derive(Clone, Debug, PartialEq, core_types::CacheHash, DynAny)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct BrushInputSample {
 	pub position: DVec2,
+	/// How hard the stylus is pressed, from 0 to 1. Always 1 for a mouse.
+	pub pressure: f64,
+	/// The angles between the stylus and the tablet's normal along the X and Y axes, in degrees from -90 to 90.
+	pub tilt: DVec2,
+	/// The clockwise rotation of the stylus around its own axis, in degrees from 0 to 359.
+	pub twist: f64,
+	/// When the sample was recorded, in milliseconds.
+	pub time: f64,
+}
+
+impl Default for BrushInputSample {
+	fn default() -> Self {
+		Self {
+			position: DVec2::ZERO,
+			pressure: 1.,
+			tilt: DVec2::ZERO,
+			twist: 0.,
+			time: 0.,
+		}
+	}
+}
+
+impl BrushInputSample {
+	fn lerp(&self, other: &Self, t: f64) -> Self {
+		let lerp = |a: f64, b: f64| a + (b - a) * t;
+		// Twist wraps around, so turn the short way between the two angles (from 350° to 10° is 20° forward, not 340° back)
+		let twist_delta = (other.twist - self.twist + 180.).rem_euclid(360.) - 180.;
+		Self {
+			position: self.position.lerp(other.position, t),
+			pressure: lerp(self.pressure, other.pressure),
+			tilt: self.tilt.lerp(other.tilt, t),
+			twist: (self.twist + twist_delta * t).rem_euclid(360.),
+			time: lerp(self.time, other.time),
+		}
+	}
+}
+
+/// A placement of the brush tip along a stroke, with the parameters of the brush style scaled by its dynamics.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BrushStamp {
+	pub position: DVec2,
+	pub diameter: f64,
+	pub hardness: f64,
+	pub flow: f64,
+	/// The most opaque the stroke may become where this stamp lands, from 0 to 1.
+	pub opacity: f64,
+	/// The clockwise rotation of the tip, in degrees from 0 to 360.
+	pub angle: f64,
+}
+
+impl BrushStamp {
+	/// The brush style which draws this stamp's texture.
+	pub fn style(&self, style: &BrushStyle) -> BrushStyle {
+		BrushStyle {
+			diameter: self.diameter,
+			hardness: self.hardness,
+			flow: self.flow,
+			dynamics: BrushDynamics::default(),
+			// Only the shape and rotation of the tip affect its texture, so leave out the rest for the texture to be shared between strokes
+			tip: BrushTip {
+				shape: style.tip.shape,
+				angle: self.angle,
+				..Default::default()
+			},
+			jitter: BrushJitter::default(),
+			mode: BrushMode::default(),
+			sampling: BrushSampling::default(),
+			..style.clone()
+		}
+	}
+
+	/// Whether both stamps are drawn with the same texture and opacity.
+	pub fn same_tip(&self, other: &Self) -> bool {
+		self.diameter == other.diameter && self.hardness == other.hardness && self.flow == other.flow && self.opacity == other.opacity && self.angle == other.angle
+	}
 }
 
 /// The parameters for a single stroke brush.
@@ -74,35 +444,81 @@ impl From<Vec<BrushStroke>> for BrushTrace {
 	}
 }
 
+/// Whether a point lies inside a polygon, by the even-odd rule, counting the edges crossed by a ray towards the right of the point.
+pub fn polygon_contains(polygon: &[DVec2], point: DVec2) -> bool {
+	let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+	let crossings = edges
+		.filter(|&(a, b)| (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x))
+		.count();
+
+	polygon.len() >= 3 && crossings % 2 == 1
+}
+
 impl BrushStroke {
 	pub fn bounding_box(&self) -> AxisAlignedBbox {
-		let radius = self.style.diameter / 2.;
-		self.compute_blit_points()
+		// A patch changes only the region its trace outlines
+		if self.style.mode == BrushMode::Patch {
+			return self
+				.trace
+				.iter()
+				.map(|sample| AxisAlignedBbox {
+					start: sample.position,
+					end: sample.position,
+				})
+				.reduce(|a, b| a.union(&b))
+				.unwrap_or(AxisAlignedBbox::ZERO);
+		}
+
+		self.compute_blit_stamps()
 			.iter()
-			.map(|pos| AxisAlignedBbox {
-				start: *pos + DVec2::new(-radius, -radius),
-				end: *pos + DVec2::new(radius, radius),
+			.map(|stamp| {
+				let radius = self.style.tip.radius(stamp.diameter);
+				AxisAlignedBbox {
+					start: stamp.position + DVec2::new(-radius, -radius),
+					end: stamp.position + DVec2::new(radius, radius),
+				}
 			})
 			.reduce(|a, b| a.union(&b))
 			.unwrap_or(AxisAlignedBbox::ZERO)
 	}
 
-	pub fn compute_blit_points(&self) -> Vec<DVec2> {
+	pub fn compute_blit_stamps(&self) -> Vec<BrushStamp> {
+		// A patch outlines a region rather than stamping along its trace
+		if self.style.mode == BrushMode::Patch {
+			return Vec::new();
+		}
+
 		// We always travel in a straight line towards the next user input,
 		// placing a blit point every time we travelled our spacing distance.
-		let spacing_dist = self.style.spacing / 100. * self.style.diameter;
+		let spacing_dist = |stamp: &BrushStamp| self.style.spacing / 100. * stamp.diameter;
 
 		let Some(first_sample) = self.trace.first() else {
 			return Vec::new();
 		};
 
+		let velocities = self.sample_velocities();
+		// The jitter of each stamp is drawn in order, so extending the stroke leaves its existing stamps unchanged
+		let mut rng = StdRng::seed_from_u64(self.style.jitter.seed as u64);
+
+		// Before the stroke moves, it faces towards wherever it goes first
+		let first_direction = self
+			.trace
+			.iter()
+			.map(|sample| sample.position - first_sample.position)
+			.find(|delta| delta.length() > 0.)
+			.unwrap_or(DVec2::X);
+
 		let mut cur_pos = first_sample.position;
-		let mut result = vec![cur_pos];
-		let mut dist_until_next_blit = spacing_dist;
-		for sample in &self.trace[1..] {
+		let first_stamp = self.stamp(first_sample, velocities[0]);
+		let mut dist_until_next_blit = spacing_dist(&first_stamp);
+		let mut result = vec![self.jitter(first_stamp, first_direction.normalize(), &mut rng)];
+		for (index, sample) in self.trace.iter().enumerate().skip(1) {
+			let previous = &self.trace[index - 1];
+
 			// Travel to the next sample.
 			let delta = sample.position - cur_pos;
-			let mut dist_left = delta.length();
+			let length = delta.length();
+			let mut dist_left = length;
 			let unit_step = delta / dist_left;
 
 			while dist_left >= dist_until_next_blit {
@@ -110,9 +526,16 @@ impl BrushStroke {
 				cur_pos += dist_until_next_blit * unit_step;
 				dist_left -= dist_until_next_blit;
 
-				// Blit.
-				result.push(cur_pos);
-				dist_until_next_blit = spacing_dist;
+				// Blit, with the stylus state interpolated between the surrounding samples.
+				let t = 1. - dist_left / length;
+				let velocity = velocities[index - 1] + (velocities[index] - velocities[index - 1]) * t;
+				let state = BrushInputSample {
+					position: cur_pos,
+					..previous.lerp(sample, t)
+				};
+				let stamp = self.stamp(&state, velocity);
+				dist_until_next_blit = spacing_dist(&stamp);
+				result.push(self.jitter(stamp, unit_step, &mut rng));
 			}
 
 			// Take the partial step to land at the sample.
@@ -122,4 +545,219 @@ impl BrushStroke {
 
 		result
 	}
+
+	/// The speed of the stylus at each sample, in pixels per millisecond, smoothed to even out the jitter between pointer events.
+	fn sample_velocities(&self) -> Vec<f64> {
+		let mut velocity = 0.;
+
+		(0..self.trace.len())
+			.map(|index| {
+				if let Some(previous) = index.checked_sub(1).map(|index| &self.trace[index]) {
+					let sample = &self.trace[index];
+					let elapsed = sample.time - previous.time;
+					if elapsed > 0. {
+						velocity = (velocity + sample.position.distance(previous.position) / elapsed) / 2.;
+					}
+				}
+				velocity
+			})
+			.collect()
+	}
+
+	fn stamp(&self, state: &BrushInputSample, velocity: f64) -> BrushStamp {
+		let BrushDynamics { diameter, flow, opacity, hardness } = &self.style.dynamics;
+		// Never shrink the brush below a pixel, unless it was already that small
+		let minimum_diameter = self.style.diameter.min(1.);
+
+		BrushStamp {
+			position: state.position,
+			diameter: (self.style.diameter * diameter.factor(state, velocity)).max(minimum_diameter),
+			hardness: self.style.hardness * hardness.factor(state, velocity),
+			flow: self.style.flow * flow.factor(state, velocity),
+			opacity: opacity.factor(state, velocity),
+			angle: 0.,
+		}
+	}
+
+	/// Rotates the stamp and applies the random variation of the style's jitter, where `direction` is the unit vector the stroke travels in.
+	fn jitter(&self, mut stamp: BrushStamp, direction: DVec2, rng: &mut StdRng) -> BrushStamp {
+		let BrushJitter { rotation, scatter, size, opacity, .. } = self.style.jitter;
+		let BrushTip { shape, angle, follow_stroke, .. } = self.style.tip;
+		// Every stamp draws the same amount of random numbers, so changing one kind of jitter keeps the others in place
+		let [rotation_random, scatter_random, size_random, opacity_random] = std::array::from_fn(|_| rng.random::<f64>());
+		let quantize = |factor: f64| (factor * DYNAMICS_STEPS).round() / DYNAMICS_STEPS;
+
+		stamp.position += direction.perp() * (scatter_random * 2. - 1.) * scatter / 100. * stamp.diameter;
+		stamp.diameter = (stamp.diameter * quantize(1. - size_random * size.clamp(0., 100.) / 100.)).max(self.style.diameter.min(1.));
+		stamp.opacity *= quantize(1. - opacity_random * opacity.clamp(0., 100.) / 100.);
+
+		// Round tips look the same at every angle
+		if shape != BrushTipShape::Round {
+			let stroke_angle = if follow_stroke { direction.to_angle().to_degrees() } else { 0. };
+			let angle = angle + stroke_angle + (rotation_random * 2. - 1.) * rotation;
+			stamp.angle = ((angle / ANGLE_STEP).round() * ANGLE_STEP).rem_euclid(360.);
+		}
+
+		stamp
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn stroke(dynamics: BrushDynamics, trace: Vec<BrushInputSample>) -> BrushStroke {
+		BrushStroke {
+			style: BrushStyle {
+				diameter: 20.,
+				spacing: 50.,
+				dynamics,
+				..Default::default()
+			},
+			trace,
+		}
+	}
+
+	#[test]
+	fn constant_dynamics_keep_the_style() {
+		let trace = vec![
+			BrushInputSample { pressure: 0.2, ..Default::default() },
+			BrushInputSample {
+				position: DVec2::new(100., 0.),
+				pressure: 0.8,
+				..Default::default()
+			},
+		];
+		let stamps = stroke(BrushDynamics::default(), trace).compute_blit_stamps();
+
+		assert_eq!(stamps.len(), 11);
+		assert!(stamps.iter().all(|stamp| stamp.diameter == 20. && stamp.flow == 100. && stamp.opacity == 1.));
+		assert!(stamps.windows(2).all(|pair| (pair[1].position.x - pair[0].position.x - 10.).abs() < 1e-9));
+	}
+
+	#[test]
+	fn pressure_scales_diameter_and_spacing() {
+		let dynamics = BrushDynamics {
+			diameter: BrushDynamicsCurve {
+				input: BrushDynamicsInput::Pressure,
+				..Default::default()
+			},
+			..Default::default()
+		};
+		let trace = vec![
+			BrushInputSample { pressure: 0.25, ..Default::default() },
+			BrushInputSample {
+				position: DVec2::new(100., 0.),
+				pressure: 1.,
+				..Default::default()
+			},
+		];
+		let stamps = stroke(dynamics, trace).compute_blit_stamps();
+
+		assert_eq!(stamps[0].diameter, 5.);
+		assert!(stamps.windows(2).all(|pair| pair[1].diameter >= pair[0].diameter));
+		// Thinner stamps are placed closer together
+		assert!(stamps.len() > 11);
+	}
+
+	#[test]
+	fn inverted_velocity_curve_shrinks_fast_strokes() {
+		let curve = BrushDynamicsCurve {
+			input: BrushDynamicsInput::Velocity,
+			invert: true,
+			..Default::default()
+		};
+
+		assert_eq!(curve.factor(&BrushInputSample::default(), 0.), 1.);
+		assert_eq!(curve.factor(&BrushInputSample::default(), FULL_VELOCITY * 2.), 0.);
+	}
+
+	#[test]
+	fn textured_tips_follow_the_stroke() {
+		let trace = vec![
+			BrushInputSample::default(),
+			BrushInputSample {
+				position: DVec2::new(0., 100.),
+				..Default::default()
+			},
+		];
+		let mut stroke = stroke(BrushDynamics::default(), trace);
+		stroke.style.tip = BrushTip {
+			shape: BrushTipShape::Texture,
+			angle: 10.,
+			follow_stroke: true,
+			..Default::default()
+		};
+		assert!(stroke.compute_blit_stamps().iter().all(|stamp| stamp.angle == 100.));
+
+		// Round tips are never rotated, so all their stamps share a texture
+		stroke.style.tip.shape = BrushTipShape::Round;
+		assert!(stroke.compute_blit_stamps().iter().all(|stamp| stamp.angle == 0.));
+	}
+
+	#[test]
+	fn jitter_is_kept_as_the_stroke_grows() {
+		let trace = vec![
+			BrushInputSample::default(),
+			BrushInputSample {
+				position: DVec2::new(100., 0.),
+				..Default::default()
+			},
+		];
+		let mut stroke = stroke(BrushDynamics::default(), trace);
+		stroke.style.tip.shape = BrushTipShape::Texture;
+		stroke.style.jitter = BrushJitter {
+			rotation: 180.,
+			scatter: 50.,
+			size: 50.,
+			opacity: 50.,
+			seed: 7,
+		};
+		let stamps = stroke.compute_blit_stamps();
+
+		assert!(
+			stamps
+				.iter()
+				.all(|stamp| stamp.position.y.abs() <= 10. && (10.0..=20.).contains(&stamp.diameter) && (0.5..=1.).contains(&stamp.opacity))
+		);
+		assert!(stamps.windows(2).any(|pair| pair[0].angle != pair[1].angle));
+
+		stroke.trace.push(BrushInputSample {
+			position: DVec2::new(100., 100.),
+			..Default::default()
+		});
+		assert_eq!(stroke.compute_blit_stamps()[..stamps.len()], stamps[..]);
+	}
+
+	#[test]
+	fn twist_interpolates_along_the_shortest_arc() {
+		let sample = |twist: f64| BrushInputSample { twist, ..Default::default() };
+
+		assert_eq!(sample(350.).lerp(&sample(10.), 0.25).twist, 355.);
+		assert_eq!(sample(350.).lerp(&sample(10.), 0.75).twist, 5.);
+		assert_eq!(sample(10.).lerp(&sample(350.), 0.5).twist, 0.);
+		assert_eq!(sample(90.).lerp(&sample(180.), 0.5).twist, 135.);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn strokes_without_dynamics_deserialize() {
+		let json = r#"{
+			"style": { "color": { "red": 0.0, "green": 0.0, "blue": 0.0, "alpha": 1.0, "linear": true }, "diameter": 20.0, "hardness": 50.0, "flow": 100.0, "spacing": 50.0, "blend_mode": "Normal" },
+			"trace": [{ "position": [1.0, 2.0] }]
+		}"#;
+		let stroke: BrushStroke = serde_json::from_str(json).unwrap();
+
+		assert_eq!(stroke.style.dynamics, BrushDynamics::default());
+		assert_eq!(stroke.style.tip, BrushTip::default());
+		assert_eq!(stroke.style.jitter, BrushJitter::default());
+		assert_eq!(stroke.style.mode, BrushMode::Paint);
+		assert_eq!(
+			stroke.trace,
+			vec![BrushInputSample {
+				position: DVec2::new(1., 2.),
+				..Default::default()
+			}]
+		);
+	}
 }
@@ -4,13 +4,13 @@ use base64::Engine;
 use canvas_utils::{Canvas, CanvasHandle};
 use core_types::color::SRGBA8;
 use core_types::list::Item;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use core_types::list::List;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use core_types::math::bbox::Bbox;
 use core_types::ops::Convert;
 use core_types::transform::Footprint;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use core_types::{ATTR_EDITOR_MERGED_LAYERS, ATTR_TRANSFORM, WasmNotSend};
 use core_types::{Color, Ctx};
 pub use graph_craft::application_io::resource::{Resource, ResourceHash};
@@ -18,17 +18,17 @@ pub use graph_craft::application_io::*;
 pub use graph_craft::document::value::RenderOutputType;
 #[cfg(target_family = "wasm")]
 pub use graphene_canvas_utils as canvas_utils;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use graphic_types::Graphic;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use graphic_types::IntoGraphicList;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use graphic_types::Vector;
 use graphic_types::raster_types::Image;
 use graphic_types::raster_types::{CPU, GPU, Raster};
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use graphic_types::vector_types::gradient::Gradient;
-#[cfg(target_family = "wasm")]
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
 use rendering::{Render, RenderParams, RenderSvgSegmentList, SvgRender};
 
 fn parse_headers(headers: &str) -> reqwest::header::HeaderMap {
@@ -218,9 +218,7 @@ async fn rasterize<T: WasmNotSend + Clone + 'n>(
 where
 	List<T>: Render + Clone + graphic_types::IntoGraphicList,
 {
-	let mut data = data;
 	let mut canvas = canvas.into_element();
-	use glam::{DAffine2, DVec2};
 
 	let footprint = footprint.into_element();
 
@@ -233,22 +231,8 @@ where
 	// when collecting metadata, exposing their click targets to editor tools (same mechanism as Boolean Operation).
 	let upstream_graphic_list = data.clone().into_graphic_list();
 
-	let mut render = SvgRender::new();
-	let aabb = Bbox::from_transform(footprint.transform).to_axis_aligned_bbox();
-	let size = aabb.size();
+	let svg_string = render_footprint_svg(data, footprint);
 	let resolution = footprint.resolution;
-	let render_params = RenderParams {
-		footprint,
-		for_export: true,
-		..Default::default()
-	};
-
-	for transform in data.iter_attribute_values_mut_or_default::<DAffine2>(ATTR_TRANSFORM) {
-		*transform = DAffine2::from_translation(-aabb.start) * *transform;
-	}
-	data.render_svg(&mut render, &render_params);
-	render.format_svg(DVec2::ZERO, size);
-	let svg_string = render.svg.to_svg_string();
 
 	canvas.set_resolution(resolution);
 	let context = canvas.context();
@@ -275,6 +259,92 @@ where
 	)
 }
 
+/// Renders a view of the input graphic within an area defined by the *Footprint*, rasterizing it on the CPU.
+#[cfg(all(not(target_family = "wasm"), feature = "resvg"))]
+#[node_macro::node(category(""))]
+async fn rasterize<T: WasmNotSend + Clone + 'n>(
+	_: impl Ctx,
+	#[implementations(
+		List<Vector>,
+		List<Raster<CPU>>,
+		List<Graphic>,
+		List<Color>,
+		List<Gradient>,
+	)]
+	data: List<T>,
+	footprint: Item<Footprint>,
+) -> List<Raster<CPU>>
+where
+	List<T>: Render + Clone + graphic_types::IntoGraphicList,
+{
+	let footprint = footprint.into_element();
+
+	if footprint.transform.matrix2.determinant() == 0. {
+		log::trace!("Invalid footprint received for rasterization");
+		return List::new();
+	}
+
+	// Snapshot the input as a List<Graphic> so the renderer can recurse into the original child layers
+	// when collecting metadata, exposing their click targets to editor tools (same mechanism as Boolean Operation).
+	let upstream_graphic_list = data.clone().into_graphic_list();
+
+	let svg_string = render_footprint_svg(data, footprint);
+	let image = match rasterize_svg(&svg_string, footprint.resolution) {
+		Ok(image) => image,
+		Err(error) => {
+			log::error!("Failed to rasterize: {error}");
+			return List::new();
+		}
+	};
+
+	List::new_from_item(
+		Item::new_from_element(Raster::new_cpu(image))
+			.with_attribute(ATTR_TRANSFORM, footprint.transform)
+			.with_attribute(ATTR_EDITOR_MERGED_LAYERS, upstream_graphic_list),
+	)
+}
+
+/// Renders the area of `data` covered by `footprint` to a standalone SVG document, sized to that area in document units.
+#[cfg(any(target_family = "wasm", feature = "resvg"))]
+fn render_footprint_svg<T>(mut data: List<T>, footprint: Footprint) -> String
+where
+	List<T>: Render,
+{
+	use glam::{DAffine2, DVec2};
+
+	let mut render = SvgRender::new();
+	let aabb = Bbox::from_transform(footprint.transform).to_axis_aligned_bbox();
+	let size = aabb.size();
+	let render_params = RenderParams {
+		footprint,
+		for_export: true,
+		..Default::default()
+	};
+
+	for transform in data.iter_attribute_values_mut_or_default::<DAffine2>(ATTR_TRANSFORM) {
+		*transform = DAffine2::from_translation(-aabb.start) * *transform;
+	}
+	data.render_svg(&mut render, &render_params);
+	render.format_svg(DVec2::ZERO, size);
+	render.svg.to_svg_string()
+}
+
+/// Rasterizes a standalone SVG document on the CPU with resvg, stretching its size to fill `resolution` pixels. This is the
+/// native counterpart to drawing the SVG onto an HTML canvas, for headless machines without a GPU to render with.
+#[cfg(all(not(target_family = "wasm"), feature = "resvg"))]
+pub fn rasterize_svg(svg: &str, resolution: glam::UVec2) -> Result<Image<Color>, String> {
+	use resvg::{tiny_skia, usvg};
+
+	let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).map_err(|error| format!("Invalid SVG: {error}"))?;
+	let mut pixmap = tiny_skia::Pixmap::new(resolution.x, resolution.y).ok_or_else(|| format!("Can't rasterize at a resolution of {}x{}", resolution.x, resolution.y))?;
+
+	let size = tree.size();
+	let transform = tiny_skia::Transform::from_scale(resolution.x as f32 / size.width(), resolution.y as f32 / size.height());
+	resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+	Ok(Image::from_image_data(&pixmap.take_demultiplied(), resolution.x, resolution.y))
+}
+
 #[node_macro::node(category(""), inject_scope)]
 pub async fn editor_api<'a: 'n>(_: impl Ctx, #[scope("editor-api")] editor_api: Item<&'a PlatformEditorApi>) -> Item<&'a PlatformEditorApi> {
 	editor_api
@@ -1039,7 +1039,7 @@ fn gradient_value(_: impl Ctx, _primary: (), gradient: Item<Gradient>) -> Item<G
 	gradient
 }
 
-/// Sets the type (linear or radial) of each gradient in the input list.
+/// Sets the type (linear, radial, conic, diamond, or mesh) of each gradient in the input list.
 #[node_macro::node(category("Color"))]
 fn gradient_type(_: impl Ctx, gradient: Item<Gradient>, gradient_type: Item<vector_types::GradientType>) -> Item<Gradient> {
 	let mut gradient = gradient;
@@ -1055,6 +1055,39 @@ fn spread_method(_: impl Ctx, gradient: Item<Gradient>, spread_method: Item<vect
 	gradient
 }
 
+/// Turns the gradient into a mesh gradient, which blends between the colors at the vertices of a grid of patches spanning its gradient line.
+///
+/// The vertices are colored by sampling the gradient diagonally across the grid, unless vertex colors are given to replace them row by row.
+/// Vertices can then be moved and recolored individually by the Gradient tool.
+#[node_macro::node(category("Color"))]
+fn mesh_gradient(
+	_: impl Ctx,
+	gradient: Item<Gradient>,
+	/// The number of patches along the gradient line.
+	#[default(2)]
+	#[hard(1..)]
+	columns: Item<u32>,
+	/// The number of patches across the gradient line.
+	#[default(2)]
+	#[hard(1..)]
+	rows: Item<u32>,
+	/// Colors for the vertices of the grid, row by row from the start of the gradient line, replacing those sampled from the gradient.
+	vertex_colors: List<Color>,
+	/// Vertices moved and recolored by hand with the Gradient tool, applied over the grid and its vertex colors.
+	vertex_edits: Item<vector_types::GradientMeshEdits>,
+) -> Item<Gradient> {
+	let mut mesh = vector_types::GradientMesh::from_gradient(gradient.element(), *columns.element(), *rows.element());
+	for (vertex_color, color) in mesh.colors.iter_mut().zip(vertex_colors.iter_element_values()) {
+		*vertex_color = *color;
+	}
+	vertex_edits.element().apply(&mut mesh);
+
+	let mut gradient = gradient;
+	gradient.set_attribute(core_types::ATTR_GRADIENT_TYPE, vector_types::GradientType::Mesh);
+	gradient.set_attribute(core_types::ATTR_GRADIENT_MESH, mesh);
+	gradient
+}
+
 /// Gets the color at the specified position along the gradient, given a position from 0 (left) to 1 (right).
 #[node_macro::node(category("Color"))]
 fn sample_gradient(_: impl Ctx, _primary: (), gradient: Item<Gradient>, position: Item<Fraction>) -> Item<Color> {
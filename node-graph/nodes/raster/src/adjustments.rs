@@ -765,6 +765,61 @@ pub enum DomainWarpType {
 	BasicGrid,
 }
 
+/// How a convolution filter samples pixels beyond the edges of the image.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum EdgeHandling {
+	/// Repeats the nearest pixel on the edge of the image.
+	#[default]
+	Clamp,
+	/// Samples from the opposite side of the image, as if it were tiled.
+	Wrap,
+	/// Treats everything beyond the edges of the image as transparent.
+	Transparent,
+}
+
+/// The kernel used to find the edges of an image.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum EdgeDetectionOperator {
+	#[default]
+	Sobel,
+	Prewitt,
+	Laplacian,
+}
+
+/// The shape of the neighborhood a morphology filter looks at around each pixel.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum StructuringElement {
+	#[default]
+	Circle,
+	Square,
+}
+
+/// How colors are read between the pixels of an image when it's resampled.
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum ResamplingFilter {
+	/// Blends the 4 nearest pixels, which is fast but slightly soft.
+	#[default]
+	Bilinear,
+	/// Blends the 16 nearest pixels along smooth curves, which keeps more detail.
+	Bicubic,
+}
+
 // Aims for interoperable compatibility with:
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=%27mixr%27%20%3D%20Channel%20Mixer
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=Lab%20color%20only-,Channel%20Mixer,-Key%20is%20%27mixr
@@ -1133,7 +1188,10 @@ fn exposure<T: Adjust<Color>>(
 
 #[cfg(feature = "std")]
 mod _graphene_hash_impls {
-	use super::{CellularDistanceFunction, CellularReturnType, DomainWarpType, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute, SelectiveColorChoice};
+	use super::{
+		CellularDistanceFunction, CellularReturnType, DomainWarpType, EdgeDetectionOperator, EdgeHandling, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha,
+		RelativeAbsolute, ResamplingFilter, SelectiveColorChoice, StructuringElement,
+	};
 	graphene_hash::impl_via_hash!(
 		LuminanceCalculation,
 		RedGreenBlue,
@@ -1143,7 +1201,11 @@ mod _graphene_hash_impls {
 		CellularDistanceFunction,
 		CellularReturnType,
 		DomainWarpType,
+		EdgeHandling,
+		EdgeDetectionOperator,
 		RelativeAbsolute,
-		SelectiveColorChoice
+		ResamplingFilter,
+		SelectiveColorChoice,
+		StructuringElement
 	);
 }
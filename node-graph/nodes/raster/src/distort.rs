@@ -0,0 +1,425 @@
+use crate::adjustments::{EdgeHandling, ResamplingFilter};
+use crate::filter::sample_with_edges;
+use core_types::ATTR_TRANSFORM;
+use core_types::color::Color;
+use core_types::context::{Ctx, ExtractFootprint};
+use core_types::list::Item;
+use core_types::registry::types::{Angle, PixelLength, SignedPercentage};
+use core_types::transform::Footprint;
+use glam::{DAffine2, DVec2, UVec2};
+use raster_types::Image;
+use raster_types::{CPU, Raster};
+use std::f64::consts::TAU;
+
+/// Positions further than this many pixels beyond the image are all sampled alike, which keeps the integer pixel coordinates from overflowing.
+const COORDINATE_LIMIT: f64 = 1e9;
+
+/// Offsets each pixel of the image by the colors of a displacement map stretched over it, where the red channel moves it horizontally and the green channel vertically.
+///
+/// Mid gray leaves pixels in place, while brighter values move them right or down and darker values move them left or up, by up to the scale.
+#[node_macro::node(category("Raster: Distort"))]
+#[allow(clippy::too_many_arguments)]
+async fn displacement_map(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be displaced.
+	image_frame: Item<Raster<CPU>>,
+	/// The image whose red and green channels offset the pixels. Its transparent areas leave them in place.
+	#[expose]
+	displacement_map: Item<Raster<CPU>>,
+	/// How far the pixels move where the red channel is fully white or black.
+	#[default(10.)]
+	#[range]
+	#[soft(-100..100)]
+	horizontal_scale: Item<PixelLength>,
+	/// How far the pixels move where the green channel is fully white or black.
+	#[default(10.)]
+	#[range]
+	#[soft(-100..100)]
+	vertical_scale: Item<PixelLength>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let scale = DVec2::new(*horizontal_scale.element(), *vertical_scale.element());
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	let map = displacement_map.element().data();
+	if map.width == 0 || map.height == 0 {
+		return image_frame;
+	}
+	let map_dimensions = DVec2::new(map.width as f64, map.height as f64);
+
+	let dimensions = dimensions(&image_frame);
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| {
+		let color = sample(map, position / dimensions * map_dimensions, ResamplingFilter::Bilinear, EdgeHandling::Clamp);
+		let [red, green, _, alpha] = color.to_unassociated_alpha().to_gamma_srgb_channels();
+
+		position - DVec2::new(red as f64 - 0.5, green as f64 - 0.5) * 2. * scale * alpha as f64
+	})
+}
+
+/// Rotates the image around its center, turning most at the center and fading out towards the edge of the radius.
+#[node_macro::node(category("Raster: Distort"))]
+async fn twirl(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be twirled.
+	image_frame: Item<Raster<CPU>>,
+	/// The rotation at the center. Positive angles turn clockwise.
+	#[default(90.)]
+	#[range]
+	#[soft(-720..720)]
+	angle: Item<Angle>,
+	/// The distance from the center beyond which the image is left in place.
+	#[default(100.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..1000)]
+	radius: Item<PixelLength>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let (angle, radius) = (angle.element().to_radians(), *radius.element());
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	let center = dimensions(&image_frame) / 2.;
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| twirl_source(position, center, angle, radius))
+}
+
+/// Bulges the middle of the image outwards like a magnifying glass, or pinches it inwards.
+#[node_macro::node(name("Pinch/Bulge"), category("Raster: Distort"))]
+async fn pinch_bulge(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be distorted.
+	image_frame: Item<Raster<CPU>>,
+	/// The strength of the distortion. Positive values bulge the center outwards and negative values pinch it inwards.
+	#[default(50.)]
+	#[range]
+	#[hard(-100..100)]
+	amount: Item<SignedPercentage>,
+	/// The distance from the center beyond which the image is left in place.
+	#[default(100.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..1000)]
+	radius: Item<PixelLength>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let (amount, radius) = (*amount.element() / 100., *radius.element());
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	let center = dimensions(&image_frame) / 2.;
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| pinch_bulge_source(position, center, amount, radius))
+}
+
+/// Wraps the image around its center, so its top edge meets at the center and its bottom edge runs around the circle touching the edges of the image.
+#[node_macro::node(category("Raster: Distort"))]
+async fn rectangular_to_polar(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be wrapped.
+	image_frame: Item<Raster<CPU>>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	let dimensions = dimensions(&image_frame);
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| rectangular_to_polar_source(position, dimensions))
+}
+
+/// Unwraps the image from around its center, which undoes Rectangular to Polar by laying the angle out horizontally and the distance from the center vertically.
+#[node_macro::node(category("Raster: Distort"))]
+async fn polar_to_rectangular(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be unwrapped.
+	image_frame: Item<Raster<CPU>>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	let dimensions = dimensions(&image_frame);
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| polar_to_rectangular_source(position, dimensions))
+}
+
+/// Offsets the pixels of the image along sine waves, shifting each row horizontally and each column vertically.
+#[node_macro::node(category("Raster: Distort"))]
+async fn ripple(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be rippled.
+	image_frame: Item<Raster<CPU>>,
+	/// How far the pixels move at the crests of the waves.
+	#[default(5.)]
+	#[range]
+	#[soft(-100..100)]
+	amplitude: Item<PixelLength>,
+	/// The distance between the crests of the waves.
+	#[default(40.)]
+	#[range]
+	#[hard(1..)]
+	#[soft(..1000)]
+	wavelength: Item<PixelLength>,
+	/// Where the waves start along their cycle, which animates them when changed over time.
+	#[range]
+	#[soft(0..360)]
+	phase: Item<Angle>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let (amplitude, wavelength, phase) = (*amplitude.element(), wavelength.element().max(1.), phase.element().to_radians());
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| {
+		let wave = |coordinate: f64| (TAU * coordinate / wavelength + phase).sin() * amplitude;
+		position + DVec2::new(wave(position.y), wave(position.x))
+	})
+}
+
+/// Corrects the distortion of a camera lens by moving pixels towards or away from the center, increasingly so towards the corners.
+#[node_macro::node(category("Raster: Distort"))]
+async fn lens_distortion(
+	ctx: impl ExtractFootprint + Ctx,
+	/// The image to be corrected.
+	image_frame: Item<Raster<CPU>>,
+	/// The strength of the correction. Positive values produce barrel distortion, which corrects pincushion distortion in the photo, while negative values produce pincushion distortion, which corrects barrel distortion.
+	#[default(20.)]
+	#[range]
+	#[soft(-100..100)]
+	distortion: Item<SignedPercentage>,
+	/// How colors are read between the pixels of the image.
+	resampling: Item<ResamplingFilter>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+) -> Item<Raster<CPU>> {
+	let distortion = *distortion.element() / 100.;
+	let (resampling, edges) = (*resampling.element(), *edges.element());
+
+	let center = dimensions(&image_frame) / 2.;
+	distort(ctx.try_footprint(), image_frame, resampling, edges, |position| lens_distortion_source(position, center, distortion))
+}
+
+fn dimensions(image_frame: &Item<Raster<CPU>>) -> DVec2 {
+	let image = image_frame.element();
+	DVec2::new(image.width as f64, image.height as f64)
+}
+
+/// Where a pixel of the twirled image is read from, rotating it back by the twirl angle faded out linearly towards the radius.
+fn twirl_source(position: DVec2, center: DVec2, angle: f64, radius: f64) -> DVec2 {
+	let offset = position - center;
+	let distance = offset.length();
+	if distance >= radius {
+		return position;
+	}
+
+	center + DVec2::from_angle(-angle * (1. - distance / radius)).rotate(offset)
+}
+
+/// Where a pixel of the pinched or bulged image is read from. Within the radius, distances from the center are raised to a power which is below 1 when pinching and above 1 when bulging.
+fn pinch_bulge_source(position: DVec2, center: DVec2, amount: f64, radius: f64) -> DVec2 {
+	let offset = position - center;
+	let distance = offset.length();
+	if distance >= radius || distance == 0. {
+		return position;
+	}
+
+	let exponent = 2_f64.powf(amount) - 1.;
+	center + offset * (distance / radius).powf(exponent)
+}
+
+/// Where a pixel of the wrapped image is read from, mapping its angle clockwise from the top to the horizontal axis and its distance from the center to the vertical axis.
+fn rectangular_to_polar_source(position: DVec2, dimensions: DVec2) -> DVec2 {
+	let offset = (position - dimensions / 2.) / (dimensions / 2.);
+	let angle = offset.x.atan2(-offset.y).rem_euclid(TAU);
+
+	DVec2::new(angle / TAU, offset.length()) * dimensions
+}
+
+/// Where a pixel of the unwrapped image is read from, which is the inverse of [`rectangular_to_polar_source`].
+fn polar_to_rectangular_source(position: DVec2, dimensions: DVec2) -> DVec2 {
+	let normalized = position / dimensions;
+	let (sin, cos) = (normalized.x * TAU).sin_cos();
+
+	dimensions / 2. + DVec2::new(sin, -cos) * normalized.y * dimensions / 2.
+}
+
+/// Where a pixel of the corrected image is read from, scaling its offset from the center by a factor growing with the square of its distance, relative to the distance of the corners.
+fn lens_distortion_source(position: DVec2, center: DVec2, distortion: f64) -> DVec2 {
+	let offset = position - center;
+	let relative_distance_squared = offset.length_squared() / center.length_squared().max(f64::EPSILON);
+
+	center + offset * (1. + distortion * relative_distance_squared)
+}
+
+/// Resamples the part of the image visible in the footprint, reading each pixel from the position in the original image given by `source_position`.
+///
+/// Positions are measured in pixels of the original image. The result is rendered at the resolution it's displayed at in the viewport, up to the resolution of the original image, so it stays sharp at any zoom.
+fn distort(footprint: Option<&Footprint>, image_frame: Item<Raster<CPU>>, resampling: ResamplingFilter, edges: EdgeHandling, source_position: impl Fn(DVec2) -> DVec2) -> Item<Raster<CPU>> {
+	let transform: DAffine2 = image_frame.attribute_cloned_or_default(ATTR_TRANSFORM);
+	let dimensions = dimensions(&image_frame);
+
+	// An empty image has no pixels to sample from
+	if dimensions.x == 0. || dimensions.y == 0. {
+		return image_frame;
+	}
+
+	// If the image would not be visible, return it unchanged
+	let Some((start, size, resolution)) = visible_region(footprint, transform, dimensions) else {
+		return image_frame;
+	};
+
+	let (image, mut attributes) = image_frame.into_parts();
+	let image = image.into_data();
+
+	let mut output = Image::new(resolution.x, resolution.y, Color::TRANSPARENT);
+	let pixel_size = size / resolution.as_dvec2();
+	for (index, pixel) in output.data.iter_mut().enumerate() {
+		let (x, y) = (index % resolution.x as usize, index / resolution.x as usize);
+		let unit_position = start + (DVec2::new(x as f64, y as f64) + 0.5) * pixel_size;
+
+		*pixel = sample(&image, source_position(unit_position * dimensions), resampling, edges);
+	}
+
+	attributes.insert(ATTR_TRANSFORM, transform * DAffine2::from_translation(start) * DAffine2::from_scale(size));
+
+	Item::from_parts(Raster::new_cpu(output), attributes)
+}
+
+/// Finds the start and size of the part of the image's unit square that's visible in the footprint, along with the resolution it's displayed at there, which is capped at the resolution of the image.
+///
+/// Without a footprint, or with one that can't be inverted, the whole image is kept at its own resolution.
+fn visible_region(footprint: Option<&Footprint>, transform: DAffine2, dimensions: DVec2) -> Option<(DVec2, DVec2, UVec2)> {
+	let (mut start, mut end, mut scale) = (DVec2::ZERO, DVec2::ONE, dimensions);
+
+	if let Some(footprint) = footprint {
+		let to_viewport = footprint.transform * transform;
+		let to_unit = to_viewport.inverse();
+
+		let resolution = footprint.resolution.as_dvec2();
+		let corners = [DVec2::ZERO, DVec2::new(resolution.x, 0.), resolution, DVec2::new(0., resolution.y)].map(|corner| to_unit.transform_point2(corner));
+		let visible_start = corners.into_iter().reduce(DVec2::min).unwrap_or_default();
+		let visible_end = corners.into_iter().reduce(DVec2::max).unwrap_or_default();
+		if visible_start.is_finite() && visible_end.is_finite() {
+			start = visible_start.max(DVec2::ZERO);
+			end = visible_end.min(DVec2::ONE);
+		}
+
+		let viewport_scale = DVec2::new(to_viewport.transform_vector2(DVec2::X).length(), to_viewport.transform_vector2(DVec2::Y).length());
+		if viewport_scale.is_finite() {
+			scale = viewport_scale.min(dimensions);
+		}
+	}
+
+	let size = end - start;
+	if size.x <= 0. || size.y <= 0. {
+		return None;
+	}
+
+	let resolution = (size * scale).ceil().max(DVec2::ONE).as_uvec2();
+	Some((start, size, resolution))
+}
+
+/// Reads the color at a position in pixels, where the center of each pixel lies halfway between whole coordinates.
+fn sample(image: &Image<Color>, position: DVec2, filter: ResamplingFilter, edges: EdgeHandling) -> Color {
+	if !position.is_finite() {
+		return Color::TRANSPARENT;
+	}
+
+	let position = position.clamp(DVec2::splat(-COORDINATE_LIMIT), DVec2::splat(COORDINATE_LIMIT)) - 0.5;
+	let base = position.floor();
+	let (x, y) = (base.x as i64, base.y as i64);
+	let fraction = (position - base).as_vec2();
+
+	match filter {
+		ResamplingFilter::Bilinear => {
+			let weights = |t: f32| [1. - t, t];
+			weighted_sum(image, x, y, &weights(fraction.x), &weights(fraction.y), edges)
+		}
+		ResamplingFilter::Bicubic => weighted_sum(image, x - 1, y - 1, &catmull_rom_weights(fraction.x), &catmull_rom_weights(fraction.y), edges),
+	}
+}
+
+/// The weights of the 4 pixels around a position a fraction `t` of the way between the middle two, following a Catmull-Rom spline.
+fn catmull_rom_weights(t: f32) -> [f32; 4] {
+	let (t2, t3) = (t * t, t * t * t);
+	[(-t3 + 2. * t2 - t) / 2., (3. * t3 - 5. * t2 + 2.) / 2., (-3. * t3 + 4. * t2 + t) / 2., (t3 - t2) / 2.]
+}
+
+/// Adds up the block of pixels starting at (`x`, `y`), weighted by the product of their column and row weights, and clamps the sum to a valid premultiplied color.
+fn weighted_sum(image: &Image<Color>, x: i64, y: i64, column_weights: &[f32], row_weights: &[f32], edges: EdgeHandling) -> Color {
+	let mut sum = [0.; 4];
+
+	for (row, row_weight) in (y..).zip(row_weights) {
+		for (column, column_weight) in (x..).zip(column_weights) {
+			let pixel = sample_with_edges(image, column, row, edges);
+			let weight = row_weight * column_weight;
+
+			sum[0] += pixel.r() * weight;
+			sum[1] += pixel.g() * weight;
+			sum[2] += pixel.b() * weight;
+			sum[3] += pixel.a() * weight;
+		}
+	}
+
+	// Overshooting weights can push the channels beyond what the pixels can represent
+	let alpha = sum[3].clamp(0., 1.);
+	Color::from_rgbaf32_unchecked(sum[0].clamp(0., alpha), sum[1].clamp(0., alpha), sum[2].clamp(0., alpha), alpha)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn polar_conversions_undo_each_other() {
+		let dimensions = DVec2::new(40., 30.);
+
+		for position in [DVec2::new(25., 10.), DVec2::new(7., 16.), DVec2::new(20.5, 28.), DVec2::new(3., 3.)] {
+			let round_trip = polar_to_rectangular_source(rectangular_to_polar_source(position, dimensions), dimensions);
+			assert!(round_trip.abs_diff_eq(position, 1e-9), "{position} became {round_trip}");
+		}
+	}
+
+	#[test]
+	fn resampling_blends_neighboring_pixels() {
+		let image = Image {
+			width: 2,
+			height: 1,
+			data: vec![Color::BLACK, Color::WHITE],
+			base64_string: None,
+		};
+
+		for filter in [ResamplingFilter::Bilinear, ResamplingFilter::Bicubic] {
+			// Pixel centers are returned exactly
+			assert_eq!(sample(&image, DVec2::new(0.5, 0.5), filter, EdgeHandling::Clamp), Color::BLACK);
+			assert_eq!(sample(&image, DVec2::new(1.5, 0.5), filter, EdgeHandling::Clamp), Color::WHITE);
+
+			// Halfway between them is halfway between their colors
+			let middle = sample(&image, DVec2::new(1., 0.5), filter, EdgeHandling::Clamp);
+			assert!((middle.r() - 0.5).abs() < 1e-6 && middle.a() == 1., "{filter:?} gave {middle:?}");
+		}
+
+		assert_eq!(sample(&image, DVec2::new(3., 0.5), ResamplingFilter::Bilinear, EdgeHandling::Transparent), Color::TRANSPARENT);
+	}
+
+	#[test]
+	fn empty_images_are_returned_unchanged() {
+		for edges in [EdgeHandling::Clamp, EdgeHandling::Wrap, EdgeHandling::Transparent] {
+			let empty = Item::new_from_element(Raster::new_cpu(Image::<Color>::default()));
+			let distorted = distort(None, empty, ResamplingFilter::Bicubic, edges, |position| position);
+
+			assert_eq!(distorted.element().width, 0);
+			assert_eq!(distorted.element().height, 0);
+		}
+	}
+}
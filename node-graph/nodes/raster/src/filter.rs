@@ -1,8 +1,9 @@
+use crate::adjustments::{EdgeDetectionOperator, EdgeHandling};
 use bytemuck::{Pod, Zeroable};
 use core_types::color::{Alpha, Color, Pixel, RGB};
 use core_types::context::Ctx;
-use core_types::list::Item;
-use core_types::registry::types::PixelLength;
+use core_types::list::{Item, List};
+use core_types::registry::types::{Angle, Percentage, PixelLength};
 use raster_types::Image;
 use raster_types::{Bitmap, BitmapMut};
 use raster_types::{CPU, Raster};
@@ -143,6 +144,199 @@ async fn median_filter(
 	Item::from_parts(filtered_image, attributes)
 }
 
+/// Sharpens the image by adding back the difference between it and a blurred copy, which boosts the contrast of its details.
+#[node_macro::node(category("Raster: Filter"))]
+async fn unsharp_mask(
+	_: impl Ctx,
+	/// The image to be sharpened.
+	image_frame: Item<Raster<CPU>>,
+	/// How much of the difference to the blurred copy is added back.
+	#[default(100.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..500)]
+	amount: Item<Percentage>,
+	/// The radius of the blur kernel, which sets the size of the details being sharpened.
+	#[default(2.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	radius: Item<PixelLength>,
+	/// The smallest difference to the blurred copy which gets sharpened, so smooth areas and noise can be left untouched.
+	#[range]
+	#[hard(0..100)]
+	threshold: Item<Percentage>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: Item<bool>,
+) -> Item<Raster<CPU>> {
+	let (amount, radius, threshold) = (*amount.element() / 100., *radius.element(), *threshold.element() / 100.);
+	let (edges, gamma) = (*edges.element(), *gamma.element());
+
+	let (image, attributes) = image_frame.into_parts();
+
+	let sharpened_image = if radius < 0.1 || amount == 0. {
+		// Minimum blur radius
+		image
+	} else {
+		Raster::new_cpu(convolution_algorithm(image.into_data(), ConvolutionFilter::UnsharpMask { radius, amount, threshold }, edges, gamma))
+	};
+
+	Item::from_parts(sharpened_image, attributes)
+}
+
+/// Sharpens the image by increasing the contrast between each pixel and its four neighbors.
+#[node_macro::node(category("Raster: Filter"))]
+async fn sharpen(
+	_: impl Ctx,
+	/// The image to be sharpened.
+	image_frame: Item<Raster<CPU>>,
+	/// The strength of the sharpening.
+	#[default(50.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..200)]
+	amount: Item<Percentage>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: Item<bool>,
+) -> Item<Raster<CPU>> {
+	let amount = *amount.element() / 100.;
+	let (edges, gamma) = (*edges.element(), *gamma.element());
+
+	let (image, attributes) = image_frame.into_parts();
+
+	let weights = [0., -amount, 0., -amount, 1. + 4. * amount, -amount, 0., -amount, 0.];
+	let sharpened_image = Raster::new_cpu(convolution_algorithm(image.into_data(), ConvolutionFilter::Kernel { weights: &weights, columns: 3 }, edges, gamma));
+
+	Item::from_parts(sharpened_image, attributes)
+}
+
+/// Highlights the edges in the image, where the color changes sharply, and darkens everything else.
+#[node_macro::node(category("Raster: Filter"))]
+async fn edge_detect(
+	_: impl Ctx,
+	/// The image to find the edges of.
+	image_frame: Item<Raster<CPU>>,
+	/// The kernel used to find the edges. Sobel and Prewitt measure the slope of the color, while Laplacian measures its curvature which makes for thinner but noisier edges.
+	operator: Item<EdgeDetectionOperator>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: Item<bool>,
+) -> Item<Raster<CPU>> {
+	let (operator, edges, gamma) = (*operator.element(), *edges.element(), *gamma.element());
+
+	let (image, attributes) = image_frame.into_parts();
+
+	let edge_image = Raster::new_cpu(convolution_algorithm(image.into_data(), ConvolutionFilter::EdgeDetect(operator), edges, gamma));
+
+	Item::from_parts(edge_image, attributes)
+}
+
+/// Makes the image look like a relief stamped into gray material, lit from the given angle.
+#[node_macro::node(category("Raster: Filter"))]
+async fn emboss(
+	_: impl Ctx,
+	/// The image to be embossed.
+	image_frame: Item<Raster<CPU>>,
+	/// The direction the light comes from, counterclockwise from the right.
+	#[default(135.)]
+	#[range]
+	#[soft(-180..180)]
+	angle: Item<Angle>,
+	/// The height of the relief.
+	#[default(100.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..500)]
+	strength: Item<Percentage>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: Item<bool>,
+) -> Item<Raster<CPU>> {
+	let (angle, strength) = (*angle.element(), *strength.element() / 100.);
+	let (edges, gamma) = (*edges.element(), *gamma.element());
+
+	let (image, attributes) = image_frame.into_parts();
+
+	let embossed_image = Raster::new_cpu(convolution_algorithm(image.into_data(), ConvolutionFilter::Emboss { angle, strength }, edges, gamma));
+
+	Item::from_parts(embossed_image, attributes)
+}
+
+/// Keeps only the fine details of the image by subtracting a blurred copy from it, leaving smooth areas a neutral gray.
+#[node_macro::node(category("Raster: Filter"))]
+async fn high_pass(
+	_: impl Ctx,
+	/// The image to be filtered.
+	image_frame: Item<Raster<CPU>>,
+	/// The radius of the blur kernel. Details smaller than this are kept.
+	#[default(10.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	radius: Item<PixelLength>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: Item<bool>,
+) -> Item<Raster<CPU>> {
+	let radius = *radius.element();
+	let (edges, gamma) = (*edges.element(), *gamma.element());
+
+	let (image, attributes) = image_frame.into_parts();
+
+	let filtered_image = Raster::new_cpu(convolution_algorithm(image.into_data(), ConvolutionFilter::HighPass { radius }, edges, gamma));
+
+	Item::from_parts(filtered_image, attributes)
+}
+
+/// Convolves the image with a matrix of weights, where each pixel becomes the weighted sum of the pixels around it.
+#[node_macro::node(category("Raster: Filter"))]
+async fn custom_kernel(
+	_: impl Ctx,
+	/// The image to be filtered.
+	image_frame: Item<Raster<CPU>>,
+	/// The weights of the kernel row by row, starting from the top left. For example, `0, -1, 0, -1, 5, -1, 0, -1, 0` sharpens the image.
+	kernel: List<f64>,
+	/// The number of weights in each row of the kernel. Weights left over after the last full row are ignored.
+	#[default(3)]
+	#[hard(1..)]
+	columns: Item<u32>,
+	/// Divide the weights by their sum, so the overall brightness of the image is kept.
+	#[default(true)]
+	normalize: Item<bool>,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: Item<EdgeHandling>,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: Item<bool>,
+) -> Item<Raster<CPU>> {
+	let columns = (*columns.element()).max(1) as usize;
+	let (normalize, edges, gamma) = (*normalize.element(), *edges.element(), *gamma.element());
+
+	let mut weights: Vec<f64> = kernel.iter_element_values().copied().collect();
+	weights.truncate(weights.len() - weights.len() % columns);
+
+	let sum = weights.iter().sum::<f64>();
+	if normalize && sum.abs() > f64::EPSILON {
+		weights.iter_mut().for_each(|weight| *weight /= sum);
+	}
+
+	let (image, attributes) = image_frame.into_parts();
+
+	let filtered_image = if weights.is_empty() {
+		image
+	} else {
+		Raster::new_cpu(convolution_algorithm(image.into_data(), ConvolutionFilter::Kernel { weights: &weights, columns }, edges, gamma))
+	};
+
+	Item::from_parts(filtered_image, attributes)
+}
+
 // 1D gaussian kernel
 fn gaussian_kernel(radius: f64) -> Vec<f64> {
 	// Given radius, compute the size of the kernel that's approximately three times the radius
@@ -289,6 +483,204 @@ where
 	y_axis
 }
 
+/// A filter built on the convolution engine, which runs on premultiplied pixels in either linear or gamma space.
+#[derive(Clone, Copy)]
+enum ConvolutionFilter<'a> {
+	/// Convolves every channel with a kernel of weights stored row by row.
+	Kernel { weights: &'a [f64], columns: usize },
+	/// Adds back `amount` times the difference between the image and a Gaussian blurred copy, where it exceeds `threshold`.
+	UnsharpMask { radius: f64, amount: f64, threshold: f64 },
+	/// Replaces the color channels by the magnitude of their gradient or curvature.
+	EdgeDetect(EdgeDetectionOperator),
+	/// Offsets the color channels from a neutral gray by their slope along the direction of the light, given in degrees.
+	Emboss { angle: f64, strength: f64 },
+	/// Offsets the color channels from a neutral gray by their difference to a Gaussian blurred copy.
+	HighPass { radius: f64 },
+}
+
+impl ConvolutionFilter<'_> {
+	fn apply<P, F>(&self, image: Image<P>, edges: EdgeHandling, construct: F) -> Image<P>
+	where
+		P: Pixel + Copy + RGB<ColorChannel = f32> + Alpha<AlphaChannel = f32>,
+		F: Fn(f32, f32, f32, f32) -> P,
+	{
+		let neutral_gray = |original: P, [r, g, b]: [f32; 3], strength: f32| {
+			let a = original.a();
+			construct(0.5 * a + strength * r, 0.5 * a + strength * g, 0.5 * a + strength * b, a)
+		};
+
+		let filtered = match *self {
+			Self::Kernel { weights, columns } => convolve(&image, weights, columns, edges, &construct),
+			Self::UnsharpMask { radius, amount, threshold } => {
+				let blurred = gaussian_convolution(&image, radius, edges, &construct);
+				zip_pixels(&image, &blurred, |original, blurred| {
+					let difference = [original.r() - blurred.r(), original.g() - blurred.g(), original.b() - blurred.b(), original.a() - blurred.a()];
+					if difference[..3].iter().all(|difference| difference.abs() < threshold as f32) {
+						return original;
+					}
+
+					let [r, g, b, a] = difference.map(|difference| difference * amount as f32);
+					construct(original.r() + r, original.g() + g, original.b() + b, original.a() + a)
+				})
+			}
+			Self::EdgeDetect(operator) => {
+				let gradients = match operator {
+					EdgeDetectionOperator::Sobel => vec![convolve(&image, &SOBEL_X, 3, edges, &construct), convolve(&image, &transpose_3x3(SOBEL_X), 3, edges, &construct)],
+					EdgeDetectionOperator::Prewitt => vec![convolve(&image, &PREWITT_X, 3, edges, &construct), convolve(&image, &transpose_3x3(PREWITT_X), 3, edges, &construct)],
+					EdgeDetectionOperator::Laplacian => vec![convolve(&image, &LAPLACIAN, 3, edges, &construct)],
+				};
+
+				let magnitude = |channel: fn(&P) -> f32, index: usize| gradients.iter().map(|gradient| channel(&gradient.data[index]).powi(2)).sum::<f32>().sqrt();
+				Image {
+					width: image.width,
+					height: image.height,
+					data: (0..image.data.len())
+						.map(|index| construct(magnitude(P::r, index), magnitude(P::g, index), magnitude(P::b, index), image.data[index].a()))
+						.collect(),
+					base64_string: None,
+				}
+			}
+			Self::Emboss { angle, strength } => {
+				let slope = convolve(&image, &emboss_kernel(angle), 3, edges, &construct);
+				zip_pixels(&image, &slope, |original, slope| neutral_gray(original, [slope.r(), slope.g(), slope.b()], strength as f32))
+			}
+			Self::HighPass { radius } => {
+				let blurred = gaussian_convolution(&image, radius, edges, &construct);
+				zip_pixels(&image, &blurred, |original, blurred| {
+					neutral_gray(original, [original.r() - blurred.r(), original.g() - blurred.g(), original.b() - blurred.b()], 1.)
+				})
+			}
+		};
+
+		// Kernels with negative weights can overshoot the range of valid premultiplied colors
+		Image {
+			data: filtered
+				.data
+				.into_iter()
+				.map(|px| {
+					let a = px.a().clamp(0., 1.);
+					construct(px.r().clamp(0., a), px.g().clamp(0., a), px.b().clamp(0., a), a)
+				})
+				.collect(),
+			..filtered
+		}
+	}
+}
+
+const SOBEL_X: [f64; 9] = [-0.25, 0., 0.25, -0.5, 0., 0.5, -0.25, 0., 0.25];
+const PREWITT_X: [f64; 9] = [-1. / 3., 0., 1. / 3., -1. / 3., 0., 1. / 3., -1. / 3., 0., 1. / 3.];
+const LAPLACIAN: [f64; 9] = [0., 1., 0., 1., -4., 1., 0., 1., 0.];
+
+fn transpose_3x3(kernel: [f64; 9]) -> [f64; 9] {
+	core::array::from_fn(|index| kernel[(index % 3) * 3 + index / 3])
+}
+
+/// Builds a 3x3 kernel measuring the slope of the image away from a light at `angle` degrees, scaled so that a step from black to white measures 1.
+fn emboss_kernel(angle: f64) -> [f64; 9] {
+	let (sin, cos) = angle.to_radians().sin_cos();
+	// Image rows go downwards, so the light's vertical direction is flipped
+	let weights: [f64; 9] = core::array::from_fn(|index| -((index % 3) as f64 - 1.) * cos + ((index / 3) as f64 - 1.) * sin);
+	let positive_sum = weights.iter().filter(|&&weight| weight > 0.).sum::<f64>();
+
+	weights.map(|weight| weight / positive_sum)
+}
+
+fn convolution_algorithm(buffer: Image<Color>, filter: ConvolutionFilter, edges: EdgeHandling, gamma: bool) -> Image<Color> {
+	if buffer.width == 0 || buffer.height == 0 {
+		return buffer;
+	}
+
+	if gamma {
+		let working = premultiply_gamma(buffer);
+		let filtered = filter.apply(working, edges, |r, g, b, a| PremultipliedGammaPixel { r, g, b, a });
+		unpremultiply_gamma_to_linear(filtered)
+	} else {
+		let mut working = buffer;
+		working.map_pixels(|px| px.apply_opacity(px.a()));
+		let mut filtered = filter.apply(working, edges, Color::from_rgbaf32_unchecked);
+		filtered.map_pixels(|px| px.to_unassociated_alpha());
+		filtered
+	}
+}
+
+/// Reads the pixel at a position which may lie beyond the edges of the image, where it's sampled according to `edges`.
+pub(crate) fn sample_with_edges<P: Pixel + Copy + Alpha>(buffer: &Image<P>, x: i64, y: i64, edges: EdgeHandling) -> P {
+	let (width, height) = (buffer.width as i64, buffer.height as i64);
+
+	let (x, y) = match edges {
+		EdgeHandling::Clamp => (x.clamp(0, width - 1), y.clamp(0, height - 1)),
+		EdgeHandling::Wrap => (x.rem_euclid(width), y.rem_euclid(height)),
+		EdgeHandling::Transparent if (0..width).contains(&x) && (0..height).contains(&y) => (x, y),
+		EdgeHandling::Transparent => return P::TRANSPARENT,
+	};
+
+	buffer.data[(y * width + x) as usize]
+}
+
+/// Convolves every channel of a non-empty image with a kernel of `columns` weights per row, whose weights are stored row by row and centered on each pixel.
+fn convolve<P, F>(buffer: &Image<P>, kernel: &[f64], columns: usize, edges: EdgeHandling, construct: F) -> Image<P>
+where
+	P: Pixel + Copy + RGB<ColorChannel = f32> + Alpha<AlphaChannel = f32>,
+	F: Fn(f32, f32, f32, f32) -> P,
+{
+	let (width, height) = buffer.dimensions();
+	let rows = kernel.len() / columns;
+	let (half_columns, half_rows) = ((columns / 2) as i64, (rows / 2) as i64);
+
+	let mut output = Image::new(width, height, P::default());
+
+	for y in 0..height {
+		for x in 0..width {
+			let (mut r_sum, mut g_sum, mut b_sum, mut a_sum) = (0., 0., 0., 0.);
+
+			for (i, &weight) in kernel.iter().enumerate().filter(|(_, weight)| **weight != 0.) {
+				let px = sample_with_edges(buffer, x as i64 + (i % columns) as i64 - half_columns, y as i64 + (i / columns) as i64 - half_rows, edges);
+				r_sum += px.r() as f64 * weight;
+				g_sum += px.g() as f64 * weight;
+				b_sum += px.b() as f64 * weight;
+				a_sum += px.a() as f64 * weight;
+			}
+
+			output.set_pixel(x, y, construct(r_sum as f32, g_sum as f32, b_sum as f32, a_sum as f32));
+		}
+	}
+
+	output
+}
+
+/// Convolves a non-empty image with a kernel that's the product of a `horizontal` row and a `vertical` column, in one pass along each axis.
+fn convolve_separable<P, F>(buffer: &Image<P>, horizontal: &[f64], vertical: &[f64], edges: EdgeHandling, construct: F) -> Image<P>
+where
+	P: Pixel + Copy + RGB<ColorChannel = f32> + Alpha<AlphaChannel = f32>,
+	F: Fn(f32, f32, f32, f32) -> P,
+{
+	let rows = convolve(buffer, horizontal, horizontal.len(), edges, &construct);
+	convolve(&rows, vertical, 1, edges, &construct)
+}
+
+fn gaussian_convolution<P, F>(buffer: &Image<P>, radius: f64, edges: EdgeHandling, construct: F) -> Image<P>
+where
+	P: Pixel + Copy + RGB<ColorChannel = f32> + Alpha<AlphaChannel = f32>,
+	F: Fn(f32, f32, f32, f32) -> P,
+{
+	if radius < 0.1 {
+		// Minimum blur radius
+		return buffer.clone();
+	}
+
+	let kernel = gaussian_kernel(radius);
+	convolve_separable(buffer, &kernel, &kernel, edges, construct)
+}
+
+fn zip_pixels<P: Pixel + Copy>(first: &Image<P>, second: &Image<P>, combine: impl Fn(P, P) -> P) -> Image<P> {
+	Image {
+		width: first.width,
+		height: first.height,
+		data: first.data.iter().zip(&second.data).map(|(&first, &second)| combine(first, second)).collect(),
+		base64_string: None,
+	}
+}
+
 fn median_filter_algorithm(original_buffer: Image<Color>, radius: u32) -> Image<Color> {
 	let (width, height) = original_buffer.dimensions();
 	let mut output = Image::new(width, height, Color::TRANSPARENT);
@@ -341,3 +733,52 @@ fn median_quickselect(values: &mut [f32]) -> f32 {
 	// Use total_cmp for safe NaN handling instead of partial_cmp().unwrap()
 	*values.select_nth_unstable_by(mid, |a, b| a.total_cmp(b)).1
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn opaque(r: f32, g: f32, b: f32) -> Color {
+		Color::from_rgbaf32_unchecked(r, g, b, 1.)
+	}
+
+	#[test]
+	fn edge_handling_samples_beyond_the_image() {
+		let image = Image {
+			width: 3,
+			height: 1,
+			data: vec![opaque(1., 0., 0.), opaque(0., 1., 0.), opaque(0., 0., 1.)],
+			base64_string: None,
+		};
+		// Moves every pixel one step to the right
+		let shift = ConvolutionFilter::Kernel { weights: &[1., 0., 0.], columns: 3 };
+
+		let clamped = convolution_algorithm(image.clone(), shift, EdgeHandling::Clamp, false);
+		assert_eq!(clamped.data, vec![opaque(1., 0., 0.), opaque(1., 0., 0.), opaque(0., 1., 0.)]);
+
+		let wrapped = convolution_algorithm(image.clone(), shift, EdgeHandling::Wrap, false);
+		assert_eq!(wrapped.data[0], opaque(0., 0., 1.));
+
+		let transparent = convolution_algorithm(image, shift, EdgeHandling::Transparent, false);
+		assert_eq!(transparent.data[0], Color::TRANSPARENT);
+	}
+
+	#[test]
+	fn edge_detection_finds_a_step() {
+		// Black on the left half and white on the right half
+		let image = Image {
+			width: 4,
+			height: 3,
+			data: (0..12).map(|index| if index % 4 < 2 { opaque(0., 0., 0.) } else { opaque(1., 1., 1.) }).collect(),
+			base64_string: None,
+		};
+
+		for operator in [EdgeDetectionOperator::Sobel, EdgeDetectionOperator::Prewitt] {
+			let edges = convolution_algorithm(image.clone(), ConvolutionFilter::EdgeDetect(operator), EdgeHandling::Clamp, false);
+
+			assert_eq!(edges.data[4], opaque(0., 0., 0.), "{operator:?} finds an edge in a flat area");
+			assert!((edges.data[5].r() - 1.).abs() < 1e-6, "{operator:?} misses the edge");
+			assert!((edges.data[6].r() - 1.).abs() < 1e-6, "{operator:?} misses the edge");
+		}
+	}
+}
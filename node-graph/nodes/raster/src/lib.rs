@@ -13,10 +13,14 @@ pub use raster_nodes_shaders::WGSL_SHADER;
 #[cfg(feature = "std")]
 pub mod dehaze;
 #[cfg(feature = "std")]
+pub mod distort;
+#[cfg(feature = "std")]
 pub mod filter;
 #[cfg(feature = "std")]
 pub mod gradient_map;
 #[cfg(feature = "std")]
 pub mod image_color_palette;
 #[cfg(feature = "std")]
+pub mod morphology;
+#[cfg(feature = "std")]
 pub mod std_nodes;
@@ -0,0 +1,621 @@
+use crate::adjustments::StructuringElement;
+use core_types::ATTR_TRANSFORM;
+use core_types::color::Color;
+use core_types::context::Ctx;
+use core_types::list::Item;
+use core_types::math::extremum::running_extremum;
+use core_types::registry::types::{Percentage, PixelLength};
+use glam::{DAffine2, DVec2};
+use raster_types::Image;
+use raster_types::{CPU, Raster};
+use vector_types::vector::style::StrokeAlign;
+
+/// Stands in for an infinite distance in the distance transform, while keeping its arithmetic finite.
+const FAR_AWAY: f64 = 1e20;
+
+/// Grows the opaque and bright areas of the image by replacing each pixel with the maximum of every channel in its neighborhood.
+#[node_macro::node(category("Raster: Filter"))]
+async fn dilate(
+	_: impl Ctx,
+	/// The image to be dilated.
+	image_frame: Item<Raster<CPU>>,
+	/// The radius of the neighborhood, which is how far the areas grow.
+	#[default(2.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	radius: Item<PixelLength>,
+	/// The shape of the neighborhood. A circle grows areas with rounded corners, while a square keeps their corners sharp.
+	shape: Item<StructuringElement>,
+) -> Item<Raster<CPU>> {
+	let (radius, shape) = (*radius.element(), *shape.element());
+
+	let (image, attributes) = image_frame.into_parts();
+	let dilated_image = Raster::new_cpu(morphology(image.into_data(), radius, shape, &[Extremum::Maximum]));
+
+	Item::from_parts(dilated_image, attributes)
+}
+
+/// Shrinks the opaque and bright areas of the image by replacing each pixel with the minimum of every channel in its neighborhood.
+#[node_macro::node(category("Raster: Filter"))]
+async fn erode(
+	_: impl Ctx,
+	/// The image to be eroded.
+	image_frame: Item<Raster<CPU>>,
+	/// The radius of the neighborhood, which is how far the areas shrink.
+	#[default(2.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	radius: Item<PixelLength>,
+	/// The shape of the neighborhood. A circle shrinks areas with rounded corners, while a square keeps their corners sharp.
+	shape: Item<StructuringElement>,
+) -> Item<Raster<CPU>> {
+	let (radius, shape) = (*radius.element(), *shape.element());
+
+	let (image, attributes) = image_frame.into_parts();
+	let eroded_image = Raster::new_cpu(morphology(image.into_data(), radius, shape, &[Extremum::Minimum]));
+
+	Item::from_parts(eroded_image, attributes)
+}
+
+/// Erodes and then dilates the image, which removes specks and thin protrusions smaller than the neighborhood while keeping the size of larger areas.
+#[node_macro::node(name("Morphological Open"), category("Raster: Filter"))]
+async fn morphological_open(
+	_: impl Ctx,
+	/// The image to be opened.
+	image_frame: Item<Raster<CPU>>,
+	/// The radius of the neighborhood. Details thinner than twice this are removed.
+	#[default(2.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	radius: Item<PixelLength>,
+	/// The shape of the neighborhood.
+	shape: Item<StructuringElement>,
+) -> Item<Raster<CPU>> {
+	let (radius, shape) = (*radius.element(), *shape.element());
+
+	let (image, attributes) = image_frame.into_parts();
+	let opened_image = Raster::new_cpu(morphology(image.into_data(), radius, shape, &[Extremum::Minimum, Extremum::Maximum]));
+
+	Item::from_parts(opened_image, attributes)
+}
+
+/// Dilates and then erodes the image, which fills holes and gaps smaller than the neighborhood while keeping the size of larger areas.
+#[node_macro::node(name("Morphological Close"), category("Raster: Filter"))]
+async fn morphological_close(
+	_: impl Ctx,
+	/// The image to be closed.
+	image_frame: Item<Raster<CPU>>,
+	/// The radius of the neighborhood. Gaps narrower than twice this are filled.
+	#[default(2.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	radius: Item<PixelLength>,
+	/// The shape of the neighborhood.
+	shape: Item<StructuringElement>,
+) -> Item<Raster<CPU>> {
+	let (radius, shape) = (*radius.element(), *shape.element());
+
+	let (image, attributes) = image_frame.into_parts();
+	let closed_image = Raster::new_cpu(morphology(image.into_data(), radius, shape, &[Extremum::Maximum, Extremum::Minimum]));
+
+	Item::from_parts(closed_image, attributes)
+}
+
+/// Generates a signed distance field from the alpha of the image, encoding the distance to the edge of its opaque areas as brightness.
+/// Pixels on the edge are middle gray, and the brightness goes up to white inside and down to black outside over the spread distance.
+#[node_macro::node(category("Raster: Filter"))]
+async fn signed_distance_field(
+	_: impl Ctx,
+	/// The image whose alpha channel defines the shape.
+	image_frame: Item<Raster<CPU>>,
+	/// The distance from the edge, on either side, at which the brightness reaches white or black.
+	#[default(16.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	spread: Item<PixelLength>,
+) -> Item<Raster<CPU>> {
+	let spread = spread.element().max(f64::EPSILON);
+
+	let (image, attributes) = image_frame.into_parts();
+	let image = image.into_data();
+
+	if image.width == 0 || image.height == 0 {
+		return Item::from_parts(Raster::new_cpu(image), attributes);
+	}
+
+	let distances = signed_distances(&image);
+	let data = distances
+		.iter()
+		.map(|distance| {
+			// Stored in gamma space so the distance maps linearly to the channel values of an exported image
+			let value = (0.5 - distance / (2. * spread)).clamp(0., 1.) as f32;
+			Color::from_gamma_srgb_channels(value, value, value, 1.)
+		})
+		.collect();
+
+	let field = Image {
+		width: image.width,
+		height: image.height,
+		data,
+		base64_string: None,
+	};
+
+	Item::from_parts(Raster::new_cpu(field), attributes)
+}
+
+/// Surrounds the opaque areas of the image with a glow which fades out away from their edges. The image is extended to fit the glow.
+#[node_macro::node(category("Raster: Filter"))]
+async fn outer_glow_from_alpha(
+	_: impl Ctx,
+	/// The image to surround with a glow.
+	image_frame: Item<Raster<CPU>>,
+	/// The color of the glow. Its alpha sets the opacity of the glow.
+	#[default(Color::WHITE)]
+	color: Item<Color>,
+	/// The distance from the edge at which the glow has faded out.
+	#[default(10.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	size: Item<PixelLength>,
+	/// The part of the size over which the glow stays solid before it starts to fade.
+	#[range]
+	#[hard(0..100)]
+	spread: Item<Percentage>,
+) -> Item<Raster<CPU>> {
+	let (color, size, spread) = (*color.element(), *size.element(), *spread.element() / 100.);
+	let color = premultiplied(color);
+
+	padded_effect(image_frame, size.ceil() as u32, |pixel, distance| {
+		let glow = color.apply_opacity(glow_falloff(distance, size, spread));
+		glow.alpha_blend(pixel)
+	})
+}
+
+/// Adds a glow along the inside of the edges of the opaque areas of the image, which fades out towards their interior.
+#[node_macro::node(category("Raster: Filter"))]
+async fn inner_glow_from_alpha(
+	_: impl Ctx,
+	/// The image to fill with a glow.
+	image_frame: Item<Raster<CPU>>,
+	/// The color of the glow. Its alpha sets the opacity of the glow.
+	#[default(Color::WHITE)]
+	color: Item<Color>,
+	/// The distance from the edge at which the glow has faded out.
+	#[default(10.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	size: Item<PixelLength>,
+	/// The part of the size over which the glow stays solid before it starts to fade.
+	#[range]
+	#[hard(0..100)]
+	choke: Item<Percentage>,
+) -> Item<Raster<CPU>> {
+	let (color, size, choke) = (*color.element(), *size.element(), *choke.element() / 100.);
+	let color = premultiplied(color);
+
+	padded_effect(image_frame, 0, |pixel, distance| {
+		// The glow is kept within the alpha of the image, so it doesn't change the shape
+		let glow = color.apply_opacity(glow_falloff(-distance, size, choke));
+		let alpha = pixel.a();
+		let remaining = 1. - glow.a();
+		Color::from_rgbaf32_unchecked(
+			pixel.r() * remaining + glow.r() * alpha,
+			pixel.g() * remaining + glow.g() * alpha,
+			pixel.b() * remaining + glow.b() * alpha,
+			alpha,
+		)
+	})
+}
+
+/// Draws a stroke of the given width along the edges of the opaque areas of the image. The image is extended to fit the stroke.
+#[node_macro::node(category("Raster: Filter"))]
+async fn stroke_from_alpha(
+	_: impl Ctx,
+	/// The image to outline.
+	image_frame: Item<Raster<CPU>>,
+	/// The color of the stroke.
+	#[default(Color::BLACK)]
+	color: Item<Color>,
+	/// The thickness of the stroke.
+	#[default(4.)]
+	#[range]
+	#[hard(0..)]
+	#[soft(..100)]
+	width: Item<PixelLength>,
+	/// Whether the stroke lies inside the edges, outside of them, or is centered on them.
+	#[default(StrokeAlign::Outside)]
+	align: Item<StrokeAlign>,
+) -> Item<Raster<CPU>> {
+	let (color, width, align) = (*color.element(), *width.element(), *align.element());
+	let color = premultiplied(color);
+
+	// The range of signed distances from the edge covered by the stroke
+	let (start, end) = match align {
+		StrokeAlign::Center => (-width / 2., width / 2.),
+		StrokeAlign::Inside => (-width, 0.),
+		StrokeAlign::Outside => (0., width),
+	};
+
+	// An extra pixel fits the antialiasing along the outside of the stroke
+	let padding = if end > 0. { end.ceil() as u32 + 1 } else { 0 };
+
+	padded_effect(image_frame, padding, |pixel, distance| {
+		// The part of the pixel's width, centered on its distance, which overlaps with the stroke
+		let coverage = ((distance + 0.5).min(end) - (distance - 0.5).max(start)).clamp(0., 1.) as f32;
+		pixel.alpha_blend(color.apply_opacity(coverage))
+	})
+}
+
+#[derive(Clone, Copy)]
+enum Extremum {
+	Minimum,
+	Maximum,
+}
+
+impl Extremum {
+	fn pick(self, a: f32, b: f32) -> f32 {
+		match self {
+			Self::Minimum => a.min(b),
+			Self::Maximum => a.max(b),
+		}
+	}
+
+	/// The value of pixels beyond the edges of the image, which never gets picked over a pixel inside it.
+	fn identity(self) -> f32 {
+		match self {
+			Self::Minimum => f32::INFINITY,
+			Self::Maximum => f32::NEG_INFINITY,
+		}
+	}
+}
+
+/// Applies each of the `passes` in turn to the premultiplied channels of the image, picking the extremum of every channel within the structuring element around each pixel.
+fn morphology(image: Image<Color>, radius: f64, shape: StructuringElement, passes: &[Extremum]) -> Image<Color> {
+	let (width, height) = (image.width as usize, image.height as usize);
+	if radius < 1. || width == 0 || height == 0 {
+		return image;
+	}
+
+	let mut channels: [Vec<f32>; 4] = [
+		image.data.iter().map(|pixel| pixel.r()).collect(),
+		image.data.iter().map(|pixel| pixel.g()).collect(),
+		image.data.iter().map(|pixel| pixel.b()).collect(),
+		image.data.iter().map(|pixel| pixel.a()).collect(),
+	];
+
+	for &extremum in passes {
+		channels = channels.map(|channel| match shape {
+			StructuringElement::Circle => circle_extremum(&channel, width, height, radius, extremum),
+			StructuringElement::Square => square_extremum(&channel, width, height, radius.floor() as usize, extremum),
+		});
+	}
+
+	let [r, g, b, a] = channels;
+	Image {
+		width: image.width,
+		height: image.height,
+		data: (0..width * height).map(|index| Color::from_rgbaf32_unchecked(r[index], g[index], b[index], a[index])).collect(),
+		base64_string: None,
+	}
+}
+
+fn filter_rows(channel: &[f32], width: usize, half_width: usize, extremum: Extremum) -> Vec<f32> {
+	channel
+		.chunks_exact(width)
+		.flat_map(|row| running_extremum(row, half_width, extremum.identity(), |a, b| extremum.pick(a, b)))
+		.collect()
+}
+
+fn transpose(channel: &[f32], width: usize, height: usize) -> Vec<f32> {
+	(0..width * height).map(|index| channel[(index % height) * width + index / height]).collect()
+}
+
+/// The square structuring element is separable, so it's applied as a horizontal and then a vertical window.
+fn square_extremum(channel: &[f32], width: usize, height: usize, half_width: usize, extremum: Extremum) -> Vec<f32> {
+	let rows = filter_rows(channel, width, half_width, extremum);
+	let columns = filter_rows(&transpose(&rows, width, height), height, half_width, extremum);
+	transpose(&columns, height, width)
+}
+
+/// The circular structuring element is made of a horizontal window for each of its rows, so the rows of the image are filtered with the window of each row offset and combined.
+fn circle_extremum(channel: &[f32], width: usize, height: usize, radius: f64, extremum: Extremum) -> Vec<f32> {
+	let reach = radius.floor() as usize;
+	let mut output = vec![extremum.identity(); width * height];
+	let mut rows = Vec::new();
+	let mut rows_half_width = None;
+
+	for offset in 0..=reach {
+		let half_width = (radius * radius - (offset * offset) as f64).sqrt().floor() as usize;
+		if rows_half_width != Some(half_width) {
+			rows = filter_rows(channel, width, half_width, extremum);
+			rows_half_width = Some(half_width);
+		}
+
+		for y in 0..height {
+			let below = Some(y + offset).filter(|&source_y| offset > 0 && source_y < height);
+			for source_y in [y.checked_sub(offset), below].into_iter().flatten() {
+				let (output_row, source_row) = (&mut output[y * width..(y + 1) * width], &rows[source_y * width..(source_y + 1) * width]);
+				output_row.iter_mut().zip(source_row).for_each(|(output, &source)| *output = extremum.pick(*output, source));
+			}
+		}
+	}
+
+	output
+}
+
+/// Computes the squared distance from every pixel to the nearest pixel where `is_feature` is true, using the exact Euclidean distance transform by Felzenszwalb and Huttenlocher.
+fn squared_distance_transform(width: usize, height: usize, is_feature: impl Fn(usize) -> bool) -> Vec<f64> {
+	let mut distances: Vec<f64> = (0..width * height).map(|index| if is_feature(index) { 0. } else { FAR_AWAY }).collect();
+
+	let mut column = vec![0.; height];
+	for x in 0..width {
+		column.iter_mut().enumerate().for_each(|(y, value)| *value = distances[y * width + x]);
+		for (y, distance) in distance_transform_1d(&column).into_iter().enumerate() {
+			distances[y * width + x] = distance;
+		}
+	}
+	for row in distances.chunks_exact_mut(width) {
+		let transformed = distance_transform_1d(row);
+		row.copy_from_slice(&transformed);
+	}
+
+	distances
+}
+
+/// Finds the lower envelope of the parabolas rooted at each sampled value, which gives the squared distance to the nearest feature along one axis.
+fn distance_transform_1d(values: &[f64]) -> Vec<f64> {
+	let length = values.len();
+	let mut parabolas = vec![0; length];
+	let mut boundaries = vec![0.; length + 1];
+	boundaries[0] = f64::NEG_INFINITY;
+	boundaries[1] = f64::INFINITY;
+
+	let intersection = |a: usize, b: usize| ((values[b] + (b * b) as f64) - (values[a] + (a * a) as f64)) / (2. * b as f64 - 2. * a as f64);
+
+	let mut last = 0;
+	for index in 1..length {
+		let mut boundary = intersection(parabolas[last], index);
+		while boundary <= boundaries[last] {
+			last -= 1;
+			boundary = intersection(parabolas[last], index);
+		}
+		last += 1;
+		parabolas[last] = index;
+		boundaries[last] = boundary;
+		boundaries[last + 1] = f64::INFINITY;
+	}
+
+	let mut current = 0;
+	(0..length)
+		.map(|index| {
+			while boundaries[current + 1] < index as f64 {
+				current += 1;
+			}
+			let offset = index as f64 - parabolas[current] as f64;
+			offset * offset + values[parabolas[current]]
+		})
+		.collect()
+}
+
+/// Measures the signed distance from the center of each pixel to the edge of the shape made by the pixels which are at least half opaque, negative inside and positive outside.
+fn signed_distances(image: &Image<Color>) -> Vec<f64> {
+	let (width, height) = (image.width as usize, image.height as usize);
+	let is_inside = |index: usize| image.data[index].a() >= 0.5;
+
+	let to_inside = squared_distance_transform(width, height, is_inside);
+	let to_outside = squared_distance_transform(width, height, |index| !is_inside(index));
+
+	(0..width * height)
+		.map(|index| {
+			// The edge runs halfway between the pixels on either side of it
+			let distance = if is_inside(index) { 0.5 - to_outside[index].sqrt() } else { to_inside[index].sqrt() - 0.5 };
+
+			// Pixels right along the edge use their alpha to place it within them, which antialiases the effects built on the distances
+			if distance.abs() <= 0.5 { 0.5 - image.data[index].a() as f64 } else { distance }
+		})
+		.collect()
+}
+
+/// Input colors have unassociated alpha, while the pixels they're composited with are premultiplied.
+fn premultiplied(color: Color) -> Color {
+	Color::new_from_unassociated_rgba(color.r(), color.g(), color.b(), color.a())
+}
+
+/// The strength of a glow at a distance from the edge, which is solid up to the `spread` fraction of its size and then fades out smoothly.
+fn glow_falloff(distance: f64, size: f64, spread: f64) -> f32 {
+	let solid = size * spread;
+	if distance <= solid {
+		return 1.;
+	}
+	if distance >= size {
+		return 0.;
+	}
+
+	let t = (distance - solid) / (size - solid);
+	(1. - t * t * (3. - 2. * t)) as f32
+}
+
+/// Extends the image by `padding` transparent pixels on every side and maps each pixel, together with its signed distance to the edge of the opaque areas, to its new color.
+fn padded_effect(image_frame: Item<Raster<CPU>>, padding: u32, effect: impl Fn(Color, f64) -> Color) -> Item<Raster<CPU>> {
+	let transform: DAffine2 = image_frame.attribute_cloned_or_default(ATTR_TRANSFORM);
+	let (image, mut attributes) = image_frame.into_parts();
+	let image = image.into_data();
+
+	if image.width == 0 || image.height == 0 {
+		return Item::from_parts(Raster::new_cpu(image), attributes);
+	}
+
+	let (width, height) = (image.width + 2 * padding, image.height + 2 * padding);
+	let mut padded = Image::new(width, height, Color::TRANSPARENT);
+	for (y, row) in image.data.chunks_exact(image.width as usize).enumerate() {
+		let start = (y + padding as usize) * width as usize + padding as usize;
+		padded.data[start..start + row.len()].copy_from_slice(row);
+	}
+
+	let distances = signed_distances(&padded);
+	padded.data.iter_mut().zip(distances).for_each(|(pixel, distance)| *pixel = effect(*pixel, distance));
+
+	if padding > 0 {
+		// Keep the original pixels in place by growing the transform to cover the padding
+		let original_size = DVec2::new(image.width as f64, image.height as f64);
+		let padded_size = DVec2::new(width as f64, height as f64);
+		let new_transform = transform * DAffine2::from_scale(1. / original_size) * DAffine2::from_translation(DVec2::splat(-(padding as f64))) * DAffine2::from_scale(padded_size);
+		attributes.insert(ATTR_TRANSFORM, new_transform);
+	}
+
+	Item::from_parts(Raster::new_cpu(padded), attributes)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// A transparent image with an opaque white square in its middle.
+	fn square_image(size: u32, square: std::ops::Range<u32>) -> Image<Color> {
+		Image {
+			width: size,
+			height: size,
+			data: (0..size * size)
+				.map(|index| {
+					if square.contains(&(index % size)) && square.contains(&(index / size)) {
+						Color::WHITE
+					} else {
+						Color::TRANSPARENT
+					}
+				})
+				.collect(),
+			base64_string: None,
+		}
+	}
+
+	#[test]
+	fn dilate_and_erode_follow_the_structuring_element() {
+		let image = square_image(9, 4..5);
+
+		let square = morphology(image.clone(), 2., StructuringElement::Square, &[Extremum::Maximum]);
+		assert_eq!(square.data, square_image(9, 2..7).data);
+
+		// A circle of radius 2 doesn't reach the corners of the 5x5 square around the pixel
+		let circle = morphology(image, 2., StructuringElement::Circle, &[Extremum::Maximum]);
+		assert_eq!(circle.data[2 * 9 + 2], Color::TRANSPARENT);
+		assert_eq!(circle.data[2 * 9 + 4], Color::WHITE);
+		assert_eq!(circle.data[3 * 9 + 3], Color::WHITE);
+
+		let eroded = morphology(square, 2., StructuringElement::Square, &[Extremum::Minimum]);
+		assert_eq!(eroded.data, square_image(9, 4..5).data);
+	}
+
+	fn item<T>(element: T) -> Item<T> {
+		Item::new_from_element(element)
+	}
+
+	fn run(effect: impl Future<Output = Item<Raster<CPU>>>) -> (Image<Color>, DAffine2) {
+		let output = futures::executor::block_on(effect);
+		let transform: DAffine2 = output.attribute_cloned_or_default(ATTR_TRANSFORM);
+		(output.into_parts().0.into_data(), transform)
+	}
+
+	fn assert_color_eq(actual: Color, expected: Color) {
+		let channels = |color: Color| [color.r(), color.g(), color.b(), color.a()];
+		assert!(channels(actual).iter().zip(channels(expected)).all(|(a, b)| (a - b).abs() < 1e-5), "{actual:?} != {expected:?}");
+	}
+
+	/// Half transparent red, whose channels are unassociated like every color input.
+	const HALF_RED: Color = Color::from_rgbaf32_unchecked(1., 0., 0., 0.5);
+
+	#[test]
+	fn semi_transparent_colors_are_premultiplied() {
+		let image = || item(Raster::new_cpu(square_image(12, 4..8)));
+
+		// Over transparent pixels, a half transparent color must keep its channels at or below its alpha
+		let (stroked, _) = run(stroke_from_alpha((), image(), item(HALF_RED), item(2.), item(StrokeAlign::Outside)));
+		assert_color_eq(stroked.data[9 * 18 + 6], Color::from_rgbaf32_unchecked(0.5, 0., 0., 0.5));
+
+		let (glow, _) = run(outer_glow_from_alpha((), image(), item(HALF_RED), item(4.), item(100.)));
+		assert_color_eq(glow.data[10 * 20 + 6], Color::from_rgbaf32_unchecked(0.5, 0., 0., 0.5));
+
+		// Over opaque white, half of the white shows through the glow
+		let (glow, _) = run(inner_glow_from_alpha((), image(), item(HALF_RED), item(4.), item(100.)));
+		assert_color_eq(glow.data[6 * 12 + 6], Color::from_rgbaf32_unchecked(1., 0.5, 0.5, 1.));
+	}
+
+	#[test]
+	fn semi_transparent_pixels_stay_premultiplied_when_dilated() {
+		let mut image = square_image(5, 2..3);
+		image.data[2 * 5 + 2] = Color::new_from_unassociated_rgba(1., 1., 1., 0.5);
+
+		let dilated = morphology(image, 1., StructuringElement::Square, &[Extremum::Maximum]);
+		assert!(dilated.data.iter().all(|pixel| pixel.r() <= pixel.a()));
+		assert_color_eq(dilated.data[5 + 1], Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 0.5));
+	}
+
+	#[test]
+	fn outer_glow_fades_out_over_its_size() {
+		let (glow, transform) = run(outer_glow_from_alpha((), item(Raster::new_cpu(square_image(12, 4..8))), item(Color::WHITE), item(4.), item(0.)));
+		assert_eq!((glow.width, glow.height), (20, 20));
+
+		// Leftwards from the edge of the square, along its middle row
+		let row = &glow.data[10 * 20..11 * 20];
+		assert_eq!(row[8], Color::WHITE);
+		let alphas: Vec<f32> = (3..8).rev().map(|x| row[x].a()).collect();
+		assert!(alphas.windows(2).all(|pair| pair[1] < pair[0]), "{alphas:?}");
+		assert!(row[..4].iter().all(|pixel| pixel.a() == 0.));
+
+		// The padding grows the image around its original pixels
+		assert!(transform.transform_point2(DVec2::splat(4. / 20.)).abs_diff_eq(DVec2::ZERO, 1e-9));
+		assert!(transform.transform_point2(DVec2::splat(16. / 20.)).abs_diff_eq(DVec2::ONE, 1e-9));
+	}
+
+	#[test]
+	fn stroke_alignment() {
+		let stroke = |square, align| run(stroke_from_alpha((), item(Raster::new_cpu(square_image(12, square))), item(Color::BLACK), item(2.), item(align))).0;
+		let is_black = |pixel: Color| pixel == Color::BLACK;
+
+		// Outside: two pixels of stroke to the left of the square, which stays white
+		let outside = stroke(4..8, StrokeAlign::Outside);
+		let row = &outside.data[9 * 18..10 * 18];
+		assert_eq!(row[4], Color::TRANSPARENT);
+		assert!(is_black(row[5]) && is_black(row[6]));
+		assert_eq!(row[7], Color::WHITE);
+
+		// Inside: the image isn't extended, and the outermost two pixels of a larger square turn black
+		let inside = stroke(2..10, StrokeAlign::Inside);
+		assert_eq!(inside.width, 12);
+		let row = &inside.data[6 * 12..7 * 12];
+		assert_eq!(row[1], Color::TRANSPARENT);
+		assert!(is_black(row[2]) && is_black(row[3]));
+		assert_eq!(row[4], Color::WHITE);
+
+		// Center: one pixel on either side of the edge
+		let center = stroke(4..8, StrokeAlign::Center);
+		let row = &center.data[8 * 16..9 * 16];
+		assert_eq!(row[4], Color::TRANSPARENT);
+		assert!(is_black(row[5]) && is_black(row[6]));
+		assert_eq!(row[7], Color::WHITE);
+	}
+
+	#[test]
+	fn signed_distances_are_negative_inside() {
+		let image = square_image(12, 4..8);
+		let distances = signed_distances(&image);
+
+		let row = &distances[6 * 12..7 * 12];
+		assert_eq!(row[3], 0.5);
+		assert_eq!(row[4], -0.5);
+		assert_eq!(row[5], -1.5);
+		assert_eq!(row[0], 3.5);
+	}
+
+	#[test]
+	fn signed_distance_field_of_an_empty_image_is_empty() {
+		let (field, _) = run(signed_distance_field((), item(Raster::new_cpu(Image::default())), item(16.)));
+		assert_eq!((field.width, field.height), (0, 0));
+	}
+}
@@ -8,14 +8,14 @@ use core_types::registry::types::{Angle, Length, Multiplier, Percentage, PixelLe
 use core_types::transform::{Footprint, Transform};
 use core_types::uuid::NodeId;
 use core_types::{
-	ATTR_BLEND_MODE, ATTR_CLIPPING_MASK, ATTR_EDITOR_LAYER_PATH, ATTR_EDITOR_MERGED_LAYERS, ATTR_GRADIENT_TYPE, ATTR_OPACITY, ATTR_OPACITY_FILL, ATTR_SPREAD_METHOD, ATTR_TRANSFORM, CloneVarArgs,
-	Color, Context, Ctx, ExtractAll, OwnedContextImpl,
+	ATTR_BLEND_MODE, ATTR_CLIPPING_MASK, ATTR_EDITOR_LAYER_PATH, ATTR_EDITOR_MERGED_LAYERS, ATTR_GRADIENT_TYPE, ATTR_OPACITY, ATTR_OPACITY_FILL, ATTR_PATTERN, ATTR_SPREAD_METHOD, ATTR_TRANSFORM,
+	CloneVarArgs, Color, Context, Ctx, ExtractAll, OwnedContextImpl,
 };
 use glam::{DAffine2, DMat2, DVec2};
 use graphic_types::Vector;
-use graphic_types::graphic::{bake_paint_transforms, graphic_list_at, has_paint_at, is_paint_present, set_paint_attribute_at};
+use graphic_types::graphic::{bake_paint_transforms, graphic_list_at, has_paint_at, is_paint_present, set_paint_attribute_at, solidify_stroke_item};
 use graphic_types::raster_types::{CPU, GPU, Raster};
-use graphic_types::{Graphic, IntoGraphicList};
+use graphic_types::{Graphic, IntoGraphicList, PatternTiling};
 use kurbo::simplify::{SimplifyOptions, simplify_bezpath};
 use kurbo::{Affine, BezPath, DEFAULT_ACCURACY, Line, ParamCurve, ParamCurveArclen, PathEl, PathSeg, Shape};
 use rand::{Rng, SeedableRng};
@@ -31,7 +31,7 @@ use vector_types::vector::misc::{
 	CentroidType, ExtrudeJoiningAlgorithm, HandleId, InterpolationDistribution, MergeByDistanceAlgorithm, PointSpacingType, RowsOrColumns, bezpath_from_manipulator_groups,
 	bezpath_to_manipulator_groups, handles_to_segment, is_linear, point_to_dvec2, segment_to_handles,
 };
-use vector_types::vector::style::{DashPattern, Gradient, PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+use vector_types::vector::style::{DashPattern, Gradient, PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin, WidthProfile};
 use vector_types::vector::{FillId, PointId, RegionId, SegmentDomain, SegmentId, StrokeId, VectorExt};
 use vector_types::vector::{PointDomain, RegionDomain};
 use vector_types::{GradientSpreadMethod, GradientType};
@@ -194,12 +194,25 @@ async fn fill<V, F: IntoGraphicList + 'n + Send + 'static>(
 	_spread_method: Item<GradientSpreadMethod>,
 	_has_transform: Item<bool>,
 	_transform: Item<DAffine2>,
+	/// Whether a graphic fill is repeated as tiles across the interior instead of being drawn once over its bounds.
+	_tile: Item<bool>,
+	/// The rotation, scale and skew of the grid of tiles.
+	_tile_transform: Item<DAffine2>,
+	/// The gap left between neighboring tiles.
+	_tile_spacing: Item<DVec2>,
+	/// How far the grid of tiles is moved.
+	_tile_offset: Item<DVec2>,
 ) -> Item<V>
 where
 	Item<V>: VectorItemMut + 'n + Send,
 {
 	let (_gradient_type, _spread_method) = (_gradient_type.into_element(), _spread_method.into_element());
 	let (_has_transform, _transform) = (_has_transform.into_element(), *_transform.element());
+	let tiling = _tile.into_element().then(|| PatternTiling {
+		transform: *_tile_transform.element(),
+		spacing: *_tile_spacing.element(),
+		offset: *_tile_offset.element(),
+	});
 
 	let mut content = content;
 	let mut fill = fill.into_graphic_list();
@@ -252,6 +265,16 @@ where
 		}
 	}
 
+	// Stamp the tiling inputs onto any graphic paint missing them, so it's repeated across the fill instead of stretched once over it
+	if let Some(tiling) = tiling {
+		for index in 0..fill.len() {
+			let is_tileable = fill.element(index).is_some_and(|graphic| !matches!(graphic, Graphic::None | Graphic::Color(_) | Graphic::Gradient(_)));
+			if is_tileable && fill.attribute::<PatternTiling>(ATTR_PATTERN, index).is_none() {
+				fill.set_attribute(ATTR_PATTERN, index, tiling);
+			}
+		}
+	}
+
 	content.set_vector_paint(ATTR_FILL, fill);
 	content
 }
@@ -291,6 +314,8 @@ async fn stroke<V, P: IntoGraphicList + 'n + Send + 'static>(
 	/// The phase offset distance from the starting point of the dash pattern.
 	#[unit(" px")]
 	dash_offset: Item<f64>,
+	/// How the stroke thickness varies along the length of each subpath, as a fraction of the weight. A non-uniform profile draws the stroke as a filled outline.
+	width_profile: Item<WidthProfile>,
 ) -> Item<V>
 where
 	Item<V>: VectorItemMut + 'n + Send,
@@ -306,6 +331,7 @@ where
 		dash_offset.into_element(),
 	);
 	let dash_lengths = dash_pattern.into_element().clamped_lengths();
+	let width_profile = width_profile.into_element();
 
 	let stroke = Stroke {
 		weight,
@@ -317,6 +343,7 @@ where
 		align,
 		transform: DAffine2::IDENTITY,
 		paint_order,
+		width_profile,
 	};
 
 	content.for_each_vector_mut(|vector, transform| {
@@ -1408,82 +1435,7 @@ where
 	// A fill exists when the canonical attribute carries paint
 	let has_fills: Vec<bool> = (0..flattened.len()).map(|index| has_paint_at(&flattened, index, ATTR_FILL)).collect();
 
-	let mut output: List<Vector> = flattened
-		.into_iter()
-		.zip(has_fills)
-		.flat_map(|(row, has_fill)| {
-			let (mut vector, attributes) = row.into_parts();
-
-			let stroke = vector.stroke.clone().unwrap_or_default();
-			let bezpaths = vector.stroke_bezpath_iter();
-			let mut solidified_stroke = Vector::default();
-
-			// Taking the existing stroke data and passing it to kurbo::stroke to generate new fill paths.
-			let join = match stroke.join {
-				StrokeJoin::Miter => kurbo::Join::Miter,
-				StrokeJoin::Bevel => kurbo::Join::Bevel,
-				StrokeJoin::Round => kurbo::Join::Round,
-			};
-			let cap = match stroke.cap {
-				StrokeCap::Butt => kurbo::Cap::Butt,
-				StrokeCap::Round => kurbo::Cap::Round,
-				StrokeCap::Square => kurbo::Cap::Square,
-			};
-			let dash_offset = stroke.dash_offset;
-			let dash_pattern = stroke.dash_lengths;
-			let miter_limit = stroke.join_miter_limit;
-			let paint_order = stroke.paint_order;
-
-			let stroke_style = kurbo::Stroke::new(stroke.weight)
-				.with_caps(cap)
-				.with_join(join)
-				.with_dashes(dash_offset, dash_pattern)
-				.with_miter_limit(miter_limit);
-
-			// Pick `stable_dash_order` per subpath: closed subpaths use the default merge so the seam-spanning dash matches Vello/SVG renderers, while open subpaths use stable order so dashes are emitted in path-length sequence
-			let stroke_options_default = kurbo::StrokeOpts::default();
-			let stroke_options_stable = kurbo::StrokeOpts::default().stable_dash_order(true);
-
-			// 0.25 is balanced between performance and accuracy of the curve.
-			const STROKE_TOLERANCE: f64 = 0.25;
-
-			for mut path in bezpaths {
-				path.apply_affine(Affine::new(stroke.transform.to_cols_array()));
-
-				let is_closed = matches!(path.elements().last(), Some(kurbo::PathEl::ClosePath));
-				let stroke_options = if is_closed { &stroke_options_default } else { &stroke_options_stable };
-
-				let mut solidified = kurbo::stroke(path, &stroke_style, stroke_options, STROKE_TOLERANCE);
-				if stroke.transform.matrix2.determinant() != 0. {
-					solidified.apply_affine(Affine::new(stroke.transform.inverse().to_cols_array()));
-				}
-
-				solidified_stroke.append_bezpath(solidified);
-			}
-
-			// If the original vector has a fill, preserve it as a separate item with the stroke cleared.
-			let fill_row = has_fill.then(|| {
-				vector.stroke = None;
-				let mut fill_attributes = attributes.clone();
-				// No stroke remains on the fill row
-				fill_attributes.remove::<List<Graphic>>(ATTR_STROKE);
-				Item::from_parts(vector, fill_attributes)
-			});
-
-			let mut stroke_attributes = attributes;
-			// Drop the original fill and use the stroke paint to fill the outlined stroke
-			stroke_attributes.remove::<List<Graphic>>(ATTR_FILL);
-			stroke_attributes.rename(ATTR_STROKE, ATTR_FILL);
-
-			let stroke_row = Item::from_parts(solidified_stroke, stroke_attributes);
-
-			// Ordering based on the paint order. The first item in the `List` is rendered below the second.
-			match paint_order {
-				PaintOrder::StrokeAbove => fill_row.into_iter().chain(std::iter::once(stroke_row)).collect::<Vec<_>>(),
-				PaintOrder::StrokeBelow => std::iter::once(stroke_row).chain(fill_row).collect::<Vec<_>>(),
-			}
-		})
-		.collect();
+	let mut output: List<Vector> = flattened.into_iter().zip(has_fills).flat_map(|(item, has_fill)| solidify_stroke_item(item, has_fill)).collect();
 
 	// Snapshot the upstream content so the renderer can recurse into it for editor click-target preservation
 	// and surface the original pre-solidified `Vector` to the Path tool for editing.